@@ -50,8 +50,8 @@ class BaseStage:
         return Stage.new_py(self)
 
 class BaseMutator:
-    def mutate(self, state, input, stage_idx):
-        pass
+    def mutate(self, state, input, stage_idx) -> bool:
+        return False
     def post_exec(self, state, stage_idx, corpus_idx):
         pass
     def as_mutator(self):
@@ -85,9 +85,22 @@ def harness(buf) -> ExitKind:
 
 executor = FooExecutor(harness, observers)
 
+
+class FlipFirstByteMutator(BaseMutator):
+    def mutate(self, state, input: BytesInput, stage_idx) -> bool:
+        if len(input) == 0:
+            return False
+        buf = bytearray(input.bytes())
+        buf[0] ^= 0xFF
+        input.set_bytes(bytes(buf))
+        return True
+
+
 stage = StdMutationalStage(StdHavocMutator().as_mutator())
 
-stage_tuple_list = StagesTuple([stage.as_stage()])
+py_stage = StdMutationalStage(FlipFirstByteMutator().as_mutator())
+
+stage_tuple_list = StagesTuple([stage.as_stage(), py_stage.as_stage()])
 
 fuzzer.add_input(state, executor.as_executor(), mgr.as_manager(), b"\0\0")
 
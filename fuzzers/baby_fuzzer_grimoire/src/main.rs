@@ -11,11 +11,7 @@ use libafl::{
     fuzzer::{Evaluator, Fuzzer, StdFuzzer},
     inputs::{GeneralizedInput, HasTargetBytes},
     monitors::SimpleMonitor,
-    mutators::{
-        havoc_mutations, scheduled::StdScheduledMutator, GrimoireExtensionMutator,
-        GrimoireRandomDeleteMutator, GrimoireRecursiveReplacementMutator,
-        GrimoireStringReplacementMutator, Tokens,
-    },
+    mutators::{grimoire_mutations, havoc_mutations, scheduled::StdScheduledMutator, Tokens},
     observers::StdMapObserver,
     schedulers::QueueScheduler,
     stages::{mutational::StdMutationalStage, GeneralizationStage},
@@ -144,17 +140,7 @@ pub fn main() {
 
     // Setup a mutational stage with a basic bytes mutator
     let mutator = StdScheduledMutator::with_max_stack_pow(havoc_mutations(), 2);
-    let grimoire_mutator = StdScheduledMutator::with_max_stack_pow(
-        tuple_list!(
-            GrimoireExtensionMutator::new(),
-            GrimoireRecursiveReplacementMutator::new(),
-            GrimoireStringReplacementMutator::new(),
-            // give more probability to avoid large inputs
-            GrimoireRandomDeleteMutator::new(),
-            GrimoireRandomDeleteMutator::new(),
-        ),
-        3,
-    );
+    let grimoire_mutator = StdScheduledMutator::with_max_stack_pow(grimoire_mutations(), 3);
     let mut stages = tuple_list!(
         generalization,
         StdMutationalStage::new(mutator),
@@ -10,9 +10,13 @@ extern crate alloc;
 use alloc::ffi::CString;
 #[cfg(not(any(windows)))]
 use core::panic::PanicInfo;
+use core::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
 
 use libafl::{
-    bolts::{current_nanos, rands::StdRand, tuples::tuple_list, AsSlice},
+    bolts::{current_nanos, rands::StdRand, set_current_time_fn, tuples::tuple_list, AsSlice},
     corpus::InMemoryCorpus,
     events::SimpleEventManager,
     executors::{inprocess::InProcessExecutor, ExitKind},
@@ -54,7 +58,7 @@ fn signals_set(idx: usize) {
     unsafe { SIGNALS[idx] = 1 };
 }
 
-/// Provide custom time in `no_std` environment
+/// Provide custom time in `no_std` environment, used until a time source is set with `set_current_time_fn`
 /// Use a time provider of your choice
 #[no_mangle]
 pub extern "C" fn external_current_millis() -> u64 {
@@ -62,12 +66,24 @@ pub extern "C" fn external_current_millis() -> u64 {
     1000
 }
 
+/// The ticks of the virtual clock, advanced each time the fuzzer asks for the time
+static TICKS: AtomicU64 = AtomicU64::new(0);
+
+/// A virtual clock, as for a firmware in an emulator, advancing a millisecond each time it is read.
+/// On real hardware, read a timer of the board here.
+fn virtual_time() -> Duration {
+    Duration::from_millis(TICKS.fetch_add(1, Ordering::Relaxed))
+}
+
 /// The main of this program.
 /// # Panics
 /// Will panic once the fuzzer finds the correct conditions.
 #[allow(clippy::similar_names)]
 #[no_mangle]
 pub extern "C" fn main(_argc: isize, _argv: *const *const u8) -> isize {
+    // Use the virtual clock for the stats and the seed of the RNG
+    set_current_time_fn(Some(virtual_time));
+
     // The closure that we want to fuzz
     let mut harness = |input: &BytesInput| {
         let target = input.target_bytes();
@@ -0,0 +1,6 @@
+/* The memory layout of the MPS2 board with a Cortex-M4 (AN386), adapt it to your board */
+MEMORY
+{
+  FLASH : ORIGIN = 0x00000000, LENGTH = 4M
+  RAM : ORIGIN = 0x20000000, LENGTH = 4M
+}
@@ -0,0 +1,173 @@
+#![no_std]
+#![no_main]
+
+use core::{
+    mem::MaybeUninit,
+    panic::PanicInfo,
+    sync::atomic::{AtomicU32, Ordering},
+    time::Duration,
+};
+
+use cortex_m_rt::entry;
+use libafl::{
+    bolts::{current_nanos, rands::StdRand, set_current_time_fn, tuples::tuple_list, AsSlice},
+    corpus::{Corpus, InMemoryCorpus},
+    events::SimpleEventManager,
+    executors::{inprocess::InProcessExecutor, ExitKind},
+    feedbacks::{CrashFeedback, MaxMapFeedback},
+    fuzzer::{Fuzzer, StdFuzzer},
+    generators::RandPrintablesGenerator,
+    inputs::{BytesInput, HasTargetBytes},
+    monitors::SimpleMonitor,
+    mutators::scheduled::{havoc_mutations, StdScheduledMutator},
+    observers::StdMapObserver,
+    schedulers::QueueScheduler,
+    stages::mutational::StdMutationalStage,
+    state::{HasSolutions, StdState},
+};
+use linked_list_allocator::LockedHeap;
+
+/// The size of the heap, for the corpus and the inputs
+const HEAP_SIZE: usize = 1024 * 1024;
+
+/// The memory of the heap
+static mut HEAP: [MaybeUninit<u8>; HEAP_SIZE] = [MaybeUninit::uninit(); HEAP_SIZE];
+
+#[global_allocator]
+static ALLOCATOR: LockedHeap = LockedHeap::empty();
+
+#[panic_handler]
+fn panic(_info: &PanicInfo) -> ! {
+    loop {
+        // On baremetal, there's not much left to do.
+        core::hint::spin_loop();
+    }
+}
+
+/// Coverage map with explicit assignments due to the lack of instrumentation
+static mut SIGNALS: [u8; 16] = [0; 16];
+
+/// Assign a signal to the signals map
+fn signals_set(idx: usize) {
+    unsafe { SIGNALS[idx] = 1 };
+}
+
+/// The milliseconds of the virtual clock, advanced each time the fuzzer asks for the time
+static TICKS: AtomicU32 = AtomicU32::new(0);
+
+/// A virtual clock, advancing a millisecond each time it is read.
+/// On a real board, read a timer here, such as the `SysTick`.
+fn virtual_time() -> Duration {
+    Duration::from_millis(u64::from(TICKS.fetch_add(1, Ordering::Relaxed)))
+}
+
+/// The default time source of `libafl` without `std`, until a time source is set with `set_current_time_fn`
+#[no_mangle]
+pub extern "C" fn external_current_millis() -> u64 {
+    u64::from(TICKS.load(Ordering::Relaxed))
+}
+
+/// The entry point of the firmware.
+/// Fuzzes until the harness reports a crash, then halts.
+#[allow(clippy::similar_names)]
+#[entry]
+fn main() -> ! {
+    unsafe {
+        ALLOCATOR
+            .lock()
+            .init(HEAP.as_mut_ptr().cast::<u8>(), HEAP_SIZE);
+    }
+
+    // Use the virtual clock for the stats and the seed of the RNG
+    set_current_time_fn(Some(virtual_time));
+
+    // The closure that we want to fuzz.
+    // Without signal handlers, the harness reports crashes itself, instead of panicking.
+    let mut harness = |input: &BytesInput| {
+        let target = input.target_bytes();
+        let buf = target.as_slice();
+        signals_set(0);
+        if !buf.is_empty() && buf[0] == b'a' {
+            signals_set(1);
+            if buf.len() > 1 && buf[1] == b'b' {
+                signals_set(2);
+                if buf.len() > 2 && buf[2] == b'c' {
+                    return ExitKind::Crash;
+                }
+            }
+        }
+        ExitKind::Ok
+    };
+
+    // Create an observation channel using the signals map
+    let observer = StdMapObserver::new("signals", unsafe { &mut SIGNALS });
+
+    // Feedback to rate the interestingness of an input
+    let mut feedback = MaxMapFeedback::new(&observer);
+
+    // A feedback to choose if an input is a solution or not
+    let mut objective = CrashFeedback::new();
+
+    // create a State from scratch
+    let mut state = StdState::new(
+        // RNG
+        StdRand::with_seed(current_nanos()),
+        // Corpus that will be evolved, in memory, as there is no file system
+        InMemoryCorpus::new(),
+        // Corpus in which we store solutions, in memory as well, read them out with a debugger
+        InMemoryCorpus::new(),
+        // States of the feedbacks.
+        // The feedbacks can report the data that should persist in the State.
+        &mut feedback,
+        // Same for objective feedbacks
+        &mut objective,
+    )
+    .unwrap();
+
+    // The Monitor trait define how the fuzzer stats are reported to the user
+    let monitor = SimpleMonitor::new(|_s| {
+        // TODO: Print `s` here, for example over a UART or semihosting, if your board permits it.
+    });
+
+    // The event manager handles the events in memory, in this single fuzzer
+    let mut mgr = SimpleEventManager::new(monitor);
+
+    // A queue policy to get testcasess from the corpus
+    let scheduler = QueueScheduler::new();
+
+    // A fuzzer with feedbacks and a corpus scheduler
+    let mut fuzzer = StdFuzzer::new(scheduler, feedback, objective);
+
+    // Create the executor for an in-process function with just one observer
+    let mut executor = InProcessExecutor::new(
+        &mut harness,
+        tuple_list!(observer),
+        &mut fuzzer,
+        &mut state,
+        &mut mgr,
+    )
+    .expect("Failed to create the Executor");
+
+    // Generator of printable bytearrays of max size 32
+    let mut generator = RandPrintablesGenerator::new(32);
+
+    // Generate 8 initial inputs
+    state
+        .generate_initial_inputs(&mut fuzzer, &mut executor, &mut generator, &mut mgr, 8)
+        .expect("Failed to generate the initial corpus");
+
+    // Setup a mutational stage with a basic bytes mutator
+    let mutator = StdScheduledMutator::new(havoc_mutations());
+    let mut stages = tuple_list!(StdMutationalStage::new(mutator));
+
+    while state.solutions().count() == 0 {
+        fuzzer
+            .fuzz_one(&mut stages, &mut executor, &mut state, &mut mgr)
+            .expect("Error in the fuzzing loop");
+    }
+
+    loop {
+        // Found a crash, it's the first entry of `state.solutions()`.
+        core::hint::spin_loop();
+    }
+}
@@ -34,12 +34,7 @@ use libafl::{
     inputs::{BytesInput, GeneralizedInput, HasTargetBytes},
     monitors::SimpleMonitor,
     mutators::{
-        grimoire::{
-            GrimoireExtensionMutator, GrimoireRandomDeleteMutator,
-            GrimoireRecursiveReplacementMutator, GrimoireStringReplacementMutator,
-        },
-        scheduled::havoc_mutations,
-        token_mutations::I2SRandReplace,
+        grimoire::grimoire_mutations, scheduled::havoc_mutations, token_mutations::I2SRandReplace,
         tokens_mutations, StdMOptMutator, StdScheduledMutator, Tokens,
     },
     observers::{HitcountsMapObserver, StdMapObserver, TimeObserver},
@@ -586,17 +581,7 @@ fn fuzz_text(
 
     let power = StdPowerMutationalStage::new(mutator, &edges_observer);
 
-    let grimoire_mutator = StdScheduledMutator::with_max_stack_pow(
-        tuple_list!(
-            GrimoireExtensionMutator::new(),
-            GrimoireRecursiveReplacementMutator::new(),
-            GrimoireStringReplacementMutator::new(),
-            // give more probability to avoid large inputs
-            GrimoireRandomDeleteMutator::new(),
-            GrimoireRandomDeleteMutator::new(),
-        ),
-        3,
-    );
+    let grimoire_mutator = StdScheduledMutator::with_max_stack_pow(grimoire_mutations(), 3);
     let grimoire = StdMutationalStage::new(grimoire_mutator);
 
     // A minimization+queue policy to get testcasess from the corpus
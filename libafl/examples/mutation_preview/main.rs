@@ -0,0 +1,60 @@
+/*!
+Prints the samples the havoc mutations generate from a seed, and the mutations applied to each of them.
+Usage: `cargo run --example mutation_preview -- [seed file] [number of samples]`
+*/
+
+use std::{env, fs};
+
+use libafl::{
+    bolts::{current_nanos, rands::StdRand},
+    corpus::{Corpus, InMemoryCorpus, Testcase},
+    feedbacks::ConstFeedback,
+    inputs::{BytesInput, HasBytesVec},
+    mutators::scheduled::{havoc_mutations, ScheduledMutator, StdScheduledMutator},
+    state::{HasCorpus, StdState},
+};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let seed = args.get(1).map_or_else(
+        || b"Hello, World!".to_vec(),
+        |path| fs::read(path).expect("Failed to read the seed file"),
+    );
+    let samples = args
+        .get(2)
+        .map_or(10, |n| n.parse().expect("Invalid number of samples"));
+    let seed = BytesInput::new(seed);
+
+    let mut feedback = ConstFeedback::new(false);
+    let mut objective = ConstFeedback::new(false);
+    let mut state = StdState::new(
+        StdRand::with_seed(current_nanos()),
+        InMemoryCorpus::new(),
+        InMemoryCorpus::new(),
+        &mut feedback,
+        &mut objective,
+    )
+    .unwrap();
+    // The splice mutations pick their donors from the corpus
+    state.corpus_mut().add(Testcase::new(seed.clone())).unwrap();
+
+    let mut mutator = StdScheduledMutator::new(havoc_mutations());
+    for (i, sample) in mutator
+        .preview(&mut state, &seed, samples)
+        .unwrap()
+        .iter()
+        .enumerate()
+    {
+        println!("#{i} ({:?}): {}", sample.result, sample.log.join(", "));
+        println!("  {}", escape(sample.input.bytes()));
+    }
+}
+
+/// Prints the bytes as string, escaping everything that is not printable
+fn escape(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .flat_map(|b| core::ascii::escape_default(*b))
+        .map(char::from)
+        .collect()
+}
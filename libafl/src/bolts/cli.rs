@@ -17,7 +17,7 @@
 //!     let parsed = parse_args();
 //!
 //!     // call appropriate logic, passing in parsed options
-//!     if parsed.replay.is_some() {
+//!     if parsed.replay.is_some() || parsed.repro.is_some() {
 //!         replay(parsed);
 //!     } else {
 //!         fuzz(parsed);
@@ -168,6 +168,11 @@ pub struct FuzzerOptions {
     #[arg(short, long, help_heading = "Frida Options")]
     pub libs_to_instrument: Vec<String>,
 
+    /// modules which will not be instrumented at all, matched by name or path; can be used to exclude the harness itself
+    #[cfg(feature = "frida_cli")]
+    #[arg(long, help_heading = "Frida Options")]
+    pub exclude_modules: Vec<String>,
+
     /// enable CmpLog instrumentation
     #[cfg_attr(
         feature = "frida_cli",
@@ -295,6 +300,10 @@ pub struct FuzzerOptions {
         requires = "replay"
     )]
     pub repeat: Option<usize>,
+
+    /// path to a reproducer, as written by the `ReproducerCorpus`, that should be run once for crash reproduction
+    #[arg(long, help_heading = "Replay Options", conflicts_with = "replay")]
+    pub repro: Option<PathBuf>,
 }
 
 impl FuzzerOptions {
@@ -14,13 +14,13 @@
 use alloc::string::ToString;
 use core::fmt::{self, Debug, Formatter};
 #[cfg(feature = "std")]
-use core::marker::PhantomData;
-#[cfg(feature = "std")]
-use std::net::SocketAddr;
+use core::{marker::PhantomData, time::Duration};
 #[cfg(all(feature = "std", any(windows, not(feature = "fork"))))]
 use std::process::Stdio;
 #[cfg(all(unix, feature = "std", feature = "fork"))]
 use std::{fs::File, os::unix::io::AsRawFd};
+#[cfg(feature = "std")]
+use std::{net::SocketAddr, path::PathBuf};
 
 #[cfg(feature = "std")]
 use serde::de::DeserializeOwned;
@@ -37,8 +37,11 @@ use crate::inputs::UsesInput;
 #[cfg(feature = "std")]
 use crate::{
     bolts::{core_affinity::Cores, shmem::ShMemProvider},
-    events::{EventConfig, LlmpRestartingEventManager, ManagerKind, RestartingMgr},
+    events::{
+        EventConfig, LlmpRestartingEventManager, ManagerKind, RestartingMgr, DEFAULT_CLIENT_TIMEOUT,
+    },
     monitors::Monitor,
+    schedulers::partition::{ClientPartition, CLIENT_PARTITION_ENV},
     state::{HasClientPerfMonitor, HasExecutions},
     Error,
 };
@@ -48,7 +51,11 @@ const _AFL_LAUNCHER_CLIENT: &str = "AFL_LAUNCHER_CLIENT";
 /// Provides a Launcher, which can be used to launch a fuzzing run on a specified list of cores
 #[cfg(feature = "std")]
 #[derive(TypedBuilder)]
-#[allow(clippy::type_complexity, missing_debug_implementations)]
+#[allow(
+    clippy::type_complexity,
+    clippy::struct_excessive_bools,
+    missing_debug_implementations
+)]
 pub struct Launcher<'a, CF, MT, S, SP>
 where
     CF: FnOnce(Option<S>, LlmpRestartingEventManager<S, SP>, usize) -> Result<(), Error>,
@@ -78,12 +85,37 @@ where
     /// clusters.
     #[builder(default = None)]
     remote_broker_addr: Option<SocketAddr>,
+    /// A unix domain socket the broker listens on next to [`Self::broker_port`], which the clients attach to instead.
+    /// Only supported on `unix`.
+    #[builder(default = None)]
+    broker_unix_path: Option<PathBuf>,
     /// If this launcher should spawn a new `broker` on `[Self::broker_port]` (default).
     /// The reason you may not want this is, if you already have a [`Launcher`]
     /// with a different configuration (for the same target) running on this machine.
     /// Then, clients launched by this [`Launcher`] can connect to the original `broker`.
     #[builder(default = true)]
     spawn_broker: bool,
+    /// If set, each client gets assigned its own [`ClientPartition`], i.e. a focus out of all clients.
+    /// Clients can get their partition using [`ClientPartition::from_env`], and use it,
+    /// for example, in a [`crate::schedulers::PartitionedScheduler`].
+    #[builder(default = false)]
+    partition_clients: bool,
+    /// If set, each client runs a [`LlmpEventManager::self_test`](crate::events::LlmpEventManager::self_test)
+    /// of its event channel at startup, and prints the results.
+    #[builder(default = false)]
+    self_test: bool,
+    /// How long a client may stay silent before the broker reports it as hung,
+    /// see [`LlmpEventBroker::set_client_timeout`](crate::events::LlmpEventBroker::set_client_timeout).
+    #[builder(default = Some(DEFAULT_CLIENT_TIMEOUT))]
+    client_timeout: Option<Duration>,
+    /// If set, the broker restarts hung clients,
+    /// see [`LlmpEventBroker::set_restart_hung_clients`](crate::events::LlmpEventBroker::set_restart_hung_clients).
+    #[builder(default = false)]
+    restart_hung_clients: bool,
+    /// How long a client may stay silent before the broker evicts it from its stats,
+    /// see [`LlmpEventBroker::set_client_evict_timeout`](crate::events::LlmpEventBroker::set_client_evict_timeout).
+    #[builder(default = None)]
+    client_evict_timeout: Option<Duration>,
     #[builder(setter(skip), default = PhantomData)]
     phantom_data: PhantomData<(&'a S, &'a SP)>,
 }
@@ -101,7 +133,13 @@ where
             .field("broker_port", &self.broker_port)
             .field("core", &self.cores)
             .field("spawn_broker", &self.spawn_broker)
+            .field("partition_clients", &self.partition_clients)
+            .field("self_test", &self.self_test)
+            .field("client_timeout", &self.client_timeout)
+            .field("restart_hung_clients", &self.restart_hung_clients)
+            .field("client_evict_timeout", &self.client_evict_timeout)
             .field("remote_broker_addr", &self.remote_broker_addr)
+            .field("broker_unix_path", &self.broker_unix_path)
             .field("stdout_file", &self.stdout_file)
             .finish_non_exhaustive()
     }
@@ -159,7 +197,13 @@ where
                         self.shmem_provider.post_fork(true)?;
 
                         #[cfg(feature = "std")]
-                        std::thread::sleep(std::time::Duration::from_millis(index * 100));
+                        std::thread::sleep(Duration::from_millis(index * 100));
+
+                        if self.partition_clients {
+                            let partition =
+                                ClientPartition::new(index as usize - 1, self.cores.ids.len());
+                            std::env::set_var(CLIENT_PARTITION_ENV, partition.to_string());
+                        }
 
                         #[cfg(feature = "std")]
                         if !debug_output {
@@ -170,9 +214,10 @@ where
                         }
 
                         // Fuzzer client. keeps retrying the connection to broker till the broker starts
-                        let (state, mgr) = RestartingMgr::<MT, S, SP>::builder()
+                        let (state, mut mgr) = RestartingMgr::<MT, S, SP>::builder()
                             .shmem_provider(self.shmem_provider.clone())
                             .broker_port(self.broker_port)
+                            .broker_unix_path(self.broker_unix_path.clone())
                             .kind(ManagerKind::Client {
                                 cpu_core: Some(*bind_to),
                             })
@@ -180,6 +225,12 @@ where
                             .build()
                             .launch()?;
 
+                        if self.self_test && state.is_none() {
+                            for result in mgr.self_test()? {
+                                println!("Self test of client on core {id}: {result}");
+                            }
+                        }
+
                         return (self.run_client.take().unwrap())(state, mgr, bind_to.id);
                     }
                 };
@@ -197,6 +248,10 @@ where
                 .broker_port(self.broker_port)
                 .kind(ManagerKind::Broker)
                 .remote_broker_addr(self.remote_broker_addr)
+                .broker_unix_path(self.broker_unix_path.clone())
+                .client_timeout(self.client_timeout)
+                .restart_hung_clients(self.restart_hung_clients)
+                .client_evict_timeout(self.client_evict_timeout)
                 .configuration(self.configuration)
                 .build()
                 .launch()?;
@@ -238,9 +293,10 @@ where
                 //todo: silence stdout and stderr for clients
 
                 // the actual client. do the fuzzing
-                let (state, mgr) = RestartingMgr::<MT, S, SP>::builder()
+                let (state, mut mgr) = RestartingMgr::<MT, S, SP>::builder()
                     .shmem_provider(self.shmem_provider.clone())
                     .broker_port(self.broker_port)
+                    .broker_unix_path(self.broker_unix_path.clone())
                     .kind(ManagerKind::Client {
                         cpu_core: Some(CoreId { id: core_id }),
                     })
@@ -248,6 +304,12 @@ where
                     .build()
                     .launch()?;
 
+                if self.self_test && state.is_none() {
+                    for result in mgr.self_test()? {
+                        println!("Self test of client on core {core_id}: {result}");
+                    }
+                }
+
                 return (self.run_client.take().unwrap())(state, mgr, core_id);
             }
             Err(std::env::VarError::NotPresent) => {
@@ -266,8 +328,15 @@ where
                 println!("spawning on cores: {:?}", self.cores);
 
                 //spawn clients
+                let mut index = 0;
                 for (id, _) in core_ids.iter().enumerate().take(num_cores) {
                     if self.cores.ids.iter().any(|&x| x == id.into()) {
+                        if self.partition_clients {
+                            let partition = ClientPartition::new(index, self.cores.ids.len());
+                            std::env::set_var(CLIENT_PARTITION_ENV, partition.to_string());
+                        }
+                        index += 1;
+
                         let stdio = if self.stdout_file.is_some() {
                             Stdio::inherit()
                         } else {
@@ -295,6 +364,10 @@ where
                 .broker_port(self.broker_port)
                 .kind(ManagerKind::Broker)
                 .remote_broker_addr(self.remote_broker_addr)
+                .broker_unix_path(self.broker_unix_path.clone())
+                .client_timeout(self.client_timeout)
+                .restart_hung_clients(self.restart_hung_clients)
+                .client_evict_timeout(self.client_evict_timeout)
                 .configuration(self.configuration)
                 .build()
                 .launch()?;
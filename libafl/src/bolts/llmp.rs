@@ -5,6 +5,7 @@ To send new messages, the clients place a new message at the end of their
 `client_out_mem`. If the current map is filled up, they place an end of page (`EOP`)
 msg and alloc a new [`ShMem`].
 Once the broker mapped this same page, it flags it as safe for unmapping.
+Once the broker read the page up to its `EOP`, the client may re-use it, instead of allocating yet another [`ShMem`].
 
 ```text
 [client0]        [client1]    ...    [clientN]
@@ -26,7 +27,8 @@ last_message->message_id`) for new incoming messages. If the page is filled up,
 the broker instead creates a new page and places an end of page (`EOP`)
 message in its queue. The `EOP` buf contains the new description to
 access the shared map. The clients then switch over to read from that new
-current map.
+current map. New clients start reading on the current map, and the broker unmaps
+the older maps once all clients read them up to their `EOP`.
 
 ```text
 [broker]
@@ -58,9 +60,9 @@ Check out the `llmp_test` example in ./examples, or build it with `cargo run --e
 */
 
 #[cfg(feature = "std")]
-use alloc::string::ToString;
+use alloc::{boxed::Box, string::ToString};
 use alloc::{string::String, vec::Vec};
-#[cfg(not(target_pointer_width = "64"))]
+#[cfg(any(feature = "std", not(target_pointer_width = "64")))]
 use core::sync::atomic::AtomicU32;
 #[cfg(target_pointer_width = "64")]
 use core::sync::atomic::AtomicU64;
@@ -78,11 +80,28 @@ use core::{
 use std::os::unix::io::AsRawFd;
 #[cfg(feature = "std")]
 use std::{
+    collections::VecDeque,
     env,
     io::{ErrorKind, Read, Write},
     net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
-    sync::mpsc::channel,
+    path::Path,
+    sync::{mpsc::channel, Arc, Mutex},
     thread,
+    time::Instant,
+};
+#[cfg(all(unix, feature = "std"))]
+use std::{
+    fs,
+    os::unix::{
+        fs::FileTypeExt,
+        net::{UnixListener, UnixStream},
+    },
+};
+#[cfg(all(target_os = "linux", feature = "std"))]
+use std::{
+    fs::File,
+    io,
+    os::unix::io::{FromRawFd, RawFd},
 };
 
 #[cfg(all(debug_assertions, feature = "llmp_debug", feature = "std"))]
@@ -90,12 +109,22 @@ use backtrace::Backtrace;
 #[cfg(all(unix, feature = "std"))]
 #[cfg(not(any(target_os = "solaris", target_os = "illumos")))]
 use nix::sys::socket::{self, sockopt::ReusePort};
+#[cfg(all(target_os = "linux", feature = "std"))]
+use nix::sys::{
+    socket::{sockopt::ReceiveTimeout, AddressFamily, SockFlag, SockType, VsockAddr},
+    time::{TimeVal, TimeValLike},
+};
 use serde::{Deserialize, Serialize};
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "executors"))]
 use crate::bolts::os::unix_signals::{
     setup_signal_handler, siginfo_t, ucontext_t, Handler, Signal,
 };
+#[cfg(feature = "std")]
+use crate::bolts::{
+    current_nanos,
+    rands::{Rand, StdRand},
+};
 use crate::{
     bolts::shmem::{ShMem, ShMemDescription, ShMemId, ShMemProvider},
     Error,
@@ -105,6 +134,9 @@ use crate::{
 /// Usually, this value should not exceed `1`, else the broker cannot keep up with the amount of incoming messages.
 /// Instead of increasing this value, you may consider sending new messages at a lower rate, else your Sender will eventually `OOM`.
 const LLMP_CFG_MAX_PENDING_UNREAD_PAGES: usize = 3;
+/// The max number of pages a [`client`] keeps mapped after the [`broker`] mapped them, to re-use them once the broker read them.
+/// One spare page is enough, as the broker usually reads a page before the next one fills up.
+const LLMP_CFG_MAX_UNUSED_CACHED_PAGES: usize = 1;
 /// We'll start off with 256 megabyte maps per fuzzer client
 #[cfg(not(feature = "llmp_small_maps"))]
 const LLMP_CFG_INITIAL_MAP_SIZE: usize = 1 << 28;
@@ -125,7 +157,11 @@ const LLMP_TAG_END_OF_PAGE: Tag = 0xAF1E0F1;
 /// A new client for this broker got added.
 const LLMP_TAG_NEW_SHM_CLIENT: Tag = 0xC11E471;
 /// The sender on this map is exiting (if broker exits, clients should exit gracefully);
-const LLMP_TAG_EXITING: Tag = 0x13C5171;
+/// If a client exits, the broker passes this tag, without payload, to its message hook once.
+pub const LLMP_TAG_EXITING: Tag = 0x13C5171;
+/// A client described itself, and may get restored on the given page of the broker later.
+/// The broker keeps this page until the client describes itself again, or exits.
+const LLMP_TAG_PIN_PAGE: Tag = 0x914E9A6;
 /// Client gave up as the receiver/broker was too slow
 const LLMP_SLOW_RECEIVER_PANIC: Tag = 0x70051041;
 
@@ -133,13 +169,29 @@ const LLMP_SLOW_RECEIVER_PANIC: Tag = 0x70051041;
 pub const LLMP_FLAG_INITIALIZED: Flags = 0x0;
 /// This message was compressed in transit
 pub const LLMP_FLAG_COMPRESSED: Flags = 0x1;
-/// From another broker.
+/// From another broker. The `broker` field of the [`LlmpMsg`] holds the id of the broker it originated from.
+/// Only relays, see [`LlmpBroker::set_b2b_relay`], forward messages with this flag to other brokers again,
+/// so every broker in a (fully meshed) broker 2 broker setup receives each message exactly once.
 pub const LLMP_FLAG_FROM_B2B: Flags = 0x2;
+/// This message is one chunk of a larger buffer, split by the sender to keep the shared maps small.
+/// LLMP itself forwards chunks like any other message, reassembling them is up to the receiver.
+pub const LLMP_FLAG_CHUNKED: Flags = 0x4;
+/// This message was serialized with `bincode`, instead of postcard
+pub const LLMP_FLAG_BINCODE: Flags = 0x8;
+/// This message was serialized to JSON, instead of postcard
+pub const LLMP_FLAG_JSON: Flags = 0x10;
 
 /// Timt the broker 2 broker connection waits for incoming data,
 /// before checking for own data to forward again.
 const _LLMP_B2B_BLOCK_TIME: Duration = Duration::from_millis(3_000);
 
+/// Time a reconnecting broker 2 broker connection waits between connection attempts.
+const _LLMP_B2B_RECONNECT_TIME: Duration = Duration::from_secs(5);
+
+/// How many payload bytes a broker 2 broker connection buffers, while it is disconnected.
+/// Once exceeded, the oldest buffered messages get dropped.
+const _LLMP_B2B_MAX_BUFFERED_BYTES: usize = 1 << 28;
+
 /// If broker2broker is enabled, bind to public IP
 #[cfg(feature = "llmp_bind_public")]
 const _LLMP_BIND_ADDR: &str = "0.0.0.0";
@@ -151,6 +203,12 @@ const _LLMP_BIND_ADDR: &str = "127.0.0.1";
 /// LLMP Client connects to this address
 const _LLMP_CONNECT_ADDR: &str = "127.0.0.1";
 
+/// The `vsock` cid of the host, as seen from a VM guest
+pub const VSOCK_CID_HOST: u32 = 2;
+
+/// The `vsock` cid to listen on for connections from any VM guest
+const _VSOCK_CID_ANY: u32 = u32::MAX;
+
 /// An env var of this value indicates that the set value was a NULL PTR
 const _NULL_ENV_STR: &str = "_NULL";
 
@@ -164,7 +222,7 @@ const EOP_MSG_SIZE: usize =
 const LLMP_PAGE_HEADER_LEN: usize = size_of::<LlmpPage>();
 
 /// The llmp broker registers a signal handler for cleanups on `SIGINT`.
-#[cfg(unix)]
+#[cfg(all(unix, feature = "executors"))]
 static mut GLOBAL_SIGHANDLER_STATE: LlmpBrokerSignalHandler = LlmpBrokerSignalHandler {
     shutting_down: false,
 };
@@ -198,6 +256,8 @@ pub enum TcpRequest {
     RemoteBrokerHello {
         /// The hostname of our broker, trying to connect.
         hostname: String,
+        /// The id of our broker, trying to connect.
+        broker_id: BrokerId,
     },
 }
 
@@ -222,6 +282,8 @@ impl TryFrom<Vec<u8>> for TcpRequest {
 pub struct TcpRemoteNewMessage {
     // The client ID of the original broker
     client_id: ClientId,
+    // The id of the broker this message originated from
+    broker_id: BrokerId,
     // The message tag
     tag: Tag,
     // The flags
@@ -264,7 +326,7 @@ pub enum TcpResponse {
     },
     /// Notify the remote broker has been accepted.
     RemoteBrokerAccepted {
-        /// The broker id of this element
+        /// The broker id of the accepting broker
         broker_id: BrokerId,
     },
     /// Something went wrong when processing the request.
@@ -296,6 +358,12 @@ impl TryFrom<Vec<u8>> for TcpResponse {
 pub enum Listener {
     /// Listener listening on `tcp`.
     Tcp(TcpListener),
+    /// Listener listening on a unix domain socket.
+    #[cfg(unix)]
+    Unix(UnixListener),
+    /// Listener listening on `vsock`, for connections from VM guests.
+    #[cfg(target_os = "linux")]
+    Vsock(VsockListener),
 }
 
 /// A listener stream abstraction
@@ -304,6 +372,12 @@ pub enum Listener {
 pub enum ListenerStream {
     /// Listener listening on `tcp`.
     Tcp(TcpStream, SocketAddr),
+    /// Listener listening on a unix domain socket.
+    #[cfg(unix)]
+    Unix(UnixStream),
+    /// Listener listening on `vsock`.
+    #[cfg(target_os = "linux")]
+    Vsock(VsockStream),
     /// No listener provided.
     Empty(),
 }
@@ -319,10 +393,140 @@ impl Listener {
                     ListenerStream::Empty()
                 }
             },
+            #[cfg(unix)]
+            Listener::Unix(inner) => match inner.accept() {
+                Ok(res) => ListenerStream::Unix(res.0),
+                Err(err) => {
+                    println!("Ignoring failed accept: {err:?}");
+                    ListenerStream::Empty()
+                }
+            },
+            #[cfg(target_os = "linux")]
+            Listener::Vsock(inner) => match inner.accept() {
+                Ok(res) => ListenerStream::Vsock(res),
+                Err(err) => {
+                    println!("Ignoring failed accept: {err:?}");
+                    ListenerStream::Empty()
+                }
+            },
+        }
+    }
+}
+
+/// A bidirectional stream, over which llmp talks to clients and other brokers during the handshake,
+/// and proxies messages for broker 2 broker connections.
+/// Next to `tcp`, this may be a unix domain socket or `vsock`, for setups where shared memory
+/// can't cross the boundary, like containers or VMs.
+#[cfg(feature = "std")]
+pub trait LlmpStream: Read + Write + Debug + Send + 'static {
+    /// Sets the read timeout of this stream. `None` blocks forever.
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<(), Error>;
+}
+
+#[cfg(feature = "std")]
+impl LlmpStream for TcpStream {
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<(), Error> {
+        Ok(TcpStream::set_read_timeout(self, dur)?)
+    }
+}
+
+#[cfg(all(unix, feature = "std"))]
+impl LlmpStream for UnixStream {
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<(), Error> {
+        Ok(UnixStream::set_read_timeout(self, dur)?)
+    }
+}
+
+/// A `vsock` stream, connecting a VM guest and its host.
+#[cfg(all(target_os = "linux", feature = "std"))]
+#[derive(Debug)]
+pub struct VsockStream {
+    inner: File,
+}
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl VsockStream {
+    /// Connects to `port` on the given `cid`. From a VM guest, the host is [`VSOCK_CID_HOST`].
+    pub fn connect(cid: u32, port: u32) -> Result<Self, Error> {
+        let fd = socket::socket(
+            AddressFamily::Vsock,
+            SockType::Stream,
+            SockFlag::SOCK_CLOEXEC,
+            None,
+        )?;
+        // Take ownership first, to close the socket on error.
+        let stream = unsafe { Self::from_raw_fd(fd) };
+        socket::connect(fd, &VsockAddr::new(cid, port))?;
+        Ok(stream)
+    }
+
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Self {
+            inner: File::from_raw_fd(fd),
         }
     }
 }
 
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl Read for VsockStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl Write for VsockStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl LlmpStream for VsockStream {
+    fn set_read_timeout(&self, dur: Option<Duration>) -> Result<(), Error> {
+        // A zero timeout blocks forever.
+        let timeout = TimeVal::microseconds(dur.map_or(0, |dur| dur.as_micros() as i64));
+        socket::setsockopt(self.inner.as_raw_fd(), ReceiveTimeout, &timeout)?;
+        Ok(())
+    }
+}
+
+/// A `vsock` listener, accepting connections from VM guests on the host.
+#[cfg(all(target_os = "linux", feature = "std"))]
+#[derive(Debug)]
+pub struct VsockListener {
+    inner: File,
+}
+
+#[cfg(all(target_os = "linux", feature = "std"))]
+impl VsockListener {
+    /// Listens on `port`, for connections from any cid.
+    pub fn bind(port: u32) -> Result<Self, Error> {
+        let fd = socket::socket(
+            AddressFamily::Vsock,
+            SockType::Stream,
+            SockFlag::SOCK_CLOEXEC,
+            None,
+        )?;
+        let listener = Self {
+            inner: unsafe { File::from_raw_fd(fd) },
+        };
+        socket::bind(fd, &VsockAddr::new(_VSOCK_CID_ANY, port))?;
+        socket::listen(fd, 128)?;
+        Ok(listener)
+    }
+
+    /// Blocks until a new connection arrives.
+    pub fn accept(&self) -> Result<VsockStream, Error> {
+        let fd = socket::accept(self.inner.as_raw_fd())?;
+        Ok(unsafe { VsockStream::from_raw_fd(fd) })
+    }
+}
+
 /// Get sharedmem from a page
 #[inline]
 #[allow(clippy::cast_ptr_alignment)]
@@ -390,8 +594,9 @@ fn tcp_bind(port: u16) -> Result<TcpListener, Error> {
 
 /// Send one message as `u32` len and `[u8;len]` bytes
 #[cfg(feature = "std")]
-fn send_tcp_msg<T>(stream: &mut TcpStream, msg: &T) -> Result<(), Error>
+fn send_tcp_msg<S, T>(stream: &mut S, msg: &T) -> Result<(), Error>
 where
+    S: Write,
     T: Serialize,
 {
     let msg = postcard::to_allocvec(msg)?;
@@ -417,14 +622,14 @@ where
 
 /// Receive one message of `u32` len and `[u8; len]` bytes
 #[cfg(feature = "std")]
-fn recv_tcp_msg(stream: &mut TcpStream) -> Result<Vec<u8>, Error> {
+fn recv_tcp_msg<S>(stream: &mut S) -> Result<Vec<u8>, Error>
+where
+    S: Read,
+{
     // Always receive one be u32 of size, then the command.
 
     #[cfg(feature = "llmp_debug")]
-    println!(
-        "LLMP TCP: Waiting for packet... (Timeout: {:?})",
-        stream.read_timeout().unwrap_or(None)
-    );
+    println!("LLMP TCP: Waiting for packet...");
 
     let mut size_bytes = [0_u8; 4];
     stream.read_exact(&mut size_bytes)?;
@@ -435,9 +640,7 @@ fn recv_tcp_msg(stream: &mut TcpStream) -> Result<Vec<u8>, Error> {
     #[cfg(feature = "llmp_debug")]
     println!("LLMP TCP: Receiving payload of size {size}");
 
-    stream
-        .read_exact(&mut bytes)
-        .expect("Failed to read message body");
+    stream.read_exact(&mut bytes)?;
     Ok(bytes)
 }
 
@@ -483,6 +686,8 @@ unsafe fn _llmp_page_init<SHM: ShMem>(shmem: &mut SHM, sender_id: ClientId, allo
     (*(*page).messages.as_mut_ptr()).tag = LLMP_TAG_UNSET;
     (*page).safe_to_unmap.store(0, Ordering::Relaxed);
     (*page).sender_dead.store(0, Ordering::Relaxed);
+    (*page).receivers_joined_count.store(0, Ordering::Relaxed);
+    (*page).receivers_left_count.store(0, Ordering::Relaxed);
     assert!((*page).size_total != 0);
 }
 
@@ -531,13 +736,16 @@ pub struct LlmpDescription {
     last_message_offset: Option<u64>,
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 /// Result of an LLMP Message hook
 pub enum LlmpMsgHookResult {
     /// This has been handled in the broker. No need to forward.
     Handled,
     /// Forward this to the clients. We are not done here.
     ForwardToClients,
+    /// Forward these bufs, each with its flags, to the clients instead of this message.
+    /// They keep the tag, the sender and the originating broker of this message.
+    ReplaceWith(Vec<(Flags, Vec<u8>)>),
 }
 
 /// Message sent over the "wire"
@@ -548,7 +756,7 @@ pub struct LlmpMsg {
     pub tag: Tag, //u32
     /// Sender of this message
     pub sender: ClientId, //u32
-    /// ID of another Broker, for b2b messages
+    /// ID of the broker this message originated from, for messages with [`LLMP_FLAG_FROM_B2B`]
     pub broker: BrokerId, //u32
     /// flags, currently only used for indicating compression
     pub flags: Flags, //u32
@@ -670,7 +878,7 @@ where
     }
 
     /// Describe this in a reproducable fashion, if it's a client
-    pub fn describe(&self) -> Result<LlmpClientDescription, Error> {
+    pub fn describe(&mut self) -> Result<LlmpClientDescription, Error> {
         Ok(match self {
             LlmpConnection::IsClient { client } => client.describe()?,
             LlmpConnection::IsBroker { .. } => todo!("Only client can be described atm."),
@@ -718,6 +926,11 @@ pub struct LlmpPage {
     pub safe_to_unmap: AtomicU16,
     /// Not used at the moment (would indicate that the sender is no longer there)
     pub sender_dead: AtomicU16,
+    /// The number of receivers that followed an `EOP` to this page,
+    /// or, for the pages of the broker, started reading on this page
+    pub receivers_joined_count: AtomicU16,
+    /// The number of receivers that read this page up to its `EOP`, acknowledging they are done with it
+    pub receivers_left_count: AtomicU16,
     #[cfg(target_pointer_width = "64")]
     /// The current message ID
     pub current_msg_id: AtomicU64,
@@ -735,6 +948,37 @@ pub struct LlmpPage {
     pub messages: [LlmpMsg; 0],
 }
 
+impl LlmpPage {
+    /// A receiver followed an `EOP` to this page
+    #[inline]
+    fn receiver_joined(&mut self) {
+        self.receivers_joined_count.fetch_add(1, Ordering::Release);
+    }
+
+    /// A receiver read this page up to its `EOP`, and will no longer access it
+    #[inline]
+    fn receiver_left(&mut self) {
+        self.receivers_left_count.fetch_add(1, Ordering::Release);
+    }
+
+    /// If all receivers of this page read it up to its `EOP`, so the sender may re-use it.
+    /// The receiver of the first page of a sender did not follow an `EOP` to it, hence the `>=`.
+    #[inline]
+    #[must_use]
+    pub fn is_fully_read(&self) -> bool {
+        let left = self.receivers_left_count.load(Ordering::Acquire);
+        left != 0 && left >= self.receivers_joined_count.load(Ordering::Acquire)
+    }
+
+    /// If all receivers that joined this page also left it.
+    /// For the pages of the broker, every receiver joined the page it started on.
+    #[inline]
+    fn is_left_by_all(&self) -> bool {
+        self.receivers_left_count.load(Ordering::Acquire)
+            >= self.receivers_joined_count.load(Ordering::Acquire)
+    }
+}
+
 /// Message payload when a client got added */
 /// This is an internal message!
 /// [`LLMP_TAG_END_OF_PAGE_V1`]
@@ -747,6 +991,17 @@ struct LlmpPayloadSharedMapInfo {
     pub shm_str: [u8; 20],
 }
 
+/// Message payload of [`LLMP_TAG_NEW_SHM_CLIENT`], when a listener of the broker accepted a new client.
+/// This is an internal message!
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+struct LlmpPayloadNewClient {
+    /// The page of the new client
+    pub map: LlmpPayloadSharedMapInfo,
+    /// The id the listener handed out to the new client, which it sends its messages as
+    pub client_id: ClientId,
+}
+
 /// Sending end on a (unidirectional) sharedmap channel
 #[derive(Debug)]
 pub struct LlmpSender<SP>
@@ -760,13 +1015,14 @@ where
     pub last_msg_sent: *const LlmpMsg,
     /// A vec of page wrappers, each containing an initialized [`ShMem`]
     pub out_shmems: Vec<LlmpSharedMap<SP::ShMem>>,
-    /// If true, pages will never be pruned.
-    /// The broker uses this feature.
-    /// By keeping the message history around,
-    /// new clients may join at any time in the future.
+    /// If true, pages will never be pruned by the sender itself.
+    /// The broker uses this feature, and prunes its pages once all of its clients read them.
     pub keep_pages_forever: bool,
     /// True, if we allocatd a message, but didn't call [`Self::send()`] yet
     has_unsent_message: bool,
+    /// Pages pruned from [`Self::out_shmems`], kept to be re-used once all receivers read them,
+    /// instead of allocating new shared maps forever
+    unused_shmem_cache: Vec<LlmpSharedMap<SP::ShMem>>,
     /// The sharedmem provider to get new sharaed maps if we're full
     shmem_provider: SP,
 }
@@ -794,6 +1050,7 @@ where
             // drop pages to the broker if it already read them
             keep_pages_forever,
             has_unsent_message: false,
+            unused_shmem_cache: vec![],
             shmem_provider,
         })
     }
@@ -918,6 +1175,7 @@ where
             // drop pages to the broker if it already read them
             keep_pages_forever: false,
             has_unsent_message: false,
+            unused_shmem_cache: vec![],
             shmem_provider,
         })
     }
@@ -942,9 +1200,46 @@ where
             panic!("The receiver/broker could not process our sent llmp messages in time. Either we're sending too many messages too fast, the broker got stuck, or it crashed. Giving up.");
         }
 
-        // Remove all maps that the broker already mapped
-        // simply removing them from the vec should then call drop and unmap them.
-        self.out_shmems.drain(0..unmap_until_excl);
+        // Move all maps that the broker already mapped to the cache, to re-use them once it read them.
+        self.unused_shmem_cache
+            .extend(self.out_shmems.drain(0..unmap_until_excl));
+        // Bound the cache, simply removing maps from the vec should then call drop and unmap them.
+        // Keep the maps the broker read already, they can be re-used right away.
+        if self.unused_shmem_cache.len() > LLMP_CFG_MAX_UNUSED_CACHED_PAGES {
+            self.unused_shmem_cache
+                .sort_by_key(|map| (*map.page()).is_fully_read());
+            let excess = self.unused_shmem_cache.len() - LLMP_CFG_MAX_UNUSED_CACHED_PAGES;
+            self.unused_shmem_cache.drain(0..excess);
+        }
+    }
+
+    /// Gets a page of at least `min_size` bytes to continue sending on.
+    /// Re-uses a page from the cache, if all receivers read it up to its `EOP`, else allocates a new one.
+    unsafe fn new_or_unused_shmem(
+        &mut self,
+        sender_id: ClientId,
+        min_size: usize,
+    ) -> Result<LlmpSharedMap<SP::ShMem>, Error> {
+        // Fully read pages too small for the next messages are of no use anymore, unmap them.
+        self.unused_shmem_cache
+            .retain(|map| !((*map.page()).is_fully_read() && map.shmem.len() < min_size));
+
+        if let Some(idx) = self
+            .unused_shmem_cache
+            .iter()
+            .position(|map| (*map.page()).is_fully_read())
+        {
+            let mut map = self.unused_shmem_cache.remove(idx);
+            #[cfg(all(feature = "llmp_debug", feature = "std"))]
+            println!("LLMP_DEBUG: Re-using map {}", map.shmem.id());
+            _llmp_page_init(&mut map.shmem, sender_id, true);
+            Ok(map)
+        } else {
+            Ok(LlmpSharedMap::new(
+                sender_id,
+                self.shmem_provider.new_shmem(min_size)?,
+            ))
+        }
     }
 
     /// Intern: Special allocation function for `EOP` messages (and nothing else!)
@@ -1124,12 +1419,11 @@ where
             next_shmem_size((*old_map).max_alloc_size)
         );
 
-        // Create a new shard page.
-        let mut new_map_shmem = LlmpSharedMap::new(
+        // Create a new shard page, or re-use one all receivers are done with.
+        let mut new_map_shmem = self.new_or_unused_shmem(
             (*old_map).sender_id,
-            self.shmem_provider
-                .new_shmem(next_shmem_size((*old_map).max_alloc_size))?,
-        );
+            next_shmem_size((*old_map).max_alloc_size),
+        )?;
         let mut new_map = new_map_shmem.page_mut();
 
         #[cfg(all(feature = "llmp_debug", feature = "std"))]
@@ -1253,6 +1547,7 @@ where
     pub fn send_buf(&mut self, tag: Tag, buf: &[u8]) -> Result<(), Error> {
         // Make sure we don't reuse already allocated tags
         if tag == LLMP_TAG_NEW_SHM_CLIENT
+            || tag == LLMP_TAG_PIN_PAGE
             || tag == LLMP_TAG_END_OF_PAGE
             || tag == LLMP_TAG_UNINITIALIZED
             || tag == LLMP_TAG_UNSET
@@ -1275,8 +1570,33 @@ where
 
     /// Send a `buf` with the given `flags`.
     pub fn send_buf_with_flags(&mut self, tag: Tag, flags: Flags, buf: &[u8]) -> Result<(), Error> {
+        self.send_buf_from_broker(tag, flags, 0, buf)
+    }
+
+    /// Send a `buf` with the given `flags`, that originated from the broker with the given `broker_id`.
+    fn send_buf_from_broker(
+        &mut self,
+        tag: Tag,
+        flags: Flags,
+        broker_id: BrokerId,
+        buf: &[u8],
+    ) -> Result<(), Error> {
+        self.send_buf_as(None, tag, flags, broker_id, buf)
+    }
+
+    /// Send a `buf` with the given `flags`, that originated from the broker with the given `broker_id`,
+    /// in the name of the client `sender`, or of this sender, if `None`.
+    fn send_buf_as(
+        &mut self,
+        sender: Option<ClientId>,
+        tag: Tag,
+        flags: Flags,
+        broker_id: BrokerId,
+        buf: &[u8],
+    ) -> Result<(), Error> {
         // Make sure we don't reuse already allocated tags
         if tag == LLMP_TAG_NEW_SHM_CLIENT
+            || tag == LLMP_TAG_PIN_PAGE
             || tag == LLMP_TAG_END_OF_PAGE
             || tag == LLMP_TAG_UNINITIALIZED
             || tag == LLMP_TAG_UNSET
@@ -1291,9 +1611,13 @@ where
             let msg = self.alloc_next(buf.len())?;
             (*msg).tag = tag;
             (*msg).flags = flags;
+            (*msg).broker = broker_id;
             buf.as_ptr()
                 .copy_to_nonoverlapping((*msg).buf.as_mut_ptr(), buf.len());
-            self.send(msg, true)
+            if let Some(sender) = sender {
+                (*msg).sender = sender;
+            }
+            self.send(msg, sender.is_none())
         }
     }
 
@@ -1341,6 +1665,8 @@ where
     pub current_recv_shmem: LlmpSharedMap<SP::ShMem>,
     /// Caches the highest msg id we've seen so far
     highest_msg_id: MessageId,
+    /// If set, only messages with one of these tags are received, see [`Self::subscribe`]
+    tag_filter: Option<Vec<Tag>>,
 }
 
 /// Receiving end of an llmp channel
@@ -1387,15 +1713,49 @@ where
             last_msg_recvd,
             shmem_provider,
             highest_msg_id: 0,
+            tag_filter: None,
         })
     }
 
+    /// Only receive messages with one of the given `tags` from now on, next to the ones subscribed to before.
+    /// Other messages are skipped without being returned, so a consumer only interested in a few kinds of messages
+    /// does not need to handle all of them. Internal messages, such as the end of a page, are still handled.
+    pub fn subscribe(&mut self, tags: &[Tag]) {
+        self.tag_filter
+            .get_or_insert_with(Vec::new)
+            .extend_from_slice(tags);
+    }
+
+    /// Receive messages of all tags again
+    pub fn clear_subscriptions(&mut self) {
+        self.tag_filter = None;
+    }
+
+    /// If messages with this `tag` are received, as opposed to skipped
+    #[must_use]
+    pub fn is_subscribed(&self, tag: Tag) -> bool {
+        match &self.tag_filter {
+            Some(tag_filter) => tag_filter.contains(&tag),
+            None => true,
+        }
+    }
+
+    /// Read next message, skipping messages filtered out by [`Self::subscribe`].
+    unsafe fn recv(&mut self) -> Result<Option<*mut LlmpMsg>, Error> {
+        loop {
+            match self.recv_unfiltered()? {
+                Some(msg) if !self.is_subscribed((*msg).tag) => {}
+                ret => return Ok(ret),
+            }
+        }
+    }
+
     // Never inline, to not get some strange effects
     /// Read next message.
     #[inline(never)]
-    unsafe fn recv(&mut self) -> Result<Option<*mut LlmpMsg>, Error> {
+    unsafe fn recv_unfiltered(&mut self) -> Result<Option<*mut LlmpMsg>, Error> {
         /* DBG("recv %p %p\n", page, last_msg); */
-        let mut page = self.current_recv_shmem.page_mut();
+        let page = self.current_recv_shmem.page_mut();
         let last_msg = self.last_msg_recvd;
 
         let (current_msg_id, loaded) =
@@ -1448,6 +1808,8 @@ where
                 LLMP_TAG_EXITING => {
                     // The other side is done.
                     assert_eq!((*msg).buf_len, 0);
+                    // Only report it once, there is nothing after it.
+                    self.last_msg_recvd = msg;
                     return Err(Error::shutting_down());
                 }
                 LLMP_TAG_END_OF_PAGE => {
@@ -1476,15 +1838,23 @@ where
                     // Mark the old page save to unmap, in case we didn't so earlier.
                     (*page).safe_to_unmap.store(1, Ordering::Relaxed);
 
-                    // Map the new page. The old one should be unmapped by Drop
-                    self.current_recv_shmem =
+                    // Map the new page.
+                    let mut new_shmem =
                         LlmpSharedMap::existing(self.shmem_provider.shmem_from_id_and_size(
                             ShMemId::from_array(&pageinfo_cpy.shm_str),
                             pageinfo_cpy.map_size,
                         )?);
-                    page = self.current_recv_shmem.page_mut();
+                    let new_page = new_shmem.page_mut();
                     // Mark the new page save to unmap also (it's mapped by us, the broker now)
-                    (*page).safe_to_unmap.store(1, Ordering::Relaxed);
+                    (*new_page).safe_to_unmap.store(1, Ordering::Relaxed);
+                    // Join the new page before leaving the old one,
+                    // so once all receivers left a page, they all joined the next one.
+                    (*new_page).receiver_joined();
+                    // We are done with the old page, the sender may re-use it from now on.
+                    (*page).receiver_left();
+
+                    // The old page should be unmapped by Drop
+                    self.current_recv_shmem = new_shmem;
 
                     #[cfg(all(feature = "llmp_debug", feature = "std"))]
                     println!(
@@ -1493,7 +1863,7 @@ where
                         self.current_recv_shmem.shmem.len()
                     );
                     // After we mapped the new page, return the next message, if available
-                    return self.recv();
+                    return self.recv_unfiltered();
                 }
                 _ => (),
             }
@@ -1509,25 +1879,27 @@ where
     /// # Safety
     /// Returns a raw ptr, on the recv map. Should be safe in general
     pub unsafe fn recv_blocking(&mut self) -> Result<*mut LlmpMsg, Error> {
-        let mut current_msg_id = 0;
-        let page = self.current_recv_shmem.page_mut();
-        let last_msg = self.last_msg_recvd;
-        if !last_msg.is_null() {
-            assert!(
-                (*last_msg).tag != LLMP_TAG_END_OF_PAGE || llmp_msg_in_page(page, last_msg),
-                "BUG: full page passed to await_message_blocking or reset failed"
-            );
-
-            current_msg_id = (*last_msg).message_id;
-        }
         loop {
-            if (*page).current_msg_id.load(Ordering::Relaxed) != current_msg_id {
-                return match self.recv()? {
-                    Some(msg) => Ok(msg),
-                    None => panic!("BUG: blocking llmp message should never be NULL"),
-                };
+            let mut current_msg_id = 0;
+            let page = self.current_recv_shmem.page_mut();
+            let last_msg = self.last_msg_recvd;
+            if !last_msg.is_null() {
+                assert!(
+                    (*last_msg).tag != LLMP_TAG_END_OF_PAGE || llmp_msg_in_page(page, last_msg),
+                    "BUG: full page passed to await_message_blocking or reset failed"
+                );
+
+                current_msg_id = (*last_msg).message_id;
+            }
+            while (*page).current_msg_id.load(Ordering::Relaxed) == current_msg_id {
+                hint::spin_loop();
+            }
+            match self.recv()? {
+                Some(msg) => return Ok(msg),
+                // Only messages we did not subscribe to arrived, wait for the next ones
+                None if self.tag_filter.is_some() => {}
+                None => panic!("BUG: blocking llmp message should never be NULL"),
             }
-            hint::spin_loop();
         }
     }
 
@@ -1751,6 +2123,18 @@ where
     }
 }
 
+/// What a broker 2 broker proxy thread needs to know to not send messages in circles.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+struct B2bRouting {
+    /// The id of our own broker
+    broker_id: BrokerId,
+    /// The id of the broker on the other side of the connection
+    peer_broker_id: BrokerId,
+    /// If we forward messages from other brokers, see [`LlmpBroker::set_b2b_relay`]
+    relay: bool,
+}
+
 /// The broker (node 0)
 #[derive(Debug)]
 pub struct LlmpBroker<SP>
@@ -1763,18 +2147,35 @@ where
     /// This allows us to intercept messages right in the broker.
     /// This keeps the out map clean.
     pub llmp_clients: Vec<LlmpReceiver<SP>>,
+    /// The next client id to hand out, shared by all listener threads, so that clients connecting
+    /// over different listeners, e.g., tcp and unix sockets, never get the same id
+    #[cfg(feature = "std")]
+    next_client_id: Arc<AtomicU32>,
+    /// The out page new clients start reading on, shared with the listener threads.
+    /// Each new client joins this page, so the broker keeps all pages from there on, until the client left them.
+    #[cfg(feature = "std")]
+    out_page: Arc<Mutex<ShMemDescription>>,
+    /// The random id of this broker, for broker 2 broker connections
+    #[cfg(feature = "std")]
+    broker_id: BrokerId,
+    /// If this broker forwards messages from other brokers to its other broker 2 broker connections
+    #[cfg(feature = "std")]
+    b2b_relay: bool,
+    /// The out page each client pinned when it last described itself, see [`LlmpClient::describe`].
+    /// A restarting client may get restored on this page, so the broker keeps it, and all later pages.
+    pinned_out_pages: Vec<(ClientId, ShMemId)>,
     /// The ShMemProvider to use
     shmem_provider: SP,
 }
 
 /// A signal handler for the [`LlmpBroker`].
-#[cfg(unix)]
+#[cfg(all(unix, feature = "executors"))]
 #[derive(Debug, Clone)]
 pub struct LlmpBrokerSignalHandler {
     shutting_down: bool,
 }
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "executors"))]
 impl Handler for LlmpBrokerSignalHandler {
     fn handle(&mut self, _signal: Signal, _info: siginfo_t, _context: &mut ucontext_t) {
         unsafe {
@@ -1795,25 +2196,108 @@ where
 {
     /// Create and initialize a new [`LlmpBroker`]
     pub fn new(mut shmem_provider: SP) -> Result<Self, Error> {
+        let out_shmem = LlmpSharedMap::new(0, shmem_provider.new_shmem(next_shmem_size(0))?);
+        #[cfg(feature = "std")]
+        let out_page = Arc::new(Mutex::new(out_shmem.shmem.description()));
         Ok(LlmpBroker {
             llmp_out: LlmpSender {
                 id: 0,
                 last_msg_sent: ptr::null_mut(),
-                out_shmems: vec![LlmpSharedMap::new(
-                    0,
-                    shmem_provider.new_shmem(next_shmem_size(0))?,
-                )],
-                // Broker never cleans up the pages so that new
-                // clients may join at any time
+                out_shmems: vec![out_shmem],
+                // The sender never cleans up the pages, the broker prunes them once all clients left them
                 keep_pages_forever: true,
                 has_unsent_message: false,
+                unused_shmem_cache: vec![],
                 shmem_provider: shmem_provider.clone(),
             },
             llmp_clients: vec![],
+            // The broker itself sends its messages as client 0
+            #[cfg(feature = "std")]
+            next_client_id: Arc::new(AtomicU32::new(1)),
+            #[cfg(feature = "std")]
+            out_page,
+            #[cfg(feature = "std")]
+            #[allow(clippy::cast_possible_truncation)]
+            broker_id: StdRand::with_seed(current_nanos()).next() as BrokerId,
+            #[cfg(feature = "std")]
+            b2b_relay: false,
+            pinned_out_pages: vec![],
             shmem_provider,
         })
     }
 
+    /// Makes this broker a relay: it forwards the messages it receives from other brokers to its other broker 2 broker connections,
+    /// except back to the broker they originated from.
+    /// Use this for the central broker of a star of brokers, instead of meshing all brokers with each other.
+    /// The relays must not form a cycle, or messages loop between them forever.
+    /// Call this before launching listeners or connecting to other brokers, existing connections keep their setting.
+    #[cfg(feature = "std")]
+    pub fn set_b2b_relay(&mut self, relay: bool) {
+        self.b2b_relay = relay;
+    }
+
+    /// Counts a new reader of the out pages of the broker, and returns the page it starts reading on, the current one.
+    /// The broker keeps this page, and all following pages, until the reader followed their `EOP`.
+    #[cfg(feature = "std")]
+    fn join_out_page(
+        out_page: &Mutex<ShMemDescription>,
+        shmem_provider: &mut SP,
+    ) -> Result<ShMemDescription, Error> {
+        // The broker only prunes pages while holding the lock, and never the current one
+        let out_page = out_page
+            .lock()
+            .map_err(|_| Error::illegal_state("The llmp out page lock is poisoned"))?;
+        let mut map = LlmpSharedMap::existing(shmem_provider.shmem_from_description(*out_page)?);
+        unsafe {
+            (*map.page_mut()).receiver_joined();
+        }
+        Ok(*out_page)
+    }
+
+    /// Undoes [`Self::join_out_page`], for a reader that never started reading, e.g., because the connection dropped.
+    #[cfg(feature = "std")]
+    fn leave_out_page(description: ShMemDescription, shmem_provider: &mut SP) -> Result<(), Error> {
+        let mut map = LlmpSharedMap::existing(shmem_provider.shmem_from_description(description)?);
+        unsafe {
+            (*map.page_mut()).receiver_left();
+        }
+        Ok(())
+    }
+
+    /// Lets new clients start on the current out page, and unmaps the older pages all readers left.
+    /// Pages are pruned in order, and readers join the next page before leaving one,
+    /// so once all readers left the oldest page, no reader can still come to it.
+    /// A client that dies, and is not restored, never leaves its page, and keeps it and all later pages mapped.
+    /// Pinned pages, see [`Self::unpin_client_page`], are kept even if all readers left them.
+    #[cfg(feature = "std")]
+    fn prune_out_pages(&mut self) -> Result<(), Error> {
+        let mut out_page = self
+            .out_page
+            .lock()
+            .map_err(|_| Error::illegal_state("The llmp out page lock is poisoned"))?;
+        *out_page = self.llmp_out.out_shmems.last().unwrap().shmem.description();
+
+        let mut prune_until_excl = 0;
+        for map in self.llmp_out.out_shmems.split_last().unwrap().1 {
+            let shmem_id = map.shmem.id();
+            if self.pinned_out_pages.iter().any(|(_, id)| *id == shmem_id)
+                || !unsafe { (*map.page()).is_left_by_all() }
+            {
+                break;
+            }
+            prune_until_excl += 1;
+        }
+        self.llmp_out.out_shmems.drain(0..prune_until_excl);
+        Ok(())
+    }
+
+    /// Releases the out page the given client pinned when it last described itself, see [`LlmpClient::describe`],
+    /// so the broker may prune it again, once all readers left it.
+    /// Exiting clients release their page on their own, call this for clients that are gone for good otherwise, e.g., evicted ones.
+    pub fn unpin_client_page(&mut self, client_id: ClientId) {
+        self.pinned_out_pages.retain(|(id, _)| *id != client_id);
+    }
+
     /// Create a new [`LlmpBroker`] sttaching to a TCP port
     #[cfg(feature = "std")]
     pub fn create_attach_to_tcp(shmem_provider: SP, port: u16) -> Result<Self, Error> {
@@ -1833,18 +2317,42 @@ where
     }
 
     /// Registers a new client for the given sharedmap str and size.
-    /// Returns the id of the new client in [`broker.client_shmem`]
-    pub fn register_client(&mut self, mut client_page: LlmpSharedMap<SP::ShMem>) {
+    /// Returns the id of the new client, which the message hooks get for its messages.
+    pub fn register_client(&mut self, client_page: LlmpSharedMap<SP::ShMem>) -> ClientId {
+        let id = self.new_client_id();
+        self.add_client(id, client_page);
+        id
+    }
+
+    /// Hands out a new client id, unique across all listeners of this broker.
+    fn new_client_id(&mut self) -> ClientId {
+        #[cfg(feature = "std")]
+        {
+            self.next_client_id.fetch_add(1, Ordering::SeqCst)
+        }
+        // The broker itself sends its messages as client 0
+        #[cfg(not(feature = "std"))]
+        {
+            self.llmp_clients
+                .iter()
+                .map(|client| client.id + 1)
+                .max()
+                .unwrap_or(1)
+        }
+    }
+
+    /// Starts tracking the page of the client with the given `id`.
+    fn add_client(&mut self, id: ClientId, mut client_page: LlmpSharedMap<SP::ShMem>) {
         // Tell the client it may unmap this page now.
         client_page.mark_safe_to_unmap();
 
-        let id = self.llmp_clients.len() as u32;
         self.llmp_clients.push(LlmpReceiver {
             id,
             current_recv_shmem: client_page,
             last_msg_recvd: ptr::null_mut(),
             shmem_provider: self.shmem_provider.clone(),
             highest_msg_id: 0,
+            tag_filter: None,
         });
     }
 
@@ -1859,7 +2367,65 @@ where
         let mut stream = TcpStream::connect(addr)?;
         println!("B2B: Connected to {stream:?}");
 
-        match recv_tcp_msg(&mut stream)?.try_into()? {
+        let peer_broker_id = Self::b2b_handshake(&mut stream, self.broker_id)?;
+        self.register_b2b_stream(stream, peer_broker_id, None)
+    }
+
+    /// Connects to a broker running on another machine, like [`LlmpBroker::connect_b2b`].
+    /// If the connection drops, the proxy thread keeps trying to reconnect to `addr`
+    /// instead of shutting down, so short network outages do not split the campaign.
+    /// Meanwhile, it buffers the outgoing messages in memory, up to `_LLMP_B2B_MAX_BUFFERED_BYTES` of payload,
+    /// and sends them once it reconnected. If the outage lasts longer, the oldest messages get dropped.
+    #[cfg(feature = "std")]
+    pub fn connect_b2b_reconnecting<A>(&mut self, addr: A) -> Result<(), Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let addrs: Vec<SocketAddr> = addr.to_socket_addrs()?.collect();
+        let mut stream = TcpStream::connect(&addrs[..])?;
+        println!("B2B: Connected to {stream:?}");
+
+        let peer_broker_id = Self::b2b_handshake(&mut stream, self.broker_id)?;
+        let broker_id = self.broker_id;
+        self.register_b2b_stream(
+            stream,
+            peer_broker_id,
+            Some(Box::new(move || Self::b2b_try_reconnect(&addrs, broker_id))),
+        )
+    }
+
+    /// Connects to a broker listening on a unix domain socket at `path`, like [`LlmpBroker::connect_b2b`].
+    /// Use this to connect the broker inside a container to a broker outside, by mounting the socket into the container.
+    #[cfg(all(unix, feature = "std"))]
+    pub fn connect_b2b_unix<P>(&mut self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        let mut stream = UnixStream::connect(path)?;
+        println!("B2B: Connected to {stream:?}");
+
+        let peer_broker_id = Self::b2b_handshake(&mut stream, self.broker_id)?;
+        self.register_b2b_stream(stream, peer_broker_id, None)
+    }
+
+    /// Connects to a broker listening on `vsock`, like [`LlmpBroker::connect_b2b`].
+    /// Use this to connect the broker inside a VM guest to the broker on the host, at [`VSOCK_CID_HOST`].
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    pub fn connect_b2b_vsock(&mut self, cid: u32, port: u32) -> Result<(), Error> {
+        let mut stream = VsockStream::connect(cid, port)?;
+        println!("B2B: Connected to vsock {cid}:{port}");
+
+        let peer_broker_id = Self::b2b_handshake(&mut stream, self.broker_id)?;
+        self.register_b2b_stream(stream, peer_broker_id, None)
+    }
+
+    /// Greets the remote broker on a freshly connected `stream` as the broker `broker_id`, returning the [`BrokerId`] of the remote broker.
+    #[cfg(feature = "std")]
+    fn b2b_handshake<S>(stream: &mut S, broker_id: BrokerId) -> Result<BrokerId, Error>
+    where
+        S: LlmpStream,
+    {
+        match recv_tcp_msg(stream)?.try_into()? {
             TcpResponse::BrokerConnectHello {
                 broker_shmem_description: _,
                 hostname,
@@ -1876,9 +2442,15 @@ where
             .to_string_lossy()
             .into();
 
-        send_tcp_msg(&mut stream, &TcpRequest::RemoteBrokerHello { hostname })?;
+        send_tcp_msg(
+            stream,
+            &TcpRequest::RemoteBrokerHello {
+                hostname,
+                broker_id,
+            },
+        )?;
 
-        let broker_id = match recv_tcp_msg(&mut stream)?.try_into()? {
+        let peer_broker_id = match recv_tcp_msg(stream)?.try_into()? {
             TcpResponse::RemoteBrokerAccepted { broker_id } => {
                 println!("B2B: Got Connection Ack, broker_id {broker_id}");
                 broker_id
@@ -1890,50 +2462,148 @@ where
             }
         };
 
-        // TODO: use broker ids!
         println!("B2B: We are broker {broker_id}");
 
-        // TODO: handle broker_ids properly/at all.
-        let map_description = Self::b2b_thread_on(
+        Ok(peer_broker_id)
+    }
+
+    /// Spawns the proxy thread for an established b2b `stream` to the broker `peer_broker_id`, and registers its page as client.
+    /// If given, `reconnect` is called every `_LLMP_B2B_RECONNECT_TIME` to get a new stream once the connection drops,
+    /// until it returns one.
+    #[cfg(feature = "std")]
+    fn register_b2b_stream<S>(
+        &mut self,
+        stream: S,
+        peer_broker_id: BrokerId,
+        reconnect: Option<Box<dyn FnMut() -> Option<S> + Send>>,
+    ) -> Result<(), Error>
+    where
+        S: LlmpStream,
+    {
+        // The proxy thread reads the out pages, like a client
+        let out_page = Self::join_out_page(&self.out_page, &mut self.shmem_provider)?;
+        let b2b_client_id = self.new_client_id();
+        let map_description = match Self::b2b_thread_on(
             stream,
-            self.llmp_clients.len() as ClientId,
-            &self
-                .llmp_out
-                .out_shmems
-                .first()
-                .unwrap()
-                .shmem
-                .description(),
-        )?;
+            b2b_client_id,
+            B2bRouting {
+                broker_id: self.broker_id,
+                peer_broker_id,
+                relay: self.b2b_relay,
+            },
+            &out_page,
+            reconnect,
+        ) {
+            Ok(map_description) => map_description,
+            Err(err) => {
+                Self::leave_out_page(out_page, &mut self.shmem_provider)?;
+                return Err(err);
+            }
+        };
 
         let new_shmem = LlmpSharedMap::existing(
             self.shmem_provider
                 .shmem_from_description(map_description)?,
         );
 
-        {
-            self.register_client(new_shmem);
-        }
+        self.add_client(b2b_client_id, new_shmem);
 
         Ok(())
     }
 
-    /// For internal use: Forward the current message to the out map.
-    unsafe fn forward_msg(&mut self, msg: *mut LlmpMsg) -> Result<(), Error> {
-        let mut out: *mut LlmpMsg = self.alloc_next((*msg).buf_len_padded as usize)?;
-
-        /* Copy over the whole message.
-        If we should need zero copy, we could instead post a link to the
-        original msg with the map_id and offset. */
-        let actual_size = (*out).buf_len_padded;
-        let complete_size = actual_size as usize + size_of::<LlmpMsg>();
-        (msg as *const u8).copy_to_nonoverlapping(out as *mut u8, complete_size);
-        (*out).buf_len_padded = actual_size;
-        /* We need to replace the message ID with our own */
-        if let Err(e) = self.llmp_out.send(out, false) {
-            panic!("Error sending msg: {e:?}");
+    /// Tries once to re-establish a b2b connection to one of the `addrs`, as the broker `broker_id`.
+    #[cfg(feature = "std")]
+    fn b2b_try_reconnect(addrs: &[SocketAddr], broker_id: BrokerId) -> Option<TcpStream> {
+        match TcpStream::connect(addrs) {
+            Ok(mut stream) => match Self::b2b_handshake(&mut stream, broker_id) {
+                Ok(_) => {
+                    stream
+                        .set_read_timeout(Some(_LLMP_B2B_BLOCK_TIME))
+                        .expect("Failed to set tcp stream timeout");
+                    Some(stream)
+                }
+                Err(e) => {
+                    println!("B2B: Handshake failed while reconnecting: {e}");
+                    None
+                }
+            },
+            Err(e) => {
+                println!("B2B: Reconnect to {addrs:?} failed: {e}");
+                None
+            }
         }
-        self.llmp_out.last_msg_sent = out;
+    }
+
+    /// Spawns a relay broker on this machine, bridging all its clients to the broker at `remote_addr` via tcp.
+    /// Local clients attach to the returned port, for example using [`LlmpClient::create_attach_to_tcp`].
+    /// The relay reconnects to the remote broker if the connection drops, and logs other errors instead of exiting.
+    #[cfg(feature = "std")]
+    pub fn launch_tcp_relay<A>(remote_addr: A) -> Result<u16, Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let remote_addrs: Vec<SocketAddr> = remote_addr.to_socket_addrs()?.collect();
+        // Let the OS pick a free port for the local side of the relay.
+        let listener = tcp_bind(0)?;
+        let port = listener.local_addr()?.port();
+
+        let (send, recv) = channel();
+
+        thread::spawn(move || {
+            let relay = SP::new().and_then(|shmem_provider| {
+                let mut broker = LlmpBroker::new(shmem_provider)?;
+                // Other brokers may connect to the relay, too, and share messages with the remote broker through it.
+                broker.set_b2b_relay(true);
+                broker.launch_listener(Listener::Tcp(listener))?;
+                broker.connect_b2b_reconnecting(&remote_addrs[..])?;
+                Ok(broker)
+            });
+            let mut broker = match relay {
+                Ok(broker) => {
+                    send.send(Ok(()))
+                        .expect("Relay: Error sending status to channel!");
+                    broker
+                }
+                Err(e) => {
+                    send.send(Err(e))
+                        .expect("Relay: Error sending status to channel!");
+                    return;
+                }
+            };
+            // We don't use `loop_forever` here, as it would steal `SIGINT` from the process we live in.
+            loop {
+                if let Err(e) =
+                    broker.once(&mut |_, _, _, _| Ok(LlmpMsgHookResult::ForwardToClients))
+                {
+                    println!("Relay: Error while relaying, continuing: {e}");
+                }
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+
+        recv.recv().map_err(|_| {
+            Error::unknown("Error launching background thread for llmp tcp relay".to_string())
+        })??;
+
+        Ok(port)
+    }
+
+    /// For internal use: Forward the current message to the out map.
+    unsafe fn forward_msg(&mut self, msg: *mut LlmpMsg) -> Result<(), Error> {
+        let mut out: *mut LlmpMsg = self.alloc_next((*msg).buf_len_padded as usize)?;
+
+        /* Copy over the whole message.
+        If we should need zero copy, we could instead post a link to the
+        original msg with the map_id and offset. */
+        let actual_size = (*out).buf_len_padded;
+        let complete_size = actual_size as usize + size_of::<LlmpMsg>();
+        (msg as *const u8).copy_to_nonoverlapping(out as *mut u8, complete_size);
+        (*out).buf_len_padded = actual_size;
+        /* We need to replace the message ID with our own */
+        if let Err(e) = self.llmp_out.send(out, false) {
+            panic!("Error sending msg: {e:?}");
+        }
+        self.llmp_out.last_msg_sent = out;
         Ok(())
     }
 
@@ -1946,15 +2616,17 @@ where
     {
         for i in 0..self.llmp_clients.len() {
             unsafe {
-                self.handle_new_msgs(i as u32, on_new_msg)?;
+                self.handle_new_msgs(i, on_new_msg)?;
             }
         }
+        #[cfg(feature = "std")]
+        self.prune_out_pages()?;
         Ok(())
     }
 
     /// Internal function, returns true when shuttdown is requested by a `SIGINT` signal
     #[inline]
-    #[cfg(unix)]
+    #[cfg(all(unix, feature = "executors"))]
     #[allow(clippy::unused_self)]
     fn is_shutting_down(&self) -> bool {
         unsafe { ptr::read_volatile(&GLOBAL_SIGHANDLER_STATE.shutting_down) }
@@ -1962,7 +2634,7 @@ where
 
     /// Always returns true on platforms, where no shutdown signal handlers are supported
     #[inline]
-    #[cfg(not(unix))]
+    #[cfg(not(all(unix, feature = "executors")))]
     #[allow(clippy::unused_self)]
     fn is_shutting_down(&self) -> bool {
         false
@@ -1975,7 +2647,22 @@ where
     where
         F: FnMut(ClientId, Tag, Flags, &[u8]) -> Result<LlmpMsgHookResult, Error>,
     {
-        #[cfg(unix)]
+        self.loop_forever_with_idle(on_new_msg, &mut |_| Ok(()), sleep_time);
+    }
+
+    /// Loops infinitely, like [`LlmpBroker::loop_forever`].
+    /// After each round of handling the incoming messages, calls `on_idle` with this broker,
+    /// for example to broadcast messages of its own.
+    pub fn loop_forever_with_idle<F, G>(
+        &mut self,
+        on_new_msg: &mut F,
+        on_idle: &mut G,
+        sleep_time: Option<Duration>,
+    ) where
+        F: FnMut(ClientId, Tag, Flags, &[u8]) -> Result<LlmpMsgHookResult, Error>,
+        G: FnMut(&mut Self) -> Result<(), Error>,
+    {
+        #[cfg(all(unix, feature = "executors"))]
         if let Err(_e) = unsafe { setup_signal_handler(&mut GLOBAL_SIGHANDLER_STATE) } {
             // We can live without a proper ctrl+c signal handler. Print and ignore.
             #[cfg(feature = "std")]
@@ -1985,6 +2672,7 @@ where
         while !self.is_shutting_down() {
             self.once(on_new_msg)
                 .expect("An error occurred when brokering. Exiting.");
+            on_idle(self).expect("An error occurred when brokering. Exiting.");
 
             #[cfg(feature = "std")]
             if let Some(time) = sleep_time {
@@ -2021,23 +2709,50 @@ where
         self.launch_listener(Listener::Tcp(listener))
     }
 
-    /// Announces a new client on the given shared map.
+    /// Launches a thread listening on a unix domain socket at `path`, on which new clients and brokers may connect to this broker.
+    /// A socket left behind at `path` by a broker that did not exit cleanly is removed first.
+    #[cfg(all(unix, feature = "std"))]
+    pub fn launch_unix_listener_on<P>(&mut self, path: P) -> Result<thread::JoinHandle<()>, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let stale = fs::symlink_metadata(&path).map_or(false, |meta| meta.file_type().is_socket());
+        // Nobody listens on a stale socket anymore, don't steal the socket of a running broker.
+        if stale && UnixStream::connect(&path).is_err() {
+            fs::remove_file(&path)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        println!("Server listening on {}", path.as_ref().display());
+        self.launch_listener(Listener::Unix(listener))
+    }
+
+    /// Launches a thread listening on `vsock` on the given port, on which brokers inside VM guests may connect to this broker.
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    pub fn launch_vsock_listener_on(&mut self, port: u32) -> Result<thread::JoinHandle<()>, Error> {
+        let listener = VsockListener::bind(port)?;
+        println!("Server listening on vsock port {port}");
+        self.launch_listener(Listener::Vsock(listener))
+    }
+
+    /// Announces a new client with the id `client_id` on the given shared map.
     /// Called from a background thread, typically.
     /// Upon receiving this message, the broker should map the announced page and start trckang it for new messages.
     #[allow(dead_code)]
     fn announce_new_client(
         sender: &mut LlmpSender<SP>,
         shmem_description: &ShMemDescription,
+        client_id: ClientId,
     ) -> Result<(), Error> {
         unsafe {
             let msg = sender
-                .alloc_next(size_of::<LlmpPayloadSharedMapInfo>())
+                .alloc_next(size_of::<LlmpPayloadNewClient>())
                 .expect("Could not allocate a new message in shared map.");
             (*msg).tag = LLMP_TAG_NEW_SHM_CLIENT;
             #[allow(clippy::cast_ptr_alignment)]
-            let pageinfo = (*msg).buf.as_mut_ptr() as *mut LlmpPayloadSharedMapInfo;
-            (*pageinfo).shm_str = *shmem_description.id.as_array();
-            (*pageinfo).map_size = shmem_description.size;
+            let payload = (*msg).buf.as_mut_ptr() as *mut LlmpPayloadNewClient;
+            (*payload).map.shm_str = *shmem_description.id.as_array();
+            (*payload).map.map_size = shmem_description.size;
+            (*payload).client_id = client_id;
             sender.send(msg, true)
         }
     }
@@ -2046,14 +2761,19 @@ where
     /// Launches a proxy thread.
     /// It will read outgoing messages from the given broker map (and handle EOP by mapping a new page).
     /// This function returns the [`ShMemDescription`] the client uses to place incoming messages.
-    /// The thread exits, when the remote broker disconnects.
+    /// The thread exits, when the remote broker disconnects, unless it can `reconnect`.
     #[cfg(feature = "std")]
-    #[allow(clippy::let_and_return)]
-    fn b2b_thread_on(
-        mut stream: TcpStream,
+    #[allow(clippy::let_and_return, clippy::too_many_lines)]
+    fn b2b_thread_on<S>(
+        mut stream: S,
         b2b_client_id: ClientId,
+        routing: B2bRouting,
         broker_shmem_description: &ShMemDescription,
-    ) -> Result<ShMemDescription, Error> {
+        mut reconnect: Option<Box<dyn FnMut() -> Option<S> + Send>>,
+    ) -> Result<ShMemDescription, Error>
+    where
+        S: LlmpStream,
+    {
         let broker_shmem_description = *broker_shmem_description;
 
         // A channel to get the new "client's" sharedmap id from
@@ -2070,7 +2790,7 @@ where
             // The background thread blocks on the incoming connection for 15 seconds (if no data is available), then checks if it should forward own messages, then blocks some more.
             stream
                 .set_read_timeout(Some(_LLMP_B2B_BLOCK_TIME))
-                .expect("Failed to set tcp stream timeout");
+                .expect("Failed to set b2b stream timeout");
 
             let mut new_sender =
                 match LlmpSender::new(shmem_provider_bg.clone(), b2b_client_id, false) {
@@ -2096,12 +2816,41 @@ where
             #[cfg(all(feature = "llmp_debug", feature = "std"))]
             println!("B2B: Starting proxy loop :)");
 
+            // The messages from the local broker that we did not send yet, because the connection dropped.
+            let mut outgoing: VecDeque<TcpRemoteNewMessage> = VecDeque::new();
+            // The payload bytes buffered in `outgoing`
+            let mut outgoing_bytes = 0;
+            let mut connected = true;
+            // When we last tried to reconnect, `None` to try right away.
+            let mut last_reconnect: Option<Instant> = None;
+
             loop {
-                // first, forward all data we have.
-                while let Some((client_id, tag, flags, payload)) = local_receiver
-                    .recv_buf_with_flags()
-                    .expect("Error reading from local page!")
+                if !connected {
+                    let retry = match last_reconnect {
+                        Some(time) => time.elapsed() >= _LLMP_B2B_RECONNECT_TIME,
+                        None => true,
+                    };
+                    if retry {
+                        last_reconnect = Some(Instant::now());
+                        if let Some(new_stream) =
+                            reconnect.as_mut().and_then(|reconnect| reconnect())
+                        {
+                            println!(
+                                "B2B: Reconnected, sending {} buffered messages",
+                                outgoing.len()
+                            );
+                            stream = new_stream;
+                            connected = true;
+                        }
+                    }
+                }
+
+                // first, collect all data we have.
+                while let Some(msg) =
+                    unsafe { local_receiver.recv() }.expect("Error reading from local page!")
                 {
+                    let (client_id, tag, flags, origin) =
+                        unsafe { ((*msg).sender, (*msg).tag, (*msg).flags, (*msg).broker) };
                     if client_id == b2b_client_id {
                         println!(
                             "Ignored message we probably sent earlier (same id), TAG: {:x}",
@@ -2110,49 +2859,122 @@ where
                         continue;
                     }
 
+                    // Messages that reached us via another b2b connection were already shared by their origin broker,
+                    // with everybody in a meshed setup. Only relays pass them on, but never back to their origin.
+                    let broker_id = if flags & LLMP_FLAG_FROM_B2B == LLMP_FLAG_FROM_B2B {
+                        if !routing.relay || origin == routing.peer_broker_id {
+                            #[cfg(all(feature = "llmp_debug", feature = "std"))]
+                            println!("Ignored message from another broker, TAG: {tag:x}");
+                            continue;
+                        }
+                        origin
+                    } else {
+                        routing.broker_id
+                    };
+
+                    let payload =
+                        unsafe { (*msg).try_as_slice(&mut local_receiver.current_recv_shmem) }
+                            .expect("Message not in the local page!");
+
+                    // We got a new message! Queue it for forwarding...
+                    outgoing_bytes += payload.len();
+                    outgoing.push_back(TcpRemoteNewMessage {
+                        client_id,
+                        broker_id,
+                        tag,
+                        flags,
+                        payload: payload.to_vec(),
+                    });
+                }
+
+                // Don't let a long outage eat up all memory, rather lose the oldest messages.
+                let mut dropped = 0;
+                while outgoing_bytes > _LLMP_B2B_MAX_BUFFERED_BYTES {
+                    let msg = outgoing.pop_front().unwrap();
+                    outgoing_bytes -= msg.payload.len();
+                    dropped += 1;
+                }
+                if dropped > 0 {
+                    println!("B2B: Buffer full, dropped the {dropped} oldest messages");
+                }
+
+                // Then, forward the queued messages in order, as long as the connection holds.
+                while let Some(msg) = outgoing.front() {
+                    if !connected {
+                        break;
+                    }
+
                     #[cfg(all(feature = "llmp_debug", feature = "std"))]
                     println!(
                         "Fowarding message ({} bytes) via broker2broker connection",
-                        payload.len()
+                        msg.payload.len()
                     );
-                    // We got a new message! Forward...
-                    send_tcp_msg(
-                        &mut stream,
-                        &TcpRemoteNewMessage {
-                            client_id,
-                            tag,
-                            flags,
-                            payload: payload.to_vec(),
-                        },
-                    )
-                    .expect("Error sending message via broker 2 broker");
+                    if let Err(e) = send_tcp_msg(&mut stream, msg) {
+                        assert!(
+                            reconnect.is_some(),
+                            "Error sending message via broker 2 broker: {e}"
+                        );
+                        println!("B2B: Connection lost ({e}), buffering messages until we reconnected...");
+                        connected = false;
+                        last_reconnect = None;
+                    } else {
+                        outgoing_bytes -= msg.payload.len();
+                        outgoing.pop_front();
+                    }
+                }
+
+                if !connected {
+                    // Keep reading the local broker, so it can unmap its pages, and try to reconnect in a bit.
+                    thread::sleep(_LLMP_B2B_BLOCK_TIME);
+                    continue;
                 }
 
                 // Then, see if we can receive something.
                 // We set a timeout on the receive earlier.
                 // This makes sure we will still forward our own stuff.
                 // Forwarding happens between each recv, too, as simplification.
-                // We ignore errors completely as they may be timeout, or stream closings.
+                // Without a way to reconnect, we ignore errors completely as they may be timeout, or stream closings.
                 // Instead, we catch stream close when/if we next try to send.
-                if let Ok(val) = recv_tcp_msg(&mut stream) {
-                    let msg: TcpRemoteNewMessage = val.try_into().expect(
-                        "Illegal message received from broker 2 broker connection - shutting down.",
-                    );
+                match recv_tcp_msg(&mut stream) {
+                    Ok(val) => {
+                        let msg: TcpRemoteNewMessage = val.try_into().expect(
+                            "Illegal message received from broker 2 broker connection - shutting down.",
+                        );
 
-                    #[cfg(all(feature = "llmp_debug", feature = "std"))]
-                    println!(
-                        "Fowarding incoming message ({} bytes) from broker2broker connection",
-                        msg.payload.len()
-                    );
+                        #[cfg(all(feature = "llmp_debug", feature = "std"))]
+                        println!(
+                            "Fowarding incoming message ({} bytes) from broker2broker connection",
+                            msg.payload.len()
+                        );
 
-                    // TODO: Could probably optimize this somehow to forward all queued messages between locks... oh well.
-                    // Todo: somehow mangle in the other broker id? ClientId?
-                    new_sender
-                        .send_buf_with_flags(msg.tag, msg.flags | LLMP_FLAG_FROM_B2B, &msg.payload)
-                        .expect("B2B: Error forwarding message. Exiting.");
-                } else {
-                    #[cfg(all(feature = "llmp_debug", feature = "std"))]
-                    println!("Received no input, timeout or closed. Looping back up :)");
+                        // A relay sent our own message back to us
+                        if msg.broker_id == routing.broker_id {
+                            continue;
+                        }
+
+                        // TODO: Could probably optimize this somehow to forward all queued messages between locks... oh well.
+                        new_sender
+                            .send_buf_from_broker(
+                                msg.tag,
+                                msg.flags | LLMP_FLAG_FROM_B2B,
+                                msg.broker_id,
+                                &msg.payload,
+                            )
+                            .expect("B2B: Error forwarding message. Exiting.");
+                    }
+                    // The connection dropped, not just a timeout: reconnect, even if we have nothing to send.
+                    Err(Error::File(e, _))
+                        if reconnect.is_some()
+                            && !matches!(e.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) =>
+                    {
+                        println!("B2B: Connection lost ({e}), buffering messages until we reconnected...");
+                        connected = false;
+                        last_reconnect = None;
+                    }
+                    Err(_) => {
+                        #[cfg(all(feature = "llmp_debug", feature = "std"))]
+                        println!("Received no input, timeout or closed. Looping back up :)");
+                    }
                 }
             }
         });
@@ -2168,79 +2990,147 @@ where
     }
 
     /// handles a single tcp request in the current context.
+    /// The new client, or the b2b proxy thread, reads the out pages from `broker_shmem_description` on, which it joined already.
     #[cfg(feature = "std")]
-    fn handle_tcp_request(
-        mut stream: TcpStream,
+    fn handle_tcp_request<S>(
+        mut stream: S,
         request: &TcpRequest,
-        current_client_id: &mut u32,
+        broker_id: BrokerId,
+        relay: bool,
+        next_client_id: &AtomicU32,
         sender: &mut LlmpSender<SP>,
         broker_shmem_description: &ShMemDescription,
-    ) {
+    ) where
+        S: LlmpStream,
+    {
+        let client_id = next_client_id.fetch_add(1, Ordering::SeqCst);
         match request {
             TcpRequest::LocalClientHello { shmem_description } => {
-                match Self::announce_new_client(sender, shmem_description) {
+                match Self::announce_new_client(sender, shmem_description, client_id) {
                     Ok(()) => (),
                     Err(e) => println!("Error forwarding client on map: {e:?}"),
                 };
 
-                if let Err(e) = send_tcp_msg(
-                    &mut stream,
-                    &TcpResponse::LocalClientAccepted {
-                        client_id: *current_client_id,
-                    },
-                ) {
+                if let Err(e) =
+                    send_tcp_msg(&mut stream, &TcpResponse::LocalClientAccepted { client_id })
+                {
                     println!("An error occurred sending via tcp {e}");
                 };
-                *current_client_id += 1;
             }
-            TcpRequest::RemoteBrokerHello { hostname } => {
-                println!("B2B new client: {hostname}");
+            TcpRequest::RemoteBrokerHello {
+                hostname,
+                broker_id: peer_broker_id,
+            } => {
+                println!("B2B new client: {hostname}, broker {peer_broker_id}");
 
-                // TODO: Clean up broker ids.
                 if send_tcp_msg(
                     &mut stream,
-                    &TcpResponse::RemoteBrokerAccepted {
-                        broker_id: *current_client_id,
-                    },
+                    &TcpResponse::RemoteBrokerAccepted { broker_id },
                 )
                 .is_err()
                 {
                     println!("Error accepting broker, ignoring.");
+                    let _ =
+                        Self::leave_out_page(*broker_shmem_description, &mut sender.shmem_provider);
                     return;
                 }
 
-                if let Ok(shmem_description) =
-                    Self::b2b_thread_on(stream, *current_client_id, broker_shmem_description)
-                {
-                    if Self::announce_new_client(sender, &shmem_description).is_err() {
-                        println!("B2B: Error announcing client {shmem_description:?}");
-                    };
-                    *current_client_id += 1;
+                let routing = B2bRouting {
+                    broker_id,
+                    peer_broker_id: *peer_broker_id,
+                    relay,
+                };
+                match Self::b2b_thread_on(
+                    stream,
+                    client_id,
+                    routing,
+                    broker_shmem_description,
+                    None,
+                ) {
+                    Ok(shmem_description) => {
+                        if Self::announce_new_client(sender, &shmem_description, client_id).is_err()
+                        {
+                            println!("B2B: Error announcing client {shmem_description:?}");
+                        };
+                    }
+                    Err(_) => {
+                        let _ = Self::leave_out_page(
+                            *broker_shmem_description,
+                            &mut sender.shmem_provider,
+                        );
+                    }
                 }
             }
         };
     }
 
+    /// Greets a freshly accepted connection and handles its (single) request.
+    /// The new client starts reading on the current out page, see [`Self::join_out_page`].
+    #[cfg(feature = "std")]
+    fn handle_new_connection<S>(
+        mut stream: S,
+        hostname: &str,
+        broker_id: BrokerId,
+        relay: bool,
+        out_page: &Mutex<ShMemDescription>,
+        next_client_id: &AtomicU32,
+        sender: &mut LlmpSender<SP>,
+    ) where
+        S: LlmpStream,
+    {
+        let broker_shmem_description =
+            match Self::join_out_page(out_page, &mut sender.shmem_provider) {
+                Ok(description) => description,
+                Err(e) => {
+                    eprintln!("Error joining the broker page: {e:?}");
+                    return;
+                }
+            };
+        let broker_hello = TcpResponse::BrokerConnectHello {
+            broker_shmem_description,
+            hostname: hostname.to_string(),
+        };
+
+        // Send initial information, without anyone asking.
+        // This makes it a tiny bit easier to map the  broker map for new Clients.
+        let req = match send_tcp_msg(&mut stream, &broker_hello)
+            .and_then(|()| recv_tcp_msg(&mut stream))
+            .and_then(TryInto::try_into)
+        {
+            Ok(req) => req,
+            Err(e) => {
+                eprintln!("Error greeting the new connection: {e:?}");
+                let _ = Self::leave_out_page(broker_shmem_description, &mut sender.shmem_provider);
+                return;
+            }
+        };
+
+        Self::handle_tcp_request(
+            stream,
+            &req,
+            broker_id,
+            relay,
+            next_client_id,
+            sender,
+            &broker_shmem_description,
+        );
+    }
+
     #[cfg(feature = "std")]
     /// Launches a thread using a listener socket, on which new clients may connect to this broker
     pub fn launch_listener(&mut self, listener: Listener) -> Result<thread::JoinHandle<()>, Error> {
-        // Later in the execution, after the initial map filled up,
-        // the current broacast map will will point to a different map.
-        // However, the original map is (as of now) never freed, new clients will start
-        // to read from the initial map id.
-
-        let client_out_shmem_mem = &self.llmp_out.out_shmems.first().unwrap().shmem;
-        let broker_shmem_description = client_out_shmem_mem.description();
-        let hostname = hostname::get()
+        // New clients start to read on the current broadcast map, which the broker updates as the maps fill up.
+        // Older maps may be gone already.
+        let out_page = self.out_page.clone();
+        let hostname: String = hostname::get()
             .unwrap_or_else(|_| "<unknown>".into())
             .to_string_lossy()
             .into();
-        let broker_hello = TcpResponse::BrokerConnectHello {
-            broker_shmem_description,
-            hostname,
-        };
 
-        let llmp_tcp_id = self.llmp_clients.len() as ClientId;
+        let (broker_id, relay) = (self.broker_id, self.b2b_relay);
+
+        let llmp_tcp_id = self.next_client_id.fetch_add(1, Ordering::SeqCst);
+        let next_client_id = self.next_client_id.clone();
 
         // Tcp out map sends messages from background thread tcp server to foreground client
         let tcp_out_shmem = LlmpSharedMap::new(
@@ -2248,14 +3138,12 @@ where
             self.shmem_provider.new_shmem(LLMP_CFG_INITIAL_MAP_SIZE)?,
         );
         let tcp_out_shmem_description = tcp_out_shmem.shmem.description();
-        self.register_client(tcp_out_shmem);
+        self.add_client(llmp_tcp_id, tcp_out_shmem);
 
         let ret = thread::spawn(move || {
             // Create a new ShMemProvider for this background thread.
             let mut shmem_provider_bg = SP::new().unwrap();
 
-            let mut current_client_id = llmp_tcp_id + 1;
-
             let mut tcp_incoming_sender = LlmpSender {
                 id: llmp_tcp_id,
                 last_msg_sent: ptr::null_mut(),
@@ -2267,49 +3155,52 @@ where
                 // drop pages to the broker, if it already read them.
                 keep_pages_forever: false,
                 has_unsent_message: false,
+                unused_shmem_cache: vec![],
                 shmem_provider: shmem_provider_bg.clone(),
             };
 
             loop {
                 match listener.accept() {
-                    ListenerStream::Tcp(mut stream, addr) => {
+                    ListenerStream::Tcp(stream, addr) => {
                         eprintln!(
                             "New connection: {:?}/{:?}",
                             addr,
                             stream.peer_addr().unwrap()
                         );
-
-                        // Send initial information, without anyone asking.
-                        // This makes it a tiny bit easier to map the  broker map for new Clients.
-                        match send_tcp_msg(&mut stream, &broker_hello) {
-                            Ok(()) => {}
-                            Err(e) => {
-                                eprintln!("Error sending initial hello: {e:?}");
-                                continue;
-                            }
-                        }
-
-                        let buf = match recv_tcp_msg(&mut stream) {
-                            Ok(buf) => buf,
-                            Err(e) => {
-                                eprintln!("Error receving from tcp: {e:?}");
-                                continue;
-                            }
-                        };
-                        let req = match buf.try_into() {
-                            Ok(req) => req,
-                            Err(e) => {
-                                eprintln!("Could not deserialize tcp message: {e:?}");
-                                continue;
-                            }
-                        };
-
-                        Self::handle_tcp_request(
+                        Self::handle_new_connection(
                             stream,
-                            &req,
-                            &mut current_client_id,
+                            &hostname,
+                            broker_id,
+                            relay,
+                            &out_page,
+                            &next_client_id,
+                            &mut tcp_incoming_sender,
+                        );
+                    }
+                    #[cfg(unix)]
+                    ListenerStream::Unix(stream) => {
+                        eprintln!("New connection: {stream:?}");
+                        Self::handle_new_connection(
+                            stream,
+                            &hostname,
+                            broker_id,
+                            relay,
+                            &out_page,
+                            &next_client_id,
+                            &mut tcp_incoming_sender,
+                        );
+                    }
+                    #[cfg(target_os = "linux")]
+                    ListenerStream::Vsock(stream) => {
+                        eprintln!("New vsock connection");
+                        Self::handle_new_connection(
+                            stream,
+                            &hostname,
+                            broker_id,
+                            relay,
+                            &out_page,
+                            &next_client_id,
                             &mut tcp_incoming_sender,
-                            &broker_shmem_description,
                         );
                     }
                     ListenerStream::Empty() => {
@@ -2325,22 +3216,33 @@ where
     /// broker broadcast to its own page for all others to read */
     #[inline]
     #[allow(clippy::cast_ptr_alignment)]
-    unsafe fn handle_new_msgs<F>(&mut self, client_id: u32, on_new_msg: &mut F) -> Result<(), Error>
+    unsafe fn handle_new_msgs<F>(
+        &mut self,
+        client_idx: usize,
+        on_new_msg: &mut F,
+    ) -> Result<(), Error>
     where
         F: FnMut(ClientId, Tag, Flags, &[u8]) -> Result<LlmpMsgHookResult, Error>,
     {
-        let mut next_id = self.llmp_clients.len() as u32;
+        let client_id = self.llmp_clients[client_idx].id;
 
         // TODO: We could memcpy a range of pending messages, instead of one by one.
         loop {
             let msg = {
-                let client = &mut self.llmp_clients[client_id as usize];
-                match client.recv()? {
-                    None => {
+                let client = &mut self.llmp_clients[client_idx];
+                match client.recv() {
+                    Ok(None) => {
                         // We're done handling this client
                         return Ok(());
                     }
-                    Some(msg) => msg,
+                    Ok(Some(msg)) => msg,
+                    Err(Error::ShuttingDown) => {
+                        // The client exited, it won't send anything anymore, nor get restored
+                        self.unpin_client_page(client_id);
+                        (on_new_msg)(client_id, LLMP_TAG_EXITING, LLMP_FLAG_INITIALIZED, &[])?;
+                        return Ok(());
+                    }
+                    Err(e) => return Err(e),
                 }
             };
 
@@ -2366,23 +3268,20 @@ where
                         )));
                     }
                     let pageinfo = (*msg).buf.as_mut_ptr() as *mut LlmpPayloadSharedMapInfo;
+                    // Our listeners send the id they handed out to the client, other clients only announce the page
+                    let new_client_id =
+                        if (*msg).buf_len >= size_of::<LlmpPayloadNewClient>() as u64 {
+                            (*((*msg).buf.as_mut_ptr() as *mut LlmpPayloadNewClient)).client_id
+                        } else {
+                            self.new_client_id()
+                        };
 
                     match self.shmem_provider.shmem_from_id_and_size(
                         ShMemId::from_array(&(*pageinfo).shm_str),
                         (*pageinfo).map_size,
                     ) {
                         Ok(new_shmem) => {
-                            let mut new_page = LlmpSharedMap::existing(new_shmem);
-                            let id = next_id;
-                            next_id += 1;
-                            new_page.mark_safe_to_unmap();
-                            self.llmp_clients.push(LlmpReceiver {
-                                id,
-                                current_recv_shmem: new_page,
-                                last_msg_recvd: ptr::null_mut(),
-                                shmem_provider: self.shmem_provider.clone(),
-                                highest_msg_id: 0,
-                            });
+                            self.add_client(new_client_id, LlmpSharedMap::existing(new_shmem));
                         }
                         Err(e) => {
                             #[cfg(feature = "std")]
@@ -2395,20 +3294,50 @@ where
                         }
                     };
                 }
+                LLMP_TAG_PIN_PAGE => {
+                    /* The client described itself, and may get restored on this page of ours.
+                    Also, no need to forward this msg. */
+                    if (*msg).buf_len < size_of::<LlmpPayloadSharedMapInfo>() as u64 {
+                        return Err(Error::illegal_state(format!(
+                            "Broken PIN_PAGE msg of client {client_id} with incorrect size {}",
+                            (*msg).buf_len
+                        )));
+                    }
+                    let pageinfo = *((*msg).buf.as_mut_ptr() as *mut LlmpPayloadSharedMapInfo);
+                    let shmem_id = ShMemId::from_array(&pageinfo.shm_str);
+                    if let Some(map) = self
+                        .llmp_out
+                        .out_shmems
+                        .iter_mut()
+                        .find(|map| map.shmem.id() == shmem_id)
+                    {
+                        // From now on, the pin keeps the page, instead of the extra join of the client
+                        (*map.page_mut()).receiver_left();
+                        self.unpin_client_page(client_id);
+                        self.pinned_out_pages.push((client_id, shmem_id));
+                    }
+                }
                 // handle all other messages
                 _ => {
                     // The message is not specifically for use. Let the user handle it, then forward it to the clients, if necessary.
-                    let mut should_forward_msg = true;
-
-                    let map = &mut self.llmp_clients[client_id as usize].current_recv_shmem;
+                    let map = &mut self.llmp_clients[client_idx].current_recv_shmem;
                     let msg_buf = (*msg).try_as_slice(map)?;
-                    if let LlmpMsgHookResult::Handled =
-                        (on_new_msg)(client_id, (*msg).tag, (*msg).flags, msg_buf)?
-                    {
-                        should_forward_msg = false;
-                    }
-                    if should_forward_msg {
-                        self.forward_msg(msg)?;
+                    match (on_new_msg)(client_id, (*msg).tag, (*msg).flags, msg_buf)? {
+                        LlmpMsgHookResult::Handled => (),
+                        LlmpMsgHookResult::ForwardToClients => self.forward_msg(msg)?,
+                        LlmpMsgHookResult::ReplaceWith(bufs) => {
+                            let (sender, tag, broker_id) =
+                                ((*msg).sender, (*msg).tag, (*msg).broker);
+                            for (flags, buf) in bufs {
+                                self.llmp_out.send_buf_as(
+                                    Some(sender),
+                                    tag,
+                                    flags,
+                                    broker_id,
+                                    &buf,
+                                )?;
+                            }
+                        }
                     }
                 }
             }
@@ -2491,14 +3420,35 @@ where
         self.receiver.to_env(&format!("{env_name}_RECEIVER"))
     }
 
-    /// Describe this client in a way that it can be recreated, for example after crash
-    pub fn describe(&self) -> Result<LlmpClientDescription, Error> {
+    /// Describe this client in a way that it can be recreated, for example after crash.
+    /// The broker keeps the page the receiver is on, until this client describes itself again, or exits,
+    /// so the client can still be restored after it read on.
+    pub fn describe(&mut self) -> Result<LlmpClientDescription, Error> {
+        self.pin_receiver_page()?;
         Ok(LlmpClientDescription {
             sender: self.sender.describe()?,
             receiver: self.receiver.describe()?,
         })
     }
 
+    /// Tells the broker to keep the page the receiver is on.
+    /// Until the broker handled the message, the page is joined once more, so it cannot be pruned in the meantime.
+    fn pin_receiver_page(&mut self) -> Result<(), Error> {
+        unsafe {
+            let recv_shmem = &mut self.receiver.current_recv_shmem;
+            (*recv_shmem.page_mut()).receiver_joined();
+            let msg = self
+                .sender
+                .alloc_next(size_of::<LlmpPayloadSharedMapInfo>())?;
+            (*msg).tag = LLMP_TAG_PIN_PAGE;
+            #[allow(clippy::cast_ptr_alignment)]
+            let pageinfo = (*msg).buf.as_mut_ptr() as *mut LlmpPayloadSharedMapInfo;
+            (*pageinfo).shm_str = *recv_shmem.shmem.id().as_array();
+            (*pageinfo).map_size = recv_shmem.shmem.len();
+            self.sender.send(msg, true)
+        }
+    }
+
     /// Create an existing client from description
     pub fn existing_client_from_description(
         shmem_provider: SP,
@@ -2553,6 +3503,7 @@ where
                 // drop pages to the broker if it already read them
                 keep_pages_forever: false,
                 has_unsent_message: false,
+                unused_shmem_cache: vec![],
                 shmem_provider: shmem_provider.clone(),
             },
 
@@ -2562,6 +3513,7 @@ where
                 last_msg_recvd: ptr::null_mut(),
                 shmem_provider,
                 highest_msg_id: 0,
+                tag_filter: None,
             },
         })
     }
@@ -2583,6 +3535,12 @@ where
         self.sender.send_buf_with_flags(tag, flags, buf)
     }
 
+    /// Tells the broker that this client is exiting, so it stops tracking it.
+    /// Nothing may be sent afterwards.
+    pub fn send_exiting(&mut self) -> Result<(), Error> {
+        self.sender.send_buf(LLMP_TAG_EXITING, &[])
+    }
+
     /// Informs the broker about a new client in town, with the given map id
     pub fn send_client_added_msg(
         &mut self,
@@ -2649,6 +3607,17 @@ where
         self.receiver.recv_buf_with_flags()
     }
 
+    /// Only receive messages from the broker with one of the given `tags` from now on,
+    /// see [`LlmpReceiver::subscribe`]
+    pub fn subscribe(&mut self, tags: &[Tag]) {
+        self.receiver.subscribe(tags);
+    }
+
+    /// Receive messages of all tags from the broker again
+    pub fn clear_subscriptions(&mut self) {
+        self.receiver.clear_subscriptions();
+    }
+
     #[cfg(feature = "std")]
     /// Creates a new [`LlmpClient`], reading the map id and len from env
     pub fn create_using_env(mut shmem_provider: SP, env_var: &str) -> Result<Self, Error> {
@@ -2659,8 +3628,8 @@ where
 
     #[cfg(feature = "std")]
     /// Create a [`LlmpClient`], getting the ID from a given port
-    pub fn create_attach_to_tcp(mut shmem_provider: SP, port: u16) -> Result<Self, Error> {
-        let mut stream = match TcpStream::connect((_LLMP_CONNECT_ADDR, port)) {
+    pub fn create_attach_to_tcp(shmem_provider: SP, port: u16) -> Result<Self, Error> {
+        let stream = match TcpStream::connect((_LLMP_CONNECT_ADDR, port)) {
             Ok(stream) => stream,
             Err(e) => {
                 match e.kind() {
@@ -2681,6 +3650,31 @@ where
         };
         println!("Connected to port {port}");
 
+        Self::attach_on_stream(shmem_provider, stream)
+    }
+
+    /// Create a client that connects to the broker listening on a unix domain socket at `path`,
+    /// see [`LlmpBroker::launch_unix_listener_on`].
+    /// Like for `tcp`, the client and the broker exchange their messages via shared memory,
+    /// so this only works if both can map the same [`ShMem`], e.g., from different containers on the same host.
+    /// There is no `vsock` counterpart: shared memory does not cross the VM boundary, connect brokers instead.
+    #[cfg(all(unix, feature = "std"))]
+    pub fn create_attach_to_unix<P>(shmem_provider: SP, path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let stream = UnixStream::connect(&path)?;
+        println!("Connected to {}", path.as_ref().display());
+
+        Self::attach_on_stream(shmem_provider, stream)
+    }
+
+    /// Greets the broker on a freshly connected `stream` as a new local client, and maps its page.
+    #[cfg(feature = "std")]
+    fn attach_on_stream<S>(mut shmem_provider: SP, mut stream: S) -> Result<Self, Error>
+    where
+        S: LlmpStream,
+    {
         let broker_shmem_description = if let TcpResponse::BrokerConnectHello {
             broker_shmem_description,
             hostname: _,
@@ -2737,12 +3731,13 @@ mod tests {
     use serial_test::serial;
 
     use super::{
-        LlmpClient,
+        recv_tcp_msg, send_tcp_msg, ClientId, LlmpBroker, LlmpClient,
         LlmpConnection::{self, IsBroker, IsClient},
         LlmpMsgHookResult::ForwardToClients,
-        Tag,
+        LlmpReceiver, LlmpSender, LlmpSharedMap, LlmpStream, Tag, TcpRequest, TcpResponse,
+        LLMP_FLAG_FROM_B2B, LLMP_TAG_EXITING,
     };
-    use crate::bolts::shmem::{ShMemProvider, StdShMemProvider};
+    use crate::bolts::shmem::{ShMem, ShMemProvider, StdShMemProvider};
 
     #[test]
     #[serial]
@@ -2795,4 +3790,367 @@ mod tests {
         // We want at least the tcp and sender clients.
         assert_eq!(broker.llmp_clients.len(), 2);
     }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_client_exiting() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut broker = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        let mut sender = LlmpSender::new(shmem_provider.clone(), 0, false).unwrap();
+        broker.register_client(LlmpSharedMap::existing(
+            shmem_provider
+                .shmem_from_description(sender.out_shmems[0].shmem.description())
+                .unwrap(),
+        ));
+
+        sender.send_buf(0x1337, &[1]).unwrap();
+        sender.send_buf(LLMP_TAG_EXITING, &[]).unwrap();
+
+        let mut tags = vec![];
+        for _ in 0..2 {
+            broker
+                .once(&mut |_sender_id, tag, _flags, _msg| {
+                    tags.push(tag);
+                    Ok(ForwardToClients)
+                })
+                .unwrap();
+        }
+        // The hook hears of the exit once, and the broker keeps going
+        assert_eq!(tags, [0x1337, LLMP_TAG_EXITING]);
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_page_reuse() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut sender = LlmpSender::new(shmem_provider.clone(), 0, false).unwrap();
+        let first_page_id = sender.out_shmems[0].shmem.id();
+        let mut receiver = LlmpReceiver::on_existing_shmem(
+            shmem_provider.clone(),
+            shmem_provider
+                .shmem_from_description(sender.out_shmems[0].shmem.description())
+                .unwrap(),
+            None,
+        )
+        .unwrap();
+
+        let tag: Tag = 0x1337;
+        sender.send_buf(tag, &[1]).unwrap();
+        unsafe { sender.handle_out_eop().unwrap() };
+        let second_page_id = sender.out_shmems.last().unwrap().shmem.id();
+        sender.send_buf(tag, &[2]).unwrap();
+        // The receiver follows the EOP to the second page, so it is done with the first one
+        assert_eq!(receiver.recv_buf().unwrap().unwrap().2, &[1]);
+        assert_eq!(receiver.recv_buf().unwrap().unwrap().2, &[2]);
+
+        unsafe { sender.handle_out_eop().unwrap() };
+        sender.send_buf(tag, &[3]).unwrap();
+
+        // The first page was read completely, the sender continues on it instead of a new one
+        unsafe { sender.handle_out_eop().unwrap() };
+        assert_eq!(sender.out_shmems.last().unwrap().shmem.id(), first_page_id);
+        sender.send_buf(tag, &[4]).unwrap();
+
+        // The receiver did not reach the EOP of the second page yet, so it may not be re-used
+        unsafe { sender.handle_out_eop().unwrap() };
+        assert_ne!(sender.out_shmems.last().unwrap().shmem.id(), second_page_id);
+        sender.send_buf(tag, &[5]).unwrap();
+
+        for expected in 3..=5 {
+            assert_eq!(receiver.recv_buf().unwrap().unwrap().2, &[expected]);
+        }
+        assert!(receiver.recv_buf().unwrap().is_none());
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_broker_page_pruning() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut broker = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        let mut join = |broker: &LlmpBroker<StdShMemProvider>| {
+            let description =
+                LlmpBroker::join_out_page(&broker.out_page, &mut shmem_provider.clone()).unwrap();
+            LlmpReceiver::on_existing_shmem(
+                shmem_provider.clone(),
+                shmem_provider.shmem_from_description(description).unwrap(),
+                None,
+            )
+            .unwrap()
+        };
+        let mut fast = join(&broker);
+        let mut slow = join(&broker);
+
+        let tag: Tag = 0x1337;
+        for i in 0..3 {
+            broker.send_buf(tag, &[i]).unwrap();
+            unsafe { broker.llmp_out.handle_out_eop().unwrap() };
+        }
+        broker.send_buf(tag, &[3]).unwrap();
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert_eq!(broker.llmp_out.out_shmems.len(), 4);
+
+        // The slow client is still on the first page, so none of the pages may go
+        while fast.recv_buf().unwrap().is_some() {}
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert_eq!(broker.llmp_out.out_shmems.len(), 4);
+
+        assert_eq!(slow.recv_buf().unwrap().unwrap().2, &[0]);
+        assert_eq!(slow.recv_buf().unwrap().unwrap().2, &[1]);
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert_eq!(broker.llmp_out.out_shmems.len(), 3);
+
+        // A new client starts on the current page, and only reads from there on
+        let current_page_id = broker.llmp_out.out_shmems.last().unwrap().shmem.id();
+        let mut late = join(&broker);
+        assert_eq!(late.current_recv_shmem.shmem.id(), current_page_id);
+        assert_eq!(late.recv_buf().unwrap().unwrap().2, &[3]);
+
+        while slow.recv_buf().unwrap().is_some() {}
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert_eq!(broker.llmp_out.out_shmems.len(), 1);
+        assert_eq!(broker.llmp_out.out_shmems[0].shmem.id(), current_page_id);
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_broker_pinned_pages() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut broker = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        let description =
+            LlmpBroker::join_out_page(&broker.out_page, &mut shmem_provider.clone()).unwrap();
+        let mut client = LlmpClient::new(
+            shmem_provider.clone(),
+            LlmpSharedMap::existing(shmem_provider.shmem_from_description(description).unwrap()),
+            1,
+        )
+        .unwrap();
+        let client_page = client.sender.out_shmems[0].shmem.description();
+        broker.register_client(LlmpSharedMap::existing(
+            shmem_provider.shmem_from_description(client_page).unwrap(),
+        ));
+
+        let tag: Tag = 0x1337;
+        for i in 0..2 {
+            broker.send_buf(tag, &[i]).unwrap();
+            unsafe { broker.llmp_out.handle_out_eop().unwrap() };
+        }
+        broker.send_buf(tag, &[2]).unwrap();
+
+        // The client may get restored on the first page, even after it read all pages
+        let client_description = client.describe().unwrap();
+        while client.recv_buf().unwrap().is_some() {}
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert_eq!(broker.llmp_out.out_shmems.len(), 3);
+        let mut restored = LlmpReceiver::on_existing_from_description(
+            shmem_provider.clone(),
+            &client_description.receiver,
+        )
+        .unwrap();
+        assert_eq!(restored.recv_buf().unwrap().unwrap().2, &[0]);
+
+        // Describing the client again moves its pin to the current page
+        client.describe().unwrap();
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert_eq!(broker.llmp_out.out_shmems.len(), 1);
+        assert_eq!(broker.pinned_out_pages.len(), 1);
+
+        // An exiting client releases its pin
+        client.send_exiting().unwrap();
+        broker.once(&mut |_, _, _, _| Ok(ForwardToClients)).unwrap();
+        assert!(broker.pinned_out_pages.is_empty());
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_subscribe() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut sender = LlmpSender::new(shmem_provider.clone(), 0, false).unwrap();
+        let mut receiver = LlmpReceiver::on_existing_shmem(
+            shmem_provider.clone(),
+            shmem_provider
+                .shmem_from_description(sender.out_shmems[0].shmem.description())
+                .unwrap(),
+            None,
+        )
+        .unwrap();
+        receiver.subscribe(&[0x1, 0x3]);
+        assert!(receiver.is_subscribed(0x3));
+        assert!(!receiver.is_subscribed(0x2));
+
+        for tag in 1..=4 {
+            sender.send_buf(tag, &[tag as u8]).unwrap();
+        }
+        assert_eq!(receiver.recv_buf().unwrap(), Some((0, 0x1, &[1_u8][..])));
+        assert_eq!(receiver.recv_buf_blocking().unwrap(), (0, 0x3, &[3_u8][..]));
+        assert!(receiver.recv_buf().unwrap().is_none());
+
+        receiver.clear_subscriptions();
+        sender.send_buf(0x2, &[2]).unwrap();
+        assert_eq!(receiver.recv_buf().unwrap(), Some((0, 0x2, &[2_u8][..])));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    #[serial]
+    pub fn test_llmp_b2b_unix() {
+        let tmp = std::env::temp_dir();
+        let path = tmp.join(format!("llmp_b2b_test_{}", std::process::id()));
+        let guest_path = tmp.join(format!("llmp_b2b_test_guest_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&guest_path);
+
+        let shmem_provider = StdShMemProvider::new().unwrap();
+        let mut host = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        host.launch_unix_listener_on(&path).unwrap();
+
+        // The guest broker listens for its own clients first, like a normal broker.
+        let mut guest = LlmpBroker::new(shmem_provider).unwrap();
+        guest.launch_unix_listener_on(&guest_path).unwrap();
+        guest.connect_b2b_unix(&path).unwrap();
+
+        let tag: Tag = 0x1337;
+        guest.send_buf(tag, &[42]).unwrap();
+
+        // Wait for the proxy threads to pick up the message
+        let mut received = None;
+        for _ in 0..100 {
+            host.once(&mut |_sender_id, tag, _flags, msg| {
+                received = Some((tag, msg.to_vec()));
+                Ok(ForwardToClients)
+            })
+            .unwrap();
+            if received.is_some() {
+                break;
+            }
+            sleep(Duration::from_millis(50));
+        }
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(&guest_path);
+
+        assert_eq!(received, Some((tag, vec![42])));
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_b2b_relay() {
+        let shmem_provider = StdShMemProvider::new().unwrap();
+        let mut relay = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        relay.set_b2b_relay(true);
+        relay.launch_tcp_listener_on(1339).unwrap();
+
+        // Two brokers, that only know the relay
+        let mut first = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        first.connect_b2b(("127.0.0.1", 1339)).unwrap();
+        let mut second = LlmpBroker::new(shmem_provider).unwrap();
+        second.connect_b2b(("127.0.0.1", 1339)).unwrap();
+
+        let tag: Tag = 0x1337;
+        first.send_buf(tag, &[42]).unwrap();
+
+        // Wait for the proxy threads to pass the message through the relay
+        let mut received = None;
+        for _ in 0..200 {
+            first
+                .once(&mut |_sender_id, tag, _flags, _msg| {
+                    panic!("The relay sent message {tag:x} back to its origin")
+                })
+                .unwrap();
+            relay
+                .once(&mut |_sender_id, _tag, _flags, _msg| Ok(ForwardToClients))
+                .unwrap();
+            second
+                .once(&mut |_sender_id, tag, flags, msg| {
+                    received = Some((tag, flags & LLMP_FLAG_FROM_B2B, msg.to_vec()));
+                    Ok(ForwardToClients)
+                })
+                .unwrap();
+            if received.is_some() {
+                break;
+            }
+            sleep(Duration::from_millis(50));
+        }
+
+        assert_eq!(received, Some((tag, LLMP_FLAG_FROM_B2B, vec![42])));
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_unix_client() {
+        let path = std::env::temp_dir().join(format!("llmp_unix_test_{}", std::process::id()));
+        // A socket left behind by a crashed broker
+        drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+        let shmem_provider = StdShMemProvider::new().unwrap();
+        let mut broker = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        broker.launch_unix_listener_on(&path).unwrap();
+        let mut client = LlmpClient::create_attach_to_unix(shmem_provider, &path).unwrap();
+        let _ = std::fs::remove_file(&path);
+
+        let tag: Tag = 0x1337;
+        client.send_buf(tag, &[42]).unwrap();
+
+        // The hook gets the id the listener handed out to the client
+        let mut received = None;
+        for _ in 0..100 {
+            broker
+                .once(&mut |sender_id, tag, _flags, msg| {
+                    received = Some((sender_id, tag, msg.to_vec()));
+                    Ok(ForwardToClients)
+                })
+                .unwrap();
+            if received.is_some() {
+                break;
+            }
+            sleep(Duration::from_millis(50));
+        }
+        assert_eq!(received, Some((client.sender.id, tag, vec![42])));
+    }
+
+    /// Says hello to a broker listener as a new local client, and returns the id the broker hands out
+    fn local_client_hello<S>(mut stream: S, shmem_provider: &mut StdShMemProvider) -> ClientId
+    where
+        S: LlmpStream,
+    {
+        let hello: TcpResponse = recv_tcp_msg(&mut stream).unwrap().try_into().unwrap();
+        assert!(matches!(hello, TcpResponse::BrokerConnectHello { .. }));
+
+        let sender = LlmpSender::new(shmem_provider.clone(), 0, false).unwrap();
+        let client_hello = TcpRequest::LocalClientHello {
+            shmem_description: sender.out_shmems[0].shmem.description(),
+        };
+        send_tcp_msg(&mut stream, &client_hello).unwrap();
+
+        match recv_tcp_msg(&mut stream).unwrap().try_into().unwrap() {
+            TcpResponse::LocalClientAccepted { client_id } => client_id,
+            _ => panic!("Unexpected response from the broker"),
+        }
+    }
+
+    #[test]
+    #[serial]
+    pub fn test_llmp_listeners_client_ids() {
+        let path = std::env::temp_dir().join(format!("llmp_ids_test_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut broker = LlmpBroker::new(shmem_provider.clone()).unwrap();
+        broker.launch_tcp_listener_on(1338).unwrap();
+        broker.launch_unix_listener_on(&path).unwrap();
+
+        let mut ids = vec![];
+        for _ in 0..2 {
+            let tcp = std::net::TcpStream::connect(("127.0.0.1", 1338)).unwrap();
+            ids.push(local_client_hello(tcp, &mut shmem_provider));
+            let unix = std::os::unix::net::UnixStream::connect(&path).unwrap();
+            ids.push(local_client_hello(unix, &mut shmem_provider));
+        }
+        let _ = std::fs::remove_file(&path);
+
+        // The ids of the listeners themselves are taken, too
+        let mut unique = ids.clone();
+        unique.sort_unstable();
+        unique.dedup();
+        assert_eq!(unique.len(), ids.len(), "Overlapping client ids: {ids:?}");
+        assert!(ids.iter().all(|id| *id >= 2));
+    }
 }
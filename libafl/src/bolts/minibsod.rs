@@ -1,14 +1,29 @@
 //! Implements a mini-bsod generator.
-//! It dumps all important registers and prints a stacktrace.
+//! It dumps all important registers, the top of the stack, and prints a stacktrace.
 //! You may use the [`crate::bolts::os::unix_signals::ucontext`]
 //! function to get a [`ucontext_t`].
 
-use std::io::{BufWriter, Write};
+use alloc::{string::String, vec::Vec};
+use std::io::{stderr, BufWriter, Write};
 
 use libc::siginfo_t;
+use serde::{Deserialize, Serialize};
 
 use crate::bolts::os::unix_signals::{ucontext_t, Signal};
 
+/// The number of bytes above the stack pointer that [`generate_minibsod`] dumps
+const STACK_DUMP_SIZE: usize = 256;
+
+/// The mini-BSOD of a crash, added to the testcase of the crashing input by the in-process executors,
+/// so that crashes can be triaged without running them again in a debugger
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct MiniBsodMetadata {
+    /// The mini-BSOD, as written to `stderr`
+    pub minibsod: String,
+}
+
+crate::impl_serdeany!(MiniBsodMetadata);
+
 /// Write the content of all important registers
 #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
 #[allow(clippy::similar_names)]
@@ -499,7 +514,145 @@ fn write_crash<W: Write>(
     Ok(())
 }
 
+/// The stack pointer at the time of the crash
+#[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+#[allow(clippy::unnecessary_wraps, clippy::cast_sign_loss)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(ucontext.uc_mcontext.gregs[libc::REG_RSP as usize] as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(
+    any(target_os = "linux", target_os = "android"),
+    target_arch = "aarch64"
+))]
+#[allow(clippy::unnecessary_wraps)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(ucontext.uc_mcontext.sp as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(target_os = "linux", target_arch = "arm"))]
+#[allow(clippy::unnecessary_wraps)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(ucontext.uc_mcontext.arm_sp as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(target_vendor = "apple", target_arch = "aarch64"))]
+#[allow(clippy::unnecessary_wraps)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(unsafe { (*ucontext.uc_mcontext).__ss.__sp } as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(target_vendor = "apple", target_arch = "x86_64"))]
+#[allow(clippy::unnecessary_wraps)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(unsafe { (*ucontext.uc_mcontext).__ss.__rsp } as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(target_os = "freebsd", target_arch = "x86_64"))]
+#[allow(clippy::unnecessary_wraps, clippy::cast_sign_loss)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(ucontext.uc_mcontext.mc_rsp as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(target_os = "openbsd", target_arch = "x86_64"))]
+#[allow(clippy::unnecessary_wraps, clippy::cast_sign_loss)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(ucontext.sc_rsp as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(all(target_os = "netbsd", target_arch = "x86_64"))]
+#[allow(clippy::unnecessary_wraps)]
+fn stack_pointer(ucontext: &ucontext_t) -> Option<usize> {
+    Some(ucontext.uc_mcontext.__gregs[libc::_REG_RSP as usize] as usize)
+}
+
+/// The stack pointer at the time of the crash
+#[cfg(not(any(
+    all(target_os = "linux", target_arch = "x86_64"),
+    all(
+        any(target_os = "linux", target_os = "android"),
+        target_arch = "aarch64"
+    ),
+    all(target_os = "linux", target_arch = "arm"),
+    all(
+        target_vendor = "apple",
+        any(target_arch = "aarch64", target_arch = "x86_64")
+    ),
+    all(
+        any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"),
+        target_arch = "x86_64"
+    ),
+)))]
+fn stack_pointer(_ucontext: &ucontext_t) -> Option<usize> {
+    None
+}
+
+/// Writes a hexdump of `bytes`, which were read at `addr`, 16 bytes per line
+pub fn write_hexdump<W: Write>(
+    writer: &mut BufWriter<W>,
+    addr: usize,
+    bytes: &[u8],
+) -> Result<(), std::io::Error> {
+    for (i, line) in bytes.chunks(16).enumerate() {
+        write!(writer, "{:#018x}:", addr + i * 16)?;
+        for byte in line {
+            write!(writer, " {byte:02x}")?;
+        }
+        for _ in line.len()..16 {
+            write!(writer, "   ")?;
+        }
+        write!(writer, "  |")?;
+        for byte in line {
+            let c = if byte.is_ascii_graphic() || *byte == b' ' {
+                *byte as char
+            } else {
+                '.'
+            };
+            write!(writer, "{c}")?;
+        }
+        writeln!(writer, "|")?;
+    }
+    Ok(())
+}
+
+/// Copies the `STACK_DUMP_SIZE` bytes at `sp`, if they are readable.
+/// At a stack overflow, or with a corrupted stack pointer, they are not, and reading them directly
+/// would fault again inside the crash handler. Writing them to a pipe fails with `EFAULT` instead.
+#[cfg(unix)]
+#[allow(clippy::cast_possible_wrap)]
+fn read_stack(sp: usize) -> Option<[u8; STACK_DUMP_SIZE]> {
+    let mut stack = [0_u8; STACK_DUMP_SIZE];
+    let mut fds = [0; 2];
+    unsafe {
+        if libc::pipe(fds.as_mut_ptr()) != 0 {
+            return None;
+        }
+        // Less than `PIPE_BUF`, so the pipe takes all of the bytes at once
+        let written = libc::write(fds[1], sp as *const libc::c_void, STACK_DUMP_SIZE);
+        let read = if written == STACK_DUMP_SIZE as isize {
+            libc::read(
+                fds[0],
+                stack.as_mut_ptr() as *mut libc::c_void,
+                STACK_DUMP_SIZE,
+            )
+        } else {
+            -1
+        };
+        libc::close(fds[0]);
+        libc::close(fds[1]);
+        (read == STACK_DUMP_SIZE as isize).then_some(stack)
+    }
+}
+
 /// Generates a mini-BSOD given a signal and context.
+/// The writer is flushed after each section, so a failure halfway through still leaves the sections before.
 #[cfg(unix)]
 #[allow(clippy::non_ascii_literal)]
 pub fn generate_minibsod<W: Write>(
@@ -510,10 +663,20 @@ pub fn generate_minibsod<W: Write>(
 ) -> Result<(), std::io::Error> {
     writeln!(writer, "{:━^100}", " CRASH ")?;
     write_crash(writer, signal, ucontext)?;
+    writer.flush()?;
     writeln!(writer, "{:━^100}", " REGISTERS ")?;
     dump_registers(writer, ucontext)?;
+    writer.flush()?;
+    if let Some((sp, stack)) =
+        stack_pointer(ucontext).and_then(|sp| read_stack(sp).map(|stack| (sp, stack)))
+    {
+        writeln!(writer, "{:━^100}", " STACK ")?;
+        write_hexdump(writer, sp, &stack)?;
+        writer.flush()?;
+    }
     writeln!(writer, "{:━^100}", " BACKTRACE ")?;
     writeln!(writer, "{:?}", backtrace::Backtrace::new())?;
+    writer.flush()?;
     #[cfg(any(target_os = "linux", target_os = "android"))]
     {
         writeln!(writer, "{:━^100}", " MAPS ")?;
@@ -524,15 +687,60 @@ pub fn generate_minibsod<W: Write>(
         };
     }
 
-    Ok(())
+    writer.flush()
+}
+
+/// Writes to `stderr`, and keeps a copy of everything written
+#[derive(Debug, Default)]
+struct StderrCopy {
+    copy: Vec<u8>,
+}
+
+impl Write for StderrCopy {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, std::io::Error> {
+        // The copy is what ends up in the testcase, so it does not depend on `stderr` being open
+        let _ = stderr().write_all(buf);
+        self.copy.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), std::io::Error> {
+        let _ = stderr().flush();
+        Ok(())
+    }
+}
+
+/// Generates a mini-BSOD given a signal and context, writing each section to `stderr` as soon as it is done.
+/// Returns a copy of the mini-BSOD, e.g., for the [`MiniBsodMetadata`] of the crashing testcase.
+#[cfg(unix)]
+#[must_use]
+pub fn generate_minibsod_to_stderr(
+    signal: Signal,
+    siginfo: siginfo_t,
+    ucontext: &ucontext_t,
+) -> String {
+    let mut writer = BufWriter::new(StderrCopy::default());
+    if let Err(err) = generate_minibsod(&mut writer, signal, siginfo, ucontext) {
+        let _ = writeln!(writer, "Failed to generate the mini-BSOD: {err:?}");
+    }
+    // Writing to a `StderrCopy` never fails
+    let copy = writer
+        .into_inner()
+        .map(|copy| copy.copy)
+        .unwrap_or_default();
+    String::from_utf8_lossy(&copy).into_owned()
 }
 
 #[cfg(test)]
 mod tests {
 
+    use alloc::string::String;
     use std::io::{stdout, BufWriter};
 
-    use crate::bolts::{minibsod::dump_registers, os::unix_signals::ucontext};
+    use crate::bolts::{
+        minibsod::{dump_registers, generate_minibsod, write_hexdump},
+        os::unix_signals::{ucontext, Signal},
+    };
 
     #[test]
     pub fn test_dump_registers() {
@@ -540,4 +748,57 @@ mod tests {
         let mut writer = BufWriter::new(stdout());
         dump_registers(&mut writer, &ucontext).unwrap();
     }
+
+    #[test]
+    pub fn test_write_hexdump() {
+        let mut writer = BufWriter::new(vec![]);
+        write_hexdump(&mut writer, 0x1000, b"LibAFL\0\x01").unwrap();
+        assert_eq!(
+            writer.into_inner().unwrap(),
+            format!(
+                "0x0000000000001000: 4c 69 62 41 46 4c 00 01{}  |LibAFL..|\n",
+                " ".repeat(24)
+            )
+            .as_bytes()
+        );
+    }
+
+    #[test]
+    pub fn test_generate_minibsod() {
+        let ucontext = ucontext().unwrap();
+        let mut writer = BufWriter::new(vec![]);
+        generate_minibsod(
+            &mut writer,
+            Signal::SigSegmentationFault,
+            unsafe { core::mem::zeroed() },
+            &ucontext,
+        )
+        .unwrap();
+        let minibsod = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(minibsod.contains(" CRASH "));
+        assert!(minibsod.contains(" REGISTERS "));
+        assert!(minibsod.contains(" BACKTRACE "));
+        #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+        assert!(minibsod.contains(" STACK "));
+    }
+
+    /// A stack pointer in unmapped memory, as at a stack overflow, skips the stack instead of faulting again
+    #[test]
+    #[cfg(all(target_os = "linux", target_arch = "x86_64"))]
+    pub fn test_generate_minibsod_unmapped_stack() {
+        let mut ucontext = ucontext().unwrap();
+        ucontext.uc_mcontext.gregs[libc::REG_RSP as usize] = 0x10;
+        let mut writer = BufWriter::new(vec![]);
+        generate_minibsod(
+            &mut writer,
+            Signal::SigSegmentationFault,
+            unsafe { core::mem::zeroed() },
+            &ucontext,
+        )
+        .unwrap();
+        let minibsod = String::from_utf8(writer.into_inner().unwrap()).unwrap();
+        assert!(minibsod.contains(" REGISTERS "));
+        assert!(!minibsod.contains(" STACK "));
+        assert!(minibsod.contains(" BACKTRACE "));
+    }
 }
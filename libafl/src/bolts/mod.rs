@@ -15,22 +15,29 @@ pub mod core_affinity;
 pub mod cpu;
 #[cfg(feature = "std")]
 pub mod fs;
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", feature = "llmp"))]
 pub mod launcher;
+#[cfg(feature = "llmp")]
 pub mod llmp;
-#[cfg(all(feature = "std", unix))]
+#[cfg(all(feature = "std", feature = "executors", unix))]
 pub mod minibsod;
 pub mod os;
 pub mod ownedref;
 pub mod rands;
 pub mod serdeany;
+pub mod serialization;
 pub mod shmem;
 #[cfg(feature = "std")]
 pub mod staterestore;
 pub mod tuples;
 
 use alloc::string::String;
-use core::{iter::Iterator, time};
+use core::{
+    iter::Iterator,
+    ptr,
+    sync::atomic::{AtomicPtr, Ordering},
+    time,
+};
 #[cfg(feature = "std")]
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -87,31 +94,64 @@ pub trait HasRefCnt {
     fn refcnt_mut(&mut self) -> &mut isize;
 }
 
-/// Current time
+/// The time source set with [`set_current_time_fn`], or null for the default one
+static CURRENT_TIME_FN: AtomicPtr<()> = AtomicPtr::new(ptr::null_mut());
+
+/// Sets the source of [`current_time`], such as the virtual clock of an emulator, or a timer of the board on baremetal.
+/// The function returns the time since an arbitrary, but fixed, point in the past.
+/// `None` restores the default source: the system time with `std`, and `external_current_millis()` for `no_std`.
+pub fn set_current_time_fn(time_fn: Option<fn() -> time::Duration>) {
+    CURRENT_TIME_FN.store(
+        time_fn.map_or(ptr::null_mut(), |time_fn| time_fn as *mut ()),
+        Ordering::Release,
+    );
+}
+
+/// The time source set with [`set_current_time_fn`], if any
+#[inline]
+fn current_time_fn() -> Option<fn() -> time::Duration> {
+    let time_fn = CURRENT_TIME_FN.load(Ordering::Acquire);
+    if time_fn.is_null() {
+        None
+    } else {
+        // Safety: only ever stored from a `fn() -> Duration` in `set_current_time_fn`
+        Some(unsafe { core::mem::transmute::<*mut (), fn() -> time::Duration>(time_fn) })
+    }
+}
+
+/// Current time, from the source set with [`set_current_time_fn`], or the system time
 #[cfg(feature = "std")]
 #[must_use]
 #[inline]
 pub fn current_time() -> time::Duration {
-    SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+    match current_time_fn() {
+        Some(time_fn) => time_fn(),
+        None => SystemTime::now().duration_since(UNIX_EPOCH).unwrap(),
+    }
 }
 
 // external defined function in case of `no_std`
 //
 // Define your own `external_current_millis()` function via `extern "C"`
-// which is linked into the binary and called from here.
+// which is linked into the binary and called from here,
+// unless a time source is set with `set_current_time_fn`.
 #[cfg(not(feature = "std"))]
 extern "C" {
     //#[no_mangle]
     fn external_current_millis() -> u64;
 }
 
-/// Current time (fixed fallback for `no_std`)
+/// Current time, from the source set with [`set_current_time_fn`], or `external_current_millis()` for `no_std`
 #[cfg(not(feature = "std"))]
 #[inline]
 #[must_use]
 pub fn current_time() -> time::Duration {
-    let millis = unsafe { external_current_millis() };
-    time::Duration::from_millis(millis)
+    if let Some(time_fn) = current_time_fn() {
+        time_fn()
+    } else {
+        let millis = unsafe { external_current_millis() };
+        time::Duration::from_millis(millis)
+    }
 }
 
 /// Given a u64 number, return a hashed number using this mixing function
@@ -168,13 +208,49 @@ pub mod bolts_prelude {
     pub use super::core_affinity::*;
     #[cfg(feature = "std")]
     pub use super::fs::*;
-    #[cfg(feature = "std")]
+    #[cfg(all(feature = "std", feature = "llmp"))]
     pub use super::launcher::*;
-    #[cfg(all(feature = "std", unix))]
+    #[cfg(feature = "llmp")]
+    pub use super::llmp::*;
+    #[cfg(all(feature = "std", feature = "executors", unix))]
     pub use super::minibsod::*;
     #[cfg(feature = "std")]
     pub use super::staterestore::*;
     pub use super::{
-        anymap::*, cpu::*, llmp::*, os::*, ownedref::*, rands::*, serdeany::*, shmem::*, tuples::*,
+        anymap::*, cpu::*, os::*, ownedref::*, rands::*, serdeany::*, shmem::*, tuples::*,
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use core::sync::atomic::{AtomicUsize, Ordering};
+
+    use serial_test::serial;
+
+    use crate::bolts::{current_time, set_current_time_fn};
+
+    static TIME_FN_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// The system time, counting the calls; other tests may read the time meanwhile
+    fn counting_time() -> core::time::Duration {
+        TIME_FN_CALLS.fetch_add(1, Ordering::Relaxed);
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+    }
+
+    #[test]
+    #[serial]
+    fn test_set_current_time_fn() {
+        let before = current_time();
+        set_current_time_fn(Some(counting_time));
+        let calls = TIME_FN_CALLS.load(Ordering::Relaxed);
+        assert!(current_time() >= before);
+        assert!(TIME_FN_CALLS.load(Ordering::Relaxed) > calls);
+
+        set_current_time_fn(None);
+        let calls = TIME_FN_CALLS.load(Ordering::Relaxed);
+        assert!(current_time() >= before);
+        assert_eq!(TIME_FN_CALLS.load(Ordering::Relaxed), calls);
+    }
+}
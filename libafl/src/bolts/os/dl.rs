@@ -0,0 +1,123 @@
+//! Dynamic loading of shared objects and their symbols, using `dlopen` and `dlsym`.
+//!
+//! This allows a single, generic fuzzer binary to fuzz harnesses it was not linked against.
+//! For the instrumentation of a loaded shared object to find the coverage maps,
+//! the fuzzer binary needs to export them, for example by linking with `-rdynamic`.
+
+use alloc::string::{String, ToString};
+use core::ffi::c_void;
+use std::{
+    ffi::{CStr, CString},
+    os::unix::ffi::OsStrExt,
+    path::Path,
+};
+
+use libc::{c_char, RTLD_GLOBAL, RTLD_NOW};
+
+use crate::Error;
+
+/// A shared object loaded with `dlopen`, or the running program itself.
+/// The shared object stays loaded as long as this lives.
+#[derive(Debug)]
+pub struct SharedLibrary {
+    handle: *mut c_void,
+    name: String,
+}
+
+impl SharedLibrary {
+    /// Loads the shared object at `path`.
+    /// Its symbols are made available globally, so that other loaded objects can use them.
+    pub fn open<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let path = path.as_ref();
+        let c_path = CString::new(path.as_os_str().as_bytes())
+            .map_err(|_| Error::illegal_argument(format!("Invalid path {}", path.display())))?;
+        let handle = unsafe { libc::dlopen(c_path.as_ptr(), RTLD_NOW | RTLD_GLOBAL) };
+        if handle.is_null() {
+            return Err(Error::illegal_argument(format!(
+                "Could not load {}: {}",
+                path.display(),
+                last_dl_error()
+            )));
+        }
+        Ok(Self {
+            handle,
+            name: path.display().to_string(),
+        })
+    }
+
+    /// The running program, including all shared objects it was linked against.
+    pub fn this() -> Result<Self, Error> {
+        let handle = unsafe { libc::dlopen(core::ptr::null(), RTLD_NOW) };
+        if handle.is_null() {
+            return Err(Error::unknown(format!(
+                "Could not open the running program: {}",
+                last_dl_error()
+            )));
+        }
+        Ok(Self {
+            handle,
+            name: "<this>".into(),
+        })
+    }
+
+    /// Looks up the address of the symbol `name`.
+    ///
+    /// # Safety
+    /// The returned pointer is only valid as long as this [`SharedLibrary`] lives,
+    /// and has to be cast to the right type by the caller.
+    pub unsafe fn symbol(&self, name: &str) -> Result<*mut c_void, Error> {
+        let c_name = CString::new(name)
+            .map_err(|_| Error::illegal_argument(format!("Invalid symbol name {name}")))?;
+        let sym = libc::dlsym(self.handle, c_name.as_ptr() as *const c_char);
+        if sym.is_null() {
+            Err(Error::key_not_found(format!(
+                "Symbol {name} not found in {}",
+                self.name
+            )))
+        } else {
+            Ok(sym)
+        }
+    }
+
+    /// The name of this [`SharedLibrary`], usually its path
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl Drop for SharedLibrary {
+    fn drop(&mut self) {
+        unsafe {
+            libc::dlclose(self.handle);
+        }
+    }
+}
+
+/// The message of the last `dl*` error
+fn last_dl_error() -> String {
+    unsafe {
+        let err = libc::dlerror();
+        if err.is_null() {
+            "unknown error".into()
+        } else {
+            CStr::from_ptr(err).to_string_lossy().into_owned()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::bolts::os::dl::SharedLibrary;
+
+    #[test]
+    fn test_dlsym_this() {
+        let this = SharedLibrary::this().unwrap();
+        assert!(unsafe { this.symbol("malloc") }.is_ok());
+        assert!(unsafe { this.symbol("__libafl_no_such_symbol") }.is_err());
+        assert!(SharedLibrary::open("/nonexistent/libafl_test.so").is_err());
+    }
+}
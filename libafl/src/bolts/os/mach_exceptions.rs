@@ -0,0 +1,607 @@
+//! Mach exception handling for `MacOS`.
+//! Hardware faults, such as `EXC_BAD_ACCESS`, first raise a Mach exception, and only turn into a POSIX signal
+//! if no Mach exception port handles them. Runtimes handling Mach exceptions themselves, e.g., crash reporters,
+//! can thereby keep faults from reaching the signal handlers.
+//! [`setup_mach_exception_handler`] takes over the exception port of the task, and routes the faults
+//! to the handlers registered with [`crate::bolts::os::unix_signals::setup_signal_handler`], as the equivalent signal.
+//! All other exceptions go on to the exception ports installed before.
+
+use core::{
+    mem::{self, size_of},
+    ptr::addr_of_mut,
+    sync::atomic::{AtomicBool, Ordering},
+};
+use std::thread;
+
+use libc::{c_int, c_void, siginfo_t, SIGBUS, SIGFPE, SIGILL, SIGSEGV, SIGTRAP};
+
+use crate::{
+    bolts::os::unix_signals::{handle_signal, has_signal_handler, ucontext_t},
+    Error,
+};
+
+#[allow(non_camel_case_types)]
+type mach_port_t = u32;
+#[allow(non_camel_case_types)]
+type kern_return_t = c_int;
+
+const KERN_SUCCESS: kern_return_t = 0;
+const KERN_FAILURE: kern_return_t = 5;
+/// The code of an `EXC_BAD_ACCESS` at an unmapped address, as opposed to a protection failure
+const KERN_INVALID_ADDRESS: i64 = 1;
+
+const MACH_PORT_NULL: mach_port_t = 0;
+const MACH_PORT_RIGHT_RECEIVE: u32 = 1;
+const MACH_MSG_TYPE_COPY_SEND: u32 = 19;
+const MACH_MSG_TYPE_MAKE_SEND: u32 = 20;
+const MACH_MSG_TYPE_MAKE_SEND_ONCE: u32 = 21;
+const MACH_MSGH_BITS_COMPLEX: u32 = 0x8000_0000;
+const MACH_MSG_PORT_DESCRIPTOR: u8 = 0;
+const MACH_SEND_MSG: i32 = 1;
+const MACH_RCV_MSG: i32 = 2;
+
+const EXC_BAD_ACCESS: i32 = 1;
+const EXC_BAD_INSTRUCTION: i32 = 2;
+const EXC_ARITHMETIC: i32 = 3;
+const EXC_BREAKPOINT: i32 = 6;
+const EXC_MASK_CRASHES: u32 = (1 << EXC_BAD_ACCESS)
+    | (1 << EXC_BAD_INSTRUCTION)
+    | (1 << EXC_ARITHMETIC)
+    | (1 << EXC_BREAKPOINT);
+
+const EXCEPTION_DEFAULT: i32 = 1;
+/// Report the 64 bit codes, to get the full fault address
+#[allow(clippy::cast_possible_wrap)]
+const MACH_EXCEPTION_CODES: i32 = 0x8000_0000_u32 as i32;
+/// The maximum number of exception ports of a task, `EXC_TYPES_COUNT`, with some headroom
+const MAX_EXCEPTION_PORTS: usize = 32;
+
+#[cfg(target_arch = "aarch64")]
+const THREAD_STATE_NONE: i32 = 5;
+#[cfg(target_arch = "x86_64")]
+const THREAD_STATE_NONE: i32 = 13;
+
+#[cfg(target_arch = "aarch64")]
+const THREAD_STATE_FLAVOR: i32 = 6; // ARM_THREAD_STATE64
+#[cfg(target_arch = "x86_64")]
+const THREAD_STATE_FLAVOR: i32 = 4; // x86_THREAD_STATE64
+
+/// The `mach_msg_header_t`
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct MachMsgHeader {
+    bits: u32,
+    size: u32,
+    remote_port: mach_port_t,
+    local_port: mach_port_t,
+    voucher_port: mach_port_t,
+    id: i32,
+}
+
+/// The `mach_msg_port_descriptor_t`
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C)]
+struct MachMsgPortDescriptor {
+    name: mach_port_t,
+    pad1: u32,
+    pad2: u16,
+    disposition: u8,
+    kind: u8,
+}
+
+/// The `__Request__mach_exception_raise_t` the kernel sends, with room for the trailer
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C, packed(4))]
+struct ExceptionRequest {
+    header: MachMsgHeader,
+    descriptor_count: u32,
+    thread: MachMsgPortDescriptor,
+    task: MachMsgPortDescriptor,
+    ndr: [u8; 8],
+    exception: i32,
+    code_count: u32,
+    code: [i64; 2],
+    trailer: [u64; 16],
+}
+
+/// The `__Reply__mach_exception_raise_t`
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C, packed(4))]
+struct ExceptionReply {
+    header: MachMsgHeader,
+    ndr: [u8; 8],
+    ret_code: kern_return_t,
+}
+
+extern "C" {
+    static mach_task_self_: mach_port_t;
+    static NDR_record: [u8; 8];
+
+    fn mach_port_allocate(task: mach_port_t, right: u32, name: *mut mach_port_t) -> kern_return_t;
+    fn mach_port_insert_right(
+        task: mach_port_t,
+        name: mach_port_t,
+        right: mach_port_t,
+        right_type: u32,
+    ) -> kern_return_t;
+    fn mach_port_deallocate(task: mach_port_t, name: mach_port_t) -> kern_return_t;
+    fn task_get_exception_ports(
+        task: mach_port_t,
+        exception_mask: u32,
+        masks: *mut u32,
+        masks_count: *mut u32,
+        old_handlers: *mut mach_port_t,
+        old_behaviors: *mut i32,
+        old_flavors: *mut i32,
+    ) -> kern_return_t;
+    fn task_set_exception_ports(
+        task: mach_port_t,
+        exception_mask: u32,
+        new_port: mach_port_t,
+        behavior: i32,
+        new_flavor: i32,
+    ) -> kern_return_t;
+    fn thread_get_state(
+        thread: mach_port_t,
+        flavor: i32,
+        state: *mut u32,
+        count: *mut u32,
+    ) -> kern_return_t;
+    fn mach_msg(
+        msg: *mut c_void,
+        option: i32,
+        send_size: u32,
+        rcv_size: u32,
+        rcv_name: mach_port_t,
+        timeout: u32,
+        notify: mach_port_t,
+    ) -> kern_return_t;
+}
+
+/// Set once the exception port of this task is taken over
+static MACH_HANDLER_SET_UP: AtomicBool = AtomicBool::new(false);
+
+/// The exception ports of the task before [`setup_mach_exception_handler`] took them over
+#[derive(Debug, Clone, Copy)]
+struct OldExceptionPorts {
+    count: usize,
+    masks: [u32; MAX_EXCEPTION_PORTS],
+    handlers: [mach_port_t; MAX_EXCEPTION_PORTS],
+    behaviors: [i32; MAX_EXCEPTION_PORTS],
+}
+
+impl OldExceptionPorts {
+    /// Saves the exception ports of `task` for the exceptions in `mask`
+    #[allow(clippy::cast_possible_truncation)]
+    unsafe fn get(task: mach_port_t, mask: u32) -> Result<Self, Error> {
+        let mut old = Self {
+            count: 0,
+            masks: [0; MAX_EXCEPTION_PORTS],
+            handlers: [MACH_PORT_NULL; MAX_EXCEPTION_PORTS],
+            behaviors: [0; MAX_EXCEPTION_PORTS],
+        };
+        let mut flavors = [0; MAX_EXCEPTION_PORTS];
+        let mut count = MAX_EXCEPTION_PORTS as u32;
+        if task_get_exception_ports(
+            task,
+            mask,
+            old.masks.as_mut_ptr(),
+            &mut count,
+            old.handlers.as_mut_ptr(),
+            old.behaviors.as_mut_ptr(),
+            flavors.as_mut_ptr(),
+        ) != KERN_SUCCESS
+        {
+            return Err(Error::unknown("Failed to get the Mach exception ports"));
+        }
+        old.count = count as usize;
+        Ok(old)
+    }
+
+    /// The port and behavior installed before for `exception`, if any
+    fn for_exception(&self, exception: i32) -> Option<(mach_port_t, i32)> {
+        (0..self.count)
+            .find(|&i| self.masks[i] & (1 << exception) != 0 && self.handlers[i] != MACH_PORT_NULL)
+            .map(|i| (self.handlers[i], self.behaviors[i]))
+    }
+}
+
+/// The signal the kernel would turn the Mach exception into, see `ux_exception` in `xnu`
+fn exception_to_signal(exception: i32, code: i64) -> Option<c_int> {
+    match exception {
+        EXC_BAD_ACCESS if code == KERN_INVALID_ADDRESS => Some(SIGSEGV),
+        EXC_BAD_ACCESS => Some(SIGBUS),
+        EXC_BAD_INSTRUCTION => Some(SIGILL),
+        EXC_ARITHMETIC => Some(SIGFPE),
+        EXC_BREAKPOINT => Some(SIGTRAP),
+        _ => None,
+    }
+}
+
+/// Routes the exception of the suspended `thread` to the signal handlers, as `signal`.
+/// The crash handlers of the in-process executors exit the process, so this only returns
+/// if no handler is registered for the signal.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+unsafe fn route_to_signal_handler(thread: mach_port_t, signal: c_int, fault_addr: i64) {
+    let mut info: siginfo_t = mem::zeroed();
+    info.si_signo = signal;
+    info.si_addr = fault_addr as *mut c_void;
+
+    let mut context: ucontext_t = mem::zeroed();
+    #[cfg(target_arch = "aarch64")]
+    let mcontext = addr_of_mut!(context.mcontext_data);
+    #[cfg(target_arch = "x86_64")]
+    let mut mcontext_data: libc::__darwin_mcontext64 = mem::zeroed();
+    #[cfg(target_arch = "x86_64")]
+    let mcontext = addr_of_mut!(mcontext_data);
+    context.uc_mcontext = mcontext;
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        (*mcontext).__es.__far = fault_addr as u64;
+    }
+    #[cfg(target_arch = "x86_64")]
+    {
+        (*mcontext).__es.__faultvaddr = fault_addr as u64;
+    }
+    let mut count = (mem::size_of_val(&(*mcontext).__ss) / size_of::<u32>()) as u32;
+    // On failure, the handlers get zeroed registers, but still the fault
+    thread_get_state(
+        thread,
+        THREAD_STATE_FLAVOR,
+        addr_of_mut!((*mcontext).__ss) as *mut u32,
+        &mut count,
+    );
+
+    handle_signal(signal, info, addr_of_mut!(context) as *mut c_void);
+}
+
+/// Sends the exception in `request` on to the `old` exception port, and returns its reply.
+/// Only ports expecting the same message, i.e., `EXCEPTION_DEFAULT` with 64 bit codes, get the exception,
+/// for all others the kernel goes on with the default handling.
+#[allow(clippy::cast_possible_truncation)]
+unsafe fn forward_exception(
+    request: &ExceptionRequest,
+    old: mach_port_t,
+    behavior: i32,
+) -> kern_return_t {
+    if behavior != EXCEPTION_DEFAULT | MACH_EXCEPTION_CODES {
+        return KERN_FAILURE;
+    }
+
+    let task = mach_task_self_;
+    let mut reply_port: mach_port_t = MACH_PORT_NULL;
+    if mach_port_allocate(task, MACH_PORT_RIGHT_RECEIVE, &mut reply_port) != KERN_SUCCESS {
+        return KERN_FAILURE;
+    }
+
+    let descriptor = |name| MachMsgPortDescriptor {
+        name,
+        disposition: MACH_MSG_TYPE_COPY_SEND as u8,
+        kind: MACH_MSG_PORT_DESCRIPTOR,
+        ..MachMsgPortDescriptor::default()
+    };
+    let mut forward = ExceptionRequest {
+        header: MachMsgHeader {
+            // MACH_MSGH_BITS(MACH_MSG_TYPE_COPY_SEND, MACH_MSG_TYPE_MAKE_SEND_ONCE) | MACH_MSGH_BITS_COMPLEX
+            bits: MACH_MSG_TYPE_COPY_SEND
+                | (MACH_MSG_TYPE_MAKE_SEND_ONCE << 8)
+                | MACH_MSGH_BITS_COMPLEX,
+            size: (size_of::<ExceptionRequest>() - size_of::<[u64; 16]>()) as u32,
+            remote_port: old,
+            local_port: reply_port,
+            voucher_port: MACH_PORT_NULL,
+            id: request.header.id,
+        },
+        descriptor_count: 2,
+        thread: descriptor(request.thread.name),
+        task: descriptor(request.task.name),
+        ndr: NDR_record,
+        exception: request.exception,
+        code_count: 2,
+        code: request.code,
+        trailer: [0; 16],
+    };
+    // The reply fits, it is smaller than the request
+    let ret = mach_msg(
+        addr_of_mut!(forward) as *mut c_void,
+        MACH_SEND_MSG | MACH_RCV_MSG,
+        forward.header.size,
+        size_of::<ExceptionRequest>() as u32,
+        reply_port,
+        0,
+        MACH_PORT_NULL,
+    );
+    let reply = addr_of_mut!(forward) as *const ExceptionReply;
+    let ret_code = if ret == KERN_SUCCESS {
+        (*reply).ret_code
+    } else {
+        KERN_FAILURE
+    };
+
+    mach_port_deallocate(task, reply_port);
+    ret_code
+}
+
+/// Replies to the exception in `request`, with `ret_code`, resuming the faulting thread on `KERN_SUCCESS`
+#[allow(clippy::cast_possible_truncation)]
+unsafe fn send_reply(request: &ExceptionRequest, ret_code: kern_return_t) {
+    let mut reply = ExceptionReply {
+        header: MachMsgHeader {
+            // MACH_MSGH_BITS(MACH_MSGH_BITS_REMOTE(request.header.bits), 0)
+            bits: request.header.bits & 0x1f,
+            size: size_of::<ExceptionReply>() as u32,
+            remote_port: request.header.remote_port,
+            local_port: 0,
+            voucher_port: 0,
+            id: request.header.id + 100,
+        },
+        ndr: NDR_record,
+        ret_code,
+    };
+    mach_msg(
+        addr_of_mut!(reply) as *mut c_void,
+        MACH_SEND_MSG,
+        size_of::<ExceptionReply>() as u32,
+        0,
+        0,
+        0,
+        0,
+    );
+}
+
+/// Receives the exceptions of this task, forever
+#[allow(clippy::cast_possible_truncation)]
+fn exception_server(port: mach_port_t, old_ports: OldExceptionPorts) {
+    loop {
+        let mut request = ExceptionRequest::default();
+        let ret = unsafe {
+            mach_msg(
+                addr_of_mut!(request) as *mut c_void,
+                MACH_RCV_MSG,
+                0,
+                size_of::<ExceptionRequest>() as u32,
+                port,
+                0,
+                0,
+            )
+        };
+        if ret != KERN_SUCCESS {
+            continue;
+        }
+
+        let thread = request.thread.name;
+        let task = request.task.name;
+        let exception = request.exception;
+        let code = request.code;
+
+        // Children forked after the setup inherit the exception port,
+        // their exceptions go on as if the port was not set, to their own signal handlers.
+        let signal = if task == unsafe { mach_task_self_ } {
+            exception_to_signal(exception, code[0])
+                .filter(|signal| unsafe { has_signal_handler(*signal) })
+        } else {
+            None
+        };
+
+        let ret_code = if let Some(signal) = signal {
+            unsafe { route_to_signal_handler(thread, signal, code[1]) };
+            // The handler returned, so the thread retries the faulting instruction, as after a signal handler.
+            // Failing here would raise the signal, and run the handler a second time.
+            KERN_SUCCESS
+        } else if let Some((old, behavior)) = old_ports.for_exception(exception) {
+            unsafe { forward_exception(&request, old, behavior) }
+        } else {
+            // Not handled, the kernel goes on with the default handling, and raises the signal
+            KERN_FAILURE
+        };
+
+        unsafe {
+            mach_port_deallocate(mach_task_self_, thread);
+            mach_port_deallocate(mach_task_self_, task);
+            send_reply(&request, ret_code);
+        }
+    }
+}
+
+/// Takes over the exception port of this task, for crashing exceptions such as `EXC_BAD_ACCESS`,
+/// and routes them to the handlers registered with [`crate::bolts::os::unix_signals::setup_signal_handler`],
+/// from a dedicated thread, while the faulting thread is suspended.
+/// Exceptions without a registered handler go to the exception port installed before, if any,
+/// else they get the default handling, as if the port was not set.
+/// Faults reach the signal handlers without it, so only call it if a runtime in the target,
+/// such as a crash reporter, handles the Mach exceptions itself.
+/// The in-process executors opt in with `with_mach_exception_handler`.
+/// Only takes effect once per process, later calls do nothing.
+/// # Safety
+/// The signal handlers will run on the exception thread, while the faulting thread is suspended.
+pub unsafe fn setup_mach_exception_handler() -> Result<(), Error> {
+    if MACH_HANDLER_SET_UP.swap(true, Ordering::SeqCst) {
+        return Ok(());
+    }
+
+    let task = mach_task_self_;
+    let old_ports = OldExceptionPorts::get(task, EXC_MASK_CRASHES)?;
+    let mut port: mach_port_t = MACH_PORT_NULL;
+    if mach_port_allocate(task, MACH_PORT_RIGHT_RECEIVE, &mut port) != KERN_SUCCESS {
+        return Err(Error::unknown("Failed to allocate the Mach exception port"));
+    }
+    if mach_port_insert_right(task, port, port, MACH_MSG_TYPE_MAKE_SEND) != KERN_SUCCESS {
+        return Err(Error::unknown(
+            "Failed to insert a send right for the Mach exception port",
+        ));
+    }
+
+    thread::Builder::new()
+        .name("mach_exceptions".into())
+        .spawn(move || exception_server(port, old_ports))?;
+
+    if task_set_exception_ports(
+        task,
+        EXC_MASK_CRASHES,
+        port,
+        EXCEPTION_DEFAULT | MACH_EXCEPTION_CODES,
+        THREAD_STATE_NONE,
+    ) != KERN_SUCCESS
+    {
+        return Err(Error::unknown("Failed to set the Mach exception port"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use core::{
+        mem::size_of,
+        ptr::{self, addr_of_mut, read_volatile, write_volatile},
+        sync::atomic::{AtomicBool, AtomicPtr, Ordering},
+    };
+    use std::thread;
+
+    use libc::{
+        c_void, mmap, mprotect, siginfo_t, MAP_ANON, MAP_FAILED, MAP_PRIVATE, PROT_READ,
+        PROT_WRITE, SIGBUS, SIGILL, SIGSEGV,
+    };
+    use serial_test::serial;
+
+    use super::{
+        exception_to_signal, mach_msg, mach_port_allocate, mach_port_insert_right, mach_task_self_,
+        send_reply, setup_mach_exception_handler, task_set_exception_ports, ExceptionRequest,
+        OldExceptionPorts, EXCEPTION_DEFAULT, EXC_ARITHMETIC, EXC_BAD_ACCESS, EXC_BAD_INSTRUCTION,
+        EXC_MASK_CRASHES, KERN_INVALID_ADDRESS, KERN_SUCCESS, MACH_EXCEPTION_CODES,
+        MACH_MSG_TYPE_MAKE_SEND, MACH_PORT_NULL, MACH_PORT_RIGHT_RECEIVE, MACH_RCV_MSG,
+        MAX_EXCEPTION_PORTS, THREAD_STATE_NONE,
+    };
+    use crate::bolts::os::unix_signals::{setup_signal_handler, ucontext_t, Handler, Signal};
+
+    const PAGE_SIZE: usize = 0x4000;
+
+    /// The read-only page the test writes to
+    static PAGE: AtomicPtr<c_void> = AtomicPtr::new(ptr::null_mut());
+    /// Set when the fault reached the signal handler
+    static HANDLED: AtomicBool = AtomicBool::new(false);
+    /// Set when the fault reached the competing exception port
+    static COMPETING: AtomicBool = AtomicBool::new(false);
+
+    /// Makes the page writable, so the faulting write succeeds on retry
+    unsafe fn unprotect_page() {
+        mprotect(
+            PAGE.load(Ordering::SeqCst),
+            PAGE_SIZE,
+            PROT_READ | PROT_WRITE,
+        );
+    }
+
+    struct UnprotectHandler;
+
+    impl Handler for UnprotectHandler {
+        fn handle(&mut self, _signal: Signal, _info: siginfo_t, _context: &mut ucontext_t) {
+            HANDLED.store(true, Ordering::SeqCst);
+            unsafe { unprotect_page() };
+        }
+
+        fn signals(&self) -> Vec<Signal> {
+            vec![Signal::SigBus, Signal::SigSegmentationFault]
+        }
+    }
+
+    #[test]
+    fn test_exception_to_signal() {
+        assert_eq!(
+            exception_to_signal(EXC_BAD_ACCESS, KERN_INVALID_ADDRESS),
+            Some(SIGSEGV)
+        );
+        assert_eq!(exception_to_signal(EXC_BAD_ACCESS, 2), Some(SIGBUS));
+        assert_eq!(exception_to_signal(EXC_BAD_INSTRUCTION, 1), Some(SIGILL));
+        assert_eq!(exception_to_signal(42, 0), None);
+    }
+
+    #[test]
+    fn test_old_exception_ports() {
+        let mut old = OldExceptionPorts {
+            count: 2,
+            masks: [0; MAX_EXCEPTION_PORTS],
+            handlers: [MACH_PORT_NULL; MAX_EXCEPTION_PORTS],
+            behaviors: [EXCEPTION_DEFAULT; MAX_EXCEPTION_PORTS],
+        };
+        old.masks[0] = 1 << EXC_BAD_ACCESS;
+        old.handlers[0] = 42;
+        // A null port is no handler
+        old.masks[1] = 1 << EXC_BAD_INSTRUCTION;
+
+        assert_eq!(
+            old.for_exception(EXC_BAD_ACCESS),
+            Some((42, EXCEPTION_DEFAULT))
+        );
+        assert_eq!(old.for_exception(EXC_BAD_INSTRUCTION), None);
+        assert_eq!(old.for_exception(EXC_ARITHMETIC), None);
+    }
+
+    #[test]
+    #[serial]
+    #[allow(clippy::cast_possible_truncation)]
+    fn test_competing_exception_port() {
+        unsafe {
+            // A runtime handling the crashes itself, such as a crash reporter
+            let task = mach_task_self_;
+            let mut competing = MACH_PORT_NULL;
+            assert_eq!(
+                mach_port_allocate(task, MACH_PORT_RIGHT_RECEIVE, &mut competing),
+                KERN_SUCCESS
+            );
+            assert_eq!(
+                mach_port_insert_right(task, competing, competing, MACH_MSG_TYPE_MAKE_SEND),
+                KERN_SUCCESS
+            );
+            thread::spawn(move || loop {
+                let mut request = ExceptionRequest::default();
+                if mach_msg(
+                    addr_of_mut!(request) as *mut c_void,
+                    MACH_RCV_MSG,
+                    0,
+                    size_of::<ExceptionRequest>() as u32,
+                    competing,
+                    0,
+                    0,
+                ) == KERN_SUCCESS
+                {
+                    COMPETING.store(true, Ordering::SeqCst);
+                    unprotect_page();
+                    send_reply(&request, KERN_SUCCESS);
+                }
+            });
+            assert_eq!(
+                task_set_exception_ports(
+                    task,
+                    EXC_MASK_CRASHES,
+                    competing,
+                    EXCEPTION_DEFAULT | MACH_EXCEPTION_CODES,
+                    THREAD_STATE_NONE,
+                ),
+                KERN_SUCCESS
+            );
+
+            setup_signal_handler(Box::leak(Box::new(UnprotectHandler))).unwrap();
+            setup_mach_exception_handler().unwrap();
+
+            let page = mmap(
+                ptr::null_mut(),
+                PAGE_SIZE,
+                PROT_READ,
+                MAP_PRIVATE | MAP_ANON,
+                -1,
+                0,
+            );
+            assert_ne!(page, MAP_FAILED);
+            PAGE.store(page, Ordering::SeqCst);
+
+            write_volatile(page as *mut u8, 42);
+            assert_eq!(read_volatile(page as *const u8), 42);
+
+            // The fault went to the signal handler, not to the port installed before
+            assert!(HANDLED.load(Ordering::SeqCst));
+            assert!(!COMPETING.load(Ordering::SeqCst));
+        }
+    }
+}
@@ -10,17 +10,23 @@ use crate::Error;
 #[cfg(all(unix, feature = "std"))]
 pub mod unix_shmem_server;
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "executors"))]
 pub mod unix_signals;
 
+#[cfg(all(target_vendor = "apple", feature = "std", feature = "executors"))]
+pub mod mach_exceptions;
+
 #[cfg(all(unix, feature = "std"))]
 pub mod pipes;
 
+#[cfg(all(unix, feature = "std"))]
+pub mod dl;
+
 #[cfg(all(unix, feature = "std"))]
 use std::ffi::CString;
 
 // Allow a few extra features we need for the whole module
-#[cfg(all(windows, feature = "std"))]
+#[cfg(all(windows, feature = "std", feature = "executors"))]
 #[allow(missing_docs, overflowing_literals)]
 pub mod windows_exceptions;
 
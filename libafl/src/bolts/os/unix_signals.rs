@@ -243,7 +243,7 @@ pub use libc::ucontext_t;
 use libc::{
     c_int, malloc, sigaction, sigaddset, sigaltstack, sigemptyset, stack_t, SA_NODEFER, SA_ONSTACK,
     SA_SIGINFO, SIGABRT, SIGALRM, SIGBUS, SIGFPE, SIGHUP, SIGILL, SIGINT, SIGKILL, SIGPIPE,
-    SIGQUIT, SIGSEGV, SIGTERM, SIGTRAP, SIGUSR2,
+    SIGQUIT, SIGSEGV, SIGTERM, SIGTRAP, SIGUSR1, SIGUSR2,
 };
 pub use libc::{c_void, siginfo_t};
 use num_enum::{IntoPrimitive, TryFromPrimitive};
@@ -273,6 +273,8 @@ pub enum Signal {
     SigPipe = SIGPIPE,
     /// `SIGSEGV` signal id
     SigSegmentationFault = SIGSEGV,
+    /// `SIGUSR1` signal id
+    SigUser1 = SIGUSR1,
     /// `SIGUSR2` signal id
     SigUser2 = SIGUSR2,
     /// `SIGALARM` signal id
@@ -320,6 +322,7 @@ impl Display for Signal {
             Signal::SigIllegalInstruction => write!(f, "SIGILL")?,
             Signal::SigPipe => write!(f, "SIGPIPE")?,
             Signal::SigSegmentationFault => write!(f, "SIGSEGV")?,
+            Signal::SigUser1 => write!(f, "SIGUSR1")?,
             Signal::SigUser2 => write!(f, "SIGUSR2")?,
             Signal::SigAlarm => write!(f, "SIGALRM")?,
             Signal::SigHangUp => write!(f, "SIGHUP")?,
@@ -361,11 +364,17 @@ static mut SIGNAL_HANDLERS: [Option<HandlerHolder>; 32] = [
     None, None, None, None, None, None, None, None, None, None, None, None, None, None, None, None,
 ];
 
+/// Returns `true` if a handler is registered for `sig` with [`setup_signal_handler()`]
+#[cfg(all(target_vendor = "apple", feature = "std"))]
+pub(crate) unsafe fn has_signal_handler(sig: c_int) -> bool {
+    Signal::try_from(sig).map_or(false, |signal| SIGNAL_HANDLERS[signal as usize].is_some())
+}
+
 /// Internal function that is being called whenever a signal we are registered for arrives.
 /// # Safety
 /// This should be somewhat safe to call for signals previously registered,
 /// unless the signal handlers registered using [`setup_signal_handler()`] are broken.
-unsafe fn handle_signal(sig: c_int, info: siginfo_t, void: *mut c_void) {
+pub(crate) unsafe fn handle_signal(sig: c_int, info: siginfo_t, void: *mut c_void) {
     let signal = &Signal::try_from(sig).unwrap();
     let handler = {
         match &SIGNAL_HANDLERS[*signal as usize] {
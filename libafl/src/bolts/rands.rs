@@ -14,8 +14,22 @@ const HASH_CONST: u64 = 0xa5b35705;
 /// The standard rand implementation for `LibAFL`.
 /// It is usually the right choice, with very good speed and a reasonable randomness.
 /// Not cryptographically secure (which is not what you want during fuzzing ;) )
+///
+/// The `std_rand_romu_trio` and `std_rand_xoshiro` features switch it to
+/// the [`RomuTrioRand`] or the [`Xoshiro256PlusPlusRand`], respectively.
+#[cfg(not(any(feature = "std_rand_romu_trio", feature = "std_rand_xoshiro")))]
 pub type StdRand = RomuDuoJrRand;
 
+/// The standard rand implementation for `LibAFL`, selected by the `std_rand_romu_trio` feature.
+/// Slightly slower than the [`RomuDuoJrRand`], with a larger state.
+#[cfg(all(feature = "std_rand_romu_trio", not(feature = "std_rand_xoshiro")))]
+pub type StdRand = RomuTrioRand;
+
+/// The standard rand implementation for `LibAFL`, selected by the `std_rand_xoshiro` feature.
+/// Slower than the `Romu` rands, but with well-studied statistical quality.
+#[cfg(feature = "std_rand_xoshiro")]
+pub type StdRand = Xoshiro256PlusPlusRand;
+
 /// Ways to get random around here.
 /// Please note that these are not cryptographically secure.
 /// Or, even if some might be by accident, at least they are not seeded in a cryptographically secure fashion.
@@ -98,7 +112,7 @@ macro_rules! default_rand {
 }
 
 // Derive Default by calling `new(DEFAULT_SEED)` on each of the following Rand types.
-default_rand!(Xoshiro256StarRand);
+default_rand!(Xoshiro256PlusPlusRand);
 default_rand!(XorShift64Rand);
 default_rand!(Lehmer64Rand);
 default_rand!(RomuTrioRand);
@@ -145,19 +159,23 @@ macro_rules! impl_random {
     };
 }
 
-impl_random!(Xoshiro256StarRand);
+impl_random!(Xoshiro256PlusPlusRand);
 impl_random!(XorShift64Rand);
 impl_random!(Lehmer64Rand);
 impl_random!(RomuTrioRand);
 impl_random!(RomuDuoJrRand);
 
-/// XXH3 Based, hopefully speedy, rnd implementation
+/// The `xoshiro256++` rand, see <https://prng.di.unimi.it/>, seeded using XXH3
 #[derive(Copy, Clone, Debug, Serialize, Deserialize)]
-pub struct Xoshiro256StarRand {
+pub struct Xoshiro256PlusPlusRand {
     rand_seed: [u64; 4],
 }
 
-impl Rand for Xoshiro256StarRand {
+/// The former name of the [`Xoshiro256PlusPlusRand`], which always implemented `xoshiro256++`
+#[deprecated(note = "Renamed to Xoshiro256PlusPlusRand, it implements xoshiro256++")]
+pub type Xoshiro256StarRand = Xoshiro256PlusPlusRand;
+
+impl Rand for Xoshiro256PlusPlusRand {
     #[allow(clippy::unreadable_literal)]
     fn set_seed(&mut self, seed: u64) {
         self.rand_seed[0] = xxh3_64_with_seed(&HASH_CONST.to_le_bytes(), seed);
@@ -187,7 +205,7 @@ impl Rand for Xoshiro256StarRand {
     }
 }
 
-impl Xoshiro256StarRand {
+impl Xoshiro256PlusPlusRand {
     /// Creates a new Xoshiro rand with the given seed
     #[must_use]
     pub fn with_seed(seed: u64) -> Self {
@@ -380,7 +398,7 @@ mod tests {
     //use xxhash_rust::xxh3::xxh3_64_with_seed;
 
     use crate::bolts::rands::{
-        Rand, RomuDuoJrRand, RomuTrioRand, StdRand, XorShift64Rand, Xoshiro256StarRand,
+        Rand, RomuDuoJrRand, RomuTrioRand, StdRand, XorShift64Rand, Xoshiro256PlusPlusRand,
     };
 
     fn test_single_rand<R: Rand>(rand: &mut R) {
@@ -398,7 +416,17 @@ mod tests {
         test_single_rand(&mut RomuTrioRand::with_seed(0));
         test_single_rand(&mut RomuDuoJrRand::with_seed(0));
         test_single_rand(&mut XorShift64Rand::with_seed(0));
-        test_single_rand(&mut Xoshiro256StarRand::with_seed(0));
+        test_single_rand(&mut Xoshiro256PlusPlusRand::with_seed(0));
+    }
+
+    #[test]
+    fn test_xoshiro256plusplus() {
+        // The reference implementation, starting from a known state
+        let mut rand = Xoshiro256PlusPlusRand {
+            rand_seed: [1, 2, 3, 4],
+        };
+        assert_eq!(rand.next(), 41_943_041);
+        assert_eq!(rand.next(), 58_720_359);
     }
 
     #[cfg(feature = "std")]
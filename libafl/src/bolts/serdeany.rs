@@ -521,6 +521,19 @@ macro_rules! create_serde_registry_for_trait {
                         .insert(xxhash_rust::xxh3::xxh3_64(name.as_bytes()), Box::new(val));
                 }
 
+                /// Remove the element of the given type and `name` from this map. Returns the removed element.
+                #[must_use]
+                #[inline]
+                pub fn remove<T>(&mut self, name: &str) -> Option<Box<T>>
+                where
+                    T: $trait_name,
+                {
+                    self.map
+                        .get_mut(&unpack_type_id(TypeId::of::<T>()))
+                        .and_then(|h| h.remove(&xxhash_rust::xxh3::xxh3_64(name.as_bytes())))
+                        .map(|x| x.as_any_boxed().downcast::<T>().unwrap())
+                }
+
                 /// Returns the `len` of this map.
                 #[must_use]
                 #[inline]
@@ -0,0 +1,134 @@
+//! Serialization formats for events, state snapshots, and on-disk metadata.
+//! Postcard is compact and fast, so it is the default. `bincode` and JSON (the latter mostly for debugging) need `std`.
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+/// The format used to turn events, states, or metadata into bytes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SerializationFormat {
+    /// A binary-encoded postcard
+    Postcard,
+    /// A binary-encoded `bincode`
+    #[cfg(feature = "std")]
+    Bincode,
+    /// JSON, to inspect what is passed around
+    #[cfg(feature = "std")]
+    Json,
+}
+
+impl Default for SerializationFormat {
+    fn default() -> Self {
+        Self::Postcard
+    }
+}
+
+impl SerializationFormat {
+    /// Serializes `value` to a new buffer
+    pub fn serialize<T>(self, value: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.serialize_into(value, vec![])
+    }
+
+    /// Serializes `value`, appending it to `buf`.
+    /// Passing the buffer of a previous serialization, cleared, avoids growing a new one.
+    pub fn serialize_into<T>(self, value: &T, buf: Vec<u8>) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        match self {
+            Self::Postcard => Ok(postcard::to_extend(value, buf)?),
+            #[cfg(feature = "std")]
+            Self::Bincode => {
+                let mut buf = buf;
+                bincode::serialize_into(&mut buf, value)
+                    .map_err(|err| Error::serialize(format!("{err:?}")))?;
+                Ok(buf)
+            }
+            #[cfg(feature = "std")]
+            Self::Json => {
+                let mut buf = buf;
+                serde_json::to_writer(&mut buf, value)?;
+                Ok(buf)
+            }
+        }
+    }
+
+    /// Deserializes a value from `bytes`
+    pub fn deserialize<'a, T>(self, bytes: &'a [u8]) -> Result<T, Error>
+    where
+        T: Deserialize<'a>,
+    {
+        match self {
+            Self::Postcard => Ok(postcard::from_bytes(bytes)?),
+            #[cfg(feature = "std")]
+            Self::Bincode => {
+                bincode::deserialize(bytes).map_err(|err| Error::serialize(format!("{err:?}")))
+            }
+            #[cfg(feature = "std")]
+            Self::Json => Ok(serde_json::from_slice(bytes)?),
+        }
+    }
+
+    /// A unique id of this format, to mark serialized buffers with
+    #[must_use]
+    pub fn id(self) -> u8 {
+        match self {
+            Self::Postcard => 0,
+            #[cfg(feature = "std")]
+            Self::Bincode => 1,
+            #[cfg(feature = "std")]
+            Self::Json => 2,
+        }
+    }
+
+    /// Gets the format from its [`SerializationFormat::id`]
+    pub fn from_id(id: u8) -> Result<Self, Error> {
+        match id {
+            0 => Ok(Self::Postcard),
+            #[cfg(feature = "std")]
+            1 => Ok(Self::Bincode),
+            #[cfg(feature = "std")]
+            2 => Ok(Self::Json),
+            _ => Err(Error::illegal_argument(format!(
+                "Unknown or unsupported serialization format id {id}"
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{string::String, vec::Vec};
+
+    use crate::bolts::serialization::SerializationFormat;
+
+    #[test]
+    fn test_serialization_formats() {
+        let value = (42_u64, String::from("libafl"), vec![1_u8, 2, 3]);
+        for format in [
+            SerializationFormat::Postcard,
+            SerializationFormat::Bincode,
+            SerializationFormat::Json,
+        ] {
+            let serialized = format.serialize(&value).unwrap();
+            let deserialized: (u64, String, Vec<u8>) = format.deserialize(&serialized).unwrap();
+            assert_eq!(deserialized, value);
+            assert_eq!(SerializationFormat::from_id(format.id()).unwrap(), format);
+
+            // Appending to a reused buffer
+            let reused = format.serialize_into(&value, vec![0xff]).unwrap();
+            assert_eq!(&reused[1..], &serialized[..]);
+        }
+        assert!(SerializationFormat::from_id(42).is_err());
+        assert_eq!(
+            SerializationFormat::Json.serialize(&[1, 2]).unwrap(),
+            b"[1,2]"
+        );
+    }
+}
@@ -839,6 +839,63 @@ pub mod unix_shmem {
                 }
             }
 
+            /// Create a new shared memory mapping backed by huge pages, and pin it in memory.
+            /// Large coverage maps get scanned after each execution, so fewer TLB misses pay off.
+            ///
+            /// Tries to allocate explicit huge pages (`SHM_HUGETLB`) first, which requires reserved pages
+            /// (see `/proc/sys/vm/nr_hugepages`). If that fails, falls back to a normal mapping and asks
+            /// for transparent huge pages using `madvise`. Failing to pin the map (for example because of
+            /// `RLIMIT_MEMLOCK`) is not fatal.
+            #[cfg(target_os = "linux")]
+            pub fn new_with_huge_pages(map_size: usize) -> Result<Self, Error> {
+                /// The huge page size on most linux configurations
+                const HUGE_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+                unsafe {
+                    // Explicit huge pages need the size to be a multiple of the huge page size
+                    let huge_size = (map_size + HUGE_PAGE_SIZE - 1) & !(HUGE_PAGE_SIZE - 1);
+                    let os_id = shmget(
+                        libc::IPC_PRIVATE,
+                        huge_size,
+                        libc::IPC_CREAT
+                            | libc::IPC_EXCL
+                            | libc::SHM_HUGETLB
+                            | libc::SHM_R
+                            | libc::SHM_W,
+                    );
+
+                    let shmem = if os_id < 0_i32 {
+                        // No hugetlb pages available, try transparent huge pages instead
+                        let shmem = Self::new(map_size)?;
+                        libc::madvise(shmem.map as *mut _, map_size, libc::MADV_HUGEPAGE);
+                        shmem
+                    } else {
+                        let map = shmat(os_id, ptr::null(), 0) as *mut c_uchar;
+
+                        if map as c_int == -1 || map.is_null() {
+                            shmctl(os_id, libc::IPC_RMID, ptr::null_mut());
+                            return Err(Error::unknown(
+                                "Failed to map the huge page shared mapping".to_string(),
+                            ));
+                        }
+
+                        Self {
+                            id: ShMemId::from_int(os_id),
+                            map,
+                            map_size,
+                        }
+                    };
+
+                    if libc::mlock(shmem.map as *const _, map_size) != 0 {
+                        println!(
+                            "Could not pin shared mapping of size {map_size} in memory - check RLIMIT_MEMLOCK"
+                        );
+                    }
+
+                    Ok(shmem)
+                }
+            }
+
             /// Get a [`UnixShMem`] of the existing shared memory mapping identified by id
             pub fn shmem_from_id_and_size(id: ShMemId, map_size: usize) -> Result<Self, Error> {
                 unsafe {
@@ -893,10 +950,23 @@ pub mod unix_shmem {
         /// A [`ShMemProvider`] which uses `shmget`/`shmat`/`shmctl` to provide shared memory mappings.
         #[cfg(unix)]
         #[derive(Clone, Debug)]
-        pub struct CommonUnixShMemProvider {}
+        pub struct CommonUnixShMemProvider {
+            #[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+            huge_pages: bool,
+        }
 
         unsafe impl Send for CommonUnixShMemProvider {}
 
+        impl CommonUnixShMemProvider {
+            /// Create a provider that backs all new maps with (pinned) huge pages,
+            /// see [`CommonUnixShMem::new_with_huge_pages`].
+            #[cfg(target_os = "linux")]
+            #[must_use]
+            pub fn with_huge_pages() -> Self {
+                Self { huge_pages: true }
+            }
+        }
+
         #[cfg(unix)]
         impl Default for CommonUnixShMemProvider {
             fn default() -> Self {
@@ -910,9 +980,13 @@ pub mod unix_shmem {
             type ShMem = CommonUnixShMem;
 
             fn new() -> Result<Self, Error> {
-                Ok(Self {})
+                Ok(Self { huge_pages: false })
             }
             fn new_shmem(&mut self, map_size: usize) -> Result<Self::ShMem, Error> {
+                #[cfg(target_os = "linux")]
+                if self.huge_pages {
+                    return CommonUnixShMem::new_with_huge_pages(map_size);
+                }
                 CommonUnixShMem::new(map_size)
             }
 
@@ -934,8 +1008,8 @@ pub mod unix_shmem {
         use std::ffi::CString;
 
         use libc::{
-            c_uint, c_ulong, c_void, close, ioctl, mmap, open, MAP_SHARED, O_RDWR, PROT_READ,
-            PROT_WRITE,
+            c_int, c_uint, c_ulong, c_void, close, ioctl, mmap, open, MAP_SHARED, O_RDWR,
+            PROT_READ, PROT_WRITE,
         };
 
         use crate::{
@@ -988,6 +1062,11 @@ pub mod unix_shmem {
                     .unwrap();
 
                     let fd = open(device_path.as_ptr(), O_RDWR);
+                    // Newer Android versions restrict access to the ashmem device, use memfd instead.
+                    #[cfg(any(target_os = "linux", target_os = "android"))]
+                    if fd == -1 {
+                        return Self::new_memfd(map_size);
+                    }
                     if fd == -1 {
                         return Err(Error::unknown(format!(
                             "Failed to open the ashmem device at {:?}",
@@ -1031,16 +1110,78 @@ pub mod unix_shmem {
                 }
             }
 
+            /// Create a new shared memory mapping using `memfd_create`.
+            /// Used as fallback if the ashmem device is not accessible.
+            /// The fd can be handed to other processes the same way as an ashmem fd.
+            #[cfg(any(target_os = "linux", target_os = "android"))]
+            pub fn new_memfd(map_size: usize) -> Result<Self, Error> {
+                unsafe {
+                    let name = CString::new("libafl").unwrap();
+                    // Use the raw syscall, the libc wrapper only exists on recent Android API levels.
+                    #[allow(clippy::cast_possible_truncation)]
+                    let fd = libc::syscall(libc::SYS_memfd_create, name.as_ptr(), 0) as c_int;
+                    if fd == -1 {
+                        return Err(Error::unknown(
+                            "Failed to open the ashmem device, and memfd_create failed".to_string(),
+                        ));
+                    }
+
+                    if libc::ftruncate(fd, map_size.try_into()?) != 0 {
+                        close(fd);
+                        return Err(Error::unknown(
+                            "Failed to set the memfd mapping's size".to_string(),
+                        ));
+                    }
+
+                    let map = mmap(
+                        ptr::null_mut(),
+                        map_size,
+                        PROT_READ | PROT_WRITE,
+                        MAP_SHARED,
+                        fd,
+                        0,
+                    );
+                    if map == usize::MAX as *mut c_void {
+                        close(fd);
+                        return Err(Error::unknown(
+                            "Failed to map the memfd mapping".to_string(),
+                        ));
+                    }
+
+                    Ok(Self {
+                        id: ShMemId::from_string(&format!("{fd}")),
+                        map: map as *mut u8,
+                        map_size,
+                    })
+                }
+            }
+
+            /// Get the size of the mapping behind `fd`, for both, ashmem and memfd mappings.
+            unsafe fn fd_size(fd: c_int) -> Option<usize> {
+                #[allow(trivial_numeric_casts)]
+                let ashmem_size = ioctl(fd, ASHMEM_GET_SIZE as _);
+                if ashmem_size >= 0 {
+                    #[allow(clippy::cast_sign_loss)]
+                    return Some(ashmem_size as u32 as usize);
+                }
+                // Not an ashmem fd, probably a memfd.
+                let mut stat: libc::stat = core::mem::zeroed();
+                if libc::fstat(fd, &mut stat) == 0 {
+                    stat.st_size.try_into().ok()
+                } else {
+                    None
+                }
+            }
+
             /// Get a [`crate::bolts::shmem::unix_shmem::UnixShMem`] of the existing [`ShMem`] mapping identified by id.
             pub fn shmem_from_id_and_size(id: ShMemId, map_size: usize) -> Result<Self, Error> {
                 unsafe {
                     let fd: i32 = id.to_string().parse().unwrap();
-                    #[allow(trivial_numeric_casts, clippy::cast_sign_loss)]
-                    if ioctl(fd, ASHMEM_GET_SIZE as _) as u32 as usize != map_size {
+                    if Self::fd_size(fd) != Some(map_size) {
                         return Err(Error::unknown(
                             "The mapping's size differs from the requested size".to_string(),
                         ));
-                    };
+                    }
 
                     let map = mmap(
                         ptr::null_mut(),
@@ -1106,6 +1247,7 @@ pub mod unix_shmem {
                         len: length,
                     };
 
+                    // Fails (harmlessly) for memfd based mappings.
                     ioctl(fd, ASHMEM_UNPIN as _, &ap);
                     close(fd);
                 }
@@ -1448,4 +1590,34 @@ mod tests {
         map.as_mut_slice()[0] = 1;
         assert!(map.as_slice()[0] == 1);
     }
+
+    #[test]
+    #[serial]
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    fn test_shmem_huge_pages() {
+        use crate::bolts::shmem::{ShMem, UnixShMemProvider};
+
+        // Falls back to regular pages if the system has no huge pages reserved
+        let mut provider = UnixShMemProvider::with_huge_pages();
+        let mut map = provider.new_shmem(1024).unwrap();
+        assert_eq!(map.len(), 1024);
+        map.as_mut_slice()[1023] = 1;
+
+        let other = provider.shmem_from_id_and_size(map.id(), 1024).unwrap();
+        assert!(other.as_slice()[1023] == 1);
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    fn test_shmem_memfd() {
+        use crate::bolts::shmem::{unix_shmem::ashmem::AshmemShMem, ShMem};
+
+        let mut map = AshmemShMem::new_memfd(1024).unwrap();
+        map.as_mut_slice()[0] = 1;
+
+        assert!(AshmemShMem::shmem_from_id_and_size(map.id(), 2048).is_err());
+        let other = AshmemShMem::shmem_from_id_and_size(map.id(), 1024).unwrap();
+        assert!(other.as_slice()[0] == 1);
+    }
 }
@@ -15,6 +15,7 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{
     bolts::{
+        serialization::SerializationFormat,
         shmem::{ShMem, ShMemProvider},
         AsSlice,
     },
@@ -25,6 +26,8 @@ use crate::{
 #[repr(C)]
 struct StateShMemContent {
     is_disk: bool,
+    /// The [`SerializationFormat::id`] of the saved state
+    format: u8,
     buf_len: usize,
     buf: [u8; 0],
 }
@@ -64,6 +67,7 @@ where
     SP: ShMemProvider,
 {
     shmem: SP::ShMem,
+    format: SerializationFormat,
     phantom: PhantomData<*const SP>,
 }
 
@@ -85,6 +89,7 @@ where
     pub fn from_env(shmem_provider: &mut SP, env_name: &str) -> Result<Self, Error> {
         Ok(Self {
             shmem: shmem_provider.existing_from_env(env_name)?,
+            format: SerializationFormat::default(),
             phantom: PhantomData,
         })
     }
@@ -93,12 +98,19 @@ where
     pub fn new(shmem: SP::ShMem) -> Self {
         let mut ret = Self {
             shmem,
+            format: SerializationFormat::default(),
             phantom: PhantomData,
         };
         ret.reset();
         ret
     }
 
+    /// Sets the format the next state is saved in.
+    /// The format is stored next to the state, so restoring does not need to know it.
+    pub fn set_serialization_format(&mut self, format: SerializationFormat) {
+        self.format = format;
+    }
+
     /// Saves a state to the connected [`ShMem`], or a tmpfile, if its serialized size get too large.
     pub fn save<S>(&mut self, state: &S) -> Result<(), Error>
     where
@@ -110,7 +122,8 @@ where
             ));
         }
 
-        let serialized = postcard::to_allocvec(state)?;
+        let serialized = self.format.serialize(state)?;
+        let format = self.format.id();
 
         if size_of::<StateShMemContent>() + serialized.len() > self.shmem.len() {
             // generate a filename
@@ -152,6 +165,7 @@ where
                 );
             }
             shmem_content.buf_len = len;
+            shmem_content.format = format;
             shmem_content.is_disk = true;
         } else {
             // write to shmem directly
@@ -165,6 +179,7 @@ where
                 );
             }
             shmem_content.buf_len = len;
+            shmem_content.format = format;
             shmem_content.is_disk = false;
         };
         Ok(())
@@ -235,7 +250,8 @@ where
             }
             state = &file_content;
         }
-        let deserialized = postcard::from_bytes(state)?;
+        let deserialized =
+            SerializationFormat::from_id(state_shmem_content.format)?.deserialize(state)?;
         Ok(Some(deserialized))
     }
 }
@@ -247,10 +263,12 @@ mod tests {
         string::{String, ToString},
         vec::Vec,
     };
+    use core::marker::PhantomData;
 
     use serial_test::serial;
 
     use crate::bolts::{
+        serialization::SerializationFormat,
         shmem::{ShMemProvider, StdShMemProvider},
         staterestore::StateRestorer,
     };
@@ -280,6 +298,18 @@ mod tests {
         assert!(!state_restorer.content().is_disk);
         assert!(state_restorer.restore::<String>().unwrap().is_none());
 
+        // A state saved as JSON restores without knowing its format
+        state_restorer.set_serialization_format(SerializationFormat::Json);
+        state_restorer.save(&state).unwrap();
+        let restorer_copy = StateRestorer::<StdShMemProvider> {
+            shmem: state_restorer.shmem.clone(),
+            format: SerializationFormat::Postcard,
+            phantom: PhantomData,
+        };
+        assert_eq!(restorer_copy.restore::<String>().unwrap().unwrap(), state);
+        state_restorer.reset();
+        state_restorer.set_serialization_format(SerializationFormat::Postcard);
+
         let too_large = vec![4u8; TESTMAP_SIZE + 1];
         state_restorer.save(&too_large).unwrap();
         assert!(state_restorer.has_content());
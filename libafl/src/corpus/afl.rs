@@ -0,0 +1,229 @@
+//! The [`AflCorpus`] stores testcases to disk the way AFL++ does, e.g., as `queue/id:000042,src:000007,time:1234,execs:5678,op:havoc`,
+//! so that existing triage tools, such as `afl-collect` or `casr-afl`, work on the output of `LibAFL` unchanged.
+
+use alloc::{string::String, vec::Vec};
+use core::cell::RefCell;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, Testcase},
+    feedbacks::afl_entry::AflEntryMetadata,
+    inputs::{Input, UsesInput},
+    state::HasMetadata,
+    Error,
+};
+
+/// The directory AFL++ keeps the queue in, inside the output directory of each instance
+pub const AFL_QUEUE_DIR: &str = "queue";
+/// The directory AFL++ keeps the crashes in, inside the output directory of each instance
+pub const AFL_CRASHES_DIR: &str = "crashes";
+
+/// A corpus storing testcases in a directory using the AFL++ naming conventions.
+///
+/// The names encode the [`AflEntryMetadata`] of each testcase, add an [`crate::feedbacks::AflEntryFeedback`]
+/// to both, the feedback and the objective, to record it.
+/// Testcases without it are just named by their id.
+/// Use [`AflCorpus::queue`] as corpus, and [`AflCorpus::crashes`] as solutions corpus, to get the AFL++ output layout,
+/// and a [`crate::stages::AflStatsStage`] to maintain the `fuzzer_stats` next to them.
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "I: serde::de::DeserializeOwned")]
+pub struct AflCorpus<I>
+where
+    I: Input,
+{
+    entries: Vec<RefCell<Testcase<I>>>,
+    current: Option<usize>,
+    dir_path: PathBuf,
+    next_id: usize,
+}
+
+impl<I> UsesInput for AflCorpus<I>
+where
+    I: Input,
+{
+    type Input = I;
+}
+
+impl<I> Corpus for AflCorpus<I>
+where
+    I: Input,
+{
+    /// Returns the number of elements
+    #[inline]
+    fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Add an entry to the corpus and return its index
+    #[inline]
+    fn add(&mut self, mut testcase: Testcase<I>) -> Result<usize, Error> {
+        self.save_testcase(&mut testcase)?;
+        self.entries.push(RefCell::new(testcase));
+        Ok(self.entries.len() - 1)
+    }
+
+    /// Replaces the testcase at the given idx
+    #[inline]
+    fn replace(&mut self, idx: usize, mut testcase: Testcase<I>) -> Result<Testcase<I>, Error> {
+        if idx >= self.entries.len() {
+            return Err(Error::key_not_found(format!("Index {idx} out of bounds")));
+        }
+        self.save_testcase(&mut testcase)?;
+        let previous = self.entries[idx].replace(testcase);
+        Self::remove_testcase(&previous)?;
+        Ok(previous)
+    }
+
+    /// Removes an entry from the corpus, returning it if it was present.
+    #[inline]
+    fn remove(&mut self, idx: usize) -> Result<Option<Testcase<I>>, Error> {
+        if idx >= self.entries.len() {
+            Ok(None)
+        } else {
+            let prev = self.entries.remove(idx).into_inner();
+            Self::remove_testcase(&prev)?;
+            Ok(Some(prev))
+        }
+    }
+
+    /// Get by id
+    #[inline]
+    fn get(&self, idx: usize) -> Result<&RefCell<Testcase<I>>, Error> {
+        Ok(&self.entries[idx])
+    }
+
+    /// Current testcase scheduled
+    #[inline]
+    fn current(&self) -> &Option<usize> {
+        &self.current
+    }
+
+    /// Current testcase scheduled (mutable)
+    #[inline]
+    fn current_mut(&mut self) -> &mut Option<usize> {
+        &mut self.current
+    }
+}
+
+impl<I> AflCorpus<I>
+where
+    I: Input,
+{
+    /// Creates a new [`AflCorpus`], storing the testcases in `dir_path`.
+    /// Will error, if [`std::fs::create_dir_all()`] failed for `dir_path`.
+    pub fn new<P>(dir_path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let dir_path = dir_path.as_ref().to_path_buf();
+        fs::create_dir_all(&dir_path)?;
+        Ok(Self {
+            entries: vec![],
+            current: None,
+            dir_path,
+            next_id: 0,
+        })
+    }
+
+    /// Creates a new [`AflCorpus`] for the queue of the AFL++ output directory `out_dir`
+    pub fn queue<P>(out_dir: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(out_dir.as_ref().join(AFL_QUEUE_DIR))
+    }
+
+    /// Creates a new [`AflCorpus`] for the crashes of the AFL++ output directory `out_dir`
+    pub fn crashes<P>(out_dir: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(out_dir.as_ref().join(AFL_CRASHES_DIR))
+    }
+
+    /// The directory the testcases are stored in
+    #[must_use]
+    pub fn dir_path(&self) -> &PathBuf {
+        &self.dir_path
+    }
+
+    fn save_testcase(&mut self, testcase: &mut Testcase<I>) -> Result<(), Error> {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let meta = testcase.metadata_mut().get_mut::<AflEntryMetadata>();
+        let name: String = if let Some(meta) = meta {
+            meta.id = Some(id);
+            meta.file_name(id)
+        } else {
+            format!("id:{id:06}")
+        };
+        let filename = self.dir_path.join(name);
+        testcase.set_filename(filename.to_str().expect("Invalid Path").into());
+        testcase.store_input()?;
+        Ok(())
+    }
+
+    fn remove_testcase(testcase: &Testcase<I>) -> Result<(), Error> {
+        if let Some(filename) = testcase.filename() {
+            fs::remove_file(filename)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::{env, fs, process};
+
+    use crate::{
+        corpus::{afl::AflCorpus, Corpus, Testcase},
+        feedbacks::afl_entry::AflEntryMetadata,
+        inputs::BytesInput,
+        state::HasMetadata,
+    };
+
+    #[test]
+    fn test_afl_corpus() {
+        let out_dir = env::temp_dir().join(format!("libafl_afl_corpus_{}", process::id()));
+        let mut corpus = AflCorpus::<BytesInput>::queue(&out_dir).unwrap();
+
+        corpus
+            .add(Testcase::new(BytesInput::new(b"seed".to_vec())))
+            .unwrap();
+        let mut testcase = Testcase::new(BytesInput::new(b"found".to_vec()));
+        testcase.add_metadata(AflEntryMetadata {
+            id: None,
+            sig: None,
+            src: Some(0),
+            time: Duration::from_millis(1234),
+            execs: 5678,
+            op: "havoc".into(),
+        });
+        let idx = corpus.add(testcase).unwrap();
+
+        let queue = out_dir.join("queue");
+        assert_eq!(fs::read(queue.join("id:000000")).unwrap(), b"seed");
+        assert_eq!(
+            fs::read(queue.join("id:000001,src:000000,time:1234,execs:5678,op:havoc")).unwrap(),
+            b"found"
+        );
+        let testcase = corpus.get(idx).unwrap().borrow();
+        assert_eq!(
+            testcase.metadata().get::<AflEntryMetadata>().unwrap().id,
+            Some(1)
+        );
+        drop(testcase);
+
+        corpus.remove(0).unwrap();
+        assert!(!queue.join("id:000000").exists());
+
+        fs::remove_dir_all(&out_dir).unwrap();
+    }
+}
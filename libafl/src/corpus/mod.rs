@@ -16,6 +16,16 @@ pub mod cached;
 #[cfg(feature = "std")]
 pub use cached::CachedOnDiskCorpus;
 
+#[cfg(feature = "std")]
+pub mod afl;
+#[cfg(feature = "std")]
+pub use afl::AflCorpus;
+
+#[cfg(feature = "std")]
+pub mod repro;
+#[cfg(feature = "std")]
+pub use repro::{Reproducer, ReproducerCorpus};
+
 #[cfg(feature = "cmin")]
 pub mod minimizer;
 use core::cell::RefCell;
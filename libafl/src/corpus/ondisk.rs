@@ -12,7 +12,7 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    bolts::serdeany::SerdeAnyMap,
+    bolts::{serdeany::SerdeAnyMap, serialization::SerializationFormat},
     corpus::{Corpus, Testcase},
     inputs::{Input, UsesInput},
     state::HasMetadata,
@@ -25,6 +25,8 @@ use crate::{
 pub enum OnDiskMetadataFormat {
     /// A binary-encoded postcard
     Postcard,
+    /// A binary-encoded `bincode`
+    Bincode,
     /// JSON
     Json,
     /// JSON formatted for readability
@@ -212,6 +214,9 @@ where
 
             let serialized = match self.meta_format.as_ref().unwrap() {
                 OnDiskMetadataFormat::Postcard => postcard::to_allocvec(&ondisk_meta)?,
+                OnDiskMetadataFormat::Bincode => {
+                    SerializationFormat::Bincode.serialize(&ondisk_meta)?
+                }
                 OnDiskMetadataFormat::Json => serde_json::to_vec(&ondisk_meta)?,
                 OnDiskMetadataFormat::JsonPretty => serde_json::to_vec_pretty(&ondisk_meta)?,
             };
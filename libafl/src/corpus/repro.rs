@@ -0,0 +1,360 @@
+//! Self-contained reproducers for solutions.
+//! The [`ReproducerCorpus`] writes a [`Reproducer`] for each testcase added to it, usually the solutions,
+//! which [`crate::fuzzer::StdFuzzer::reproduce`] runs again, e.g., for `--repro <file>` of the same fuzzer binary.
+
+use alloc::{string::String, vec::Vec};
+use core::{cell::RefCell, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{serdeany::SerdeAnyMap, serialization::SerializationFormat},
+    corpus::{Corpus, Testcase},
+    inputs::{Input, UsesInput},
+    state::HasMetadata,
+    Error,
+};
+
+/// The file extension of the [`Reproducer`] files
+pub const REPRODUCER_EXTENSION: &str = "repro";
+
+/// A self-contained reproducer of a testcase: its input, its metadata,
+/// and the configuration of the harness it was found with
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(bound = "I: serde::de::DeserializeOwned")]
+pub struct Reproducer<I>
+where
+    I: Input,
+{
+    /// The input
+    pub input: I,
+    /// The metadata of the testcase, such as the [`crate::executors::ExitKind`]
+    pub metadata: SerdeAnyMap,
+    /// The time the testcase took to run, if known
+    pub exec_time: Option<Duration>,
+    /// The configuration of the harness, such as the command line of the fuzzer
+    pub config: String,
+}
+
+impl<I> Reproducer<I>
+where
+    I: Input,
+{
+    /// Creates the [`Reproducer`] of a [`Testcase`], loading its input if needed
+    pub fn from_testcase(testcase: &mut Testcase<I>, config: &str) -> Result<Self, Error> {
+        let input = testcase.load_input()?.clone();
+        Ok(Self {
+            input,
+            metadata: testcase.metadata().clone(),
+            exec_time: *testcase.exec_time(),
+            config: config.into(),
+        })
+    }
+
+    /// Writes this [`Reproducer`] to `path`, in the given `format`.
+    /// The file starts with the [`SerializationFormat::id`] of the format, so reading it does not need to know it.
+    pub fn to_file<P>(&self, path: P, format: SerializationFormat) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        fs::write(path, format.serialize_into(self, vec![format.id()])?)?;
+        Ok(())
+    }
+
+    /// Reads a [`Reproducer`] from `path`, as written by [`Reproducer::to_file`]
+    pub fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = fs::read(path)?;
+        match bytes.split_first() {
+            Some((id, serialized)) => SerializationFormat::from_id(*id)?.deserialize(serialized),
+            None => Err(Error::illegal_argument("Empty reproducer file")),
+        }
+    }
+}
+
+/// A corpus writing a [`Reproducer`] to `dir_path` for each testcase added to it,
+/// and storing the testcases in the inner corpus.
+/// The reproducers of replaced and removed testcases get deleted.
+/// Wrap the solutions with it to get a reproducer for each objective.
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct ReproducerCorpus<C> {
+    inner: C,
+    dir_path: PathBuf,
+    config: String,
+    format: SerializationFormat,
+    /// The reproducer file of each testcase, by index
+    paths: Vec<PathBuf>,
+}
+
+impl<C> UsesInput for ReproducerCorpus<C>
+where
+    C: UsesInput,
+{
+    type Input = C::Input;
+}
+
+impl<C> Corpus for ReproducerCorpus<C>
+where
+    C: Corpus,
+{
+    /// Returns the number of elements
+    #[inline]
+    fn count(&self) -> usize {
+        self.inner.count()
+    }
+
+    /// Add an entry to the corpus, write its [`Reproducer`], and return its index.
+    /// If the reproducer cannot be written, the entry is removed again.
+    fn add(&mut self, testcase: Testcase<C::Input>) -> Result<usize, Error> {
+        let idx = self.inner.add(testcase)?;
+        match self.write_reproducer(idx) {
+            Ok(path) => {
+                self.paths.insert(idx.min(self.paths.len()), path);
+                Ok(idx)
+            }
+            Err(err) => {
+                self.inner.remove(idx)?;
+                Err(err)
+            }
+        }
+    }
+
+    /// Replaces the testcase at the given idx, and its [`Reproducer`]
+    fn replace(
+        &mut self,
+        idx: usize,
+        testcase: Testcase<C::Input>,
+    ) -> Result<Testcase<C::Input>, Error> {
+        let previous = self.inner.replace(idx, testcase)?;
+        let path = self.write_reproducer(idx)?;
+        if let Some(stale) = self.paths.get_mut(idx) {
+            if *stale != path {
+                fs::remove_file(&stale)?;
+            }
+            *stale = path;
+        }
+        Ok(previous)
+    }
+
+    /// Removes an entry from the corpus, and its [`Reproducer`], returning the entry if it was present.
+    fn remove(&mut self, idx: usize) -> Result<Option<Testcase<C::Input>>, Error> {
+        let removed = self.inner.remove(idx)?;
+        if removed.is_some() && idx < self.paths.len() {
+            fs::remove_file(self.paths.remove(idx))?;
+        }
+        Ok(removed)
+    }
+
+    /// Get by id
+    #[inline]
+    fn get(&self, idx: usize) -> Result<&RefCell<Testcase<C::Input>>, Error> {
+        self.inner.get(idx)
+    }
+
+    /// Current testcase scheduled
+    #[inline]
+    fn current(&self) -> &Option<usize> {
+        self.inner.current()
+    }
+
+    /// Current testcase scheduled (mutable)
+    #[inline]
+    fn current_mut(&mut self) -> &mut Option<usize> {
+        self.inner.current_mut()
+    }
+}
+
+impl<C> ReproducerCorpus<C>
+where
+    C: Corpus,
+{
+    /// Creates the [`ReproducerCorpus`], writing the reproducers of the testcases added to `inner` to `dir_path`.
+    /// The `config` describes the harness, e.g., the command line of the fuzzer, and ends up in each [`Reproducer`].
+    /// Will error, if [`std::fs::create_dir_all()`] failed for `dir_path`.
+    pub fn new<P>(inner: C, dir_path: P, config: &str) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        fs::create_dir_all(dir_path.as_ref())?;
+        Ok(Self {
+            inner,
+            dir_path: dir_path.as_ref().to_path_buf(),
+            config: config.into(),
+            format: SerializationFormat::default(),
+            paths: vec![],
+        })
+    }
+
+    /// Sets the format the next reproducers are written in, see [`Reproducer::to_file`]
+    pub fn set_serialization_format(&mut self, format: SerializationFormat) {
+        self.format = format;
+    }
+
+    /// Writes the [`Reproducer`] of the testcase at `idx`, named after its file, if it has one, and returns its path
+    fn write_reproducer(&self, idx: usize) -> Result<PathBuf, Error> {
+        let mut testcase = self.inner.get(idx)?.borrow_mut();
+        let reproducer = Reproducer::from_testcase(&mut testcase, &self.config)?;
+        let name = match testcase.filename() {
+            Some(filename) => Path::new(filename)
+                .file_name()
+                .ok_or_else(|| {
+                    Error::illegal_argument(format!(
+                        "The file of the testcase {filename} has no name to name its reproducer after"
+                    ))
+                })?
+                .to_string_lossy()
+                .into_owned(),
+            None => reproducer.input.generate_name(idx),
+        };
+        let path = self.dir_path.join(format!("{name}.{REPRODUCER_EXTENSION}"));
+        reproducer.to_file(&path, self.format)?;
+        Ok(path)
+    }
+
+    /// The directory the reproducers get written to
+    #[must_use]
+    pub fn dir_path(&self) -> &Path {
+        &self.dir_path
+    }
+
+    /// The wrapped corpus
+    #[must_use]
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+}
+
+#[cfg(all(test, feature = "executors"))]
+mod tests {
+    use alloc::vec::Vec;
+    use std::{env, fs, process};
+
+    use crate::{
+        bolts::{rands::StdRand, serialization::SerializationFormat, tuples::tuple_list},
+        corpus::{
+            repro::{Reproducer, ReproducerCorpus},
+            Corpus, InMemoryCorpus, Testcase,
+        },
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::{ConstFeedback, CrashFeedback},
+        inputs::{BytesInput, HasBytesVec},
+        schedulers::QueueScheduler,
+        state::{HasMetadata, HasSolutions, StdState},
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_reproducer_corpus() {
+        let dir = env::temp_dir().join(format!("libafl_repro_{}", process::id()));
+        let mut corpus = ReproducerCorpus::new(
+            InMemoryCorpus::<BytesInput>::new(),
+            &dir,
+            "./fuzzer --crash",
+        )
+        .unwrap();
+        let mut testcase = Testcase::new(BytesInput::new(b"crash".to_vec()));
+        testcase.add_metadata(ExitKind::Crash);
+        let idx = corpus.add(testcase).unwrap();
+
+        let path = fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+        let reproducer = Reproducer::<BytesInput>::from_file(&path).unwrap();
+        assert_eq!(reproducer.input.bytes(), b"crash");
+        assert_eq!(reproducer.config, "./fuzzer --crash");
+        assert_eq!(
+            reproducer.metadata.get::<ExitKind>(),
+            corpus
+                .get(idx)
+                .unwrap()
+                .borrow()
+                .metadata()
+                .get::<ExitKind>()
+        );
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = CrashFeedback::new();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+        let mut harness = |input: &BytesInput| {
+            if input.bytes() == b"crash" {
+                ExitKind::Crash
+            } else {
+                ExitKind::Ok
+            }
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )
+        .unwrap();
+
+        let result = fuzzer
+            .reproduce(&mut state, &mut executor, &mut mgr, &path)
+            .unwrap();
+        assert_eq!(result.exit_kind, ExitKind::Crash);
+        assert!(result.solution);
+        assert_eq!(state.solutions().count(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reproducer_files() {
+        let dir = env::temp_dir().join(format!("libafl_repro_files_{}", process::id()));
+        let mut corpus =
+            ReproducerCorpus::new(InMemoryCorpus::<BytesInput>::new(), &dir, "./fuzzer").unwrap();
+        let files = || {
+            let mut files: Vec<_> = fs::read_dir(&dir)
+                .unwrap()
+                .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+                .collect();
+            files.sort();
+            files
+        };
+        let testcase = |bytes: &[u8], filename: &str| {
+            Testcase::with_filename(BytesInput::new(bytes.to_vec()), filename.into())
+        };
+
+        corpus.set_serialization_format(SerializationFormat::Json);
+        corpus.add(testcase(b"first", "/crashes/first")).unwrap();
+        let second = dir.join("second.repro");
+        corpus.set_serialization_format(SerializationFormat::Postcard);
+        corpus.add(testcase(b"second", "second")).unwrap();
+        assert_eq!(files(), ["first.repro", "second.repro"]);
+        assert_eq!(fs::read(dir.join("first.repro")).unwrap()[1], b'{');
+        let reproducer = Reproducer::<BytesInput>::from_file(&second).unwrap();
+        assert_eq!(reproducer.input.bytes(), b"second");
+
+        // Testcases with a file without a name get no reproducer, and do not end up in the corpus
+        assert!(corpus.add(testcase(b"unnamed", "..")).is_err());
+        assert_eq!(corpus.count(), 2);
+
+        // The reproducers of replaced and removed testcases are gone
+        corpus.replace(0, testcase(b"third", "third")).unwrap();
+        assert_eq!(files(), ["second.repro", "third.repro"]);
+        corpus.remove(0).unwrap();
+        assert_eq!(files(), ["second.repro"]);
+        corpus.replace(0, testcase(b"fourth", "second")).unwrap();
+        let reproducer = Reproducer::<BytesInput>::from_file(&second).unwrap();
+        assert_eq!(reproducer.input.bytes(), b"fourth");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
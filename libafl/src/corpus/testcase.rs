@@ -1,5 +1,5 @@
 //! The testcase is a struct embedded in each corpus.
-//! It will contain a respective input, and metadata.
+//! It will contain a respective input, and metadata, by type and by name.
 
 use alloc::string::String;
 use core::{default::Default, option::Option, time::Duration};
@@ -7,9 +7,12 @@ use core::{default::Default, option::Option, time::Duration};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    bolts::{serdeany::SerdeAnyMap, HasLen},
+    bolts::{
+        serdeany::{NamedSerdeAnyMap, SerdeAnyMap},
+        HasLen,
+    },
     inputs::Input,
-    state::HasMetadata,
+    state::{HasMetadata, HasNamedMetadata},
     Error,
 };
 
@@ -26,6 +29,9 @@ where
     filename: Option<String>,
     /// Map of metadata associated with this testcase
     metadata: SerdeAnyMap,
+    /// Map of metadata associated with this testcase, addressed by name,
+    /// so that different components can keep metadata of the same type
+    named_metadata: NamedSerdeAnyMap,
     /// Time needed to execute the input
     exec_time: Option<Duration>,
     /// Cached len of the input, if any
@@ -55,6 +61,23 @@ where
     }
 }
 
+impl<I> HasNamedMetadata for Testcase<I>
+where
+    I: Input,
+{
+    /// Get all the named metadata
+    #[inline]
+    fn named_metadata(&self) -> &NamedSerdeAnyMap {
+        &self.named_metadata
+    }
+
+    /// Get all the named metadata (mutable)
+    #[inline]
+    fn named_metadata_mut(&mut self) -> &mut NamedSerdeAnyMap {
+        &mut self.named_metadata
+    }
+}
+
 /// Impl of a testcase
 impl<I> Testcase<I>
 where
@@ -226,6 +249,7 @@ where
             input: None,
             filename: None,
             metadata: SerdeAnyMap::new(),
+            named_metadata: NamedSerdeAnyMap::new(),
             exec_time: None,
             cached_len: None,
             fuzz_level: 0,
@@ -344,6 +368,56 @@ impl SchedulerTestcaseMetaData {
 
 crate::impl_serdeany!(SchedulerTestcaseMetaData);
 
+#[cfg(test)]
+mod tests {
+    use crate::{
+        corpus::{SchedulerTestcaseMetaData, Testcase},
+        inputs::BytesInput,
+        state::{HasMetadata, HasNamedMetadata},
+    };
+
+    #[test]
+    fn test_named_metadata() {
+        let mut testcase = Testcase::new(BytesInput::new(vec![]));
+        testcase.add_metadata(SchedulerTestcaseMetaData::new(0));
+        // The same type, stored by two components, doesn't collide
+        testcase.add_named_metadata(SchedulerTestcaseMetaData::new(1), "first");
+        testcase.add_named_metadata(SchedulerTestcaseMetaData::new(2), "second");
+
+        let named = testcase.named_metadata();
+        assert_eq!(
+            named
+                .get::<SchedulerTestcaseMetaData>("first")
+                .unwrap()
+                .depth(),
+            1
+        );
+        assert_eq!(
+            named
+                .get::<SchedulerTestcaseMetaData>("second")
+                .unwrap()
+                .depth(),
+            2
+        );
+        assert_eq!(
+            testcase
+                .metadata()
+                .get::<SchedulerTestcaseMetaData>()
+                .unwrap()
+                .depth(),
+            0
+        );
+
+        let removed = testcase
+            .named_metadata_mut()
+            .remove::<SchedulerTestcaseMetaData>("first")
+            .unwrap();
+        assert_eq!(removed.depth(), 1);
+        assert!(!testcase.has_named_metadata::<SchedulerTestcaseMetaData>("first"));
+        assert!(testcase.has_named_metadata::<SchedulerTestcaseMetaData>("second"));
+    }
+}
+
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
 /// `Testcase` Python bindings
@@ -7,13 +7,18 @@ use alloc::{
 };
 #[cfg(feature = "std")]
 use core::sync::atomic::{compiler_fence, Ordering};
-use core::{marker::PhantomData, time::Duration};
+use core::{cell::RefCell, marker::PhantomData, time::Duration};
 #[cfg(feature = "std")]
 use std::net::{SocketAddr, ToSocketAddrs};
+#[cfg(all(unix, feature = "std"))]
+use std::path::Path;
+#[cfg(feature = "std")]
+use std::path::PathBuf;
 
-use serde::Deserialize;
+use hashbrown::HashMap;
 #[cfg(feature = "std")]
-use serde::{de::DeserializeOwned, Serialize};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
 #[cfg(feature = "std")]
 use typed_builder::TypedBuilder;
 
@@ -25,15 +30,17 @@ use crate::bolts::os::startable_self;
 #[cfg(all(feature = "std", feature = "fork", unix))]
 use crate::bolts::os::{fork, ForkResult};
 #[cfg(feature = "llmp_compression")]
-use crate::bolts::{
-    compress::GzipCompressor,
-    llmp::{LLMP_FLAG_COMPRESSED, LLMP_FLAG_INITIALIZED},
-};
+use crate::bolts::{compress::GzipCompressor, llmp::LLMP_FLAG_COMPRESSED};
 #[cfg(feature = "std")]
 use crate::bolts::{llmp::LlmpConnection, shmem::StdShMemProvider, staterestore::StateRestorer};
 use crate::{
     bolts::{
-        llmp::{self, Flags, LlmpClient, LlmpClientDescription, Tag},
+        current_nanos, current_time,
+        llmp::{
+            self, ClientId, Flags, LlmpClient, LlmpClientDescription, Tag, LLMP_FLAG_BINCODE,
+            LLMP_FLAG_CHUNKED, LLMP_FLAG_FROM_B2B, LLMP_FLAG_INITIALIZED, LLMP_FLAG_JSON,
+        },
+        serialization::SerializationFormat,
         shmem::ShMemProvider,
     },
     events::{
@@ -43,7 +50,9 @@ use crate::{
     executors::{Executor, HasObservers},
     fuzzer::{EvaluatorObservers, ExecutionProcessor},
     inputs::{Input, UsesInput},
-    monitors::Monitor,
+    monitors::{ClientStats, Monitor, UserStats},
+    mutators::CrossClientInputsMetadata,
+    observers::ObserversTuple,
     state::{HasClientPerfMonitor, HasExecutions, HasMetadata, UsesState},
     Error,
 };
@@ -52,18 +61,277 @@ use crate::{
 const _LLMP_TAG_EVENT_TO_CLIENT: Tag = 0x2C11E471;
 /// Only handle this in the broker
 const _LLMP_TAG_EVENT_TO_BROKER: Tag = 0x2B80438;
-/// Handle in both
-///
-const LLMP_TAG_EVENT_TO_BOTH: Tag = 0x2B0741;
+/// Handle in both.
+/// The tag of all events without a tag of their own, such as [`Event::Log`]
+pub const LLMP_TAG_EVENT_TO_BOTH: Tag = 0x2B0741;
+/// The tag of [`Event::NewTestcase`], to subscribe to with [`LlmpEventManager::subscribe`]
+pub const LLMP_TAG_EVENT_TESTCASE: Tag = 0x2B7E57C5;
+/// The tag of [`Event::Objective`], to subscribe to with [`LlmpEventManager::subscribe`]
+pub const LLMP_TAG_EVENT_OBJECTIVE: Tag = 0x2B0B7EC7;
+/// The tag of the events updating the stats of a client, such as [`Event::UpdateExecStats`],
+/// to subscribe to with [`LlmpEventManager::subscribe`]
+pub const LLMP_TAG_EVENT_STATS: Tag = 0x2B57A75;
 const _LLMP_TAG_RESTART: Tag = 0x8357A87;
 const _LLMP_TAG_NO_RESTART: Tag = 0x57A7EE71;
+/// Test messages of [`LlmpEventManager::self_test`], starting with the id of the sending client.
+/// The broker echoes them back, instead of forwarding them, and all other clients ignore them.
+const LLMP_TAG_SELF_TEST: Tag = 0x5E1F7E57;
+/// The [`BrokerStats`] a broker sends to the brokers connected to it, ignored by all clients
+const LLMP_TAG_BROKER_STATS: Tag = 0xB5757A75;
+
+/// A client sends its pid with this tag once it is set up, so that its broker can restart it if it hangs
+const LLMP_TAG_CLIENT_PID: Tag = 0xC11E7919;
+
+/// A client sends an empty message with this tag regularly, so that its broker knows it is alive
+const LLMP_TAG_KEEP_ALIVE: Tag = 0xA11FE;
+
+/// How often a client sends a keep-alive message to its broker
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// How often a broker sends the [`BrokerStats`] of its own clients to the brokers connected to it
+const BROKER_STATS_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How long a client may stay silent before its broker reports it as hung, see [`LlmpEventBroker::set_client_timeout`].
+/// Fuzzing clients report their progress every 15 seconds.
+pub const DEFAULT_CLIENT_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// The message sizes used by [`LlmpEventManager::self_test`]
+#[cfg(feature = "std")]
+const SELF_TEST_MSG_SIZES: [usize; 4] = [64, 4096, 256 * 1024, 4 * 1024 * 1024];
+/// The number of messages per size sent by [`LlmpEventManager::self_test`]
+#[cfg(feature = "std")]
+const SELF_TEST_MSGS_PER_SIZE: usize = 16;
+/// How long [`LlmpEventManager::self_test`] waits for its messages to come back
+#[cfg(feature = "std")]
+const SELF_TEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 /// The minimum buffer size at which to compress LLMP IPC messages.
 #[cfg(feature = "llmp_compression")]
 const COMPRESS_THRESHOLD: usize = 1024;
 
+/// The maximum size of a single event message.
+/// Larger events, after compression, are split into chunks flagged with [`LLMP_FLAG_CHUNKED`],
+/// so that huge inputs do not blow up the shared maps.
+const CHUNK_SIZE: usize = 4 * 1024 * 1024;
+/// The header of each chunk: the nonce of the event, its total length, and the offset of the chunk,
+/// as little endian `u64`s
+const CHUNK_HEADER_LEN: usize = 24;
+
+/// The flags marking an event serialized in `format`
+fn format_flags(format: SerializationFormat) -> Flags {
+    match format {
+        SerializationFormat::Postcard => LLMP_FLAG_INITIALIZED,
+        #[cfg(feature = "std")]
+        SerializationFormat::Bincode => LLMP_FLAG_BINCODE,
+        #[cfg(feature = "std")]
+        SerializationFormat::Json => LLMP_FLAG_JSON,
+    }
+}
+
+/// The tag an event is sent with, so that consumers can subscribe to the kinds of events they need
+fn event_tag<I>(event: &Event<I>) -> Tag
+where
+    I: Input,
+{
+    match event {
+        Event::NewTestcase { .. } => LLMP_TAG_EVENT_TESTCASE,
+        Event::Objective { .. } => LLMP_TAG_EVENT_OBJECTIVE,
+        Event::UpdateExecStats { .. } | Event::UpdateUserStats { .. } => LLMP_TAG_EVENT_STATS,
+        #[cfg(feature = "introspection")]
+        Event::UpdatePerfMonitor { .. } => LLMP_TAG_EVENT_STATS,
+        _ => LLMP_TAG_EVENT_TO_BOTH,
+    }
+}
+
+/// If messages with this `tag` hold an event
+fn is_event_tag(tag: Tag) -> bool {
+    matches!(
+        tag,
+        LLMP_TAG_EVENT_TO_BOTH
+            | LLMP_TAG_EVENT_TESTCASE
+            | LLMP_TAG_EVENT_OBJECTIVE
+            | LLMP_TAG_EVENT_STATS
+    )
+}
+
+/// The format of an event, given the `flags` of its message
+fn format_from_flags(flags: Flags) -> Result<SerializationFormat, Error> {
+    if flags & LLMP_FLAG_BINCODE == LLMP_FLAG_BINCODE {
+        SerializationFormat::from_id(1)
+    } else if flags & LLMP_FLAG_JSON == LLMP_FLAG_JSON {
+        SerializationFormat::from_id(2)
+    } else {
+        Ok(SerializationFormat::Postcard)
+    }
+}
+
+/// Reassembles the events split into chunks by [`LlmpEventManager`]
+#[derive(Debug, Default)]
+struct ChunkAssembler {
+    /// The events received in part so far, by sender and nonce
+    pending: HashMap<(ClientId, u64), Vec<u8>>,
+    /// The nonce of the last event split into chunks by [`Self::split_with_flags`]
+    nonce: u64,
+}
+
+impl ChunkAssembler {
+    /// Splits `buf` into chunks of at most [`CHUNK_SIZE`] bytes, each prefixed with a header
+    fn split(nonce: u64, buf: &[u8]) -> impl Iterator<Item = Vec<u8>> + '_ {
+        buf.chunks(CHUNK_SIZE).enumerate().map(move |(i, data)| {
+            let mut chunk = Vec::with_capacity(CHUNK_HEADER_LEN + data.len());
+            chunk.extend_from_slice(&nonce.to_le_bytes());
+            chunk.extend_from_slice(&(buf.len() as u64).to_le_bytes());
+            chunk.extend_from_slice(&((i * CHUNK_SIZE) as u64).to_le_bytes());
+            chunk.extend_from_slice(data);
+            chunk
+        })
+    }
+
+    /// The messages to send `buf` with the given `flags` in, split into chunks if it is larger than [`CHUNK_SIZE`]
+    fn split_with_flags(&mut self, flags: Flags, buf: Vec<u8>) -> Vec<(Flags, Vec<u8>)> {
+        if buf.len() <= CHUNK_SIZE {
+            vec![(flags, buf)]
+        } else {
+            self.nonce = self.nonce.wrapping_add(1);
+            Self::split(self.nonce, &buf)
+                .map(|chunk| (flags | LLMP_FLAG_CHUNKED, chunk))
+                .collect()
+        }
+    }
+
+    /// Adds a chunk received from `client_id`, and returns the whole buffer once its last chunk arrived.
+    /// Chunks of a sender arrive in order, an event missing a chunk (e.g., because the sender crashed) is dropped,
+    /// at the latest once the first chunk of the next event of that sender arrives.
+    fn push(&mut self, client_id: ClientId, chunk: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        if chunk.len() < CHUNK_HEADER_LEN {
+            return Err(Error::illegal_argument(format!(
+                "Received a chunk of {} bytes, shorter than its header",
+                chunk.len()
+            )));
+        }
+        let read_u64 = |pos: usize| u64::from_le_bytes(chunk[pos..pos + 8].try_into().unwrap());
+        let (nonce, total_len, offset) = (read_u64(0), read_u64(8) as usize, read_u64(16) as usize);
+        let data = &chunk[CHUNK_HEADER_LEN..];
+
+        if offset == 0 {
+            // A sender sends the chunks of one event after the other, so its unfinished events are lost
+            self.pending.retain(|(id, _), _| *id != client_id);
+            // Grow the buffer as the chunks arrive, instead of trusting the length in the header
+            self.pending.insert(
+                (client_id, nonce),
+                Vec::with_capacity(total_len.min(CHUNK_SIZE)),
+            );
+        }
+        let buf = match self.pending.get_mut(&(client_id, nonce)) {
+            Some(buf) if buf.len() == offset && offset + data.len() <= total_len => buf,
+            _ => {
+                self.pending.remove(&(client_id, nonce));
+                return Ok(None);
+            }
+        };
+        buf.extend_from_slice(data);
+        if buf.len() == total_len {
+            Ok(self.pending.remove(&(client_id, nonce)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+/// A hook on the events arriving in an [`LlmpEventBroker`], added with [`LlmpEventBroker::add_event_hook`],
+/// to log or mirror them, for example to report objectives to a bug tracker.
+/// Events from other brokers reach the hooks as well, from the id of their b2b connection.
+/// The hook may rewrite the event, which is then forwarded to the clients in its new form.
+/// Returning [`BrokerEventResult::Handled`] drops the event: the monitor does not see it,
+/// and it is not forwarded to the clients, not even in part, if it was sent in chunks.
+pub type BrokerEventHookFn<I> = dyn FnMut(u32, &mut Event<I>) -> Result<BrokerEventResult, Error>;
+
+/// A hook on the events of other clients arriving in an [`LlmpEventManager`], added with [`LlmpEventManager::add_event_hook`].
+/// The hook may rewrite the event. Returning [`CustomBufEventResult::Handled`] skips the remaining hooks
+/// and the default processing of the event.
+pub type ClientEventHookFn<S> = dyn FnMut(
+    &mut S,
+    u32,
+    &mut Event<<S as UsesInput>::Input>,
+) -> Result<CustomBufEventResult, Error>;
+
+/// The stats of the clients of a broker, summed up.
+/// Each [`LlmpEventBroker`] periodically sends them to the brokers connected to it via b2b,
+/// which show them in their monitor as one entry per remote broker, next to their own clients.
+/// So one main node connected to the brokers of all machines of a campaign displays its merged stats.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BrokerStats {
+    /// The hostname of the broker
+    pub hostname: String,
+    /// The number of clients that reported stats
+    pub clients: u64,
+    /// The corpus size of all clients
+    pub corpus_size: u64,
+    /// The objectives of all clients
+    pub objective_size: u64,
+    /// The executions of all clients
+    pub executions: u64,
+}
+
+impl BrokerStats {
+    /// Sums up the stats of the own clients in `monitor`, leaving out the entries of the `remote_brokers`
+    fn from_monitor<MT>(monitor: &MT, remote_brokers: &HashMap<ClientId, BrokerStats>) -> Self
+    where
+        MT: Monitor,
+    {
+        #[cfg(feature = "std")]
+        let hostname = hostname::get()
+            .unwrap_or_else(|_| "<unknown>".into())
+            .to_string_lossy()
+            .into();
+        #[cfg(not(feature = "std"))]
+        let hostname = String::new();
+
+        let mut stats = Self {
+            hostname,
+            ..Self::default()
+        };
+        for (client_id, client) in monitor.client_stats().iter().enumerate() {
+            if remote_brokers.contains_key(&(client_id as ClientId))
+                || (client.executions == 0 && client.corpus_size == 0)
+            {
+                continue;
+            }
+            stats.clients += 1;
+            stats.corpus_size += client.corpus_size;
+            stats.objective_size += client.objective_size;
+            stats.executions += client.executions;
+        }
+        stats
+    }
+
+    /// Shows these stats, received from the broker connected as `client_id`, in `monitor`.
+    /// The entry is displayed under the hostname of the broker.
+    fn update_monitor<MT>(&self, monitor: &mut MT, client_id: ClientId)
+    where
+        MT: Monitor,
+    {
+        let client = monitor.client_stats_mut_for(client_id);
+        client.update_corpus_size(self.corpus_size);
+        client.update_objective_size(self.objective_size);
+        client.update_executions(self.executions, current_time());
+        client.update_user_stats("clients".into(), UserStats::Number(self.clients));
+        monitor.display(format!("Broker {}", self.hostname), client_id);
+    }
+}
+
+/// What a broker knows about the liveness of one of its clients
+#[derive(Debug, Clone, Copy, Default)]
+struct ClientLiveness {
+    /// When the last message of this client arrived
+    last_seen: Duration,
+    /// The pid of the client process, if it sent it
+    pid: Option<u32>,
+    /// If the client has been silent for longer than the client timeout
+    hung: bool,
+}
+
 /// An LLMP-backed event manager for scalable multi-processed fuzzing
-#[derive(Debug)]
 pub struct LlmpEventBroker<I, MT, SP>
 where
     I: Input,
@@ -75,9 +343,45 @@ where
     llmp: llmp::LlmpBroker<SP>,
     #[cfg(feature = "llmp_compression")]
     compressor: GzipCompressor,
+    chunks: ChunkAssembler,
+    /// The hooks run on each event, before the monitor
+    hooks: Vec<Box<BrokerEventHookFn<I>>>,
+    /// The last stats received from each broker connected to this one, by the id of its connection
+    remote_brokers: HashMap<ClientId, BrokerStats>,
+    /// The liveness of each client that sent a message so far
+    clients: HashMap<ClientId, ClientLiveness>,
+    /// How long a client may stay silent before it is reported as hung
+    client_timeout: Option<Duration>,
+    /// If hung clients get restarted
+    restart_hung_clients: bool,
+    /// How long a client may stay silent before it is evicted
+    client_evict_timeout: Option<Duration>,
     phantom: PhantomData<I>,
 }
 
+impl<I, MT, SP> core::fmt::Debug for LlmpEventBroker<I, MT, SP>
+where
+    I: Input,
+    SP: ShMemProvider + 'static,
+    MT: Monitor,
+{
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let mut debug_struct = f.debug_struct("LlmpEventBroker");
+        let debug = debug_struct.field("llmp", &self.llmp);
+        #[cfg(feature = "llmp_compression")]
+        let debug = debug.field("compressor", &self.compressor);
+        debug
+            .field("chunks", &self.chunks)
+            .field("hooks", &self.hooks.len())
+            .field("remote_brokers", &self.remote_brokers)
+            .field("clients", &self.clients)
+            .field("client_timeout", &self.client_timeout)
+            .field("restart_hung_clients", &self.restart_hung_clients)
+            .field("client_evict_timeout", &self.client_evict_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<I, MT, SP> LlmpEventBroker<I, MT, SP>
 where
     I: Input,
@@ -91,6 +395,13 @@ where
             llmp,
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
+            chunks: ChunkAssembler::default(),
+            hooks: vec![],
+            remote_brokers: HashMap::default(),
+            clients: HashMap::default(),
+            client_timeout: Some(DEFAULT_CLIENT_TIMEOUT),
+            restart_hung_clients: false,
+            client_evict_timeout: None,
             phantom: PhantomData,
         })
     }
@@ -104,10 +415,85 @@ where
             llmp: llmp::LlmpBroker::create_attach_to_tcp(shmem_provider, port)?,
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
+            chunks: ChunkAssembler::default(),
+            hooks: vec![],
+            remote_brokers: HashMap::default(),
+            clients: HashMap::default(),
+            client_timeout: Some(DEFAULT_CLIENT_TIMEOUT),
+            restart_hung_clients: false,
+            client_evict_timeout: None,
             phantom: PhantomData,
         })
     }
 
+    /// Sets how long a client may stay silent before it is reported as hung, [`DEFAULT_CLIENT_TIMEOUT`] by default.
+    /// Hung clients get the user stat `hung` set to `1`, until they send a message again.
+    /// Clients that did not report any executions yet, for example while they load a large initial corpus, are never hung.
+    /// `None` disables the check.
+    pub fn set_client_timeout(&mut self, client_timeout: Option<Duration>) {
+        self.client_timeout = client_timeout;
+    }
+
+    /// If set, each hung client that sent its pid gets a `SIGUSR2`, on `unix`.
+    /// The in-process executors abort the current run, without recording its input as an objective,
+    /// so the restarter of the client spawns it anew.
+    /// This only restarts clients hung in the target, inside `run_target`: elsewhere, for example in a stage,
+    /// or with other executors, the signal is ignored, and the client stays reported as hung.
+    pub fn set_restart_hung_clients(&mut self, restart_hung_clients: bool) {
+        self.restart_hung_clients = restart_hung_clients;
+    }
+
+    /// Sets how long a client may stay silent before it is evicted, see [`Self::evict_client`].
+    /// `None`, the default, keeps silent clients forever.
+    pub fn set_client_evict_timeout(&mut self, client_evict_timeout: Option<Duration>) {
+        self.client_evict_timeout = client_evict_timeout;
+    }
+
+    /// When the last message of each client arrived, as [`current_time`], sorted by client id.
+    /// Clients send keep-alive messages regularly, so a client is likely gone if it has not been seen for long.
+    #[must_use]
+    pub fn clients_last_seen(&self) -> Vec<(ClientId, Duration)> {
+        let mut last_seen: Vec<(ClientId, Duration)> = self
+            .clients
+            .iter()
+            .map(|(client_id, client)| (*client_id, client.last_seen))
+            .collect();
+        last_seen.sort_unstable();
+        last_seen
+    }
+
+    /// Evicts a client that disappeared: forgets its liveness, resets its stats in the monitor,
+    /// so its stale corpus size and executions no longer count, and releases the page it pinned for its restarts.
+    /// If the client sends a message again, it is tracked anew.
+    /// Returns `false`, if no message of this client arrived so far.
+    pub fn evict_client(&mut self, client_id: ClientId) -> bool {
+        self.llmp.unpin_client_page(client_id);
+        Self::evict(&mut self.monitor, &mut self.clients, client_id)
+    }
+
+    /// The clients that have been silent for longer than the client timeout
+    #[must_use]
+    pub fn hung_clients(&self) -> Vec<ClientId> {
+        let mut hung: Vec<ClientId> = self
+            .clients
+            .iter()
+            .filter(|(_, client)| client.hung)
+            .map(|(client_id, _)| *client_id)
+            .collect();
+        hung.sort_unstable();
+        hung
+    }
+
+    /// Adds a hook, run on each event arriving in this broker, in the order they were added
+    pub fn add_event_hook(&mut self, hook: Box<BrokerEventHookFn<I>>) {
+        self.hooks.push(hook);
+    }
+
+    /// The last [`BrokerStats`] received from each broker connected to this one, by the id of its connection
+    pub fn remote_brokers(&self) -> &HashMap<ClientId, BrokerStats> {
+        &self.remote_brokers
+    }
+
     /// Connect to an llmp broker on the givien address
     #[cfg(feature = "std")]
     pub fn connect_b2b<A>(&mut self, addr: A) -> Result<(), Error>
@@ -117,40 +503,340 @@ where
         self.llmp.connect_b2b(addr)
     }
 
-    /// Run forever in the broker
+    /// Connect to an llmp broker listening on a unix domain socket at the given `path`
+    #[cfg(all(unix, feature = "std"))]
+    pub fn connect_b2b_unix<P>(&mut self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.llmp.connect_b2b_unix(path)
+    }
+
+    /// Connect to an llmp broker listening on `vsock`, for example from inside a VM guest to the host
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    pub fn connect_b2b_vsock(&mut self, cid: u32, port: u32) -> Result<(), Error> {
+        self.llmp.connect_b2b_vsock(cid, port)
+    }
+
+    /// Listen for clients and brokers on a unix domain socket at the given `path`, next to `tcp`
+    #[cfg(all(unix, feature = "std"))]
+    pub fn launch_unix_listener_on<P>(&mut self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        self.llmp.launch_unix_listener_on(path)?;
+        Ok(())
+    }
+
+    /// Listen for brokers in VM guests on `vsock`, next to `tcp`
+    #[cfg(all(target_os = "linux", feature = "std"))]
+    pub fn launch_vsock_listener_on(&mut self, port: u32) -> Result<(), Error> {
+        self.llmp.launch_vsock_listener_on(port)?;
+        Ok(())
+    }
+
+    /// Run forever in the broker.
+    /// The chunks of large events are held back until the whole event arrived, and are then forwarded together,
+    /// unless a hook dropped the event.
+    /// Events that originated from other brokers reach the hooks, but not the monitor:
+    /// the stats of the other brokers only arrive as their [`BrokerStats`].
+    /// Every [`BROKER_STATS_INTERVAL`], the [`BrokerStats`] of the own clients are sent to the connected brokers,
+    /// and the clients that stayed silent for too long are reported as hung, or evicted.
+    #[allow(clippy::too_many_lines)]
     pub fn broker_loop(&mut self) -> Result<(), Error> {
-        let monitor = &mut self.monitor;
+        let monitor = RefCell::new(&mut self.monitor);
+        let remote_brokers = RefCell::new(&mut self.remote_brokers);
+        let clients = RefCell::new(&mut self.clients);
+        let client_timeout = self.client_timeout;
+        let restart_hung_clients = self.restart_hung_clients;
+        let client_evict_timeout = self.client_evict_timeout;
+        let chunks = &mut self.chunks;
+        let hooks = &mut self.hooks;
         #[cfg(feature = "llmp_compression")]
         let compressor = &self.compressor;
-        self.llmp.loop_forever(
-            &mut |client_id: u32, tag: Tag, _flags: Flags, msg: &[u8]| {
-                if tag == LLMP_TAG_EVENT_TO_BOTH {
-                    #[cfg(not(feature = "llmp_compression"))]
-                    let event_bytes = msg;
-                    #[cfg(feature = "llmp_compression")]
-                    let compressed;
-                    #[cfg(feature = "llmp_compression")]
-                    let event_bytes = if _flags & LLMP_FLAG_COMPRESSED == LLMP_FLAG_COMPRESSED {
-                        compressed = compressor.decompress(msg)?;
-                        &compressed
-                    } else {
-                        msg
-                    };
-                    let event: Event<I> = postcard::from_bytes(event_bytes)?;
-                    match Self::handle_in_broker(monitor, client_id, &event)? {
-                        BrokerEventResult::Forward => Ok(llmp::LlmpMsgHookResult::ForwardToClients),
-                        BrokerEventResult::Handled => Ok(llmp::LlmpMsgHookResult::Handled),
+        let mut last_stats_time = current_time();
+        // The self test messages to echo back to their clients, once the broker may send again
+        let self_test_echoes = RefCell::new(vec![]);
+        self.llmp.loop_forever_with_idle(
+            &mut |client_id: u32, tag: Tag, flags: Flags, msg: &[u8]| {
+                if tag == llmp::LLMP_TAG_EXITING {
+                    // The client is gone on purpose, it is not hung
+                    clients.borrow_mut().remove(&client_id);
+                    return Ok(llmp::LlmpMsgHookResult::Handled);
+                }
+                // Messages from other brokers, and their connections, say nothing about our own clients
+                if flags & LLMP_FLAG_FROM_B2B != LLMP_FLAG_FROM_B2B {
+                    Self::client_alive(
+                        *monitor.borrow_mut(),
+                        *clients.borrow_mut(),
+                        client_id,
+                        current_time(),
+                    );
+                }
+                if tag == LLMP_TAG_KEEP_ALIVE {
+                    // Only there to mark the client alive, which happened above
+                    Ok(llmp::LlmpMsgHookResult::Handled)
+                } else if tag == LLMP_TAG_CLIENT_PID {
+                    if let Ok(pid) = msg.try_into().map(u32::from_le_bytes) {
+                        if let Some(client) = clients.borrow_mut().get_mut(&client_id) {
+                            client.pid = Some(pid);
+                        }
+                    }
+                    Ok(llmp::LlmpMsgHookResult::Handled)
+                } else if tag == LLMP_TAG_BROKER_STATS {
+                    // Our own clients ignore them, and they do not reach other brokers twice
+                    if flags & LLMP_FLAG_FROM_B2B == LLMP_FLAG_FROM_B2B {
+                        let stats: BrokerStats = postcard::from_bytes(msg)?;
+                        stats.update_monitor(*monitor.borrow_mut(), client_id);
+                        remote_brokers.borrow_mut().insert(client_id, stats);
                     }
+                    Ok(llmp::LlmpMsgHookResult::Handled)
+                } else if tag == LLMP_TAG_SELF_TEST {
+                    // Only the broker of the testing client echoes, other brokers drop the message.
+                    // The client recognizes its own messages by the sender id they start with.
+                    if flags & LLMP_FLAG_FROM_B2B != LLMP_FLAG_FROM_B2B {
+                        self_test_echoes.borrow_mut().push(msg.to_vec());
+                    }
+                    Ok(llmp::LlmpMsgHookResult::Handled)
+                } else if is_event_tag(tag) {
+                    Self::handle_event_msg(
+                        *monitor.borrow_mut(),
+                        hooks,
+                        chunks,
+                        #[cfg(feature = "llmp_compression")]
+                        compressor,
+                        client_id,
+                        flags,
+                        msg,
+                    )
                 } else {
                     Ok(llmp::LlmpMsgHookResult::ForwardToClients)
                 }
             },
+            &mut |broker| {
+                for msg in self_test_echoes.borrow_mut().drain(..) {
+                    broker.send_buf(LLMP_TAG_SELF_TEST, &msg)?;
+                }
+                let now = current_time();
+                if now.checked_sub(last_stats_time).unwrap_or_default() >= BROKER_STATS_INTERVAL {
+                    last_stats_time = now;
+                    let stats =
+                        BrokerStats::from_monitor(*monitor.borrow(), *remote_brokers.borrow());
+                    broker.send_buf(LLMP_TAG_BROKER_STATS, &postcard::to_allocvec(&stats)?)?;
+                    if let Some(client_timeout) = client_timeout {
+                        Self::check_clients(
+                            *monitor.borrow_mut(),
+                            *clients.borrow_mut(),
+                            client_timeout,
+                            restart_hung_clients,
+                            now,
+                        );
+                    }
+                    if let Some(client_evict_timeout) = client_evict_timeout {
+                        for client_id in Self::evict_silent_clients(
+                            *monitor.borrow_mut(),
+                            *clients.borrow_mut(),
+                            client_evict_timeout,
+                            now,
+                        ) {
+                            broker.unpin_client_page(client_id);
+                        }
+                    }
+                }
+                Ok(())
+            },
             Some(Duration::from_millis(5)),
         );
 
         Ok(())
     }
 
+    /// Handles the message of an event arriving from `client_id`: runs the hooks on it, updates the stats,
+    /// and decides how to forward it.
+    /// Chunks are held back until the event is complete. The event is then forwarded in one go,
+    /// re-encoded in the same format, if a hook rewrote it.
+    #[allow(clippy::too_many_arguments)]
+    fn handle_event_msg(
+        monitor: &mut MT,
+        hooks: &mut [Box<BrokerEventHookFn<I>>],
+        chunks: &mut ChunkAssembler,
+        #[cfg(feature = "llmp_compression")] compressor: &GzipCompressor,
+        client_id: ClientId,
+        flags: Flags,
+        msg: &[u8],
+    ) -> Result<llmp::LlmpMsgHookResult, Error> {
+        let from_b2b = flags & LLMP_FLAG_FROM_B2B == LLMP_FLAG_FROM_B2B;
+        if from_b2b && hooks.is_empty() {
+            // Nothing to do: the stats for this event are tracked by the broker it originated from.
+            return Ok(llmp::LlmpMsgHookResult::ForwardToClients);
+        }
+        let chunked = flags & LLMP_FLAG_CHUNKED == LLMP_FLAG_CHUNKED;
+        let assembled;
+        let msg = if chunked {
+            match chunks.push(client_id, msg)? {
+                Some(buf) => {
+                    assembled = buf;
+                    &assembled
+                }
+                // The chunks are forwarded together, once the event is complete
+                None => return Ok(llmp::LlmpMsgHookResult::Handled),
+            }
+        } else {
+            msg
+        };
+        #[cfg(not(feature = "llmp_compression"))]
+        let event_bytes = msg;
+        #[cfg(feature = "llmp_compression")]
+        let compressed;
+        #[cfg(feature = "llmp_compression")]
+        let event_bytes = if flags & LLMP_FLAG_COMPRESSED == LLMP_FLAG_COMPRESSED {
+            compressed = compressor.decompress(msg)?;
+            &compressed
+        } else {
+            msg
+        };
+        let format = format_from_flags(flags)?;
+        let mut event: Event<I> = format.deserialize(event_bytes)?;
+        for hook in hooks.iter_mut() {
+            if let BrokerEventResult::Handled = hook(client_id, &mut event)? {
+                return Ok(llmp::LlmpMsgHookResult::Handled);
+            }
+        }
+        if !from_b2b {
+            if let BrokerEventResult::Handled = Self::handle_in_broker(monitor, client_id, &event)?
+            {
+                return Ok(llmp::LlmpMsgHookResult::Handled);
+            }
+        }
+        let rewritten = if hooks.is_empty() {
+            None
+        } else {
+            // Only re-encode the events the hooks rewrote
+            Some(format.serialize(&event)?).filter(|buf| buf != event_bytes)
+        };
+        let (flags, buf) = match rewritten {
+            #[cfg(feature = "llmp_compression")]
+            Some(event_bytes) => {
+                let flags = flags & !(LLMP_FLAG_CHUNKED | LLMP_FLAG_COMPRESSED);
+                match compressor.compress(&event_bytes)? {
+                    Some(comp_buf) => (flags | LLMP_FLAG_COMPRESSED, comp_buf),
+                    None => (flags, event_bytes),
+                }
+            }
+            #[cfg(not(feature = "llmp_compression"))]
+            Some(event_bytes) => (flags & !LLMP_FLAG_CHUNKED, event_bytes),
+            None if chunked => (flags & !LLMP_FLAG_CHUNKED, msg.to_vec()),
+            None => return Ok(llmp::LlmpMsgHookResult::ForwardToClients),
+        };
+        Ok(llmp::LlmpMsgHookResult::ReplaceWith(
+            chunks.split_with_flags(flags, buf),
+        ))
+    }
+
+    /// Notes that `client_id` sent a message at `now`, and reports it if it was hung before
+    fn client_alive(
+        monitor: &mut MT,
+        clients: &mut HashMap<ClientId, ClientLiveness>,
+        client_id: ClientId,
+        now: Duration,
+    ) {
+        let client = clients.entry(client_id).or_default();
+        client.last_seen = now;
+        if client.hung {
+            client.hung = false;
+            #[cfg(feature = "std")]
+            println!("[ALIVE] client {client_id} is back");
+            monitor
+                .client_stats_mut_for(client_id)
+                .update_user_stats("hung".into(), UserStats::Number(0));
+        }
+    }
+
+    /// Reports the clients that have been silent for longer than `client_timeout` as hung,
+    /// and restarts them, if `restart` is set and they sent their pid.
+    /// Clients that did not report any executions yet are still starting up, and are skipped.
+    fn check_clients(
+        monitor: &mut MT,
+        clients: &mut HashMap<ClientId, ClientLiveness>,
+        client_timeout: Duration,
+        restart: bool,
+        now: Duration,
+    ) {
+        for (client_id, client) in clients.iter_mut() {
+            let silent = now.checked_sub(client.last_seen).unwrap_or_default();
+            let started = monitor
+                .client_stats()
+                .get(*client_id as usize)
+                .map_or(false, |stats| stats.executions > 0);
+            if client.hung || !started || silent <= client_timeout {
+                continue;
+            }
+            client.hung = true;
+            #[cfg(feature = "std")]
+            println!(
+                "[HUNG] client {client_id}: no message for {}s",
+                silent.as_secs()
+            );
+            monitor
+                .client_stats_mut_for(*client_id)
+                .update_user_stats("hung".into(), UserStats::Number(1));
+            monitor.display("Hung".into(), *client_id);
+
+            #[cfg(unix)]
+            if let (true, Some(pid)) = (restart, client.pid) {
+                #[cfg(feature = "std")]
+                println!("[HUNG] restarting client {client_id} (pid {pid})");
+                #[allow(clippy::cast_possible_wrap)]
+                unsafe {
+                    libc::kill(pid as libc::pid_t, libc::SIGUSR2);
+                }
+            }
+        }
+        #[cfg(not(unix))]
+        let _ = restart;
+    }
+
+    /// Forgets the liveness of `client_id`, and resets its stats in the monitor
+    fn evict(
+        monitor: &mut MT,
+        clients: &mut HashMap<ClientId, ClientLiveness>,
+        client_id: ClientId,
+    ) -> bool {
+        if let Some(stats) = monitor.client_stats_mut().get_mut(client_id as usize) {
+            *stats = ClientStats {
+                last_window_time: current_time(),
+                ..ClientStats::default()
+            };
+        }
+        clients.remove(&client_id).is_some()
+    }
+
+    /// Evicts the clients that have been silent for longer than `client_evict_timeout`, and returns them
+    fn evict_silent_clients(
+        monitor: &mut MT,
+        clients: &mut HashMap<ClientId, ClientLiveness>,
+        client_evict_timeout: Duration,
+        now: Duration,
+    ) -> Vec<ClientId> {
+        let silent_clients: Vec<ClientId> = clients
+            .iter()
+            .filter(|(_, client)| {
+                now.checked_sub(client.last_seen).unwrap_or_default() > client_evict_timeout
+            })
+            .map(|(client_id, _)| *client_id)
+            .collect();
+        for client_id in &silent_clients {
+            #[cfg(feature = "std")]
+            println!(
+                "[EVICTED] client {client_id}: no message for more than {}s",
+                client_evict_timeout.as_secs()
+            );
+            Self::evict(monitor, clients, *client_id);
+            monitor.display("Evicted".into(), *client_id);
+        }
+        silent_clients
+    }
+
     /// Handle arriving events in the broker
     #[allow(clippy::unnecessary_wraps)]
     fn handle_in_broker(
@@ -236,6 +922,19 @@ where
                 println!("[LOG {severity_level}]: {message}");
                 Ok(BrokerEventResult::Handled)
             }
+            Event::ClientDescription { description } => {
+                #[cfg(feature = "std")]
+                println!("[DESCRIPTION] client {client_id}: {description}");
+                let client = monitor.client_stats_mut_for(client_id);
+                client.update_description((**description).clone());
+                Ok(BrokerEventResult::Handled)
+            }
+            #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+            Event::HarnessInitFailed { attempt, message } => {
+                #[cfg(feature = "std")]
+                println!("[HARNESS INIT FAILED] client {client_id}, attempt {attempt}: {message}");
+                Ok(BrokerEventResult::Handled)
+            }
             Event::CustomBuf { .. } => Ok(BrokerEventResult::Forward),
             //_ => Ok(BrokerEventResult::Forward),
         }
@@ -252,12 +951,56 @@ where
     llmp: LlmpClient<SP>,
     /// The custom buf handler
     custom_buf_handlers: Vec<Box<CustomBufHandlerFn<S>>>,
+    /// The hooks run on each event of other clients, before processing it
+    event_hooks: Vec<Box<ClientEventHookFn<S>>>,
     #[cfg(feature = "llmp_compression")]
     compressor: GzipCompressor,
     configuration: EventConfig,
+    /// Messages of other clients that arrived during [`Self::self_test`], processed next
+    backlog: Vec<(ClientId, Tag, Flags, Vec<u8>)>,
+    /// The events of other clients received in part
+    chunks: ChunkAssembler,
+    /// The nonce of the last event sent in chunks
+    chunk_nonce: u64,
+    /// Reused by each serialization, so it does not need to grow a new buffer each time
+    serialization_buf: Vec<u8>,
+    /// Reused by each [`EventFirer::serialize_observers`], handed back by [`EventFirer::fire`] with the event
+    observers_buf: Vec<u8>,
+    /// The format the events are serialized in
+    serialization_format: SerializationFormat,
+    /// When this client last sent a keep-alive message to the broker
+    last_keep_alive: Duration,
     phantom: PhantomData<S>,
 }
 
+/// The result of [`LlmpEventManager::self_test`], for one message size
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct LlmpSelfTestResult {
+    /// The size of each message
+    pub msg_size: usize,
+    /// The number of messages sent, and received back
+    pub msgs: usize,
+    /// The average time for a message to travel through the broker and back
+    pub latency: Duration,
+    /// The throughput, in bytes per second
+    pub throughput: f64,
+}
+
+#[cfg(feature = "std")]
+impl core::fmt::Display for LlmpSelfTestResult {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} msgs of {} bytes: latency {:?}, throughput {:.2} MiB/s",
+            self.msgs,
+            self.msg_size,
+            self.latency,
+            self.throughput / (1024.0 * 1024.0)
+        )
+    }
+}
+
 impl<S, SP> core::fmt::Debug for LlmpEventManager<S, SP>
 where
     SP: ShMemProvider + 'static,
@@ -299,8 +1042,16 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            backlog: vec![],
+            chunks: ChunkAssembler::default(),
+            chunk_nonce: current_nanos(),
+            serialization_buf: vec![],
+            observers_buf: vec![],
+            serialization_format: SerializationFormat::default(),
             phantom: PhantomData,
             custom_buf_handlers: vec![],
+            event_hooks: vec![],
+            last_keep_alive: current_time(),
         })
     }
 
@@ -318,11 +1069,54 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            backlog: vec![],
+            chunks: ChunkAssembler::default(),
+            chunk_nonce: current_nanos(),
+            serialization_buf: vec![],
+            observers_buf: vec![],
+            serialization_format: SerializationFormat::default(),
             phantom: PhantomData,
             custom_buf_handlers: vec![],
+            event_hooks: vec![],
+            last_keep_alive: current_time(),
         })
     }
 
+    /// Create an llmp client for the broker listening on a unix domain socket at `path`,
+    /// see [`LlmpClient::create_attach_to_unix`].
+    #[cfg(all(unix, feature = "std"))]
+    pub fn new_on_unix<P>(
+        shmem_provider: SP,
+        path: P,
+        configuration: EventConfig,
+    ) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Self::new(
+            LlmpClient::create_attach_to_unix(shmem_provider, path)?,
+            configuration,
+        )
+    }
+
+    /// Create an llmp client for a broker running on a remote machine, at `addr`.
+    /// This spawns a local relay broker (see [`llmp::LlmpBroker::launch_tcp_relay`]) that forwards
+    /// all messages via tcp, reconnecting if the connection drops, and attaches to it.
+    /// The relay runs in a thread of the calling process. To keep it across restarts of the fuzzer,
+    /// use a [`RestartingMgr`] of [`ManagerKind::Client`] with a `remote_broker_addr`, so the relay lives in the restarter.
+    #[cfg(feature = "std")]
+    pub fn new_on_tcp<A>(
+        shmem_provider: SP,
+        addr: A,
+        configuration: EventConfig,
+    ) -> Result<Self, Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let port = llmp::LlmpBroker::<SP>::launch_tcp_relay(addr)?;
+        Self::new_on_port(shmem_provider, port, configuration)
+    }
+
     /// If a client respawns, it may reuse the existing connection, previously stored by [`LlmpClient::to_env()`].
     #[cfg(feature = "std")]
     pub fn existing_client_from_env(
@@ -335,13 +1129,21 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            backlog: vec![],
+            chunks: ChunkAssembler::default(),
+            chunk_nonce: current_nanos(),
+            serialization_buf: vec![],
+            observers_buf: vec![],
+            serialization_format: SerializationFormat::default(),
             phantom: PhantomData,
             custom_buf_handlers: vec![],
+            event_hooks: vec![],
+            last_keep_alive: current_time(),
         })
     }
 
     /// Describe the client event mgr's llmp parts in a restorable fashion
-    pub fn describe(&self) -> Result<LlmpClientDescription, Error> {
+    pub fn describe(&mut self) -> Result<LlmpClientDescription, Error> {
         self.llmp.describe()
     }
 
@@ -356,8 +1158,16 @@ where
             #[cfg(feature = "llmp_compression")]
             compressor: GzipCompressor::new(COMPRESS_THRESHOLD),
             configuration,
+            backlog: vec![],
+            chunks: ChunkAssembler::default(),
+            chunk_nonce: current_nanos(),
+            serialization_buf: vec![],
+            observers_buf: vec![],
+            serialization_format: SerializationFormat::default(),
             phantom: PhantomData,
             custom_buf_handlers: vec![],
+            event_hooks: vec![],
+            last_keep_alive: current_time(),
         })
     }
 
@@ -367,6 +1177,123 @@ where
         self.llmp.to_env(env_name).unwrap();
     }
 
+    /// Sends test messages of varying sizes to the broker, and waits for it to echo them back.
+    /// Reports the latency and throughput for each message size.
+    ///
+    /// Call this at startup, to catch misconfigured shared memory (permissions, size limits)
+    /// before the campaign silently underperforms.
+    /// Messages from other clients that arrive in the meantime are processed later.
+    #[cfg(feature = "std")]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn self_test(&mut self) -> Result<Vec<LlmpSelfTestResult>, Error> {
+        let self_id = self.llmp.sender.id;
+        let mut results = vec![];
+
+        let self_prefix = self_id.to_le_bytes();
+
+        for msg_size in SELF_TEST_MSG_SIZES {
+            // The id of this client, the sequence number, and the padding
+            let mut buf = vec![0xA5_u8; msg_size];
+            buf[..4].copy_from_slice(&self_prefix);
+            let start = current_time();
+            let mut sent_at = vec![];
+            for seq in 0..SELF_TEST_MSGS_PER_SIZE {
+                buf[4..12].copy_from_slice(&(seq as u64).to_le_bytes());
+                sent_at.push(current_time());
+                self.llmp.send_buf(LLMP_TAG_SELF_TEST, &buf)?;
+            }
+
+            let mut received = 0;
+            let mut total_latency = Duration::ZERO;
+            while received < SELF_TEST_MSGS_PER_SIZE {
+                if current_time() - start > SELF_TEST_TIMEOUT {
+                    return Err(Error::unknown(format!(
+                        "Self test timed out: only {received} of {SELF_TEST_MSGS_PER_SIZE} messages of {msg_size} bytes came back. Is the broker running?"
+                    )));
+                }
+                match self.llmp.recv_buf_with_flags()? {
+                    Some((_, LLMP_TAG_SELF_TEST, _, msg)) if msg.get(..4) == Some(&self_prefix) => {
+                        if msg.len() != msg_size {
+                            return Err(Error::illegal_state(format!(
+                                "Self test message of {msg_size} bytes got corrupted"
+                            )));
+                        }
+                        let seq = u64::from_le_bytes(msg[4..12].try_into()?) as usize;
+                        if seq >= SELF_TEST_MSGS_PER_SIZE || msg[12..].iter().any(|&b| b != 0xA5) {
+                            return Err(Error::illegal_state(format!(
+                                "Self test message of {msg_size} bytes got corrupted"
+                            )));
+                        }
+                        total_latency += current_time() - sent_at[seq];
+                        received += 1;
+                    }
+                    Some((_, LLMP_TAG_SELF_TEST, _, _)) => (),
+                    Some((client_id, tag, flags, msg)) => {
+                        self.backlog.push((client_id, tag, flags, msg.to_vec()));
+                    }
+                    None => std::thread::yield_now(),
+                }
+            }
+
+            let elapsed = current_time() - start;
+            results.push(LlmpSelfTestResult {
+                msg_size,
+                msgs: received,
+                latency: total_latency / received as u32,
+                throughput: (msg_size * received) as f64 / elapsed.as_secs_f64(),
+            });
+        }
+        Ok(results)
+    }
+
+    /// Decodes an event that arrived from `client_id`, reassembling chunks and decompressing it if needed.
+    /// Returns `None` for messages that are no events for this client, and for chunks of incomplete events.
+    #[allow(clippy::type_complexity)]
+    fn decode_event(
+        chunks: &mut ChunkAssembler,
+        #[cfg(feature = "llmp_compression")] compressor: &GzipCompressor,
+        self_id: ClientId,
+        client_id: ClientId,
+        tag: Tag,
+        flags: Flags,
+        msg: &[u8],
+    ) -> Result<Option<(SerializationFormat, Event<S::Input>)>, Error> {
+        assert!(
+            tag != _LLMP_TAG_EVENT_TO_BROKER,
+            "EVENT_TO_BROKER parcel should not have arrived in the client!"
+        );
+
+        if client_id == self_id || tag == LLMP_TAG_SELF_TEST || tag == LLMP_TAG_BROKER_STATS {
+            return Ok(None);
+        }
+        let assembled;
+        let msg = if flags & LLMP_FLAG_CHUNKED == LLMP_FLAG_CHUNKED {
+            match chunks.push(client_id, msg)? {
+                Some(buf) => {
+                    assembled = buf;
+                    &assembled
+                }
+                None => return Ok(None),
+            }
+        } else {
+            msg
+        };
+        #[cfg(not(feature = "llmp_compression"))]
+        let event_bytes = msg;
+        #[cfg(feature = "llmp_compression")]
+        let compressed;
+        #[cfg(feature = "llmp_compression")]
+        let event_bytes = if flags & LLMP_FLAG_COMPRESSED == LLMP_FLAG_COMPRESSED {
+            compressed = compressor.decompress(msg)?;
+            &compressed
+        } else {
+            msg
+        };
+        let format = format_from_flags(flags)?;
+        let event = format.deserialize(event_bytes)?;
+        Ok(Some((format, event)))
+    }
+
     // Handle arriving events in the client
     #[allow(clippy::unused_self)]
     fn handle_in_client<E, Z>(
@@ -374,14 +1301,21 @@ where
         fuzzer: &mut Z,
         executor: &mut E,
         state: &mut S,
-        _client_id: u32,
-        event: Event<S::Input>,
+        client_id: u32,
+        format: SerializationFormat,
+        mut event: Event<S::Input>,
     ) -> Result<(), Error>
     where
+        S: HasMetadata,
         E: Executor<Self, Z> + HasObservers<State = S>,
         for<'a> E::Observers: Deserialize<'a>,
         Z: ExecutionProcessor<E::Observers, State = S> + EvaluatorObservers<E::Observers>,
     {
+        for hook in &mut self.event_hooks {
+            if hook(state, client_id, &mut event)? == CustomBufEventResult::Handled {
+                return Ok(());
+            }
+        }
         match event {
             Event::NewTestcase {
                 input,
@@ -395,14 +1329,23 @@ where
                 #[cfg(feature = "std")]
                 println!(
                     "Received new Testcase from {} ({:?})",
-                    _client_id, client_config
+                    client_id, client_config
                 );
 
-                let _res = if client_config.match_with(&self.configuration)
-                    && observers_buf.is_some()
-                {
-                    let observers: E::Observers =
-                        postcard::from_bytes(observers_buf.as_ref().unwrap())?;
+                if let Some(meta) = state.metadata_mut().get_mut::<CrossClientInputsMetadata>() {
+                    meta.push(self.serialization_format, &input)?;
+                }
+
+                // Only a client with the same configuration runs the same observers.
+                // If they still do not deserialize, execute the input locally instead of failing.
+                let observers = if client_config.match_with(&self.configuration) {
+                    observers_buf
+                        .as_ref()
+                        .and_then(|buf| format.deserialize::<E::Observers>(buf).ok())
+                } else {
+                    None
+                };
+                let _res = if let Some(observers) = observers {
                     fuzzer.process_execution(state, self, input, &observers, &exit_kind, false)?
                 } else {
                     fuzzer.evaluate_input_with_observers::<E, Self>(
@@ -450,22 +1393,17 @@ where
         _state: &mut Self::State,
         event: Event<<Self::State as UsesInput>::Input>,
     ) -> Result<(), Error> {
-        let serialized = postcard::to_allocvec(&event)?;
-        let flags: Flags = LLMP_FLAG_INITIALIZED;
-
-        match self.compressor.compress(&serialized)? {
-            Some(comp_buf) => {
-                self.llmp.send_buf_with_flags(
-                    LLMP_TAG_EVENT_TO_BOTH,
-                    flags | LLMP_FLAG_COMPRESSED,
-                    &comp_buf,
-                )?;
-            }
-            None => {
-                self.llmp.send_buf(LLMP_TAG_EVENT_TO_BOTH, &serialized)?;
-            }
-        }
-        Ok(())
+        let tag = event_tag(&event);
+        let serialized = self.serialize_to_buf(&event)?;
+        let flags: Flags = LLMP_FLAG_INITIALIZED | format_flags(self.serialization_format);
+
+        let res = match self.compressor.compress(&serialized)? {
+            Some(comp_buf) => self.send_event_buf(tag, flags | LLMP_FLAG_COMPRESSED, &comp_buf),
+            None => self.send_event_buf(tag, flags, &serialized),
+        };
+        self.serialization_buf = serialized;
+        self.recycle_observers_buf(event);
+        res
     }
 
     #[cfg(not(feature = "llmp_compression"))]
@@ -474,9 +1412,22 @@ where
         _state: &mut Self::State,
         event: Event<<Self::State as UsesInput>::Input>,
     ) -> Result<(), Error> {
-        let serialized = postcard::to_allocvec(&event)?;
-        self.llmp.send_buf(LLMP_TAG_EVENT_TO_BOTH, &serialized)?;
-        Ok(())
+        let tag = event_tag(&event);
+        let serialized = self.serialize_to_buf(&event)?;
+        let flags: Flags = LLMP_FLAG_INITIALIZED | format_flags(self.serialization_format);
+        let res = self.send_event_buf(tag, flags, &serialized);
+        self.serialization_buf = serialized;
+        self.recycle_observers_buf(event);
+        res
+    }
+
+    fn serialize_observers<OT>(&mut self, observers: &OT) -> Result<Vec<u8>, Error>
+    where
+        OT: ObserversTuple<Self::State> + Serialize,
+    {
+        let mut buf = core::mem::take(&mut self.observers_buf);
+        buf.clear();
+        self.serialization_format.serialize_into(observers, buf)
     }
 
     fn configuration(&self) -> EventConfig {
@@ -484,6 +1435,95 @@ where
     }
 }
 
+impl<S, SP> LlmpEventManager<S, SP>
+where
+    S: UsesInput,
+    SP: ShMemProvider,
+{
+    /// Serializes `value` into the reused serialization buffer, which is taken out of `self`.
+    /// Hand it back once done, so the next serialization does not need to allocate.
+    fn serialize_to_buf<T>(&mut self, value: &T) -> Result<Vec<u8>, Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let mut buf = core::mem::take(&mut self.serialization_buf);
+        buf.clear();
+        self.serialization_format.serialize_into(value, buf)
+    }
+
+    /// Takes back the observers buffer of a sent event, for the next [`EventFirer::serialize_observers`]
+    fn recycle_observers_buf(&mut self, event: Event<S::Input>) {
+        if let Event::NewTestcase {
+            observers_buf: Some(buf),
+            ..
+        } = event
+        {
+            self.observers_buf = buf;
+        }
+    }
+
+    /// Adds a hook, run on each event of other clients arriving in this manager, in the order they were added
+    pub fn add_event_hook(&mut self, hook: Box<ClientEventHookFn<S>>) {
+        self.event_hooks.push(hook);
+    }
+
+    /// Sends the pid of this process to the broker, so that it can restart this client if it hangs
+    #[cfg(feature = "std")]
+    fn send_pid(&mut self) -> Result<(), Error> {
+        self.llmp
+            .send_buf(LLMP_TAG_CLIENT_PID, &std::process::id().to_le_bytes())
+    }
+
+    /// Sends a keep-alive message to the broker every [`KEEP_ALIVE_INTERVAL`],
+    /// so that it knows this client is alive, even if it has no progress to report for a while
+    fn maybe_send_keep_alive(&mut self) -> Result<(), Error> {
+        let now = current_time();
+        if now.checked_sub(self.last_keep_alive).unwrap_or_default() >= KEEP_ALIVE_INTERVAL {
+            self.last_keep_alive = now;
+            self.llmp.send_buf(LLMP_TAG_KEEP_ALIVE, &[])?;
+        }
+        Ok(())
+    }
+
+    /// The format the events of this manager are serialized in
+    #[must_use]
+    pub fn serialization_format(&self) -> SerializationFormat {
+        self.serialization_format
+    }
+
+    /// Sets the format the events of this manager are serialized in.
+    /// Each event is marked with its format, so clients with different formats can still talk to each other.
+    pub fn set_serialization_format(&mut self, format: SerializationFormat) {
+        self.serialization_format = format;
+    }
+
+    /// Sends a serialized event with the given `tag`, split into chunks if it is larger than [`CHUNK_SIZE`]
+    fn send_event_buf(&mut self, tag: Tag, flags: Flags, buf: &[u8]) -> Result<(), Error> {
+        if buf.len() <= CHUNK_SIZE {
+            return self.llmp.send_buf_with_flags(tag, flags, buf);
+        }
+        self.chunk_nonce = self.chunk_nonce.wrapping_add(1);
+        for chunk in ChunkAssembler::split(self.chunk_nonce, buf) {
+            self.llmp
+                .send_buf_with_flags(tag, flags | LLMP_FLAG_CHUNKED, &chunk)?;
+        }
+        Ok(())
+    }
+
+    /// Only process the events with one of the given `tags` from now on, such as [`LLMP_TAG_EVENT_OBJECTIVE`],
+    /// next to the ones subscribed to before.
+    /// The messages of other events are skipped without deserializing them,
+    /// so a secondary consumer, such as a logger, does not need to handle all new testcases.
+    pub fn subscribe(&mut self, tags: &[Tag]) {
+        self.llmp.subscribe(tags);
+    }
+
+    /// Process all events again
+    pub fn clear_subscriptions(&mut self) {
+        self.llmp.clear_subscriptions();
+    }
+}
+
 impl<S, SP> EventRestarter for LlmpEventManager<S, SP>
 where
     S: UsesInput,
@@ -495,11 +1535,16 @@ where
         // wait until we can drop the message safely.
         self.llmp.await_safe_to_unmap_blocking();
     }
+
+    /// Tells the broker to forget this client, instead of reporting it as hung
+    fn send_exiting(&mut self) -> Result<(), Error> {
+        self.llmp.send_exiting()
+    }
 }
 
 impl<E, S, SP, Z> EventProcessor<E, Z> for LlmpEventManager<S, SP>
 where
-    S: UsesInput + HasClientPerfMonitor + HasExecutions,
+    S: UsesInput + HasClientPerfMonitor + HasExecutions + HasMetadata,
     SP: ShMemProvider,
     E: HasObservers<State = S> + Executor<Self, Z>,
     for<'a> E::Observers: Deserialize<'a>,
@@ -511,32 +1556,42 @@ where
         state: &mut Self::State,
         executor: &mut E,
     ) -> Result<usize, Error> {
+        self.maybe_send_keep_alive()?;
         // TODO: Get around local event copy by moving handle_in_client
         let self_id = self.llmp.sender.id;
         let mut count = 0;
-        while let Some((client_id, tag, _flags, msg)) = self.llmp.recv_buf_with_flags()? {
-            assert!(
-                tag != _LLMP_TAG_EVENT_TO_BROKER,
-                "EVENT_TO_BROKER parcel should not have arrived in the client!"
-            );
-
-            if client_id == self_id {
-                continue;
+        // First, the messages that arrived during a self test
+        for (client_id, tag, flags, msg) in core::mem::take(&mut self.backlog) {
+            let decoded = Self::decode_event(
+                &mut self.chunks,
+                #[cfg(feature = "llmp_compression")]
+                &self.compressor,
+                self_id,
+                client_id,
+                tag,
+                flags,
+                &msg,
+            )?;
+            if let Some((format, event)) = decoded {
+                self.handle_in_client(fuzzer, executor, state, client_id, format, event)?;
+                count += 1;
+            }
+        }
+        while let Some((client_id, tag, flags, msg)) = self.llmp.recv_buf_with_flags()? {
+            let decoded = Self::decode_event(
+                &mut self.chunks,
+                #[cfg(feature = "llmp_compression")]
+                &self.compressor,
+                self_id,
+                client_id,
+                tag,
+                flags,
+                msg,
+            )?;
+            if let Some((format, event)) = decoded {
+                self.handle_in_client(fuzzer, executor, state, client_id, format, event)?;
+                count += 1;
             }
-            #[cfg(not(feature = "llmp_compression"))]
-            let event_bytes = msg;
-            #[cfg(feature = "llmp_compression")]
-            let compressed;
-            #[cfg(feature = "llmp_compression")]
-            let event_bytes = if _flags & LLMP_FLAG_COMPRESSED == LLMP_FLAG_COMPRESSED {
-                compressed = self.compressor.decompress(msg)?;
-                &compressed
-            } else {
-                msg
-            };
-            let event: Event<S::Input> = postcard::from_bytes(event_bytes)?;
-            self.handle_in_client(fuzzer, executor, state, client_id, event)?;
-            count += 1;
         }
         Ok(count)
     }
@@ -633,6 +1688,13 @@ where
         self.llmp_mgr.fire(state, event)
     }
 
+    fn serialize_observers<OT>(&mut self, observers: &OT) -> Result<Vec<u8>, Error>
+    where
+        OT: ObserversTuple<Self::State> + Serialize,
+    {
+        self.llmp_mgr.serialize_observers(observers)
+    }
+
     fn configuration(&self) -> EventConfig {
         self.llmp_mgr.configuration()
     }
@@ -652,6 +1714,10 @@ where
         self.llmp_mgr.await_restart_safe();
     }
 
+    fn send_exiting(&mut self) -> Result<(), Error> {
+        self.llmp_mgr.send_exiting()
+    }
+
     /// Reset the single page (we reuse it over and over from pos 0), then send the current state to the next runner.
     fn on_restart(&mut self, state: &mut S) -> Result<(), Error> {
         // First, reset the page to 0 so the next iteration can read read from the beginning of this page
@@ -666,7 +1732,7 @@ impl<E, S, SP, Z> EventProcessor<E, Z> for LlmpRestartingEventManager<S, SP>
 where
     E: HasObservers<State = S> + Executor<LlmpEventManager<S, SP>, Z>,
     for<'a> E::Observers: Deserialize<'a>,
-    S: UsesInput + HasExecutions + HasClientPerfMonitor,
+    S: UsesInput + HasExecutions + HasClientPerfMonitor + HasMetadata,
     SP: ShMemProvider + 'static,
     Z: EvaluatorObservers<E::Observers, State = S> + ExecutionProcessor<E::Observers>, //CE: CustomEvent<I>,
 {
@@ -718,6 +1784,12 @@ where
         }
     }
 
+    /// Adds a hook, run on each event of other clients arriving in this manager.
+    /// See [`LlmpEventManager::add_event_hook`].
+    pub fn add_event_hook(&mut self, hook: Box<ClientEventHookFn<S>>) {
+        self.llmp_mgr.add_event_hook(hook);
+    }
+
     /// Get the staterestorer
     pub fn staterestorer(&self) -> &StateRestorer<SP> {
         &self.staterestorer
@@ -729,6 +1801,18 @@ where
     }
 }
 
+#[cfg(feature = "std")]
+impl<S, SP> LlmpRestartingEventManager<S, SP>
+where
+    S: UsesInput + HasExecutions + HasClientPerfMonitor,
+    SP: ShMemProvider + 'static,
+{
+    /// Run a [`LlmpEventManager::self_test`] on the embedded llmp event manager
+    pub fn self_test(&mut self) -> Result<Vec<LlmpSelfTestResult>, Error> {
+        self.llmp_mgr.self_test()
+    }
+}
+
 /// The kind of manager we're creating right now
 #[cfg(feature = "std")]
 #[derive(Debug, Clone, Copy)]
@@ -791,12 +1875,26 @@ where
     /// The broker port to use
     #[builder(default = 1337_u16)]
     broker_port: u16,
-    /// The address to connect to
+    /// The address of a broker on another machine.
+    /// A broker connects to it, a [`ManagerKind::Client`] attaches to it via a tcp relay, see [`LlmpEventManager::new_on_tcp`].
     #[builder(default = None)]
     remote_broker_addr: Option<SocketAddr>,
+    /// A unix domain socket the broker listens on next to `broker_port`, and a [`ManagerKind::Client`] attaches to instead,
+    /// for example, for fuzzers in containers. Only supported on `unix`.
+    #[builder(default = None)]
+    broker_unix_path: Option<PathBuf>,
     /// The type of manager to build
     #[builder(default = ManagerKind::Any)]
     kind: ManagerKind,
+    /// How long a client may stay silent before the broker reports it as hung, see [`LlmpEventBroker::set_client_timeout`]
+    #[builder(default = Some(DEFAULT_CLIENT_TIMEOUT))]
+    client_timeout: Option<Duration>,
+    /// If the broker restarts hung clients, see [`LlmpEventBroker::set_restart_hung_clients`]
+    #[builder(default = false)]
+    restart_hung_clients: bool,
+    /// How long a client may stay silent before the broker evicts it, see [`LlmpEventBroker::set_client_evict_timeout`]
+    #[builder(default = None)]
+    client_evict_timeout: Option<Duration>,
     #[builder(setter(skip), default = PhantomData)]
     phantom_data: PhantomData<S>,
 }
@@ -815,8 +1913,24 @@ where
         let (staterestorer, new_shmem_provider, core_id) = if std::env::var(_ENV_FUZZER_SENDER)
             .is_err()
         {
+            let client_timeout = self.client_timeout;
+            let restart_hung_clients = self.restart_hung_clients;
+            let client_evict_timeout = self.client_evict_timeout;
+            let broker_unix_path = self.broker_unix_path.clone();
             let broker_things = |mut broker: LlmpEventBroker<S::Input, MT, SP>,
                                  remote_broker_addr| {
+                broker.set_client_timeout(client_timeout);
+                broker.set_restart_hung_clients(restart_hung_clients);
+                broker.set_client_evict_timeout(client_evict_timeout);
+                if let Some(path) = &broker_unix_path {
+                    #[cfg(unix)]
+                    broker.launch_unix_listener_on(path)?;
+                    #[cfg(not(unix))]
+                    return Err(Error::illegal_argument(format!(
+                        "Cannot listen on {}, unix domain sockets are only supported on unix",
+                        path.display()
+                    )));
+                }
                 if let Some(remote_broker_addr) = remote_broker_addr {
                     println!("B2b: Connecting to {:?}", &remote_broker_addr);
                     broker.connect_b2b(remote_broker_addr)?;
@@ -865,11 +1979,34 @@ where
                 }
                 ManagerKind::Client { cpu_core } => {
                     // We are a client
-                    let mgr = LlmpEventManager::<S, SP>::new_on_port(
-                        self.shmem_provider.clone(),
-                        self.broker_port,
-                        self.configuration,
-                    )?;
+                    let mgr = if let Some(remote_broker_addr) = self.remote_broker_addr {
+                        // The relay lives in this restarter, the respawned fuzzers keep using it
+                        LlmpEventManager::<S, SP>::new_on_tcp(
+                            self.shmem_provider.clone(),
+                            remote_broker_addr,
+                            self.configuration,
+                        )?
+                    } else if let Some(path) = &self.broker_unix_path {
+                        #[cfg(unix)]
+                        {
+                            LlmpEventManager::<S, SP>::new_on_unix(
+                                self.shmem_provider.clone(),
+                                path,
+                                self.configuration,
+                            )?
+                        }
+                        #[cfg(not(unix))]
+                        return Err(Error::illegal_argument(format!(
+                            "Cannot attach to {}, unix domain sockets are only supported on unix",
+                            path.display()
+                        )));
+                    } else {
+                        LlmpEventManager::<S, SP>::new_on_port(
+                            self.shmem_provider.clone(),
+                            self.broker_port,
+                            self.configuration,
+                        )?
+                    };
 
                     (mgr, cpu_core)
                 }
@@ -914,11 +2051,17 @@ where
                 // On windows (or in any case without fork), we spawn ourself again
                 #[cfg(any(windows, not(feature = "fork")))]
                 let child_status = startable_self()?.status()?;
-                #[cfg(all(unix, not(feature = "fork")))]
-                let child_status = child_status.code().unwrap_or_default();
+                #[cfg(any(windows, not(feature = "fork")))]
+                let child_status = child_status.code().unwrap_or(-1);
 
                 compiler_fence(Ordering::SeqCst);
 
+                // A client that exits normally is done, for example after a fixed number of iterations
+                if child_status == 0 {
+                    println!("Fuzzer-respawner: The client finished, not spawning the next one.");
+                    return Err(Error::shutting_down());
+                }
+
                 #[allow(clippy::manual_assert)]
                 if !staterestorer.has_content() {
                     #[cfg(unix)]
@@ -929,7 +2072,7 @@ where
                     }
 
                     // Storing state in the last round did not work
-                    panic!("Fuzzer-respawner: Storing state in crashed fuzzer instance did not work, no point to spawn the next client! This can happen if the child calls `exit()`, in that case make sure it uses `abort()`, if it got killed unrecoverable (OOM), or if there is a bug in the fuzzer itself. (Child exited with: {child_status})");
+                    panic!("Fuzzer-respawner: Storing state in crashed fuzzer instance did not work, no point to spawn the next client! This can happen if the child calls `exit()` with an error code, in that case make sure it uses `abort()`, if it got killed unrecoverable (OOM), or if there is a bug in the fuzzer itself. (Child exited with: {child_status})");
                 }
 
                 ctr = ctr.wrapping_add(1);
@@ -976,6 +2119,7 @@ where
         };
         // We reset the staterestorer, the next staterestorer and receiver (after crash) will reuse the page from the initial message.
         mgr.staterestorer.reset();
+        mgr.llmp_mgr.send_pid()?;
 
         /* TODO: Not sure if this is needed
         // We commit an empty NO_RESTART message to this buf, against infinite loops,
@@ -988,33 +2132,462 @@ where
 }
 
 #[cfg(test)]
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", feature = "executors"))]
 mod tests {
-    use core::sync::atomic::{compiler_fence, Ordering};
+    use alloc::{boxed::Box, rc::Rc, string::ToString, vec::Vec};
+    use core::{
+        cell::RefCell,
+        marker::PhantomData,
+        sync::atomic::{compiler_fence, Ordering},
+        time::Duration,
+    };
 
+    use hashbrown::HashMap;
     use serial_test::serial;
 
     use crate::{
         bolts::{
-            llmp::{LlmpClient, LlmpSharedMap},
+            compress::GzipCompressor,
+            current_time,
+            llmp::{
+                LlmpClient, LlmpMsgHookResult, LlmpSharedMap, LLMP_FLAG_CHUNKED,
+                LLMP_FLAG_COMPRESSED, LLMP_FLAG_FROM_B2B, LLMP_FLAG_INITIALIZED,
+            },
             rands::StdRand,
+            serialization::SerializationFormat,
             shmem::{ShMemProvider, StdShMemProvider},
             staterestore::StateRestorer,
             tuples::tuple_list,
         },
         corpus::{Corpus, InMemoryCorpus, Testcase},
-        events::{llmp::_ENV_FUZZER_SENDER, LlmpEventManager},
+        events::{
+            llmp::{
+                event_tag, format_flags, format_from_flags, is_event_tag, BrokerEventHookFn,
+                BrokerStats, ChunkAssembler, _ENV_FUZZER_SENDER, CHUNK_SIZE, COMPRESS_THRESHOLD,
+                LLMP_TAG_BROKER_STATS, LLMP_TAG_EVENT_OBJECTIVE, LLMP_TAG_EVENT_STATS,
+                LLMP_TAG_EVENT_TO_BOTH,
+            },
+            BrokerEventResult, CustomBufEventResult, Event, EventConfig, EventFirer,
+            HasCustomBufHandlers, LlmpEventBroker, LlmpEventManager, NopEventManager,
+        },
         executors::{ExitKind, InProcessExecutor},
         feedbacks::ConstFeedback,
         fuzzer::Fuzzer,
         inputs::BytesInput,
+        monitors::{Monitor, NopMonitor, UserStats},
         mutators::BitFlipMutator,
+        observers::StdMapObserver,
         schedulers::RandScheduler,
         stages::StdMutationalStage,
-        state::StdState,
+        state::{NopState, StdState},
         StdFuzzer,
     };
 
+    static mut MAP: [u32; 4] = [1, 2, 3, 4];
+
+    #[test]
+    fn test_chunk_assembler() {
+        let buf: Vec<u8> = (0..CHUNK_SIZE * 2 + 17).map(|i| i as u8).collect();
+        let chunks: Vec<_> = ChunkAssembler::split(42, &buf).collect();
+        assert_eq!(chunks.len(), 3);
+
+        let mut assembler = ChunkAssembler::default();
+        assert!(assembler.push(1, &chunks[0]).unwrap().is_none());
+        // A chunk from another client does not mix in
+        assert!(assembler.push(2, &chunks[1]).unwrap().is_none());
+        assert!(assembler.push(1, &chunks[1]).unwrap().is_none());
+        assert_eq!(assembler.push(1, &chunks[2]).unwrap(), Some(buf.clone()));
+        assert!(assembler.pending.is_empty());
+
+        // The sender died halfway through an event, its restarted instance starts the next one
+        assert!(assembler.push(1, &chunks[0]).unwrap().is_none());
+        let next_chunks: Vec<_> = ChunkAssembler::split(43, &buf).collect();
+        assert!(assembler.push(1, &next_chunks[0]).unwrap().is_none());
+        assert_eq!(assembler.pending.len(), 1);
+        assert!(assembler.push(1, &next_chunks[1]).unwrap().is_none());
+        assert_eq!(assembler.push(1, &next_chunks[2]).unwrap(), Some(buf));
+        assert!(assembler.pending.is_empty());
+    }
+
+    #[test]
+    #[serial]
+    fn test_serialize_observers() {
+        let observers = tuple_list!(StdMapObserver::new("map", unsafe { &mut MAP }));
+        let expected = postcard::to_allocvec(&observers).unwrap();
+
+        // Nobody can use the observers of an `AlwaysUnique` client
+        let mut nop_mgr = NopEventManager::<NopState<BytesInput>>::new();
+        assert!(nop_mgr
+            .serialize_observers_if_needed(&observers)
+            .unwrap()
+            .is_none());
+
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut llmp_client = LlmpClient::new(
+            shmem_provider.clone(),
+            LlmpSharedMap::new(0, shmem_provider.new_shmem(1024).unwrap()),
+            0,
+        )
+        .unwrap();
+        // A little hack for CI. Don't do that in a real-world scenario.
+        unsafe {
+            llmp_client.mark_safe_to_unmap();
+        }
+        let mut llmp_mgr =
+            LlmpEventManager::<NopState<BytesInput>, _>::new(llmp_client, "fuzzer".into()).unwrap();
+
+        let mut bufs = vec![];
+        for _ in 0..3 {
+            let observers_buf = llmp_mgr.serialize_observers_if_needed(&observers).unwrap();
+            assert_eq!(observers_buf.as_ref(), Some(&expected));
+            bufs.push(observers_buf.as_ref().unwrap().as_ptr());
+            llmp_mgr
+                .fire(
+                    &mut NopState::new(),
+                    Event::NewTestcase {
+                        input: BytesInput::new(vec![0; 16]),
+                        observers_buf,
+                        exit_kind: ExitKind::Ok,
+                        corpus_size: 1,
+                        client_config: EventConfig::AlwaysUnique,
+                        time: current_time(),
+                        executions: 1,
+                    },
+                )
+                .unwrap();
+        }
+        // The buffers are kept for the next serialization, nothing is allocated after the first round
+        assert_eq!(bufs[1], bufs[0]);
+        assert_eq!(bufs[2], bufs[0]);
+        assert!(llmp_mgr.serialization_buf.capacity() > expected.len());
+        assert!(llmp_mgr.observers_buf.capacity() >= expected.len());
+
+        llmp_mgr.set_serialization_format(SerializationFormat::Json);
+        let observers_buf = llmp_mgr.serialize_observers(&observers).unwrap();
+        assert_eq!(observers_buf, serde_json::to_vec(&observers).unwrap());
+        for format in [
+            SerializationFormat::Postcard,
+            SerializationFormat::Bincode,
+            SerializationFormat::Json,
+        ] {
+            let flags = LLMP_FLAG_COMPRESSED | format_flags(format);
+            assert_eq!(format_from_flags(flags).unwrap(), format);
+        }
+    }
+
+    #[test]
+    fn test_broker_stats() {
+        let mut monitor = NopMonitor::new();
+        for (client_id, executions) in [(1, 100), (2, 50)] {
+            let client = monitor.client_stats_mut_for(client_id);
+            client.update_executions(executions, current_time());
+            client.update_corpus_size(10);
+        }
+        let remote = BrokerStats {
+            hostname: "remote".into(),
+            clients: 4,
+            corpus_size: 20,
+            objective_size: 1,
+            executions: 1000,
+        };
+        remote.update_monitor(&mut monitor, 3);
+        assert_eq!(monitor.total_execs(), 1150);
+        assert_eq!(monitor.objective_size(), 1);
+
+        // The stats sent to other brokers only cover the own clients
+        let mut remote_brokers = HashMap::new();
+        remote_brokers.insert(3, remote);
+        let stats = BrokerStats::from_monitor(&monitor, &remote_brokers);
+        assert_eq!(stats.clients, 2);
+        assert_eq!(stats.corpus_size, 20);
+        assert_eq!(stats.executions, 150);
+    }
+
+    #[test]
+    fn test_hung_clients() {
+        type Broker = LlmpEventBroker<BytesInput, NopMonitor, StdShMemProvider>;
+        let mut monitor = NopMonitor::new();
+        let mut clients = HashMap::new();
+        let timeout = Duration::from_secs(60);
+        let hung = |monitor: &mut NopMonitor| {
+            monitor
+                .client_stats_mut_for(1)
+                .get_user_stats("hung")
+                .cloned()
+        };
+
+        Broker::client_alive(&mut monitor, &mut clients, 1, Duration::from_secs(10));
+        Broker::check_clients(
+            &mut monitor,
+            &mut clients,
+            timeout,
+            false,
+            Duration::from_secs(70),
+        );
+        assert!(!clients[&1].hung);
+        assert!(hung(&mut monitor).is_none());
+
+        // A client that did not report any executions yet is still loading its initial corpus
+        Broker::check_clients(
+            &mut monitor,
+            &mut clients,
+            timeout,
+            false,
+            Duration::from_secs(71),
+        );
+        assert!(!clients[&1].hung);
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(100, Duration::from_secs(10));
+
+        Broker::check_clients(
+            &mut monitor,
+            &mut clients,
+            timeout,
+            false,
+            Duration::from_secs(71),
+        );
+        assert!(clients[&1].hung);
+        assert!(matches!(hung(&mut monitor), Some(UserStats::Number(1))));
+
+        // The client is back once it sends anything
+        Broker::client_alive(&mut monitor, &mut clients, 1, Duration::from_secs(72));
+        assert!(!clients[&1].hung);
+        assert!(matches!(hung(&mut monitor), Some(UserStats::Number(0))));
+    }
+
+    #[test]
+    fn test_event_tags() {
+        let objective = Event::<BytesInput>::Objective { objective_size: 1 };
+        let stats = Event::<BytesInput>::UpdateExecStats {
+            time: Duration::from_secs(1),
+            executions: 1,
+            phantom: PhantomData,
+        };
+        let custom = Event::<BytesInput>::CustomBuf {
+            buf: vec![],
+            tag: "custom".into(),
+        };
+        assert_eq!(event_tag(&objective), LLMP_TAG_EVENT_OBJECTIVE);
+        assert_eq!(event_tag(&stats), LLMP_TAG_EVENT_STATS);
+        assert_eq!(event_tag(&custom), LLMP_TAG_EVENT_TO_BOTH);
+        for event in [objective, stats, custom] {
+            assert!(is_event_tag(event_tag(&event)));
+        }
+        assert!(!is_event_tag(LLMP_TAG_BROKER_STATS));
+    }
+
+    #[test]
+    fn test_evict_silent_clients() {
+        type Broker = LlmpEventBroker<BytesInput, NopMonitor, StdShMemProvider>;
+        let mut monitor = NopMonitor::new();
+        let mut clients = HashMap::new();
+        let timeout = Duration::from_secs(60);
+
+        Broker::client_alive(&mut monitor, &mut clients, 1, Duration::from_secs(10));
+        Broker::client_alive(&mut monitor, &mut clients, 2, Duration::from_secs(50));
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(1000, Duration::from_secs(10));
+        monitor
+            .client_stats_mut_for(2)
+            .update_executions(500, Duration::from_secs(50));
+        assert_eq!(monitor.total_execs(), 1500);
+
+        let evicted = Broker::evict_silent_clients(
+            &mut monitor,
+            &mut clients,
+            timeout,
+            Duration::from_secs(71),
+        );
+        assert_eq!(evicted, [1]);
+        assert!(!clients.contains_key(&1));
+        assert_eq!(clients[&2].last_seen, Duration::from_secs(50));
+        // The stale executions of the evicted client no longer count
+        assert_eq!(monitor.total_execs(), 500);
+
+        // An evicted client is tracked anew, once it sends anything
+        Broker::client_alive(&mut monitor, &mut clients, 1, Duration::from_secs(80));
+        assert_eq!(clients[&1].last_seen, Duration::from_secs(80));
+    }
+
+    #[test]
+    #[serial]
+    fn test_event_hooks() {
+        let mut corpus = InMemoryCorpus::<BytesInput>::new();
+        corpus.add(Testcase::new(vec![0; 4].into())).unwrap();
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut llmp_client = LlmpClient::new(
+            shmem_provider.clone(),
+            LlmpSharedMap::new(0, shmem_provider.new_shmem(1024).unwrap()),
+            0,
+        )
+        .unwrap();
+        // A little hack for CI. Don't do that in a real-world scenario.
+        unsafe {
+            llmp_client.mark_safe_to_unmap();
+        }
+        let mut llmp_mgr = LlmpEventManager::new(llmp_client, "fuzzer".into()).unwrap();
+
+        let mut fuzzer = StdFuzzer::new(
+            RandScheduler::new(),
+            ConstFeedback::new(false),
+            ConstFeedback::new(false),
+        );
+        let mut harness = |_buf: &BytesInput| ExitKind::Ok;
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut llmp_mgr,
+        )
+        .unwrap();
+
+        // The first hook rewrites the tag, the second one drops the events of client 2
+        llmp_mgr.add_event_hook(Box::new(|_state, _client_id, event| {
+            if let Event::CustomBuf { tag, .. } = event {
+                *tag = "rewritten".into();
+            }
+            Ok(CustomBufEventResult::Next)
+        }));
+        llmp_mgr.add_event_hook(Box::new(|_state, client_id, _event| {
+            Ok(if client_id == 2 {
+                CustomBufEventResult::Handled
+            } else {
+                CustomBufEventResult::Next
+            })
+        }));
+        let received = Rc::new(RefCell::new(vec![]));
+        let received_clone = received.clone();
+        llmp_mgr.add_custom_buf_handler(Box::new(move |_state, tag, _buf| {
+            received_clone.borrow_mut().push(tag.clone());
+            Ok(CustomBufEventResult::Handled)
+        }));
+
+        for client_id in [1, 2] {
+            llmp_mgr
+                .handle_in_client(
+                    &mut fuzzer,
+                    &mut executor,
+                    &mut state,
+                    client_id,
+                    SerializationFormat::Postcard,
+                    Event::CustomBuf {
+                        buf: vec![],
+                        tag: "original".into(),
+                    },
+                )
+                .unwrap();
+        }
+        assert_eq!(*received.borrow(), vec!["rewritten".to_string()]);
+    }
+
+    #[test]
+    fn test_broker_event_hooks() {
+        type Broker = LlmpEventBroker<BytesInput, NopMonitor, StdShMemProvider>;
+        let mut monitor = NopMonitor::new();
+        let mut chunks = ChunkAssembler::default();
+        let compressor = GzipCompressor::new(COMPRESS_THRESHOLD);
+        // The first hook rewrites the tag, the second one drops the events of client 2
+        let mut hooks: Vec<Box<BrokerEventHookFn<BytesInput>>> = vec![
+            Box::new(|_client_id, event| {
+                if let Event::CustomBuf { tag, .. } = event {
+                    *tag = "rewritten".into();
+                }
+                Ok(BrokerEventResult::Forward)
+            }),
+            Box::new(|client_id, _event| {
+                Ok(if client_id == 2 {
+                    BrokerEventResult::Handled
+                } else {
+                    BrokerEventResult::Forward
+                })
+            }),
+        ];
+        let mut handle = |client_id, flags, msg: &[u8]| {
+            Broker::handle_event_msg(
+                &mut monitor,
+                &mut hooks,
+                &mut chunks,
+                &compressor,
+                client_id,
+                flags,
+                msg,
+            )
+            .unwrap()
+        };
+        let custom_buf = |tag: &str, len| {
+            postcard::to_allocvec(&Event::<BytesInput>::CustomBuf {
+                buf: vec![0x41; len],
+                tag: tag.into(),
+            })
+            .unwrap()
+        };
+        let rewritten = custom_buf("rewritten", 16);
+
+        // Events from other brokers reach the hooks as well
+        for flags in [
+            LLMP_FLAG_INITIALIZED,
+            LLMP_FLAG_INITIALIZED | LLMP_FLAG_FROM_B2B,
+        ] {
+            match handle(1, flags, &custom_buf("original", 16)) {
+                LlmpMsgHookResult::ReplaceWith(bufs) => {
+                    assert_eq!(bufs, [(flags, rewritten.clone())])
+                }
+                res => panic!("The rewritten event was not replaced: {res:?}"),
+            }
+            assert!(matches!(
+                handle(2, flags, &custom_buf("original", 16)),
+                LlmpMsgHookResult::Handled
+            ));
+        }
+        // Events the hooks leave alone are forwarded as they are
+        assert!(matches!(
+            handle(1, LLMP_FLAG_INITIALIZED, &rewritten),
+            LlmpMsgHookResult::ForwardToClients
+        ));
+
+        // The chunks of an event are held back until it is complete, and dropped as a whole
+        let large = custom_buf("rewritten", CHUNK_SIZE + 1);
+        let flags = LLMP_FLAG_INITIALIZED | LLMP_FLAG_CHUNKED;
+        let large_chunks: Vec<_> = ChunkAssembler::split(1, &large).collect();
+        assert_eq!(large_chunks.len(), 2);
+        assert!(matches!(
+            handle(2, flags, &large_chunks[0]),
+            LlmpMsgHookResult::Handled
+        ));
+        assert!(matches!(
+            handle(2, flags, &large_chunks[1]),
+            LlmpMsgHookResult::Handled
+        ));
+        assert!(matches!(
+            handle(1, flags, &large_chunks[0]),
+            LlmpMsgHookResult::Handled
+        ));
+        let LlmpMsgHookResult::ReplaceWith(bufs) = handle(1, flags, &large_chunks[1]) else {
+            panic!("The complete event was not forwarded");
+        };
+        let mut assembler = ChunkAssembler::default();
+        let mut forwarded = None;
+        for (chunk_flags, chunk) in bufs {
+            assert_eq!(chunk_flags, flags);
+            forwarded = assembler.push(1, &chunk).unwrap();
+        }
+        assert_eq!(forwarded, Some(large));
+    }
+
     #[test]
     #[serial]
     fn test_mgr_state_restore() {
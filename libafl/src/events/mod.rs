@@ -2,15 +2,17 @@
 
 pub mod simple;
 pub use simple::*;
+#[cfg(feature = "llmp")]
 pub mod llmp;
 use alloc::{
     boxed::Box,
     string::{String, ToString},
     vec::Vec,
 };
-use core::{fmt, hash::Hasher, marker::PhantomData, time::Duration};
+use core::{any::type_name, fmt, hash::Hasher, marker::PhantomData, time::Duration};
 
 use ahash::AHasher;
+#[cfg(feature = "llmp")]
 pub use llmp::*;
 use serde::{Deserialize, Serialize};
 #[cfg(feature = "std")]
@@ -20,7 +22,7 @@ use crate::{
     bolts::current_time,
     executors::ExitKind,
     inputs::Input,
-    monitors::UserStats,
+    monitors::{FuzzerDescription, UserStats},
     observers::ObserversTuple,
     stages::calibrate::UnstableEntriesMetadata,
     state::{HasClientPerfMonitor, HasExecutions, HasMetadata},
@@ -63,7 +65,8 @@ impl fmt::Display for LogSeverity {
     }
 }
 
-/// The result of a custom buf handler added using [`HasCustomBufHandlers::add_custom_buf_handler`]
+/// The result of a custom buf handler added using [`HasCustomBufHandlers::add_custom_buf_handler`],
+/// or of an event hook added to an llmp event manager
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum CustomBufEventResult {
     /// Exit early from event handling
@@ -119,6 +122,31 @@ impl EventConfig {
         }
     }
 
+    /// Mixes the type of the observers `OT` into this [`EventConfig`].
+    /// Clients built from the same binary, but running different observer sets,
+    /// then no longer try to deserialize each other's observers, and re-execute the received inputs instead.
+    #[must_use]
+    pub fn with_observers<OT>(self) -> Self {
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        hasher.write(type_name::<OT>().as_bytes());
+        let observers_hash = hasher.finish();
+        match self {
+            EventConfig::AlwaysUnique => EventConfig::AlwaysUnique,
+            EventConfig::FromName { name_hash } => {
+                hasher = AHasher::new_with_keys(0, 0);
+                hasher.write_u64(name_hash);
+                hasher.write_u64(observers_hash);
+                EventConfig::FromName {
+                    name_hash: hasher.finish(),
+                }
+            }
+            #[cfg(feature = "std")]
+            EventConfig::BuildID { id } => EventConfig::BuildID {
+                id: Uuid::from_u128(id.as_u128() ^ u128::from(observers_hash)),
+            },
+        }
+    }
+
     /// Match if the currenti [`EventConfig`] matches another given config
     #[must_use]
     pub fn match_with(&self, other: &EventConfig) -> bool {
@@ -240,6 +268,18 @@ where
         /// `PhantomData`
         phantom: PhantomData<I>,
     },
+    /// The component configuration of a client
+    ClientDescription {
+        /// The description of the client
+        description: Box<FuzzerDescription>,
+    },
+    /// The harness of a client failed to initialize, see [`crate::executors::HarnessInit`]
+    HarnessInitFailed {
+        /// The number of the failed attempt, starting at `1`
+        attempt: usize,
+        /// The error returned by the init function
+        message: String,
+    },
     /// Sends a custom buffer to other clients
     CustomBuf {
         /// The buffer
@@ -292,6 +332,8 @@ where
                 message: _,
                 phantom: _,
             } => "Log",
+            Event::ClientDescription { .. } => "Description",
+            Event::HarnessInitFailed { .. } => "HarnessInitFailed",
             Event::CustomBuf { .. } => "CustomBuf",
             /*Event::Custom {
                 sender_id: _, /*custom_event} => custom_event.name()*/
@@ -334,6 +376,21 @@ pub trait EventFirer: UsesState {
         )
     }
 
+    /// Send off an [`Event::ClientDescription`] event to the broker, usually once, at startup.
+    /// This is a shortcut for [`EventFirer::fire`] with [`Event::ClientDescription`] as argument.
+    fn describe(
+        &mut self,
+        state: &mut Self::State,
+        description: FuzzerDescription,
+    ) -> Result<(), Error> {
+        self.fire(
+            state,
+            Event::ClientDescription {
+                description: Box::new(description),
+            },
+        )
+    }
+
     /// Serialize all observers for this type and manager
     fn serialize_observers<OT>(&mut self, observers: &OT) -> Result<Vec<u8>, Error>
     where
@@ -342,6 +399,23 @@ pub trait EventFirer: UsesState {
         Ok(postcard::to_allocvec(observers)?)
     }
 
+    /// Serialize the observers to send along with an [`Event::NewTestcase`], if other clients can use them.
+    /// Serialization is costly, so only call this once the feedbacks declared the execution interesting.
+    fn serialize_observers_if_needed<OT>(
+        &mut self,
+        observers: &OT,
+    ) -> Result<Option<Vec<u8>>, Error>
+    where
+        OT: ObserversTuple<Self::State> + Serialize,
+    {
+        if self.configuration() == EventConfig::AlwaysUnique {
+            // No other client will ever deserialize them
+            Ok(None)
+        } else {
+            self.serialize_observers(observers).map(Some)
+        }
+    }
+
     /// Get the configuration
     fn configuration(&self) -> EventConfig {
         EventConfig::AlwaysUnique
@@ -430,6 +504,13 @@ pub trait EventRestarter: UsesState {
     /// Block until we are safe to exit.
     #[inline]
     fn await_restart_safe(&mut self) {}
+
+    /// Tell the other side that this client is done, so it does not wait for it anymore, for example after fuzzing a fixed number of iterations.
+    /// Nothing may be sent afterwards.
+    #[inline]
+    fn send_exiting(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
 }
 
 /// [`EventProcessor`] process all the incoming messages
@@ -549,6 +630,7 @@ impl<S> HasEventManagerId for NopEventManager<S> {
 
 #[cfg(test)]
 mod tests {
+    use alloc::boxed::Box;
 
     use tuple_list::tuple_list_type;
 
@@ -560,6 +642,8 @@ mod tests {
         events::{Event, EventConfig},
         executors::ExitKind,
         inputs::bytes::BytesInput,
+        monitors::FuzzerDescription,
+        mutators::{BitFlipMutator, ByteFlipMutator},
         observers::StdMapObserver,
     };
 
@@ -602,6 +686,51 @@ mod tests {
             _ => panic!("mistmatch"),
         };
     }
+
+    #[test]
+    fn test_event_config_observers() {
+        type U8Observers = tuple_list_type!(StdMapObserver<'static, u8>);
+        type U32Observers = tuple_list_type!(StdMapObserver<'static, u32>);
+
+        let config = EventConfig::from_name("fuzzer").with_observers::<U8Observers>();
+        assert!(
+            config.match_with(&EventConfig::from_name("fuzzer").with_observers::<U8Observers>())
+        );
+        assert!(
+            !config.match_with(&EventConfig::from_name("fuzzer").with_observers::<U32Observers>())
+        );
+        assert!(!config.match_with(&EventConfig::from_name("fuzzer")));
+
+        let config = EventConfig::from_build_id();
+        assert!(!config
+            .with_observers::<U8Observers>()
+            .match_with(&config.with_observers::<U32Observers>()));
+        assert_eq!(
+            EventConfig::AlwaysUnique.with_observers::<U8Observers>(),
+            EventConfig::AlwaysUnique
+        );
+    }
+
+    #[test]
+    fn test_description_serde() {
+        let obv = StdMapObserver::new("test", unsafe { &mut MAP });
+        let observers = tuple_list!(obv);
+        let mutators = tuple_list!(BitFlipMutator::new(), ByteFlipMutator::new());
+        let description = FuzzerDescription::new()
+            .with_observers(&observers)
+            .with_mutators(&mutators);
+        assert_eq!(description.observers, ["test"]);
+        assert_eq!(description.mutators, ["BitFlipMutator", "ByteFlipMutator"]);
+
+        let e = Event::<BytesInput>::ClientDescription {
+            description: Box::new(description.clone()),
+        };
+        let serialized = postcard::to_allocvec(&e).unwrap();
+        match postcard::from_bytes::<Event<BytesInput>>(&serialized).unwrap() {
+            Event::ClientDescription { description: d } => assert_eq!(*d, description),
+            _ => panic!("mistmatch"),
+        }
+    }
 }
 
 /// `EventManager` Python bindings
@@ -266,6 +266,20 @@ where
                 println!("[LOG {severity_level}]: {message}");
                 Ok(BrokerEventResult::Handled)
             }
+            Event::ClientDescription { description } => {
+                #[cfg(feature = "std")]
+                println!("[DESCRIPTION]: {description}");
+                monitor
+                    .client_stats_mut_for(0)
+                    .update_description((**description).clone());
+                Ok(BrokerEventResult::Handled)
+            }
+            #[cfg_attr(not(feature = "std"), allow(unused_variables))]
+            Event::HarnessInitFailed { attempt, message } => {
+                #[cfg(feature = "std")]
+                println!("[HARNESS INIT FAILED] attempt {attempt}: {message}");
+                Ok(BrokerEventResult::Handled)
+            }
             Event::CustomBuf { .. } => Ok(BrokerEventResult::Forward),
             //_ => Ok(BrokerEventResult::Forward),
         }
@@ -0,0 +1,215 @@
+//! The [`AdaptiveTimeoutExecutor`] derives the timeout of each run from the calibrated execution times of the corpus
+
+use core::{fmt::Debug, time::Duration};
+
+use crate::{
+    corpus::Corpus,
+    executors::{Executor, ExitKind, HasObservers, TimeoutExecutor},
+    observers::UsesObservers,
+    schedulers::powersched::SchedulerMetadata,
+    state::{HasCorpus, HasMetadata, UsesState},
+    Error,
+};
+
+/// The default factor between the execution time of a testcase and the timeout of its runs, as for AFL
+pub const DEFAULT_TIMEOUT_MULTIPLIER: u32 = 5;
+
+/// The default lower bound of the timeout of a run
+pub const DEFAULT_MIN_TIMEOUT: Duration = Duration::from_millis(20);
+
+/// A wrapper for a [`TimeoutExecutor`], setting the timeout of each run to a multiple of the average execution time
+/// of the corpus, as measured by the [`crate::stages::CalibrationStage`], bounded by a minimum and a maximum timeout.
+/// While fuzzing a testcase slower than the average, as measured by the calibration or a [`crate::feedbacks::TimeFeedback`],
+/// the timeout is a multiple of its execution time instead.
+/// Without calibrated execution times, such as for the initial inputs, runs use the maximum.
+/// This way, slow seeds do not time out, while fast corpora do not need to wait for a long global timeout.
+/// Runs of inputs other than the mutants of the current testcase, such as inputs synced from other fuzzers,
+/// still get at least the timeout of an average testcase.
+#[derive(Debug)]
+pub struct AdaptiveTimeoutExecutor<E> {
+    executor: TimeoutExecutor<E>,
+    multiplier: u32,
+    min_timeout: Duration,
+    max_timeout: Duration,
+    current_timeout: Option<Duration>,
+}
+
+impl<E, EM, Z> Executor<EM, Z> for AdaptiveTimeoutExecutor<E>
+where
+    E: Debug + UsesState,
+    E::State: HasCorpus + HasMetadata,
+    TimeoutExecutor<E>: Executor<EM, Z, State = E::State>,
+    EM: UsesState<State = E::State>,
+    Z: UsesState<State = E::State>,
+{
+    fn run_target(
+        &mut self,
+        fuzzer: &mut Z,
+        state: &mut Self::State,
+        mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        let exec_time = match state.corpus().current() {
+            Some(idx) => *state.corpus().get(*idx)?.borrow().exec_time(),
+            None => None,
+        };
+        let avg_exec_time = state
+            .metadata()
+            .get::<SchedulerMetadata>()
+            .filter(|meta| meta.cycles() > 0)
+            .map(|meta| meta.exec_time() / meta.cycles().try_into().unwrap_or(u32::MAX));
+        let timeout = self.timeout_for(exec_time, avg_exec_time);
+        if self.current_timeout != Some(timeout) {
+            self.executor.set_timeout(timeout);
+            self.current_timeout = Some(timeout);
+        }
+        self.executor.run_target(fuzzer, state, mgr, input)
+    }
+
+    fn post_run_reset(&mut self) {
+        self.executor.post_run_reset();
+    }
+}
+
+impl<E> UsesState for AdaptiveTimeoutExecutor<E>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E> UsesObservers for AdaptiveTimeoutExecutor<E>
+where
+    E: UsesObservers,
+{
+    type Observers = E::Observers;
+}
+
+impl<E> HasObservers for AdaptiveTimeoutExecutor<E>
+where
+    E: HasObservers,
+{
+    #[inline]
+    fn observers(&self) -> &Self::Observers {
+        self.executor.observers()
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut Self::Observers {
+        self.executor.observers_mut()
+    }
+}
+
+impl<E> AdaptiveTimeoutExecutor<E> {
+    /// Creates a new [`AdaptiveTimeoutExecutor`], wrapping the given [`TimeoutExecutor`].
+    /// The timeout of each run is [`DEFAULT_TIMEOUT_MULTIPLIER`] times the execution time of the current testcase,
+    /// at least [`DEFAULT_MIN_TIMEOUT`], and at most `max_timeout`.
+    #[must_use]
+    pub fn new(executor: TimeoutExecutor<E>, max_timeout: Duration) -> Self {
+        Self {
+            executor,
+            multiplier: DEFAULT_TIMEOUT_MULTIPLIER,
+            min_timeout: DEFAULT_MIN_TIMEOUT.min(max_timeout),
+            max_timeout,
+            current_timeout: None,
+        }
+    }
+
+    /// Sets the factor between the execution time of a testcase and the timeout of its runs
+    pub fn set_multiplier(&mut self, multiplier: u32) {
+        self.multiplier = multiplier;
+    }
+
+    /// Sets the lower bound of the timeout of a run
+    pub fn set_min_timeout(&mut self, min_timeout: Duration) {
+        self.min_timeout = min_timeout.min(self.max_timeout);
+    }
+
+    /// Sets the upper bound of the timeout of a run, also used if the execution time is unknown
+    pub fn set_max_timeout(&mut self, max_timeout: Duration) {
+        self.max_timeout = max_timeout;
+        self.min_timeout = self.min_timeout.min(max_timeout);
+    }
+
+    /// The timeout of a run of a testcase with the given execution time, in a corpus with the given average execution time
+    #[must_use]
+    pub fn timeout_for(
+        &self,
+        exec_time: Option<Duration>,
+        avg_exec_time: Option<Duration>,
+    ) -> Duration {
+        match avg_exec_time {
+            Some(avg_exec_time) => exec_time
+                .map_or(avg_exec_time, |exec_time| exec_time.max(avg_exec_time))
+                .saturating_mul(self.multiplier)
+                .clamp(self.min_timeout, self.max_timeout),
+            None => self.max_timeout,
+        }
+    }
+
+    /// The wrapped executor
+    pub fn inner(&mut self) -> &mut TimeoutExecutor<E> {
+        &mut self.executor
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use core::{marker::PhantomData, time::Duration};
+
+    use crate::{
+        executors::{
+            adaptive_timeout::{AdaptiveTimeoutExecutor, DEFAULT_MIN_TIMEOUT},
+            NopExecutor, TimeoutExecutor,
+        },
+        inputs::BytesInput,
+        state::NopState,
+    };
+
+    #[test]
+    fn test_adaptive_timeout() {
+        let executor = TimeoutExecutor::new(
+            NopExecutor::<NopState<BytesInput>> {
+                phantom: PhantomData,
+            },
+            Duration::from_secs(1),
+        );
+        let mut executor = AdaptiveTimeoutExecutor::new(executor, Duration::from_secs(1));
+
+        let avg = Some(Duration::from_millis(10));
+        // Not calibrated yet
+        assert_eq!(executor.timeout_for(None, None), Duration::from_secs(1));
+        assert_eq!(
+            executor.timeout_for(Some(Duration::from_millis(10)), None),
+            Duration::from_secs(1)
+        );
+        // Inputs other than the mutants of a testcase get the timeout of an average testcase
+        assert_eq!(executor.timeout_for(None, avg), Duration::from_millis(50));
+        assert_eq!(
+            executor.timeout_for(Some(Duration::from_micros(100)), avg),
+            Duration::from_millis(50)
+        );
+        assert_eq!(
+            executor.timeout_for(
+                Some(Duration::from_micros(100)),
+                Some(Duration::from_micros(100))
+            ),
+            DEFAULT_MIN_TIMEOUT
+        );
+        assert_eq!(
+            executor.timeout_for(Some(Duration::from_millis(50)), avg),
+            Duration::from_millis(250)
+        );
+        assert_eq!(
+            executor.timeout_for(Some(Duration::from_millis(500)), avg),
+            Duration::from_secs(1)
+        );
+
+        executor.set_multiplier(10);
+        executor.set_max_timeout(Duration::from_secs(10));
+        assert_eq!(
+            executor.timeout_for(Some(Duration::from_millis(500)), avg),
+            Duration::from_secs(5)
+        );
+    }
+}
@@ -20,6 +20,8 @@ use std::{
 use super::HasObservers;
 #[cfg(all(feature = "std", unix))]
 use crate::executors::{Executor, ExitKind};
+#[cfg(all(feature = "std", unix))]
+use crate::observers::rusage::account_child;
 use crate::{
     bolts::{
         fs::{InputFile, INPUTFILE_STD},
@@ -322,7 +324,7 @@ where
         {
             // for reference: https://www.man7.org/linux/man-pages/man7/signal.7.html
             Some(Some(9)) => Ok(ExitKind::Oom),
-            Some(Some(_)) => Ok(ExitKind::Crash),
+            Some(Some(signal)) => Ok(ExitKind::Signal(signal)),
             Some(None) => Ok(ExitKind::Ok),
             None => {
                 // if this fails, there is not much we can do. let's hope it failed because the process finished
@@ -333,6 +335,7 @@ where
                 Ok(ExitKind::Timeout)
             }
         };
+        account_child();
 
         if self.observers.observes_stderr() {
             let mut stderr = String::new();
@@ -643,6 +646,8 @@ pub trait CommandConfigurator: Sized + Debug {
 
 #[cfg(test)]
 mod tests {
+    use serial_test::serial;
+
     use crate::{
         events::SimpleEventManager,
         executors::{
@@ -656,6 +661,7 @@ mod tests {
     };
 
     #[test]
+    #[serial]
     #[cfg(unix)]
     fn test_builder() {
         let mut mgr = SimpleEventManager::new(SimpleMonitor::new(|status| {
@@ -680,6 +686,7 @@ mod tests {
     }
 
     #[test]
+    #[serial]
     #[cfg(unix)]
     fn test_parse_afl_cmdline() {
         use alloc::string::ToString;
@@ -8,6 +8,7 @@ use core::{
 };
 use std::{
     ffi::{OsStr, OsString},
+    fs,
     io::{self, prelude::*, ErrorKind},
     os::unix::{io::RawFd, process::CommandExt},
     path::Path,
@@ -47,10 +48,58 @@ const FS_OPT_ENABLED: i32 = 0x80000001_u32 as i32;
 const FS_OPT_SHDMEM_FUZZ: i32 = 0x01000000_u32 as i32;
 #[allow(clippy::cast_possible_wrap)]
 const FS_OPT_AUTODICT: i32 = 0x10000000_u32 as i32;
+#[allow(clippy::cast_possible_wrap)]
+const FS_OPT_MAPSIZE: i32 = 0x40000000_u32 as i32;
+#[allow(clippy::cast_possible_wrap)]
+const FS_OPT_ERROR: i32 = 0xf800008f_u32 as i32;
+
+/// The marker `afl-clang-fast` puts into targets using `__AFL_LOOP`
+const PERSIST_SIG: &[u8] = b"##SIG_AFL_PERSISTENT##";
+/// The marker `afl-clang-fast` puts into targets using `__AFL_INIT`
+const DEFER_SIG: &[u8] = b"##SIG_AFL_DEFER_FORKSRV##";
 /// The length of header bytes which tells shmem size
 const SHMEM_FUZZ_HDR_SIZE: usize = 4;
+/// The maximum size of an input delivered over shared memory, larger inputs get truncated
 const MAX_FILE: usize = 1024 * 1024;
 
+/// The size of the coverage map the target reported with `FS_OPT_MAPSIZE`
+#[allow(clippy::cast_sign_loss)]
+fn fs_opt_get_mapsize(status: i32) -> usize {
+    (((status as u32) & 0x00fffffe) >> 1) as usize + 1
+}
+
+/// The description of the error the target reported with `FS_OPT_ERROR`, as defined by AFL++
+#[allow(clippy::cast_sign_loss)]
+fn fs_opt_get_error(status: i32) -> &'static str {
+    match ((status as u32) & 0x00ffff00) >> 8 {
+        1 => "the coverage map is too small for the target",
+        2 => "the target could not map its coverage at a fixed address",
+        4 => "the target could not open its shared memory",
+        8 => "the target could not attach its shared memory",
+        16 => "the target could not mmap its shared memory",
+        32 | 64 => "the target uses an incompatible cmplog version",
+        _ => "unknown error",
+    }
+}
+
+/// Writes `buf` to the shared memory input `map`, as `u32` length followed by the data, as AFL++ expects it.
+/// Errors for inputs larger than [`MAX_FILE`], instead of running a truncated input.
+fn write_shmem_input<SHM>(map: &mut SHM, buf: &[u8]) -> Result<(), Error>
+where
+    SHM: ShMem,
+{
+    let size = buf.len();
+    if size > MAX_FILE {
+        return Err(Error::illegal_argument(format!(
+            "Input of {size} bytes does not fit the shared memory input of {MAX_FILE} bytes, limit the max size of the inputs"
+        )));
+    }
+    let map = map.as_mut_slice();
+    map[..SHMEM_FUZZ_HDR_SIZE].copy_from_slice(&(size as u32).to_ne_bytes());
+    map[SHMEM_FUZZ_HDR_SIZE..(SHMEM_FUZZ_HDR_SIZE + size)].copy_from_slice(buf);
+    Ok(())
+}
+
 /// Configure the target, `limit`, `setsid`, `pipe_stdin`, the code was borrowed from the [`Angora`](https://github.com/AngoraFuzzer/Angora) fuzzer
 pub trait ConfigTarget {
     /// Sets the sid
@@ -418,15 +467,7 @@ where
         let last_run_timed_out = self.executor.forkserver().last_run_timed_out();
 
         match &mut self.executor.shmem_mut() {
-            Some(shmem) => {
-                let target_bytes = input.target_bytes();
-                let size = target_bytes.as_slice().len();
-                let size_in_bytes = size.to_ne_bytes();
-                // The first four bytes tells the size of the shmem.
-                shmem.as_mut_slice()[..4].copy_from_slice(&size_in_bytes[..4]);
-                shmem.as_mut_slice()[SHMEM_FUZZ_HDR_SIZE..(SHMEM_FUZZ_HDR_SIZE + size)]
-                    .copy_from_slice(target_bytes.as_slice());
-            }
+            Some(shmem) => write_shmem_input(shmem, input.target_bytes().as_slice())?,
             None => {
                 self.executor
                     .input_file_mut()
@@ -471,7 +512,7 @@ where
         {
             self.executor.forkserver_mut().set_status(status);
             if libc::WIFSIGNALED(self.executor.forkserver().status()) {
-                exit_kind = ExitKind::Crash;
+                exit_kind = ExitKind::Signal(libc::WTERMSIG(self.executor.forkserver().status()));
             }
         } else {
             self.executor.forkserver_mut().set_last_run_timed_out(1);
@@ -506,6 +547,7 @@ where
     forkserver: Forkserver,
     observers: OT,
     map: Option<SP::ShMem>,
+    map_size: Option<usize>,
     phantom: PhantomData<S>,
     /// Cache that indicates if we have a `ASan` observer registered.
     has_asan_observer: Option<bool>,
@@ -524,6 +566,7 @@ where
             .field("forkserver", &self.forkserver)
             .field("observers", &self.observers)
             .field("map", &self.map)
+            .field("map_size", &self.map_size)
             .finish()
     }
 }
@@ -561,6 +604,12 @@ where
     pub fn input_file(&self) -> &InputFile {
         &self.input_file
     }
+
+    /// The size of the coverage map, if the target reported it in the forkserver handshake
+    #[must_use]
+    pub fn coverage_map_size(&self) -> Option<usize> {
+        self.map_size
+    }
 }
 
 /// The builder for `ForkserverExecutor`
@@ -572,8 +621,8 @@ pub struct ForkserverExecutorBuilder<'a, SP> {
     envs: Vec<(OsString, OsString)>,
     debug_child: bool,
     use_stdin: bool,
-    is_persistent: bool,
-    is_deferred_frksrv: bool,
+    is_persistent: Option<bool>,
+    is_deferred_frksrv: Option<bool>,
     autotokens: Option<&'a mut Tokens>,
     input_filename: Option<OsString>,
     shmem_provider: Option<&'a mut SP>,
@@ -596,19 +645,22 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
 
         let input_file = InputFile::create(input_filename)?;
 
-        let map = match &mut self.shmem_provider {
+        let mut map = match &mut self.shmem_provider {
             None => None,
             Some(provider) => {
                 // setup shared memory
                 let mut shmem = provider.new_shmem(MAX_FILE + SHMEM_FUZZ_HDR_SIZE)?;
                 shmem.write_to_env("__AFL_SHM_FUZZ_ID")?;
 
-                let size_in_bytes = (MAX_FILE + SHMEM_FUZZ_HDR_SIZE).to_ne_bytes();
-                shmem.as_mut_slice()[..4].clone_from_slice(&size_in_bytes[..4]);
+                let size_in_bytes = ((MAX_FILE + SHMEM_FUZZ_HDR_SIZE) as u32).to_ne_bytes();
+                shmem.as_mut_slice()[..SHMEM_FUZZ_HDR_SIZE].clone_from_slice(&size_in_bytes);
                 Some(shmem)
             }
         };
 
+        if let Some(program) = self.program.clone() {
+            self.detect_afl_signatures(&program);
+        }
         let (target, mut forkserver) = match &self.program {
             Some(t) => {
                 let forkserver = Forkserver::new(
@@ -618,8 +670,8 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
                     input_file.as_raw_fd(),
                     self.use_stdin,
                     0,
-                    self.is_persistent,
-                    self.is_deferred_frksrv,
+                    self.is_persistent.unwrap_or(false),
+                    self.is_deferred_frksrv.unwrap_or(false),
                     self.debug_child,
                 )?;
 
@@ -637,7 +689,24 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
         if rlen != 4 {
             return Err(Error::unknown("Failed to start a forkserver".to_string()));
         }
+        if status & FS_OPT_ERROR == FS_OPT_ERROR {
+            return Err(Error::illegal_state(format!(
+                "The forkserver of the target failed: {}",
+                fs_opt_get_error(status)
+            )));
+        }
         println!("All right - fork server is up.");
+        let mut shmem_fuzz = false;
+
+        let map_size = if status & FS_OPT_ENABLED == FS_OPT_ENABLED
+            && status & FS_OPT_MAPSIZE == FS_OPT_MAPSIZE
+        {
+            let map_size = fs_opt_get_mapsize(status);
+            println!("Target map size: {map_size}");
+            Some(map_size)
+        } else {
+            None
+        };
         // If forkserver is responding, we then check if there's any option enabled.
         // We'll send 4-bytes message back to the forkserver to tell which features to use
         // The forkserver is listening to our response if either shmem fuzzing is enabled or auto dict is enabled
@@ -651,6 +720,7 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
             if (status & FS_OPT_SHDMEM_FUZZ == FS_OPT_SHDMEM_FUZZ) && map.is_some() {
                 println!("Using SHARED MEMORY FUZZING feature.");
                 send_status |= FS_OPT_SHDMEM_FUZZ;
+                shmem_fuzz = true;
             }
 
             if (status & FS_OPT_AUTODICT == FS_OPT_AUTODICT) && self.autotokens.is_some() {
@@ -693,6 +763,13 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
             println!("Forkserver Options are not available.");
         }
 
+        // Only deliver inputs over shared memory if the target agreed to read them from there,
+        // else it would keep reading the (stale) input file.
+        if map.is_some() && !shmem_fuzz {
+            println!("The target does not support shared memory fuzzing, falling back to the input file.");
+            map = None;
+        }
+
         println!(
             "ForkserverExecutor: program: {:?}, arguments: {:?}, use_stdin: {:?}",
             target,
@@ -707,11 +784,32 @@ impl<'a, SP> ForkserverExecutorBuilder<'a, SP> {
             forkserver,
             observers,
             map,
+            map_size,
             phantom: PhantomData,
             has_asan_observer: None, // initialized on first use
         })
     }
 
+    /// Enables persistent mode, or the deferred forkserver, if the binary of the target
+    /// contains the markers `afl-clang-fast` adds for `__AFL_LOOP`, or `__AFL_INIT`, as AFL++ does.
+    /// Modes set explicitly in the builder are kept as they are.
+    fn detect_afl_signatures(&mut self, program: &OsStr) {
+        let binary = match fs::read(program) {
+            Ok(binary) => binary,
+            // Not a path, e.g., a program in `PATH`, it won't be an AFL++ target anyway
+            Err(_) => return,
+        };
+        let contains = |sig: &[u8]| binary.windows(sig.len()).any(|window| window == sig);
+        if self.is_persistent.is_none() && contains(PERSIST_SIG) {
+            println!("Persistent mode binary detected.");
+            self.is_persistent = Some(true);
+        }
+        if self.is_deferred_frksrv.is_none() && contains(DEFER_SIG) {
+            println!("Deferred forkserver binary detected.");
+            self.is_deferred_frksrv = Some(true);
+        }
+    }
+
     /// Use autodict?
     #[must_use]
     pub fn autotokens(mut self, tokens: &'a mut Tokens) -> Self {
@@ -765,8 +863,8 @@ impl<'a> ForkserverExecutorBuilder<'a, StdShMemProvider> {
             envs: vec![],
             debug_child: false,
             use_stdin: true,
-            is_persistent: false,
-            is_deferred_frksrv: false,
+            is_persistent: None,
+            is_deferred_frksrv: None,
             autotokens: None,
             input_filename: None,
             shmem_provider: None,
@@ -858,16 +956,18 @@ impl<'a> ForkserverExecutorBuilder<'a, StdShMemProvider> {
     }
 
     #[must_use]
-    /// Call this if you want to run it under persistent mode; default is false
+    /// Call this if you want to run it under persistent mode; default is false.
+    /// Targets built with `afl-clang-fast` using `__AFL_LOOP` are detected automatically, unless this is set.
     pub fn is_persistent(mut self, is_persistent: bool) -> Self {
-        self.is_persistent = is_persistent;
+        self.is_persistent = Some(is_persistent);
         self
     }
 
     #[must_use]
-    /// Call this if the harness uses deferred forkserver mode; default is false
+    /// Call this if the harness uses deferred forkserver mode; default is false.
+    /// Targets built with `afl-clang-fast` using `__AFL_INIT` are detected automatically, unless this is set.
     pub fn is_deferred_frksrv(mut self, is_deferred_frksrv: bool) -> Self {
-        self.is_deferred_frksrv = is_deferred_frksrv;
+        self.is_deferred_frksrv = Some(is_deferred_frksrv);
         self
     }
 
@@ -918,16 +1018,7 @@ where
 
         // Write to testcase
         match &mut self.map {
-            Some(map) => {
-                let target_bytes = input.target_bytes();
-                let size = target_bytes.as_slice().len();
-                let size_in_bytes = size.to_ne_bytes();
-                // The first four bytes tells the size of the shmem.
-                map.as_mut_slice()[..SHMEM_FUZZ_HDR_SIZE]
-                    .copy_from_slice(&size_in_bytes[..SHMEM_FUZZ_HDR_SIZE]);
-                map.as_mut_slice()[SHMEM_FUZZ_HDR_SIZE..(SHMEM_FUZZ_HDR_SIZE + size)]
-                    .copy_from_slice(target_bytes.as_slice());
-            }
+            Some(map) => write_shmem_input(map, input.target_bytes().as_slice())?,
             None => {
                 self.input_file.write_buf(input.target_bytes().as_slice())?;
             }
@@ -967,7 +1058,7 @@ where
         self.forkserver.set_status(status);
 
         if libc::WIFSIGNALED(self.forkserver.status()) {
-            exit_kind = ExitKind::Crash;
+            exit_kind = ExitKind::Signal(libc::WTERMSIG(self.forkserver.status()));
             if self.has_asan_observer.is_none() {
                 self.has_asan_observer = Some(
                     self.observers()
@@ -1094,7 +1185,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::ffi::OsString;
+    use std::{env, ffi::OsString, fs, process};
 
     use serial_test::serial;
 
@@ -1102,9 +1193,12 @@ mod tests {
         bolts::{
             shmem::{ShMem, ShMemProvider, StdShMemProvider},
             tuples::tuple_list,
-            AsMutSlice,
+            AsMutSlice, AsSlice,
+        },
+        executors::forkserver::{
+            fs_opt_get_mapsize, write_shmem_input, ForkserverExecutorBuilder, FS_OPT_ENABLED,
+            FS_OPT_MAPSIZE, MAX_FILE, PERSIST_SIG, SHMEM_FUZZ_HDR_SIZE,
         },
-        executors::forkserver::ForkserverExecutorBuilder,
         observers::{ConstMapObserver, HitcountsMapObserver},
         Error,
     };
@@ -1144,4 +1238,40 @@ mod tests {
         };
         assert!(result);
     }
+    #[test]
+    #[serial]
+    fn test_shmem_input() {
+        let mut shmem_provider = StdShMemProvider::new().unwrap();
+        let mut map = shmem_provider
+            .new_shmem(MAX_FILE + SHMEM_FUZZ_HDR_SIZE)
+            .unwrap();
+
+        write_shmem_input(&mut map, b"AAAA").unwrap();
+        assert_eq!(map.as_slice()[..4], 4_u32.to_ne_bytes());
+        assert_eq!(&map.as_slice()[4..8], b"AAAA");
+
+        write_shmem_input(&mut map, &vec![0x41; MAX_FILE]).unwrap();
+        assert_eq!(map.as_slice()[..4], (MAX_FILE as u32).to_ne_bytes());
+
+        // Oversized inputs are not run truncated
+        assert!(write_shmem_input(&mut map, &vec![0x41; MAX_FILE + 1]).is_err());
+    }
+    #[test]
+    fn test_afl_handshake_options() {
+        let status = FS_OPT_ENABLED | FS_OPT_MAPSIZE | ((65536 - 1) << 1);
+        assert_eq!(fs_opt_get_mapsize(status), 65536);
+
+        let target = env::temp_dir().join(format!("libafl_persistent_target_{}", process::id()));
+        fs::write(&target, [b"\x7fELF...".as_slice(), PERSIST_SIG].concat()).unwrap();
+        let mut builder = ForkserverExecutorBuilder::new();
+        builder.detect_afl_signatures(target.as_os_str());
+        assert_eq!(builder.is_persistent, Some(true));
+        assert_eq!(builder.is_deferred_frksrv, None);
+
+        // An explicit choice in the builder wins over the detection
+        let mut builder = ForkserverExecutorBuilder::new().is_persistent(false);
+        builder.detect_afl_signatures(target.as_os_str());
+        assert_eq!(builder.is_persistent, Some(false));
+        fs::remove_file(&target).unwrap();
+    }
 }
@@ -0,0 +1,157 @@
+//! Structured initialization of a harness, such as `LLVMFuzzerInitialize`, before fuzzing starts.
+//!
+//! A failing init should never leave the client fuzzing a half-initialized target.
+//! The [`HarnessInit`] runs the init function according to a [`HarnessInitPolicy`],
+//! and reports each failure to the broker as [`Event::HarnessInitFailed`].
+
+use alloc::string::ToString;
+use core::time::Duration;
+use std::thread;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    events::{Event, EventFirer},
+    Error,
+};
+
+/// The default backoff before the first retry of a failing harness init
+pub const DEFAULT_INIT_BACKOFF: Duration = Duration::from_secs(1);
+
+/// What to do if the harness fails to initialize
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum HarnessInitPolicy {
+    /// Return the error, stopping the fuzzer
+    Abort,
+    /// Retry up to `retries` times, waiting `backoff` before the first retry,
+    /// doubling the wait after each further failure. Aborts if all retries fail.
+    Retry {
+        /// The number of retries after the first attempt
+        retries: usize,
+        /// The time to wait before the first retry
+        backoff: Duration,
+    },
+    /// Stop this client only, returning [`Error::ShuttingDown`], while the other clients keep fuzzing.
+    /// The broker still gets to know about the failure.
+    SkipClient,
+}
+
+impl Default for HarnessInitPolicy {
+    fn default() -> Self {
+        Self::Abort
+    }
+}
+
+/// Runs the initialization of a harness according to a [`HarnessInitPolicy`].
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct HarnessInit {
+    policy: HarnessInitPolicy,
+}
+
+impl HarnessInit {
+    /// Creates a new [`HarnessInit`] with the given `policy`
+    #[must_use]
+    pub fn new(policy: HarnessInitPolicy) -> Self {
+        Self { policy }
+    }
+
+    /// Creates a new [`HarnessInit`], retrying `retries` times, starting with a [`DEFAULT_INIT_BACKOFF`]
+    #[must_use]
+    pub fn with_retries(retries: usize) -> Self {
+        Self::new(HarnessInitPolicy::Retry {
+            retries,
+            backoff: DEFAULT_INIT_BACKOFF,
+        })
+    }
+
+    /// The policy of this [`HarnessInit`]
+    #[must_use]
+    pub fn policy(&self) -> &HarnessInitPolicy {
+        &self.policy
+    }
+
+    /// Runs `init` until it succeeds, or the policy gives up.
+    /// Each failed attempt fires an [`Event::HarnessInitFailed`] through `mgr`.
+    pub fn run<EM, F>(&self, state: &mut EM::State, mgr: &mut EM, mut init: F) -> Result<(), Error>
+    where
+        EM: EventFirer,
+        F: FnMut() -> Result<(), Error>,
+    {
+        let (retries, mut backoff) = match self.policy {
+            HarnessInitPolicy::Retry { retries, backoff } => (retries, backoff),
+            HarnessInitPolicy::Abort | HarnessInitPolicy::SkipClient => (0, Duration::ZERO),
+        };
+
+        let mut attempt = 0;
+        loop {
+            let err = match init() {
+                Ok(()) => return Ok(()),
+                Err(err) => err,
+            };
+            attempt += 1;
+            mgr.fire(
+                state,
+                Event::HarnessInitFailed {
+                    attempt,
+                    message: err.to_string(),
+                },
+            )?;
+
+            if attempt > retries {
+                return match self.policy {
+                    HarnessInitPolicy::SkipClient => Err(Error::shutting_down()),
+                    _ => Err(err),
+                };
+            }
+            thread::sleep(backoff);
+            backoff *= 2;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        events::NopEventManager,
+        executors::init::{HarnessInit, HarnessInitPolicy},
+        inputs::BytesInput,
+        state::NopState,
+        Error,
+    };
+
+    #[test]
+    fn test_harness_init() {
+        let mut state = NopState::<BytesInput>::new();
+        let mut mgr = NopEventManager::new();
+
+        // An init failing `n` times before succeeding
+        let failing = |mut n: usize| {
+            move || {
+                if n > 0 {
+                    n -= 1;
+                    Err(Error::unknown("LLVMFuzzerInitialize returned -1"))
+                } else {
+                    Ok(())
+                }
+            }
+        };
+
+        let retry = HarnessInit::new(HarnessInitPolicy::Retry {
+            retries: 2,
+            backoff: Duration::ZERO,
+        });
+        retry.run(&mut state, &mut mgr, failing(2)).unwrap();
+        assert!(retry.run(&mut state, &mut mgr, failing(3)).is_err());
+
+        assert!(matches!(
+            HarnessInit::default().run(&mut state, &mut mgr, failing(1)),
+            Err(Error::Unknown(..))
+        ));
+        assert!(matches!(
+            HarnessInit::new(HarnessInitPolicy::SkipClient).run(&mut state, &mut mgr, failing(1)),
+            Err(Error::ShuttingDown)
+        ));
+    }
+}
@@ -6,6 +6,10 @@
 use alloc::boxed::Box;
 #[cfg(all(unix, feature = "std"))]
 use alloc::vec::Vec;
+#[cfg(all(target_os = "linux", feature = "std"))]
+use core::ptr::addr_of_mut;
+#[cfg(any(unix, feature = "std"))]
+use core::time::Duration;
 use core::{
     borrow::BorrowMut,
     ffi::c_void,
@@ -13,8 +17,6 @@ use core::{
     marker::PhantomData,
     ptr::{self, null_mut},
 };
-#[cfg(all(target_os = "linux", feature = "std"))]
-use core::{ptr::addr_of_mut, time::Duration};
 #[cfg(any(unix, all(windows, feature = "std")))]
 use core::{
     ptr::write_volatile,
@@ -22,17 +24,21 @@ use core::{
 };
 #[cfg(all(feature = "std", unix))]
 use std::intrinsics::transmute;
+#[cfg(all(feature = "std", unix))]
+use std::path::Path;
 
 #[cfg(all(feature = "std", unix))]
 use libc::siginfo_t;
 #[cfg(all(feature = "std", unix))]
 use nix::{
-    sys::wait::{waitpid, WaitStatus},
+    sys::wait::WaitStatus,
     unistd::{fork, ForkResult},
 };
 #[cfg(windows)]
 use windows::Win32::System::Threading::SetThreadStackGuarantee;
 
+#[cfg(all(target_vendor = "apple", feature = "std"))]
+use crate::bolts::os::mach_exceptions::setup_mach_exception_handler;
 #[cfg(unix)]
 use crate::bolts::os::unix_signals::setup_signal_handler;
 #[cfg(all(feature = "std", unix))]
@@ -40,7 +46,15 @@ use crate::bolts::os::unix_signals::{ucontext_t, Handler, Signal};
 #[cfg(all(windows, feature = "std"))]
 use crate::bolts::os::windows_exceptions::setup_exception_handler;
 #[cfg(all(feature = "std", unix))]
-use crate::bolts::shmem::ShMemProvider;
+use crate::bolts::shmem::{ShMem, ShMemProvider};
+#[cfg(all(feature = "std", unix))]
+use crate::bolts::{os::dl::SharedLibrary, AsSlice};
+#[cfg(any(unix, feature = "std"))]
+use crate::executors::TimeoutExecutor;
+#[cfg(all(feature = "std", unix))]
+use crate::inputs::HasTargetBytes;
+#[cfg(all(feature = "std", unix))]
+use crate::observers::rusage::wait_child;
 use crate::{
     events::{EventFirer, EventRestarter},
     executors::{Executor, ExitKind, HasObservers},
@@ -64,6 +78,8 @@ pub type OwnedInProcessExecutor<OT, S> = GenericInProcessExecutor<
 >;
 
 /// The inmem executor simply calls a target function, then returns afterwards.
+/// On `MacOS`, if a runtime in the target handles the Mach exceptions of crashes itself,
+/// create it with `with_mach_exception_handler` to still catch them.
 #[allow(dead_code)]
 pub struct GenericInProcessExecutor<H, HB, OT, S>
 where
@@ -233,6 +249,138 @@ where
     pub fn handlers_mut(&mut self) -> &mut InProcessHandlers {
         &mut self.handlers
     }
+
+    /// Also routes the Mach exceptions of crashes to the crash handler of this executor.
+    /// Use it if a runtime in the target, such as a crash reporter, handles the Mach exceptions itself,
+    /// else its crashes never become signals.
+    #[cfg(all(target_vendor = "apple", feature = "std"))]
+    pub fn with_mach_exception_handler(self) -> Result<Self, Error> {
+        self.handlers.catch_mach_exceptions()?;
+        Ok(self)
+    }
+}
+
+#[cfg(any(unix, feature = "std"))]
+impl<H, HB, OT, S> GenericInProcessExecutor<H, HB, OT, S>
+where
+    H: FnMut(&<S as UsesInput>::Input) -> ExitKind + ?Sized,
+    HB: BorrowMut<H>,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    /// Wraps this executor in a [`TimeoutExecutor`], arming a timer before each run.
+    /// Runs taking longer than `exec_tmout` are interrupted (by `SIGALRM`, on `unix`),
+    /// and the timeout handler reports their input as [`ExitKind::Timeout`], instead of hanging the client forever.
+    pub fn with_timeout(self, exec_tmout: Duration) -> TimeoutExecutor<Self> {
+        TimeoutExecutor::new(self, exec_tmout)
+    }
+}
+
+/// A `libFuzzer`-style harness function: `int LLVMFuzzerTestOneInput(const uint8_t *data, size_t size)`
+#[cfg(all(unix, feature = "std"))]
+pub type LibfuzzerHarnessFn = unsafe extern "C" fn(*const u8, usize) -> i32;
+
+/// The [`ExitKind::Custom`] kind of runs for which a [`LibfuzzerHarnessFn`] returned `-1`,
+/// which `libFuzzer` harnesses use to reject an input, to keep it out of the corpus.
+#[cfg(all(unix, feature = "std"))]
+pub const LIBFUZZER_REJECTED_INPUT: u32 = u32::MAX;
+
+#[cfg(all(unix, feature = "std"))]
+impl<OT, S> OwnedInProcessExecutor<OT, S>
+where
+    OT: ObserversTuple<S>,
+    S: HasSolutions + HasClientPerfMonitor,
+    S::Input: HasTargetBytes,
+{
+    /// Create a new in mem executor for the `libFuzzer`-style harness function `name`,
+    /// looked up in the running program with `dlsym`, for example `LLVMFuzzerTestOneInput`.
+    pub fn with_dlsym<EM, OF, Z>(
+        name: &str,
+        observers: OT,
+        fuzzer: &mut Z,
+        state: &mut S,
+        event_mgr: &mut EM,
+    ) -> Result<Self, Error>
+    where
+        Self: Executor<EM, Z, State = S>,
+        EM: EventFirer<State = S> + EventRestarter,
+        OF: Feedback<S>,
+        Z: HasObjective<OF, State = S>,
+    {
+        Self::with_library(
+            SharedLibrary::this()?,
+            name,
+            observers,
+            fuzzer,
+            state,
+            event_mgr,
+        )
+    }
+
+    /// Create a new in mem executor for the `libFuzzer`-style harness function `name`,
+    /// in the shared object at `path`, loaded with `dlopen`.
+    /// This way, a generic fuzzer can fuzz any harness passed on the command line.
+    pub fn with_dlopen<EM, OF, P, Z>(
+        path: P,
+        name: &str,
+        observers: OT,
+        fuzzer: &mut Z,
+        state: &mut S,
+        event_mgr: &mut EM,
+    ) -> Result<Self, Error>
+    where
+        Self: Executor<EM, Z, State = S>,
+        EM: EventFirer<State = S> + EventRestarter,
+        OF: Feedback<S>,
+        P: AsRef<Path>,
+        Z: HasObjective<OF, State = S>,
+    {
+        Self::with_library(
+            SharedLibrary::open(path)?,
+            name,
+            observers,
+            fuzzer,
+            state,
+            event_mgr,
+        )
+    }
+
+    /// Create a new in mem executor for the `libFuzzer`-style harness function `name` in an already loaded `library`,
+    /// for example after calling its `LLVMFuzzerInitialize`.
+    /// The executor keeps the `library` loaded.
+    ///
+    /// A non-zero return value of the harness is reported as [`ExitKind::Custom`] with the value as kind,
+    /// [`LIBFUZZER_REJECTED_INPUT`] for a rejected input. Wrap the feedback in a
+    /// `feedback_and_fast!(feedback_not!(CustomExitKindFeedback::new(&[LIBFUZZER_REJECTED_INPUT])), ..)`
+    /// to keep rejected inputs out of the corpus.
+    pub fn with_library<EM, OF, Z>(
+        library: SharedLibrary,
+        name: &str,
+        observers: OT,
+        fuzzer: &mut Z,
+        state: &mut S,
+        event_mgr: &mut EM,
+    ) -> Result<Self, Error>
+    where
+        Self: Executor<EM, Z, State = S>,
+        EM: EventFirer<State = S> + EventRestarter,
+        OF: Feedback<S>,
+        Z: HasObjective<OF, State = S>,
+    {
+        let harness_fn: LibfuzzerHarnessFn = unsafe { transmute(library.symbol(name)?) };
+        let harness = move |input: &S::Input| {
+            // Keep the library loaded as long as the harness lives.
+            let _library = &library;
+            let target = input.target_bytes();
+            let buf = target.as_slice();
+            #[allow(clippy::cast_sign_loss)]
+            match unsafe { harness_fn(buf.as_ptr(), buf.len()) } {
+                0 => ExitKind::Ok,
+                ret => ExitKind::Custom(ret as u32),
+            }
+        };
+        Self::new(Box::new(harness), observers, fuzzer, state, event_mgr)
+    }
 }
 
 /// The struct has [`InProcessHandlers`].
@@ -243,9 +391,10 @@ pub trait HasInProcessHandlers {
 }
 
 #[cfg(windows)]
-impl<'a, H, OT, S> HasInProcessHandlers for InProcessExecutor<'a, H, OT, S>
+impl<H, HB, OT, S> HasInProcessHandlers for GenericInProcessExecutor<H, HB, OT, S>
 where
-    H: FnMut(&S::Input) -> ExitKind,
+    H: FnMut(&S::Input) -> ExitKind + ?Sized,
+    HB: BorrowMut<H>,
     OT: ObserversTuple<S>,
     S: UsesInput,
 {
@@ -378,6 +527,16 @@ impl InProcessHandlers {
         Ok(Self {})
     }
 
+    /// Routes the Mach exceptions of crashes to these handlers, as the equivalent signal,
+    /// see [`setup_mach_exception_handler`].
+    /// The setup is global, and only takes effect once per process.
+    #[cfg(all(target_vendor = "apple", feature = "std"))]
+    #[allow(clippy::unused_self)]
+    pub fn catch_mach_exceptions(&self) -> Result<(), Error> {
+        // The crash handlers only use the global state, so they may run on the exception thread
+        unsafe { setup_mach_exception_handler() }
+    }
+
     /// Replace the handlers with `nop` handlers, deactivating the handlers
     #[must_use]
     pub fn nop() -> Self {
@@ -419,6 +578,9 @@ pub(crate) struct InProcessExecutorHandlerData {
     pub(crate) critical: *mut c_void,
     #[cfg(all(windows, feature = "std"))]
     pub(crate) timeout_input_ptr: *mut c_void,
+    /// The thread running the target, suspended by the timeout handler
+    #[cfg(all(windows, feature = "std"))]
+    pub(crate) fuzz_thread: *mut c_void,
 }
 
 unsafe impl Send for InProcessExecutorHandlerData {}
@@ -494,6 +656,8 @@ pub(crate) static mut GLOBAL_STATE: InProcessExecutorHandlerData = InProcessExec
     critical: null_mut(),
     #[cfg(all(windows, feature = "std"))]
     timeout_input_ptr: null_mut(),
+    #[cfg(all(windows, feature = "std"))]
+    fuzz_thread: null_mut(),
 };
 
 /// Get the inprocess [`crate::state::State`]
@@ -575,7 +739,7 @@ mod unix_signal_handler {
             unsafe {
                 let data = &mut GLOBAL_STATE;
                 match signal {
-                    Signal::SigUser2 | Signal::SigAlarm => {
+                    Signal::SigUser1 | Signal::SigUser2 | Signal::SigAlarm => {
                         if !data.timeout_handler.is_null() {
                             let func: HandlerFuncPtr = transmute(data.timeout_handler);
                             (func)(signal, info, context, data);
@@ -594,6 +758,7 @@ mod unix_signal_handler {
         fn signals(&self) -> Vec<Signal> {
             vec![
                 Signal::SigAlarm,
+                Signal::SigUser1,
                 Signal::SigUser2,
                 Signal::SigAbort,
                 Signal::SigBus,
@@ -678,7 +843,7 @@ mod unix_signal_handler {
 
     #[cfg(unix)]
     pub(crate) unsafe fn inproc_timeout_handler<E, EM, OF, Z>(
-        _signal: Signal,
+        signal: Signal,
         _info: siginfo_t,
         _context: &mut ucontext_t,
         data: &mut InProcessExecutorHandlerData,
@@ -691,7 +856,12 @@ mod unix_signal_handler {
     {
         if !data.is_valid() {
             #[cfg(feature = "std")]
-            println!("TIMEOUT or SIGUSR2 happened, but currently not fuzzing.");
+            println!("TIMEOUT, SIGUSR1 or SIGUSR2 happened, but currently not fuzzing.");
+            return;
+        }
+        // A late `SIGUSR1` of the `OomExecutor`, for a run that is over
+        #[cfg(feature = "std")]
+        if signal == Signal::SigUser1 && !crate::executors::oom::oom_in_current_run() {
             return;
         }
 
@@ -703,23 +873,38 @@ mod unix_signal_handler {
 
         let input = data.take_current_input::<<E::State as UsesInput>::Input>();
 
+        // An [`crate::executors::OomExecutor`] interrupts runs that exceed its memory limit with a `SIGUSR1`
+        let exit_kind = if signal == Signal::SigUser1 {
+            ExitKind::Oom
+        } else {
+            ExitKind::Timeout
+        };
+
+        // The broker restarts hung clients with a `SIGUSR2`. The run did not hit the timeout, so its input is no objective.
+        let restarted = signal == Signal::SigUser2;
+
         #[cfg(feature = "std")]
-        println!("Timeout in fuzz run.");
+        if restarted {
+            println!("Restarted by the broker in fuzz run.");
+        } else {
+            println!("{exit_kind:?} in fuzz run.");
+        }
         #[cfg(feature = "std")]
         let _res = stdout().flush();
 
         observers
-            .post_exec_all(state, input, &ExitKind::Timeout)
+            .post_exec_all(state, input, &exit_kind)
             .expect("Observers post_exec_all failed");
 
-        let interesting = fuzzer
-            .objective_mut()
-            .is_interesting(state, event_mgr, input, observers, &ExitKind::Timeout)
-            .expect("In timeout handler objective failure.");
+        let interesting = !restarted
+            && fuzzer
+                .objective_mut()
+                .is_interesting(state, event_mgr, input, observers, &exit_kind)
+                .expect("In timeout handler objective failure.");
 
         if interesting {
             let mut new_testcase = Testcase::new(input.clone());
-            new_testcase.add_metadata(ExitKind::Timeout);
+            new_testcase.add_metadata(exit_kind);
             fuzzer
                 .objective_mut()
                 .append_metadata(state, &mut new_testcase)
@@ -784,31 +969,31 @@ mod unix_signal_handler {
 
             let input = data.take_current_input::<<E::State as UsesInput>::Input>();
 
+            let exit_kind = ExitKind::Signal(signal as i32);
             observers
-                .post_exec_all(state, input, &ExitKind::Crash)
+                .post_exec_all(state, input, &exit_kind)
                 .expect("Observers post_exec_all failed");
 
             #[cfg(feature = "std")]
             eprintln!("Child crashed!");
 
             #[cfg(all(feature = "std", unix))]
-            {
-                let mut writer = std::io::BufWriter::new(std::io::stderr());
-                writeln!(writer, "input: {:?}", input.generate_name(0)).unwrap();
-                crate::bolts::minibsod::generate_minibsod(&mut writer, signal, _info, _context)
-                    .unwrap();
-                writer.flush().unwrap();
-            }
+            eprintln!("input: {:?}", input.generate_name(0));
+            #[cfg(all(feature = "std", unix))]
+            let minibsod =
+                crate::bolts::minibsod::generate_minibsod_to_stderr(signal, _info, _context);
 
             let interesting = fuzzer
                 .objective_mut()
-                .is_interesting(state, event_mgr, input, observers, &ExitKind::Crash)
+                .is_interesting(state, event_mgr, input, observers, &exit_kind)
                 .expect("In crash handler objective failure.");
 
             if interesting {
                 let new_input = input.clone();
                 let mut new_testcase = Testcase::new(new_input);
-                new_testcase.add_metadata(ExitKind::Crash);
+                new_testcase.add_metadata(exit_kind);
+                #[cfg(all(feature = "std", unix))]
+                new_testcase.add_metadata(crate::bolts::minibsod::MiniBsodMetadata { minibsod });
                 fuzzer
                     .objective_mut()
                     .append_metadata(state, &mut new_testcase)
@@ -935,8 +1120,14 @@ mod windows_exception_handler {
         }
     }
 
-    use windows::Win32::System::Threading::{
-        EnterCriticalSection, LeaveCriticalSection, RTL_CRITICAL_SECTION,
+    use windows::Win32::{
+        Foundation::HANDLE,
+        System::{
+            Memory::{GetProcessHeap, HeapLock, HeapUnlock},
+            Threading::{
+                EnterCriticalSection, LeaveCriticalSection, SuspendThread, RTL_CRITICAL_SECTION,
+            },
+        },
     };
 
     use crate::inputs::UsesInput;
@@ -1067,6 +1258,20 @@ mod windows_exception_handler {
                 #[cfg(feature = "std")]
                 let _res = stdout().flush();
 
+                // Unlike a signal handler, the timer runs on its own thread, while the target keeps running.
+                // Stop it before touching the observers and the state it uses.
+                // Hold the lock of the process heap, which backs the global allocator, while stopping it,
+                // so the target is never stopped in the middle of an allocation, and the allocations below can't deadlock.
+                if !data.fuzz_thread.is_null() {
+                    let locked_heap = GetProcessHeap()
+                        .ok()
+                        .filter(|heap| HeapLock(*heap).as_bool());
+                    SuspendThread(HANDLE(data.fuzz_thread as isize));
+                    if let Some(heap) = locked_heap {
+                        HeapUnlock(heap);
+                    }
+                }
+
                 let input = (data.timeout_input_ptr as *const <E::State as UsesInput>::Input)
                     .as_ref()
                     .unwrap();
@@ -1436,7 +1641,60 @@ impl Handler for InProcessForkExecutorGlobalData {
     }
 }
 
+/// Allocates the shared map a forked child passes the [`ExitKind`] of the harness back to the parent in
+#[cfg(all(feature = "std", unix))]
+fn new_exit_kind_shmem<SP>(shmem_provider: &mut SP) -> Result<SP::ShMem, Error>
+where
+    SP: ShMemProvider,
+{
+    shmem_provider.new_shmem(core::mem::size_of::<Option<ExitKind>>())
+}
+
+/// Ends a forked child, passing the `exit_kind` of the harness to the parent in the `exit_kind_shmem`.
+#[cfg(all(feature = "std", unix))]
+fn exit_child<SHM>(exit_kind_shmem: &mut SHM, exit_kind: ExitKind) -> !
+where
+    SHM: ShMem,
+{
+    unsafe {
+        *exit_kind_shmem.as_object_mut::<Option<ExitKind>>() = Some(exit_kind);
+        // Don't run the `atexit` handlers of the parent
+        libc::_exit(0)
+    }
+}
+
+/// The [`ExitKind`] of a forked child, the one the harness returned in the `exit_kind_shmem`,
+/// or, if the harness did not return, the one from its wait `status`.
+/// Timeouts use `SIGALRM`, or `SIGUSR2`, and `SIGKILL`, the signal of the OOM killer, means [`ExitKind::Oom`].
+/// Any other signal is an [`ExitKind::Signal`].
+#[cfg(all(feature = "std", unix))]
+fn child_exit_kind<SHM>(exit_kind_shmem: &SHM, status: WaitStatus) -> ExitKind
+where
+    SHM: ShMem,
+{
+    if let Some(exit_kind) = unsafe { *exit_kind_shmem.as_object::<Option<ExitKind>>() } {
+        return exit_kind;
+    }
+    let signal = match status {
+        WaitStatus::Signaled(_, signal, _) => signal as i32,
+        // Signal exit codes, from our own handlers
+        WaitStatus::Exited(_, code) if code > 128 && code < 160 => code - 128,
+        _ => return ExitKind::Ok,
+    };
+    if signal == Signal::SigAlarm as i32 || signal == Signal::SigUser2 as i32 {
+        ExitKind::Timeout
+    } else if signal == Signal::SigKill as i32 {
+        ExitKind::Oom
+    } else {
+        ExitKind::Signal(signal)
+    }
+}
+
 /// [`InProcessForkExecutor`] is an executor that forks the current process before each execution.
+/// The harness runs in the child, so crashes, leaks and corrupted global state never reach the fuzzer.
+/// Observers have to keep their maps in shared memory, e.g., using the `shmem_provider`,
+/// to pass the coverage back to the parent.
+/// The [`ExitKind`] returned by the harness, or the way the child died, is the result of the run.
 #[cfg(all(feature = "std", unix))]
 pub struct InProcessForkExecutor<'a, H, OT, S, SP>
 where
@@ -1447,6 +1705,7 @@ where
 {
     harness_fn: &'a mut H,
     shmem_provider: SP,
+    exit_kind_shmem: SP::ShMem,
     observers: OT,
     handlers: InChildProcessHandlers,
     phantom: PhantomData<S>,
@@ -1463,6 +1722,7 @@ where
 {
     harness_fn: &'a mut H,
     shmem_provider: SP,
+    exit_kind_shmem: SP::ShMem,
     observers: OT,
     handlers: InChildProcessHandlers,
     itimerspec: libc::itimerspec,
@@ -1534,7 +1794,6 @@ where
     SP: ShMemProvider,
     Z: UsesState<State = S>,
 {
-    #[allow(unreachable_code)]
     #[inline]
     fn run_target(
         &mut self,
@@ -1544,6 +1803,8 @@ where
         input: &Self::Input,
     ) -> Result<ExitKind, Error> {
         unsafe {
+            // The child sets the exit kind only if the harness returns
+            *self.exit_kind_shmem.as_object_mut::<Option<ExitKind>>() = None;
             self.shmem_provider.pre_fork()?;
             match fork() {
                 Ok(ForkResult::Child) => {
@@ -1556,35 +1817,21 @@ where
                         .pre_exec_child_all(state, input)
                         .expect("Failed to run post_exec on observers");
 
-                    (self.harness_fn)(input);
+                    let exit_kind = (self.harness_fn)(input);
 
                     self.observers
-                        .post_exec_child_all(state, input, &ExitKind::Ok)
+                        .post_exec_child_all(state, input, &exit_kind)
                         .expect("Failed to run post_exec on observers");
 
-                    std::process::exit(0);
-
-                    Ok(ExitKind::Ok)
+                    exit_child(&mut self.exit_kind_shmem, exit_kind);
                 }
                 Ok(ForkResult::Parent { child }) => {
                     // Parent
                     // println!("from parent {} child is {}", std::process::id(), child);
                     self.shmem_provider.post_fork(false)?;
 
-                    let res = waitpid(child, None)?;
-
-                    match res {
-                        WaitStatus::Signaled(_, _, _) => Ok(ExitKind::Crash),
-                        WaitStatus::Exited(_, code) => {
-                            if code > 128 && code < 160 {
-                                // Signal exit codes
-                                Ok(ExitKind::Crash)
-                            } else {
-                                Ok(ExitKind::Ok)
-                            }
-                        }
-                        _ => Ok(ExitKind::Ok),
-                    }
+                    let status = wait_child(child)?;
+                    Ok(child_exit_kind(&self.exit_kind_shmem, status))
                 }
                 Err(e) => Err(Error::from(e)),
             }
@@ -1602,7 +1849,6 @@ where
     SP: ShMemProvider,
     Z: UsesState<State = S>,
 {
-    #[allow(unreachable_code)]
     #[inline]
     fn run_target(
         &mut self,
@@ -1612,6 +1858,8 @@ where
         input: &Self::Input,
     ) -> Result<ExitKind, Error> {
         unsafe {
+            // The child sets the exit kind only if the harness returns
+            *self.exit_kind_shmem.as_object_mut::<Option<ExitKind>>() = None;
             self.shmem_provider.pre_fork()?;
             match fork() {
                 Ok(ForkResult::Child) => {
@@ -1629,50 +1877,22 @@ where
                     // we can't do this from the parent, timerid is unique to each process.
                     libc::timer_create(libc::CLOCK_MONOTONIC, null_mut(), addr_of_mut!(timerid));
 
-                    println!("Set timer! {:#?} {timerid:#?}", self.itimerspec);
-                    let v =
-                        libc::timer_settime(timerid, 0, addr_of_mut!(self.itimerspec), null_mut());
-                    println!("{v:#?} {}", nix::errno::errno());
-                    (self.harness_fn)(input);
+                    libc::timer_settime(timerid, 0, addr_of_mut!(self.itimerspec), null_mut());
+                    let exit_kind = (self.harness_fn)(input);
 
                     self.observers
-                        .post_exec_child_all(state, input, &ExitKind::Ok)
+                        .post_exec_child_all(state, input, &exit_kind)
                         .expect("Failed to run post_exec on observers");
 
-                    std::process::exit(0);
-
-                    Ok(ExitKind::Ok)
+                    exit_child(&mut self.exit_kind_shmem, exit_kind);
                 }
                 Ok(ForkResult::Parent { child }) => {
                     // Parent
                     // println!("from parent {} child is {}", std::process::id(), child);
                     self.shmem_provider.post_fork(false)?;
 
-                    let res = waitpid(child, None)?;
-                    println!("{res:#?}");
-                    match res {
-                        WaitStatus::Signaled(_, signal, _) => match signal {
-                            nix::sys::signal::Signal::SIGALRM
-                            | nix::sys::signal::Signal::SIGUSR2 => Ok(ExitKind::Timeout),
-                            _ => Ok(ExitKind::Crash),
-                        },
-                        WaitStatus::Exited(_, code) => {
-                            if code > 128 && code < 160 {
-                                // Signal exit codes
-                                let signal = code - 128;
-                                if signal == Signal::SigAlarm as libc::c_int
-                                    || signal == Signal::SigUser2 as libc::c_int
-                                {
-                                    Ok(ExitKind::Timeout)
-                                } else {
-                                    Ok(ExitKind::Crash)
-                                }
-                            } else {
-                                Ok(ExitKind::Ok)
-                            }
-                        }
-                        _ => Ok(ExitKind::Ok),
-                    }
+                    let status = wait_child(child)?;
+                    Ok(child_exit_kind(&self.exit_kind_shmem, status))
                 }
                 Err(e) => Err(Error::from(e)),
             }
@@ -1695,7 +1915,7 @@ where
         _fuzzer: &mut Z,
         _state: &mut S,
         _event_mgr: &mut EM,
-        shmem_provider: SP,
+        mut shmem_provider: SP,
     ) -> Result<Self, Error>
     where
         EM: EventFirer<State = S> + EventRestarter,
@@ -1704,9 +1924,11 @@ where
         Z: HasObjective<OF, State = S>,
     {
         let handlers = InChildProcessHandlers::new::<Self>()?;
+        let exit_kind_shmem = new_exit_kind_shmem(&mut shmem_provider)?;
         Ok(Self {
             harness_fn,
             shmem_provider,
+            exit_kind_shmem,
             observers,
             handlers,
             phantom: PhantomData,
@@ -1742,7 +1964,7 @@ where
         _state: &mut S,
         _event_mgr: &mut EM,
         timeout: Duration,
-        shmem_provider: SP,
+        mut shmem_provider: SP,
     ) -> Result<Self, Error>
     where
         EM: EventFirer<State = S> + EventRestarter<State = S>,
@@ -1765,9 +1987,12 @@ where
             it_value,
         };
 
+        let exit_kind_shmem = new_exit_kind_shmem(&mut shmem_provider)?;
+
         Ok(Self {
             harness_fn,
             shmem_provider,
+            exit_kind_shmem,
             observers,
             handlers,
             itimerspec,
@@ -1938,11 +2163,83 @@ pub mod child_signal_handlers {
     }
 }
 
+/// Hooks to intercept `AddressSanitizer` error reports in the [`InProcessExecutor`].
+///
+/// Once [`setup_asan_callbacks`](asan_hooks::setup_asan_callbacks) is called, the report of an `ASan` error is captured,
+/// and instead of exiting, `ASan` aborts the target, so that the crash handler of the [`InProcessExecutor`]
+/// treats it as [`ExitKind::Crash`].
+/// The report can then be attached to the testcase using the [`crate::feedbacks::AsanErrorsFeedback`].
+#[cfg(all(unix, feature = "std"))]
+pub mod asan_hooks {
+    use alloc::string::String;
+    use core::{ffi::c_void, mem::transmute};
+    use std::ffi::CStr;
+
+    use libc::c_char;
+
+    use crate::Error;
+
+    /// The report of the last `ASan` error, if any
+    static mut ASAN_REPORT: Option<String> = None;
+
+    type ErrorReportCallback = unsafe extern "C" fn(*const c_char);
+    type DeathCallback = unsafe extern "C" fn();
+
+    unsafe extern "C" fn asan_error_report_callback(report: *const c_char) {
+        if !report.is_null() {
+            ASAN_REPORT = Some(CStr::from_ptr(report).to_string_lossy().into_owned());
+        }
+    }
+
+    unsafe extern "C" fn asan_death_callback() {
+        // Raise `SIGABRT` so that the crash handler of the executor takes over.
+        libc::abort();
+    }
+
+    /// Looks up an `ASan` interface function, which is only present if the target is linked against `ASan`.
+    /// The `name` has to be nul-terminated.
+    unsafe fn asan_symbol(name: &str) -> Result<*mut c_void, Error> {
+        let sym = libc::dlsym(libc::RTLD_DEFAULT, name.as_ptr() as *const c_char);
+        if sym.is_null() {
+            Err(Error::unsupported(format!(
+                "{} not found, is the target compiled with ASan?",
+                name.trim_end_matches('\0')
+            )))
+        } else {
+            Ok(sym)
+        }
+    }
+
+    /// Registers `__asan_set_error_report_callback` and `__sanitizer_set_death_callback`,
+    /// to capture `ASan` error reports and turn them into crashes of the [`super::InProcessExecutor`].
+    ///
+    /// Returns [`Error::Unsupported`] if the target is not linked against `ASan`.
+    pub fn setup_asan_callbacks() -> Result<(), Error> {
+        unsafe {
+            let set_error_report_callback: unsafe extern "C" fn(ErrorReportCallback) =
+                transmute(asan_symbol("__asan_set_error_report_callback\0")?);
+            let set_death_callback: unsafe extern "C" fn(DeathCallback) =
+                transmute(asan_symbol("__sanitizer_set_death_callback\0")?);
+            set_error_report_callback(asan_error_report_callback);
+            set_death_callback(asan_death_callback);
+        }
+        Ok(())
+    }
+
+    /// Takes the report of the last `ASan` error, if any
+    #[must_use]
+    pub fn take_asan_report() -> Option<String> {
+        unsafe { ASAN_REPORT.take() }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use core::marker::PhantomData;
 
-    #[cfg(all(feature = "std", feature = "fork", unix))]
+    #[cfg(unix)]
+    use serde::{Deserialize, Serialize};
+    #[cfg(unix)]
     use serial_test::serial;
 
     use crate::{
@@ -1953,6 +2250,21 @@ mod tests {
         state::NopState,
         NopFuzzer,
     };
+    #[cfg(unix)]
+    use crate::{
+        bolts::{
+            os::{fork, ForkResult},
+            rands::StdRand,
+            tuples::Named,
+        },
+        corpus::InMemoryCorpus,
+        events::EventFirer,
+        feedbacks::{ConstFeedback, Feedback, TimeoutFeedback},
+        observers::{Observer, ObserversTuple},
+        schedulers::QueueScheduler,
+        state::{HasClientPerfMonitor, StdState},
+        Error, StdFuzzer,
+    };
 
     impl UsesInput for () {
         type Input = NopInput;
@@ -1979,24 +2291,222 @@ mod tests {
             .unwrap();
     }
 
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_inmem_exec_with_timeout() {
+        use core::time::Duration;
+
+        let mut harness = |_buf: &NopInput| ExitKind::Ok;
+        let in_process_executor = InProcessExecutor::<_, _, _> {
+            harness_fn: &mut harness,
+            observers: tuple_list!(),
+            handlers: InProcessHandlers::nop(),
+            phantom: PhantomData,
+        };
+        let mut executor = in_process_executor.with_timeout(Duration::from_millis(500));
+        let input = NopInput {};
+        for _ in 0..4 {
+            // Runs well within the timeout, which is disarmed after each of them
+            assert_eq!(
+                executor
+                    .run_target(
+                        &mut NopFuzzer::new(),
+                        &mut NopState::new(),
+                        &mut NopEventManager::new(),
+                        &input,
+                    )
+                    .unwrap(),
+                ExitKind::Ok
+            );
+        }
+        std::thread::sleep(Duration::from_millis(600));
+
+        // A run exceeding the timeout ends in the timeout handler, which exits the process, so hang in a child
+        match unsafe { fork() }.unwrap() {
+            ForkResult::Parent(child) => assert_eq!(child.status(), 0),
+            ForkResult::Child => {
+                check_inmem_exec_hang();
+                unsafe { libc::_exit(2) }
+            }
+        }
+    }
+
+    /// Exits the process with `0` if the timeout handler reports the run as [`ExitKind::Timeout`], else with `1`
+    #[cfg(unix)]
+    #[derive(Debug, Serialize, Deserialize)]
+    struct ExitOnTimeoutObserver;
+
+    #[cfg(unix)]
+    impl Named for ExitOnTimeoutObserver {
+        fn name(&self) -> &str {
+            "exit_on_timeout"
+        }
+    }
+
+    #[cfg(unix)]
+    impl<S> Observer<S> for ExitOnTimeoutObserver
+    where
+        S: UsesInput,
+    {
+        fn post_exec(
+            &mut self,
+            _state: &mut S,
+            _input: &S::Input,
+            exit_kind: &ExitKind,
+        ) -> Result<(), Error> {
+            unsafe { libc::_exit(i32::from(*exit_kind != ExitKind::Timeout)) }
+        }
+    }
+
+    /// Runs a harness sleeping past the timeout, with the in-process handlers set up
+    #[cfg(unix)]
+    fn check_inmem_exec_hang() {
+        use core::time::Duration;
+
+        let mut harness = |_buf: &NopInput| {
+            std::thread::sleep(Duration::from_secs(2));
+            ExitKind::Ok
+        };
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = TimeoutFeedback::new();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<NopInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut fuzzer: StdFuzzer<_, _, _, ()> =
+            StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+        let mut mgr = NopEventManager::new();
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(ExitOnTimeoutObserver),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )
+        .unwrap()
+        .with_timeout(Duration::from_millis(100));
+        let _ = executor.run_target(&mut fuzzer, &mut state, &mut mgr, &NopInput {});
+    }
+
+    #[test]
+    #[serial]
+    #[cfg(unix)]
+    fn test_inmem_exec_restarted_by_broker() {
+        // The broker restarts a hung client with a `SIGUSR2`, which must not make its input an objective
+        match unsafe { fork() }.unwrap() {
+            ForkResult::Parent(child) => {
+                let status = child.status();
+                assert!(libc::WIFEXITED(status));
+                assert_eq!(libc::WEXITSTATUS(status), 55);
+            }
+            ForkResult::Child => {
+                check_inmem_exec_restart();
+                unsafe { libc::_exit(2) }
+            }
+        }
+    }
+
+    /// Exits the process with `1` once asked if a run is an objective
+    #[cfg(unix)]
+    #[derive(Debug)]
+    struct ExitOnObjectiveFeedback;
+
+    #[cfg(unix)]
+    impl Named for ExitOnObjectiveFeedback {
+        fn name(&self) -> &str {
+            "exit_on_objective"
+        }
+    }
+
+    #[cfg(unix)]
+    impl<S> Feedback<S> for ExitOnObjectiveFeedback
+    where
+        S: UsesInput + HasClientPerfMonitor,
+    {
+        fn is_interesting<EM, OT>(
+            &mut self,
+            _state: &mut S,
+            _manager: &mut EM,
+            _input: &S::Input,
+            _observers: &OT,
+            _exit_kind: &ExitKind,
+        ) -> Result<bool, Error>
+        where
+            EM: EventFirer<State = S>,
+            OT: ObserversTuple<S>,
+        {
+            unsafe { libc::_exit(1) }
+        }
+    }
+
+    /// Runs a harness that gets the `SIGUSR2` of the broker, with the in-process handlers set up
+    #[cfg(unix)]
+    fn check_inmem_exec_restart() {
+        let mut harness = |_buf: &NopInput| {
+            unsafe { libc::raise(libc::SIGUSR2) };
+            ExitKind::Ok
+        };
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ExitOnObjectiveFeedback;
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<NopInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut fuzzer: StdFuzzer<_, _, _, ()> =
+            StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+        let mut mgr = NopEventManager::new();
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )
+        .unwrap();
+        let _ = executor.run_target(&mut fuzzer, &mut state, &mut mgr, &NopInput {});
+    }
+
     #[test]
     #[serial]
     #[cfg(all(feature = "std", feature = "fork", unix))]
     fn test_inprocessfork_exec() {
+        use core::cell::Cell;
+
         use crate::{
-            bolts::shmem::{ShMemProvider, StdShMemProvider},
+            bolts::{
+                os::unix_signals::Signal,
+                shmem::{ShMemProvider, StdShMemProvider},
+            },
             events::SimpleEventManager,
-            executors::{inprocess::InChildProcessHandlers, InProcessForkExecutor},
+            executors::{
+                inprocess::{new_exit_kind_shmem, InChildProcessHandlers},
+                InProcessForkExecutor,
+            },
             state::NopState,
             NopFuzzer,
         };
 
-        let provider = StdShMemProvider::new().unwrap();
+        let mut provider = StdShMemProvider::new().unwrap();
+        let exit_kind_shmem = new_exit_kind_shmem(&mut provider).unwrap();
 
-        let mut harness = |_buf: &NopInput| ExitKind::Ok;
+        let exit_kind = Cell::new(None);
+        let mut harness = |_buf: &NopInput| match exit_kind.get() {
+            Some(exit_kind) => exit_kind,
+            None => unsafe { libc::abort() },
+        };
         let mut in_process_fork_executor = InProcessForkExecutor::<_, (), _, _> {
             harness_fn: &mut harness,
             shmem_provider: provider,
+            exit_kind_shmem,
             observers: tuple_list!(),
             handlers: InChildProcessHandlers::nop(),
             phantom: PhantomData,
@@ -2005,9 +2515,26 @@ mod tests {
         let mut fuzzer = NopFuzzer::new();
         let mut state = NopState::new();
         let mut mgr = SimpleEventManager::printing();
-        in_process_fork_executor
-            .run_target(&mut fuzzer, &mut state, &mut mgr, &input)
-            .unwrap();
+
+        // The exit kind the harness returns in the child is the result
+        for expected in [ExitKind::Ok, ExitKind::Crash, ExitKind::Custom(1337)] {
+            exit_kind.set(Some(expected));
+            assert_eq!(
+                in_process_fork_executor
+                    .run_target(&mut fuzzer, &mut state, &mut mgr, &input)
+                    .unwrap(),
+                expected
+            );
+        }
+
+        // If the child dies instead, the signal is the result
+        exit_kind.set(None);
+        assert_eq!(
+            in_process_fork_executor
+                .run_target(&mut fuzzer, &mut state, &mut mgr, &input)
+                .unwrap(),
+            ExitKind::Signal(Signal::SigAbort as i32)
+        );
     }
 }
 
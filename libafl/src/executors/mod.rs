@@ -1,8 +1,10 @@
 //! Executors take input, and run it in the target.
 
+#[cfg(feature = "executors")]
 pub mod inprocess;
+#[cfg(feature = "executors")]
 pub use inprocess::InProcessExecutor;
-#[cfg(all(feature = "std", feature = "fork", unix))]
+#[cfg(all(feature = "std", feature = "fork", feature = "executors", unix))]
 pub use inprocess::InProcessForkExecutor;
 
 pub mod differential;
@@ -10,14 +12,24 @@ pub use differential::DiffExecutor;
 
 /// Timeout executor.
 /// Not possible on `no-std` Windows or `no-std`, but works for unix
-#[cfg(any(unix, feature = "std"))]
+#[cfg(all(feature = "executors", any(unix, feature = "std")))]
 pub mod timeout;
-#[cfg(any(unix, feature = "std"))]
+#[cfg(all(feature = "executors", any(unix, feature = "std")))]
 pub use timeout::TimeoutExecutor;
 
-#[cfg(all(feature = "std", feature = "fork", unix))]
+#[cfg(all(feature = "executors", any(unix, feature = "std")))]
+pub mod adaptive_timeout;
+#[cfg(all(feature = "executors", any(unix, feature = "std")))]
+pub use adaptive_timeout::AdaptiveTimeoutExecutor;
+
+#[cfg(all(unix, feature = "std", feature = "executors"))]
+pub mod oom;
+#[cfg(all(unix, feature = "std", feature = "executors"))]
+pub use oom::OomExecutor;
+
+#[cfg(all(feature = "std", feature = "fork", feature = "executors", unix))]
 pub mod forkserver;
-#[cfg(all(feature = "std", feature = "fork", unix))]
+#[cfg(all(feature = "std", feature = "fork", feature = "executors", unix))]
 pub use forkserver::{Forkserver, ForkserverExecutor, TimeoutForkserverExecutor};
 
 pub mod combined;
@@ -29,6 +41,11 @@ pub use shadow::ShadowExecutor;
 pub mod with_observers;
 pub use with_observers::WithObservers;
 
+#[cfg(feature = "std")]
+pub mod init;
+#[cfg(feature = "std")]
+pub use init::{HarnessInit, HarnessInitPolicy};
+
 #[cfg(all(feature = "std", any(unix, doc)))]
 pub mod command;
 use core::{fmt::Debug, marker::PhantomData};
@@ -52,6 +69,10 @@ pub enum ExitKind {
     Ok,
     /// The run resulted in a target crash.
     Crash,
+    /// The target was terminated by the given signal, such as `SIGSEGV`.
+    /// This is a crash, too, see [`ExitKind::is_crash`], but objectives can tell the signals apart,
+    /// for example with a [`crate::feedbacks::SignalFeedback`].
+    Signal(i32),
     /// The run hit an out of memory error.
     Oom,
     /// The run timed out
@@ -63,8 +84,9 @@ pub enum ExitKind {
         /// The exitkind of the secondary executor
         secondary: DiffExitKind,
     },
-    // The run resulted in a custom `ExitKind`.
-    // Custom(Box<dyn SerdeAny>),
+    /// The harness reported a custom outcome, such as a parse error or a semantic violation.
+    /// Use a [`crate::feedbacks::CustomExitKindFeedback`] to route custom kinds to the feedback or the objective.
+    Custom(u32),
 }
 
 /// How one of the diffing executions finished.
@@ -74,26 +96,47 @@ pub enum DiffExitKind {
     Ok,
     /// The run resulted in a target crash.
     Crash,
+    /// The target was terminated by the given signal
+    Signal(i32),
     /// The run hit an out of memory error.
     Oom,
     /// The run timed out
     Timeout,
     /// One of the executors itelf repots a differential, we can't go into further details.
     Diff,
-    // The run resulted in a custom `ExitKind`.
-    // Custom(Box<dyn SerdeAny>),
+    /// The harness reported a custom outcome
+    Custom(u32),
 }
 
 crate::impl_serdeany!(ExitKind);
 
+impl ExitKind {
+    /// If the run crashed, as [`ExitKind::Crash`] or terminated by an [`ExitKind::Signal`]
+    #[must_use]
+    pub fn is_crash(&self) -> bool {
+        matches!(self, Self::Crash | Self::Signal(_))
+    }
+
+    /// The signal that terminated the run, if it is known
+    #[must_use]
+    pub fn signal(&self) -> Option<i32> {
+        match self {
+            Self::Signal(signal) => Some(*signal),
+            _ => None,
+        }
+    }
+}
+
 impl From<ExitKind> for DiffExitKind {
     fn from(exitkind: ExitKind) -> Self {
         match exitkind {
             ExitKind::Ok => DiffExitKind::Ok,
             ExitKind::Crash => DiffExitKind::Crash,
+            ExitKind::Signal(signal) => DiffExitKind::Signal(signal),
             ExitKind::Oom => DiffExitKind::Oom,
             ExitKind::Timeout => DiffExitKind::Timeout,
             ExitKind::Diff { .. } => DiffExitKind::Diff,
+            ExitKind::Custom(kind) => DiffExitKind::Custom(kind),
         }
     }
 }
@@ -107,6 +150,12 @@ pub trait HasObservers: UsesObservers {
 
     /// Get the linked observers (mutable)
     fn observers_mut(&mut self) -> &mut Self::Observers;
+
+    /// Replace the linked observers, returning the previous ones.
+    /// To attach observers of another type, wrap a borrowed executor in a [`WithObservers`] instead.
+    fn replace_observers(&mut self, observers: Self::Observers) -> Self::Observers {
+        core::mem::replace(self.observers_mut(), observers)
+    }
 }
 
 /// An executor takes the given inputs, and runs the harness/target.
@@ -128,6 +177,8 @@ where
     ///
     /// If the executor already implements [`HasObservers`], then the original implementation will be overshadowed by
     /// the implementation of this wrapper.
+    /// To attach other observers only for a while, for example in a stage, wrap a borrowed executor:
+    /// `WithObservers::new(&mut *executor, observers)`.
     fn with_observers<OT>(self, observers: OT) -> WithObservers<Self, OT>
     where
         Self: Sized,
@@ -181,9 +232,27 @@ where
 mod test {
     use core::marker::PhantomData;
 
-    use super::{Executor, NopExecutor};
+    use super::{DiffExitKind, Executor, ExitKind, NopExecutor};
+    #[cfg(feature = "std")]
+    use super::{HasObservers, WithObservers};
+    #[cfg(feature = "std")]
+    use crate::{
+        bolts::tuples::{tuple_list, MatchName},
+        observers::{ObserversTuple, TimeObserver},
+    };
     use crate::{events::NopEventManager, inputs::BytesInput, state::NopState, NopFuzzer};
 
+    #[test]
+    fn test_exit_kind_signal() {
+        let segv = ExitKind::Signal(11);
+        assert!(segv.is_crash());
+        assert_eq!(segv.signal(), Some(11));
+        assert!(ExitKind::Crash.is_crash());
+        assert_eq!(ExitKind::Crash.signal(), None);
+        assert!(!ExitKind::Timeout.is_crash());
+        assert_eq!(DiffExitKind::from(segv), DiffExitKind::Signal(11));
+    }
+
     #[test]
     fn nop_executor() {
         let empty_input = BytesInput::new(vec![]);
@@ -212,6 +281,35 @@ mod test {
             )
             .unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn scoped_observers() {
+        let input = BytesInput::new(vec![1u8]);
+        let mut executor = NopExecutor {
+            phantom: PhantomData,
+        };
+        let mut fuzzer = NopFuzzer::new();
+        let mut state = NopState::new();
+
+        // Attach an observer to the borrowed executor, for a single run
+        let mut scoped = WithObservers::new(&mut executor, tuple_list!(TimeObserver::new("time")));
+        scoped
+            .observers_mut()
+            .pre_exec_all(&mut state, &input)
+            .unwrap();
+        let exit_kind = scoped
+            .run_target(&mut fuzzer, &mut state, &mut NopEventManager::new(), &input)
+            .unwrap();
+        scoped
+            .observers_mut()
+            .post_exec_all(&mut state, &input, &exit_kind)
+            .unwrap();
+
+        let observers = scoped.replace_observers(tuple_list!(TimeObserver::new("time")));
+        let time = observers.match_name::<TimeObserver>("time").unwrap();
+        assert!(time.last_runtime().is_some());
+    }
 }
 
 #[cfg(feature = "python")]
@@ -262,7 +360,12 @@ pub mod pybind {
 
         #[must_use]
         fn is_crash(&self) -> bool {
-            self.inner == ExitKind::Crash
+            self.inner.is_crash()
+        }
+
+        #[must_use]
+        fn signal(&self) -> Option<i32> {
+            self.inner.signal()
         }
 
         #[must_use]
@@ -275,6 +378,14 @@ pub mod pybind {
             self.inner == ExitKind::Timeout
         }
 
+        #[must_use]
+        fn custom_kind(&self) -> Option<u32> {
+            match self.inner {
+                ExitKind::Custom(kind) => Some(kind),
+                _ => None,
+            }
+        }
+
         #[staticmethod]
         #[must_use]
         fn ok() -> Self {
@@ -306,6 +417,14 @@ pub mod pybind {
                 inner: ExitKind::Timeout,
             }
         }
+
+        #[staticmethod]
+        #[must_use]
+        fn custom(kind: u32) -> Self {
+            Self {
+                inner: ExitKind::Custom(kind),
+            }
+        }
     }
 
     #[derive(Clone, Debug)]
@@ -0,0 +1,387 @@
+//! The [`OomExecutor`] watches the memory use of the target while it runs, similar to libFuzzer's `-rss_limit_mb`,
+//! and reports runs exceeding the limit as [`ExitKind::Oom`].
+//! A watchdog thread polls the resident set size of the process, and interrupts runs allocating more than the limit,
+//! measured from the start of the run, with a `SIGUSR1`.
+//! The in-process executors handle it like a timeout, but report the run as [`ExitKind::Oom`], see [`last_oom`].
+//! `SIGUSR1` is not used otherwise, so timeouts, and the `SIGUSR2` the broker sends to hung clients, are never mistaken for an OOM.
+
+use core::{
+    fmt::Debug,
+    sync::atomic::{AtomicU32, AtomicUsize, Ordering},
+    time::Duration,
+};
+use std::{process, thread};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    executors::{Executor, ExitKind, HasObservers},
+    observers::UsesObservers,
+    state::UsesState,
+    Error,
+};
+
+/// The default memory limit of an [`OomExecutor`], in MB, as for libFuzzer
+pub const DEFAULT_RSS_LIMIT_MB: usize = 2048;
+
+/// How often the watchdog thread measures the memory use during a run
+const OOM_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The memory limit of the current run, in bytes, or `0` outside of runs
+static RSS_LIMIT: AtomicUsize = AtomicUsize::new(0);
+/// The memory use when the current run started
+static RUN_START_RSS: AtomicUsize = AtomicUsize::new(0);
+/// Counts the runs, so a `SIGUSR1` sent for a run is never taken for an OOM of a later one
+static RUN_GENERATION: AtomicUsize = AtomicUsize::new(0);
+/// The run the watchdog interrupted, or `0`
+static OOM_GENERATION: AtomicUsize = AtomicUsize::new(0);
+/// The thread running the target, interrupted once it exceeds the limit, or `0` outside of runs
+static FUZZ_THREAD: AtomicUsize = AtomicUsize::new(0);
+/// The memory use of the last run when it exceeded the limit, or `0`
+static OOM_RSS: AtomicUsize = AtomicUsize::new(0);
+/// The limit the last run exceeded
+static OOM_RSS_LIMIT: AtomicUsize = AtomicUsize::new(0);
+/// The memory the last run allocated until it exceeded the limit
+static OOM_ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+/// The process the watchdog thread runs in, as threads do not survive a `fork`
+static WATCHDOG_PID: AtomicU32 = AtomicU32::new(0);
+
+/// The memory use of a run that exceeded the limit of an [`OomExecutor`],
+/// added to its testcase by the [`crate::feedbacks::OomFeedback`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OomMetadata {
+    /// The resident set size of the process when the limit was exceeded, in bytes
+    pub rss: usize,
+    /// The limit of the memory a run may allocate, in bytes
+    pub rss_limit: usize,
+    /// The memory the run allocated, i.e., how much the resident set size grew since the run started, in bytes
+    pub allocated: usize,
+}
+
+crate::impl_serdeany!(OomMetadata);
+
+/// The [`OomMetadata`] of the last run of an [`OomExecutor`], if it exceeded the memory limit
+#[must_use]
+pub fn last_oom() -> Option<OomMetadata> {
+    match OOM_RSS.load(Ordering::SeqCst) {
+        0 => None,
+        rss => Some(OomMetadata {
+            rss,
+            rss_limit: OOM_RSS_LIMIT.load(Ordering::SeqCst),
+            allocated: OOM_ALLOCATED.load(Ordering::SeqCst),
+        }),
+    }
+}
+
+/// The `mach_task_basic_info`
+#[cfg(target_vendor = "apple")]
+#[allow(dead_code)]
+#[derive(Debug, Default, Clone, Copy)]
+#[repr(C, packed(4))]
+struct MachTaskBasicInfo {
+    virtual_size: u64,
+    resident_size: u64,
+    resident_size_max: u64,
+    user_time: [i32; 2],
+    system_time: [i32; 2],
+    policy: i32,
+    suspend_count: i32,
+}
+
+#[cfg(target_vendor = "apple")]
+const MACH_TASK_BASIC_INFO: i32 = 20;
+
+#[cfg(target_vendor = "apple")]
+extern "C" {
+    static mach_task_self_: u32;
+
+    fn task_info(task: u32, flavor: i32, task_info: *mut i32, count: *mut u32) -> i32;
+}
+
+/// The resident set size of this process, in bytes.
+/// Outside of Linux, Android, and `MacOS`, this is the peak resident set size.
+#[must_use]
+pub fn current_rss() -> usize {
+    #[cfg(any(target_os = "linux", target_os = "android"))]
+    {
+        // The second field of `statm` is the resident set size, in pages
+        let pages = std::fs::read_to_string("/proc/self/statm")
+            .ok()
+            .and_then(|statm| statm.split_whitespace().nth(1)?.parse::<usize>().ok())
+            .unwrap_or_default();
+        #[allow(clippy::cast_sign_loss)]
+        let page_size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        pages * page_size
+    }
+    #[cfg(target_vendor = "apple")]
+    {
+        let mut info = MachTaskBasicInfo::default();
+        #[allow(clippy::cast_possible_truncation)]
+        let mut count = (core::mem::size_of::<MachTaskBasicInfo>() / 4) as u32;
+        let ret = unsafe {
+            task_info(
+                mach_task_self_,
+                MACH_TASK_BASIC_INFO,
+                core::ptr::addr_of_mut!(info) as *mut i32,
+                &mut count,
+            )
+        };
+        if ret == 0 {
+            usize::try_from(info.resident_size).unwrap_or(usize::MAX)
+        } else {
+            0
+        }
+    }
+    #[cfg(not(any(target_os = "linux", target_os = "android", target_vendor = "apple")))]
+    {
+        let mut usage: libc::rusage = unsafe { core::mem::zeroed() };
+        unsafe {
+            libc::getrusage(libc::RUSAGE_SELF, &mut usage);
+        }
+        // The BSDs report kilobytes
+        usize::try_from(usage.ru_maxrss).unwrap_or_default() * 1024
+    }
+}
+
+/// If the watchdog interrupted the ongoing run, for the handlers to tell its `SIGUSR1` from a late one
+#[must_use]
+pub(crate) fn oom_in_current_run() -> bool {
+    FUZZ_THREAD.load(Ordering::SeqCst) != 0
+        && OOM_GENERATION.load(Ordering::SeqCst) == RUN_GENERATION.load(Ordering::SeqCst)
+}
+
+/// Polls the memory use during runs, and interrupts the fuzz thread once its run allocated more than the limit
+fn watchdog() {
+    loop {
+        thread::sleep(OOM_POLL_INTERVAL);
+        let generation = RUN_GENERATION.load(Ordering::SeqCst);
+        let rss_limit = RSS_LIMIT.load(Ordering::SeqCst);
+        if rss_limit == 0 || OOM_GENERATION.load(Ordering::SeqCst) == generation {
+            continue;
+        }
+        let rss = current_rss();
+        // Where only the peak is known, it may not grow before it exceeds the peak of earlier runs
+        let allocated = rss.saturating_sub(RUN_START_RSS.load(Ordering::SeqCst));
+        if allocated <= rss_limit {
+            continue;
+        }
+        // The run may have ended, or the next one started, while measuring
+        let thread = FUZZ_THREAD.load(Ordering::SeqCst);
+        if thread == 0 || RUN_GENERATION.load(Ordering::SeqCst) != generation {
+            continue;
+        }
+        OOM_RSS_LIMIT.store(rss_limit, Ordering::SeqCst);
+        OOM_ALLOCATED.store(allocated, Ordering::SeqCst);
+        OOM_RSS.store(rss, Ordering::SeqCst);
+        OOM_GENERATION.store(generation, Ordering::SeqCst);
+        unsafe {
+            libc::pthread_kill(thread as libc::pthread_t, libc::SIGUSR1);
+        }
+    }
+}
+
+/// Starts the watchdog thread, unless it already runs in this process
+fn ensure_watchdog() {
+    let pid = process::id();
+    if WATCHDOG_PID.swap(pid, Ordering::SeqCst) != pid {
+        thread::spawn(watchdog);
+    }
+}
+
+/// A wrapper for an in-process [`Executor`], reporting runs that allocate more than a memory limit as [`ExitKind::Oom`].
+/// The limit applies to the growth of the resident set size since the start of each run.
+/// Runs exceeding the limit are interrupted by the handlers of the in-process executors,
+/// which store the input as objective, if a [`crate::feedbacks::OomFeedback`] is part of the objective, and restart the client.
+/// Runs that end before the watchdog interrupts them return [`ExitKind::Oom`] right away.
+#[derive(Debug)]
+pub struct OomExecutor<E> {
+    executor: E,
+    rss_limit: usize,
+}
+
+impl<E, EM, Z> Executor<EM, Z> for OomExecutor<E>
+where
+    E: Executor<EM, Z>,
+    EM: UsesState<State = E::State>,
+    Z: UsesState<State = E::State>,
+{
+    fn run_target(
+        &mut self,
+        fuzzer: &mut Z,
+        state: &mut Self::State,
+        mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        OOM_RSS.store(0, Ordering::SeqCst);
+        RUN_START_RSS.store(current_rss(), Ordering::SeqCst);
+        let generation = RUN_GENERATION.fetch_add(1, Ordering::SeqCst) + 1;
+        FUZZ_THREAD.store(unsafe { libc::pthread_self() } as usize, Ordering::SeqCst);
+        RSS_LIMIT.store(self.rss_limit, Ordering::SeqCst);
+        let ret = self.executor.run_target(fuzzer, state, mgr, input);
+        RSS_LIMIT.store(0, Ordering::SeqCst);
+        FUZZ_THREAD.store(0, Ordering::SeqCst);
+
+        let exit_kind = ret?;
+        if OOM_GENERATION.load(Ordering::SeqCst) == generation {
+            Ok(ExitKind::Oom)
+        } else {
+            Ok(exit_kind)
+        }
+    }
+
+    fn post_run_reset(&mut self) {
+        self.executor.post_run_reset();
+    }
+}
+
+impl<E> UsesState for OomExecutor<E>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E> UsesObservers for OomExecutor<E>
+where
+    E: UsesObservers,
+{
+    type Observers = E::Observers;
+}
+
+impl<E> HasObservers for OomExecutor<E>
+where
+    E: HasObservers,
+{
+    #[inline]
+    fn observers(&self) -> &Self::Observers {
+        self.executor.observers()
+    }
+
+    #[inline]
+    fn observers_mut(&mut self) -> &mut Self::Observers {
+        self.executor.observers_mut()
+    }
+}
+
+impl<E> OomExecutor<E> {
+    /// Creates a new [`OomExecutor`], reporting runs of `executor` that allocate more than `rss_limit_mb` MB as [`ExitKind::Oom`].
+    /// See [`DEFAULT_RSS_LIMIT_MB`] for libFuzzer's default.
+    #[must_use]
+    pub fn new(executor: E, rss_limit_mb: usize) -> Self {
+        ensure_watchdog();
+        Self {
+            executor,
+            rss_limit: rss_limit_mb << 20,
+        }
+    }
+
+    /// The memory limit, in bytes
+    #[must_use]
+    pub fn rss_limit(&self) -> usize {
+        self.rss_limit
+    }
+
+    /// Sets the memory limit, in MB
+    pub fn set_rss_limit_mb(&mut self, rss_limit_mb: usize) {
+        self.rss_limit = rss_limit_mb << 20;
+    }
+
+    /// The wrapped executor
+    pub fn inner(&mut self) -> &mut E {
+        &mut self.executor
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::{sync::atomic::Ordering, time::Duration};
+    use std::{panic, thread};
+
+    use crate::{
+        bolts::os::{fork, ForkResult},
+        events::NopEventManager,
+        executors::{
+            oom::{last_oom, oom_in_current_run, OomExecutor, FUZZ_THREAD},
+            Executor, ExitKind,
+        },
+        inputs::NopInput,
+        state::{NopState, UsesState},
+        Error, NopFuzzer,
+    };
+
+    /// Allocates and holds 64 MB for a while, if `allocate` is set
+    #[derive(Debug)]
+    struct AllocatingExecutor {
+        allocate: bool,
+    }
+
+    impl UsesState for AllocatingExecutor {
+        type State = NopState<NopInput>;
+    }
+
+    impl<EM, Z> Executor<EM, Z> for AllocatingExecutor
+    where
+        EM: UsesState<State = Self::State>,
+        Z: UsesState<State = Self::State>,
+    {
+        fn run_target(
+            &mut self,
+            _fuzzer: &mut Z,
+            _state: &mut Self::State,
+            _mgr: &mut EM,
+            _input: &NopInput,
+        ) -> Result<ExitKind, Error> {
+            if self.allocate {
+                let buf = vec![0x42_u8; 64 << 20];
+                thread::sleep(Duration::from_millis(500));
+                assert_eq!(buf[1234], 0x42);
+            }
+            Ok(ExitKind::Ok)
+        }
+    }
+
+    #[test]
+    fn test_oom_executor() {
+        // Runs in a child, so that neither the ignored signal nor the memory use of other tests leaks into the measurement
+        match unsafe { fork() }.unwrap() {
+            ForkResult::Parent(child) => assert_eq!(child.status(), 0),
+            ForkResult::Child => {
+                let res = panic::catch_unwind(check_oom_executor);
+                unsafe { libc::_exit(i32::from(res.is_err())) }
+            }
+        }
+    }
+
+    fn check_oom_executor() {
+        // Without the in-process handlers, the watchdog interrupt is ignored, and the run reports the OOM once done.
+        unsafe {
+            libc::signal(libc::SIGUSR1, libc::SIG_IGN);
+        }
+
+        // The limit applies to the memory allocated during the run, not to the memory in use before
+        let mut executor = OomExecutor::new(AllocatingExecutor { allocate: false }, 32);
+        assert_eq!(executor.rss_limit(), 32 << 20);
+
+        let mut fuzzer = NopFuzzer::new();
+        let mut state = NopState::new();
+        let mut mgr = NopEventManager::new();
+        let exit_kind = executor
+            .run_target(&mut fuzzer, &mut state, &mut mgr, &NopInput {})
+            .unwrap();
+        assert_eq!(exit_kind, ExitKind::Ok);
+        assert!(last_oom().is_none());
+
+        executor.inner().allocate = true;
+        let exit_kind = executor
+            .run_target(&mut fuzzer, &mut state, &mut mgr, &NopInput {})
+            .unwrap();
+        assert_eq!(exit_kind, ExitKind::Oom);
+        let oom = last_oom().unwrap();
+        assert!(oom.allocated > oom.rss_limit);
+        assert!(oom.allocated <= oom.rss);
+        // The watchdog does not interrupt the thread once the run is over,
+        // and a late interrupt is not taken for an OOM of the run
+        assert_eq!(FUZZ_THREAD.load(Ordering::SeqCst), 0);
+        assert!(!oom_in_current_run());
+    }
+}
@@ -21,11 +21,11 @@ use core::{
 use libc::c_int;
 #[cfg(all(windows, feature = "std"))]
 use windows::Win32::{
-    Foundation::FILETIME,
+    Foundation::{CloseHandle, FILETIME, HANDLE},
     System::Threading::{
-        CreateThreadpoolTimer, EnterCriticalSection, InitializeCriticalSection,
-        LeaveCriticalSection, SetThreadpoolTimer, RTL_CRITICAL_SECTION, TP_CALLBACK_ENVIRON_V3,
-        TP_CALLBACK_INSTANCE, TP_TIMER,
+        CreateThreadpoolTimer, EnterCriticalSection, GetCurrentThreadId, InitializeCriticalSection,
+        LeaveCriticalSection, OpenThread, SetThreadpoolTimer, RTL_CRITICAL_SECTION,
+        THREAD_SUSPEND_RESUME, TP_CALLBACK_ENVIRON_V3, TP_CALLBACK_INSTANCE, TP_TIMER,
     },
 };
 
@@ -91,6 +91,9 @@ pub struct TimeoutExecutor<E> {
     tp_timer: *mut TP_TIMER,
     #[cfg(windows)]
     critical: RTL_CRITICAL_SECTION,
+    /// The thread running the target, opened on the first run, for the timeout handler to suspend it
+    #[cfg(windows)]
+    fuzz_thread: HANDLE,
 }
 
 impl<E: Debug> Debug for TimeoutExecutor<E> {
@@ -188,7 +191,7 @@ impl<E> TimeoutExecutor<E> {
         let milli_sec = exec_tmout.as_millis();
         let it_value = Timeval {
             tv_sec: (milli_sec / 1000) as i64,
-            tv_usec: (milli_sec % 1000) as i64,
+            tv_usec: ((milli_sec % 1000) * 1000) as i64,
         };
         let it_interval = Timeval {
             tv_sec: 0,
@@ -209,7 +212,7 @@ impl<E> TimeoutExecutor<E> {
         let milli_sec = exec_tmout.as_millis();
         let it_value = Timeval {
             tv_sec: (milli_sec / 1000) as i64,
-            tv_usec: (milli_sec % 1000) as i64,
+            tv_usec: ((milli_sec % 1000) * 1000) as i64,
         };
         let it_interval = Timeval {
             tv_sec: 0,
@@ -248,11 +251,14 @@ impl<E: HasInProcessHandlers> TimeoutExecutor<E> {
             milli_sec,
             tp_timer,
             critical,
+            fuzz_thread: HANDLE::default(),
         }
     }
+}
 
+#[cfg(windows)]
+impl<E> TimeoutExecutor<E> {
     /// Set the timeout for this executor
-    #[cfg(windows)]
     pub fn set_timeout(&mut self, exec_tmout: Duration) {
         self.milli_sec = exec_tmout.as_millis() as i64;
     }
@@ -289,6 +295,11 @@ where
                 &mut data.timeout_input_ptr,
                 addr_of_mut!(data.current_input_ptr) as *mut c_void,
             );
+            if self.fuzz_thread.is_invalid() {
+                self.fuzz_thread = OpenThread(THREAD_SUSPEND_RESUME, false, GetCurrentThreadId())
+                    .unwrap_or_default();
+            }
+            write_volatile(&mut data.fuzz_thread, self.fuzz_thread.0 as *mut c_void);
             let tm: i64 = -self.milli_sec * 10 * 1000;
             let ft = FILETIME {
                 dwLowDateTime: (tm & 0xffffffff) as u32,
@@ -317,6 +328,7 @@ where
             compiler_fence(Ordering::SeqCst);
 
             write_volatile(&mut data.timeout_input_ptr, core::ptr::null_mut());
+            write_volatile(&mut data.fuzz_thread, core::ptr::null_mut());
 
             self.post_run_reset();
             ret
@@ -334,6 +346,17 @@ where
     }
 }
 
+#[cfg(windows)]
+impl<E> Drop for TimeoutExecutor<E> {
+    fn drop(&mut self) {
+        if !self.fuzz_thread.is_invalid() {
+            unsafe {
+                CloseHandle(self.fuzz_thread);
+            }
+        }
+    }
+}
+
 #[cfg(target_os = "linux")]
 impl<E, EM, Z> Executor<EM, Z> for TimeoutExecutor<E>
 where
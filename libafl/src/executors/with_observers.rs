@@ -1,4 +1,5 @@
 //! A wrapper for any [`Executor`] to make it implement [`HasObservers`] using a given [`ObserversTuple`].
+//! Wrapping a borrowed executor lets a stage temporarily attach its own observers to the executor of the fuzzer.
 
 use core::fmt::Debug;
 
@@ -51,7 +52,7 @@ where
 
 impl<E, OT> HasObservers for WithObservers<E, OT>
 where
-    E: HasObservers + Debug,
+    E: UsesState + Debug,
     OT: ObserversTuple<E::State> + Debug,
 {
     fn observers(&self) -> &OT {
@@ -74,4 +75,69 @@ impl<E: Debug, OT: Debug> WithObservers<E, OT> {
             observers,
         }
     }
+
+    /// The wrapped executor
+    pub fn inner(&self) -> &E {
+        &self.executor
+    }
+
+    /// The wrapped executor (mutable)
+    pub fn inner_mut(&mut self) -> &mut E {
+        &mut self.executor
+    }
+
+    /// Unwraps the executor and the observers
+    pub fn into_inner(self) -> (E, OT) {
+        (self.executor, self.observers)
+    }
+}
+
+// A borrowed executor is an executor, too, so that it can be wrapped for a while.
+
+impl<E> UsesState for &mut E
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E> UsesObservers for &mut E
+where
+    E: UsesObservers,
+{
+    type Observers = E::Observers;
+}
+
+impl<E> HasObservers for &mut E
+where
+    E: HasObservers,
+{
+    fn observers(&self) -> &E::Observers {
+        (**self).observers()
+    }
+
+    fn observers_mut(&mut self) -> &mut E::Observers {
+        (**self).observers_mut()
+    }
+}
+
+impl<E, EM, Z> Executor<EM, Z> for &mut E
+where
+    E: Executor<EM, Z>,
+    EM: UsesState<State = E::State>,
+    Z: UsesState<State = E::State>,
+{
+    fn run_target(
+        &mut self,
+        fuzzer: &mut Z,
+        state: &mut Self::State,
+        mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        (**self).run_target(fuzzer, state, mgr, input)
+    }
+
+    fn post_run_reset(&mut self) {
+        (**self).post_run_reset();
+    }
 }
@@ -0,0 +1,249 @@
+//! The [`AflEntryFeedback`] records, for each new testcase, the information AFL++ encodes in its file names,
+//! so that an [`crate::corpus::AflCorpus`] can store it in the AFL++ layout.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Write, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{current_time, tuples::Named},
+    corpus::{Corpus, Testcase},
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasCorpus, HasExecutions, HasMetadata, HasStartTime},
+    Error,
+};
+
+/// The operation AFL++ reports for entries found by mutations
+pub const DEFAULT_AFL_OP: &str = "havoc";
+
+/// The provenance of a testcase, as encoded by AFL++ in the file name,
+/// e.g., `id:000042,src:000007,time:1234,execs:5678,op:havoc`, or `id:000003,sig:11,src:000007,...` for crashes
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct AflEntryMetadata {
+    /// The id of this entry in its AFL++ directory, assigned by the [`crate::corpus::AflCorpus`] storing it
+    pub id: Option<usize>,
+    /// The signal that terminated the target, for crashes
+    pub sig: Option<i32>,
+    /// The id of the queue entry this testcase was derived from
+    pub src: Option<usize>,
+    /// The time since the start of the campaign
+    pub time: Duration,
+    /// The number of executions done at discovery time
+    pub execs: usize,
+    /// The operation that found this testcase
+    pub op: String,
+}
+
+crate::impl_serdeany!(AflEntryMetadata);
+
+impl AflEntryMetadata {
+    /// The AFL++ file name of the entry with the given `id`
+    #[must_use]
+    pub fn file_name(&self, id: usize) -> String {
+        let mut name = format!("id:{id:06}");
+        if let Some(sig) = self.sig {
+            write!(name, ",sig:{sig:02}").unwrap();
+        }
+        if let Some(src) = self.src {
+            write!(name, ",src:{src:06}").unwrap();
+        }
+        write!(
+            name,
+            ",time:{},execs:{},op:{}",
+            self.time.as_millis(),
+            self.execs,
+            self.op
+        )
+        .unwrap();
+        name
+    }
+}
+
+/// The start of the campaign, for the times AFL++ reports.
+/// If the start time of the state was never set, the campaign starts now.
+pub(crate) fn campaign_start_time<S>(state: &mut S) -> Duration
+where
+    S: HasStartTime,
+{
+    if state.start_time().is_zero() {
+        *state.start_time_mut() = current_time();
+    }
+    *state.start_time()
+}
+
+/// A [`AflEntryFeedback`] adds an [`AflEntryMetadata`] to each new testcase, or solution.
+/// The source of the testcase is the corpus entry currently fuzzed.
+/// It is never interesting, so combine it with other feedbacks. Put it first, so that it sees the signal of each run,
+/// e.g., `feedback_or_fast!(AflEntryFeedback::new(), CrashFeedback::new())`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AflEntryFeedback {
+    op: String,
+    /// The signal that terminated the last run, if any
+    last_signal: Option<i32>,
+}
+
+impl<S> Feedback<S> for AflEntryFeedback
+where
+    S: UsesInput + HasClientPerfMonitor + HasCorpus + HasExecutions + HasStartTime,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.last_signal = exit_kind.signal();
+        Ok(false)
+    }
+
+    fn append_metadata(
+        &mut self,
+        state: &mut S,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error> {
+        // Refer to the parent by its AFL++ id, if it is stored in an `AflCorpus`
+        let src = match *state.corpus().current() {
+            Some(idx) => Some(
+                state
+                    .corpus()
+                    .get(idx)?
+                    .borrow()
+                    .metadata()
+                    .get::<AflEntryMetadata>()
+                    .and_then(|meta| meta.id)
+                    .unwrap_or(idx),
+            ),
+            None => None,
+        };
+        let start_time = campaign_start_time(state);
+        testcase.add_metadata(AflEntryMetadata {
+            id: None,
+            sig: self.last_signal,
+            src,
+            time: current_time().saturating_sub(start_time),
+            execs: *state.executions(),
+            op: self.op.clone(),
+        });
+        Ok(())
+    }
+}
+
+impl Named for AflEntryFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "AflEntryFeedback"
+    }
+}
+
+impl AflEntryFeedback {
+    /// Creates a new [`AflEntryFeedback`], reporting the [`DEFAULT_AFL_OP`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_op(DEFAULT_AFL_OP)
+    }
+
+    /// Creates a new [`AflEntryFeedback`], reporting `op` as the operation that found each testcase
+    #[must_use]
+    pub fn with_op(op: &str) -> Self {
+        Self {
+            op: op.to_string(),
+            last_signal: None,
+        }
+    }
+}
+
+impl Default for AflEntryFeedback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{AflEntryFeedback, AflEntryMetadata, Feedback},
+        inputs::BytesInput,
+        state::{HasCorpus, HasExecutions, HasMetadata, HasStartTime, StdState},
+    };
+
+    #[test]
+    fn test_afl_entry_feedback() {
+        let mut feedback = AflEntryFeedback::new();
+        let mut objective = AflEntryFeedback::with_op("splice");
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+
+        // The parent is known by its AFL++ id
+        let mut parent = Testcase::new(BytesInput::new(b"parent".to_vec()));
+        parent.add_metadata(AflEntryMetadata {
+            id: Some(7),
+            sig: None,
+            src: None,
+            time: Duration::ZERO,
+            execs: 0,
+            op: "havoc".into(),
+        });
+        let idx = state.corpus_mut().add(parent).unwrap();
+        *state.corpus_mut().current_mut() = Some(idx);
+        *state.executions_mut() = 42;
+
+        let input = BytesInput::new(b"crash".to_vec());
+        assert!(!objective
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Signal(11))
+            .unwrap());
+        let mut testcase = Testcase::new(input);
+        objective
+            .append_metadata(&mut state, &mut testcase)
+            .unwrap();
+        // The campaign starts with the first entry, if nothing set the start time
+        assert!(!state.start_time().is_zero());
+
+        let meta = testcase.metadata().get::<AflEntryMetadata>().unwrap();
+        assert_eq!(meta.sig, Some(11));
+        assert_eq!(meta.src, Some(7));
+        assert_eq!(meta.execs, 42);
+        assert_eq!(
+            meta.file_name(3),
+            format!(
+                "id:000003,sig:11,src:000007,time:{},execs:42,op:splice",
+                meta.time.as_millis()
+            )
+        );
+
+        // Runs without a signal record none
+        let input = BytesInput::new(b"fine".to_vec());
+        feedback
+            .is_interesting(&mut state, &mut mgr, &input, &(), &ExitKind::Ok)
+            .unwrap();
+        let mut testcase = Testcase::new(input);
+        feedback.append_metadata(&mut state, &mut testcase).unwrap();
+        let meta = testcase.metadata().get::<AflEntryMetadata>().unwrap();
+        assert_eq!(meta.sig, None);
+        assert!(meta.file_name(4).starts_with("id:000004,src:000007,time:"));
+    }
+}
@@ -0,0 +1,91 @@
+//! The [`AsanErrorsFeedback`] reports crashes caused by `AddressSanitizer` errors,
+//! and attaches the `ASan` report to the testcase.
+//! Needs the hooks in [`crate::executors::inprocess::asan_hooks`].
+
+use alloc::string::String;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::{inprocess::asan_hooks::take_asan_report, ExitKind},
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// A testcase metadata holding the `ASan` report of a crash
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsanErrorMetadata {
+    /// The report, as printed by `ASan`
+    pub report: String,
+}
+
+crate::impl_serdeany!(AsanErrorMetadata);
+
+/// An [`AsanErrorsFeedback`] reports as interesting if the target crashed because of an `ASan` error.
+/// The `ASan` report is attached to the testcase as [`AsanErrorMetadata`].
+///
+/// Call [`crate::executors::inprocess::asan_hooks::setup_asan_callbacks`] before fuzzing,
+/// else no report will be captured.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct AsanErrorsFeedback {
+    report: Option<String>,
+}
+
+impl<S> Feedback<S> for AsanErrorsFeedback
+where
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        self.report = take_asan_report();
+        Ok(exit_kind.is_crash() && self.report.is_some())
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut S,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error> {
+        if let Some(report) = self.report.take() {
+            testcase.add_metadata(AsanErrorMetadata { report });
+        }
+        Ok(())
+    }
+
+    fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.report = None;
+        Ok(())
+    }
+}
+
+impl Named for AsanErrorsFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "AsanErrorsFeedback"
+    }
+}
+
+impl AsanErrorsFeedback {
+    /// Creates a new [`AsanErrorsFeedback`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self { report: None }
+    }
+}
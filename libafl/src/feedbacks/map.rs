@@ -43,7 +43,7 @@ pub type MinMapFeedback<O, S, T> = MapFeedback<DifferentIsNovel, O, MinReducer,
 /// but only, if a value is larger than `pow2` of the previous.
 pub type MaxMapPow2Feedback<O, S, T> = MapFeedback<NextPow2IsNovel, O, MaxReducer, S, T>;
 /// A [`MapFeedback`] that strives to maximize the map contents,
-/// but only, if a value is larger than `pow2` of the previous.
+/// but only, if a value is either `T::one()` or `T::max_value()`.
 pub type MaxMapOneOrFilledFeedback<O, S, T> = MapFeedback<OneOrFilledIsNovel, O, MaxReducer, S, T>;
 
 /// A `Reducer` function is used to aggregate values for the novelty search
@@ -83,6 +83,21 @@ where
     }
 }
 
+/// A [`NopReducer`] does not reduce, it always keeps the new value.
+/// Together with [`DifferentIsNovel`], any change of an entry is novel, e.g., for maps of states.
+#[derive(Clone, Debug)]
+pub struct NopReducer {}
+
+impl<T> Reducer<T> for NopReducer
+where
+    T: Default + Copy + 'static,
+{
+    #[inline]
+    fn reduce(_history: T, new: T) -> T {
+        new
+    }
+}
+
 /// A [`MaxReducer`] reduces int values and returns their maximum.
 #[derive(Clone, Debug)]
 pub struct MaxReducer {}
@@ -190,7 +205,7 @@ where
     }
 }
 
-/// A filter that only saves values which are at least the next pow2 class
+/// Only consider as novel the values which are either `T::one()` or `T::max_value()`, and larger than the old value
 #[derive(Clone, Debug)]
 pub struct OneOrFilledIsNovel {}
 impl<T> IsNovel<T> for OneOrFilledIsNovel
@@ -203,7 +218,8 @@ where
     }
 }
 
-/// A testcase metadata holding a list of indexes of a map
+/// A testcase metadata holding a list of indexes of a map,
+/// the entries the testcase covered for a [`MapFeedback`] tracking indexes
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MapIndexesMetadata {
     /// The list of indexes.
@@ -245,7 +261,8 @@ impl MapIndexesMetadata {
     }
 }
 
-/// A testcase metadata holding a list of indexes of a map
+/// A testcase metadata holding a list of indexes of a map,
+/// the entries that were novel when the testcase was found, for a [`MapFeedback`] tracking novelties
 #[derive(Debug, Serialize, Deserialize)]
 pub struct MapNoveltiesMetadata {
     /// A `list` of novelties.
@@ -454,6 +471,12 @@ where
         let mut interesting = false;
         // TODO Replace with match_name_type when stable
         let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        if let Some(novelties) = self.novelties.as_mut() {
+            novelties.clear();
+        }
+        if let Some(indexes) = self.indexes.as_mut() {
+            indexes.clear();
+        }
 
         let map_state = state
             .named_metadata_mut()
@@ -522,14 +545,16 @@ where
 
         let initial = observer.initial();
         if interesting {
+            // Only the testcases added to the corpus need them
+            if let Some(indexes) = self.indexes.as_mut() {
+                covered_indexes(observer, indexes);
+            }
+
             let len = history_map.len();
             let mut filled = 0;
             for i in 0..len {
                 if history_map[i] != initial {
                     filled += 1;
-                    if self.indexes.is_some() {
-                        self.indexes.as_mut().unwrap().push(i);
-                    }
                 }
             }
             manager.fire(
@@ -568,6 +593,25 @@ where
     }
 }
 
+/// Collects the indexes of the entries of the `observer` the last run covered,
+/// i.e., the entries that are not the initial value, into `indexes`
+fn covered_indexes<O, T>(observer: &O, indexes: &mut Vec<usize>)
+where
+    O: MapObserver<Entry = T>,
+    for<'it> O: AsIter<'it, Item = T>,
+    T: PartialEq + Copy,
+{
+    let initial = observer.initial();
+    indexes.clear();
+    indexes.extend(
+        observer
+            .as_iter()
+            .enumerate()
+            .filter(|(_, item)| **item != initial)
+            .map(|(i, _)| i),
+    );
+}
+
 fn create_stats_name(name: &str) -> String {
     name.to_lowercase()
 }
@@ -594,7 +638,8 @@ where
         }
     }
 
-    /// Create new `MapFeedback` specifying if it must track indexes of used entries and/or novelties
+    /// Create new `MapFeedback` specifying if it must track indexes of used entries and/or novelties.
+    /// They are added to the testcase as [`struct@MapIndexesMetadata`] and [`struct@MapNoveltiesMetadata`].
     #[must_use]
     pub fn new_tracking(map_observer: &O, track_indexes: bool, track_novelties: bool) -> Self {
         Self {
@@ -671,6 +716,12 @@ where
         let mut interesting = false;
         // TODO Replace with match_name_type when stable
         let observer = observers.match_name::<O>(&self.observer_name).unwrap();
+        if let Some(novelties) = self.novelties.as_mut() {
+            novelties.clear();
+        }
+        if let Some(indexes) = self.indexes.as_mut() {
+            indexes.clear();
+        }
 
         let map_state = state
             .named_metadata_mut()
@@ -696,14 +747,16 @@ where
 
         let initial = observer.initial();
         if interesting {
+            // Only the testcases added to the corpus need them
+            if let Some(indexes) = self.indexes.as_mut() {
+                covered_indexes(observer, indexes);
+            }
+
             let len = history_map.len();
             let mut filled = 0;
             for i in 0..len {
                 if history_map[i] != initial {
                     filled += 1;
-                    if self.indexes.is_some() {
-                        self.indexes.as_mut().unwrap().push(i);
-                    }
                 }
             }
             manager.fire(
@@ -825,7 +878,65 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::feedbacks::{AllIsNovel, IsNovel, NextPow2IsNovel};
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list, AsMutSlice},
+        corpus::{InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            AllIsNovel, AndReducer, Feedback, IsNovel, MapIndexesMetadata, MapNoveltiesMetadata,
+            MaxMapFeedback, MaxReducer, MinReducer, NextPow2IsNovel, NopReducer, OrReducer,
+            Reducer,
+        },
+        inputs::BytesInput,
+        observers::StdMapObserver,
+        state::{HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_map_tracking() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut (),
+            &mut (),
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![]);
+        let mut observers = tuple_list!(StdMapObserver::new_owned("map", vec![1_u8, 0, 1, 0]));
+        let mut feedback = MaxMapFeedback::new_tracking(&observers.0, true, true);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |observers: &_, state: &mut StdState<_, _, _, _>| {
+            let interesting = feedback
+                .is_interesting(state, &mut mgr, &input, observers, &ExitKind::Ok)
+                .unwrap();
+            let mut testcase = Testcase::new(input.clone());
+            feedback.append_metadata(state, &mut testcase).unwrap();
+            let indexes = testcase.metadata().get::<MapIndexesMetadata>().unwrap();
+            let novelties = testcase.metadata().get::<MapNoveltiesMetadata>().unwrap();
+            (interesting, indexes.list.clone(), novelties.list.clone())
+        };
+        assert_eq!(run(&observers, &mut state), (true, vec![0, 2], vec![0, 2]));
+
+        // The indexes are the entries of this run, not the whole history
+        observers.0.as_mut_slice().copy_from_slice(&[1_u8, 1, 0, 0]);
+        assert_eq!(run(&observers, &mut state), (true, vec![0, 1], vec![1]));
+
+        // Nothing new, so no indexes
+        assert_eq!(run(&observers, &mut state), (false, vec![], vec![]));
+    }
+
+    #[test]
+    fn test_map_reducers() {
+        assert_eq!(MaxReducer::reduce(3_u8, 5), 5);
+        assert_eq!(MinReducer::reduce(3_u64, 5), 3);
+        assert_eq!(OrReducer::reduce(0b01_u8, 0b10), 0b11);
+        assert_eq!(AndReducer::reduce(0b011_u8, 0b110), 0b010);
+        assert_eq!(NopReducer::reduce(7_u32, 2), 2);
+    }
 
     #[test]
     fn test_map_is_novel() {
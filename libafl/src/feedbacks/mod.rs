@@ -13,6 +13,27 @@ pub mod concolic;
 #[cfg(feature = "std")]
 pub use concolic::ConcolicFeedback;
 
+#[cfg(all(unix, feature = "std", feature = "executors"))]
+pub mod asan;
+#[cfg(all(unix, feature = "std", feature = "executors"))]
+pub use asan::{AsanErrorMetadata, AsanErrorsFeedback};
+
+#[cfg(all(unix, feature = "std"))]
+pub mod resources;
+#[cfg(all(unix, feature = "std"))]
+pub use resources::RusageFeedback;
+
+#[cfg(feature = "std")]
+pub mod target_version;
+#[cfg(feature = "std")]
+pub use target_version::{TargetVersionFeedback, TargetVersionMetadata};
+
+pub mod afl_entry;
+pub use afl_entry::{AflEntryFeedback, AflEntryMetadata};
+
+pub mod rate_limit;
+pub use rate_limit::BucketRateLimitFeedback;
+
 #[cfg(feature = "std")]
 pub mod new_hash_feedback;
 #[cfg(feature = "std")]
@@ -22,7 +43,10 @@ pub use new_hash_feedback::NewHashFeedbackMetadata;
 
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
-use alloc::string::{String, ToString};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{
     fmt::{self, Debug, Formatter},
     marker::PhantomData,
@@ -33,6 +57,8 @@ use core::{
 pub use nautilus::*;
 use serde::{Deserialize, Serialize};
 
+#[cfg(all(unix, feature = "std", feature = "executors"))]
+use crate::state::HasMetadata;
 use crate::{
     bolts::tuples::Named,
     corpus::Testcase,
@@ -120,6 +146,44 @@ where
     fn discard_metadata(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
         Ok(())
     }
+
+    /// Combines this feedback with `other`, interesting if both are, see [`EagerAndFeedback`]
+    fn and<B>(self, other: B) -> EagerAndFeedback<Self, B, S>
+    where
+        Self: Sized,
+        B: Feedback<S>,
+    {
+        EagerAndFeedback::new(self, other)
+    }
+
+    /// Combines this feedback with `other`, interesting if both are.
+    /// `other` is skipped if this feedback is not interesting, see [`FastAndFeedback`]
+    fn and_fast<B>(self, other: B) -> FastAndFeedback<Self, B, S>
+    where
+        Self: Sized,
+        B: Feedback<S>,
+    {
+        FastAndFeedback::new(self, other)
+    }
+
+    /// Combines this feedback with `other`, interesting if any of them is, see [`EagerOrFeedback`]
+    fn or<B>(self, other: B) -> EagerOrFeedback<Self, B, S>
+    where
+        Self: Sized,
+        B: Feedback<S>,
+    {
+        EagerOrFeedback::new(self, other)
+    }
+
+    /// Combines this feedback with `other`, interesting if any of them is.
+    /// `other` is skipped if this feedback is interesting, see [`FastOrFeedback`]
+    fn or_fast<B>(self, other: B) -> FastOrFeedback<Self, B, S>
+    where
+        Self: Sized,
+        B: Feedback<S>,
+    {
+        FastOrFeedback::new(self, other)
+    }
 }
 
 /// Has an associated observer name (mostly used to retrieve the observer with `MatchName` from an `ObserverTuple`)
@@ -697,18 +761,19 @@ macro_rules! feedback_and {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::EagerAndFeedback::new($head , feedback_and!($($tail),+))
+        $crate::feedbacks::EagerAndFeedback::new($head , $crate::feedback_and!($($tail),+))
     };
 }
-///
-/// Variadic macro to create a chain of (fast) [`AndFeedback`](FastAndFeedback)
+
+/// Variadic macro to create a chain of (fast) [`AndFeedback`](FastAndFeedback),
+/// not executing feedbacks after the first negative result
 #[macro_export]
 macro_rules! feedback_and_fast {
     ( $last:expr ) => { $last };
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::FastAndFeedback::new($head , feedback_and_fast!($($tail),+))
+        $crate::feedbacks::FastAndFeedback::new($head , $crate::feedback_and_fast!($($tail),+))
     };
 }
 
@@ -719,7 +784,7 @@ macro_rules! feedback_or {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::EagerOrFeedback::new($head , feedback_or!($($tail),+))
+        $crate::feedbacks::EagerOrFeedback::new($head , $crate::feedback_or!($($tail),+))
     };
 }
 
@@ -730,7 +795,7 @@ macro_rules! feedback_or_fast {
 
     ( $head:expr, $($tail:expr), +) => {
         // recursive call
-        $crate::feedbacks::FastOrFeedback::new($head , feedback_or_fast!($($tail),+))
+        $crate::feedbacks::FastOrFeedback::new($head , $crate::feedback_or_fast!($($tail),+))
     };
 }
 
@@ -771,7 +836,8 @@ impl Named for () {
     }
 }
 
-/// A [`CrashFeedback`] reports as interesting if the target crashed.
+/// A [`CrashFeedback`] reports as interesting if the target crashed, see [`ExitKind::is_crash`].
+/// It only looks at the [`ExitKind`] of the run, so it works with any executor.
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct CrashFeedback {}
 
@@ -792,11 +858,7 @@ where
         EM: EventFirer<State = S>,
         OT: ObserversTuple<S>,
     {
-        if let ExitKind::Crash = exit_kind {
-            Ok(true)
-        } else {
-            Ok(false)
-        }
+        Ok(exit_kind.is_crash())
     }
 }
 
@@ -824,11 +886,14 @@ impl Default for CrashFeedback {
 /// A feedback factory for crash feedbacks
 pub type CrashFeedbackFactory = DefaultFeedbackFactory<CrashFeedback>;
 
-/// A [`TimeoutFeedback`] reduces the timeout value of a run.
+/// A [`CustomExitKindFeedback`] reports as interesting if the harness returned one of the given [`ExitKind::Custom`] kinds.
+/// This way, each custom kind can be routed to the feedback, to keep exploring from it, or to the objective.
 #[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct TimeoutFeedback {}
+pub struct CustomExitKindFeedback {
+    kinds: Vec<u32>,
+}
 
-impl<S> Feedback<S> for TimeoutFeedback
+impl<S> Feedback<S> for CustomExitKindFeedback
 where
     S: UsesInput + HasClientPerfMonitor,
 {
@@ -845,14 +910,105 @@ where
         EM: EventFirer<State = S>,
         OT: ObserversTuple<S>,
     {
-        if let ExitKind::Timeout = exit_kind {
-            Ok(true)
+        if let ExitKind::Custom(kind) = exit_kind {
+            Ok(self.kinds.contains(kind))
         } else {
             Ok(false)
         }
     }
 }
 
+impl Named for CustomExitKindFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "CustomExitKindFeedback"
+    }
+}
+
+impl CustomExitKindFeedback {
+    /// Creates a new [`CustomExitKindFeedback`], reporting the given custom `kinds` as interesting
+    #[must_use]
+    pub fn new(kinds: &[u32]) -> Self {
+        Self {
+            kinds: kinds.to_vec(),
+        }
+    }
+}
+
+/// A [`SignalFeedback`] reports as interesting if the target was terminated by one of the given signals,
+/// see [`ExitKind::Signal`]. Use it to put, e.g., segfaults and aborts in separate objectives.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SignalFeedback {
+    signals: Vec<i32>,
+}
+
+impl<S> Feedback<S> for SignalFeedback
+where
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        Ok(exit_kind
+            .signal()
+            .map_or(false, |signal| self.signals.contains(&signal)))
+    }
+}
+
+impl Named for SignalFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "SignalFeedback"
+    }
+}
+
+impl SignalFeedback {
+    /// Creates a new [`SignalFeedback`], reporting the given `signals` as interesting
+    #[must_use]
+    pub fn new(signals: &[i32]) -> Self {
+        Self {
+            signals: signals.to_vec(),
+        }
+    }
+}
+
+/// A [`TimeoutFeedback`] reports as interesting if the run timed out.
+/// Combine it with a [`CrashFeedback`], e.g., `CrashFeedback::new().or(TimeoutFeedback::new())`,
+/// to keep both crashes and timeouts as objectives.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TimeoutFeedback {}
+
+impl<S> Feedback<S> for TimeoutFeedback
+where
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        Ok(*exit_kind == ExitKind::Timeout)
+    }
+}
+
 impl Named for TimeoutFeedback {
     #[inline]
     fn name(&self) -> &str {
@@ -877,6 +1033,69 @@ impl Default for TimeoutFeedback {
 /// A feedback factory for timeout feedbacks
 pub type TimeoutFeedbackFactory = DefaultFeedbackFactory<TimeoutFeedback>;
 
+/// An [`OomFeedback`] reports as interesting if the run ran out of memory.
+/// Testcases get the [`OomMetadata`](crate::executors::oom::OomMetadata) of the run,
+/// if an [`OomExecutor`](crate::executors::OomExecutor) measured it.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct OomFeedback {}
+
+impl<S> Feedback<S> for OomFeedback
+where
+    S: UsesInput + HasClientPerfMonitor,
+{
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        _state: &mut S,
+        _manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        Ok(*exit_kind == ExitKind::Oom)
+    }
+
+    #[cfg(all(unix, feature = "std", feature = "executors"))]
+    fn append_metadata(
+        &mut self,
+        _state: &mut S,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error> {
+        if let Some(oom) = crate::executors::oom::last_oom() {
+            testcase.add_metadata(oom);
+        }
+        Ok(())
+    }
+}
+
+impl Named for OomFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "OomFeedback"
+    }
+}
+
+impl OomFeedback {
+    /// Returns a new [`OomFeedback`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Default for OomFeedback {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A feedback factory for out of memory feedbacks
+pub type OomFeedbackFactory = DefaultFeedbackFactory<OomFeedback>;
+
 /// Nop feedback that annotates execution time in the new testcase, if any
 /// for this Feedback, the testcase is never interesting (use with an OR).
 /// It decides, if the given [`TimeObserver`] value of a run is interesting.
@@ -1086,6 +1305,116 @@ impl From<bool> for ConstFeedback {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+
+    use crate::{
+        bolts::tuples::{tuple_list, Named},
+        events::{EventFirer, NopEventManager},
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, CrashFeedback, Feedback, TimeoutFeedback},
+        inputs::{BytesInput, UsesInput},
+        observers::ObserversTuple,
+        state::{HasClientPerfMonitor, NopState},
+        Error,
+    };
+
+    /// Counts how often it was asked
+    #[derive(Debug)]
+    struct CountingFeedback<'a>(bool, &'a Cell<usize>);
+
+    impl Named for CountingFeedback<'_> {
+        fn name(&self) -> &str {
+            "CountingFeedback"
+        }
+    }
+
+    impl<S> Feedback<S> for CountingFeedback<'_>
+    where
+        S: UsesInput + HasClientPerfMonitor,
+    {
+        fn is_interesting<EM, OT>(
+            &mut self,
+            _state: &mut S,
+            _manager: &mut EM,
+            _input: &S::Input,
+            _observers: &OT,
+            _exit_kind: &ExitKind,
+        ) -> Result<bool, Error>
+        where
+            EM: EventFirer<State = S>,
+            OT: ObserversTuple<S>,
+        {
+            self.1.set(self.1.get() + 1);
+            Ok(self.0)
+        }
+    }
+
+    fn check<F>(mut feedback: F, exit_kind: ExitKind) -> bool
+    where
+        F: Feedback<NopState<BytesInput>>,
+    {
+        feedback
+            .is_interesting(
+                &mut NopState::new(),
+                &mut NopEventManager::new(),
+                &BytesInput::new(vec![]),
+                &tuple_list!(),
+                &exit_kind,
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn test_feedback_logic() {
+        let (t, f) = (ConstFeedback::True, ConstFeedback::False);
+        assert!(check(crate::feedback_or!(f, f, t), ExitKind::Ok));
+        assert!(!check(crate::feedback_or_fast!(f, f), ExitKind::Ok));
+        assert!(check(crate::feedback_and!(t, t, t), ExitKind::Ok));
+        assert!(!check(crate::feedback_and_fast!(t, f, t), ExitKind::Ok));
+        assert!(check(crate::feedback_not!(f), ExitKind::Ok));
+
+        // The fast variants skip the second feedback once the result is known
+        let calls = Cell::new(0);
+        assert!(check(
+            crate::feedback_or_fast!(t, CountingFeedback(false, &calls)),
+            ExitKind::Ok
+        ));
+        assert!(!check(
+            crate::feedback_and_fast!(f, CountingFeedback(true, &calls)),
+            ExitKind::Ok
+        ));
+        assert_eq!(calls.get(), 0);
+        assert!(check(
+            crate::feedback_or!(t, CountingFeedback(false, &calls)),
+            ExitKind::Ok
+        ));
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_exit_kind_feedbacks() {
+        let objective = || CrashFeedback::new().or(TimeoutFeedback::new());
+        assert!(check(objective(), ExitKind::Crash));
+        assert!(check(objective(), ExitKind::Signal(6)));
+        assert!(check(objective(), ExitKind::Timeout));
+        assert!(!check(objective(), ExitKind::Oom));
+        assert!(!check(
+            CrashFeedback::new().and_fast(ConstFeedback::False),
+            ExitKind::Crash
+        ));
+        assert!(check(
+            TimeoutFeedback::new().or_fast(ConstFeedback::False),
+            ExitKind::Timeout
+        ));
+        assert!(check(
+            TimeoutFeedback::new().and(ConstFeedback::True),
+            ExitKind::Timeout
+        ));
+    }
+}
+
 /// `Feedback` Python bindings
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
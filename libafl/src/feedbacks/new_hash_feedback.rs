@@ -1,4 +1,4 @@
-//! The ``NewHashFeedback`` uses the backtrace hash and a hashset to only keep novel cases
+//! The ``NewHashFeedback`` uses the hash of an observer, such as a backtrace, and a hashset to only keep novel cases
 
 use alloc::string::{String, ToString};
 use std::{fmt::Debug, marker::PhantomData};
@@ -66,7 +66,8 @@ impl HashSetState<u64> for NewHashFeedbackMetadata {
     }
 }
 
-/// A [`NewHashFeedback`] maintains a hashset of already seen stacktraces and considers interesting unseen ones
+/// A [`NewHashFeedback`] maintains a hashset of the already seen hashes of an [`ObserverWithHashField`],
+/// such as stacktraces, `ASan` reports or the output of the target, and considers interesting unseen ones
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct NewHashFeedback<O, S> {
     name: String,
@@ -99,7 +100,7 @@ where
     {
         let observer = observers
             .match_name::<O>(&self.observer_name)
-            .expect("A NewHashFeedback needs a BacktraceObserver");
+            .expect("A NewHashFeedback needs an ObserverWithHashField");
 
         let backtrace_state = state
             .named_metadata_mut()
@@ -160,3 +161,52 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{ConstFeedback, Feedback, NewHashFeedback},
+        inputs::BytesInput,
+        observers::{Observer, StdOutObserver},
+        state::{NopState, StdState},
+    };
+
+    #[test]
+    fn test_new_hash_feedback() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut ConstFeedback::False,
+            &mut ConstFeedback::False,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![]);
+        let mut observer = StdOutObserver::new("stdout".to_string());
+        let mut feedback = NewHashFeedback::new(&observer);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |observer: &mut StdOutObserver, stdout: &str| {
+            Observer::<NopState<BytesInput>>::observe_stdout(observer, stdout);
+            feedback
+                .is_interesting(
+                    &mut state,
+                    &mut mgr,
+                    &input,
+                    &tuple_list!(observer.clone()),
+                    &ExitKind::Ok,
+                )
+                .unwrap()
+        };
+        assert!(run(&mut observer, "a"));
+        assert!(run(&mut observer, "b"));
+        assert!(!run(&mut observer, "a"));
+    }
+}
@@ -0,0 +1,255 @@
+//! The [`BucketRateLimitFeedback`] limits how many objectives are kept per crash bucket.
+//!
+//! A shallow crash may fire thousands of times per second. Each time, the state gets serialized,
+//! the solution gets written, and an event gets broadcasted, which tanks the throughput.
+//! With this feedback, only the first few objectives of each bucket are kept, later ones are only counted.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Debug, marker::PhantomData};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named,
+    corpus::Testcase,
+    events::EventFirer,
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    observers::{ObserverWithHashField, ObserversTuple},
+    state::{HasClientPerfMonitor, HasNamedMetadata},
+    Error,
+};
+
+/// The prefix of the metadata names
+pub const BUCKET_RATE_LIMIT_PREFIX: &str = "bucketratelimit_metadata_";
+
+/// The default number of objectives kept per bucket
+pub const DEFAULT_MAX_PER_BUCKET: u64 = 4;
+
+/// The state of [`BucketRateLimitFeedback`]
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct BucketRateLimitMetadata {
+    /// The number of objectives seen, per bucket
+    pub counts: HashMap<u64, u64>,
+    /// The number of objectives that were only counted, but not kept
+    pub dropped: u64,
+}
+
+crate::impl_serdeany!(BucketRateLimitMetadata);
+
+impl BucketRateLimitMetadata {
+    /// Create a new [`BucketRateLimitMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of objectives seen for the bucket with the given hash
+    #[must_use]
+    pub fn count(&self, bucket: u64) -> u64 {
+        self.counts.get(&bucket).copied().unwrap_or(0)
+    }
+}
+
+/// A [`BucketRateLimitFeedback`] wraps an objective [`Feedback`], and only reports the first `max_per_bucket`
+/// objectives of each bucket as interesting. The bucket is the hash of an [`ObserverWithHashField`],
+/// such as a [`crate::observers::BacktraceObserver`].
+///
+/// Objectives without a hash are always reported.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BucketRateLimitFeedback<F, O, S> {
+    inner: F,
+    name: String,
+    observer_name: String,
+    max_per_bucket: u64,
+    phantom: PhantomData<(O, S)>,
+}
+
+impl<F, O, S> Feedback<S> for BucketRateLimitFeedback<F, O, S>
+where
+    F: Feedback<S>,
+    O: ObserverWithHashField + Named + Debug,
+    S: UsesInput + Debug + HasNamedMetadata + HasClientPerfMonitor,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        state.add_named_metadata(BucketRateLimitMetadata::new(), &self.name);
+        self.inner.init_state(state)
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        input: &<S as UsesInput>::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        if !self
+            .inner
+            .is_interesting(state, manager, input, observers, exit_kind)?
+        {
+            return Ok(false);
+        }
+
+        let observer = observers
+            .match_name::<O>(&self.observer_name)
+            .expect("A BucketRateLimitFeedback needs an ObserverWithHashField");
+        let bucket = match observer.hash() {
+            Some(hash) => *hash,
+            None => return Ok(true),
+        };
+
+        let meta = state
+            .named_metadata_mut()
+            .get_mut::<BucketRateLimitMetadata>(&self.name)
+            .unwrap();
+        let count = meta.counts.entry(bucket).or_insert(0);
+        *count += 1;
+        if *count > self.max_per_bucket {
+            meta.dropped += 1;
+            Ok(false)
+        } else {
+            Ok(true)
+        }
+    }
+
+    #[inline]
+    fn append_metadata(
+        &mut self,
+        state: &mut S,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error> {
+        self.inner.append_metadata(state, testcase)
+    }
+
+    #[inline]
+    fn discard_metadata(&mut self, state: &mut S, input: &S::Input) -> Result<(), Error> {
+        self.inner.discard_metadata(state, input)
+    }
+}
+
+impl<F, O, S> Named for BucketRateLimitFeedback<F, O, S> {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<F, O, S> HasObserverName for BucketRateLimitFeedback<F, O, S> {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl<F, O, S> BucketRateLimitFeedback<F, O, S>
+where
+    O: ObserverWithHashField + Named + Debug,
+{
+    /// Returns a new [`BucketRateLimitFeedback`], keeping [`DEFAULT_MAX_PER_BUCKET`] objectives per bucket.
+    #[must_use]
+    pub fn new(inner: F, observer: &O) -> Self {
+        Self::with_max_per_bucket(inner, observer, DEFAULT_MAX_PER_BUCKET)
+    }
+
+    /// Returns a new [`BucketRateLimitFeedback`], keeping `max_per_bucket` objectives per bucket.
+    #[must_use]
+    pub fn with_max_per_bucket(inner: F, observer: &O, max_per_bucket: u64) -> Self {
+        Self {
+            inner,
+            name: BUCKET_RATE_LIMIT_PREFIX.to_string() + observer.name(),
+            observer_name: observer.name().to_string(),
+            max_per_bucket,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::ExitKind,
+        feedbacks::{
+            rate_limit::{BucketRateLimitFeedback, BucketRateLimitMetadata},
+            ConstFeedback, Feedback,
+        },
+        inputs::BytesInput,
+        observers::{ObserverWithHashField, StdOutObserver},
+        state::{HasNamedMetadata, StdState},
+    };
+
+    #[test]
+    fn test_bucket_rate_limit() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut ConstFeedback::False,
+            &mut ConstFeedback::False,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+        let input = BytesInput::new(vec![]);
+        let mut observer = StdOutObserver::new("stdout".to_string());
+        let mut feedback =
+            BucketRateLimitFeedback::with_max_per_bucket(ConstFeedback::True, &observer, 2);
+        feedback.init_state(&mut state).unwrap();
+
+        let mut run = |state: &mut StdState<_, _, _, _>, observer: &StdOutObserver| {
+            feedback
+                .is_interesting(
+                    state,
+                    &mut mgr,
+                    &input,
+                    &tuple_list!(observer.clone()),
+                    &ExitKind::Crash,
+                )
+                .unwrap()
+        };
+
+        // Objectives without a bucket are always kept
+        assert!(run(&mut state, &observer));
+        assert!(run(&mut state, &observer));
+        assert!(run(&mut state, &observer));
+
+        observer.update_hash(1);
+        assert!(run(&mut state, &observer));
+        assert!(run(&mut state, &observer));
+        assert!(!run(&mut state, &observer));
+        observer.update_hash(2);
+        assert!(run(&mut state, &observer));
+
+        // The counts are part of the state, so the limit holds after a restart
+        let serialized = postcard::to_allocvec(&state).unwrap();
+        let mut state: StdState<
+            _,
+            InMemoryCorpus<BytesInput>,
+            StdRand,
+            InMemoryCorpus<BytesInput>,
+        > = postcard::from_bytes(&serialized).unwrap();
+        let meta = state
+            .named_metadata()
+            .get::<BucketRateLimitMetadata>("bucketratelimit_metadata_stdout")
+            .unwrap();
+        assert_eq!(meta.count(1), 3);
+        assert_eq!(meta.count(2), 1);
+        assert_eq!(meta.dropped, 1);
+
+        assert!(run(&mut state, &observer));
+        assert!(!run(&mut state, &observer));
+        observer.update_hash(1);
+        assert!(!run(&mut state, &observer));
+    }
+}
@@ -0,0 +1,224 @@
+//! The [`RusageFeedback`] accounts the resources used by the target, as recorded by a [`RusageObserver`].
+//!
+//! It reports the average CPU time of the target to the monitor, separate from the overhead of the fuzzer,
+//! and can flag runs exceeding a CPU time or memory limit, for example as objective.
+
+use alloc::string::{String, ToString};
+use core::{marker::PhantomData, time::Duration};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{current_time, tuples::Named},
+    events::{Event, EventFirer},
+    executors::ExitKind,
+    feedbacks::{Feedback, HasObserverName},
+    inputs::UsesInput,
+    monitors::UserStats,
+    observers::{ObserversTuple, ResourceUsage, RusageObserver},
+    state::{HasClientPerfMonitor, HasMetadata},
+    Error,
+};
+
+/// The name of the user stat reported by the [`RusageFeedback`], the average CPU time of the target per run, in microseconds
+pub const TARGET_CPU_STATS_NAME: &str = "target cpu us/exec";
+
+/// The interval at which the [`RusageFeedback`] reports to the monitor
+const REPORT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A state metadata accumulating the resources used by the target over all runs
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub struct RusageStatsMetadata {
+    /// The number of runs accounted
+    pub runs: u64,
+    /// The total time the target spent in user mode
+    pub user_time: Duration,
+    /// The total time the target spent in kernel mode
+    pub sys_time: Duration,
+    /// The maximum resident set size of the target, in kilobytes
+    pub max_rss_kb: u64,
+    /// The total number of page faults of the target
+    pub page_faults: u64,
+    /// The total number of children forked for the target
+    pub forks: u64,
+}
+
+crate::impl_serdeany!(RusageStatsMetadata);
+
+impl RusageStatsMetadata {
+    /// Accounts the resources used by a single run
+    pub fn add(&mut self, usage: &ResourceUsage) {
+        self.runs += 1;
+        self.user_time += usage.user_time;
+        self.sys_time += usage.sys_time;
+        self.max_rss_kb = self.max_rss_kb.max(usage.max_rss_kb);
+        self.page_faults += usage.minor_faults + usage.major_faults;
+        self.forks += usage.forks;
+    }
+
+    /// The average CPU time of the target per run
+    #[must_use]
+    pub fn avg_cpu_time(&self) -> Duration {
+        if self.runs == 0 {
+            Duration::ZERO
+        } else {
+            let avg_nanos = (self.user_time + self.sys_time).as_nanos() / u128::from(self.runs);
+            Duration::from_nanos(avg_nanos.try_into().unwrap_or(u64::MAX))
+        }
+    }
+}
+
+/// A [`RusageFeedback`] accounts the resources used by the target in the [`struct@RusageStatsMetadata`],
+/// and periodically reports the average CPU time of the target as user stat.
+/// A run is interesting if it exceeds the configured CPU time or maximum resident set size.
+/// Without limits, it is never interesting, and only does the accounting.
+///
+/// The accounting happens in `is_interesting`, so combined with `feedback_and_fast` or `feedback_or_fast`,
+/// runs get skipped whenever the feedbacks before it already decided.
+/// Put it first, or combine it with `feedback_and` or `feedback_or`, to account all runs.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RusageFeedback {
+    name: String,
+    observer_name: String,
+    max_cpu_time: Option<Duration>,
+    max_rss_kb: Option<u64>,
+    last_report: Duration,
+}
+
+impl<S> Feedback<S> for RusageFeedback
+where
+    S: UsesInput + HasClientPerfMonitor + HasMetadata,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        if !state.has_metadata::<RusageStatsMetadata>() {
+            state.add_metadata(RusageStatsMetadata::default());
+        }
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        _input: &S::Input,
+        observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        let observer = observers
+            .match_name::<RusageObserver>(&self.observer_name)
+            .unwrap();
+        let usage = match observer.last_usage() {
+            Some(usage) => *usage,
+            None => return Ok(false),
+        };
+
+        let meta = state
+            .metadata_mut()
+            .get_mut::<RusageStatsMetadata>()
+            .unwrap();
+        meta.add(&usage);
+        let avg_cpu_time = meta.avg_cpu_time();
+
+        let cur_time = current_time();
+        if cur_time >= self.last_report + REPORT_INTERVAL {
+            self.last_report = cur_time;
+            manager.fire(
+                state,
+                Event::UpdateUserStats {
+                    name: TARGET_CPU_STATS_NAME.to_string(),
+                    value: UserStats::Float(avg_cpu_time.as_secs_f64() * 1_000_000.0),
+                    phantom: PhantomData,
+                },
+            )?;
+        }
+
+        Ok(
+            matches!(self.max_cpu_time, Some(max) if usage.cpu_time() > max)
+                || matches!(self.max_rss_kb, Some(max) if usage.max_rss_kb > max),
+        )
+    }
+}
+
+impl Named for RusageFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl HasObserverName for RusageFeedback {
+    #[inline]
+    fn observer_name(&self) -> &str {
+        &self.observer_name
+    }
+}
+
+impl RusageFeedback {
+    /// Creates a new [`RusageFeedback`] for the given [`RusageObserver`], only accounting the resources
+    #[must_use]
+    pub fn new(observer: &RusageObserver) -> Self {
+        Self {
+            name: "RusageFeedback".to_string(),
+            observer_name: observer.name().to_string(),
+            max_cpu_time: None,
+            max_rss_kb: None,
+            last_report: Duration::ZERO,
+        }
+    }
+
+    /// Flags runs using more than `max` CPU time as interesting
+    #[must_use]
+    pub fn with_max_cpu_time(mut self, max: Duration) -> Self {
+        self.max_cpu_time = Some(max);
+        self
+    }
+
+    /// Flags runs reaching a resident set size of more than `max` kilobytes as interesting.
+    /// Unless the executor waits for its children with [`crate::observers::rusage::wait_child`],
+    /// only runs larger than all runs before are measured, see [`ResourceUsage::max_rss_kb`].
+    #[must_use]
+    pub fn with_max_rss_kb(mut self, max: u64) -> Self {
+        self.max_rss_kb = Some(max);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{feedbacks::resources::RusageStatsMetadata, observers::ResourceUsage};
+
+    #[test]
+    fn test_rusage_stats() {
+        let mut stats = RusageStatsMetadata::default();
+        assert_eq!(stats.avg_cpu_time(), Duration::ZERO);
+        for (user_ms, rss) in [(3, 100), (1, 300)] {
+            stats.add(&ResourceUsage {
+                user_time: Duration::from_millis(user_ms),
+                sys_time: Duration::from_millis(1),
+                max_rss_kb: rss,
+                minor_faults: 2,
+                major_faults: 0,
+                forks: 1,
+            });
+        }
+        assert_eq!(stats.avg_cpu_time(), Duration::from_millis(3));
+        assert_eq!(stats.max_rss_kb, 300);
+        assert_eq!(stats.page_faults, 4);
+        assert_eq!(stats.forks, 2);
+
+        // More runs than fit in a `u32`
+        let stats = RusageStatsMetadata {
+            runs: 1 << 33,
+            user_time: Duration::from_micros(1 << 33),
+            ..RusageStatsMetadata::default()
+        };
+        assert_eq!(stats.avg_cpu_time(), Duration::from_micros(1));
+    }
+}
@@ -0,0 +1,273 @@
+//! The [`TargetVersionFeedback`] tags the state and all new testcases with the version of the target,
+//! and warns when a state gets resumed against a different build of the target.
+//!
+//! The coverage map indices of a target change with each build, so resuming a campaign,
+//! or reusing its corpus metadata, after the target got updated silently mixes unrelated coverage.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Write, marker::PhantomData};
+use std::{fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{build_id, tuples::Named},
+    corpus::{Corpus, Testcase},
+    events::{Event, EventFirer},
+    executors::ExitKind,
+    feedbacks::Feedback,
+    inputs::UsesInput,
+    monitors::UserStats,
+    observers::ObserversTuple,
+    state::{HasClientPerfMonitor, HasCorpus, HasMetadata},
+    Error,
+};
+
+/// The name of the user stat reported by the [`TargetVersionFeedback`]
+pub const TARGET_VERSION_STATS_NAME: &str = "target version";
+
+/// The `ELF` note header of a GNU build-id, in little endian: name size 4, descriptor size 20, type 3 (`NT_GNU_BUILD_ID`), name `GNU`.
+const GNU_BUILD_ID_NOTE: [u8; 16] = [4, 0, 0, 0, 20, 0, 0, 0, 3, 0, 0, 0, b'G', b'N', b'U', 0];
+
+/// The size of a `SHA1` GNU build-id
+const GNU_BUILD_ID_LEN: usize = 20;
+
+/// A metadata holding the version of the target.
+/// It is added to the state, and to each testcase, so that it ends up in the on-disk corpus metadata.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TargetVersionMetadata {
+    version: String,
+}
+
+crate::impl_serdeany!(TargetVersionMetadata);
+
+impl TargetVersionMetadata {
+    /// Creates a new [`struct@TargetVersionMetadata`] with a user-supplied version string
+    #[must_use]
+    pub fn new<V>(version: V) -> Self
+    where
+        V: Into<String>,
+    {
+        Self {
+            version: version.into(),
+        }
+    }
+
+    /// Creates a new [`struct@TargetVersionMetadata`] for the target binary at `path`.
+    /// The version is the GNU build-id of the binary, if it has one, else a hash of its content.
+    pub fn from_binary<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let bytes = fs::read(path)?;
+        let build_id = bytes
+            .windows(GNU_BUILD_ID_NOTE.len())
+            .position(|window| window == GNU_BUILD_ID_NOTE)
+            .map(|pos| pos + GNU_BUILD_ID_NOTE.len())
+            .and_then(|start| bytes.get(start..start + GNU_BUILD_ID_LEN));
+        Ok(match build_id {
+            Some(build_id) => {
+                let mut version = "build-id:".to_string();
+                for byte in build_id {
+                    write!(version, "{byte:02x}").unwrap();
+                }
+                Self::new(version)
+            }
+            None => Self::new(format!("xxh3:{:016x}", xxhash_rust::xxh3::xxh3_64(&bytes))),
+        })
+    }
+
+    /// Creates a new [`struct@TargetVersionMetadata`] for the current binary, see [`crate::bolts::build_id::get`].
+    /// Use this for in-process targets, that are linked into the fuzzer.
+    #[must_use]
+    pub fn from_build_id() -> Self {
+        Self::new(format!("build-id:{}", build_id::get()))
+    }
+
+    /// The version of the target
+    #[must_use]
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    /// Tags the `state` with this version.
+    /// If the state already holds a different version, for example because it was resumed after the target got updated,
+    /// or testcases in its corpus were found with a different version, for example when loaded from an older on-disk corpus,
+    /// a warning gets printed and `false` is returned.
+    pub fn check_and_set<S>(&self, state: &mut S) -> bool
+    where
+        S: HasMetadata + HasCorpus,
+    {
+        let mut matches = match state.metadata().get::<Self>() {
+            Some(previous) if previous != self => {
+                println!(
+                    "WARNING: the state was created for target version {}, but the target is now at version {}. \
+                    Coverage map indices may not match, consider starting over with a fresh state.",
+                    previous.version, self.version
+                );
+                false
+            }
+            _ => true,
+        };
+
+        let count = state.corpus().count();
+        let mismatched = (0..count)
+            .filter_map(|idx| state.corpus().get(idx).ok())
+            .filter(|testcase| {
+                matches!(testcase.borrow().metadata().get::<Self>(), Some(version) if version != self)
+            })
+            .count();
+        if mismatched > 0 {
+            println!(
+                "WARNING: {mismatched} of {count} testcases in the corpus were found with another target version than {}. \
+                Their metadata, e.g., coverage map indices, may not match.",
+                self.version
+            );
+            matches = false;
+        }
+
+        state.add_metadata(self.clone());
+        matches
+    }
+}
+
+/// A [`TargetVersionFeedback`] tags the state and every new testcase with a [`struct@TargetVersionMetadata`],
+/// and reports the version to the monitor.
+/// On the first execution, it checks a resumed state, and the testcases loaded into its corpus, against the current version,
+/// and warns on a mismatch.
+/// It is never interesting, so combine it with other feedbacks, e.g., using `feedback_or_fast`.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TargetVersionFeedback {
+    version: TargetVersionMetadata,
+    checked: bool,
+}
+
+impl<S> Feedback<S> for TargetVersionFeedback
+where
+    S: UsesInput + HasClientPerfMonitor + HasMetadata + HasCorpus,
+{
+    fn init_state(&mut self, state: &mut S) -> Result<(), Error> {
+        self.version.check_and_set(state);
+        Ok(())
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn is_interesting<EM, OT>(
+        &mut self,
+        state: &mut S,
+        manager: &mut EM,
+        _input: &S::Input,
+        _observers: &OT,
+        _exit_kind: &ExitKind,
+    ) -> Result<bool, Error>
+    where
+        EM: EventFirer<State = S>,
+        OT: ObserversTuple<S>,
+    {
+        if !self.checked {
+            // A restored state did not go through `init_state`
+            self.checked = true;
+            self.version.check_and_set(state);
+            manager.fire(
+                state,
+                Event::UpdateUserStats {
+                    name: TARGET_VERSION_STATS_NAME.to_string(),
+                    value: UserStats::String(self.version.version().to_string()),
+                    phantom: PhantomData,
+                },
+            )?;
+        }
+        Ok(false)
+    }
+
+    fn append_metadata(
+        &mut self,
+        _state: &mut S,
+        testcase: &mut Testcase<S::Input>,
+    ) -> Result<(), Error> {
+        testcase.add_metadata(self.version.clone());
+        Ok(())
+    }
+}
+
+impl Named for TargetVersionFeedback {
+    #[inline]
+    fn name(&self) -> &str {
+        "TargetVersionFeedback"
+    }
+}
+
+impl TargetVersionFeedback {
+    /// Creates a new [`TargetVersionFeedback`] with the given version
+    #[must_use]
+    pub fn new(version: TargetVersionMetadata) -> Self {
+        Self {
+            version,
+            checked: false,
+        }
+    }
+
+    /// Creates a new [`TargetVersionFeedback`] for the target binary at `path`,
+    /// see [`TargetVersionMetadata::from_binary`]
+    pub fn from_binary<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::new(TargetVersionMetadata::from_binary(path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::{target_version::TargetVersionMetadata, ConstFeedback},
+        inputs::BytesInput,
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_target_version() {
+        let path = env::temp_dir().join(format!("libafl_target_version_{}", process::id()));
+        let mut binary = b"\x7fELF....".to_vec();
+        binary.extend_from_slice(&[4, 0, 0, 0, 20, 0, 0, 0, 3, 0, 0, 0, b'G', b'N', b'U', 0]);
+        binary.extend(0..20_u8);
+        fs::write(&path, &binary).unwrap();
+        let built = TargetVersionMetadata::from_binary(&path).unwrap();
+        assert_eq!(
+            built.version(),
+            "build-id:000102030405060708090a0b0c0d0e0f10111213"
+        );
+        fs::remove_file(&path).unwrap();
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state: StdState<BytesInput, _, _, _> = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        assert!(built.check_and_set(&mut state));
+        assert!(built.check_and_set(&mut state));
+
+        let rebuilt = TargetVersionMetadata::new("v2");
+        assert!(!rebuilt.check_and_set(&mut state));
+        assert_eq!(
+            state.metadata().get::<TargetVersionMetadata>(),
+            Some(&rebuilt)
+        );
+        assert!(rebuilt.check_and_set(&mut state));
+
+        // A testcase of the old version, e.g., from an on-disk corpus
+        let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+        testcase.add_metadata(built);
+        state.corpus_mut().add(testcase).unwrap();
+        assert!(!rebuilt.check_and_set(&mut state));
+    }
+}
@@ -1,11 +1,22 @@
 //! The `Fuzzer` is the main struct for a fuzz campaign.
 
 use alloc::string::ToString;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
 use core::{fmt::Debug, marker::PhantomData, time::Duration};
+#[cfg(feature = "std")]
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
+#[cfg(feature = "std")]
+use hashbrown::HashSet;
 use serde::{de::DeserializeOwned, Serialize};
 
-#[cfg(test)]
+#[cfg(feature = "std")]
+use crate::corpus::Reproducer;
+#[cfg(any(test, feature = "std"))]
 use crate::inputs::Input;
 #[cfg(feature = "introspection")]
 use crate::monitors::PerfFeature;
@@ -14,7 +25,7 @@ use crate::state::NopState;
 use crate::{
     bolts::current_time,
     corpus::{Corpus, Testcase},
-    events::{Event, EventConfig, EventFirer, EventProcessor, ProgressReporter},
+    events::{Event, EventFirer, EventProcessor, LogSeverity, ProgressReporter},
     executors::{Executor, ExitKind, HasObservers},
     feedbacks::Feedback,
     inputs::UsesInput,
@@ -120,6 +131,19 @@ where
         self.evaluate_input_events(state, executor, manager, input, true)
     }
 
+    /// Runs the input and triggers observers and feedback, like [`Evaluator::evaluate_input`],
+    /// but only copies the input if it gets added to a corpus.
+    /// The caller keeps the input, for example to mutate the same buffer again for the next run.
+    fn evaluate_input_ref(
+        &mut self,
+        state: &mut Self::State,
+        executor: &mut E,
+        manager: &mut EM,
+        input: &<Self::State as UsesInput>::Input,
+    ) -> Result<(ExecuteInputResult, Option<usize>), Error> {
+        self.evaluate_input(state, executor, manager, input.clone())
+    }
+
     /// Runs the input and triggers observers and feedback,
     /// returns if is interesting an (option) the index of the new testcase in the corpus
     /// This version has a boolean to decide if send events to the manager.
@@ -227,6 +251,41 @@ where
     }
 }
 
+/// The outcome of running a single file with [`StdFuzzer::replay`]
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayResult {
+    /// The file the input got loaded from
+    pub path: PathBuf,
+    /// How the run finished
+    pub exit_kind: ExitKind,
+    /// If the feedback considered the input interesting, e.g., for new coverage
+    pub interesting: bool,
+    /// If the objective considered the input interesting, e.g., for a crash or a timeout
+    pub solution: bool,
+}
+
+/// Collects all files at `path`, recursing into directories.
+/// Each directory is only visited once, by its canonical path, so symlink loops end.
+#[cfg(feature = "std")]
+fn collect_files(
+    path: &Path,
+    visited: &mut HashSet<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<(), Error> {
+    if path.is_dir() {
+        if !visited.insert(fs::canonicalize(path)?) {
+            return Ok(());
+        }
+        for entry in fs::read_dir(path)? {
+            collect_files(&entry?.path(), visited, files)?;
+        }
+    } else if path.is_file() {
+        files.push(path.to_path_buf());
+    }
+    Ok(())
+}
+
 /// The corpus this input should be added to
 #[derive(Debug, PartialEq, Eq)]
 pub enum ExecuteInputResult {
@@ -331,18 +390,52 @@ where
     ) -> Result<(ExecuteInputResult, Option<usize>), Error>
     where
         EM: EventFirer<State = Self::State>,
+    {
+        let res = self.execution_result(state, manager, &input, observers, exit_kind)?;
+        self.add_execution(
+            state,
+            manager,
+            input,
+            observers,
+            exit_kind,
+            res,
+            send_events,
+        )
+    }
+}
+
+impl<CS, F, OF, OT> StdFuzzer<CS, F, OF, OT>
+where
+    CS: Scheduler,
+    F: Feedback<CS::State>,
+    OF: Feedback<CS::State>,
+    OT: ObserversTuple<CS::State> + Serialize + DeserializeOwned,
+    CS::State: HasCorpus + HasSolutions + HasClientPerfMonitor + HasExecutions,
+{
+    /// Asks the objective, and then the feedback, if the execution is interesting.
+    /// Discards the metadata of both, if it is not.
+    fn execution_result<EM>(
+        &mut self,
+        state: &mut CS::State,
+        manager: &mut EM,
+        input: &<CS::State as UsesInput>::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+    ) -> Result<ExecuteInputResult, Error>
+    where
+        EM: EventFirer<State = CS::State>,
     {
         let mut res = ExecuteInputResult::None;
 
         #[cfg(not(feature = "introspection"))]
         let is_solution = self
             .objective_mut()
-            .is_interesting(state, manager, &input, observers, exit_kind)?;
+            .is_interesting(state, manager, input, observers, exit_kind)?;
 
         #[cfg(feature = "introspection")]
         let is_solution = self
             .objective_mut()
-            .is_interesting_introspection(state, manager, &input, observers, exit_kind)?;
+            .is_interesting_introspection(state, manager, input, observers, exit_kind)?;
 
         if is_solution {
             res = ExecuteInputResult::Solution;
@@ -350,24 +443,42 @@ where
             #[cfg(not(feature = "introspection"))]
             let is_corpus = self
                 .feedback_mut()
-                .is_interesting(state, manager, &input, observers, exit_kind)?;
+                .is_interesting(state, manager, input, observers, exit_kind)?;
 
             #[cfg(feature = "introspection")]
             let is_corpus = self
                 .feedback_mut()
-                .is_interesting_introspection(state, manager, &input, observers, exit_kind)?;
+                .is_interesting_introspection(state, manager, input, observers, exit_kind)?;
 
             if is_corpus {
                 res = ExecuteInputResult::Corpus;
             }
         }
 
+        if res == ExecuteInputResult::None {
+            self.feedback_mut().discard_metadata(state, input)?;
+            self.objective_mut().discard_metadata(state, input)?;
+        }
+        Ok(res)
+    }
+
+    /// Adds the input of an interesting execution to the respective corpus, and fires the right events
+    #[allow(clippy::too_many_arguments)]
+    fn add_execution<EM>(
+        &mut self,
+        state: &mut CS::State,
+        manager: &mut EM,
+        input: <CS::State as UsesInput>::Input,
+        observers: &OT,
+        exit_kind: &ExitKind,
+        res: ExecuteInputResult,
+        send_events: bool,
+    ) -> Result<(ExecuteInputResult, Option<usize>), Error>
+    where
+        EM: EventFirer<State = CS::State>,
+    {
         match res {
-            ExecuteInputResult::None => {
-                self.feedback_mut().discard_metadata(state, &input)?;
-                self.objective_mut().discard_metadata(state, &input)?;
-                Ok((res, None))
-            }
+            ExecuteInputResult::None => Ok((res, None)),
             ExecuteInputResult::Corpus => {
                 // Not a solution
                 self.objective_mut().discard_metadata(state, &input)?;
@@ -379,12 +490,7 @@ where
                 self.scheduler_mut().on_add(state, idx)?;
 
                 if send_events {
-                    // TODO set None for fast targets
-                    let observers_buf = if manager.configuration() == EventConfig::AlwaysUnique {
-                        None
-                    } else {
-                        Some(manager.serialize_observers::<OT>(observers)?)
-                    };
+                    let observers_buf = manager.serialize_observers_if_needed(observers)?;
                     manager.fire(
                         state,
                         Event::NewTestcase {
@@ -406,6 +512,8 @@ where
 
                 // The input is a solution, add it to the respective corpus
                 let mut testcase = Testcase::with_executions(input, *state.executions());
+                // Keep how it failed, to tell different failures apart later
+                testcase.add_metadata(*exit_kind);
                 self.objective_mut().append_metadata(state, &mut testcase)?;
                 state.solutions_mut().add(testcase)?;
 
@@ -475,6 +583,31 @@ where
         self.evaluate_input_with_observers(state, executor, manager, input, send_events)
     }
 
+    /// Process one input, only copying it if it gets added to a corpus
+    fn evaluate_input_ref(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+        input: &<CS::State as UsesInput>::Input,
+    ) -> Result<(ExecuteInputResult, Option<usize>), Error> {
+        let exit_kind = self.execute_input(state, executor, manager, input)?;
+        let observers = executor.observers();
+        let res = self.execution_result(state, manager, input, observers, &exit_kind)?;
+        if res == ExecuteInputResult::None {
+            return Ok((res, None));
+        }
+        self.add_execution(
+            state,
+            manager,
+            input.clone(),
+            observers,
+            &exit_kind,
+            res,
+            true,
+        )
+    }
+
     /// Adds an input, even if it's not considered `interesting` by any of the executors
     fn add_input(
         &mut self,
@@ -496,11 +629,7 @@ where
         let idx = state.corpus_mut().add(testcase)?;
         self.scheduler_mut().on_add(state, idx)?;
 
-        let observers_buf = if manager.configuration() == EventConfig::AlwaysUnique {
-            None
-        } else {
-            Some(manager.serialize_observers::<OT>(observers)?)
-        };
+        let observers_buf = manager.serialize_observers_if_needed(observers)?;
         manager.fire(
             state,
             Event::NewTestcase {
@@ -615,6 +744,184 @@ where
 
         Ok(exit_kind)
     }
+
+    /// Replays all entries of the corpus against the current feedback,
+    /// for example after its configuration changed between runs (new map size, added feedbacks).
+    /// The feedback state is reset and rebuilt from the corpus. Each entry is replaced, in place, by a fresh testcase
+    /// without the metadata of the old feedback, or of the scheduler, only keeping its input, file name and executions.
+    /// Still interesting entries get the metadata of the current feedback, and the scheduler is told about each replacement.
+    /// Entries that are no longer interesting stay in the corpus.
+    /// Returns the number of entries that are still interesting.
+    pub fn reevaluate_corpus<E, EM>(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+    ) -> Result<usize, Error>
+    where
+        E: Executor<EM, Self> + HasObservers<Observers = OT, State = CS::State>,
+        EM: EventFirer<State = CS::State>,
+        OT: ObserversTuple<CS::State>,
+        CS::State: HasCorpus,
+    {
+        self.feedback_mut().init_state(state)?;
+
+        let mut interesting = 0;
+        for idx in 0..state.corpus().count() {
+            let (input, was_loaded, filename, executions) = {
+                let mut testcase = state.corpus().get(idx)?.borrow_mut();
+                let was_loaded = testcase.input().is_some();
+                let input = testcase.load_input()?.clone();
+                (
+                    input,
+                    was_loaded,
+                    testcase.filename().clone(),
+                    *testcase.executions(),
+                )
+            };
+            let exit_kind = self.execute_input(state, executor, manager, &input)?;
+            let observers = executor.observers();
+
+            let is_interesting = self
+                .feedback_mut()
+                .is_interesting(state, manager, &input, observers, &exit_kind)?;
+            if !is_interesting {
+                self.feedback_mut().discard_metadata(state, &input)?;
+            }
+
+            let mut testcase = Testcase::with_executions(input, executions);
+            *testcase.filename_mut() = filename;
+            if is_interesting {
+                interesting += 1;
+                self.feedback_mut().append_metadata(state, &mut testcase)?;
+            }
+            if !was_loaded {
+                // Leave it to the corpus to load the input again
+                *testcase.input_mut() = None;
+            }
+
+            // Replace the entry in its cell, so that corpora on disk keep the files of the entry
+            let old = state.corpus().get(idx)?.replace(testcase);
+            self.scheduler_mut().on_replace(state, idx, &old)?;
+        }
+        Ok(interesting)
+    }
+
+    /// Runs each file in `in_paths`, and in the directories among them, once, without mutations,
+    /// to check if crashes reproduce, or what a corpus covers, e.g., in CI.
+    /// Nothing gets added to the corpus, or the solutions. The feedback state gets updated though,
+    /// so an input is only `interesting` if it covers something none of the files before covered.
+    /// The files run in lexicographic order. Real crashes end an in-process executor, so prefer a forking one.
+    #[cfg(feature = "std")]
+    pub fn replay<E, EM>(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+        in_paths: &[PathBuf],
+    ) -> Result<Vec<ReplayResult>, Error>
+    where
+        E: Executor<EM, Self> + HasObservers<Observers = OT, State = CS::State>,
+        EM: EventFirer<State = CS::State>,
+        OT: ObserversTuple<CS::State>,
+    {
+        let mut files = vec![];
+        let mut visited = HashSet::new();
+        for path in in_paths {
+            collect_files(path, &mut visited, &mut files)?;
+        }
+        files.sort();
+
+        let mut results = Vec::with_capacity(files.len());
+        for path in files {
+            let input = <CS::State as UsesInput>::Input::from_file(&path)?;
+            results.push(self.replay_input(state, executor, manager, path, &input)?);
+        }
+        Ok(results)
+    }
+
+    /// Runs the [`crate::corpus::Reproducer`] at `path`, as written by the [`crate::corpus::ReproducerCorpus`], once,
+    /// with the observers enabled, e.g., for `--repro <file>` of the same fuzzer binary.
+    /// As for [`StdFuzzer::replay`], nothing gets added to the corpus, or the solutions.
+    #[cfg(feature = "std")]
+    pub fn reproduce<E, EM>(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+        path: &Path,
+    ) -> Result<ReplayResult, Error>
+    where
+        E: Executor<EM, Self> + HasObservers<Observers = OT, State = CS::State>,
+        EM: EventFirer<State = CS::State>,
+        OT: ObserversTuple<CS::State>,
+    {
+        let reproducer = Reproducer::<<CS::State as UsesInput>::Input>::from_file(path)?;
+        manager.log(
+            state,
+            LogSeverity::Info,
+            format!(
+                "Reproducing {}, found with: {}",
+                path.display(),
+                reproducer.config
+            ),
+        )?;
+        if let Some(exit_kind) = reproducer.metadata.get::<ExitKind>() {
+            manager.log(state, LogSeverity::Info, format!("Expected: {exit_kind:?}"))?;
+        }
+        self.replay_input(
+            state,
+            executor,
+            manager,
+            path.to_path_buf(),
+            &reproducer.input,
+        )
+    }
+
+    /// Runs a single input for [`StdFuzzer::replay`] and [`StdFuzzer::reproduce`]
+    #[cfg(feature = "std")]
+    fn replay_input<E, EM>(
+        &mut self,
+        state: &mut CS::State,
+        executor: &mut E,
+        manager: &mut EM,
+        path: PathBuf,
+        input: &<CS::State as UsesInput>::Input,
+    ) -> Result<ReplayResult, Error>
+    where
+        E: Executor<EM, Self> + HasObservers<Observers = OT, State = CS::State>,
+        EM: EventFirer<State = CS::State>,
+        OT: ObserversTuple<CS::State>,
+    {
+        let exit_kind = self.execute_input(state, executor, manager, input)?;
+        let observers = executor.observers();
+
+        let interesting = self
+            .feedback_mut()
+            .is_interesting(state, manager, input, observers, &exit_kind)?;
+        self.feedback_mut().discard_metadata(state, input)?;
+        let solution = self
+            .objective_mut()
+            .is_interesting(state, manager, input, observers, &exit_kind)?;
+        self.objective_mut().discard_metadata(state, input)?;
+
+        manager.log(
+            state,
+            LogSeverity::Info,
+            format!(
+                "Replayed {}: {exit_kind:?}{}{}",
+                path.display(),
+                if interesting { ", interesting" } else { "" },
+                if solution { ", solution" } else { "" }
+            ),
+        )?;
+        Ok(ReplayResult {
+            path,
+            exit_kind,
+            interesting,
+            solution,
+        })
+    }
 }
 
 /// Structs with this trait will execute an input
@@ -712,6 +1019,141 @@ where
     }
 }
 
+#[cfg(all(test, feature = "std", feature = "executors"))]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::{ConstFeedback, CrashFeedback, MapIndexesMetadata, MaxMapFeedback},
+        inputs::{BytesInput, HasBytesVec},
+        observers::StdMapObserver,
+        schedulers::QueueScheduler,
+        state::{HasCorpus, HasMetadata, HasSolutions, StdState},
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_replay() {
+        let dir = env::temp_dir().join(format!("libafl_replay_{}", process::id()));
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a"), b"fine").unwrap();
+        fs::write(dir.join("sub").join("b"), b"crash").unwrap();
+        // A symlink loop must not recurse forever
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&dir, dir.join("sub").join("loop")).unwrap();
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = CrashFeedback::new();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mgr = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+
+        let mut harness = |input: &BytesInput| {
+            if input.bytes() == b"crash" {
+                ExitKind::Crash
+            } else {
+                ExitKind::Ok
+            }
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )
+        .unwrap();
+
+        let results = fuzzer
+            .replay(
+                &mut state,
+                &mut executor,
+                &mut mgr,
+                core::slice::from_ref(&dir),
+            )
+            .unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].exit_kind, ExitKind::Ok);
+        assert!(!results[0].solution);
+        assert_eq!(results[1].exit_kind, ExitKind::Crash);
+        assert!(results[1].solution);
+        // Replaying never adds anything
+        assert_eq!(state.corpus().count(), 0);
+        assert_eq!(state.solutions().count(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_reevaluate_corpus() {
+        static mut MAP: [u8; 4] = [0; 4];
+
+        let observer = StdMapObserver::new("map", unsafe { &mut MAP });
+        let mut feedback = MaxMapFeedback::new_tracking(&observer, true, false);
+        let mut objective = CrashFeedback::new();
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        // The corpus of a previous run, with the metadata of a feedback that no longer exists
+        for byte in [0, 0, 1] {
+            let mut testcase = Testcase::new(BytesInput::new(vec![byte]));
+            testcase.add_metadata(MapIndexesMetadata::new(vec![3]));
+            testcase.set_fuzz_leve(5);
+            state.corpus_mut().add(testcase).unwrap();
+        }
+        let mut mgr = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+
+        let mut harness = |input: &BytesInput| {
+            unsafe { MAP[input.bytes()[0] as usize] = 1 };
+            ExitKind::Ok
+        };
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(observer),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )
+        .unwrap();
+
+        // The second entry covers nothing new
+        let interesting = fuzzer
+            .reevaluate_corpus(&mut state, &mut executor, &mut mgr)
+            .unwrap();
+        assert_eq!(interesting, 2);
+        assert_eq!(state.corpus().count(), 3);
+
+        let indexes = |idx: usize| {
+            let testcase = state.corpus().get(idx).unwrap().borrow();
+            assert_eq!(testcase.fuzz_level(), 0);
+            testcase
+                .metadata()
+                .get::<MapIndexesMetadata>()
+                .map(|meta| meta.list.clone())
+        };
+        assert_eq!(indexes(0), Some(vec![0]));
+        assert_eq!(indexes(1), None);
+        assert_eq!(indexes(2), Some(vec![1]));
+    }
+}
+
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
 /// `Fuzzer` Python bindings
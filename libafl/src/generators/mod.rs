@@ -1,7 +1,7 @@
 //! Generators may generate bytes or, in general, data, for inputs.
 
 use alloc::vec::Vec;
-use core::{cmp::min, marker::PhantomData};
+use core::{cmp::min, marker::PhantomData, time::Duration};
 
 use crate::{
     bolts::rands::Rand,
@@ -21,16 +21,59 @@ pub use nautilus::*;
 /// The maximum size of dummy bytes generated by _dummy generator methods
 const DUMMY_BYTES_MAX: usize = 64;
 
+/// The default number of attempts per requested input, see [`GenerationBudget`]
+pub const DEFAULT_GENERATION_TRIES_PER_INPUT: usize = 16;
+
+/// Limits the effort spent on generating inputs, for example in
+/// [`crate::state::StdState::generate_initial_inputs`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GenerationBudget {
+    /// The maximum number of attempts per requested input.
+    /// Duplicates and inputs that were not added to the corpus count as failed attempts.
+    pub tries_per_input: usize,
+    /// The maximum time to spend generating, or `None` for no time limit.
+    pub timeout: Option<Duration>,
+}
+
+impl Default for GenerationBudget {
+    fn default() -> Self {
+        Self {
+            tries_per_input: DEFAULT_GENERATION_TRIES_PER_INPUT,
+            timeout: None,
+        }
+    }
+}
+
+impl GenerationBudget {
+    /// Creates a new [`GenerationBudget`]
+    #[must_use]
+    pub fn new(tries_per_input: usize, timeout: Option<Duration>) -> Self {
+        Self {
+            tries_per_input,
+            timeout,
+        }
+    }
+}
+
 /// Generators can generate ranges of bytes.
 pub trait Generator<I, S>
 where
     I: Input,
 {
-    /// Generate a new input
+    /// Generate a new input.
+    ///
+    /// Generators that cannot produce any more (unique) inputs should return [`Error::IteratorEnd`],
+    /// callers will then stop generating gracefully.
     fn generate(&mut self, state: &mut S) -> Result<I, Error>;
 
     /// Generate a new dummy input
     fn generate_dummy(&self, state: &mut S) -> I;
+
+    /// The budget for generating inputs with this generator.
+    /// Generators with a small output space may want to limit the number of attempts.
+    fn budget(&self) -> GenerationBudget {
+        GenerationBudget::default()
+    }
 }
 
 /// A Generator that produces [`GeneralizedInput`]s from a wrapped [`BytesInput`] generator
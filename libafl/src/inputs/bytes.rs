@@ -105,3 +105,82 @@ impl BytesInput {
         Self { bytes }
     }
 }
+
+/// `BytesInput` Python bindings
+#[cfg(feature = "python")]
+pub mod pybind {
+    use alloc::{boxed::Box, vec::Vec};
+
+    use pyo3::{prelude::*, types::PyBytes};
+
+    use crate::{
+        bolts::ownedref::OwnedPtrMut,
+        inputs::{BytesInput, HasBytesVec},
+    };
+
+    #[pyclass(unsendable, name = "BytesInput")]
+    #[derive(Debug)]
+    /// Python class for BytesInput
+    pub struct PythonBytesInput {
+        /// Rust wrapped BytesInput object
+        pub inner: OwnedPtrMut<BytesInput>,
+    }
+
+    impl PythonBytesInput {
+        /// Wraps an input of the fuzzer, so that Python code, e.g., a mutator, can change it in place.
+        /// The wrapper holds a raw pointer to `r`, and Python code may keep it after the call it was passed to.
+        /// Call [`PythonBytesInput::detach`] once the call returns, so that it never outlives `r`.
+        pub fn wrap(r: &mut BytesInput) -> Self {
+            Self {
+                inner: OwnedPtrMut::Ptr(r),
+            }
+        }
+
+        /// Replaces the wrapped input by a copy owned by this wrapper,
+        /// so Python code keeping the wrapper no longer touches the input of the fuzzer.
+        pub fn detach(&mut self) {
+            if let OwnedPtrMut::Ptr(_) = self.inner {
+                self.inner = OwnedPtrMut::Owned(Box::new(self.inner.as_ref().clone()));
+            }
+        }
+
+        /// The wrapped input
+        #[must_use]
+        pub fn unwrap(&self) -> &BytesInput {
+            self.inner.as_ref()
+        }
+
+        /// The wrapped input (mutable)
+        pub fn unwrap_mut(&mut self) -> &mut BytesInput {
+            self.inner.as_mut()
+        }
+    }
+
+    #[pymethods]
+    impl PythonBytesInput {
+        #[new]
+        fn new(bytes: Vec<u8>) -> Self {
+            Self {
+                inner: OwnedPtrMut::Owned(Box::new(BytesInput::new(bytes))),
+            }
+        }
+
+        fn bytes<'py>(&self, py: Python<'py>) -> &'py PyBytes {
+            PyBytes::new(py, self.inner.as_ref().bytes())
+        }
+
+        fn set_bytes(&mut self, bytes: Vec<u8>) {
+            *self.inner.as_mut().bytes_mut() = bytes;
+        }
+
+        fn __len__(&self) -> usize {
+            self.inner.as_ref().bytes().len()
+        }
+    }
+
+    /// Register the classes to the python module
+    pub fn register(_py: Python, m: &PyModule) -> PyResult<()> {
+        m.add_class::<PythonBytesInput>()?;
+        Ok(())
+    }
+}
@@ -235,3 +235,33 @@ impl GeneralizedInput {
         &mut self.generalized
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::inputs::{GeneralizedInput, GeneralizedItem};
+
+    #[test]
+    fn test_generalized() {
+        let mut input = GeneralizedInput::new(b"(a+b)".to_vec());
+        input.generalized_from_options(&[Some(b'('), None, Some(b'+'), None, Some(b')')]);
+        assert_eq!(
+            input.generalized().unwrap(),
+            [
+                GeneralizedItem::Gap,
+                GeneralizedItem::Bytes(b"(".to_vec()),
+                GeneralizedItem::Gap,
+                GeneralizedItem::Bytes(b"+".to_vec()),
+                GeneralizedItem::Gap,
+                GeneralizedItem::Bytes(b")".to_vec()),
+                GeneralizedItem::Gap,
+            ]
+        );
+        assert_eq!(input.generalized_len(), 7);
+        assert_eq!(input.generalized_to_bytes(), b"(+)");
+
+        // Adjacent gaps get merged
+        input.generalized_extend(&[GeneralizedItem::Gap, GeneralizedItem::Bytes(b"!".to_vec())]);
+        assert_eq!(input.generalized_to_bytes(), b"(+)!");
+        assert_eq!(input.generalized().unwrap().len(), 8);
+    }
+}
@@ -0,0 +1,352 @@
+//! The [`MappedBytesInput`] is a bytes input backed by a private memory mapping of a file,
+//! for targets taking inputs of hundreds of megabytes, such as disk images or videos.
+//!
+//! Loading it only maps the file, and the pages get read lazily, on first access.
+//! Mutating it in place only copies the pages actually touched, the file itself never changes.
+//! Use the mutators working on a [`crate::inputs::HasBytesSlice`], such as the [`crate::mutators::in_place_mutations`],
+//! that never resize the input.
+//!
+//! For large seed corpora fuzzed with all mutations, use the [`LazyBytesInput`] instead:
+//! its seeds stay mapped in the corpus, and each mutated copy gets materialized on its first mutation.
+
+use alloc::{rc::Rc, string::String, vec::Vec};
+use core::{fmt, hash::Hasher, ptr, slice};
+use std::{fs::File, os::unix::io::AsRawFd, path::Path};
+
+use ahash::AHasher;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+    bolts::{fs::write_file_atomic, ownedref::OwnedSlice, HasLen},
+    inputs::{HasBytesSlice, HasBytesVec, HasTargetBytes, Input},
+    Error,
+};
+
+/// A bytes input, backed by a private, copy-on-write memory mapping.
+///
+/// Clones share the mapping, until one of them gets written to, which then copies the bytes into a mapping of its own.
+///
+/// The file the input got loaded from must not be truncated while the input is alive,
+/// else accessing the now missing pages crashes with `SIGBUS`.
+#[derive(Clone)]
+pub struct MappedBytesInput {
+    mapping: Rc<Mapping>,
+}
+
+/// A private memory mapping, unmapped on drop
+struct Mapping {
+    ptr: *mut u8,
+    len: usize,
+}
+
+impl MappedBytesInput {
+    /// Creates a new [`MappedBytesInput`] holding a copy of `bytes`, in an anonymous mapping
+    pub fn new(bytes: &[u8]) -> Result<Self, Error> {
+        let mapping = Mapping::new(bytes.len(), libc::MAP_ANONYMOUS, -1)?;
+        unsafe {
+            slice::from_raw_parts_mut(mapping.ptr, mapping.len).copy_from_slice(bytes);
+        }
+        Ok(Self {
+            mapping: Rc::new(mapping),
+        })
+    }
+
+    /// If this input shares its mapping with a clone, so writing to it needs a copy first
+    #[must_use]
+    pub fn is_shared(&self) -> bool {
+        Rc::strong_count(&self.mapping) > 1
+    }
+}
+
+impl Mapping {
+    /// Maps `len` bytes privately, either of the file `fd`, or anonymous memory
+    fn new(len: usize, flags: libc::c_int, fd: libc::c_int) -> Result<Self, Error> {
+        if len == 0 {
+            return Ok(Self {
+                ptr: ptr::NonNull::dangling().as_ptr(),
+                len,
+            });
+        }
+        let ptr = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                len,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | flags,
+                fd,
+                0,
+            )
+        };
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::file(std::io::Error::last_os_error()));
+        }
+        Ok(Self {
+            ptr: ptr.cast(),
+            len,
+        })
+    }
+}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len > 0 {
+            unsafe {
+                libc::munmap(self.ptr.cast(), self.len);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for MappedBytesInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MappedBytesInput")
+            .field("len", &self.mapping.len)
+            .field("shared", &self.is_shared())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Serialize for MappedBytesInput {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.bytes_slice())
+    }
+}
+
+impl<'de> Deserialize<'de> for MappedBytesInput {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        Self::new(&bytes).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Input for MappedBytesInput {
+    /// Write this input to the file
+    fn to_file<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        write_file_atomic(path, self.bytes_slice())
+    }
+
+    /// Map the content of this input from a file
+    fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len() as usize;
+        Ok(Self {
+            mapping: Rc::new(Mapping::new(len, 0, file.as_raw_fd())?),
+        })
+    }
+
+    /// Generate a name for this input
+    fn generate_name(&self, _idx: usize) -> String {
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        hasher.write(self.bytes_slice());
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+impl HasBytesSlice for MappedBytesInput {
+    #[inline]
+    fn bytes_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.mapping.ptr, self.mapping.len) }
+    }
+
+    /// Copies the bytes into a mapping of their own first, if the mapping is shared with a clone
+    #[inline]
+    fn bytes_slice_mut(&mut self) -> &mut [u8] {
+        if self.is_shared() {
+            *self =
+                Self::new(self.bytes_slice()).expect("Failed to map memory for the written input");
+        }
+        unsafe { slice::from_raw_parts_mut(self.mapping.ptr, self.mapping.len) }
+    }
+}
+
+impl HasTargetBytes for MappedBytesInput {
+    #[inline]
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        OwnedSlice::from(self.bytes_slice())
+    }
+}
+
+impl HasLen for MappedBytesInput {
+    #[inline]
+    fn len(&self) -> usize {
+        self.mapping.len
+    }
+}
+
+/// A bytes input that maps its file when loaded, and copies the bytes into an owned [`Vec`] on the first mutation.
+///
+/// Loading, e.g., with [`crate::state::StdState::load_initial_inputs`], only maps the seed files,
+/// and clones of a mapped input share the mapping, so the corpus never holds copies of the seeds.
+/// Unlike the [`MappedBytesInput`], all mutators working on a [`HasBytesVec`] can be used.
+#[derive(Clone, Debug)]
+pub enum LazyBytesInput {
+    /// The input is still backed by the mapped file
+    Mapped(MappedBytesInput),
+    /// The input has been mutated, or created from bytes
+    Owned(Vec<u8>),
+}
+
+impl LazyBytesInput {
+    /// Creates a new [`LazyBytesInput`], owning the `bytes`
+    #[must_use]
+    pub fn new(bytes: Vec<u8>) -> Self {
+        Self::Owned(bytes)
+    }
+
+    /// If this input is still backed by a mapped file
+    #[must_use]
+    pub fn is_mapped(&self) -> bool {
+        matches!(self, Self::Mapped(_))
+    }
+}
+
+impl Serialize for LazyBytesInput {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(self.bytes())
+    }
+}
+
+impl<'de> Deserialize<'de> for LazyBytesInput {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Self::Owned(Vec::<u8>::deserialize(deserializer)?))
+    }
+}
+
+impl Input for LazyBytesInput {
+    /// Write this input to the file
+    fn to_file<P>(&self, path: P) -> Result<(), Error>
+    where
+        P: AsRef<Path>,
+    {
+        write_file_atomic(path, self.bytes())
+    }
+
+    /// Map the content of this input from a file
+    fn from_file<P>(path: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        Ok(Self::Mapped(MappedBytesInput::from_file(path)?))
+    }
+
+    /// Generate a name for this input
+    fn generate_name(&self, _idx: usize) -> String {
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        hasher.write(self.bytes());
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+impl HasBytesVec for LazyBytesInput {
+    #[inline]
+    fn bytes(&self) -> &[u8] {
+        match self {
+            Self::Mapped(mapped) => mapped.bytes_slice(),
+            Self::Owned(bytes) => bytes,
+        }
+    }
+
+    fn bytes_mut(&mut self) -> &mut Vec<u8> {
+        if let Self::Mapped(mapped) = self {
+            *self = Self::Owned(mapped.bytes_slice().to_vec());
+        }
+        match self {
+            Self::Owned(bytes) => bytes,
+            Self::Mapped(_) => unreachable!(),
+        }
+    }
+}
+
+impl HasTargetBytes for LazyBytesInput {
+    #[inline]
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        OwnedSlice::from(self.bytes())
+    }
+}
+
+impl HasLen for LazyBytesInput {
+    #[inline]
+    fn len(&self) -> usize {
+        self.bytes().len()
+    }
+}
+
+impl From<Vec<u8>> for LazyBytesInput {
+    fn from(bytes: Vec<u8>) -> Self {
+        Self::new(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::inputs::{HasBytesSlice, HasBytesVec, Input, LazyBytesInput, MappedBytesInput};
+
+    #[test]
+    fn test_mapped_input() {
+        let path = env::temp_dir().join(format!("libafl_mapped_input_{}", process::id()));
+        fs::write(&path, b"some large disk image").unwrap();
+
+        let mut input = MappedBytesInput::from_file(&path).unwrap();
+        assert_eq!(input.bytes_slice(), b"some large disk image");
+        input.bytes_slice_mut()[0] = b'S';
+        let mut copy = input.clone();
+        assert!(copy.is_shared());
+
+        // Writing to a clone copies the bytes first, the original stays untouched
+        copy.bytes_slice_mut()[1] = b'O';
+        assert!(!copy.is_shared() && !input.is_shared());
+        assert_eq!(input.bytes_slice(), b"Some large disk image");
+        drop(input);
+
+        // The mapping is private, so the file stays untouched
+        assert_eq!(fs::read(&path).unwrap(), b"some large disk image");
+        assert_eq!(copy.bytes_slice(), b"SOme large disk image");
+
+        let restored: MappedBytesInput =
+            postcard::from_bytes(&postcard::to_allocvec(&copy).unwrap()).unwrap();
+        assert_eq!(restored.bytes_slice(), copy.bytes_slice());
+        assert!(MappedBytesInput::new(&[]).unwrap().bytes_slice().is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_lazy_input() {
+        let path = env::temp_dir().join(format!("libafl_lazy_input_{}", process::id()));
+        fs::write(&path, b"large seed").unwrap();
+
+        let seed = LazyBytesInput::from_file(&path).unwrap();
+        let mut mutated = seed.clone();
+        assert!(mutated.is_mapped());
+        assert_eq!(mutated.bytes(), b"large seed");
+
+        // The first mutation copies the bytes, the seed stays mapped
+        mutated.bytes_mut().extend_from_slice(b"!!");
+        assert!(!mutated.is_mapped());
+        assert_eq!(mutated.bytes(), b"large seed!!");
+        assert!(seed.is_mapped());
+        assert_eq!(seed.bytes(), b"large seed");
+
+        fs::remove_file(&path).unwrap();
+    }
+}
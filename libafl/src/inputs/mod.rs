@@ -12,8 +12,17 @@ pub use gramatron::*;
 pub mod generalized;
 pub use generalized::*;
 
+pub mod value;
+pub use value::ValueInput;
+
+pub mod multipart;
+pub use multipart::{MultipartInput, PartsEncoding};
+
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
+
+#[cfg(all(unix, feature = "std"))]
+pub mod mapped;
 use alloc::{
     string::{String, ToString},
     vec::Vec,
@@ -22,6 +31,8 @@ use core::{clone::Clone, fmt::Debug};
 #[cfg(feature = "std")]
 use std::{fs::File, hash::Hash, io::Read, path::Path};
 
+#[cfg(all(unix, feature = "std"))]
+pub use mapped::{LazyBytesInput, MappedBytesInput};
 #[cfg(feature = "nautilus")]
 pub use nautilus::*;
 use serde::{Deserialize, Serialize};
@@ -110,6 +121,31 @@ pub trait HasBytesVec {
     fn bytes_mut(&mut self) -> &mut Vec<u8>;
 }
 
+/// Contains bytes that can be mutated in place, but not resized,
+/// such as the bytes of a [`MappedBytesInput`].
+/// All inputs with an internal bytes [`Vec`] implement it.
+pub trait HasBytesSlice {
+    /// The bytes
+    fn bytes_slice(&self) -> &[u8];
+    /// The bytes (as mutable borrow)
+    fn bytes_slice_mut(&mut self) -> &mut [u8];
+}
+
+impl<I> HasBytesSlice for I
+where
+    I: HasBytesVec,
+{
+    #[inline]
+    fn bytes_slice(&self) -> &[u8] {
+        self.bytes()
+    }
+
+    #[inline]
+    fn bytes_slice_mut(&mut self) -> &mut [u8] {
+        self.bytes_mut()
+    }
+}
+
 /// Defines the input type shared across traits of the type.
 /// Needed for consistency across HasCorpus/HasSolutions and friends.
 pub trait UsesInput {
@@ -0,0 +1,155 @@
+//! The [`MultipartInput`] is made of several named parts, such as the header and the body of a request,
+//! for harnesses taking more than one buffer.
+
+use alloc::{string::String, vec::Vec};
+use core::hash::Hasher;
+
+use ahash::AHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{ownedref::OwnedSlice, AsSlice},
+    inputs::{HasTargetBytes, Input},
+};
+
+/// How the parts of a [`MultipartInput`] are joined into its target bytes
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PartsEncoding {
+    /// The bytes of the parts, one after the other
+    Concat,
+    /// The bytes of the parts, with the given separator in between
+    Separator(Vec<u8>),
+    /// Each part prefixed with its length, as little-endian `u32`
+    LengthPrefixed,
+}
+
+impl Default for PartsEncoding {
+    fn default() -> Self {
+        Self::Concat
+    }
+}
+
+/// An input made of several named parts.
+/// Use a [`crate::mutators::MultipartMutator`] to apply byte mutators to one part at a time,
+/// and the [`crate::mutators::MultipartCrossoverInsertMutator`] and [`crate::mutators::MultipartCrossoverReplaceMutator`]
+/// to cross over parts with the same name.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct MultipartInput<I> {
+    names: Vec<String>,
+    parts: Vec<I>,
+    encoding: PartsEncoding,
+}
+
+impl<I> Input for MultipartInput<I>
+where
+    I: Input + HasTargetBytes,
+{
+    /// Generate a name for this input
+    fn generate_name(&self, _idx: usize) -> String {
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        for (name, part) in self.names.iter().zip(&self.parts) {
+            hasher.write(name.as_bytes());
+            hasher.write(part.target_bytes().as_slice());
+        }
+        format!("{:016x}", hasher.finish())
+    }
+}
+
+impl<I> HasTargetBytes for MultipartInput<I>
+where
+    I: HasTargetBytes,
+{
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        let mut bytes = vec![];
+        for (i, part) in self.parts.iter().enumerate() {
+            let part_bytes = part.target_bytes();
+            match &self.encoding {
+                PartsEncoding::Concat => (),
+                PartsEncoding::Separator(separator) => {
+                    if i > 0 {
+                        bytes.extend_from_slice(separator);
+                    }
+                }
+                PartsEncoding::LengthPrefixed => {
+                    #[allow(clippy::cast_possible_truncation)]
+                    let len = part_bytes.as_slice().len() as u32;
+                    bytes.extend_from_slice(&len.to_le_bytes());
+                }
+            }
+            bytes.extend_from_slice(part_bytes.as_slice());
+        }
+        OwnedSlice::from(bytes)
+    }
+}
+
+impl<I> MultipartInput<I> {
+    /// Creates a new [`MultipartInput`] without parts, joining them as given by `encoding`
+    #[must_use]
+    pub fn new(encoding: PartsEncoding) -> Self {
+        Self {
+            names: vec![],
+            parts: vec![],
+            encoding,
+        }
+    }
+
+    /// Adds a part named `name`, returning its index
+    pub fn add_part<N>(&mut self, name: N, part: I) -> usize
+    where
+        N: Into<String>,
+    {
+        self.names.push(name.into());
+        self.parts.push(part);
+        self.parts.len() - 1
+    }
+
+    /// The number of parts
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.parts.len()
+    }
+
+    /// Returns `true` if there are no parts
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.parts.is_empty()
+    }
+
+    /// The names of the parts
+    #[must_use]
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    /// The parts
+    #[must_use]
+    pub fn parts(&self) -> &[I] {
+        &self.parts
+    }
+
+    /// The parts (mutable)
+    #[must_use]
+    pub fn parts_mut(&mut self) -> &mut [I] {
+        &mut self.parts
+    }
+
+    /// The first part named `name`, if any
+    #[must_use]
+    pub fn part_by_name(&self, name: &str) -> Option<&I> {
+        let idx = self.names.iter().position(|n| n == name)?;
+        Some(&self.parts[idx])
+    }
+
+    /// The first part named `name`, if any (mutable)
+    #[must_use]
+    pub fn part_by_name_mut(&mut self, name: &str) -> Option<&mut I> {
+        let idx = self.names.iter().position(|n| n == name)?;
+        Some(&mut self.parts[idx])
+    }
+
+    /// How the parts are joined into the target bytes
+    #[must_use]
+    pub fn encoding(&self) -> &PartsEncoding {
+        &self.encoding
+    }
+}
@@ -0,0 +1,192 @@
+//! The [`ValueInput`] is a single number, for targets taking a few scalar parameters instead of a byte buffer.
+
+use alloc::string::String;
+use core::fmt::Debug;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::ownedref::OwnedSlice,
+    inputs::{HasTargetBytes, Input},
+};
+
+/// A number that can be wrapped in a [`ValueInput`], and mutated by the mutators in [`mod@crate::mutators::value_mutations`]
+pub trait Numeric: Copy + Debug + PartialOrd + Serialize + DeserializeOwned + 'static {
+    /// The width of this type, in bits
+    const BITS: u32;
+
+    /// Values that often trigger corner cases
+    const INTERESTING: &'static [Self];
+
+    /// The raw bits of this value, zero-extended to 64 bits
+    fn to_raw_bits(self) -> u64;
+
+    /// Creates a value from the lowest [`Numeric::BITS`] of `bits`
+    fn from_raw_bits(bits: u64) -> Self;
+
+    /// Adds `delta`, wrapping around for integers
+    #[must_use]
+    fn add_delta(self, delta: i64) -> Self;
+
+    /// Wraps this value around into `min..=max`, modulo the size of the range.
+    /// `NaN` and infinite values end up at `min`.
+    #[must_use]
+    fn wrap_within(self, min: Self, max: Self) -> Self;
+}
+
+macro_rules! impl_numeric_int {
+    ($($t:ty),*) => {
+        $(
+            impl Numeric for $t {
+                const BITS: u32 = <$t>::BITS;
+
+                const INTERESTING: &'static [Self] = &[
+                    0,
+                    1,
+                    16,
+                    <$t>::MIN,
+                    <$t>::MIN / 2,
+                    <$t>::MAX,
+                    <$t>::MAX - 1,
+                    <$t>::MAX / 2,
+                ];
+
+                #[allow(clippy::cast_sign_loss, clippy::cast_lossless, trivial_numeric_casts)]
+                fn to_raw_bits(self) -> u64 {
+                    (self as u64) & (u64::MAX >> (64 - Self::BITS))
+                }
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap, trivial_numeric_casts)]
+                fn from_raw_bits(bits: u64) -> Self {
+                    bits as Self
+                }
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_possible_wrap, trivial_numeric_casts)]
+                fn add_delta(self, delta: i64) -> Self {
+                    self.wrapping_add(delta as Self)
+                }
+
+                #[allow(clippy::cast_possible_truncation, clippy::cast_lossless, trivial_numeric_casts)]
+                fn wrap_within(self, min: Self, max: Self) -> Self {
+                    let span = max as i128 - min as i128 + 1;
+                    (min as i128 + (self as i128 - min as i128).rem_euclid(span)) as Self
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_int!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+macro_rules! impl_numeric_float {
+    ($($t:ty, $bits:ty),*) => {
+        $(
+            impl Numeric for $t {
+                const BITS: u32 = <$bits>::BITS;
+
+                const INTERESTING: &'static [Self] = &[
+                    0.0,
+                    -0.0,
+                    1.0,
+                    -1.0,
+                    <$t>::MIN,
+                    <$t>::MAX,
+                    <$t>::MIN_POSITIVE,
+                    <$t>::EPSILON,
+                    <$t>::INFINITY,
+                    <$t>::NEG_INFINITY,
+                    <$t>::NAN,
+                ];
+
+                #[allow(clippy::cast_lossless, trivial_numeric_casts)]
+                fn to_raw_bits(self) -> u64 {
+                    self.to_bits() as u64
+                }
+
+                #[allow(clippy::cast_possible_truncation, trivial_numeric_casts)]
+                fn from_raw_bits(bits: u64) -> Self {
+                    <$t>::from_bits(bits as $bits)
+                }
+
+                #[allow(clippy::cast_precision_loss)]
+                fn add_delta(self, delta: i64) -> Self {
+                    self + delta as Self
+                }
+
+                fn wrap_within(self, min: Self, max: Self) -> Self {
+                    if (min..=max).contains(&self) {
+                        return self;
+                    }
+                    let span = max - min;
+                    let offset = (self - min) % span;
+                    if offset.is_nan() {
+                        return min;
+                    }
+                    let offset = if offset < 0.0 { offset + span } else { offset };
+                    // Rounding may push the sum just past `max`
+                    let value = min + offset;
+                    if value > max {
+                        max
+                    } else {
+                        value
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_numeric_float!(f32, u32, f64, u64);
+
+/// An input holding a single [`Numeric`] value
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, PartialOrd)]
+pub struct ValueInput<T> {
+    value: T,
+}
+
+impl<T> Input for ValueInput<T>
+where
+    T: Numeric,
+{
+    /// Generate a name for this input
+    fn generate_name(&self, _idx: usize) -> String {
+        format!("{:016x}", self.value.to_raw_bits())
+    }
+}
+
+impl<T> HasTargetBytes for ValueInput<T>
+where
+    T: Numeric,
+{
+    /// The value, as little-endian bytes
+    fn target_bytes(&self) -> OwnedSlice<u8> {
+        let bytes = self.value.to_raw_bits().to_le_bytes();
+        OwnedSlice::from(bytes[..(T::BITS / 8) as usize].to_vec())
+    }
+}
+
+impl<T> From<T> for ValueInput<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> ValueInput<T> {
+    /// Creates a new [`ValueInput`] holding `value`
+    #[must_use]
+    pub fn new(value: T) -> Self {
+        Self { value }
+    }
+
+    /// The value
+    #[must_use]
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    /// The value (mutable)
+    #[must_use]
+    pub fn value_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
@@ -450,7 +450,7 @@ pub mod prelude {
 }
 
 // TODO: no_std test
-#[cfg(feature = "std")]
+#[cfg(all(feature = "std", feature = "executors"))]
 #[cfg(test)]
 mod tests {
 
@@ -713,6 +713,7 @@ pub mod pybind {
         executors::pybind::register(py, m)?;
         executors::inprocess::pybind::register(py, m)?;
         generators::pybind::register(py, m)?;
+        inputs::bytes::pybind::register(py, m)?;
         mutators::pybind::register(py, m)?;
         mutators::scheduled::pybind::register(py, m)?;
         corpus::pybind::register(py, m)?;
@@ -3,18 +3,23 @@
 use alloc::{string::String, vec::Vec};
 use core::time::Duration;
 use std::{
-    fs::{File, OpenOptions},
-    io::Write,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, Write},
     path::PathBuf,
 };
 
+use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use crate::{
     bolts::{current_time, format_duration_hms},
     monitors::{ClientStats, Monitor, NopMonitor},
+    Error,
 };
 
+/// The default interval at which the [`OnDiskStatsMonitor`] persists the statistics
+pub const DEFAULT_STATS_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Wrap a monitor and log the current state of the monitor into a TOML file.
 #[derive(Debug, Clone)]
 pub struct OnDiskTOMLMonitor<M>
@@ -45,6 +50,10 @@ where
         self.base.start_time()
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.base.set_start_time(time);
+    }
+
     fn display(&mut self, event_msg: String, sender_id: u32) {
         let cur_time = current_time();
 
@@ -190,6 +199,10 @@ where
         self.base.start_time()
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.base.set_start_time(time);
+    }
+
     fn display(&mut self, event_msg: String, sender_id: u32) {
         if (self.log_record)(&mut self.base) {
             let file = OpenOptions::new()
@@ -212,3 +225,311 @@ where
         self.base.display(event_msg, sender_id);
     }
 }
+
+/// The aggregate statistics of a fuzzing campaign, as persisted by the [`OnDiskStatsMonitor`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PersistedStats {
+    /// The time the campaign started
+    pub start_time: Duration,
+    /// The total executions of each client, by client id
+    pub client_executions: Vec<u64>,
+}
+
+impl PersistedStats {
+    /// The total executions of all clients
+    #[must_use]
+    pub fn total_execs(&self) -> u64 {
+        self.client_executions.iter().sum()
+    }
+}
+
+/// Wraps a base monitor and periodically persists the aggregate statistics into a JSON file.
+/// If the file already exists on creation, the statistics get restored from it,
+/// so that the start time, the total executions, and the executions per client carry over a broker restart.
+#[derive(Debug, Clone)]
+pub struct OnDiskStatsMonitor<M>
+where
+    M: Monitor,
+{
+    base: M,
+    filename: PathBuf,
+    interval: Duration,
+    last_update: Duration,
+}
+
+impl<M> Monitor for OnDiskStatsMonitor<M>
+where
+    M: Monitor,
+{
+    fn client_stats_mut(&mut self) -> &mut Vec<ClientStats> {
+        self.base.client_stats_mut()
+    }
+
+    fn client_stats(&self) -> &[ClientStats] {
+        self.base.client_stats()
+    }
+
+    fn start_time(&mut self) -> Duration {
+        self.base.start_time()
+    }
+
+    fn set_start_time(&mut self, time: Duration) {
+        self.base.set_start_time(time);
+    }
+
+    fn display(&mut self, event_msg: String, sender_id: u32) {
+        let cur_time = current_time();
+        if cur_time >= self.last_update + self.interval {
+            self.last_update = cur_time;
+            self.persist().expect("Failed to persist the statistics");
+        }
+        self.base.display(event_msg, sender_id);
+    }
+}
+
+impl<M> OnDiskStatsMonitor<M>
+where
+    M: Monitor,
+{
+    /// Create a new [`OnDiskStatsMonitor`], persisting the statistics every [`DEFAULT_STATS_PERSIST_INTERVAL`].
+    /// Restores the statistics from `filename`, if it exists.
+    pub fn new<P>(filename: P, base: M) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        Self::with_interval(filename, base, DEFAULT_STATS_PERSIST_INTERVAL)
+    }
+
+    /// Create a new [`OnDiskStatsMonitor`], persisting the statistics every `interval`.
+    /// Restores the statistics from `filename`, if it exists.
+    pub fn with_interval<P>(filename: P, mut base: M, interval: Duration) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let filename = filename.into();
+        if filename.exists() {
+            let stats: PersistedStats =
+                serde_json::from_reader(BufReader::new(File::open(&filename)?))?;
+            base.set_start_time(stats.start_time);
+            for (client_id, executions) in stats.client_executions.iter().enumerate() {
+                base.client_stats_mut_for(client_id as u32)
+                    .restore_executions(*executions);
+            }
+        }
+        Ok(Self {
+            base,
+            filename,
+            interval,
+            last_update: current_time(),
+        })
+    }
+
+    /// The current statistics, as they get persisted
+    pub fn stats(&mut self) -> PersistedStats {
+        PersistedStats {
+            start_time: self.base.start_time(),
+            client_executions: self
+                .base
+                .client_stats()
+                .iter()
+                .map(|client| client.executions)
+                .collect(),
+        }
+    }
+
+    /// Write the current statistics to disk.
+    /// The file gets replaced atomically, so that a crash never leaves a truncated file behind.
+    pub fn persist(&mut self) -> Result<(), Error> {
+        let stats = self.stats();
+        let mut tmp = self.filename.clone().into_os_string();
+        tmp.push(".tmp");
+        fs::write(&tmp, serde_json::to_vec(&stats)?)?;
+        fs::rename(&tmp, &self.filename)?;
+        Ok(())
+    }
+}
+
+/// The header of an AFL++ `plot_data` file, as written by the [`OnDiskPlotDataMonitor`]
+pub const PLOT_DATA_HEADER: &str = "# relative_time, cycles_done, cur_item, corpus_count, pending_total, pending_favs, map_size, saved_crashes, saved_hangs, max_depth, execs_per_sec, total_execs, edges_found";
+
+/// The default interval at which the [`OnDiskPlotDataMonitor`] appends a record
+pub const DEFAULT_PLOT_DATA_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Wraps a base monitor and periodically appends the aggregate statistics to a CSV file
+/// compatible with the AFL++ `plot_data`, so that campaigns can be graphed afterwards with `afl-plot`
+/// or any other tool consuming it.
+/// The columns `LibAFL` does not track, such as the cycles and the pending entries, are always `0`.
+#[derive(Debug, Clone)]
+pub struct OnDiskPlotDataMonitor<M>
+where
+    M: Monitor,
+{
+    base: M,
+    filename: PathBuf,
+    interval: Duration,
+    last_update: Duration,
+}
+
+impl<M> Monitor for OnDiskPlotDataMonitor<M>
+where
+    M: Monitor,
+{
+    fn client_stats_mut(&mut self) -> &mut Vec<ClientStats> {
+        self.base.client_stats_mut()
+    }
+
+    fn client_stats(&self) -> &[ClientStats] {
+        self.base.client_stats()
+    }
+
+    fn start_time(&mut self) -> Duration {
+        self.base.start_time()
+    }
+
+    fn set_start_time(&mut self, time: Duration) {
+        self.base.set_start_time(time);
+    }
+
+    fn display(&mut self, event_msg: String, sender_id: u32) {
+        let cur_time = current_time();
+        if cur_time >= self.last_update + self.interval {
+            self.last_update = cur_time;
+            self.append_record()
+                .expect("Failed to append to the plot_data file");
+        }
+        self.base.display(event_msg, sender_id);
+    }
+}
+
+impl<M> OnDiskPlotDataMonitor<M>
+where
+    M: Monitor,
+{
+    /// Create a new [`OnDiskPlotDataMonitor`], appending a record every [`DEFAULT_PLOT_DATA_INTERVAL`].
+    /// The [`PLOT_DATA_HEADER`] is written, unless `filename` already holds records.
+    pub fn new<P>(filename: P, base: M) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        Self::with_interval(filename, base, DEFAULT_PLOT_DATA_INTERVAL)
+    }
+
+    /// Create a new [`OnDiskPlotDataMonitor`], appending a record every `interval`.
+    /// The [`PLOT_DATA_HEADER`] is written, unless `filename` already holds records.
+    pub fn with_interval<P>(filename: P, base: M, interval: Duration) -> Result<Self, Error>
+    where
+        P: Into<PathBuf>,
+    {
+        let filename = filename.into();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&filename)?;
+        if file.metadata()?.len() == 0 {
+            writeln!(file, "{PLOT_DATA_HEADER}")?;
+        }
+        Ok(Self {
+            base,
+            filename,
+            interval,
+            last_update: current_time(),
+        })
+    }
+
+    /// Append a record with the current statistics to the file
+    pub fn append_record(&mut self) -> Result<(), Error> {
+        let relative_time = current_time()
+            .saturating_sub(self.base.start_time())
+            .as_secs();
+        let corpus_count = self.base.corpus_size();
+        let saved_crashes = self.base.objective_size();
+        let execs_per_sec = self.base.execs_per_sec();
+        let total_execs = self.base.total_execs();
+
+        let mut file = OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.filename)?;
+        writeln!(
+            file,
+            "{relative_time}, 0, 0, {corpus_count}, 0, 0, 0.00%, {saved_crashes}, 0, 0, {execs_per_sec}.00, {total_execs}, 0"
+        )?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+    use std::{env, fs, process};
+
+    use crate::monitors::{
+        disk::{OnDiskPlotDataMonitor, OnDiskStatsMonitor, PLOT_DATA_HEADER},
+        Monitor, NopMonitor,
+    };
+
+    #[test]
+    fn test_stats_persistence() {
+        let path = env::temp_dir().join(format!("libafl_stats_test_{}.json", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut monitor = OnDiskStatsMonitor::new(&path, NopMonitor::new()).unwrap();
+        let start_time = monitor.start_time();
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(1000, start_time);
+        monitor.persist().unwrap();
+
+        // The broker restarts, and the client starts over
+        let mut monitor = OnDiskStatsMonitor::new(&path, NopMonitor::new()).unwrap();
+        assert_eq!(monitor.start_time(), start_time);
+        assert_eq!(monitor.total_execs(), 1000);
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(10, start_time);
+        assert_eq!(monitor.total_execs(), 1010);
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(20, start_time);
+        assert_eq!(monitor.total_execs(), 1020);
+
+        // The broker restarts, but the client kept running, and reports its total
+        let mut monitor = OnDiskStatsMonitor::new(&path, NopMonitor::new()).unwrap();
+        assert_eq!(monitor.total_execs(), 1000);
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(1100, start_time);
+        assert_eq!(monitor.total_execs(), 1100);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_plot_data() {
+        let path = env::temp_dir().join(format!("libafl_plot_data_test_{}", process::id()));
+        let _ = fs::remove_file(&path);
+
+        let mut monitor = OnDiskPlotDataMonitor::new(&path, NopMonitor::new()).unwrap();
+        let start_time = monitor.start_time();
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(1337, start_time);
+        monitor.client_stats_mut_for(1).update_corpus_size(42);
+        monitor.append_record().unwrap();
+
+        // Reopening keeps the records, without repeating the header
+        let mut monitor = OnDiskPlotDataMonitor::new(&path, NopMonitor::new()).unwrap();
+        monitor.append_record().unwrap();
+
+        let plot_data = fs::read_to_string(&path).unwrap();
+        let lines: Vec<&str> = plot_data.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], PLOT_DATA_HEADER);
+        let record: Vec<&str> = lines[1].split(", ").collect();
+        assert_eq!(record.len(), 13);
+        assert_eq!(record[3], "42");
+        assert_eq!(record[11], "1337");
+
+        fs::remove_file(&path).unwrap();
+    }
+}
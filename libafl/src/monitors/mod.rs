@@ -9,21 +9,25 @@ pub mod tui;
 
 #[cfg(feature = "std")]
 pub mod disk;
+
+#[cfg(feature = "std")]
+pub mod prometheus;
 use alloc::{fmt::Debug, string::String, vec::Vec};
-use core::{fmt, time::Duration};
+use core::{any::type_name, fmt, fmt::Write as _, time::Duration};
 
 #[cfg(feature = "std")]
-pub use disk::{OnDiskJSONMonitor, OnDiskTOMLMonitor};
+pub use disk::{OnDiskJSONMonitor, OnDiskPlotDataMonitor, OnDiskStatsMonitor, OnDiskTOMLMonitor};
 use hashbrown::HashMap;
+#[cfg(feature = "std")]
+pub use prometheus::PrometheusMonitor;
 use serde::{Deserialize, Serialize};
 
-use crate::bolts::{current_time, format_duration_hms};
+use crate::bolts::{current_time, format_duration_hms, tuples::NamedTuple};
 
 #[cfg(feature = "afl_exec_sec")]
 const CLIENT_STATS_TIME_WINDOW_SECS: u64 = 5; // 5 seconds
 
 /// User-defined stat types
-/// TODO define aggregation function (avg, median, max, ...)
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum UserStats {
     /// A numerical value
@@ -36,6 +40,37 @@ pub enum UserStats {
     Ratio(u64, u64),
 }
 
+impl UserStats {
+    /// Aggregates the values of the same stat, reported by different clients.
+    /// Numbers and ratios are summed up, floats are averaged.
+    /// Strings are kept, if all clients reported the same one, e.g., the version of the target.
+    /// Different strings, and stats reported with different types, cannot be aggregated.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn aggregate<'a, I>(values: I) -> Option<Self>
+    where
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let mut values = values.into_iter();
+        let mut acc = values.next()?.clone();
+        let mut count = 1_u64;
+        for value in values {
+            acc = match (acc, value) {
+                (UserStats::Number(a), UserStats::Number(b)) => UserStats::Number(a + b),
+                (UserStats::Float(a), UserStats::Float(b)) => UserStats::Float(a + b),
+                (UserStats::Ratio(a, b), UserStats::Ratio(c, d)) => UserStats::Ratio(a + c, b + d),
+                (UserStats::String(a), UserStats::String(b)) if a == *b => UserStats::String(a),
+                _ => return None,
+            };
+            count += 1;
+        }
+        match acc {
+            UserStats::Float(sum) => Some(UserStats::Float(sum / count as f64)),
+            acc => Some(acc),
+        }
+    }
+}
+
 impl fmt::Display for UserStats {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -53,6 +88,111 @@ impl fmt::Display for UserStats {
     }
 }
 
+/// The component configuration of a fuzzer client, reported with [`crate::events::Event::ClientDescription`],
+/// so that operators of heterogeneous fleets can audit what each client is running.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct FuzzerDescription {
+    /// The `LibAFL` version
+    pub version: String,
+    /// The type of the executor
+    pub executor: String,
+    /// The type of the scheduler
+    pub scheduler: String,
+    /// The names of the observers
+    pub observers: Vec<String>,
+    /// The type of the feedback
+    pub feedback: String,
+    /// The type of the objective
+    pub objective: String,
+    /// The type of the stages
+    pub stages: String,
+    /// The names of the mutators
+    pub mutators: Vec<String>,
+}
+
+impl FuzzerDescription {
+    /// Creates a new [`FuzzerDescription`] holding the `LibAFL` version, the components can then be added one by one.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").into(),
+            ..Self::default()
+        }
+    }
+
+    /// Adds the type of the executor
+    #[must_use]
+    pub fn with_executor<E>(mut self, _executor: &E) -> Self {
+        self.executor = type_name::<E>().into();
+        self
+    }
+
+    /// Adds the type of the scheduler
+    #[must_use]
+    pub fn with_scheduler<CS>(mut self, _scheduler: &CS) -> Self {
+        self.scheduler = type_name::<CS>().into();
+        self
+    }
+
+    /// Adds the names of the observers
+    #[must_use]
+    pub fn with_observers<OT>(mut self, observers: &OT) -> Self
+    where
+        OT: NamedTuple,
+    {
+        self.observers = tuple_names(observers);
+        self
+    }
+
+    /// Adds the types of the feedback and the objective
+    #[must_use]
+    pub fn with_feedbacks<F, OF>(mut self, _feedback: &F, _objective: &OF) -> Self {
+        self.feedback = type_name::<F>().into();
+        self.objective = type_name::<OF>().into();
+        self
+    }
+
+    /// Adds the type of the stages
+    #[must_use]
+    pub fn with_stages<ST>(mut self, _stages: &ST) -> Self {
+        self.stages = type_name::<ST>().into();
+        self
+    }
+
+    /// Adds the names of the mutators
+    #[must_use]
+    pub fn with_mutators<MT>(mut self, mutators: &MT) -> Self
+    where
+        MT: NamedTuple,
+    {
+        self.mutators = tuple_names(mutators);
+        self
+    }
+}
+
+fn tuple_names<T>(tuple: &T) -> Vec<String>
+where
+    T: NamedTuple,
+{
+    (0..tuple.len())
+        .filter_map(|i| tuple.name(i))
+        .map(String::from)
+        .collect()
+}
+
+impl fmt::Display for FuzzerDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "LibAFL {}", self.version)?;
+        writeln!(f, "  executor: {}", self.executor)?;
+        writeln!(f, "  scheduler: {}", self.scheduler)?;
+        writeln!(f, "  observers: {}", self.observers.join(", "))?;
+        writeln!(f, "  feedback: {}", self.feedback)?;
+        writeln!(f, "  objective: {}", self.objective)?;
+        writeln!(f, "  stages: {}", self.stages)?;
+        write!(f, "  mutators: {}", self.mutators.join(", "))
+    }
+}
+
 /// A simple struct to keep track of client monitor
 #[derive(Debug, Clone, Default, Serialize)]
 pub struct ClientStats {
@@ -71,8 +211,16 @@ pub struct ClientStats {
     pub last_execs_per_sec: f64,
     /// The last time we got this information
     pub last_window_time: Duration,
+    /// The executions restored from a previous run
+    pub restored_executions: u64,
+    /// The executions the client last reported
+    pub reported_executions: u64,
+    /// The executions before the client last started counting from zero, added to the ones it reports
+    pub executions_before_reset: u64,
     /// User-defined monitor
     pub user_monitor: HashMap<String, UserStats>,
+    /// The component configuration of this client, if it reported it
+    pub description: Option<FuzzerDescription>,
     /// Client performance statistics
     #[cfg(feature = "introspection")]
     pub introspection_monitor: ClientPerfMonitor,
@@ -90,13 +238,36 @@ impl ClientStats {
             self.last_window_time = cur_time;
             self.last_window_executions = self.executions;
         }
-        self.executions = executions;
+        self.add_reported_executions(executions);
     }
 
     /// We got a new information about executions for this client, insert them.
     #[cfg(not(feature = "afl_exec_sec"))]
     pub fn update_executions(&mut self, executions: u64, _cur_time: Duration) {
+        self.add_reported_executions(executions);
+    }
+
+    /// Accounts the executions reported by the client.
+    /// Reporting less than before means the client started counting from zero again, e.g., after the whole client restarted.
+    fn add_reported_executions(&mut self, executions: u64) {
+        if executions < self.reported_executions {
+            self.executions_before_reset = self.executions;
+        }
+        self.reported_executions = executions;
+        self.executions = self.executions_before_reset + executions;
+    }
+
+    /// Restore the executions of this client from a previous run.
+    /// If the client kept running, it keeps reporting its total, else the executions it reports get added to them.
+    pub fn restore_executions(&mut self, executions: u64) {
+        self.restored_executions = executions;
+        self.reported_executions = executions;
+        self.executions_before_reset = 0;
         self.executions = executions;
+        #[cfg(feature = "afl_exec_sec")]
+        {
+            self.last_window_executions = executions;
+        }
     }
 
     /// We got a new information about corpus size for this client, insert them.
@@ -155,7 +326,12 @@ impl ClientStats {
             return 0;
         }
 
-        ((self.executions as f64) / elapsed) as u64
+        ((self.executions - self.restored_executions) as f64 / elapsed) as u64
+    }
+
+    /// Update the component configuration of this client
+    pub fn update_description(&mut self, description: FuzzerDescription) {
+        self.description = Some(description);
     }
 
     /// Update the user-defined stat with name and value
@@ -186,6 +362,10 @@ pub trait Monitor {
     /// Creation time
     fn start_time(&mut self) -> Duration;
 
+    /// Set the creation time, for example to resume the statistics of a previous run.
+    /// Does nothing, by default.
+    fn set_start_time(&mut self, _time: Duration) {}
+
     /// Show the monitor to the user
     fn display(&mut self, event_msg: String, sender_id: u32);
 
@@ -220,6 +400,28 @@ pub trait Monitor {
             .fold(0_u64, |acc, x| acc + x.execs_per_sec(cur_time))
     }
 
+    /// The user-defined stats of all clients, aggregated by name with [`UserStats::aggregate`], sorted by name.
+    /// Stats that cannot be aggregated are left out.
+    fn aggregated_user_stats(&self) -> Vec<(String, UserStats)> {
+        let mut names: Vec<&String> = self
+            .client_stats()
+            .iter()
+            .flat_map(|client| client.user_monitor.keys())
+            .collect();
+        names.sort_unstable();
+        names.dedup();
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let values = self
+                    .client_stats()
+                    .iter()
+                    .filter_map(|client| client.user_monitor.get(name));
+                UserStats::aggregate(values).map(|value| (name.clone(), value))
+            })
+            .collect()
+    }
+
     /// The client monitor for a specific id, creating new if it doesn't exist
     fn client_stats_mut_for(&mut self, client_id: u32) -> &mut ClientStats {
         let client_stat_count = self.client_stats().len();
@@ -257,6 +459,10 @@ impl Monitor for NopMonitor {
         self.start_time
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.start_time = time;
+    }
+
     fn display(&mut self, _event_msg: String, _sender_id: u32) {}
 }
 
@@ -311,6 +517,10 @@ impl Monitor for SimplePrintingMonitor {
         self.start_time
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.start_time = time;
+    }
+
     fn display(&mut self, event_msg: String, sender_id: u32) {
         println!(
             "[{} #{}] run time: {}, clients: {}, corpus: {}, objectives: {}, executions: {}, exec/sec: {}",
@@ -380,8 +590,12 @@ where
         self.start_time
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.start_time = time;
+    }
+
     fn display(&mut self, event_msg: String, sender_id: u32) {
-        let fmt = format!(
+        let mut fmt = format!(
             "[{} #{}] run time: {}, clients: {}, corpus: {}, objectives: {}, executions: {}, exec/sec: {}",
             event_msg,
             sender_id,
@@ -392,6 +606,9 @@ where
             self.total_execs(),
             self.execs_per_sec()
         );
+        for (name, value) in self.aggregated_user_stats() {
+            write!(fmt, ", {name}: {value}").unwrap();
+        }
         (self.print_fn)(fmt);
 
         // Only print perf monitor if the feature is enabled
@@ -789,6 +1006,15 @@ impl ClientPerfMonitor {
         self.scheduler
     }
 
+    /// The amount of cycles spent executing the target, summed up over all stages.
+    /// All remaining cycles are overhead of the fuzzer.
+    #[must_use]
+    pub fn target_execution_cycles(&self) -> u64 {
+        self.used_stages()
+            .map(|(_, features)| features[PerfFeature::TargetExecution as usize])
+            .sum()
+    }
+
     /// Iterator over all used stages
     pub fn used_stages(
         &self,
@@ -823,7 +1049,14 @@ impl core::fmt::Display for ClientPerfMonitor {
         other_percent -= scheduler_percent;
         other_percent -= manager_percent;
 
+        // The share of the target execution over all stages, already included in the stages below
+        let target_percent = self.target_execution_cycles() as f64 / elapsed;
+
         // Create the formatted string
+        writeln!(
+            f,
+            "  {target_percent:6.4}: Target Execution (all stages), the rest is fuzzer overhead"
+        )?;
         writeln!(
             f,
             "  {:6.4}: Scheduler\n  {:6.4}: Manager",
@@ -887,6 +1120,40 @@ impl Default for ClientPerfMonitor {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::monitors::{Monitor, NopMonitor, UserStats};
+
+    #[test]
+    fn test_aggregated_user_stats() {
+        let mut monitor = NopMonitor::new();
+        for (client, hits, stability) in [(1, 3, 1.5), (2, 4, 2.0)] {
+            let stats = monitor.client_stats_mut_for(client);
+            stats.update_user_stats("cmplog hits".into(), UserStats::Number(hits));
+            stats.update_user_stats("stability".into(), UserStats::Float(stability));
+            stats.update_user_stats("edges".into(), UserStats::Ratio(hits, 10));
+            stats.update_user_stats("phase".into(), UserStats::String(format!("stage {client}")));
+            stats.update_user_stats("target version".into(), UserStats::String("v1".into()));
+        }
+
+        let aggregated = monitor.aggregated_user_stats();
+        let names: Vec<&str> = aggregated.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["cmplog hits", "edges", "stability", "target version"]
+        );
+        assert!(matches!(aggregated[0].1, UserStats::Number(7)));
+        assert!(matches!(aggregated[1].1, UserStats::Ratio(7, 20)));
+        assert!(
+            matches!(aggregated[2].1, UserStats::Float(avg) if (avg - 1.75).abs() < f64::EPSILON)
+        );
+        assert!(matches!(&aggregated[3].1, UserStats::String(version) if version == "v1"));
+    }
+}
+
 /// `Monitor` Python bindings
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
@@ -1018,6 +1285,10 @@ pub mod pybind {
             unwrap_me_mut!(self.wrapper, m, { m.start_time() })
         }
 
+        fn set_start_time(&mut self, time: Duration) {
+            unwrap_me_mut!(self.wrapper, m, { m.set_start_time(time) });
+        }
+
         fn display(&mut self, event_msg: String, sender_id: u32) {
             unwrap_me_mut!(self.wrapper, m, { m.display(event_msg, sender_id) });
         }
@@ -40,6 +40,10 @@ where
         self.start_time
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.start_time = time;
+    }
+
     fn display(&mut self, event_msg: String, sender_id: u32) {
         let sender = format!("#{sender_id}");
         let pad = if event_msg.len() + sender.len() < 13 {
@@ -0,0 +1,238 @@
+//! A monitor that wraps a base one and exposes the aggregate statistics to [Prometheus](https://prometheus.io),
+//! over an HTTP `/metrics` endpoint.
+
+use alloc::{string::String, sync::Arc, vec::Vec};
+use core::{fmt::Write as _, time::Duration};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs},
+    sync::Mutex,
+    thread,
+};
+
+use crate::{
+    bolts::current_time,
+    monitors::{ClientStats, Monitor, NopMonitor},
+    Error,
+};
+
+/// How long a scraper may take to send its request, before the connection is dropped.
+/// The requests are served one after the other, so a silent connection would block all others.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The values exported to Prometheus, updated on each [`Monitor::display`]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PrometheusMetrics {
+    /// Total executions of all clients
+    pub executions: u64,
+    /// Executions per second of all clients
+    pub execs_per_sec: u64,
+    /// Size of the corpus of all clients
+    pub corpus_size: u64,
+    /// Number of objectives of all clients
+    pub objectives: u64,
+    /// Number of clients
+    pub clients: u64,
+    /// Time since the campaign started
+    pub run_time: Duration,
+}
+
+impl PrometheusMetrics {
+    /// The metrics in the Prometheus text exposition format
+    #[must_use]
+    pub fn render(&self) -> String {
+        let metrics: [(&str, &str, &str, u64); 6] = [
+            (
+                "libafl_executions_total",
+                "counter",
+                "Total executions of all clients",
+                self.executions,
+            ),
+            (
+                "libafl_execs_per_sec",
+                "gauge",
+                "Executions per second of all clients",
+                self.execs_per_sec,
+            ),
+            (
+                "libafl_corpus_size",
+                "gauge",
+                "Size of the corpus of all clients",
+                self.corpus_size,
+            ),
+            (
+                "libafl_objectives",
+                "gauge",
+                "Number of objectives of all clients",
+                self.objectives,
+            ),
+            ("libafl_clients", "gauge", "Number of clients", self.clients),
+            (
+                "libafl_run_time_seconds",
+                "counter",
+                "Time since the campaign started",
+                self.run_time.as_secs(),
+            ),
+        ];
+
+        let mut out = String::new();
+        for (name, kind, help, value) in metrics {
+            writeln!(out, "# HELP {name} {help}").unwrap();
+            writeln!(out, "# TYPE {name} {kind}").unwrap();
+            writeln!(out, "{name} {value}").unwrap();
+        }
+        out
+    }
+}
+
+/// Wraps a base monitor and serves the aggregate statistics on an HTTP `/metrics` endpoint,
+/// so that Prometheus can scrape them, for example to monitor long-running campaigns in Grafana.
+#[derive(Debug, Clone)]
+pub struct PrometheusMonitor<M>
+where
+    M: Monitor,
+{
+    base: M,
+    metrics: Arc<Mutex<PrometheusMetrics>>,
+    local_addr: SocketAddr,
+}
+
+impl<M> Monitor for PrometheusMonitor<M>
+where
+    M: Monitor,
+{
+    fn client_stats_mut(&mut self) -> &mut Vec<ClientStats> {
+        self.base.client_stats_mut()
+    }
+
+    fn client_stats(&self) -> &[ClientStats] {
+        self.base.client_stats()
+    }
+
+    fn start_time(&mut self) -> Duration {
+        self.base.start_time()
+    }
+
+    fn set_start_time(&mut self, time: Duration) {
+        self.base.set_start_time(time);
+    }
+
+    fn display(&mut self, event_msg: String, sender_id: u32) {
+        let metrics = PrometheusMetrics {
+            executions: self.total_execs(),
+            execs_per_sec: self.execs_per_sec(),
+            corpus_size: self.corpus_size(),
+            objectives: self.objective_size(),
+            clients: self.client_stats().len() as u64,
+            run_time: current_time().saturating_sub(self.start_time()),
+        };
+        *self.metrics.lock().unwrap() = metrics;
+
+        self.base.display(event_msg, sender_id);
+    }
+}
+
+impl<M> PrometheusMonitor<M>
+where
+    M: Monitor,
+{
+    /// Create a new [`PrometheusMonitor`], serving the metrics on `addr`, for example `0.0.0.0:9090`.
+    /// The endpoint is served from a background thread.
+    pub fn new<A>(addr: A, base: M) -> Result<Self, Error>
+    where
+        A: ToSocketAddrs,
+    {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let metrics = Arc::new(Mutex::new(PrometheusMetrics::default()));
+
+        let served = metrics.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // A broken connection should never bring down the broker
+                let _ = serve(stream, &served);
+            }
+        });
+
+        Ok(Self {
+            base,
+            metrics,
+            local_addr,
+        })
+    }
+
+    /// The address the metrics are served on
+    #[must_use]
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// The metrics, as of the last [`Monitor::display`]
+    #[must_use]
+    pub fn metrics(&self) -> PrometheusMetrics {
+        *self.metrics.lock().unwrap()
+    }
+}
+
+impl PrometheusMonitor<NopMonitor> {
+    /// Create a new [`PrometheusMonitor`] without a base
+    pub fn nop<A>(addr: A) -> Result<Self, Error>
+    where
+        A: ToSocketAddrs,
+    {
+        Self::new(addr, NopMonitor::new())
+    }
+}
+
+/// Answers a single HTTP request, serving the metrics on `/metrics`
+fn serve(mut stream: TcpStream, metrics: &Mutex<PrometheusMetrics>) -> Result<(), Error> {
+    stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+
+    let mut parts = request_line.split_whitespace();
+    let response = match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/metrics")) => {
+            let body = metrics.lock().unwrap().render();
+            format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        }
+        _ => "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n".into(),
+    };
+    stream.write_all(response.as_bytes())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::String;
+    use std::{
+        io::{Read, Write},
+        net::TcpStream,
+    };
+
+    use crate::monitors::{prometheus::PrometheusMonitor, Monitor};
+
+    #[test]
+    fn test_prometheus_monitor() {
+        let mut monitor = PrometheusMonitor::nop("127.0.0.1:0").unwrap();
+        let start_time = monitor.start_time();
+        monitor
+            .client_stats_mut_for(1)
+            .update_executions(1337, start_time);
+        monitor.display("test".into(), 1);
+        assert_eq!(monitor.metrics().executions, 1337);
+
+        let mut stream = TcpStream::connect(monitor.local_addr()).unwrap();
+        stream
+            .write_all(b"GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n")
+            .unwrap();
+        let mut response = String::new();
+        stream.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("libafl_executions_total 1337\n"));
+        assert!(response.contains("libafl_clients 2\n"));
+    }
+}
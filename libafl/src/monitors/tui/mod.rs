@@ -256,6 +256,11 @@ impl Monitor for TuiMonitor {
         self.start_time
     }
 
+    fn set_start_time(&mut self, time: Duration) {
+        self.start_time = time;
+        self.context.write().unwrap().start_time = time;
+    }
+
     fn display(&mut self, event_msg: String, sender_id: u32) {
         let cur_time = current_time();
 
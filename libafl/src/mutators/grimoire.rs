@@ -5,7 +5,10 @@ use alloc::vec::Vec;
 use core::cmp::{max, min};
 
 use crate::{
-    bolts::{rands::Rand, tuples::Named},
+    bolts::{
+        rands::Rand,
+        tuples::{tuple_list, tuple_list_type, Named},
+    },
     corpus::Corpus,
     inputs::{GeneralizedInput, GeneralizedItem, UsesInput},
     mutators::{token_mutations::Tokens, MutationResult, Mutator},
@@ -411,3 +414,26 @@ impl GrimoireRandomDeleteMutator {
         }
     }
 }
+
+/// Tuple type of the Grimoire mutations, see [`grimoire_mutations`]
+pub type GrimoireMutationsType = tuple_list_type!(
+    GrimoireExtensionMutator,
+    GrimoireRecursiveReplacementMutator,
+    GrimoireStringReplacementMutator,
+    GrimoireRandomDeleteMutator,
+    GrimoireRandomDeleteMutator,
+);
+
+/// Get the mutations that compose Grimoire.
+/// The random delete is in twice, to keep the inputs from growing too large.
+/// They need a [`crate::stages::GeneralizationStage`] to generalize the corpus entries first.
+#[must_use]
+pub fn grimoire_mutations() -> GrimoireMutationsType {
+    tuple_list!(
+        GrimoireExtensionMutator::new(),
+        GrimoireRecursiveReplacementMutator::new(),
+        GrimoireStringReplacementMutator::new(),
+        GrimoireRandomDeleteMutator::new(),
+        GrimoireRandomDeleteMutator::new(),
+    )
+}
@@ -14,14 +14,29 @@ pub mod gramatron;
 pub use gramatron::*;
 pub mod grimoire;
 pub use grimoire::*;
+pub mod tuneable;
+pub use tuneable::*;
+pub mod text;
+pub use text::*;
+pub mod value_mutations;
+pub use value_mutations::{
+    value_mutations, ValueAddMutator, ValueBitFlipMutator, ValueBounds, ValueInterestingMutator,
+    ValueMutationsType,
+};
+pub mod multipart_mutations;
+pub use multipart_mutations::{
+    MultipartCrossoverInsertMutator, MultipartCrossoverReplaceMutator, MultipartMutator, PartState,
+};
 
 #[cfg(feature = "nautilus")]
 pub mod nautilus;
 #[cfg(feature = "nautilus")]
 pub use nautilus::*;
 
+use alloc::{boxed::Box, vec::Vec};
+
 use crate::{
-    bolts::tuples::{HasConstLen, Named},
+    bolts::tuples::{HasConstLen, Named, NamedTuple},
     inputs::UsesInput,
     Error,
 };
@@ -208,6 +223,133 @@ where
     }
 }
 
+/// A [`Mutator`] with a name.
+/// A [`Vec`] of boxed [`NamedMutator`]s is a [`MutatorsTuple`] assembled at runtime,
+/// e.g., from the command line, see [`IntoMutatorsVec`].
+pub trait NamedMutator<S>: Mutator<S> + Named
+where
+    S: UsesInput,
+{
+}
+
+impl<M, S> NamedMutator<S> for M
+where
+    M: Mutator<S> + Named,
+    S: UsesInput,
+{
+}
+
+/// The length of the list is only known at runtime, so [`HasConstLen::LEN`] is `0`. Use [`HasConstLen::len`] instead.
+impl<S> HasConstLen for Vec<Box<dyn NamedMutator<S>>>
+where
+    S: UsesInput,
+{
+    const LEN: usize = 0;
+
+    fn len(&self) -> usize {
+        <[_]>::len(self)
+    }
+}
+
+impl<S> NamedTuple for Vec<Box<dyn NamedMutator<S>>>
+where
+    S: UsesInput,
+{
+    fn name(&self, index: usize) -> Option<&str> {
+        self.get(index).map(|mutator| mutator.name())
+    }
+}
+
+impl<S> MutatorsTuple<S> for Vec<Box<dyn NamedMutator<S>>>
+where
+    S: UsesInput,
+{
+    fn mutate_all(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let mut result = MutationResult::Skipped;
+        for mutator in self.iter_mut() {
+            if mutator.mutate(state, input, stage_idx)? == MutationResult::Mutated {
+                result = MutationResult::Mutated;
+            }
+        }
+        Ok(result)
+    }
+
+    fn post_exec_all(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        for mutator in self.iter_mut() {
+            mutator.post_exec(state, stage_idx, corpus_idx)?;
+        }
+        Ok(())
+    }
+
+    fn get_and_mutate(
+        &mut self,
+        index: usize,
+        state: &mut S,
+        input: &mut S::Input,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        match self.get_mut(index) {
+            Some(mutator) => mutator.mutate(state, input, stage_idx),
+            None => Ok(MutationResult::Skipped),
+        }
+    }
+
+    fn get_and_post_exec(
+        &mut self,
+        index: usize,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        match self.get_mut(index) {
+            Some(mutator) => mutator.post_exec(state, stage_idx, corpus_idx),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Converts a tuple of [`Mutator`]s into a [`Vec`] of boxed [`NamedMutator`]s,
+/// to pick the mutators at runtime, e.g., `havoc_mutations().into_vec()`, filtered by their names.
+pub trait IntoMutatorsVec<S>
+where
+    S: UsesInput,
+{
+    /// Boxes each [`Mutator`] of this tuple, in order
+    fn into_vec(self) -> Vec<Box<dyn NamedMutator<S>>>;
+}
+
+impl<S> IntoMutatorsVec<S> for ()
+where
+    S: UsesInput,
+{
+    fn into_vec(self) -> Vec<Box<dyn NamedMutator<S>>> {
+        Vec::new()
+    }
+}
+
+impl<Head, Tail, S> IntoMutatorsVec<S> for (Head, Tail)
+where
+    Head: Mutator<S> + Named + 'static,
+    Tail: IntoMutatorsVec<S>,
+    S: UsesInput,
+{
+    fn into_vec(self) -> Vec<Box<dyn NamedMutator<S>>> {
+        let mut vec = self.1.into_vec();
+        vec.insert(0, Box::new(self.0));
+        vec
+    }
+}
+
 /// `Mutator` Python bindings
 #[cfg(feature = "python")]
 #[allow(missing_docs)]
@@ -216,7 +358,7 @@ pub mod pybind {
 
     use super::{MutationResult, Mutator};
     use crate::{
-        inputs::{BytesInput, HasBytesVec},
+        inputs::{bytes::pybind::PythonBytesInput, BytesInput},
         mutators::scheduled::pybind::PythonStdHavocMutator,
         state::pybind::{PythonStdState, PythonStdStateWrapper},
         Error,
@@ -242,13 +384,19 @@ pub mod pybind {
             stage_idx: i32,
         ) -> Result<MutationResult, Error> {
             let mutated = Python::with_gil(|py| -> PyResult<bool> {
-                self.inner
-                    .call_method1(
-                        py,
-                        "mutate",
-                        (PythonStdStateWrapper::wrap(state), input.bytes(), stage_idx),
-                    )?
-                    .extract(py)
+                let wrapped_input = Py::new(py, PythonBytesInput::wrap(input))?;
+                let res = self.inner.call_method1(
+                    py,
+                    "mutate",
+                    (
+                        PythonStdStateWrapper::wrap(state),
+                        wrapped_input.clone_ref(py),
+                        stage_idx,
+                    ),
+                );
+                // The mutator may keep the input around, it must not point to `input` after this call
+                wrapped_input.borrow_mut(py).detach();
+                res?.extract(py)
             })?;
             Ok(if mutated {
                 MutationResult::Mutated
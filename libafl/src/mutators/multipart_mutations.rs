@@ -0,0 +1,439 @@
+//! The [`MultipartMutator`] applies a byte-level mutator to one part of a [`MultipartInput`] at a time,
+//! the [`MultipartCrossoverInsertMutator`] and [`MultipartCrossoverReplaceMutator`] cross over parts with the same name.
+
+use core::{cmp::min, marker::PhantomData, mem};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{
+        rands::{Rand, StdRand},
+        serdeany::SerdeAnyMap,
+        tuples::Named,
+    },
+    corpus::Corpus,
+    inputs::{multipart::MultipartInput, HasBytesVec, HasTargetBytes, Input, UsesInput},
+    mutators::{buffer_copy, buffer_self_copy, MutationResult, Mutator},
+    state::{HasCorpus, HasMaxSize, HasMetadata, HasRand, State},
+    Error,
+};
+
+/// The state the inner mutator of a [`MultipartMutator`] runs on, with a single part as input.
+/// It lends the metadata and the max size of the fuzzer state, and has a rand seeded from it.
+/// It has no corpus, so cross over parts with the [`MultipartCrossoverInsertMutator`]
+/// and the [`MultipartCrossoverReplaceMutator`] instead.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartState<I> {
+    rand: StdRand,
+    metadata: SerdeAnyMap,
+    max_size: usize,
+    phantom: PhantomData<I>,
+}
+
+impl<I> PartState<I> {
+    /// Borrows the metadata of `state`, until [`PartState::give_back`]
+    fn lend<S>(state: &mut S) -> Self
+    where
+        S: HasRand + HasMaxSize + HasMetadata,
+    {
+        Self {
+            rand: StdRand::with_seed(state.rand_mut().next()),
+            metadata: mem::take(state.metadata_mut()),
+            max_size: state.max_size(),
+            phantom: PhantomData,
+        }
+    }
+
+    /// Returns the metadata, as changed by the inner mutator, to `state`
+    fn give_back<S>(self, state: &mut S)
+    where
+        S: HasMetadata,
+    {
+        *state.metadata_mut() = self.metadata;
+    }
+}
+
+impl<I> UsesInput for PartState<I>
+where
+    I: Input,
+{
+    type Input = I;
+}
+
+impl<I> State for PartState<I> where I: Input {}
+
+impl<I> HasRand for PartState<I> {
+    type Rand = StdRand;
+
+    fn rand(&self) -> &StdRand {
+        &self.rand
+    }
+
+    fn rand_mut(&mut self) -> &mut StdRand {
+        &mut self.rand
+    }
+}
+
+impl<I> HasMaxSize for PartState<I> {
+    fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+    }
+}
+
+impl<I> HasMetadata for PartState<I> {
+    fn metadata(&self) -> &SerdeAnyMap {
+        &self.metadata
+    }
+
+    fn metadata_mut(&mut self) -> &mut SerdeAnyMap {
+        &mut self.metadata
+    }
+}
+
+/// A [`Mutator`] that selects a random part of a [`MultipartInput`],
+/// and lets the `inner` mutator change that part, running on a [`PartState`].
+#[derive(Debug)]
+pub struct MultipartMutator<M> {
+    inner: M,
+}
+
+impl<I, M, S> Mutator<S> for MultipartMutator<M>
+where
+    I: Input,
+    M: Mutator<PartState<I>>,
+    S: UsesInput<Input = MultipartInput<I>> + HasRand + HasMaxSize + HasMetadata,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut MultipartInput<I>,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        if input.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+        let part = state.rand_mut().below(input.len() as u64) as usize;
+        let mut part_state = PartState::lend(state);
+        let res = self
+            .inner
+            .mutate(&mut part_state, &mut input.parts_mut()[part], stage_idx);
+        part_state.give_back(state);
+        res
+    }
+
+    #[inline]
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        stage_idx: i32,
+        corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        let mut part_state = PartState::lend(state);
+        let res = self.inner.post_exec(&mut part_state, stage_idx, corpus_idx);
+        part_state.give_back(state);
+        res
+    }
+}
+
+impl<M> Named for MultipartMutator<M> {
+    fn name(&self) -> &str {
+        "MultipartMutator"
+    }
+}
+
+impl<M> MultipartMutator<M> {
+    /// Creates a new [`MultipartMutator`], applying `inner` to a random part of each input
+    #[must_use]
+    pub fn new(inner: M) -> Self {
+        Self { inner }
+    }
+}
+
+/// Picks a random part of `input` and the corpus index of a random donor,
+/// or `None` if the input has no parts, or the donor is the testcase currently fuzzed
+fn random_part_and_donor<S, I>(state: &mut S, input: &MultipartInput<I>) -> Option<(usize, usize)>
+where
+    S: HasCorpus + HasRand,
+{
+    if input.is_empty() {
+        return None;
+    }
+    let part = state.rand_mut().below(input.len() as u64) as usize;
+    let count = state.corpus().count();
+    let idx = state.rand_mut().below(count as u64) as usize;
+    match state.corpus().current() {
+        Some(cur) if *cur == idx => None,
+        _ => Some((part, idx)),
+    }
+}
+
+/// Calls `f` with the bytes of the part named `name` of the donor at `idx`, if it has one
+fn with_donor_part<S, I, F, R>(state: &S, idx: usize, name: &str, f: F) -> Result<Option<R>, Error>
+where
+    S: HasCorpus + UsesInput<Input = MultipartInput<I>>,
+    I: Input + HasBytesVec + HasTargetBytes,
+    F: FnOnce(&[u8]) -> R,
+{
+    let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
+    Ok(other_testcase
+        .load_input()?
+        .part_by_name(name)
+        .map(|part| f(part.bytes())))
+}
+
+/// Crossover insert mutation for the parts of a [`MultipartInput`],
+/// inserting bytes of the part with the same name of a donor into a random part
+#[derive(Debug, Default)]
+pub struct MultipartCrossoverInsertMutator;
+
+impl<I, S> Mutator<S> for MultipartCrossoverInsertMutator
+where
+    I: Input + HasBytesVec + HasTargetBytes,
+    S: UsesInput<Input = MultipartInput<I>> + HasCorpus + HasRand + HasMaxSize,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut MultipartInput<I>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let Some((part, idx)) = random_part_and_donor(state, input) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let name = input.names()[part].clone();
+        let size = input.parts()[part].bytes().len();
+
+        let other_size = match with_donor_part(state, idx, &name, <[u8]>::len)? {
+            Some(other_size) if other_size >= 2 => other_size,
+            _ => return Ok(MutationResult::Skipped),
+        };
+
+        let max_size = state.max_size();
+        let from = state.rand_mut().below(other_size as u64) as usize;
+        let to = state.rand_mut().below(size as u64) as usize;
+        let mut len = 1 + state.rand_mut().below((other_size - from) as u64) as usize;
+
+        if size + len > max_size {
+            if max_size > size {
+                len = max_size - size;
+            } else {
+                return Ok(MutationResult::Skipped);
+            }
+        }
+
+        with_donor_part(state, idx, &name, |other| {
+            let bytes = input.parts_mut()[part].bytes_mut();
+            bytes.resize(size + len, 0);
+            buffer_self_copy(bytes, to, to + len, size - to);
+            buffer_copy(bytes, other, from, to, len);
+        })?;
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for MultipartCrossoverInsertMutator {
+    fn name(&self) -> &str {
+        "MultipartCrossoverInsertMutator"
+    }
+}
+
+impl MultipartCrossoverInsertMutator {
+    /// Creates a new [`MultipartCrossoverInsertMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Crossover replace mutation for the parts of a [`MultipartInput`],
+/// overwriting bytes of a random part with bytes of the part with the same name of a donor
+#[derive(Debug, Default)]
+pub struct MultipartCrossoverReplaceMutator;
+
+impl<I, S> Mutator<S> for MultipartCrossoverReplaceMutator
+where
+    I: Input + HasBytesVec + HasTargetBytes,
+    S: UsesInput<Input = MultipartInput<I>> + HasCorpus + HasRand,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut MultipartInput<I>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let Some((part, idx)) = random_part_and_donor(state, input) else {
+            return Ok(MutationResult::Skipped);
+        };
+        let name = input.names()[part].clone();
+        let size = input.parts()[part].bytes().len();
+        if size == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let other_size = match with_donor_part(state, idx, &name, <[u8]>::len)? {
+            Some(other_size) if other_size >= 2 => other_size,
+            _ => return Ok(MutationResult::Skipped),
+        };
+
+        let from = state.rand_mut().below(other_size as u64) as usize;
+        let len = state.rand_mut().below(min(other_size - from, size) as u64) as usize;
+        let to = state.rand_mut().below((size - len) as u64) as usize;
+
+        with_donor_part(state, idx, &name, |other| {
+            buffer_copy(input.parts_mut()[part].bytes_mut(), other, from, to, len);
+        })?;
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for MultipartCrossoverReplaceMutator {
+    fn name(&self) -> &str {
+        "MultipartCrossoverReplaceMutator"
+    }
+}
+
+impl MultipartCrossoverReplaceMutator {
+    /// Creates a new [`MultipartCrossoverReplaceMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::vec::Vec;
+
+    use crate::{
+        bolts::{rands::StdRand, AsSlice},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::ConstFeedback,
+        inputs::{
+            multipart::{MultipartInput, PartsEncoding},
+            BytesInput, HasBytesVec, HasTargetBytes, UsesInput,
+        },
+        mutators::{
+            havoc_mutations_no_crossover,
+            multipart_mutations::{
+                MultipartCrossoverInsertMutator, MultipartCrossoverReplaceMutator, MultipartMutator,
+            },
+            MutationResult, Mutator, StdScheduledMutator,
+        },
+        state::{HasCorpus, StdState},
+    };
+
+    #[test]
+    fn test_multipart() {
+        let mut input = MultipartInput::new(PartsEncoding::LengthPrefixed);
+        input.add_part("header", BytesInput::new(b"GET".to_vec()));
+        input.add_part("body", BytesInput::new(b"abc".to_vec()));
+        assert_eq!(
+            input.target_bytes().as_slice(),
+            b"\x03\0\0\0GET\x03\0\0\0abc"
+        );
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<MultipartInput<BytesInput>>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut mutator =
+            MultipartMutator::new(StdScheduledMutator::new(havoc_mutations_no_crossover()));
+        let original = input.clone();
+        let mut mutated = [false, false];
+        for _ in 0..64 {
+            let mut mutant = original.clone();
+            if mutator.mutate(&mut state, &mut mutant, 0).unwrap() == MutationResult::Mutated {
+                // Only one part changes
+                let changed: Vec<usize> = (0..2)
+                    .filter(|i| mutant.parts()[*i] != original.parts()[*i])
+                    .collect();
+                assert!(changed.len() <= 1);
+                for i in changed {
+                    mutated[i] = true;
+                }
+            }
+        }
+        assert_eq!(mutated, [true, true]);
+        assert_eq!(original.part_by_name("body").unwrap().bytes(), b"abc");
+    }
+
+    #[test]
+    fn test_multipart_crossover() {
+        let mut input = MultipartInput::new(PartsEncoding::Concat);
+        input.add_part("header", BytesInput::new(b"GET".to_vec()));
+        input.add_part("body", BytesInput::new(b"abc".to_vec()));
+
+        // The donor has the same parts, in another order
+        let mut donor = MultipartInput::new(PartsEncoding::Concat);
+        donor.add_part("body", BytesInput::new(b"123".to_vec()));
+        donor.add_part("header", BytesInput::new(b"XYZ".to_vec()));
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<MultipartInput<BytesInput>>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        state.corpus_mut().add(Testcase::new(donor)).unwrap();
+
+        assert!(crossover_mutates(
+            &mut MultipartCrossoverInsertMutator::new(),
+            &mut state,
+            &input
+        ));
+        assert!(crossover_mutates(
+            &mut MultipartCrossoverReplaceMutator::new(),
+            &mut state,
+            &input
+        ));
+    }
+
+    /// Crosses `input` over with the corpus, checking that the parts only get bytes of the same-named donor part
+    fn crossover_mutates<M, S>(
+        mutator: &mut M,
+        state: &mut S,
+        input: &MultipartInput<BytesInput>,
+    ) -> bool
+    where
+        M: Mutator<S>,
+        S: UsesInput<Input = MultipartInput<BytesInput>>,
+    {
+        let mut mutated = false;
+        for _ in 0..64 {
+            let mut mutant = input.clone();
+            if mutator.mutate(state, &mut mutant, 0).unwrap() == MutationResult::Mutated {
+                mutated |= mutant != *input;
+                assert!(mutant
+                    .part_by_name("header")
+                    .unwrap()
+                    .bytes()
+                    .iter()
+                    .all(|b| b"GETXYZ".contains(b)));
+                assert!(mutant
+                    .part_by_name("body")
+                    .unwrap()
+                    .bytes()
+                    .iter()
+                    .all(|b| b"abc123".contains(b)));
+            }
+        }
+        mutated
+    }
+}
@@ -1,17 +1,21 @@
 //! A wide variety of mutations used during fuzzing.
 
-use alloc::{borrow::ToOwned, vec::Vec};
+use alloc::{borrow::ToOwned, rc::Rc, vec::Vec};
 use core::{
+    any::Any,
     cmp::{max, min},
     mem::size_of,
 };
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
-    bolts::{rands::Rand, tuples::Named},
+    bolts::{rands::Rand, serialization::SerializationFormat, tuples::Named},
     corpus::Corpus,
-    inputs::{HasBytesVec, UsesInput},
+    inputs::{HasBytesSlice, HasBytesVec, Input, UsesInput},
     mutators::{MutationResult, Mutator},
-    state::{HasCorpus, HasMaxSize, HasRand},
+    schedulers::{donors::SpliceDonorsMetadata, size_stats::InputSizeMetadata},
+    state::{HasCorpus, HasMaxSize, HasMetadata, HasRand},
     Error,
 };
 
@@ -96,6 +100,77 @@ pub const INTERESTING_32: [i32; 27] = [
     2147483647,
 ];
 
+/// A state metadata holding the interesting values used by [`ByteInterestingMutator`],
+/// [`WordInterestingMutator`], and [`DwordInterestingMutator`], instead of the built-in
+/// [`INTERESTING_8`], [`INTERESTING_16`], and [`INTERESTING_32`] tables.
+///
+/// Use it to add target-specific values, such as protocol magic numbers, or to replace the tables altogether.
+/// If a table is empty, the corresponding mutator skips.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InterestingValuesMetadata {
+    /// The values for [`ByteInterestingMutator`]
+    pub values_8: Vec<u8>,
+    /// The values for [`WordInterestingMutator`]
+    pub values_16: Vec<u16>,
+    /// The values for [`DwordInterestingMutator`]
+    pub values_32: Vec<u32>,
+}
+
+crate::impl_serdeany!(InterestingValuesMetadata);
+
+impl Default for InterestingValuesMetadata {
+    /// The built-in tables
+    #[allow(clippy::cast_sign_loss)]
+    fn default() -> Self {
+        Self {
+            values_8: INTERESTING_8.iter().map(|v| *v as u8).collect(),
+            values_16: INTERESTING_16.iter().map(|v| *v as u16).collect(),
+            values_32: INTERESTING_32.iter().map(|v| *v as u32).collect(),
+        }
+    }
+}
+
+impl InterestingValuesMetadata {
+    /// Creates a new [`struct@InterestingValuesMetadata`], starting with the built-in tables
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new [`struct@InterestingValuesMetadata`] with empty tables, to replace the built-in ones
+    #[must_use]
+    pub fn empty() -> Self {
+        Self {
+            values_8: vec![],
+            values_16: vec![],
+            values_32: vec![],
+        }
+    }
+
+    /// Adds the given 8-bit values, skipping duplicates
+    pub fn add_8(&mut self, values: &[u8]) {
+        Self::add_unique(&mut self.values_8, values);
+    }
+
+    /// Adds the given 16-bit values, skipping duplicates
+    pub fn add_16(&mut self, values: &[u16]) {
+        Self::add_unique(&mut self.values_16, values);
+    }
+
+    /// Adds the given 32-bit values, skipping duplicates
+    pub fn add_32(&mut self, values: &[u32]) {
+        Self::add_unique(&mut self.values_32, values);
+    }
+
+    fn add_unique<T: PartialEq + Copy>(table: &mut Vec<T>, values: &[T]) {
+        for v in values {
+            if !table.contains(v) {
+                table.push(*v);
+            }
+        }
+    }
+}
+
 /// Bitflip mutation for inputs with a bytes vector
 #[derive(Default, Debug)]
 pub struct BitFlipMutator;
@@ -103,7 +178,7 @@ pub struct BitFlipMutator;
 impl<S> Mutator<S> for BitFlipMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -111,11 +186,11 @@ where
         input: &mut <S as UsesInput>::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        if input.bytes().is_empty() {
+        if input.bytes_slice().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
             let bit = 1 << state.rand_mut().choose(0..8);
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let byte = state.rand_mut().choose(input.bytes_slice_mut());
             *byte ^= bit;
             Ok(MutationResult::Mutated)
         }
@@ -143,7 +218,7 @@ pub struct ByteFlipMutator;
 impl<S> Mutator<S> for ByteFlipMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -151,10 +226,10 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        if input.bytes().is_empty() {
+        if input.bytes_slice().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            *state.rand_mut().choose(input.bytes_mut()) ^= 0xff;
+            *state.rand_mut().choose(input.bytes_slice_mut()) ^= 0xff;
             Ok(MutationResult::Mutated)
         }
     }
@@ -181,7 +256,7 @@ pub struct ByteIncMutator;
 impl<S> Mutator<S> for ByteIncMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -189,10 +264,10 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        if input.bytes().is_empty() {
+        if input.bytes_slice().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let byte = state.rand_mut().choose(input.bytes_slice_mut());
             *byte = byte.wrapping_add(1);
             Ok(MutationResult::Mutated)
         }
@@ -220,7 +295,7 @@ pub struct ByteDecMutator;
 impl<S> Mutator<S> for ByteDecMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -228,10 +303,10 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        if input.bytes().is_empty() {
+        if input.bytes_slice().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let byte = state.rand_mut().choose(input.bytes_slice_mut());
             *byte = byte.wrapping_sub(1);
             Ok(MutationResult::Mutated)
         }
@@ -259,7 +334,7 @@ pub struct ByteNegMutator;
 impl<S> Mutator<S> for ByteNegMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -267,10 +342,10 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        if input.bytes().is_empty() {
+        if input.bytes_slice().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let byte = state.rand_mut().choose(input.bytes_slice_mut());
             *byte = (!(*byte)).wrapping_add(1);
             Ok(MutationResult::Mutated)
         }
@@ -298,7 +373,7 @@ pub struct ByteRandMutator;
 impl<S> Mutator<S> for ByteRandMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -306,10 +381,10 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        if input.bytes().is_empty() {
+        if input.bytes_slice().is_empty() {
             Ok(MutationResult::Skipped)
         } else {
-            let byte = state.rand_mut().choose(input.bytes_mut());
+            let byte = state.rand_mut().choose(input.bytes_slice_mut());
             *byte = state.rand_mut().next() as u8;
             Ok(MutationResult::Mutated)
         }
@@ -342,7 +417,7 @@ macro_rules! add_mutator_impl {
         impl<S> Mutator<S> for $name
         where
             S: UsesInput + HasRand,
-            S::Input: HasBytesVec,
+            S::Input: HasBytesSlice,
         {
             fn mutate(
                 &mut self,
@@ -350,13 +425,13 @@ macro_rules! add_mutator_impl {
                 input: &mut S::Input,
                 _stage_idx: i32,
             ) -> Result<MutationResult, Error> {
-                if input.bytes().len() < size_of::<$size>() {
+                if input.bytes_slice().len() < size_of::<$size>() {
                     Ok(MutationResult::Skipped)
                 } else {
                     // choose a random window of bytes (windows overlap) and convert to $size
                     let (index, bytes) = state
                         .rand_mut()
-                        .choose(input.bytes().windows(size_of::<$size>()).enumerate());
+                        .choose(input.bytes_slice().windows(size_of::<$size>()).enumerate());
                     let val = <$size>::from_ne_bytes(bytes.try_into().unwrap());
 
                     // mutate
@@ -369,7 +444,7 @@ macro_rules! add_mutator_impl {
                     };
 
                     // set bytes to mutated value
-                    let new_bytes = &mut input.bytes_mut()[index..index + size_of::<$size>()];
+                    let new_bytes = &mut input.bytes_slice_mut()[index..index + size_of::<$size>()];
                     new_bytes.copy_from_slice(&new_val.to_ne_bytes());
                     Ok(MutationResult::Mutated)
                 }
@@ -400,15 +475,16 @@ add_mutator_impl!(QwordAddMutator, u64);
 ///////////////////////////
 
 macro_rules! interesting_mutator_impl {
-    ($name: ident, $size: ty, $interesting: ident) => {
-        /// Inserts an interesting value at a random place in the input vector
+    ($name: ident, $size: ty, $interesting: ident, $values: ident) => {
+        /// Inserts an interesting value at a random place in the input vector.
+        /// The values come from the [`struct@InterestingValuesMetadata`], if present, else from the built-in table.
         #[derive(Default, Debug)]
         pub struct $name;
 
         impl<S> Mutator<S> for $name
         where
-            S: UsesInput + HasRand,
-            S::Input: HasBytesVec,
+            S: UsesInput + HasRand + HasMetadata,
+            S::Input: HasBytesSlice,
         {
             #[allow(clippy::cast_sign_loss)]
             fn mutate(
@@ -417,13 +493,28 @@ macro_rules! interesting_mutator_impl {
                 input: &mut S::Input,
                 _stage_idx: i32,
             ) -> Result<MutationResult, Error> {
-                if input.bytes().len() < size_of::<$size>() {
+                if input.bytes_slice().len() < size_of::<$size>() {
                     Ok(MutationResult::Skipped)
                 } else {
-                    let bytes = input.bytes_mut();
+                    let bytes = input.bytes_slice_mut();
                     let upper_bound = (bytes.len() + 1 - size_of::<$size>()) as u64;
                     let idx = state.rand_mut().below(upper_bound) as usize;
-                    let val = *state.rand_mut().choose(&$interesting) as $size;
+                    let custom_len = state
+                        .metadata()
+                        .get::<InterestingValuesMetadata>()
+                        .map(|meta| meta.$values.len());
+                    let val = match custom_len {
+                        None => *state.rand_mut().choose(&$interesting) as $size,
+                        Some(0) => return Ok(MutationResult::Skipped),
+                        Some(len) => {
+                            let i = state.rand_mut().below(len as u64) as usize;
+                            state
+                                .metadata()
+                                .get::<InterestingValuesMetadata>()
+                                .unwrap()
+                                .$values[i]
+                        }
+                    };
                     let new_bytes = match state.rand_mut().choose(&[0, 1]) {
                         0 => val.to_be_bytes(),
                         _ => val.to_le_bytes(),
@@ -450,9 +541,58 @@ macro_rules! interesting_mutator_impl {
     };
 }
 
-interesting_mutator_impl!(ByteInterestingMutator, u8, INTERESTING_8);
-interesting_mutator_impl!(WordInterestingMutator, u16, INTERESTING_16);
-interesting_mutator_impl!(DwordInterestingMutator, u32, INTERESTING_32);
+interesting_mutator_impl!(ByteInterestingMutator, u8, INTERESTING_8, values_8);
+interesting_mutator_impl!(WordInterestingMutator, u16, INTERESTING_16, values_16);
+interesting_mutator_impl!(DwordInterestingMutator, u32, INTERESTING_32, values_32);
+
+/// The default growth limit of the [`struct@LengthBiasMetadata`], as multiple of the median corpus input length
+pub const DEFAULT_LENGTH_BIAS_FACTOR: usize = 2;
+
+/// A state metadata that, if present, biases the length of inputs toward the median corpus input length.
+/// The expand and insert mutators grow inputs at most to `factor` times the median,
+/// and the [`BytesDeleteMutator`] shrinks larger inputs right back to this limit.
+/// The median is taken from the [`crate::schedulers::size_stats::InputSizeMetadata`],
+/// so the [`crate::schedulers::SizeStatsScheduler`] needs to be in use.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct LengthBiasMetadata {
+    /// Inputs grow at most to `factor` times the median corpus input length
+    pub factor: usize,
+}
+
+crate::impl_serdeany!(LengthBiasMetadata);
+
+impl LengthBiasMetadata {
+    /// Creates a new [`struct@LengthBiasMetadata`], letting inputs grow to `factor` times the median corpus input length
+    #[must_use]
+    pub fn new(factor: usize) -> Self {
+        Self { factor }
+    }
+}
+
+impl Default for LengthBiasMetadata {
+    fn default() -> Self {
+        Self::new(DEFAULT_LENGTH_BIAS_FACTOR)
+    }
+}
+
+/// The length limit of the [`struct@LengthBiasMetadata`], if present and the median corpus input length is known
+fn length_bias_limit<S>(state: &S) -> Option<usize>
+where
+    S: HasMetadata,
+{
+    let factor = state.metadata().get::<LengthBiasMetadata>()?.factor;
+    let median = state.metadata().get::<InputSizeMetadata>()?.median()?;
+    Some(max(1, median.saturating_mul(factor)))
+}
+
+/// The size inputs may grow to, taking the [`struct@LengthBiasMetadata`] into account
+fn biased_max_size<S>(state: &S) -> usize
+where
+    S: HasMetadata + HasMaxSize,
+{
+    let max_size = state.max_size();
+    length_bias_limit(state).map_or(max_size, |limit| min(max_size, limit))
+}
 
 /// Bytes delete mutation for inputs with a bytes vector
 #[derive(Default, Debug)]
@@ -460,7 +600,7 @@ pub struct BytesDeleteMutator;
 
 impl<S> Mutator<S> for BytesDeleteMutator
 where
-    S: UsesInput + HasRand,
+    S: UsesInput + HasRand + HasMetadata,
     S::Input: HasBytesVec,
 {
     fn mutate(
@@ -474,6 +614,15 @@ where
             return Ok(MutationResult::Skipped);
         }
 
+        // Shrink oversized inputs right back to the length bias limit
+        if let Some(limit) = length_bias_limit(state) {
+            if size > limit {
+                let off = state.rand_mut().below((limit + 1) as u64) as usize;
+                input.bytes_mut().drain(off..off + (size - limit));
+                return Ok(MutationResult::Mutated);
+            }
+        }
+
         let off = state.rand_mut().below(size as u64) as usize;
         let len = state.rand_mut().below((size - off) as u64) as usize;
         input.bytes_mut().drain(off..off + len);
@@ -502,7 +651,7 @@ pub struct BytesExpandMutator;
 
 impl<S> Mutator<S> for BytesExpandMutator
 where
-    S: UsesInput + HasRand + HasMaxSize,
+    S: UsesInput + HasRand + HasMaxSize + HasMetadata,
     S::Input: HasBytesVec,
 {
     fn mutate(
@@ -511,7 +660,7 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let max_size = state.max_size();
+        let max_size = biased_max_size(state);
         let size = input.bytes().len();
         let off = state.rand_mut().below((size + 1) as u64) as usize;
         let mut len = 1 + state.rand_mut().below(16) as usize;
@@ -551,7 +700,7 @@ pub struct BytesInsertMutator;
 
 impl<S> Mutator<S> for BytesInsertMutator
 where
-    S: UsesInput + HasRand + HasMaxSize,
+    S: UsesInput + HasRand + HasMaxSize + HasMetadata,
     S::Input: HasBytesVec,
 {
     fn mutate(
@@ -560,7 +709,7 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let max_size = state.max_size();
+        let max_size = biased_max_size(state);
         let size = input.bytes().len();
         if size == 0 {
             return Ok(MutationResult::Skipped);
@@ -606,7 +755,7 @@ pub struct BytesRandInsertMutator;
 
 impl<S> Mutator<S> for BytesRandInsertMutator
 where
-    S: UsesInput + HasRand + HasMaxSize,
+    S: UsesInput + HasRand + HasMaxSize + HasMetadata,
     S::Input: HasBytesVec,
 {
     fn mutate(
@@ -615,7 +764,7 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let max_size = state.max_size();
+        let max_size = biased_max_size(state);
         let size = input.bytes().len();
         let off = state.rand_mut().below((size + 1) as u64) as usize;
         let mut len = 1 + state.rand_mut().below(16) as usize;
@@ -659,7 +808,7 @@ pub struct BytesSetMutator;
 impl<S> Mutator<S> for BytesSetMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -667,16 +816,16 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let size = input.bytes().len();
+        let size = input.bytes_slice().len();
         if size == 0 {
             return Ok(MutationResult::Skipped);
         }
         let off = state.rand_mut().below(size as u64) as usize;
         let len = 1 + state.rand_mut().below(min(16, size - off) as u64) as usize;
 
-        let val = *state.rand_mut().choose(input.bytes());
+        let val = *state.rand_mut().choose(input.bytes_slice());
 
-        buffer_set(input.bytes_mut(), off, len, val);
+        buffer_set(input.bytes_slice_mut(), off, len, val);
 
         Ok(MutationResult::Mutated)
     }
@@ -703,7 +852,7 @@ pub struct BytesRandSetMutator;
 impl<S> Mutator<S> for BytesRandSetMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -711,7 +860,7 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let size = input.bytes().len();
+        let size = input.bytes_slice().len();
         if size == 0 {
             return Ok(MutationResult::Skipped);
         }
@@ -720,7 +869,7 @@ where
 
         let val = state.rand_mut().next() as u8;
 
-        buffer_set(input.bytes_mut(), off, len, val);
+        buffer_set(input.bytes_slice_mut(), off, len, val);
 
         Ok(MutationResult::Mutated)
     }
@@ -747,7 +896,7 @@ pub struct BytesCopyMutator;
 impl<S> Mutator<S> for BytesCopyMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -755,16 +904,16 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let size = input.bytes().len();
+        let size = input.bytes_slice().len();
         if size <= 1 {
             return Ok(MutationResult::Skipped);
         }
 
-        let from = state.rand_mut().below(input.bytes().len() as u64) as usize;
-        let to = state.rand_mut().below(input.bytes().len() as u64) as usize;
+        let from = state.rand_mut().below(input.bytes_slice().len() as u64) as usize;
+        let to = state.rand_mut().below(input.bytes_slice().len() as u64) as usize;
         let len = 1 + state.rand_mut().below((size - max(from, to)) as u64) as usize;
 
-        buffer_self_copy(input.bytes_mut(), from, to, len);
+        buffer_self_copy(input.bytes_slice_mut(), from, to, len);
 
         Ok(MutationResult::Mutated)
     }
@@ -855,7 +1004,7 @@ pub struct BytesSwapMutator;
 impl<S> Mutator<S> for BytesSwapMutator
 where
     S: UsesInput + HasRand,
-    S::Input: HasBytesVec,
+    S::Input: HasBytesSlice,
 {
     fn mutate(
         &mut self,
@@ -863,18 +1012,18 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        let size = input.bytes().len();
+        let size = input.bytes_slice().len();
         if size <= 1 {
             return Ok(MutationResult::Skipped);
         }
 
-        let first = state.rand_mut().below(input.bytes().len() as u64) as usize;
-        let second = state.rand_mut().below(input.bytes().len() as u64) as usize;
+        let first = state.rand_mut().below(input.bytes_slice().len() as u64) as usize;
+        let second = state.rand_mut().below(input.bytes_slice().len() as u64) as usize;
         let len = 1 + state.rand_mut().below((size - max(first, second)) as u64) as usize;
 
-        let tmp = input.bytes()[first..(first + len)].to_vec();
-        buffer_self_copy(input.bytes_mut(), second, first, len);
-        buffer_copy(input.bytes_mut(), &tmp, 0, second, len);
+        let tmp = input.bytes_slice()[first..(first + len)].to_vec();
+        buffer_self_copy(input.bytes_slice_mut(), second, first, len);
+        buffer_copy(input.bytes_slice_mut(), &tmp, 0, second, len);
 
         Ok(MutationResult::Mutated)
     }
@@ -894,13 +1043,69 @@ impl BytesSwapMutator {
     }
 }
 
+/// Picks the corpus index of a random donor for crossovers, or `None` if it's the testcase currently fuzzed.
+/// With a [`struct@SpliceDonorsMetadata`], the donor is one of the resident copies, preferring donors
+/// with a length similar to `near_len`, else it may be any corpus entry.
+fn random_donor<S>(state: &mut S, near_len: Option<usize>) -> Option<usize>
+where
+    S: HasCorpus + HasRand + HasMetadata,
+{
+    let (donors, similar) = state
+        .metadata()
+        .get::<SpliceDonorsMetadata>()
+        .map_or((0, 0), |meta| {
+            (
+                meta.len(),
+                near_len.map_or(0, |len| meta.bucket_for_len(len).len()),
+            )
+        });
+
+    let idx = if similar > 0 {
+        let n = state.rand_mut().below(similar as u64) as usize;
+        let meta = state.metadata().get::<SpliceDonorsMetadata>().unwrap();
+        meta.bucket_for_len(near_len.unwrap())[n]
+    } else if donors > 0 {
+        let n = state.rand_mut().below(donors as u64) as usize;
+        let meta = state.metadata().get::<SpliceDonorsMetadata>().unwrap();
+        meta.nth(n).unwrap()
+    } else {
+        let count = state.corpus().count();
+        state.rand_mut().below(count as u64) as usize
+    };
+
+    // We don't want to use the testcase we're already using for splicing
+    match state.corpus().current() {
+        Some(cur) if *cur == idx => None,
+        _ => Some(idx),
+    }
+}
+
+/// Calls `f` with the bytes of the donor at `idx`,
+/// from its resident copy in the [`struct@SpliceDonorsMetadata`], if any, else from the corpus.
+fn with_donor<S, F, R>(state: &S, idx: usize, f: F) -> Result<R, Error>
+where
+    S: HasCorpus + HasMetadata,
+    S::Input: HasBytesVec,
+    F: FnOnce(&[u8]) -> R,
+{
+    if let Some(bytes) = state
+        .metadata()
+        .get::<SpliceDonorsMetadata>()
+        .and_then(|meta| meta.get(idx))
+    {
+        return Ok(f(bytes));
+    }
+    let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
+    Ok(f(other_testcase.load_input()?.bytes()))
+}
+
 /// Crossover insert mutation for inputs with a bytes vector
 #[derive(Debug, Default)]
 pub struct CrossoverInsertMutator;
 
 impl<S> Mutator<S> for CrossoverInsertMutator
 where
-    S: HasCorpus + HasRand + HasMaxSize,
+    S: HasCorpus + HasRand + HasMaxSize + HasMetadata,
     S::Input: HasBytesVec,
 {
     fn mutate(
@@ -911,22 +1116,12 @@ where
     ) -> Result<MutationResult, Error> {
         let size = input.bytes().len();
 
-        // We don't want to use the testcase we're already using for splicing
-        let count = state.corpus().count();
-        let idx = state.rand_mut().below(count as u64) as usize;
-        if let Some(cur) = state.corpus().current() {
-            if idx == *cur {
-                return Ok(MutationResult::Skipped);
-            }
-        }
+        let idx = match random_donor(state, None) {
+            Some(idx) => idx,
+            None => return Ok(MutationResult::Skipped),
+        };
 
-        let other_size = state
-            .corpus()
-            .get(idx)?
-            .borrow_mut()
-            .load_input()?
-            .bytes()
-            .len();
+        let other_size = with_donor(state, idx, <[u8]>::len)?;
         if other_size < 2 {
             return Ok(MutationResult::Skipped);
         }
@@ -936,9 +1131,6 @@ where
         let to = state.rand_mut().below(size as u64) as usize;
         let mut len = 1 + state.rand_mut().below((other_size - from) as u64) as usize;
 
-        let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
-        let other = other_testcase.load_input()?;
-
         if size + len > max_size {
             if max_size > size {
                 len = max_size - size;
@@ -947,9 +1139,11 @@ where
             }
         }
 
-        input.bytes_mut().resize(size + len, 0);
-        buffer_self_copy(input.bytes_mut(), to, to + len, size - to);
-        buffer_copy(input.bytes_mut(), other.bytes(), from, to, len);
+        with_donor(state, idx, |other| {
+            input.bytes_mut().resize(size + len, 0);
+            buffer_self_copy(input.bytes_mut(), to, to + len, size - to);
+            buffer_copy(input.bytes_mut(), other, from, to, len);
+        })?;
 
         Ok(MutationResult::Mutated)
     }
@@ -975,7 +1169,7 @@ pub struct CrossoverReplaceMutator;
 
 impl<S> Mutator<S> for CrossoverReplaceMutator
 where
-    S: HasCorpus + HasRand,
+    S: HasCorpus + HasRand + HasMetadata,
     S::Input: HasBytesVec,
 {
     fn mutate(
@@ -989,22 +1183,12 @@ where
             return Ok(MutationResult::Skipped);
         }
 
-        // We don't want to use the testcase we're already using for splicing
-        let count = state.corpus().count();
-        let idx = state.rand_mut().below(count as u64) as usize;
-        if let Some(cur) = state.corpus().current() {
-            if idx == *cur {
-                return Ok(MutationResult::Skipped);
-            }
-        }
+        let idx = match random_donor(state, None) {
+            Some(idx) => idx,
+            None => return Ok(MutationResult::Skipped),
+        };
 
-        let other_size = state
-            .corpus()
-            .get(idx)?
-            .borrow_mut()
-            .load_input()?
-            .bytes()
-            .len();
+        let other_size = with_donor(state, idx, <[u8]>::len)?;
         if other_size < 2 {
             return Ok(MutationResult::Skipped);
         }
@@ -1013,10 +1197,9 @@ where
         let len = state.rand_mut().below(min(other_size - from, size) as u64) as usize;
         let to = state.rand_mut().below((size - len) as u64) as usize;
 
-        let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
-        let other = other_testcase.load_input()?;
-
-        buffer_copy(input.bytes_mut(), other.bytes(), from, to, len);
+        with_donor(state, idx, |other| {
+            buffer_copy(input.bytes_mut(), other, from, to, len);
+        })?;
 
         Ok(MutationResult::Mutated)
     }
@@ -1058,7 +1241,7 @@ pub struct SpliceMutator;
 
 impl<S> Mutator<S> for SpliceMutator
 where
-    S: HasCorpus + HasRand,
+    S: HasCorpus + HasRand + HasMetadata,
     S::Input: HasBytesVec,
 {
     #[allow(clippy::cast_sign_loss)]
@@ -1068,40 +1251,32 @@ where
         input: &mut S::Input,
         _stage_idx: i32,
     ) -> Result<MutationResult, Error> {
-        // We don't want to use the testcase we're already using for splicing
-        let count = state.corpus().count();
-        let idx = state.rand_mut().below(count as u64) as usize;
-        if let Some(cur) = state.corpus().current() {
-            if idx == *cur {
-                return Ok(MutationResult::Skipped);
-            }
-        }
-
-        let (first_diff, last_diff) = {
-            let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
-            let other = other_testcase.load_input()?;
-
-            let mut counter: u32 = 0;
-            loop {
-                let (f, l) = locate_diffs(input.bytes(), other.bytes());
+        // Donors of a similar length are more likely to share a prefix
+        let idx = match random_donor(state, Some(input.bytes().len())) {
+            Some(idx) => idx,
+            None => return Ok(MutationResult::Skipped),
+        };
 
-                if f != l && f >= 0 && l >= 2 {
-                    break (f as u64, l as u64);
-                }
-                if counter == 3 {
-                    return Ok(MutationResult::Skipped);
-                }
-                counter += 1;
+        let diffs = with_donor(state, idx, |other| {
+            let (f, l) = locate_diffs(input.bytes(), other);
+            if f != l && f >= 0 && l >= 2 {
+                Some((f as u64, l as u64))
+            } else {
+                None
             }
+        })?;
+        let (first_diff, last_diff) = match diffs {
+            Some(diffs) => diffs,
+            None => return Ok(MutationResult::Skipped),
         };
 
         let split_at = state.rand_mut().between(first_diff, last_diff) as usize;
 
-        let mut other_testcase = state.corpus().get(idx)?.borrow_mut();
-        let other = other_testcase.load_input()?;
-        input
-            .bytes_mut()
-            .splice(split_at.., other.bytes()[split_at..].iter().copied());
+        with_donor(state, idx, |other| {
+            input
+                .bytes_mut()
+                .splice(split_at.., other[split_at..].iter().copied());
+        })?;
 
         Ok(MutationResult::Mutated)
     }
@@ -1121,6 +1296,168 @@ impl SpliceMutator {
     }
 }
 
+/// The default number of inputs from other clients kept in the [`struct@CrossClientInputsMetadata`]
+pub const DEFAULT_CROSS_CLIENT_INPUTS: usize = 64;
+
+/// A state metadata holding the most recent inputs received from other clients.
+/// If it is in the state, the [`crate::events::LlmpEventManager`] fills it with each received testcase,
+/// serialized in the format of the manager,
+/// and the [`CrossClientSpliceMutator`] splices from it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrossClientInputsMetadata {
+    /// The serialized inputs, each with the format it is serialized in
+    inputs: Vec<(SerializationFormat, Vec<u8>)>,
+    /// The inputs deserialized so far, at the same positions as `inputs`
+    #[serde(skip)]
+    deserialized: Vec<Option<Rc<dyn Any>>>,
+    /// The position of the oldest input, overwritten next once the metadata is full
+    next: usize,
+    capacity: usize,
+}
+
+crate::impl_serdeany!(CrossClientInputsMetadata);
+
+impl Default for CrossClientInputsMetadata {
+    fn default() -> Self {
+        Self::new(DEFAULT_CROSS_CLIENT_INPUTS)
+    }
+}
+
+impl CrossClientInputsMetadata {
+    /// Creates a new [`struct@CrossClientInputsMetadata`], keeping the last `capacity` inputs from other clients
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inputs: Vec::with_capacity(capacity),
+            deserialized: Vec::with_capacity(capacity),
+            next: 0,
+            capacity,
+        }
+    }
+
+    /// The number of inputs kept
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.inputs.len()
+    }
+
+    /// Returns `true` if no input from other clients arrived yet
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.inputs.is_empty()
+    }
+
+    /// Adds an input received from another client, serialized in `format`, replacing the oldest one if full
+    pub fn push<I>(&mut self, format: SerializationFormat, input: &I) -> Result<(), Error>
+    where
+        I: Input,
+    {
+        if self.capacity == 0 {
+            return Ok(());
+        }
+        let serialized = (format, format.serialize(input)?);
+        // Nothing is deserialized yet after a restart
+        self.deserialized.resize(self.inputs.len(), None);
+        if self.inputs.len() < self.capacity {
+            self.inputs.push(serialized);
+            self.deserialized.push(None);
+        } else {
+            self.inputs[self.next] = serialized;
+            self.deserialized[self.next] = None;
+            self.next = (self.next + 1) % self.capacity;
+        }
+        Ok(())
+    }
+
+    /// Gets the `n`-th input kept.
+    /// Each input is deserialized at most once, later calls return the cached input.
+    pub fn get<I>(&mut self, n: usize) -> Result<&I, Error>
+    where
+        I: Input + 'static,
+    {
+        let (format, serialized) = self
+            .inputs
+            .get(n)
+            .ok_or_else(|| Error::key_not_found(format!("No cross-client input at {n}")))?;
+        self.deserialized.resize(self.inputs.len(), None);
+        let cached = &mut self.deserialized[n];
+        if !cached.as_ref().map_or(false, |input| input.is::<I>()) {
+            *cached = Some(Rc::new(format.deserialize::<I>(serialized)?));
+        }
+        Ok(cached
+            .as_ref()
+            .and_then(|input| input.downcast_ref::<I>())
+            .unwrap())
+    }
+}
+
+/// Splice mutation with the inputs received from other clients, for inputs with a bytes vector.
+/// It draws its donors from the [`struct@CrossClientInputsMetadata`], and skips if there is none.
+#[derive(Debug, Default)]
+pub struct CrossClientSpliceMutator;
+
+impl<S> Mutator<S> for CrossClientSpliceMutator
+where
+    S: UsesInput + HasRand + HasMetadata,
+    S::Input: HasBytesVec + 'static,
+{
+    #[allow(clippy::cast_sign_loss)]
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let count = state
+            .metadata()
+            .get::<CrossClientInputsMetadata>()
+            .map_or(0, CrossClientInputsMetadata::len);
+        if count == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let n = state.rand_mut().below(count as u64) as usize;
+        let other: &S::Input = state
+            .metadata_mut()
+            .get_mut::<CrossClientInputsMetadata>()
+            .unwrap()
+            .get(n)?;
+
+        let (first_diff, last_diff) = locate_diffs(input.bytes(), other.bytes());
+        if first_diff == last_diff || first_diff < 0 || last_diff < 2 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let split_at = state
+            .rand_mut()
+            .between(first_diff as u64, last_diff as u64) as usize;
+        // The donor is cached by now
+        let other: &S::Input = state
+            .metadata_mut()
+            .get_mut::<CrossClientInputsMetadata>()
+            .unwrap()
+            .get(n)?;
+        input
+            .bytes_mut()
+            .splice(split_at.., other.bytes()[split_at..].iter().copied());
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for CrossClientSpliceMutator {
+    fn name(&self) -> &str {
+        "CrossClientSpliceMutator"
+    }
+}
+
+impl CrossClientSpliceMutator {
+    /// Creates a new [`CrossClientSpliceMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
 // Converts a hex u8 to its u8 value: 'A' -> 10 etc.
 fn from_hex(hex: u8) -> Result<u8, Error> {
     match hex {
@@ -1172,10 +1509,11 @@ mod tests {
             rands::StdRand,
             tuples::{tuple_list, HasConstLen},
         },
-        corpus::{Corpus, InMemoryCorpus},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
         feedbacks::ConstFeedback,
         inputs::BytesInput,
         mutators::MutatorsTuple,
+        schedulers::{QueueScheduler, Scheduler, SizeStatsScheduler},
         state::{HasMetadata, StdState},
     };
 
@@ -1261,4 +1599,115 @@ mod tests {
             inputs.append(&mut new_testcases);
         }
     }
+
+    #[test]
+    fn test_interesting_values_metadata() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut meta = InterestingValuesMetadata::empty();
+        meta.add_32(&[0x1234_5678, 0x1234_5678]);
+        assert_eq!(meta.values_32.len(), 1);
+        state.add_metadata(meta);
+
+        let mut input = BytesInput::new(vec![0; 4]);
+        let result = DwordInterestingMutator::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(result, MutationResult::Mutated);
+        assert!(
+            input.bytes() == 0x1234_5678_u32.to_be_bytes()
+                || input.bytes() == 0x1234_5678_u32.to_le_bytes()
+        );
+
+        // An empty table disables the mutator
+        let result = ByteInterestingMutator::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(result, MutationResult::Skipped);
+    }
+
+    #[test]
+    fn test_length_bias_metadata() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let scheduler = SizeStatsScheduler::new(QueueScheduler::new());
+        for len in [2, 4, 6] {
+            let idx = state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0; len])))
+                .unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+        state.add_metadata(LengthBiasMetadata::new(2));
+
+        // Oversized inputs get shrunk to twice the median
+        let mut input = BytesInput::new(vec![0; 100]);
+        let result = BytesDeleteMutator::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(result, MutationResult::Mutated);
+        assert_eq!(input.bytes().len(), 8);
+
+        // ... and do not grow any further
+        let result = BytesExpandMutator::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(result, MutationResult::Skipped);
+    }
+
+    #[test]
+    fn test_cross_client_splice() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut mutator = CrossClientSpliceMutator::new();
+        let mut input = BytesInput::new(b"aaaaaaaa".to_vec());
+        let result = mutator.mutate(&mut state, &mut input, 0).unwrap();
+        assert_eq!(result, MutationResult::Skipped);
+
+        // Only the last two inputs of other clients are kept
+        let mut meta = CrossClientInputsMetadata::new(2);
+        for donor in [&b"zzzzzzzz"[..], b"abcdefgh", b"abcdefgh"] {
+            meta.push(SerializationFormat::Json, &BytesInput::new(donor.to_vec()))
+                .unwrap();
+        }
+        assert_eq!(meta.len(), 2);
+
+        // After a restart, the inputs are deserialized again, in the format they were pushed in
+        let mut meta: CrossClientInputsMetadata =
+            postcard::from_bytes(&postcard::to_allocvec(&meta).unwrap()).unwrap();
+        assert_eq!(meta.get::<BytesInput>(1).unwrap().bytes(), &b"abcdefgh"[..]);
+        state.add_metadata(meta);
+
+        let result = mutator.mutate(&mut state, &mut input, 0).unwrap();
+        assert_eq!(result, MutationResult::Mutated);
+        let split_at = input.bytes().iter().position(|b| *b != b'a').unwrap();
+        assert_eq!(&input.bytes()[split_at..], &b"abcdefgh"[split_at..]);
+    }
 }
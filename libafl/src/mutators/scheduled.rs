@@ -1,6 +1,9 @@
 //! The `ScheduledMutator` schedules multiple mutations internally.
 
-use alloc::{string::String, vec::Vec};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
 use core::{
     fmt::{self, Debug},
     marker::PhantomData,
@@ -12,13 +15,13 @@ pub use crate::mutators::{mutations::*, token_mutations::*};
 use crate::{
     bolts::{
         rands::Rand,
-        tuples::{tuple_list, tuple_list_type, NamedTuple},
+        tuples::{tuple_list, tuple_list_type, Named, NamedTuple},
         AsMutSlice, AsSlice,
     },
     corpus::Corpus,
     inputs::UsesInput,
     mutators::{MutationResult, Mutator, MutatorsTuple},
-    state::{HasCorpus, HasMetadata, HasRand, State},
+    state::{HasCorpus, HasMetadata, HasNamedMetadata, HasRand, HasSolutions, State},
     Error,
 };
 
@@ -98,6 +101,50 @@ where
         }
         Ok(r)
     }
+
+    /// Mutates the `seed` `samples` times, just like [`ScheduledMutator::scheduled_mutate`], without executing anything.
+    /// Returns each mutated sample, together with the names of the mutations that changed it, in order.
+    /// Use it to develop and review mutators, without running a full campaign.
+    fn preview(
+        &mut self,
+        state: &mut S,
+        seed: &S::Input,
+        samples: usize,
+    ) -> Result<Vec<MutationSample<S::Input>>, Error>
+    where
+        MT: NamedTuple,
+    {
+        let mut res = Vec::with_capacity(samples);
+        for _ in 0..samples {
+            let mut input = seed.clone();
+            let mut result = MutationResult::Skipped;
+            let mut log = vec![];
+            let num = self.iterations(state, &input);
+            for _ in 0..num {
+                let idx = self.schedule(state, &input);
+                let outcome = self
+                    .mutations_mut()
+                    .get_and_mutate(idx, state, &mut input, 0)?;
+                if outcome == MutationResult::Mutated {
+                    result = MutationResult::Mutated;
+                    log.push(String::from(self.mutations().name(idx).unwrap_or("?")));
+                }
+            }
+            res.push(MutationSample { input, result, log });
+        }
+        Ok(res)
+    }
+}
+
+/// A sample generated by [`ScheduledMutator::preview`]
+#[derive(Debug, Clone)]
+pub struct MutationSample<I> {
+    /// The mutated input
+    pub input: I,
+    /// If any of the mutations changed the input
+    pub result: MutationResult,
+    /// The names of the mutations that changed the input, in order
+    pub log: Vec<String>,
 }
 
 /// A [`Mutator`] that schedules one of the embedded mutations on each call.
@@ -266,6 +313,115 @@ pub fn havoc_mutations() -> HavocMutationsType {
     )
 }
 
+/// Tuple type of the mutations of the Havoc mutator without crossovers, see [`havoc_mutations_no_crossover`]
+pub type HavocMutationsNoCrossoverType = tuple_list_type!(
+    BitFlipMutator,
+    ByteFlipMutator,
+    ByteIncMutator,
+    ByteDecMutator,
+    ByteNegMutator,
+    ByteRandMutator,
+    ByteAddMutator,
+    WordAddMutator,
+    DwordAddMutator,
+    QwordAddMutator,
+    ByteInterestingMutator,
+    WordInterestingMutator,
+    DwordInterestingMutator,
+    BytesDeleteMutator,
+    BytesDeleteMutator,
+    BytesDeleteMutator,
+    BytesDeleteMutator,
+    BytesExpandMutator,
+    BytesInsertMutator,
+    BytesRandInsertMutator,
+    BytesSetMutator,
+    BytesRandSetMutator,
+    BytesCopyMutator,
+    BytesInsertCopyMutator,
+    BytesSwapMutator,
+);
+
+/// Get the mutations of the Havoc mutator without crossovers.
+/// They only need the input, such as the parts mutated by a [`crate::mutators::MultipartMutator`].
+#[must_use]
+pub fn havoc_mutations_no_crossover() -> HavocMutationsNoCrossoverType {
+    tuple_list!(
+        BitFlipMutator::new(),
+        ByteFlipMutator::new(),
+        ByteIncMutator::new(),
+        ByteDecMutator::new(),
+        ByteNegMutator::new(),
+        ByteRandMutator::new(),
+        ByteAddMutator::new(),
+        WordAddMutator::new(),
+        DwordAddMutator::new(),
+        QwordAddMutator::new(),
+        ByteInterestingMutator::new(),
+        WordInterestingMutator::new(),
+        DwordInterestingMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesDeleteMutator::new(),
+        BytesExpandMutator::new(),
+        BytesInsertMutator::new(),
+        BytesRandInsertMutator::new(),
+        BytesSetMutator::new(),
+        BytesRandSetMutator::new(),
+        BytesCopyMutator::new(),
+        BytesInsertCopyMutator::new(),
+        BytesSwapMutator::new(),
+    )
+}
+
+/// Tuple type of the mutations that never change the size of the input, see [`in_place_mutations`]
+pub type InPlaceMutationsType = tuple_list_type!(
+    BitFlipMutator,
+    ByteFlipMutator,
+    ByteIncMutator,
+    ByteDecMutator,
+    ByteNegMutator,
+    ByteRandMutator,
+    ByteAddMutator,
+    WordAddMutator,
+    DwordAddMutator,
+    QwordAddMutator,
+    ByteInterestingMutator,
+    WordInterestingMutator,
+    DwordInterestingMutator,
+    BytesSetMutator,
+    BytesRandSetMutator,
+    BytesCopyMutator,
+    BytesSwapMutator,
+);
+
+/// Get the mutations of the Havoc mutator that never change the size of the input.
+/// They work on every [`crate::inputs::HasBytesSlice`], such as the memory-mapped `MappedBytesInput`,
+/// and only touch a few bytes of large inputs.
+#[must_use]
+pub fn in_place_mutations() -> InPlaceMutationsType {
+    tuple_list!(
+        BitFlipMutator::new(),
+        ByteFlipMutator::new(),
+        ByteIncMutator::new(),
+        ByteDecMutator::new(),
+        ByteNegMutator::new(),
+        ByteRandMutator::new(),
+        ByteAddMutator::new(),
+        WordAddMutator::new(),
+        DwordAddMutator::new(),
+        QwordAddMutator::new(),
+        ByteInterestingMutator::new(),
+        WordInterestingMutator::new(),
+        DwordInterestingMutator::new(),
+        BytesSetMutator::new(),
+        BytesRandSetMutator::new(),
+        BytesCopyMutator::new(),
+        BytesSwapMutator::new(),
+    )
+}
+
 /// Get the mutations that uses the Tokens metadata
 #[must_use]
 pub fn tokens_mutations() -> tuple_list_type!(TokenInsert, TokenReplace) {
@@ -412,19 +568,327 @@ where
     }
 }
 
+/// The default name of a [`StatsScheduledMutator`], and of its [`struct@MutationStatsMetadata`]
+pub const DEFAULT_STATS_MUTATOR_NAME: &str = "StatsScheduledMutator";
+
+/// The per-mutation statistics of a [`StatsScheduledMutator`], stored in the state under the name of the mutator.
+/// Each list is indexed by the position of the mutation in the mutations tuple.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct MutationStatsMetadata {
+    /// How often each mutation changed an input
+    pub used: Vec<u64>,
+    /// How often each mutation contributed to an input added to the corpus
+    pub corpus_finds: Vec<u64>,
+    /// How often each mutation contributed to an input added to the solutions
+    pub objective_finds: Vec<u64>,
+    /// The cumulative weights, cached for the adaptive schedule
+    #[serde(skip)]
+    cumulative_weights: Vec<f64>,
+}
+
+crate::impl_serdeany!(MutationStatsMetadata);
+
+impl MutationStatsMetadata {
+    /// Creates a new [`struct@MutationStatsMetadata`] for `len` mutations
+    #[must_use]
+    pub fn new(len: usize) -> Self {
+        Self {
+            used: vec![0; len],
+            corpus_finds: vec![0; len],
+            objective_finds: vec![0; len],
+            cumulative_weights: vec![],
+        }
+    }
+
+    /// The number of inputs, corpus entries or solutions, the mutation at `idx` contributed to
+    #[must_use]
+    pub fn finds(&self, idx: usize) -> u64 {
+        self.corpus_finds[idx] + self.objective_finds[idx]
+    }
+
+    /// The weight of the mutation at `idx` for an adaptive schedule: its smoothed ratio of finds per use
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn weight(&self, idx: usize) -> f64 {
+        (self.finds(idx) + 1) as f64 / (self.used[idx] + 2) as f64
+    }
+
+    /// Recomputes the cached weights of the adaptive schedule.
+    /// Call it after changing the counts by hand.
+    pub fn update_weights(&mut self) {
+        let mut cumulative_weights = core::mem::take(&mut self.cumulative_weights);
+        cumulative_weights.clear();
+        let mut sum = 0.0;
+        for idx in 0..self.used.len() {
+            sum += self.weight(idx);
+            cumulative_weights.push(sum);
+        }
+        self.cumulative_weights = cumulative_weights;
+    }
+
+    /// Picks a mutation proportionally to its weight, for a `point` in `[0.0, 1.0)`
+    fn sample(&mut self, point: f64) -> usize {
+        let len = self.used.len();
+        if self.cumulative_weights.len() != len {
+            self.update_weights();
+        }
+        let target = point * self.cumulative_weights[len - 1];
+        self.cumulative_weights
+            .partition_point(|&weight| weight <= target)
+            .min(len - 1)
+    }
+}
+
+/// A [`Mutator`] that wraps around a [`ScheduledMutator`], counting how often each mutation
+/// contributed to new corpus entries or solutions, in the [`struct@MutationStatsMetadata`] of its name in the state.
+/// In adaptive mode, it also picks mutations proportionally to their success so far.
+pub struct StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: UsesInput + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    name: String,
+    scheduled: SM,
+    adaptive: bool,
+    applied: Vec<usize>,
+    corpus_before: usize,
+    solutions_before: usize,
+    phantom: PhantomData<(MT, S)>,
+}
+
+impl<MT, S, SM> Debug for StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: UsesInput + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "StatsScheduledMutator {} with {} mutations for Input type {}, adaptive: {}",
+            self.name,
+            self.scheduled.mutations().len(),
+            core::any::type_name::<<S as UsesInput>::Input>(),
+            self.adaptive
+        )
+    }
+}
+
+impl<MT, S, SM> Mutator<S> for StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut <S as UsesInput>::Input,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        self.corpus_before = state.corpus().count();
+        self.solutions_before = state.solutions().count();
+        self.scheduled_mutate(state, input, stage_idx)
+    }
+
+    fn post_exec(
+        &mut self,
+        state: &mut S,
+        _stage_idx: i32,
+        _corpus_idx: Option<usize>,
+    ) -> Result<(), Error> {
+        let new_entries = state.corpus().count().saturating_sub(self.corpus_before) as u64;
+        let new_solutions = state
+            .solutions()
+            .count()
+            .saturating_sub(self.solutions_before) as u64;
+
+        if self.applied.is_empty() {
+            return Ok(());
+        }
+        let meta = self.stats_mut(state);
+        for &idx in &self.applied {
+            meta.used[idx] += 1;
+            meta.corpus_finds[idx] += new_entries;
+            meta.objective_finds[idx] += new_solutions;
+        }
+        meta.update_weights();
+        self.applied.clear();
+        Ok(())
+    }
+}
+
+impl<MT, S, SM> Named for StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<MT, S, SM> ComposedByMutations<MT, S> for StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    #[inline]
+    fn mutations(&self) -> &MT {
+        self.scheduled.mutations()
+    }
+
+    #[inline]
+    fn mutations_mut(&mut self) -> &mut MT {
+        self.scheduled.mutations_mut()
+    }
+}
+
+impl<MT, S, SM> ScheduledMutator<MT, S> for StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    /// Compute the number of iterations used to apply stacked mutations
+    #[inline]
+    fn iterations(&self, state: &mut S, input: &<S as UsesInput>::Input) -> u64 {
+        self.scheduled.iterations(state, input)
+    }
+
+    /// Get the next mutation to apply.
+    /// In adaptive mode, each mutation is picked proportionally to its [`MutationStatsMetadata::weight`].
+    #[allow(clippy::cast_precision_loss)]
+    fn schedule(&self, state: &mut S, input: &<S as UsesInput>::Input) -> usize {
+        const RESOLUTION: u64 = 1 << 53;
+        if !self.adaptive {
+            return self.scheduled.schedule(state, input);
+        }
+        let len = self.scheduled.mutations().len();
+        debug_assert!(len > 0);
+        // Draw first, the metadata and the rand can't be borrowed at the same time
+        let point = state.rand_mut().below(RESOLUTION) as f64 / RESOLUTION as f64;
+        if let Some(meta) = state
+            .named_metadata_mut()
+            .get_mut::<MutationStatsMetadata>(&self.name)
+        {
+            if meta.used.len() == len {
+                return meta.sample(point);
+            }
+        }
+        self.scheduled.schedule(state, input)
+    }
+
+    fn scheduled_mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut <S as UsesInput>::Input,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let mut r = MutationResult::Skipped;
+        let num = self.iterations(state, input);
+        self.applied.clear();
+        for _ in 0..num {
+            let idx = self.schedule(state, input);
+            let outcome = self
+                .mutations_mut()
+                .get_and_mutate(idx, state, input, stage_idx)?;
+            if outcome == MutationResult::Mutated {
+                self.applied.push(idx);
+                r = MutationResult::Mutated;
+            }
+        }
+        Ok(r)
+    }
+}
+
+impl<MT, S, SM> StatsScheduledMutator<MT, S, SM>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasCorpus + HasSolutions + HasNamedMetadata,
+    SM: ScheduledMutator<MT, S>,
+{
+    /// Create a new [`StatsScheduledMutator`], only tracking the statistics of the `scheduled` mutator,
+    /// called [`DEFAULT_STATS_MUTATOR_NAME`]
+    pub fn new(scheduled: SM) -> Self {
+        Self::with_name(DEFAULT_STATS_MUTATOR_NAME, scheduled)
+    }
+
+    /// Create a new [`StatsScheduledMutator`], only tracking the statistics of the `scheduled` mutator,
+    /// with its own `name`, so that several of them keep separate statistics in the same state
+    pub fn with_name(name: &str, scheduled: SM) -> Self {
+        Self {
+            name: name.to_string(),
+            scheduled,
+            adaptive: false,
+            applied: vec![],
+            corpus_before: 0,
+            solutions_before: 0,
+            phantom: PhantomData,
+        }
+    }
+
+    /// Create a new [`StatsScheduledMutator`], biasing the schedule toward the mutations
+    /// that were productive so far, called [`DEFAULT_STATS_MUTATOR_NAME`]
+    pub fn adaptive(scheduled: SM) -> Self {
+        Self::adaptive_with_name(DEFAULT_STATS_MUTATOR_NAME, scheduled)
+    }
+
+    /// Create a new [`StatsScheduledMutator`], biasing the schedule toward the mutations
+    /// that were productive so far, with its own `name`
+    pub fn adaptive_with_name(name: &str, scheduled: SM) -> Self {
+        Self {
+            adaptive: true,
+            ..Self::with_name(name, scheduled)
+        }
+    }
+
+    /// The [`struct@MutationStatsMetadata`] of this mutator in the `state`, if it tracked any mutation yet
+    pub fn stats<'a>(&self, state: &'a S) -> Option<&'a MutationStatsMetadata> {
+        state
+            .named_metadata()
+            .get::<MutationStatsMetadata>(&self.name)
+    }
+
+    /// The [`struct@MutationStatsMetadata`] of this mutator in the `state`,
+    /// (re)created if it does not fit the number of mutations
+    fn stats_mut<'a>(&self, state: &'a mut S) -> &'a mut MutationStatsMetadata {
+        let len = self.scheduled.mutations().len();
+        if self
+            .stats(state)
+            .map_or(true, |stats| stats.used.len() != len)
+        {
+            state.add_named_metadata(MutationStatsMetadata::new(len), &self.name);
+        }
+        state
+            .named_metadata_mut()
+            .get_mut::<MutationStatsMetadata>(&self.name)
+            .unwrap()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
-        bolts::rands::{Rand, StdRand, XkcdRand},
+        bolts::{
+            rands::{Rand, StdRand, XkcdRand},
+            tuples::tuple_list,
+        },
         corpus::{Corpus, InMemoryCorpus, Testcase},
         feedbacks::ConstFeedback,
         inputs::{BytesInput, HasBytesVec},
         mutators::{
             mutations::SpliceMutator,
-            scheduled::{havoc_mutations, StdScheduledMutator},
-            Mutator,
+            scheduled::{
+                havoc_mutations, MutationStatsMetadata, ScheduledMutator, StatsScheduledMutator,
+                StdScheduledMutator, DEFAULT_STATS_MUTATOR_NAME,
+            },
+            BitFlipMutator, ByteIncMutator, IntoMutatorsVec, MutationResult, Mutator,
         },
-        state::StdState,
+        state::{HasCorpus, HasNamedMetadata, StdState},
     };
 
     #[test]
@@ -513,6 +977,125 @@ mod tests {
             assert_ne!(equal_in_a_row, 5);
         }
     }
+
+    #[test]
+    fn test_mutations_vec() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0x1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        // As picked from the command line
+        let names = ["BitFlipMutator", "ByteIncMutator"];
+        let mut mutations = havoc_mutations().into_vec();
+        mutations.retain(|mutator| names.contains(&mutator.name()));
+        assert_eq!(mutations.len(), 2);
+
+        let seed = BytesInput::new(vec![b'x'; 16]);
+        let mut mutator = StdScheduledMutator::new(mutations);
+        for sample in mutator.preview(&mut state, &seed, 8).unwrap() {
+            assert!(sample.log.iter().all(|name| names.contains(&name.as_str())));
+        }
+    }
+
+    #[test]
+    fn test_preview() {
+        let mut corpus: InMemoryCorpus<BytesInput> = InMemoryCorpus::new();
+        corpus
+            .add(Testcase::new(vec![b'a', b'b', b'c'].into()))
+            .unwrap();
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0x1337),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let seed = BytesInput::new(vec![b'x'; 16]);
+        let mut mutator = StdScheduledMutator::new(havoc_mutations());
+        let samples = mutator.preview(&mut state, &seed, 8).unwrap();
+
+        assert_eq!(samples.len(), 8);
+        for sample in &samples {
+            assert_eq!(
+                sample.result == MutationResult::Mutated,
+                !sample.log.is_empty()
+            );
+        }
+        assert!(samples.iter().any(|sample| sample.input != seed));
+    }
+
+    #[test]
+    fn test_mutation_stats() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0x1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut mutator = StatsScheduledMutator::adaptive(StdScheduledMutator::new(tuple_list!(
+            BitFlipMutator::new(),
+            ByteIncMutator::new()
+        )));
+        let mut input = BytesInput::new(vec![b'x'; 16]);
+        mutator.mutate(&mut state, &mut input, 0).unwrap();
+        state
+            .corpus_mut()
+            .add(Testcase::new(input.clone()))
+            .unwrap();
+        mutator.post_exec(&mut state, 0, Some(0)).unwrap();
+
+        let meta = mutator.stats(&state).unwrap();
+        assert_eq!(meta.used.len(), 2);
+        assert!(meta.used.iter().sum::<u64>() > 0);
+        assert_eq!(meta.corpus_finds, meta.used);
+        assert_eq!(meta.objective_finds, vec![0, 0]);
+
+        // Another mutator keeps its own statistics
+        let other = StatsScheduledMutator::with_name(
+            "other",
+            StdScheduledMutator::new(tuple_list!(BitFlipMutator::new())),
+        );
+        assert!(other.stats(&state).is_none());
+
+        // Only the `ByteIncMutator` was productive, so far
+        let mut meta = MutationStatsMetadata::new(2);
+        meta.used = vec![1000, 1000];
+        meta.corpus_finds = vec![0, 1000];
+        state.add_named_metadata(meta, DEFAULT_STATS_MUTATOR_NAME);
+        let picked = (0..100)
+            .filter(|_| mutator.schedule(&mut state, &input) == 1)
+            .count();
+        assert!(picked > 90);
+
+        // Changed by hand, the cached weights follow after an update
+        let meta = state
+            .named_metadata_mut()
+            .get_mut::<MutationStatsMetadata>(DEFAULT_STATS_MUTATOR_NAME)
+            .unwrap();
+        meta.corpus_finds = vec![1000, 0];
+        meta.update_weights();
+        let picked = (0..100)
+            .filter(|_| mutator.schedule(&mut state, &input) == 0)
+            .count();
+        assert!(picked > 90);
+    }
 }
 
 /// `SchedulerMutator` Python bindings
@@ -0,0 +1,305 @@
+//! Mutations on the tokens of textual inputs, such as SQL queries or config files.
+//! Instead of shredding the input byte by byte, they move around whole words and statements.
+
+use alloc::vec::Vec;
+use core::ops::Range;
+
+use crate::{
+    bolts::{
+        rands::Rand,
+        tuples::{tuple_list, tuple_list_type, Named},
+    },
+    inputs::{HasBytesVec, UsesInput},
+    mutators::{MutationResult, Mutator, Tokens},
+    state::{HasMaxSize, HasMetadata, HasRand},
+    Error,
+};
+
+/// The bytes separating the tokens of a textual input, besides ASCII whitespace
+pub const TEXT_DELIMITERS: &[u8] = b";,.:()[]{}<>=+-*/!&|\"'`";
+
+/// The bytes terminating a statement of a textual input
+pub const STATEMENT_TERMINATORS: &[u8] = b";\n";
+
+/// Returns `true` if `byte` separates two tokens
+#[inline]
+fn is_delimiter(byte: u8) -> bool {
+    byte.is_ascii_whitespace() || TEXT_DELIMITERS.contains(&byte)
+}
+
+/// The byte ranges of the tokens of `bytes`, i.e., the maximal runs of non-delimiter bytes
+#[must_use]
+pub fn text_tokens(bytes: &[u8]) -> Vec<Range<usize>> {
+    let mut tokens = vec![];
+    let mut start = None;
+    for (i, byte) in bytes.iter().enumerate() {
+        match (start, is_delimiter(*byte)) {
+            (None, false) => start = Some(i),
+            (Some(s), true) => {
+                tokens.push(s..i);
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    if let Some(s) = start {
+        tokens.push(s..bytes.len());
+    }
+    tokens
+}
+
+/// The byte ranges of the statements of `bytes`, each including its terminator, if any
+#[must_use]
+pub fn text_statements(bytes: &[u8]) -> Vec<Range<usize>> {
+    let mut statements = vec![];
+    let mut start = 0;
+    for (i, byte) in bytes.iter().enumerate() {
+        if STATEMENT_TERMINATORS.contains(byte) {
+            let end = i + 1;
+            statements.push(start..end);
+            start = end;
+        }
+    }
+    if start < bytes.len() {
+        statements.push(start..bytes.len());
+    }
+    statements
+}
+
+/// Swaps two tokens of a textual input
+#[derive(Debug, Default)]
+pub struct TextTokenSwapMutator;
+
+impl<S> Mutator<S> for TextTokenSwapMutator
+where
+    S: UsesInput + HasRand,
+    S::Input: HasBytesVec,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let tokens = text_tokens(input.bytes());
+        if tokens.len() < 2 {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let a = state.rand_mut().below(tokens.len() as u64) as usize;
+        let b = state.rand_mut().below(tokens.len() as u64 - 1) as usize;
+        // Pick two different tokens, the first one coming first
+        let (first, second) = if b >= a {
+            (&tokens[a], &tokens[b + 1])
+        } else {
+            (&tokens[b], &tokens[a])
+        };
+        if input.bytes()[first.clone()] == input.bytes()[second.clone()] {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let bytes = input.bytes();
+        let mut swapped = Vec::with_capacity(bytes.len());
+        swapped.extend_from_slice(&bytes[..first.start]);
+        swapped.extend_from_slice(&bytes[second.clone()]);
+        swapped.extend_from_slice(&bytes[first.end..second.start]);
+        swapped.extend_from_slice(&bytes[first.clone()]);
+        swapped.extend_from_slice(&bytes[second.end..]);
+        *input.bytes_mut() = swapped;
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for TextTokenSwapMutator {
+    fn name(&self) -> &str {
+        "TextTokenSwapMutator"
+    }
+}
+
+impl TextTokenSwapMutator {
+    /// Creates a new [`TextTokenSwapMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Duplicates a statement of a textual input, right after itself
+#[derive(Debug, Default)]
+pub struct TextStatementDuplicateMutator;
+
+impl<S> Mutator<S> for TextStatementDuplicateMutator
+where
+    S: UsesInput + HasRand + HasMaxSize,
+    S::Input: HasBytesVec,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let statements = text_statements(input.bytes());
+        if statements.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let idx = state.rand_mut().below(statements.len() as u64) as usize;
+        let statement = statements[idx].clone();
+        if input.bytes().len() + statement.len() > state.max_size() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let copy = input.bytes()[statement.clone()].to_vec();
+        input.bytes_mut().splice(statement.end..statement.end, copy);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for TextStatementDuplicateMutator {
+    fn name(&self) -> &str {
+        "TextStatementDuplicateMutator"
+    }
+}
+
+impl TextStatementDuplicateMutator {
+    /// Creates a new [`TextStatementDuplicateMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Replaces a whole token of a textual input, such as an identifier or a keyword,
+/// with an entry of the [`Tokens`] dictionary in the state
+#[derive(Debug, Default)]
+pub struct TextTokenReplaceMutator;
+
+impl<S> Mutator<S> for TextTokenReplaceMutator
+where
+    S: UsesInput + HasRand + HasMaxSize + HasMetadata,
+    S::Input: HasBytesVec,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let dict_len = state.metadata().get::<Tokens>().map_or(0, Tokens::len);
+        if dict_len == 0 {
+            return Ok(MutationResult::Skipped);
+        }
+        let tokens = text_tokens(input.bytes());
+        if tokens.is_empty() {
+            return Ok(MutationResult::Skipped);
+        }
+
+        let token = tokens[state.rand_mut().below(tokens.len() as u64) as usize].clone();
+        let entry_idx = state.rand_mut().below(dict_len as u64) as usize;
+        let entry = state.metadata().get::<Tokens>().unwrap().tokens()[entry_idx].clone();
+        if input.bytes()[token.clone()] == entry[..]
+            || input.bytes().len() - token.len() + entry.len() > state.max_size()
+        {
+            return Ok(MutationResult::Skipped);
+        }
+
+        input.bytes_mut().splice(token, entry);
+
+        Ok(MutationResult::Mutated)
+    }
+}
+
+impl Named for TextTokenReplaceMutator {
+    fn name(&self) -> &str {
+        "TextTokenReplaceMutator"
+    }
+}
+
+impl TextTokenReplaceMutator {
+    /// Creates a new [`TextTokenReplaceMutator`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Tuple type of the mutations on the tokens of textual inputs
+pub type TextMutationsType = tuple_list_type!(
+    TextTokenSwapMutator,
+    TextStatementDuplicateMutator,
+    TextTokenReplaceMutator,
+);
+
+/// Get the mutations on the tokens of textual inputs
+#[must_use]
+pub fn text_mutations() -> TextMutationsType {
+    tuple_list!(
+        TextTokenSwapMutator::new(),
+        TextStatementDuplicateMutator::new(),
+        TextTokenReplaceMutator::new(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        feedbacks::ConstFeedback,
+        inputs::{BytesInput, HasBytesVec},
+        mutators::{
+            text::{
+                text_statements, text_tokens, TextStatementDuplicateMutator,
+                TextTokenReplaceMutator, TextTokenSwapMutator,
+            },
+            MutationResult, Mutator, Tokens,
+        },
+        state::{HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_text_mutations() {
+        let query = b"SELECT a FROM t;\nDROP t";
+        assert_eq!(
+            text_tokens(query),
+            vec![0..6, 7..8, 9..13, 14..15, 17..21, 22..23]
+        );
+        assert_eq!(text_statements(query), vec![0..16, 16..17, 17..23]);
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let mut input = BytesInput::new(b"SELECT a".to_vec());
+        let result = TextTokenSwapMutator::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(result, MutationResult::Mutated);
+        assert_eq!(input.bytes(), b"a SELECT");
+
+        let mut input = BytesInput::new(b"x=1;".to_vec());
+        TextStatementDuplicateMutator::new()
+            .mutate(&mut state, &mut input, 0)
+            .unwrap();
+        assert_eq!(input.bytes(), b"x=1;x=1;");
+
+        let mut input = BytesInput::new(b"f(x)".to_vec());
+        let mut replace = TextTokenReplaceMutator::new();
+        let result = replace.mutate(&mut state, &mut input, 0).unwrap();
+        assert_eq!(result, MutationResult::Skipped);
+        state.add_metadata(Tokens::from([b"y".to_vec()]));
+        while input.bytes() != b"y(y)" {
+            replace.mutate(&mut state, &mut input, 0).unwrap();
+        }
+    }
+}
@@ -0,0 +1,431 @@
+//! The [`TuneableScheduledMutator`] is a [`ScheduledMutator`] whose mutation probabilities and
+//! number of stacked iterations are read from the state at runtime.
+//! An outer loop, or the user, can change them through the [`TuneableScheduledMutatorMetadata`],
+//! stored in the state under the name of the mutator.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{
+    fmt::{self, Debug},
+    marker::PhantomData,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::{rands::Rand, tuples::Named},
+    impl_serdeany,
+    mutators::{ComposedByMutations, MutationResult, Mutator, MutatorsTuple, ScheduledMutator},
+    state::{HasNamedMetadata, HasRand, State},
+    Error,
+};
+
+/// The maximum deviation from `1.0` tolerated for the sum of a probability distribution
+const PROBABILITY_EPSILON: f32 = 0.001;
+
+/// The default maximum power of two of stacked iterations, as in the [`crate::mutators::StdScheduledMutator`]
+const DEFAULT_MAX_STACK_POW: u64 = 7;
+
+/// The default name of a [`TuneableScheduledMutator`], and of its metadata
+pub const DEFAULT_TUNEABLE_MUTATOR_NAME: &str = "TuneableScheduledMutator";
+
+/// The named metadata in the state, used to tune the [`TuneableScheduledMutator`] of the same name at runtime
+#[derive(Default, Serialize, Deserialize, Clone, Debug)]
+pub struct TuneableScheduledMutatorMetadata {
+    /// The cumulative probabilities of each mutation, or empty to pick mutations uniformly
+    pub mutation_probabilities_cumulative: Vec<f32>,
+    /// The cumulative probabilities of each power of two of stacked iterations, or empty for the default distribution.
+    /// The entry at index `i` stands for `2^i` iterations.
+    pub iter_probabilities_pow_cumulative: Vec<f32>,
+    /// A fixed number of stacked iterations, overriding the distribution
+    pub iters: Option<u64>,
+}
+
+impl_serdeany!(TuneableScheduledMutatorMetadata);
+
+impl TuneableScheduledMutatorMetadata {
+    /// Gets the [`TuneableScheduledMutatorMetadata`] of the mutator called `name` from the `state`,
+    /// adding the default one if missing
+    pub fn get_mut<'a, S>(state: &'a mut S, name: &str) -> &'a mut Self
+    where
+        S: HasNamedMetadata,
+    {
+        if !state.has_named_metadata::<Self>(name) {
+            state.add_named_metadata(Self::default(), name);
+        }
+        state.named_metadata_mut().get_mut::<Self>(name).unwrap()
+    }
+
+    /// Sets the probability of each mutation, in the order of the mutations tuple.
+    /// The probabilities must not be negative and must add up to `1.0`.
+    pub fn set_mutation_probabilities(&mut self, probabilities: &[f32]) -> Result<(), Error> {
+        self.mutation_probabilities_cumulative = cumulative(probabilities)?;
+        Ok(())
+    }
+
+    /// Sets the probability of `2^i` stacked iterations, for each index `i`.
+    /// The probabilities must not be negative and must add up to `1.0`.
+    pub fn set_iter_probabilities_pow(&mut self, probabilities: &[f32]) -> Result<(), Error> {
+        if probabilities.len() >= 64 {
+            return Err(Error::illegal_argument(
+                "Cannot stack 2^64 or more iterations",
+            ));
+        }
+        self.iter_probabilities_pow_cumulative = cumulative(probabilities)?;
+        Ok(())
+    }
+
+    /// Always stack `iters` mutations, ignoring the iterations distribution
+    pub fn set_iters(&mut self, iters: u64) {
+        self.iters = Some(iters);
+    }
+
+    /// Goes back to picking mutations uniformly, with the default iterations distribution
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Checks the probability distribution and returns its cumulative values
+fn cumulative(probabilities: &[f32]) -> Result<Vec<f32>, Error> {
+    if probabilities.is_empty() {
+        return Err(Error::illegal_argument("No probabilities given"));
+    }
+    if probabilities.iter().any(|p| !(0.0..=1.0).contains(p)) {
+        return Err(Error::illegal_argument(format!(
+            "Probabilities must be between 0.0 and 1.0, got {probabilities:?}"
+        )));
+    }
+    let mut sum = 0.0;
+    let cumulative: Vec<f32> = probabilities
+        .iter()
+        .map(|p| {
+            sum += p;
+            sum
+        })
+        .collect();
+    // `f32::abs` needs `std`
+    if libm::fabsf(sum - 1.0) > PROBABILITY_EPSILON {
+        return Err(Error::illegal_argument(format!(
+            "Probabilities must add up to 1.0, got {sum}"
+        )));
+    }
+    Ok(cumulative)
+}
+
+/// Draws a random point in `[0.0, 1.0)`, to [`sample`] from a cumulative distribution
+#[allow(clippy::cast_precision_loss)]
+fn random_point<R>(rand: &mut R) -> f32
+where
+    R: Rand,
+{
+    const RESOLUTION: u64 = 1 << 24;
+    rand.below(RESOLUTION) as f32 / RESOLUTION as f32
+}
+
+/// Picks the index of the `point` in a cumulative distribution
+fn sample(point: f32, cumulative: &[f32]) -> usize {
+    if let Some(idx) = cumulative.iter().position(|&p| point < p) {
+        idx
+    } else {
+        // Rounding may leave the sum slightly below 1.0, pick the last entry with a probability above 0.0
+        let sum = cumulative[cumulative.len() - 1];
+        cumulative.iter().position(|&p| p >= sum).unwrap()
+    }
+}
+
+/// A [`Mutator`] scheduling its mutations with the probabilities found in
+/// the [`TuneableScheduledMutatorMetadata`] of its name in the state.
+/// Without tuning, it behaves like the [`crate::mutators::StdScheduledMutator`].
+pub struct TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    name: String,
+    mutations: MT,
+    phantom: PhantomData<S>,
+}
+
+impl<MT, S> Debug for TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TuneableScheduledMutator {} with {} mutations for Input type {}",
+            self.name,
+            self.mutations.len(),
+            core::any::type_name::<S::Input>()
+        )
+    }
+}
+
+impl<MT, S> Mutator<S> for TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    #[inline]
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut S::Input,
+        stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        if let Some(meta) = state
+            .named_metadata()
+            .get::<TuneableScheduledMutatorMetadata>(&self.name)
+        {
+            let probabilities = meta.mutation_probabilities_cumulative.len();
+            if probabilities != 0 && probabilities != self.mutations.len() {
+                return Err(Error::illegal_state(format!(
+                    "TuneableScheduledMutator {}: got {probabilities} mutation probabilities for {} mutations",
+                    self.name,
+                    self.mutations.len()
+                )));
+            }
+        }
+        self.scheduled_mutate(state, input, stage_idx)
+    }
+}
+
+impl<MT, S> Named for TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl<MT, S> ComposedByMutations<MT, S> for TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    #[inline]
+    fn mutations(&self) -> &MT {
+        &self.mutations
+    }
+
+    #[inline]
+    fn mutations_mut(&mut self) -> &mut MT {
+        &mut self.mutations
+    }
+}
+
+impl<MT, S> ScheduledMutator<MT, S> for TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    /// Compute the number of iterations used to apply stacked mutations
+    fn iterations(&self, state: &mut S, _: &S::Input) -> u64 {
+        // Draw first, the metadata and the rand can't be borrowed at the same time
+        let point = random_point(state.rand_mut());
+        if let Some(meta) = state
+            .named_metadata()
+            .get::<TuneableScheduledMutatorMetadata>(&self.name)
+        {
+            if let Some(iters) = meta.iters {
+                return iters;
+            }
+            if !meta.iter_probabilities_pow_cumulative.is_empty() {
+                return 1 << sample(point, &meta.iter_probabilities_pow_cumulative);
+            }
+        }
+        1 << (1 + state.rand_mut().below(DEFAULT_MAX_STACK_POW))
+    }
+
+    /// Get the next mutation to apply
+    fn schedule(&self, state: &mut S, _: &S::Input) -> usize {
+        debug_assert!(!self.mutations().is_empty());
+        // Draw first, the metadata and the rand can't be borrowed at the same time
+        let point = random_point(state.rand_mut());
+        if let Some(meta) = state
+            .named_metadata()
+            .get::<TuneableScheduledMutatorMetadata>(&self.name)
+        {
+            // Probabilities of the wrong length are refused in `mutate`
+            let probabilities = &meta.mutation_probabilities_cumulative;
+            if probabilities.len() == self.mutations().len() {
+                return sample(point, probabilities);
+            }
+        }
+        state.rand_mut().below(self.mutations().len() as u64) as usize
+    }
+}
+
+impl<MT, S> TuneableScheduledMutator<MT, S>
+where
+    MT: MutatorsTuple<S>,
+    S: State + HasRand + HasNamedMetadata,
+{
+    /// Create a new [`TuneableScheduledMutator`] instance specifying mutations,
+    /// called [`DEFAULT_TUNEABLE_MUTATOR_NAME`].
+    /// Adds an untuned [`TuneableScheduledMutatorMetadata`] to the `state`, if there is none yet.
+    pub fn new(state: &mut S, mutations: MT) -> Self {
+        Self::with_name(DEFAULT_TUNEABLE_MUTATOR_NAME, state, mutations)
+    }
+
+    /// Create a new [`TuneableScheduledMutator`] instance specifying mutations, with its own `name`,
+    /// so that several of them can be tuned independently in the same `state`.
+    /// Adds an untuned [`TuneableScheduledMutatorMetadata`] of this name to the `state`, if there is none yet.
+    pub fn with_name(name: &str, state: &mut S, mutations: MT) -> Self {
+        TuneableScheduledMutatorMetadata::get_mut(state, name);
+        Self {
+            name: name.to_string(),
+            mutations,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The [`TuneableScheduledMutatorMetadata`] of this mutator in the `state`
+    pub fn metadata_mut<'a>(&self, state: &'a mut S) -> &'a mut TuneableScheduledMutatorMetadata {
+        TuneableScheduledMutatorMetadata::get_mut(state, &self.name)
+    }
+
+    /// Sets the probability of each mutation of this mutator, in the `state`
+    pub fn set_mutation_probabilities(
+        &self,
+        state: &mut S,
+        probabilities: &[f32],
+    ) -> Result<(), Error> {
+        if probabilities.len() != self.mutations.len() {
+            return Err(Error::illegal_argument(format!(
+                "Got {} probabilities for {} mutations",
+                probabilities.len(),
+                self.mutations.len()
+            )));
+        }
+        self.metadata_mut(state)
+            .set_mutation_probabilities(probabilities)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        feedbacks::ConstFeedback,
+        inputs::BytesInput,
+        mutators::{
+            tuneable::{sample, TuneableScheduledMutator},
+            BitFlipMutator, ByteDecMutator, ByteIncMutator, Mutator, ScheduledMutator,
+        },
+        state::StdState,
+    };
+
+    #[test]
+    fn test_sample_skips_trailing_zeros() {
+        // Rounding left the sum below 1.0, the entries without probability must never be picked
+        let cumulative = [0.25, 0.999, 0.999, 0.999];
+        assert_eq!(sample(0.0, &cumulative), 0);
+        assert_eq!(sample(0.5, &cumulative), 1);
+        assert_eq!(sample(0.9995, &cumulative), 1);
+    }
+
+    #[test]
+    fn test_tuning() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0x1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let input = BytesInput::new(vec![0; 4]);
+
+        let mutator = TuneableScheduledMutator::new(
+            &mut state,
+            tuple_list!(
+                BitFlipMutator::new(),
+                ByteDecMutator::new(),
+                ByteIncMutator::new()
+            ),
+        );
+        assert!(mutator
+            .set_mutation_probabilities(&mut state, &[0.5, 0.5])
+            .is_err());
+        assert!(mutator
+            .set_mutation_probabilities(&mut state, &[0.5, 0.4, 0.4])
+            .is_err());
+
+        mutator
+            .set_mutation_probabilities(&mut state, &[0.0, 1.0, 0.0])
+            .unwrap();
+        for _ in 0..32 {
+            assert_eq!(mutator.schedule(&mut state, &input), 1);
+        }
+
+        let meta = mutator.metadata_mut(&mut state);
+        meta.set_iter_probabilities_pow(&[0.0, 0.0, 1.0]).unwrap();
+        for _ in 0..32 {
+            assert_eq!(mutator.iterations(&mut state, &input), 4);
+        }
+        mutator.metadata_mut(&mut state).set_iters(3);
+        assert_eq!(mutator.iterations(&mut state, &input), 3);
+
+        mutator.metadata_mut(&mut state).reset();
+        assert!((0..64).any(|_| mutator.schedule(&mut state, &input) != 1));
+    }
+
+    #[test]
+    fn test_tuning_by_name() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0x1337),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut input = BytesInput::new(vec![0; 4]);
+
+        let first = TuneableScheduledMutator::with_name(
+            "first",
+            &mut state,
+            tuple_list!(BitFlipMutator::new(), ByteDecMutator::new()),
+        );
+        let mut second = TuneableScheduledMutator::with_name(
+            "second",
+            &mut state,
+            tuple_list!(
+                BitFlipMutator::new(),
+                ByteDecMutator::new(),
+                ByteIncMutator::new()
+            ),
+        );
+
+        // Each mutator reads the probabilities of its own name
+        first
+            .set_mutation_probabilities(&mut state, &[0.0, 1.0])
+            .unwrap();
+        second
+            .set_mutation_probabilities(&mut state, &[0.0, 0.0, 1.0])
+            .unwrap();
+        for _ in 0..32 {
+            assert_eq!(first.schedule(&mut state, &input), 1);
+            assert_eq!(second.schedule(&mut state, &input), 2);
+        }
+
+        // Probabilities set on the metadata directly, for a different number of mutations, are refused
+        second
+            .metadata_mut(&mut state)
+            .set_mutation_probabilities(&[0.5, 0.5])
+            .unwrap();
+        assert!(second.mutate(&mut state, &mut input, 0).is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Mutations of [`ValueInput`]s, optionally keeping the value within configured bounds.
+
+use crate::{
+    bolts::{
+        rands::Rand,
+        tuples::{tuple_list, tuple_list_type, Named},
+    },
+    inputs::{value::Numeric, UsesInput, ValueInput},
+    mutators::{MutationResult, Mutator},
+    state::HasRand,
+    Error,
+};
+
+/// The maximum absolute delta added by the [`ValueAddMutator`], as for AFL's arithmetic mutations
+pub const VALUE_ARITH_MAX: u64 = 35;
+
+/// The inclusive bounds of a [`ValueInput`], shared by the value mutators
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ValueBounds<T> {
+    min: T,
+    max: T,
+}
+
+impl<T> ValueBounds<T>
+where
+    T: Numeric,
+{
+    /// Creates new [`ValueBounds`], from `min` to `max` (inclusive)
+    pub fn new(min: T, max: T) -> Result<Self, Error> {
+        if min <= max {
+            Ok(Self { min, max })
+        } else {
+            Err(Error::illegal_argument(format!(
+                "Invalid value bounds: {min:?} > {max:?}"
+            )))
+        }
+    }
+
+    /// Brings `value` back within the bounds, wrapping around modulo the size of the range,
+    /// see [`Numeric::wrap_within`]. `NaN` values end up at the minimum.
+    #[must_use]
+    pub fn wrap(&self, value: T) -> T {
+        value.wrap_within(self.min, self.max)
+    }
+}
+
+/// Applies a new value to the `input`, wrapped into the `bounds`, if any.
+/// Skips if the value did not change.
+fn apply<T>(input: &mut ValueInput<T>, value: T, bounds: Option<&ValueBounds<T>>) -> MutationResult
+where
+    T: Numeric,
+{
+    let value = bounds.map_or(value, |bounds| bounds.wrap(value));
+    if value.to_raw_bits() == input.value().to_raw_bits() {
+        MutationResult::Skipped
+    } else {
+        *input.value_mut() = value;
+        MutationResult::Mutated
+    }
+}
+
+/// Adds or subtracts a small delta to a [`ValueInput`], wrapping around for integers
+#[derive(Debug, Default)]
+pub struct ValueAddMutator<T> {
+    bounds: Option<ValueBounds<T>>,
+}
+
+impl<S, T> Mutator<S> for ValueAddMutator<T>
+where
+    S: UsesInput<Input = ValueInput<T>> + HasRand,
+    T: Numeric,
+{
+    #[allow(clippy::cast_possible_wrap)]
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut ValueInput<T>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let delta = 1 + state.rand_mut().below(VALUE_ARITH_MAX) as i64;
+        let delta = if state.rand_mut().below(2) == 0 {
+            delta
+        } else {
+            -delta
+        };
+        Ok(apply(
+            input,
+            input.value().add_delta(delta),
+            self.bounds.as_ref(),
+        ))
+    }
+}
+
+impl<T> Named for ValueAddMutator<T> {
+    fn name(&self) -> &str {
+        "ValueAddMutator"
+    }
+}
+
+impl<T> ValueAddMutator<T> {
+    /// Creates a new [`ValueAddMutator`], keeping the value within `bounds`, if any
+    #[must_use]
+    pub fn new(bounds: Option<ValueBounds<T>>) -> Self {
+        Self { bounds }
+    }
+}
+
+/// Replaces a [`ValueInput`] with an interesting value of its type
+#[derive(Debug, Default)]
+pub struct ValueInterestingMutator<T> {
+    bounds: Option<ValueBounds<T>>,
+}
+
+impl<S, T> Mutator<S> for ValueInterestingMutator<T>
+where
+    S: UsesInput<Input = ValueInput<T>> + HasRand,
+    T: Numeric,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut ValueInput<T>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let value = *state.rand_mut().choose(T::INTERESTING);
+        Ok(apply(input, value, self.bounds.as_ref()))
+    }
+}
+
+impl<T> Named for ValueInterestingMutator<T> {
+    fn name(&self) -> &str {
+        "ValueInterestingMutator"
+    }
+}
+
+impl<T> ValueInterestingMutator<T> {
+    /// Creates a new [`ValueInterestingMutator`], keeping the value within `bounds`, if any
+    #[must_use]
+    pub fn new(bounds: Option<ValueBounds<T>>) -> Self {
+        Self { bounds }
+    }
+}
+
+/// Flips a bit of a [`ValueInput`], within the width of its type
+#[derive(Debug, Default)]
+pub struct ValueBitFlipMutator<T> {
+    bounds: Option<ValueBounds<T>>,
+}
+
+impl<S, T> Mutator<S> for ValueBitFlipMutator<T>
+where
+    S: UsesInput<Input = ValueInput<T>> + HasRand,
+    T: Numeric,
+{
+    fn mutate(
+        &mut self,
+        state: &mut S,
+        input: &mut ValueInput<T>,
+        _stage_idx: i32,
+    ) -> Result<MutationResult, Error> {
+        let bit = state.rand_mut().below(u64::from(T::BITS));
+        let value = T::from_raw_bits(input.value().to_raw_bits() ^ (1 << bit));
+        Ok(apply(input, value, self.bounds.as_ref()))
+    }
+}
+
+impl<T> Named for ValueBitFlipMutator<T> {
+    fn name(&self) -> &str {
+        "ValueBitFlipMutator"
+    }
+}
+
+impl<T> ValueBitFlipMutator<T> {
+    /// Creates a new [`ValueBitFlipMutator`], keeping the value within `bounds`, if any
+    #[must_use]
+    pub fn new(bounds: Option<ValueBounds<T>>) -> Self {
+        Self { bounds }
+    }
+}
+
+/// Tuple type of the mutations of a [`ValueInput`]
+pub type ValueMutationsType<T> = tuple_list_type!(
+    ValueAddMutator<T>,
+    ValueInterestingMutator<T>,
+    ValueBitFlipMutator<T>,
+);
+
+/// Get the mutations of a [`ValueInput`], keeping the value within `bounds`, if any
+#[must_use]
+pub fn value_mutations<T>(bounds: Option<ValueBounds<T>>) -> ValueMutationsType<T>
+where
+    T: Copy,
+{
+    tuple_list!(
+        ValueAddMutator::new(bounds),
+        ValueInterestingMutator::new(bounds),
+        ValueBitFlipMutator::new(bounds),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::{rands::StdRand, AsSlice},
+        corpus::InMemoryCorpus,
+        feedbacks::ConstFeedback,
+        inputs::{HasTargetBytes, ValueInput},
+        mutators::{
+            value_mutations::{value_mutations, ValueBounds},
+            MutatorsTuple,
+        },
+        state::StdState,
+    };
+
+    #[test]
+    fn test_value_mutations() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(1337),
+            InMemoryCorpus::<ValueInput<i16>>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        assert!(ValueBounds::new(10, -10).is_err());
+        // Values out of bounds wrap around inside them, instead of piling up at the bounds
+        let bounds = ValueBounds::new(-10_i16, 100).unwrap();
+        assert_eq!(bounds.wrap(42), 42);
+        assert_eq!(bounds.wrap(101), -10);
+        assert_eq!(bounds.wrap(105), -6);
+        assert_eq!(bounds.wrap(-11), 100);
+        assert_eq!(bounds.wrap(i16::MIN), 88);
+        assert_eq!(ValueBounds::new(0_u64, 9).unwrap().wrap(u64::MAX), 5);
+        let bounds = ValueBounds::new(0.0_f64, 10.0).unwrap();
+        assert!((bounds.wrap(12.5) - 2.5).abs() < f64::EPSILON);
+        assert!((bounds.wrap(-2.5) - 7.5).abs() < f64::EPSILON);
+        assert!(bounds.wrap(f64::NAN).abs() < f64::EPSILON);
+        assert!(bounds.wrap(f64::INFINITY).abs() < f64::EPSILON);
+
+        let mut mutations = value_mutations(Some(ValueBounds::new(-10, 100).unwrap()));
+        let mut input = ValueInput::new(42_i16);
+        for i in 0..1000 {
+            mutations
+                .get_and_mutate(i % 3, &mut state, &mut input, 0)
+                .unwrap();
+            assert!((-10..=100).contains(input.value()));
+        }
+
+        assert_eq!(
+            ValueInput::new(0x1234_u16).target_bytes().as_slice(),
+            &[0x34, 0x12]
+        );
+        assert_eq!(ValueInput::new(-1_i8).target_bytes().as_slice(), &[0xff]);
+    }
+}
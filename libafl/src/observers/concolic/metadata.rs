@@ -18,6 +18,18 @@ impl ConcolicMetadata {
         std::iter::from_fn(move || parser.next_message()).flatten()
     }
 
+    /// The raw, serialized trace, for example to store it to disk and inspect it later
+    #[must_use]
+    pub fn buffer(&self) -> &[u8] {
+        &self.buffer
+    }
+
+    /// Returns `true` if no constraints were recorded
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
     pub(crate) fn from_buffer(buffer: Vec<u8>) -> Self {
         Self { buffer }
     }
@@ -16,6 +16,11 @@ pub mod stacktrace;
 #[cfg(feature = "std")]
 pub use stacktrace::*;
 
+#[cfg(all(unix, feature = "std"))]
+pub mod rusage;
+#[cfg(all(unix, feature = "std"))]
+pub use rusage::{ResourceUsage, RusageObserver};
+
 pub mod concolic;
 
 // Rust is breaking this with 'error: intrinsic safety mismatch between list of intrinsics within the compiler and core library intrinsics for intrinsic `type_id`' and so we disable this component for the moment
@@ -0,0 +1,258 @@
+//! The [`RusageObserver`] records the resources used by the target, for executors running it in a child process.
+//! Executors wait for their children with [`wait_child`], or report them with [`account_child`],
+//! so that the children of each run are counted, and their memory use is known per run.
+
+use alloc::string::{String, ToString};
+use core::{
+    mem::MaybeUninit,
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use nix::{sys::wait::WaitStatus, unistd::Pid};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::tuples::Named, executors::ExitKind, inputs::UsesInput, observers::Observer, Error,
+};
+
+/// The number of children waited for with [`wait_child`], or reported with [`account_child`]
+static FORKS: AtomicU64 = AtomicU64::new(0);
+/// The maximum resident set size of the last child waited for with [`wait_child`], or `0`
+static LAST_CHILD_MAX_RSS_KB: AtomicU64 = AtomicU64::new(0);
+
+/// Waits for the child `pid`, like `waitpid`, and accounts it, and its own maximum resident set size,
+/// for the [`RusageObserver`].
+#[allow(clippy::cast_sign_loss)]
+pub fn wait_child(pid: Pid) -> Result<WaitStatus, Error> {
+    let mut status = 0;
+    let mut usage = MaybeUninit::<libc::rusage>::uninit();
+    if unsafe { libc::wait4(pid.as_raw(), &mut status, 0, usage.as_mut_ptr()) } < 0 {
+        return Err(Error::file(std::io::Error::last_os_error()));
+    }
+    let usage = unsafe { usage.assume_init() };
+    LAST_CHILD_MAX_RSS_KB.store(usage.ru_maxrss as u64, Ordering::SeqCst);
+    FORKS.fetch_add(1, Ordering::SeqCst);
+    Ok(WaitStatus::from_raw(pid, status)?)
+}
+
+/// Accounts a child that was waited for without [`wait_child`], for example with [`std::process::Child::wait`].
+/// It is counted, but its memory use is only known if it exceeds all children before, see [`ResourceUsage::max_rss_kb`].
+pub fn account_child() {
+    LAST_CHILD_MAX_RSS_KB.store(0, Ordering::SeqCst);
+    FORKS.fetch_add(1, Ordering::SeqCst);
+}
+
+/// The resources used by the target during a single run
+#[allow(clippy::unsafe_derive_deserialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ResourceUsage {
+    /// Time spent executing in user mode
+    pub user_time: Duration,
+    /// Time spent executing in kernel mode
+    pub sys_time: Duration,
+    /// The maximum resident set size, in kilobytes.
+    /// For a run that forked a single child, waited for with [`wait_child`], this is the peak of that child.
+    /// Else, the kernel only reports the largest child so far, so for a single run, see [`ResourceUsage::since`],
+    /// this is only set if the run was larger than all runs before, else `0`.
+    pub max_rss_kb: u64,
+    /// Page faults serviced without any I/O
+    pub minor_faults: u64,
+    /// Page faults that required I/O
+    pub major_faults: u64,
+    /// The number of children forked and waited for, see [`wait_child`] and [`account_child`]
+    pub forks: u64,
+}
+
+impl ResourceUsage {
+    /// The CPU time spent by the target, in user and kernel mode
+    #[must_use]
+    pub fn cpu_time(&self) -> Duration {
+        self.user_time + self.sys_time
+    }
+
+    /// The resources used by all terminated and waited-for children of this process.
+    /// Only [`ResourceUsage::forks`] is specific to the children accounted with [`wait_child`] or [`account_child`].
+    #[allow(clippy::cast_sign_loss)]
+    pub fn children() -> Result<Self, Error> {
+        let mut usage = MaybeUninit::<libc::rusage>::uninit();
+        if unsafe { libc::getrusage(libc::RUSAGE_CHILDREN, usage.as_mut_ptr()) } != 0 {
+            return Err(Error::file(std::io::Error::last_os_error()));
+        }
+        let usage = unsafe { usage.assume_init() };
+        let to_duration = |tv: libc::timeval| {
+            Duration::from_secs(tv.tv_sec as u64) + Duration::from_micros(tv.tv_usec as u64)
+        };
+        Ok(Self {
+            user_time: to_duration(usage.ru_utime),
+            sys_time: to_duration(usage.ru_stime),
+            max_rss_kb: usage.ru_maxrss as u64,
+            minor_faults: usage.ru_minflt as u64,
+            major_faults: usage.ru_majflt as u64,
+            forks: FORKS.load(Ordering::SeqCst),
+        })
+    }
+
+    /// The resources used since `earlier`, both taken with [`ResourceUsage::children`].
+    /// If a single child was waited for with [`wait_child`] since `earlier`, the maximum resident set size is its peak,
+    /// else it is `0` unless it grew since `earlier`.
+    #[must_use]
+    pub fn since(&self, earlier: &Self) -> Self {
+        let forks = self.forks.saturating_sub(earlier.forks);
+        let last_child_max_rss_kb = LAST_CHILD_MAX_RSS_KB.load(Ordering::SeqCst);
+        if forks == 1 && last_child_max_rss_kb != 0 {
+            return Self {
+                max_rss_kb: last_child_max_rss_kb,
+                ..self.since_peak(earlier)
+            };
+        }
+        self.since_peak(earlier)
+    }
+
+    /// The resources used since `earlier`, where the maximum resident set size is only known if it grew
+    fn since_peak(&self, earlier: &Self) -> Self {
+        Self {
+            user_time: self.user_time.saturating_sub(earlier.user_time),
+            sys_time: self.sys_time.saturating_sub(earlier.sys_time),
+            max_rss_kb: if self.max_rss_kb > earlier.max_rss_kb {
+                self.max_rss_kb
+            } else {
+                0
+            },
+            minor_faults: self.minor_faults.saturating_sub(earlier.minor_faults),
+            major_faults: self.major_faults.saturating_sub(earlier.major_faults),
+            forks: self.forks.saturating_sub(earlier.forks),
+        }
+    }
+}
+
+/// An observer recording the [`ResourceUsage`] of the target for each run.
+///
+/// It works for all executors that run the target in a child process and wait for it before the observers
+/// get their `post_exec`, such as the [`crate::executors::CommandExecutor`] and the
+/// [`crate::executors::InProcessForkExecutor`].
+/// The [`crate::executors::InProcessForkExecutor`] waits with [`wait_child`], so the memory use of each run is known.
+/// The children of a forkserver are waited for by the forkserver, not by the fuzzer, so they are not accounted.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RusageObserver {
+    name: String,
+    before: ResourceUsage,
+    last_usage: Option<ResourceUsage>,
+}
+
+impl RusageObserver {
+    /// Creates a new [`RusageObserver`] with the given name.
+    #[must_use]
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name: name.to_string(),
+            before: ResourceUsage::default(),
+            last_usage: None,
+        }
+    }
+
+    /// Gets the resources used by the last execution of the target.
+    #[must_use]
+    pub fn last_usage(&self) -> &Option<ResourceUsage> {
+        &self.last_usage
+    }
+}
+
+impl<S> Observer<S> for RusageObserver
+where
+    S: UsesInput,
+{
+    fn pre_exec(&mut self, _state: &mut S, _input: &S::Input) -> Result<(), Error> {
+        self.last_usage = None;
+        self.before = ResourceUsage::children()?;
+        Ok(())
+    }
+
+    fn post_exec(
+        &mut self,
+        _state: &mut S,
+        _input: &S::Input,
+        _exit_kind: &ExitKind,
+    ) -> Result<(), Error> {
+        self.last_usage = Some(ResourceUsage::children()?.since(&self.before));
+        Ok(())
+    }
+}
+
+impl Named for RusageObserver {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::process::Command;
+
+    use nix::{sys::wait::WaitStatus, unistd::Pid};
+    use serial_test::serial;
+
+    use crate::{
+        executors::ExitKind,
+        inputs::NopInput,
+        observers::{
+            rusage::{wait_child, ResourceUsage, RusageObserver},
+            Observer,
+        },
+        state::NopState,
+    };
+
+    #[test]
+    fn test_children_usage() {
+        let before = ResourceUsage::children().unwrap();
+        Command::new("true").status().unwrap();
+        let after = ResourceUsage::children().unwrap();
+        assert!(after.max_rss_kb > 0);
+        assert!(after.minor_faults >= before.minor_faults);
+    }
+
+    #[test]
+    fn test_usage_since() {
+        let earlier = ResourceUsage {
+            user_time: Duration::from_millis(10),
+            max_rss_kb: 1000,
+            ..ResourceUsage::default()
+        };
+        // The peak of the children did not grow, the size of the run is unknown
+        let same_peak = ResourceUsage {
+            user_time: Duration::from_millis(15),
+            ..earlier
+        };
+        let usage = same_peak.since(&earlier);
+        assert_eq!(usage.user_time, Duration::from_millis(5));
+        assert_eq!(usage.max_rss_kb, 0);
+
+        let larger = ResourceUsage {
+            max_rss_kb: 2000,
+            ..earlier
+        };
+        assert_eq!(larger.since(&earlier).max_rss_kb, 2000);
+    }
+
+    #[test]
+    #[serial]
+    fn test_observer_wait_child() {
+        let mut observer = RusageObserver::new("rusage");
+        let mut state = NopState::<NopInput>::new();
+        // Each run is measured on its own, not only the ones exceeding all runs before
+        for _ in 0..2 {
+            observer.pre_exec(&mut state, &NopInput {}).unwrap();
+            let child = Command::new("true").spawn().unwrap();
+            let pid = Pid::from_raw(child.id().try_into().unwrap());
+            assert_eq!(wait_child(pid).unwrap(), WaitStatus::Exited(pid, 0));
+            observer
+                .post_exec(&mut state, &NopInput {}, &ExitKind::Ok)
+                .unwrap();
+
+            let usage = observer.last_usage().unwrap();
+            assert_eq!(usage.forks, 1);
+            assert!(usage.max_rss_kb > 0);
+        }
+    }
+}
@@ -108,7 +108,7 @@ where
         exit_kind: &ExitKind,
     ) -> Result<(), Error> {
         if self.harness_type == HarnessType::InProcess {
-            if exit_kind == &ExitKind::Crash {
+            if exit_kind.is_crash() {
                 self.update_hash(collect_backtrace());
             } else {
                 self.clear_hash();
@@ -124,7 +124,7 @@ where
         exit_kind: &ExitKind,
     ) -> Result<(), Error> {
         if self.harness_type == HarnessType::Child {
-            if exit_kind == &ExitKind::Crash {
+            if exit_kind.is_crash() {
                 self.update_hash(collect_backtrace());
             } else {
                 self.clear_hash();
@@ -1,12 +1,27 @@
 //! The [`StdOutObserver`] and [`StdErrObserver`] observers look at the stdout of a program
 //! The executor must explicitly support these observers.
 //! For example, they are supported on the [`crate::executors::CommandExecutor`].
+//! Both keep a hash of the captured output, so a [`crate::feedbacks::NewHashFeedback`]
+//! can look for outputs never seen before.
 
 use alloc::string::String;
+use core::hash::Hasher;
 
+use ahash::AHasher;
 use serde::{Deserialize, Serialize};
 
-use crate::{bolts::tuples::Named, inputs::UsesInput, observers::Observer};
+use crate::{
+    bolts::tuples::Named,
+    inputs::UsesInput,
+    observers::{Observer, ObserverWithHashField},
+};
+
+/// The hash of a captured output
+fn output_hash(output: &str) -> u64 {
+    let mut hasher = AHasher::new_with_keys(0, 0);
+    hasher.write(output.as_bytes());
+    hasher.finish()
+}
 
 /// An observer that captures stdout of a target.
 /// Only works for supported executors.
@@ -16,6 +31,7 @@ pub struct StdOutObserver {
     pub name: String,
     /// The stdout of the target during its last execution.
     pub stdout: Option<String>,
+    hash: Option<u64>,
 }
 
 /// An observer that captures stdout of a target.
@@ -23,7 +39,11 @@ impl StdOutObserver {
     /// Create a new [`StdOutObserver`] with the given name.
     #[must_use]
     pub fn new(name: String) -> Self {
-        Self { name, stdout: None }
+        Self {
+            name,
+            stdout: None,
+            hash: None,
+        }
     }
 }
 
@@ -39,6 +59,22 @@ where
     /// React to new `stdout`
     fn observe_stdout(&mut self, stdout: &str) {
         self.stdout = Some(stdout.into());
+        self.hash = Some(output_hash(stdout));
+    }
+}
+
+impl ObserverWithHashField for StdOutObserver {
+    /// The hash of the last stdout
+    fn hash(&self) -> &Option<u64> {
+        &self.hash
+    }
+
+    fn update_hash(&mut self, hash: u64) {
+        self.hash = Some(hash);
+    }
+
+    fn clear_hash(&mut self) {
+        self.hash = None;
     }
 }
 
@@ -56,6 +92,7 @@ pub struct StdErrObserver {
     pub name: String,
     /// The stderr of the target during its last execution.
     pub stderr: Option<String>,
+    hash: Option<u64>,
 }
 
 /// An observer that captures stderr of a target.
@@ -63,7 +100,11 @@ impl StdErrObserver {
     /// Create a new [`StdErrObserver`] with the given name.
     #[must_use]
     pub fn new(name: String) -> Self {
-        Self { name, stderr: None }
+        Self {
+            name,
+            stderr: None,
+            hash: None,
+        }
     }
 }
 
@@ -79,6 +120,22 @@ where
     /// React to new `stderr`
     fn observe_stderr(&mut self, stderr: &str) {
         self.stderr = Some(stderr.into());
+        self.hash = Some(output_hash(stderr));
+    }
+}
+
+impl ObserverWithHashField for StdErrObserver {
+    /// The hash of the last stderr
+    fn hash(&self) -> &Option<u64> {
+        &self.hash
+    }
+
+    fn update_hash(&mut self, hash: u64) {
+        self.hash = Some(hash);
+    }
+
+    fn clear_hash(&mut self) {
+        self.hash = None;
     }
 }
 
@@ -142,7 +142,29 @@ where
         idx: usize,
         testcase: &Option<Testcase<<Self::State as UsesInput>::Input>>,
     ) -> Result<(), Error> {
-        self.inner.on_remove(state, idx, testcase)
+        self.inner.on_remove(state, idx, testcase)?;
+        if let Some(top_acc) = state.metadata_mut().get_mut::<TopAccountingMetadata>() {
+            let TopAccountingMetadata {
+                map,
+                changed,
+                max_accounting,
+            } = top_acc;
+            map.retain(|entry, other_idx| {
+                if *other_idx == idx {
+                    // The entry is free to be claimed by the next testcase hitting it
+                    max_accounting[*entry] = 0;
+                    *changed = true;
+                    false
+                } else {
+                    true
+                }
+            });
+            // The following testcases moved down by one in the corpus
+            map.values_mut()
+                .filter(|other_idx| **other_idx > idx)
+                .for_each(|other_idx| *other_idx -= 1);
+        }
+        Ok(())
     }
 
     fn next(&self, state: &mut Self::State) -> Result<usize, Error> {
@@ -323,3 +345,58 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::MapIndexesMetadata,
+        inputs::BytesInput,
+        schedulers::{
+            accounting::{CoverageAccountingScheduler, TopAccountingMetadata},
+            QueueScheduler, Scheduler,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_accounting_remove() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut (),
+            &mut (),
+        )
+        .unwrap();
+
+        // Each testcase hits other memory operations in the accounting map
+        for accounting_map in [[1_u32, 0, 0], [0, 2, 0], [0, 0, 3]] {
+            let scheduler = CoverageAccountingScheduler::new(
+                &mut state,
+                QueueScheduler::new(),
+                &accounting_map,
+            );
+            let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+            testcase.set_exec_time(Duration::from_millis(1));
+            testcase.add_metadata(MapIndexesMetadata::new(vec![]));
+            let idx = state.corpus_mut().add(testcase).unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+
+        let accounting_map = [0_u32; 3];
+        let scheduler =
+            CoverageAccountingScheduler::new(&mut state, QueueScheduler::new(), &accounting_map);
+        let testcase = state.corpus_mut().remove(1).unwrap();
+        scheduler.on_remove(&mut state, 1, &testcase).unwrap();
+
+        let top_acc = state.metadata().get::<TopAccountingMetadata>().unwrap();
+        assert_eq!(top_acc.map.get(&0), Some(&0));
+        assert_eq!(top_acc.map.get(&1), None);
+        assert_eq!(top_acc.map.get(&2), Some(&1));
+        assert_eq!(top_acc.max_accounting, vec![1, 0, 3]);
+    }
+}
@@ -0,0 +1,327 @@
+//! The aging scheduler retires [`Testcase`]s that stopped producing finds to an archive,
+//! which is only revisited once the whole campaign plateaus.
+//! This keeps iteration over the active queue fast in long-running campaigns.
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, Testcase},
+    inputs::UsesInput,
+    schedulers::{minimizer::IsFavoredMetadata, Scheduler},
+    state::{HasCorpus, HasMetadata, UsesState},
+    Error,
+};
+
+/// Default number of times a [`Testcase`] may get scheduled without a find, before it can be retired
+pub const DEFAULT_AGING_MAX_ROUNDS: usize = 64;
+
+/// Default number of scheduled rounds without any find, after which the campaign is considered plateaued
+pub const DEFAULT_AGING_PLATEAU_ROUNDS: usize = 4096;
+
+/// A testcase metadata counting the rounds this testcase got scheduled since its last find
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AgingTestcaseMetadata {
+    /// The number of rounds since this testcase last produced a new corpus entry
+    pub rounds_without_find: usize,
+}
+
+crate::impl_serdeany!(AgingTestcaseMetadata);
+
+/// A state metadata holding the archive of retired testcases
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct AgingMetadata {
+    /// The (sorted) corpus indices of the retired testcases
+    pub archive: Vec<usize>,
+    /// The number of scheduled rounds since any testcase produced a new corpus entry
+    pub rounds_since_find: usize,
+    /// The next archive entry to revisit
+    archive_pos: usize,
+    /// The number of archive entries revisited during the current plateau
+    plateau_visits: usize,
+}
+
+crate::impl_serdeany!(AgingMetadata);
+
+impl AgingMetadata {
+    /// Creates a new [`struct@AgingMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns `true` if the testcase at `idx` is retired
+    #[must_use]
+    pub fn is_archived(&self, idx: usize) -> bool {
+        self.archive.binary_search(&idx).is_ok()
+    }
+
+    fn archive(&mut self, idx: usize) {
+        if let Err(pos) = self.archive.binary_search(&idx) {
+            self.archive.insert(pos, idx);
+        }
+    }
+
+    fn restore(&mut self, idx: usize) {
+        if let Ok(pos) = self.archive.binary_search(&idx) {
+            self.archive.remove(pos);
+        }
+    }
+}
+
+/// The [`AgingScheduler`] wraps a `base` [`Scheduler`] and retires testcases that were scheduled
+/// more than `max_rounds` times without producing a new corpus entry, and that are dominated
+/// by other testcases in coverage (i.e. not marked with [`IsFavoredMetadata`]).
+///
+/// Retired testcases stay in the corpus, but are skipped, until no testcase produced a find
+/// for `plateau_rounds`. Then, the archive is revisited, and testcases that produce a find get restored.
+/// After one pass over the archive, the active testcases get scheduled again for another `plateau_rounds`.
+///
+/// To only retire coverage-dominated testcases, use it on top of a [`super::MinimizerScheduler`],
+/// else every testcase is considered dominated.
+#[derive(Debug, Clone)]
+pub struct AgingScheduler<CS> {
+    base: CS,
+    max_rounds: usize,
+    plateau_rounds: usize,
+}
+
+impl<CS> UsesState for AgingScheduler<CS>
+where
+    CS: UsesState,
+{
+    type State = CS::State;
+}
+
+impl<CS> Scheduler for AgingScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata,
+{
+    /// Add an entry to the corpus, and credit the find to the currently scheduled testcase
+    fn on_add(&self, state: &mut CS::State, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+
+        let parent = *state.corpus().current();
+        if let Some(parent) = parent {
+            if let Some(meta) = state
+                .corpus()
+                .get(parent)?
+                .borrow_mut()
+                .metadata_mut()
+                .get_mut::<AgingTestcaseMetadata>()
+            {
+                meta.rounds_without_find = 0;
+            }
+        }
+
+        let meta = self.aging_metadata_mut(state);
+        meta.rounds_since_find = 0;
+        if let Some(parent) = parent {
+            meta.restore(parent);
+        }
+        Ok(())
+    }
+
+    /// Replaces the testcase at the given idx
+    fn on_replace(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Testcase<<CS::State as UsesInput>::Input>,
+    ) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)
+    }
+
+    /// Removes an entry from the corpus, and from the archive
+    fn on_remove(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Option<Testcase<<CS::State as UsesInput>::Input>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)?;
+        let meta = self.aging_metadata_mut(state);
+        meta.restore(idx);
+        for other_idx in &mut meta.archive {
+            if *other_idx > idx {
+                *other_idx -= 1;
+            }
+        }
+        Ok(())
+    }
+
+    /// Gets the next active entry, or an archived entry, if the campaign plateaued
+    fn next(&self, state: &mut CS::State) -> Result<usize, Error> {
+        let plateau_rounds = self.plateau_rounds;
+        let meta = self.aging_metadata_mut(state);
+        meta.rounds_since_find += 1;
+        if meta.rounds_since_find >= plateau_rounds && !meta.archive.is_empty() {
+            let idx = meta.archive[meta.archive_pos % meta.archive.len()];
+            meta.archive_pos = meta.archive_pos.wrapping_add(1);
+            meta.plateau_visits += 1;
+            // One pass over the archive per plateau, then give the active testcases another chance
+            if meta.plateau_visits >= meta.archive.len() {
+                meta.rounds_since_find = 0;
+                meta.plateau_visits = 0;
+            }
+            *state.corpus_mut().current_mut() = Some(idx);
+            return Ok(idx);
+        }
+
+        // Try at most once per corpus entry to find an active one.
+        let mut idx = self.base.next(state)?;
+        for _ in 0..state.corpus().count() {
+            if self.aging_metadata_mut(state).is_archived(idx) {
+                idx = self.base.next(state)?;
+                continue;
+            }
+
+            let retire = {
+                let mut testcase = state.corpus().get(idx)?.borrow_mut();
+                let is_favored = testcase.has_metadata::<IsFavoredMetadata>();
+                if !testcase.has_metadata::<AgingTestcaseMetadata>() {
+                    testcase.add_metadata(AgingTestcaseMetadata::default());
+                }
+                let tc_meta = testcase
+                    .metadata_mut()
+                    .get_mut::<AgingTestcaseMetadata>()
+                    .unwrap();
+                tc_meta.rounds_without_find += 1;
+                tc_meta.rounds_without_find > self.max_rounds && !is_favored
+            };
+
+            let count = state.corpus().count();
+            let meta = self.aging_metadata_mut(state);
+            // Always keep at least one testcase active
+            if retire && meta.archive.len() + 1 < count {
+                meta.archive(idx);
+                idx = self.base.next(state)?;
+                continue;
+            }
+            break;
+        }
+        Ok(idx)
+    }
+}
+
+impl<CS> AgingScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata,
+{
+    /// Creates a new [`AgingScheduler`] that wraps a `base` [`Scheduler`],
+    /// using [`DEFAULT_AGING_MAX_ROUNDS`] and [`DEFAULT_AGING_PLATEAU_ROUNDS`].
+    pub fn new(base: CS) -> Self {
+        Self::with_rounds(base, DEFAULT_AGING_MAX_ROUNDS, DEFAULT_AGING_PLATEAU_ROUNDS)
+    }
+
+    /// Creates a new [`AgingScheduler`] that wraps a `base` [`Scheduler`].
+    /// Testcases get retired after `max_rounds` rounds without a find,
+    /// the archive gets revisited after `plateau_rounds` rounds without any find.
+    pub fn with_rounds(base: CS, max_rounds: usize, plateau_rounds: usize) -> Self {
+        Self {
+            base,
+            max_rounds,
+            plateau_rounds,
+        }
+    }
+
+    /// Get a reference to the base scheduler
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+
+    #[allow(clippy::unused_self)]
+    fn aging_metadata_mut<'a>(&self, state: &'a mut CS::State) -> &'a mut AgingMetadata {
+        if !state.has_metadata::<AgingMetadata>() {
+            state.add_metadata(AgingMetadata::new());
+        }
+        state.metadata_mut().get_mut::<AgingMetadata>().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::ConstFeedback,
+        inputs::bytes::BytesInput,
+        schedulers::{aging::AgingMetadata, AgingScheduler, QueueScheduler, Scheduler},
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_aging_scheduler() {
+        let mut corpus = InMemoryCorpus::new();
+        for i in 0..3_u8 {
+            corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap();
+        }
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let scheduler = AgingScheduler::with_rounds(QueueScheduler::new(), 1, 100);
+        for _ in 0..6 {
+            let idx = scheduler.next(&mut state).unwrap();
+            // Only entry 2 keeps producing finds.
+            if idx == 2 {
+                let new_idx = state
+                    .corpus_mut()
+                    .add(Testcase::new(BytesInput::new(vec![2])))
+                    .unwrap();
+                scheduler.on_add(&mut state, new_idx).unwrap();
+            }
+        }
+
+        let meta = state.metadata().get::<AgingMetadata>().unwrap();
+        assert!(meta.is_archived(0));
+        assert!(meta.is_archived(1));
+        assert!(!meta.is_archived(2));
+    }
+
+    #[test]
+    fn test_aging_scheduler_plateau() {
+        let mut corpus = InMemoryCorpus::new();
+        for i in 0..3_u8 {
+            corpus.add(Testcase::new(BytesInput::new(vec![i]))).unwrap();
+        }
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut meta = AgingMetadata::new();
+        meta.archive = vec![0, 1];
+        meta.rounds_since_find = 10;
+        state.add_metadata(meta);
+
+        let scheduler = AgingScheduler::with_rounds(QueueScheduler::new(), 100, 10);
+        // One pass over the archive, then back to the active testcases
+        assert_eq!(scheduler.next(&mut state).unwrap(), 0);
+        assert_eq!(scheduler.next(&mut state).unwrap(), 1);
+        assert_eq!(scheduler.next(&mut state).unwrap(), 2);
+        assert_eq!(
+            state
+                .metadata()
+                .get::<AgingMetadata>()
+                .unwrap()
+                .rounds_since_find,
+            1
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! The [`SpliceDonorsScheduler`] keeps resident copies of small corpus entries,
+//! so that crossover and splice mutators never load a donor from disk in the hot loop.
+
+use alloc::{collections::VecDeque, vec::Vec};
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    corpus::{Corpus, Testcase},
+    inputs::{HasBytesVec, UsesInput},
+    schedulers::Scheduler,
+    state::{HasCorpus, HasMetadata, UsesState},
+    Error,
+};
+
+/// Default maximum number of donors kept resident
+pub const DEFAULT_MAX_DONORS: usize = 256;
+
+/// Default maximum length of a donor kept resident
+pub const DEFAULT_MAX_DONOR_LEN: usize = 4096;
+
+/// The length bucket of a donor: bucket `i` holds lengths in `[2^i, 2^(i+1))`, bucket 0 also holds empty donors
+fn len_bucket(len: usize) -> usize {
+    (usize::BITS - len.leading_zeros()).saturating_sub(1) as usize
+}
+
+/// A state metadata holding resident copies of splice donors, bucketed by length
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpliceDonorsMetadata {
+    /// The bytes of each donor, by corpus index
+    donors: HashMap<usize, Vec<u8>>,
+    /// The corpus indices of the donors, by length bucket
+    buckets: Vec<Vec<usize>>,
+    /// The corpus indices of the donors, the oldest first
+    order: VecDeque<usize>,
+    max_donors: usize,
+    max_len: usize,
+}
+
+crate::impl_serdeany!(SpliceDonorsMetadata);
+
+impl SpliceDonorsMetadata {
+    /// Creates a new [`struct@SpliceDonorsMetadata`], holding at most `max_donors` donors of at most `max_len` bytes
+    #[must_use]
+    pub fn new(max_donors: usize, max_len: usize) -> Self {
+        Self {
+            donors: HashMap::default(),
+            buckets: vec![],
+            order: VecDeque::new(),
+            max_donors,
+            max_len,
+        }
+    }
+
+    /// The number of resident donors
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.donors.len()
+    }
+
+    /// Returns `true` if no donor is resident
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.donors.is_empty()
+    }
+
+    /// The bytes of the corpus entry at `idx`, if resident
+    #[must_use]
+    pub fn get(&self, idx: usize) -> Option<&[u8]> {
+        self.donors.get(&idx).map(Vec::as_slice)
+    }
+
+    /// The corpus index of the `n`-th donor, counting through all buckets
+    #[must_use]
+    pub fn nth(&self, mut n: usize) -> Option<usize> {
+        for bucket in &self.buckets {
+            if n < bucket.len() {
+                return Some(bucket[n]);
+            }
+            n -= bucket.len();
+        }
+        None
+    }
+
+    /// The corpus indices of the donors with a length similar to `len`, i.e., in the same power-of-two bucket.
+    /// If that bucket is empty, the donors in the nearest non-empty bucket, preferring shorter ones.
+    #[must_use]
+    pub fn bucket_for_len(&self, len: usize) -> &[usize] {
+        let bucket = len_bucket(len);
+        for distance in 0..self.buckets.len().max(bucket + 1) {
+            let shorter = bucket
+                .checked_sub(distance)
+                .and_then(|b| self.buckets.get(b));
+            let longer = self.buckets.get(bucket + distance);
+            if let Some(donors) = shorter.into_iter().chain(longer).find(|b| !b.is_empty()) {
+                return donors;
+            }
+        }
+        &[]
+    }
+
+    /// Adds the corpus entry at `idx` as donor, if it is small enough.
+    /// Once there are `max_donors` donors, the oldest one makes room for it.
+    fn insert(&mut self, idx: usize, bytes: &[u8]) {
+        if bytes.len() > self.max_len || self.max_donors == 0 {
+            return;
+        }
+        if !self.donors.contains_key(&idx) && self.donors.len() >= self.max_donors {
+            if let Some(oldest) = self.order.front().copied() {
+                self.remove(oldest);
+            }
+        }
+        let bucket = len_bucket(bytes.len());
+        if self.buckets.len() <= bucket {
+            self.buckets.resize(bucket + 1, vec![]);
+        }
+        if self.donors.insert(idx, bytes.to_vec()).is_none() {
+            self.buckets[bucket].push(idx);
+            self.order.push_back(idx);
+        }
+    }
+
+    /// Removes the donor for the corpus entry at `idx`, if any
+    fn remove(&mut self, idx: usize) {
+        if let Some(bytes) = self.donors.remove(&idx) {
+            self.buckets[len_bucket(bytes.len())].retain(|i| *i != idx);
+            self.order.retain(|i| *i != idx);
+        }
+    }
+
+    /// The corpus entry at `idx` got removed, so all following entries move down by one
+    fn shift_after_remove(&mut self, idx: usize) {
+        self.remove(idx);
+        self.donors = self
+            .donors
+            .drain()
+            .map(|(i, bytes)| (if i > idx { i - 1 } else { i }, bytes))
+            .collect();
+        for i in &mut self.order {
+            if *i > idx {
+                *i -= 1;
+            }
+        }
+        for bucket in &mut self.buckets {
+            for i in bucket.iter_mut() {
+                if *i > idx {
+                    *i -= 1;
+                }
+            }
+        }
+    }
+}
+
+/// The [`SpliceDonorsScheduler`] wraps a `base` [`Scheduler`] and keeps resident copies of
+/// the last `max_donors` corpus entries up to `max_len` bytes in the [`struct@SpliceDonorsMetadata`].
+/// If present, the crossover and splice mutators pick their donors from there, instead of loading them from the corpus.
+#[derive(Debug, Clone)]
+pub struct SpliceDonorsScheduler<CS> {
+    base: CS,
+    max_donors: usize,
+    max_len: usize,
+}
+
+impl<CS> UsesState for SpliceDonorsScheduler<CS>
+where
+    CS: UsesState,
+{
+    type State = CS::State;
+}
+
+impl<CS> Scheduler for SpliceDonorsScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata,
+    <CS::State as UsesInput>::Input: HasBytesVec,
+{
+    /// Add an entry to the corpus, and keep a copy, if it is small enough
+    fn on_add(&self, state: &mut CS::State, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+        let bytes = state
+            .corpus()
+            .get(idx)?
+            .borrow_mut()
+            .load_input()?
+            .bytes()
+            .to_vec();
+        self.donors_metadata_mut(state).insert(idx, &bytes);
+        Ok(())
+    }
+
+    /// Replaces the testcase at the given idx, and its copy
+    fn on_replace(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Testcase<<CS::State as UsesInput>::Input>,
+    ) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)?;
+        let bytes = state
+            .corpus()
+            .get(idx)?
+            .borrow_mut()
+            .load_input()?
+            .bytes()
+            .to_vec();
+        let meta = self.donors_metadata_mut(state);
+        meta.remove(idx);
+        meta.insert(idx, &bytes);
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, and its copy
+    fn on_remove(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Option<Testcase<<CS::State as UsesInput>::Input>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)?;
+        self.donors_metadata_mut(state).shift_after_remove(idx);
+        Ok(())
+    }
+
+    /// Gets the next entry from the base scheduler
+    fn next(&self, state: &mut CS::State) -> Result<usize, Error> {
+        self.base.next(state)
+    }
+}
+
+impl<CS> SpliceDonorsScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata,
+{
+    /// Creates a new [`SpliceDonorsScheduler`] that wraps a `base` [`Scheduler`],
+    /// keeping [`DEFAULT_MAX_DONORS`] donors of up to [`DEFAULT_MAX_DONOR_LEN`] bytes.
+    pub fn new(base: CS) -> Self {
+        Self::with_limits(base, DEFAULT_MAX_DONORS, DEFAULT_MAX_DONOR_LEN)
+    }
+
+    /// Creates a new [`SpliceDonorsScheduler`] that wraps a `base` [`Scheduler`],
+    /// keeping `max_donors` donors of up to `max_len` bytes.
+    pub fn with_limits(base: CS, max_donors: usize, max_len: usize) -> Self {
+        Self {
+            base,
+            max_donors,
+            max_len,
+        }
+    }
+
+    /// Get a reference to the base scheduler
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+
+    fn donors_metadata_mut<'a>(&self, state: &'a mut CS::State) -> &'a mut SpliceDonorsMetadata {
+        if !state.has_metadata::<SpliceDonorsMetadata>() {
+            state.add_metadata(SpliceDonorsMetadata::new(self.max_donors, self.max_len));
+        }
+        state
+            .metadata_mut()
+            .get_mut::<SpliceDonorsMetadata>()
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::ConstFeedback,
+        inputs::bytes::BytesInput,
+        schedulers::{
+            donors::SpliceDonorsMetadata, QueueScheduler, Scheduler, SpliceDonorsScheduler,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_splice_donors_scheduler() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let scheduler = SpliceDonorsScheduler::with_limits(QueueScheduler::new(), 8, 16);
+        for len in [3, 100, 2, 12] {
+            let idx = state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![len as u8; len])))
+                .unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+
+        let meta = state.metadata().get::<SpliceDonorsMetadata>().unwrap();
+        // The 100 bytes entry is too large
+        assert_eq!(meta.len(), 3);
+        assert!(meta.get(1).is_none());
+        assert_eq!(meta.bucket_for_len(3), [0, 2]);
+        assert_eq!(meta.bucket_for_len(15), [3]);
+
+        let removed = state.corpus_mut().remove(0).unwrap();
+        scheduler.on_remove(&mut state, 0, &removed).unwrap();
+        let meta = state.metadata().get::<SpliceDonorsMetadata>().unwrap();
+        assert_eq!(meta.len(), 2);
+        assert_eq!(meta.get(1), Some(&[2_u8; 2][..]));
+        assert_eq!(meta.bucket_for_len(12), [2]);
+        assert_eq!(meta.nth(0), Some(1));
+        // No donor of a similar length, the nearest ones
+        assert_eq!(meta.bucket_for_len(1000), [2]);
+        assert_eq!(meta.bucket_for_len(5), [1]);
+    }
+
+    #[test]
+    fn test_splice_donors_eviction() {
+        let mut meta = SpliceDonorsMetadata::new(2, 16);
+        meta.insert(0, b"a");
+        meta.insert(1, b"bb");
+        meta.insert(2, b"ccc");
+        // The oldest donor made room
+        assert_eq!(meta.len(), 2);
+        assert!(meta.get(0).is_none());
+        assert_eq!(meta.get(2), Some(&b"ccc"[..]));
+
+        meta.shift_after_remove(1);
+        meta.insert(5, b"dddd");
+        assert_eq!(meta.len(), 2);
+        assert_eq!(meta.get(1), Some(&b"ccc"[..]));
+        assert_eq!(meta.get(5), Some(&b"dddd"[..]));
+    }
+}
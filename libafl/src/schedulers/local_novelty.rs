@@ -0,0 +1,231 @@
+//! The [`LocalNoveltyScheduler`] prefers corpus entries that cover map entries no other site covered yet.
+//! The coverage of the other sites is received by the [`crate::stages::CoverageExchangeStage`].
+
+use alloc::vec::Vec;
+
+use hashbrown::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::rands::Rand,
+    corpus::{Corpus, Testcase},
+    feedbacks::MapIndexesMetadata,
+    inputs::UsesInput,
+    schedulers::Scheduler,
+    state::{HasCorpus, HasMetadata, HasRand, UsesState},
+    Error,
+};
+
+/// Default probability to skip an entry whose coverage is already known to other sites
+pub const DEFAULT_SKIP_COVERED_ELSEWHERE_PROB: u64 = 75;
+
+/// The maximum number of entries [`LocalNoveltyScheduler`] skips in a row, before it takes the last one drawn,
+/// so it does not loop forever once every entry is covered elsewhere
+pub const MAX_SKIPPED_COVERED_ELSEWHERE: usize = 32;
+
+/// Returns `true` if bit `idx` is set in `bitmap`
+fn bit_is_set(bitmap: &[u8], idx: usize) -> bool {
+    bitmap
+        .get(idx / 8)
+        .map_or(false, |byte| byte & (1 << (idx % 8)) != 0)
+}
+
+/// A state metadata holding the coverage reported by the other sites, as one bitmap per site
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RemoteCoverageMetadata {
+    sites: HashMap<u32, Vec<u8>>,
+}
+
+crate::impl_serdeany!(RemoteCoverageMetadata);
+
+impl RemoteCoverageMetadata {
+    /// Creates a new, empty [`struct@RemoteCoverageMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Merges a coverage bitmap reported by `site` into the coverage known for it so far
+    pub fn update(&mut self, site: u32, bitmap: Vec<u8>) {
+        let known = self.sites.entry(site).or_default();
+        if known.len() < bitmap.len() {
+            known.resize(bitmap.len(), 0);
+        }
+        for (known, new) in known.iter_mut().zip(bitmap) {
+            *known |= new;
+        }
+    }
+
+    /// The number of sites that reported their coverage
+    #[must_use]
+    pub fn sites(&self) -> usize {
+        self.sites.len()
+    }
+
+    /// Returns `true` if any other site covered the map entry at `idx`
+    #[must_use]
+    pub fn covered_elsewhere(&self, idx: usize) -> bool {
+        self.sites.values().any(|bitmap| bit_is_set(bitmap, idx))
+    }
+}
+
+/// The [`LocalNoveltyScheduler`] wraps a `base` [`Scheduler`] and, with a given probability,
+/// skips the entries whose [`MapIndexesMetadata`] is entirely covered by other sites,
+/// according to the [`struct@RemoteCoverageMetadata`].
+/// This biases each site toward the regions only it reached so far.
+#[derive(Debug, Clone)]
+pub struct LocalNoveltyScheduler<CS> {
+    base: CS,
+    skip_covered_elsewhere_prob: u64,
+}
+
+impl<CS> UsesState for LocalNoveltyScheduler<CS>
+where
+    CS: UsesState,
+{
+    type State = CS::State;
+}
+
+impl<CS> Scheduler for LocalNoveltyScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata + HasRand,
+{
+    /// Add an entry to the corpus
+    fn on_add(&self, state: &mut CS::State, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)
+    }
+
+    /// Replaces the testcase at the given idx
+    fn on_replace(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Testcase<<CS::State as UsesInput>::Input>,
+    ) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)
+    }
+
+    /// Removes an entry from the corpus
+    fn on_remove(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Option<Testcase<<CS::State as UsesInput>::Input>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)
+    }
+
+    /// Gets the next entry from the base scheduler, skipping the ones covered elsewhere with some probability,
+    /// at most [`MAX_SKIPPED_COVERED_ELSEWHERE`] times in a row
+    fn next(&self, state: &mut CS::State) -> Result<usize, Error> {
+        let mut idx = self.base.next(state)?;
+        for _ in 0..MAX_SKIPPED_COVERED_ELSEWHERE {
+            if self.is_locally_novel(state, idx)?
+                || state.rand_mut().below(100) >= self.skip_covered_elsewhere_prob
+            {
+                break;
+            }
+            idx = self.base.next(state)?;
+        }
+        Ok(idx)
+    }
+}
+
+impl<CS> LocalNoveltyScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata + HasRand,
+{
+    /// Creates a new [`LocalNoveltyScheduler`] that wraps a `base` [`Scheduler`],
+    /// skipping entries covered elsewhere with [`DEFAULT_SKIP_COVERED_ELSEWHERE_PROB`] percent probability.
+    pub fn new(base: CS) -> Self {
+        Self::with_skip_prob(base, DEFAULT_SKIP_COVERED_ELSEWHERE_PROB)
+    }
+
+    /// Creates a new [`LocalNoveltyScheduler`] that wraps a `base` [`Scheduler`],
+    /// skipping entries covered elsewhere with `skip_prob` percent probability.
+    pub fn with_skip_prob(base: CS, skip_prob: u64) -> Self {
+        Self {
+            base,
+            skip_covered_elsewhere_prob: skip_prob,
+        }
+    }
+
+    /// Get a reference to the base scheduler
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+
+    /// Returns `true` if the entry at `idx` covers a map entry not covered by any other site.
+    /// Entries without [`MapIndexesMetadata`] are always considered novel.
+    pub fn is_locally_novel(&self, state: &CS::State, idx: usize) -> Result<bool, Error> {
+        let remote = match state.metadata().get::<RemoteCoverageMetadata>() {
+            Some(remote) => remote,
+            None => return Ok(true),
+        };
+        let testcase = state.corpus().get(idx)?.borrow();
+        Ok(match testcase.metadata().get::<MapIndexesMetadata>() {
+            Some(meta) => meta.list.iter().any(|i| !remote.covered_elsewhere(*i)),
+            None => true,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::{ConstFeedback, MapIndexesMetadata},
+        inputs::bytes::BytesInput,
+        schedulers::{
+            local_novelty::RemoteCoverageMetadata, LocalNoveltyScheduler, QueueScheduler, Scheduler,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_local_novelty_scheduler() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let scheduler = LocalNoveltyScheduler::with_skip_prob(QueueScheduler::new(), 100);
+        for list in [vec![0, 9], vec![1, 9]] {
+            let mut testcase = Testcase::new(BytesInput::new(vec![0]));
+            testcase.add_metadata(MapIndexesMetadata::new(list));
+            let idx = state.corpus_mut().add(testcase).unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+
+        // Another site covered map entries 0 and 9, so only the second entry is novel here
+        let mut remote = RemoteCoverageMetadata::new();
+        remote.update(1, vec![0b0000_0001, 0b0000_0010]);
+        state.add_metadata(remote);
+
+        assert!(!scheduler.is_locally_novel(&state, 0).unwrap());
+        assert!(scheduler.is_locally_novel(&state, 1).unwrap());
+        for _ in 0..4 {
+            assert_eq!(scheduler.next(&mut state).unwrap(), 1);
+        }
+
+        // Coverage reported earlier is kept, so now both entries are covered elsewhere,
+        // and the scheduler still returns an entry instead of skipping forever
+        state
+            .metadata_mut()
+            .get_mut::<RemoteCoverageMetadata>()
+            .unwrap()
+            .update(1, vec![0b0000_0010]);
+        assert!(!scheduler.is_locally_novel(&state, 0).unwrap());
+        assert!(!scheduler.is_locally_novel(&state, 1).unwrap());
+        assert!(scheduler.next(&mut state).unwrap() < 2);
+    }
+}
@@ -22,6 +22,24 @@ pub use minimizer::{
 pub mod weighted;
 pub use weighted::{StdWeightedScheduler, WeightedScheduler};
 
+pub mod aging;
+pub use aging::AgingScheduler;
+
+pub mod partition;
+pub use partition::{ClientPartition, PartitionedScheduler};
+
+pub mod size_stats;
+pub use size_stats::SizeStatsScheduler;
+
+pub mod donors;
+pub use donors::SpliceDonorsScheduler;
+
+pub mod local_novelty;
+pub use local_novelty::LocalNoveltyScheduler;
+
+pub mod multi_corpus;
+pub use multi_corpus::{CorpusAllocation, MultiCorpusScheduler};
+
 pub mod powersched;
 use alloc::borrow::ToOwned;
 
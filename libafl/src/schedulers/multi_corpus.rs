@@ -0,0 +1,426 @@
+//! Two-dimensional scheduling: the [`MultiCorpusScheduler`] first picks one of several named corpora,
+//! such as the main queue, the crash exploration corpus, or the entries imported from other fuzzers,
+//! and then an entry of it.
+//!
+//! All named corpora live in the corpus of the state, each testcase is tagged with the
+//! [`struct@CorpusNameMetadata`] of the corpus it belongs to.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::rands::Rand,
+    corpus::{Corpus, Testcase},
+    inputs::UsesInput,
+    schedulers::Scheduler,
+    state::{HasCorpus, HasMetadata, HasRand, UsesState},
+    Error,
+};
+
+/// The name of the corpus of testcases not tagged otherwise
+pub const DEFAULT_CORPUS_NAME: &str = "queue";
+/// The name of the corpus of testcases imported from other fuzzers, see [`crate::stages::SyncFromDiskStage`]
+pub const IMPORTED_CORPUS_NAME: &str = "imported";
+
+/// The probability, in percent, that the bandit allocation explores a random corpus
+pub const DEFAULT_EXPLORE_PROB: u64 = 10;
+/// The number of selections after which the bandit allocation halves its counters, to focus on recent yield
+pub const DEFAULT_BANDIT_WINDOW: u64 = 1000;
+
+/// The named corpus a testcase belongs to.
+///
+/// As testcase metadata, it tags the testcase.
+/// As state metadata, it is the corpus all new testcases get added to, instead of [`DEFAULT_CORPUS_NAME`],
+/// for example while importing testcases from other fuzzers.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorpusNameMetadata {
+    /// The name of the corpus
+    pub name: String,
+}
+
+crate::impl_serdeany!(CorpusNameMetadata);
+
+impl CorpusNameMetadata {
+    /// Creates a new [`struct@CorpusNameMetadata`]
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+        }
+    }
+}
+
+/// The statistics of a named corpus
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct NamedCorpusStats {
+    /// The number of testcases in this corpus
+    pub entries: usize,
+    /// How often this corpus got picked, recently
+    pub selections: u64,
+    /// How many new testcases got found fuzzing entries of this corpus, recently
+    pub finds: u64,
+    /// The (sorted) corpus indices of the testcases in this corpus
+    indices: Vec<usize>,
+}
+
+impl NamedCorpusStats {
+    /// The (sorted) corpus indices of the testcases in this corpus
+    #[must_use]
+    pub fn indices(&self) -> &[usize] {
+        &self.indices
+    }
+}
+
+/// The state metadata of the [`MultiCorpusScheduler`], with the statistics of all named corpora
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MultiCorpusMetadata {
+    corpora: Vec<(String, NamedCorpusStats)>,
+}
+
+crate::impl_serdeany!(MultiCorpusMetadata);
+
+impl MultiCorpusMetadata {
+    /// The statistics of all named corpora seen so far
+    #[must_use]
+    pub fn corpora(&self) -> &[(String, NamedCorpusStats)] {
+        &self.corpora
+    }
+
+    /// The statistics of the named corpus `name`, if it has been seen
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&NamedCorpusStats> {
+        self.corpora
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, stats)| stats)
+    }
+
+    fn get_or_insert(&mut self, name: &str) -> &mut NamedCorpusStats {
+        if let Some(pos) = self.corpora.iter().position(|(n, _)| n == name) {
+            return &mut self.corpora[pos].1;
+        }
+        self.corpora
+            .push((name.to_string(), NamedCorpusStats::default()));
+        &mut self.corpora.last_mut().unwrap().1
+    }
+}
+
+/// How the [`MultiCorpusScheduler`] allocates the fuzzing time across the named corpora
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CorpusAllocation {
+    /// Pick each named corpus proportionally to its ratio. Corpora without a ratio are never picked.
+    Ratios(Vec<(String, u64)>),
+    /// Pick the named corpus with the best recent yield, the finds per selection,
+    /// but explore a random one with a probability of `explore_prob` percent.
+    /// Every `window` selections, the counters get halved, so old yield fades out.
+    Bandit {
+        /// The probability, in percent, to pick a random corpus
+        explore_prob: u64,
+        /// The number of selections after which the counters get halved
+        window: u64,
+    },
+}
+
+impl Default for CorpusAllocation {
+    fn default() -> Self {
+        Self::Bandit {
+            explore_prob: DEFAULT_EXPLORE_PROB,
+            window: DEFAULT_BANDIT_WINDOW,
+        }
+    }
+}
+
+/// The [`MultiCorpusScheduler`] wraps a `base` [`Scheduler`], and allocates the fuzzing time
+/// across the named corpora according to a [`CorpusAllocation`].
+/// Within the picked corpus, the entries come from the `base` scheduler.
+/// If it picks an entry of another corpus, it is asked again, up to once per corpus entry,
+/// so its weighting still decides among the entries of the picked corpus.
+/// If none of its picks matches, the last one is fuzzed anyway.
+#[derive(Debug, Clone)]
+pub struct MultiCorpusScheduler<CS> {
+    base: CS,
+    allocation: CorpusAllocation,
+}
+
+impl<CS> UsesState for MultiCorpusScheduler<CS>
+where
+    CS: UsesState,
+{
+    type State = CS::State;
+}
+
+impl<CS> Scheduler for MultiCorpusScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata + HasRand,
+{
+    /// Add an entry to the corpus, tag it, and credit the corpus of its parent with the find
+    fn on_add(&self, state: &mut CS::State, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+
+        let tagged = state
+            .corpus()
+            .get(idx)?
+            .borrow()
+            .metadata()
+            .get::<CorpusNameMetadata>()
+            .cloned();
+        let name = match (tagged, state.metadata().get::<CorpusNameMetadata>()) {
+            (Some(tagged), _) => tagged,
+            (None, Some(target)) => target.clone(),
+            (None, None) => {
+                // A find of the fuzzer
+                if let Some(parent) = *state.corpus().current() {
+                    let parent_name = Self::corpus_name(state, parent)?;
+                    Self::stats_mut(state).get_or_insert(&parent_name).finds += 1;
+                }
+                CorpusNameMetadata::new(DEFAULT_CORPUS_NAME)
+            }
+        };
+        let corpus_stats = Self::stats_mut(state).get_or_insert(&name.name);
+        if let Err(pos) = corpus_stats.indices.binary_search(&idx) {
+            corpus_stats.indices.insert(pos, idx);
+        }
+        corpus_stats.entries = corpus_stats.indices.len();
+        state.corpus().get(idx)?.borrow_mut().add_metadata(name);
+        Ok(())
+    }
+
+    /// Replaces the testcase at the given idx
+    fn on_replace(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Testcase<<CS::State as UsesInput>::Input>,
+    ) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)?;
+
+        // Keep the replacement in the corpus of the replaced testcase
+        if let Some(name) = testcase.metadata().get::<CorpusNameMetadata>() {
+            state
+                .corpus()
+                .get(idx)?
+                .borrow_mut()
+                .add_metadata(name.clone());
+        }
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, and from the index of its named corpus
+    fn on_remove(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Option<Testcase<<CS::State as UsesInput>::Input>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)?;
+
+        for (_, corpus_stats) in &mut Self::stats_mut(state).corpora {
+            if let Ok(pos) = corpus_stats.indices.binary_search(&idx) {
+                corpus_stats.indices.remove(pos);
+            }
+            for other_idx in &mut corpus_stats.indices {
+                if *other_idx > idx {
+                    *other_idx -= 1;
+                }
+            }
+            corpus_stats.entries = corpus_stats.indices.len();
+        }
+        Ok(())
+    }
+
+    /// Picks a named corpus, and gets the next entry of it
+    fn next(&self, state: &mut CS::State) -> Result<usize, Error> {
+        let Some(name) = self.choose(state) else {
+            return self.base.next(state);
+        };
+
+        let mut idx = self.base.next(state)?;
+        let mut retries = state.corpus().count();
+        while retries > 0 && Self::corpus_name(state, idx)? != name {
+            // The base scheduler picked an entry of another corpus, ask it again
+            idx = self.base.next(state)?;
+            retries -= 1;
+        }
+
+        // Credit the corpus of the entry we actually return
+        let name = Self::corpus_name(state, idx)?;
+        Self::stats_mut(state).get_or_insert(&name).selections += 1;
+        Ok(idx)
+    }
+}
+
+impl<CS> MultiCorpusScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata + HasRand,
+{
+    /// Creates a new [`MultiCorpusScheduler`] that wraps a `base` [`Scheduler`],
+    /// with the default bandit [`CorpusAllocation`]
+    pub fn new(base: CS) -> Self {
+        Self::with_allocation(base, CorpusAllocation::default())
+    }
+
+    /// Creates a new [`MultiCorpusScheduler`] that wraps a `base` [`Scheduler`], with the given [`CorpusAllocation`]
+    pub fn with_allocation(base: CS, allocation: CorpusAllocation) -> Self {
+        Self { base, allocation }
+    }
+
+    /// Get a reference to the base scheduler
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+
+    /// The name of the corpus of the entry at `idx`
+    fn corpus_name(state: &CS::State, idx: usize) -> Result<String, Error> {
+        Ok(state
+            .corpus()
+            .get(idx)?
+            .borrow()
+            .metadata()
+            .get::<CorpusNameMetadata>()
+            .map_or_else(|| DEFAULT_CORPUS_NAME.to_string(), |meta| meta.name.clone()))
+    }
+
+    fn stats_mut(state: &mut CS::State) -> &mut MultiCorpusMetadata {
+        if !state.has_metadata::<MultiCorpusMetadata>() {
+            state.add_metadata(MultiCorpusMetadata::default());
+        }
+        state
+            .metadata_mut()
+            .get_mut::<MultiCorpusMetadata>()
+            .unwrap()
+    }
+
+    /// Picks the named corpus to fuzz next, if there is any non-empty one to pick
+    fn choose(&self, state: &mut CS::State) -> Option<String> {
+        let candidates: Vec<(String, NamedCorpusStats)> = state
+            .metadata()
+            .get::<MultiCorpusMetadata>()?
+            .corpora
+            .iter()
+            .filter(|(_, stats)| stats.entries > 0)
+            .cloned()
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+
+        match &self.allocation {
+            CorpusAllocation::Ratios(ratios) => {
+                let weighted: Vec<(&String, u64)> = ratios
+                    .iter()
+                    .filter(|(name, _)| candidates.iter().any(|(n, _)| n == name))
+                    .map(|(name, ratio)| (name, *ratio))
+                    .collect();
+                let total: u64 = weighted.iter().map(|(_, ratio)| ratio).sum();
+                if total == 0 {
+                    return None;
+                }
+                let mut pick = state.rand_mut().below(total);
+                for (name, ratio) in weighted {
+                    if pick < ratio {
+                        return Some(name.clone());
+                    }
+                    pick -= ratio;
+                }
+                None
+            }
+            CorpusAllocation::Bandit {
+                explore_prob,
+                window,
+            } => {
+                let total_selections: u64 = candidates.iter().map(|(_, s)| s.selections).sum();
+                if total_selections >= *window {
+                    for (_, stats) in &mut Self::stats_mut(state).corpora {
+                        stats.selections /= 2;
+                        stats.finds /= 2;
+                    }
+                }
+                if state.rand_mut().below(100) < *explore_prob {
+                    let pick = state.rand_mut().below(candidates.len() as u64) as usize;
+                    return Some(candidates[pick].0.clone());
+                }
+                // Highest (finds + 1) / (selections + 1), compared without divisions
+                candidates
+                    .iter()
+                    .max_by(|(_, a), (_, b)| {
+                        ((a.finds + 1) * (b.selections + 1))
+                            .cmp(&((b.finds + 1) * (a.selections + 1)))
+                    })
+                    .map(|(name, _)| name.clone())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::ConstFeedback,
+        inputs::BytesInput,
+        schedulers::{
+            multi_corpus::{
+                CorpusAllocation, CorpusNameMetadata, MultiCorpusMetadata, MultiCorpusScheduler,
+                IMPORTED_CORPUS_NAME,
+            },
+            QueueScheduler, Scheduler,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_multi_corpus_ratios() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state: StdState<BytesInput, _, _, _> = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let scheduler = MultiCorpusScheduler::with_allocation(
+            QueueScheduler::new(),
+            CorpusAllocation::Ratios(vec![(IMPORTED_CORPUS_NAME.into(), 1)]),
+        );
+
+        for i in 0..4_u8 {
+            if i == 2 {
+                state.add_metadata(CorpusNameMetadata::new(IMPORTED_CORPUS_NAME));
+            }
+            let idx = state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![i])))
+                .unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+
+        let meta = state.metadata().get::<MultiCorpusMetadata>().unwrap();
+        assert_eq!(meta.get("queue").unwrap().entries, 2);
+        assert_eq!(meta.get(IMPORTED_CORPUS_NAME).unwrap().entries, 2);
+
+        // Only the imported corpus has a ratio, its entries still come in the order of the base scheduler
+        for i in 0..8 {
+            let idx = scheduler.next(&mut state).unwrap();
+            assert_eq!(idx, 2 + i % 2);
+            assert_eq!(*state.corpus().current(), Some(idx));
+        }
+        let meta = state.metadata().get::<MultiCorpusMetadata>().unwrap();
+        assert_eq!(meta.get("queue").unwrap().selections, 0);
+        assert_eq!(meta.get(IMPORTED_CORPUS_NAME).unwrap().selections, 8);
+
+        let testcase = state.corpus_mut().remove(0).unwrap();
+        scheduler.on_remove(&mut state, 0, &testcase).unwrap();
+        let meta = state.metadata().get::<MultiCorpusMetadata>().unwrap();
+        assert_eq!(meta.get("queue").unwrap().indices(), [0]);
+        assert_eq!(meta.get(IMPORTED_CORPUS_NAME).unwrap().indices(), [1, 2]);
+    }
+}
@@ -0,0 +1,235 @@
+//! Partitioning of the work between multiple fuzzer clients.
+//!
+//! Without partitioning, all clients of a campaign fuzz the same (synced) corpus in the same way.
+//! A [`ClientPartition`] gives each client a focus: the [`PartitionedScheduler`] prefers
+//! the corpus entries that hash into this client's shard, and [`ClientPartition::focus`]
+//! can be used to pick a different stage mix per client.
+
+use core::{
+    fmt::{self, Display},
+    hash::Hasher,
+    str::FromStr,
+};
+
+use ahash::AHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::rands::Rand,
+    corpus::{Corpus, Testcase},
+    inputs::UsesInput,
+    schedulers::Scheduler,
+    state::{HasCorpus, HasMetadata, HasRand, UsesState},
+    Error,
+};
+
+/// The `env` variable the [`crate::bolts::launcher::Launcher`] uses to pass the [`ClientPartition`] to its clients.
+pub const CLIENT_PARTITION_ENV: &str = "LIBAFL_CLIENT_PARTITION";
+
+/// Default probability to skip testcases that belong to the shard of another client
+pub const DEFAULT_SKIP_FOREIGN_PROB: u64 = 90;
+
+/// The partition, or focus, of a single client: shard `index` out of `count` shards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientPartition {
+    /// The shard of this client
+    pub index: usize,
+    /// The total number of shards, usually the number of clients
+    pub count: usize,
+}
+
+crate::impl_serdeany!(ClientPartition);
+
+impl ClientPartition {
+    /// Creates a new [`ClientPartition`] for shard `index` out of `count` shards.
+    ///
+    /// # Panics
+    /// Panics if `index` is not smaller than `count`.
+    #[must_use]
+    pub fn new(index: usize, count: usize) -> Self {
+        assert!(index < count, "Partition {index} out of {count} shards");
+        Self { index, count }
+    }
+
+    /// Reads the [`ClientPartition`] the [`crate::bolts::launcher::Launcher`] assigned to this client,
+    /// if the launcher was configured to partition its clients.
+    #[cfg(feature = "std")]
+    #[must_use]
+    pub fn from_env() -> Option<Self> {
+        std::env::var(CLIENT_PARTITION_ENV).ok()?.parse().ok()
+    }
+
+    /// Returns `true` if the given `hash` falls into the shard of this client.
+    #[must_use]
+    pub fn owns(&self, hash: u64) -> bool {
+        hash % (self.count as u64) == self.index as u64
+    }
+
+    /// Picks one out of `choices` for this client, for example a stage mix.
+    /// Clients get assigned the choices round-robin.
+    #[must_use]
+    pub fn focus(&self, choices: usize) -> usize {
+        self.index % choices
+    }
+}
+
+impl Display for ClientPartition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
+impl FromStr for ClientPartition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (index, count) = s.split_once('/').ok_or_else(|| {
+            Error::illegal_argument(format!("Expected a partition as index/count, got {s}"))
+        })?;
+        let index: usize = index.parse()?;
+        let count: usize = count.parse()?;
+        if index >= count {
+            return Err(Error::illegal_argument(format!(
+                "Partition {index} out of {count} shards"
+            )));
+        }
+        Ok(Self { index, count })
+    }
+}
+
+/// A testcase metadata holding the hash used to assign the testcase to a shard
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PartitionHashMetadata {
+    /// The hash of the input
+    pub hash: u64,
+}
+
+crate::impl_serdeany!(PartitionHashMetadata);
+
+/// The [`PartitionedScheduler`] wraps a `base` [`Scheduler`] and skips testcases
+/// that belong to the shard of another client, with a probability of `skip_foreign_prob` percent.
+///
+/// Testcases get assigned to shards by the hash of their input, so every client
+/// agrees on the shard of a synced testcase.
+#[derive(Debug, Clone)]
+pub struct PartitionedScheduler<CS> {
+    base: CS,
+    partition: ClientPartition,
+    skip_foreign_prob: u64,
+}
+
+impl<CS> UsesState for PartitionedScheduler<CS>
+where
+    CS: UsesState,
+{
+    type State = CS::State;
+}
+
+impl<CS> Scheduler for PartitionedScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata + HasRand,
+{
+    /// Add an entry to the corpus and compute its shard hash
+    fn on_add(&self, state: &mut CS::State, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+
+        let mut testcase = state.corpus().get(idx)?.borrow_mut();
+        let mut hasher = AHasher::new_with_keys(0, 0);
+        hasher.write(&postcard::to_allocvec(testcase.load_input()?)?);
+        let hash = hasher.finish();
+        testcase.add_metadata(PartitionHashMetadata { hash });
+        Ok(())
+    }
+
+    /// Replaces the testcase at the given idx
+    fn on_replace(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Testcase<<CS::State as UsesInput>::Input>,
+    ) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)
+    }
+
+    /// Removes an entry from the corpus
+    fn on_remove(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Option<Testcase<<CS::State as UsesInput>::Input>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)
+    }
+
+    /// Gets the next entry, preferring the ones in this client's shard
+    fn next(&self, state: &mut CS::State) -> Result<usize, Error> {
+        let mut idx = self.base.next(state)?;
+        // Don't starve if our shard is empty: redraw at most once per corpus entry.
+        for _ in 0..state.corpus().count() {
+            let owned = state
+                .corpus()
+                .get(idx)?
+                .borrow()
+                .metadata()
+                .get::<PartitionHashMetadata>()
+                .map_or(true, |meta| self.partition.owns(meta.hash));
+            if owned || state.rand_mut().below(100) >= self.skip_foreign_prob {
+                break;
+            }
+            idx = self.base.next(state)?;
+        }
+        Ok(idx)
+    }
+}
+
+impl<CS> PartitionedScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata + HasRand,
+{
+    /// Creates a new [`PartitionedScheduler`] that wraps a `base` [`Scheduler`]
+    /// and focuses on the testcases in the shard of the given [`ClientPartition`].
+    pub fn new(base: CS, partition: ClientPartition) -> Self {
+        Self::with_skip_prob(base, partition, DEFAULT_SKIP_FOREIGN_PROB)
+    }
+
+    /// Creates a new [`PartitionedScheduler`] that wraps a `base` [`Scheduler`]
+    /// and skips foreign testcases with the given probability, in percent.
+    pub fn with_skip_prob(base: CS, partition: ClientPartition, skip_foreign_prob: u64) -> Self {
+        Self {
+            base,
+            partition,
+            skip_foreign_prob,
+        }
+    }
+
+    /// The [`ClientPartition`] of this scheduler
+    pub fn partition(&self) -> &ClientPartition {
+        &self.partition
+    }
+
+    /// Get a reference to the base scheduler
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::string::ToString;
+
+    use crate::schedulers::partition::ClientPartition;
+
+    #[test]
+    fn test_client_partition() {
+        let partition: ClientPartition = "3/8".parse().unwrap();
+        assert_eq!(partition, ClientPartition::new(3, 8));
+        assert_eq!(partition.to_string(), "3/8");
+        assert!(partition.owns(11));
+        assert!(!partition.owns(12));
+        assert_eq!(partition.focus(2), 1);
+        assert!("8/8".parse::<ClientPartition>().is_err());
+        assert!("8".parse::<ClientPartition>().is_err());
+    }
+}
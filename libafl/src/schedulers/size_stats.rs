@@ -0,0 +1,235 @@
+//! The [`SizeStatsScheduler`] keeps track of the sizes of all inputs in the corpus,
+//! so that stages, mutators, and the monitor can see how the corpus grows.
+
+use alloc::vec::Vec;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    bolts::HasLen,
+    corpus::{Corpus, Testcase},
+    inputs::UsesInput,
+    schedulers::Scheduler,
+    state::{HasCorpus, HasMetadata, UsesState},
+    Error,
+};
+
+/// A state metadata holding the distribution of the sizes of the inputs in the corpus
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct InputSizeMetadata {
+    /// The size of each corpus entry, by corpus index
+    sizes: Vec<usize>,
+    /// The same sizes, sorted
+    sorted: Vec<usize>,
+    /// The sum of all sizes
+    total_bytes: u64,
+}
+
+crate::impl_serdeany!(InputSizeMetadata);
+
+impl InputSizeMetadata {
+    /// Creates a new, empty [`struct@InputSizeMetadata`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of inputs tracked
+    #[must_use]
+    pub fn count(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// The size of the corpus entry at `idx`, if tracked
+    #[must_use]
+    pub fn size(&self, idx: usize) -> Option<usize> {
+        self.sizes.get(idx).copied()
+    }
+
+    /// The size of the smallest input
+    #[must_use]
+    pub fn min(&self) -> Option<usize> {
+        self.sorted.first().copied()
+    }
+
+    /// The median input size
+    #[must_use]
+    pub fn median(&self) -> Option<usize> {
+        self.sorted.get(self.sorted.len() / 2).copied()
+    }
+
+    /// The size of the largest input
+    #[must_use]
+    pub fn max(&self) -> Option<usize> {
+        self.sorted.last().copied()
+    }
+
+    /// The sum of the sizes of all inputs
+    #[must_use]
+    pub fn total_bytes(&self) -> u64 {
+        self.total_bytes
+    }
+
+    /// The number of bytes the corpus may still grow by, until it reaches `budget` bytes in total.
+    /// Size-growing mutators can use this to respect a corpus-wide budget.
+    #[must_use]
+    pub fn remaining_budget(&self, budget: u64) -> u64 {
+        budget.saturating_sub(self.total_bytes)
+    }
+
+    fn insert(&mut self, idx: usize, size: usize) {
+        self.sizes.insert(idx, size);
+        let pos = self.sorted.partition_point(|s| *s < size);
+        self.sorted.insert(pos, size);
+        self.total_bytes += size as u64;
+    }
+
+    fn remove(&mut self, idx: usize) -> Option<usize> {
+        if idx >= self.sizes.len() {
+            return None;
+        }
+        let size = self.sizes.remove(idx);
+        if let Ok(pos) = self.sorted.binary_search(&size) {
+            self.sorted.remove(pos);
+        }
+        self.total_bytes -= size as u64;
+        Some(size)
+    }
+}
+
+/// The [`SizeStatsScheduler`] wraps a `base` [`Scheduler`] and updates the
+/// [`struct@InputSizeMetadata`] each time an entry gets added to, replaced in, or removed from the corpus.
+/// Use a [`crate::stages::SizeStatsStage`] to report the sizes to the monitor.
+#[derive(Debug, Clone)]
+pub struct SizeStatsScheduler<CS> {
+    base: CS,
+}
+
+impl<CS> UsesState for SizeStatsScheduler<CS>
+where
+    CS: UsesState,
+{
+    type State = CS::State;
+}
+
+impl<CS> Scheduler for SizeStatsScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata,
+    <CS::State as UsesInput>::Input: HasLen,
+{
+    /// Add an entry to the corpus, and track its size
+    fn on_add(&self, state: &mut CS::State, idx: usize) -> Result<(), Error> {
+        self.base.on_add(state, idx)?;
+        let size = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+        self.size_metadata_mut(state).insert(idx, size);
+        Ok(())
+    }
+
+    /// Replaces the testcase at the given idx, and tracks the size of the new one
+    fn on_replace(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Testcase<<CS::State as UsesInput>::Input>,
+    ) -> Result<(), Error> {
+        self.base.on_replace(state, idx, testcase)?;
+        let size = state.corpus().get(idx)?.borrow_mut().cached_len()?;
+        let meta = self.size_metadata_mut(state);
+        meta.remove(idx);
+        meta.insert(idx, size);
+        Ok(())
+    }
+
+    /// Removes an entry from the corpus, and its size
+    fn on_remove(
+        &self,
+        state: &mut CS::State,
+        idx: usize,
+        testcase: &Option<Testcase<<CS::State as UsesInput>::Input>>,
+    ) -> Result<(), Error> {
+        self.base.on_remove(state, idx, testcase)?;
+        self.size_metadata_mut(state).remove(idx);
+        Ok(())
+    }
+
+    /// Gets the next entry from the base scheduler
+    fn next(&self, state: &mut CS::State) -> Result<usize, Error> {
+        self.base.next(state)
+    }
+}
+
+impl<CS> SizeStatsScheduler<CS>
+where
+    CS: Scheduler,
+    CS::State: HasCorpus + HasMetadata,
+{
+    /// Creates a new [`SizeStatsScheduler`] that wraps a `base` [`Scheduler`]
+    pub fn new(base: CS) -> Self {
+        Self { base }
+    }
+
+    /// Get a reference to the base scheduler
+    pub fn base(&self) -> &CS {
+        &self.base
+    }
+
+    #[allow(clippy::unused_self)]
+    fn size_metadata_mut<'a>(&self, state: &'a mut CS::State) -> &'a mut InputSizeMetadata {
+        if !state.has_metadata::<InputSizeMetadata>() {
+            state.add_metadata(InputSizeMetadata::new());
+        }
+        state.metadata_mut().get_mut::<InputSizeMetadata>().unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::ConstFeedback,
+        inputs::bytes::BytesInput,
+        schedulers::{
+            size_stats::InputSizeMetadata, QueueScheduler, Scheduler, SizeStatsScheduler,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_size_stats_scheduler() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let scheduler = SizeStatsScheduler::new(QueueScheduler::new());
+        for len in [4, 1, 8] {
+            let idx = state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0; len])))
+                .unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+
+        let meta = state.metadata().get::<InputSizeMetadata>().unwrap();
+        assert_eq!(meta.min(), Some(1));
+        assert_eq!(meta.median(), Some(4));
+        assert_eq!(meta.max(), Some(8));
+        assert_eq!(meta.total_bytes(), 13);
+        assert_eq!(meta.remaining_budget(10), 0);
+
+        let removed = state.corpus_mut().remove(2).unwrap();
+        scheduler.on_remove(&mut state, 2, &removed).unwrap();
+        let meta = state.metadata().get::<InputSizeMetadata>().unwrap();
+        assert_eq!(meta.max(), Some(4));
+        assert_eq!(meta.total_bytes(), 5);
+        assert_eq!(meta.size(1), Some(1));
+    }
+}
@@ -331,9 +331,10 @@ where
                 | PowerSchedule::COE
                 | PowerSchedule::LIN
                 | PowerSchedule::QUAD => {
+                    // Entries on frequently hit paths are less interesting, as in AFL++
                     let hits = psmeta.n_fuzz()[tcmeta.n_fuzz_entry()];
                     if hits > 0 {
-                        weight *= libm::log10(f64::from(hits)) + 1.0;
+                        weight /= libm::log10(f64::from(hits)) + 1.0;
                     }
                 }
                 // EXPLORE and EXPLOIT fall into this
@@ -154,6 +154,12 @@ where
             sum += weight;
         }
 
+        // Without any usable weight, fall back to a uniform selection
+        if !(sum > 0.0 && sum.is_finite()) {
+            weights.fill(1.0);
+            sum = n as f64;
+        }
+
         for i in 0..n {
             p_arr[i] = weights[i] * (n as f64) / sum;
         }
@@ -306,13 +312,10 @@ where
                     Error::key_not_found("WeigthedScheduleMetadata not found".to_string())
                 })?;
 
-            let current_cycles = wsmeta.runs_in_current_cycle();
-
-            if current_cycles >= corpus_counts {
-                wsmeta.set_runs_current_cycle(0);
-            } else {
-                wsmeta.set_runs_current_cycle(current_cycles + 1);
-            }
+            // A whole cycle over the corpus is done with the pick of as many entries as it holds
+            let runs = wsmeta.runs_in_current_cycle() + 1;
+            let cycle_done = runs >= corpus_counts;
+            wsmeta.set_runs_current_cycle(if cycle_done { 0 } else { runs });
 
             let idx = if probability < wsmeta.alias_probability()[s] {
                 s
@@ -320,8 +323,7 @@ where
                 wsmeta.alias_table()[s]
             };
 
-            // Update depth
-            if current_cycles > corpus_counts {
+            if cycle_done {
                 let psmeta = state
                     .metadata_mut()
                     .get_mut::<SchedulerMetadata>()
@@ -353,3 +355,76 @@ where
 
 /// The standard corpus weight, same as aflpp
 pub type StdWeightedScheduler<S> = WeightedScheduler<CorpusWeightTestcaseScore<S>, S>;
+
+#[cfg(test)]
+mod tests {
+    use core::marker::PhantomData;
+
+    use crate::{
+        bolts::{rands::StdRand, HasLen},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        inputs::BytesInput,
+        schedulers::{
+            powersched::SchedulerMetadata, testcase_score::TestcaseScore, Scheduler,
+            WeightedScheduler,
+        },
+        state::{HasCorpus, HasMetadata, StdState},
+        Error,
+    };
+
+    /// Weighs testcases by their length
+    #[derive(Debug)]
+    struct LenScore<S>(PhantomData<S>);
+
+    impl<S> TestcaseScore<S> for LenScore<S>
+    where
+        S: HasCorpus + HasMetadata,
+        S::Input: HasLen,
+    {
+        #[allow(clippy::cast_precision_loss)]
+        fn compute(entry: &mut Testcase<S::Input>, _state: &S) -> Result<f64, Error> {
+            Ok(entry.cached_len()? as f64)
+        }
+    }
+
+    #[test]
+    fn test_weighted_scheduler() {
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut (),
+            &mut (),
+        )
+        .unwrap();
+        let scheduler = WeightedScheduler::<LenScore<_>, _>::new();
+        for len in [1, 3] {
+            let idx = state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![0; len])))
+                .unwrap();
+            scheduler.on_add(&mut state, idx).unwrap();
+        }
+
+        // The entries are selected proportionally to their weight
+        let mut hits = [0_usize; 2];
+        for _ in 0..4000 {
+            hits[scheduler.next(&mut state).unwrap()] += 1;
+        }
+        assert!((800..1200).contains(&hits[0]), "{hits:?}");
+
+        // A cycle over the corpus takes as many picks as there are entries
+        let queue_cycles = |state: &StdState<_, _, _, _>| {
+            state
+                .metadata()
+                .get::<SchedulerMetadata>()
+                .unwrap()
+                .queue_cycles()
+        };
+        assert_eq!(queue_cycles(&state), 2000);
+        scheduler.next(&mut state).unwrap();
+        assert_eq!(queue_cycles(&state), 2000);
+        scheduler.next(&mut state).unwrap();
+        assert_eq!(queue_cycles(&state), 2001);
+    }
+}
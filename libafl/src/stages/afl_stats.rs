@@ -0,0 +1,208 @@
+//! The [`AflStatsStage`] maintains an AFL++ compatible `fuzzer_stats` file,
+//! next to the queue and crashes of an [`crate::corpus::AflCorpus`].
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Write, marker::PhantomData, time::Duration};
+use std::{
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::{
+    bolts::{current_time, fs::write_file_atomic},
+    corpus::Corpus,
+    feedbacks::afl_entry::campaign_start_time,
+    stages::Stage,
+    state::{HasCorpus, HasExecutions, HasSolutions, HasStartTime, UsesState},
+    Error,
+};
+
+/// The name of the stats file in the AFL++ output directory
+pub const AFL_FUZZER_STATS_FILE: &str = "fuzzer_stats";
+
+/// The default interval between two updates of the `fuzzer_stats`
+pub const DEFAULT_AFL_STATS_INTERVAL: Duration = Duration::from_secs(15);
+
+/// A stage that, every `interval`, rewrites the `fuzzer_stats` file in an AFL++ output directory.
+/// The fields `LibAFL` does not track, such as the cycles and the pending entries, are always `0`.
+#[derive(Debug)]
+pub struct AflStatsStage<E, EM, Z> {
+    stats_file: PathBuf,
+    interval: Duration,
+    last_update: Duration,
+    phantom: PhantomData<(E, EM, Z)>,
+}
+
+impl<E, EM, Z> UsesState for AflStatsStage<E, EM, Z>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, EM, Z> Stage<E, EM, Z> for AflStatsStage<E, EM, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: UsesState<State = Z::State>,
+    Z: UsesState,
+    Z::State: HasCorpus + HasSolutions + HasExecutions + HasStartTime,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut Z::State,
+        _manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        campaign_start_time(state);
+        let now = current_time();
+        if now < self.last_update + self.interval {
+            return Ok(());
+        }
+        self.last_update = now;
+        write_file_atomic(&self.stats_file, Self::fuzzer_stats(state, now).as_bytes())
+    }
+}
+
+impl<E, EM, Z> AflStatsStage<E, EM, Z>
+where
+    Z: UsesState,
+    Z::State: HasCorpus + HasSolutions + HasExecutions + HasStartTime,
+{
+    /// Creates a new [`AflStatsStage`], updating the `fuzzer_stats` in the AFL++ output directory `out_dir`
+    /// every [`DEFAULT_AFL_STATS_INTERVAL`]
+    #[must_use]
+    pub fn new<P>(out_dir: P) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self::with_interval(out_dir, DEFAULT_AFL_STATS_INTERVAL)
+    }
+
+    /// Creates a new [`AflStatsStage`], updating the `fuzzer_stats` in the AFL++ output directory `out_dir`
+    /// every `interval`
+    #[must_use]
+    pub fn with_interval<P>(out_dir: P, interval: Duration) -> Self
+    where
+        P: AsRef<Path>,
+    {
+        Self {
+            stats_file: out_dir.as_ref().join(AFL_FUZZER_STATS_FILE),
+            interval,
+            last_update: Duration::ZERO,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The content of the `fuzzer_stats` file for `state`, at time `now`
+    #[allow(clippy::cast_precision_loss)]
+    fn fuzzer_stats(state: &Z::State, now: Duration) -> String {
+        let start_time = *state.start_time();
+        let run_time = now.saturating_sub(start_time);
+        let execs = *state.executions();
+        let execs_per_sec = if run_time.is_zero() {
+            0.0
+        } else {
+            execs as f64 / run_time.as_secs_f64()
+        };
+
+        let fields: Vec<(&str, String)> = vec![
+            ("start_time", start_time.as_secs().to_string()),
+            ("last_update", now.as_secs().to_string()),
+            ("run_time", run_time.as_secs().to_string()),
+            ("fuzzer_pid", process::id().to_string()),
+            ("cycles_done", "0".into()),
+            ("execs_done", execs.to_string()),
+            ("execs_per_sec", format!("{execs_per_sec:.2}")),
+            ("corpus_count", state.corpus().count().to_string()),
+            ("pending_favs", "0".into()),
+            ("pending_total", "0".into()),
+            ("saved_crashes", state.solutions().count().to_string()),
+            ("saved_hangs", "0".into()),
+            ("afl_banner", "libafl".into()),
+            ("afl_version", env!("CARGO_PKG_VERSION").into()),
+        ];
+        let mut content = String::new();
+        for (name, value) in fields {
+            writeln!(content, "{name:<18}: {value}").unwrap();
+        }
+        content
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::time::Duration;
+    use std::{env, process};
+
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        feedbacks::ConstFeedback,
+        fuzzer::StdFuzzer,
+        inputs::BytesInput,
+        schedulers::QueueScheduler,
+        stages::afl_stats::{AflStatsStage, AFL_FUZZER_STATS_FILE},
+        state::{HasCorpus, HasExecutions, HasSolutions, HasStartTime, StdState},
+    };
+
+    type TestState =
+        StdState<BytesInput, InMemoryCorpus<BytesInput>, StdRand, InMemoryCorpus<BytesInput>>;
+    type TestFuzzer = StdFuzzer<QueueScheduler<TestState>, ConstFeedback, ConstFeedback, ()>;
+
+    #[test]
+    fn test_afl_fuzzer_stats() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state: TestState = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        for input in [&b"a"[..], b"b"] {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(input.to_vec())))
+                .unwrap();
+        }
+        state
+            .solutions_mut()
+            .add(Testcase::new(BytesInput::new(b"crash".to_vec())))
+            .unwrap();
+        *state.executions_mut() = 1000;
+        *state.start_time_mut() = Duration::from_secs(100);
+
+        let out_dir = env::temp_dir().join(format!("libafl_afl_stats_{}", process::id()));
+        let stage = AflStatsStage::<(), (), TestFuzzer>::new(&out_dir);
+        assert_eq!(stage.stats_file, out_dir.join(AFL_FUZZER_STATS_FILE));
+
+        let stats =
+            AflStatsStage::<(), (), TestFuzzer>::fuzzer_stats(&state, Duration::from_secs(110));
+        let field = |name: &str| {
+            stats
+                .lines()
+                .find_map(|line| {
+                    let (key, value) = line.split_once(':')?;
+                    (key.trim_end() == name).then_some(value.trim())
+                })
+                .unwrap()
+        };
+        assert_eq!(field("start_time"), "100");
+        assert_eq!(field("last_update"), "110");
+        assert_eq!(field("run_time"), "10");
+        assert_eq!(field("execs_done"), "1000");
+        assert_eq!(field("execs_per_sec"), "100.00");
+        assert_eq!(field("corpus_count"), "2");
+        assert_eq!(field("saved_crashes"), "1");
+        // AFL++ tools parse the fields at a fixed column
+        assert!(stats.lines().all(|line| line.as_bytes()[18] == b':'));
+    }
+}
@@ -121,7 +121,8 @@ where
         let mut start = current_time();
 
         let exit_kind = executor.run_target(fuzzer, state, mgr, &input)?;
-        let mut total_time = if exit_kind == ExitKind::Ok {
+        // Custom exit kinds are regular outcomes of the harness
+        let mut total_time = if matches!(exit_kind, ExitKind::Ok | ExitKind::Custom(_)) {
             current_time() - start
         } else {
             mgr.log(
@@ -162,7 +163,7 @@ where
             start = current_time();
 
             let exit_kind = executor.run_target(fuzzer, state, mgr, &input)?;
-            if exit_kind != ExitKind::Ok {
+            if !matches!(exit_kind, ExitKind::Ok | ExitKind::Custom(_)) {
                 if !has_errors {
                     mgr.log(
                         state,
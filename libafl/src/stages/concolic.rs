@@ -384,7 +384,10 @@ where
             for mutation in mutations {
                 let mut input_copy = input.to_owned();
                 for (index, new_byte) in mutation {
-                    input_copy.bytes_mut()[index] = new_byte;
+                    // The testcase may have been replaced by a shorter one since it was traced
+                    if let Some(byte) = input_copy.bytes_mut().get_mut(index) {
+                        *byte = new_byte;
+                    }
                 }
                 // Time is measured directly the `evaluate_input` function
                 let _ = fuzzer.evaluate_input(state, executor, manager, input_copy)?;
@@ -401,3 +404,11 @@ impl<Z> Default for SimpleConcolicMutationalStage<Z> {
         }
     }
 }
+
+impl<Z> SimpleConcolicMutationalStage<Z> {
+    /// Creates a new [`SimpleConcolicMutationalStage`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
@@ -0,0 +1,234 @@
+//! The [`CoverageExchangeStage`] periodically shares the coverage of this site with the other ones,
+//! in bridged multi-broker setups, so that each site can tell what is already covered elsewhere.
+
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use core::{fmt::Debug, marker::PhantomData, time::Duration};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    bolts::{current_time, tuples::Named},
+    events::{CustomBufEventResult, Event, EventFirer},
+    feedbacks::{MapFeedbackMetadata, MAPFEEDBACK_PREFIX},
+    schedulers::local_novelty::RemoteCoverageMetadata,
+    stages::Stage,
+    state::{HasMetadata, HasNamedMetadata, UsesState},
+    Error,
+};
+
+/// The tag of the [`Event::CustomBuf`] carrying a [`CoverageSummary`]
+pub const COVERAGE_EXCHANGE_TAG: &str = "libafl_coverage_exchange";
+
+/// The default interval between two coverage exchanges
+pub const DEFAULT_COVERAGE_EXCHANGE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// The coverage of a site, as a bitmap with one bit per map entry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverageSummary {
+    /// The site this coverage belongs to
+    pub site: u32,
+    /// The name of the map observer
+    pub map_name: String,
+    /// Bit `i` is set if map entry `i` got covered
+    pub bitmap: Vec<u8>,
+}
+
+impl CoverageSummary {
+    /// Creates a new [`CoverageSummary`] from the history map of a map feedback,
+    /// treating all entries different from the default as covered
+    #[must_use]
+    pub fn from_history_map<T>(site: u32, map_name: &str, history_map: &[T]) -> Self
+    where
+        T: Default + PartialEq,
+    {
+        let mut bitmap = vec![0_u8; (history_map.len() + 7) / 8];
+        for (i, entry) in history_map.iter().enumerate() {
+            if *entry != T::default() {
+                bitmap[i / 8] |= 1 << (i % 8);
+            }
+        }
+        Self {
+            site,
+            map_name: map_name.to_string(),
+            bitmap,
+        }
+    }
+}
+
+/// Creates a handler for [`crate::events::HasCustomBufHandlers::add_custom_buf_handler`] that
+/// stores the [`CoverageSummary`]s of the other sites for the map `map_name` in the [`struct@RemoteCoverageMetadata`].
+/// Summaries sent by `site` itself are ignored.
+pub fn coverage_exchange_handler<S>(
+    site: u32,
+    map_name: &str,
+) -> impl FnMut(&mut S, &String, &[u8]) -> Result<CustomBufEventResult, Error>
+where
+    S: HasMetadata,
+{
+    let map_name = map_name.to_string();
+    move |state: &mut S, tag: &String, buf: &[u8]| {
+        if tag != COVERAGE_EXCHANGE_TAG {
+            return Ok(CustomBufEventResult::Next);
+        }
+        let summary: CoverageSummary = postcard::from_bytes(buf)?;
+        if summary.map_name != map_name {
+            // Another handler may be interested in this map
+            return Ok(CustomBufEventResult::Next);
+        }
+        if summary.site != site {
+            if !state.has_metadata::<RemoteCoverageMetadata>() {
+                state.add_metadata(RemoteCoverageMetadata::new());
+            }
+            state
+                .metadata_mut()
+                .get_mut::<RemoteCoverageMetadata>()
+                .unwrap()
+                .update(summary.site, summary.bitmap);
+        }
+        Ok(CustomBufEventResult::Handled)
+    }
+}
+
+/// A stage that, every `interval`, sends the coverage of the map feedback for the map observer `map_name`
+/// as [`CoverageSummary`] to the other sites.
+/// The receiving sites handle it with the [`coverage_exchange_handler`], and can then prefer their
+/// locally-novel entries with a [`crate::schedulers::LocalNoveltyScheduler`].
+#[derive(Debug)]
+pub struct CoverageExchangeStage<E, EM, T, Z> {
+    site: u32,
+    map_name: String,
+    interval: Duration,
+    last_sent: Duration,
+    phantom: PhantomData<(E, EM, T, Z)>,
+}
+
+impl<E, EM, T, Z> UsesState for CoverageExchangeStage<E, EM, T, Z>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, EM, T, Z> Stage<E, EM, Z> for CoverageExchangeStage<E, EM, T, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: EventFirer<State = Z::State>,
+    T: Default + Copy + PartialEq + Serialize + DeserializeOwned + Debug + 'static,
+    Z: UsesState,
+    Z::State: HasNamedMetadata,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let now = current_time();
+        if now < self.last_sent + self.interval {
+            return Ok(());
+        }
+        self.last_sent = now;
+
+        let feedback_name = MAPFEEDBACK_PREFIX.to_string() + &self.map_name;
+        let summary = match state
+            .named_metadata()
+            .get::<MapFeedbackMetadata<T>>(&feedback_name)
+        {
+            Some(meta) => {
+                CoverageSummary::from_history_map(self.site, &self.map_name, &meta.history_map)
+            }
+            None => return Ok(()),
+        };
+        manager.fire(
+            state,
+            Event::CustomBuf {
+                buf: postcard::to_allocvec(&summary)?,
+                tag: COVERAGE_EXCHANGE_TAG.to_string(),
+            },
+        )
+    }
+}
+
+impl<E, EM, T, Z> CoverageExchangeStage<E, EM, T, Z> {
+    /// Creates a new [`CoverageExchangeStage`] for the given map observer,
+    /// sending the coverage of `site` every [`DEFAULT_COVERAGE_EXCHANGE_INTERVAL`]
+    #[must_use]
+    pub fn new<O>(site: u32, map_observer: &O) -> Self
+    where
+        O: Named,
+    {
+        Self::with_interval(site, map_observer, DEFAULT_COVERAGE_EXCHANGE_INTERVAL)
+    }
+
+    /// Creates a new [`CoverageExchangeStage`] for the given map observer,
+    /// sending the coverage of `site` every `interval`
+    #[must_use]
+    pub fn with_interval<O>(site: u32, map_observer: &O, interval: Duration) -> Self
+    where
+        O: Named,
+    {
+        Self {
+            site,
+            map_name: map_observer.name().to_string(),
+            interval,
+            last_sent: Duration::ZERO,
+            phantom: PhantomData,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        bolts::rands::StdRand,
+        corpus::InMemoryCorpus,
+        events::CustomBufEventResult,
+        feedbacks::ConstFeedback,
+        inputs::BytesInput,
+        schedulers::local_novelty::RemoteCoverageMetadata,
+        stages::coverage_exchange::{
+            coverage_exchange_handler, CoverageSummary, COVERAGE_EXCHANGE_TAG,
+        },
+        state::{HasMetadata, StdState},
+    };
+
+    #[test]
+    fn test_coverage_exchange_handler() {
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state: StdState<BytesInput, _, _, _> = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+
+        let summary =
+            CoverageSummary::from_history_map(2, "edges", &[0_u8, 3, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(summary.bitmap, [0b0000_0010, 0b0000_0001]);
+        let buf = postcard::to_allocvec(&summary).unwrap();
+
+        let mut handler = coverage_exchange_handler(1, "edges");
+        assert_eq!(
+            handler(&mut state, &"other".into(), &buf).unwrap(),
+            CustomBufEventResult::Next
+        );
+        assert_eq!(
+            handler(&mut state, &COVERAGE_EXCHANGE_TAG.into(), &buf).unwrap(),
+            CustomBufEventResult::Handled
+        );
+
+        let remote = state.metadata().get::<RemoteCoverageMetadata>().unwrap();
+        assert!(remote.covered_elsewhere(1));
+        assert!(remote.covered_elsewhere(8));
+        assert!(!remote.covered_elsewhere(0));
+    }
+}
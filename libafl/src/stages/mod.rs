@@ -28,7 +28,13 @@ pub mod generalization;
 pub use generalization::GeneralizationStage;
 
 pub mod owned;
-pub use owned::StagesOwnedList;
+pub use owned::{BoxedStage, StagesOwnedList};
+
+pub mod size_report;
+pub use size_report::SizeStatsStage;
+
+pub mod coverage_exchange;
+pub use coverage_exchange::CoverageExchangeStage;
 
 #[cfg(feature = "std")]
 pub mod concolic;
@@ -37,6 +43,16 @@ pub use concolic::ConcolicTracingStage;
 #[cfg(feature = "std")]
 pub use concolic::SimpleConcolicMutationalStage;
 
+#[cfg(feature = "std")]
+pub mod afl_stats;
+#[cfg(feature = "std")]
+pub use afl_stats::AflStatsStage;
+
+#[cfg(feature = "std")]
+pub mod showmap;
+#[cfg(feature = "std")]
+pub use showmap::ShowmapStage;
+
 #[cfg(feature = "std")]
 pub mod sync;
 use core::{convert::From, marker::PhantomData};
@@ -51,22 +51,32 @@ where
     ) -> Result<(), Error> {
         let num = self.iterations(state, corpus_idx)?;
 
+        // Load the parent once per stage run, instead of borrowing (and possibly loading it from disk)
+        // again for each iteration. Each iteration refills one scratch input from it, reusing its buffers,
+        // the input only gets copied if it is added to a corpus.
+        start_timer!(state);
+        let parent = state
+            .corpus()
+            .get(corpus_idx)?
+            .borrow_mut()
+            .load_input()?
+            .clone();
+        let mut input = parent.clone();
+        mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+
         for i in 0..num {
-            start_timer!(state);
-            let mut input = state
-                .corpus()
-                .get(corpus_idx)?
-                .borrow_mut()
-                .load_input()?
-                .clone();
-            mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+            if i > 0 {
+                start_timer!(state);
+                input.clone_from(&parent);
+                mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+            }
 
             start_timer!(state);
             self.mutator_mut().mutate(state, &mut input, i as i32)?;
             mark_feature_time!(state, PerfFeature::Mutate);
 
-            // Time is measured directly the `evaluate_input` function
-            let (_, corpus_idx) = fuzzer.evaluate_input(state, executor, manager, input)?;
+            // Time is measured directly the `evaluate_input_ref` function
+            let (_, corpus_idx) = fuzzer.evaluate_input_ref(state, executor, manager, &input)?;
 
             start_timer!(state);
             self.mutator_mut().post_exec(state, i as i32, corpus_idx)?;
@@ -1,9 +1,10 @@
-//! A dynamic collection of owned Stages
+//! A dynamic collection of owned Stages, to assemble the stages at runtime
 
 use alloc::{boxed::Box, vec::Vec};
 
 use crate::{
     bolts::anymap::AsAny,
+    inputs::UsesInput,
     stages::{Stage, StagesTuple},
     state::UsesState,
     Error,
@@ -18,7 +19,14 @@ where
 {
 }
 
-/// An owned list of `Observer` trait objects
+/// A boxed [`Stage`] trait object, as held by a [`StagesOwnedList`]
+pub type BoxedStage<E, EM, Z> =
+    Box<dyn Stage<E, EM, Z, State = <E as UsesState>::State, Input = <E as UsesInput>::Input>>;
+
+/// An owned list of `Stage` trait objects.
+/// Other than a tuple of stages, the list can be assembled at runtime, e.g., from the configuration of the fuzzer,
+/// to only add a tracing stage if the target has a cmp map.
+/// The stages run in the order of the list. The list is a [`Stage`] itself, so it can be part of a tuple of stages.
 #[derive(Default)]
 #[allow(missing_debug_implementations)]
 pub struct StagesOwnedList<E, EM, Z>
@@ -26,8 +34,7 @@ where
     E: UsesState,
 {
     /// The named trait objects map
-    #[allow(clippy::type_complexity)]
-    pub list: Vec<Box<dyn AnyStage<E, EM, Z, State = E::State, Input = E::Input>>>,
+    pub list: Vec<BoxedStage<E, EM, Z>>,
 }
 
 impl<E, EM, Z> StagesTuple<E, EM, E::State, Z> for StagesOwnedList<E, EM, Z>
@@ -51,14 +58,129 @@ where
     }
 }
 
+impl<E, EM, Z> UsesState for StagesOwnedList<E, EM, Z>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, EM, Z> Stage<E, EM, Z> for StagesOwnedList<E, EM, Z>
+where
+    E: UsesState,
+    EM: UsesState<State = E::State>,
+    Z: UsesState<State = E::State>,
+{
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut E::State,
+        manager: &mut EM,
+        corpus_idx: usize,
+    ) -> Result<(), Error> {
+        self.perform_all(fuzzer, executor, state, manager, corpus_idx)
+    }
+}
+
 impl<E, EM, Z> StagesOwnedList<E, EM, Z>
 where
     E: UsesState,
 {
     /// Create a new instance
     #[must_use]
-    #[allow(clippy::type_complexity)]
-    pub fn new(list: Vec<Box<dyn AnyStage<E, EM, Z, Input = E::Input, State = E::State>>>) -> Self {
+    pub fn new(list: Vec<BoxedStage<E, EM, Z>>) -> Self {
         Self { list }
     }
+
+    /// Appends a stage, to run after the stages already in this list
+    pub fn push<S>(&mut self, stage: S)
+    where
+        S: Stage<E, EM, Z, State = E::State, Input = E::Input> + 'static,
+        EM: UsesState<State = E::State>,
+        Z: UsesState<State = E::State>,
+    {
+        self.list.push(Box::new(stage));
+    }
+
+    /// The number of stages in this list
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// If this list holds no stages
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{rc::Rc, vec, vec::Vec};
+    use core::cell::RefCell;
+
+    use crate::{
+        bolts::tuples::tuple_list,
+        events::NopEventManager,
+        inputs::NopInput,
+        stages::{ClosureStage, StagesOwnedList, StagesTuple},
+        state::NopState,
+        NopFuzzer,
+    };
+
+    type State = NopState<NopInput>;
+    type Mgr = NopEventManager<State>;
+
+    /// A stage recording its `id` when performed
+    #[allow(clippy::type_complexity)]
+    fn recording_stage(
+        id: usize,
+        performed: &Rc<RefCell<Vec<usize>>>,
+    ) -> ClosureStage<
+        impl FnMut(
+            &mut NopFuzzer<NopInput>,
+            &mut Mgr,
+            &mut State,
+            &mut Mgr,
+            usize,
+        ) -> Result<(), crate::Error>,
+        Mgr,
+        Mgr,
+        NopFuzzer<NopInput>,
+    > {
+        let performed = performed.clone();
+        ClosureStage::new(
+            move |_fuzzer: &mut NopFuzzer<NopInput>,
+                  _executor: &mut Mgr,
+                  _state: &mut State,
+                  _mgr: &mut Mgr,
+                  _idx| {
+                performed.borrow_mut().push(id);
+                Ok(())
+            },
+        )
+    }
+
+    #[test]
+    fn test_stages_owned_list() {
+        let performed = Rc::new(RefCell::new(vec![]));
+
+        let mut list = StagesOwnedList::new(vec![]);
+        assert!(list.is_empty());
+        list.push(recording_stage(1, &performed));
+        list.push(recording_stage(2, &performed));
+        assert_eq!(list.len(), 2);
+
+        let mut stages = tuple_list!(recording_stage(0, &performed), list);
+        let mut fuzzer = NopFuzzer::new();
+        let mut executor = NopEventManager::new();
+        let mut state = NopState::new();
+        let mut mgr = NopEventManager::new();
+        stages
+            .perform_all(&mut fuzzer, &mut executor, &mut state, &mut mgr, 0)
+            .unwrap();
+        assert_eq!(*performed.borrow(), vec![0, 1, 2]);
+    }
 }
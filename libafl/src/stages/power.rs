@@ -3,17 +3,21 @@
 use alloc::string::{String, ToString};
 use core::{fmt::Debug, marker::PhantomData};
 
+#[cfg(feature = "introspection")]
+use crate::monitors::PerfFeature;
 use crate::{
     bolts::tuples::MatchName,
     corpus::{Corpus, SchedulerTestcaseMetaData},
     executors::{Executor, HasObservers},
     fuzzer::Evaluator,
+    mark_feature_time,
     mutators::Mutator,
     observers::MapObserver,
     schedulers::{
         powersched::SchedulerMetadata, testcase_score::CorpusPowerTestcaseScore, TestcaseScore,
     },
     stages::{MutationalStage, Stage},
+    start_timer,
     state::{HasClientPerfMonitor, HasCorpus, HasMetadata, HasRand, UsesState},
     Error,
 };
@@ -77,17 +81,30 @@ where
     ) -> Result<(), Error> {
         let num = self.iterations(state, corpus_idx)?;
 
+        // Load the parent once, see [`MutationalStage::perform_mutational`]
+        start_timer!(state);
+        let parent = state
+            .corpus()
+            .get(corpus_idx)?
+            .borrow_mut()
+            .load_input()?
+            .clone();
+        let mut input = parent.clone();
+        mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+
         for i in 0..num {
-            let mut input = state
-                .corpus()
-                .get(corpus_idx)?
-                .borrow_mut()
-                .load_input()?
-                .clone();
+            if i > 0 {
+                start_timer!(state);
+                input.clone_from(&parent);
+                mark_feature_time!(state, PerfFeature::GetInputFromCorpus);
+            }
 
+            start_timer!(state);
             self.mutator_mut().mutate(state, &mut input, i as i32)?;
+            mark_feature_time!(state, PerfFeature::Mutate);
 
-            let (_, corpus_idx) = fuzzer.evaluate_input(state, executor, manager, input)?;
+            // Time is measured directly the `evaluate_input_ref` function
+            let (_, corpus_idx) = fuzzer.evaluate_input_ref(state, executor, manager, &input)?;
 
             let observer = executor
                 .observers()
@@ -118,7 +135,9 @@ where
                     .set_n_fuzz_entry(hash);
             }
 
+            start_timer!(state);
             self.mutator_mut().post_exec(state, i as i32, corpus_idx)?;
+            mark_feature_time!(state, PerfFeature::MutatePostExec);
         }
 
         Ok(())
@@ -0,0 +1,248 @@
+//! The [`ShowmapStage`] runs the corpus entries and writes their coverage maps to files, like `afl-showmap`,
+//! to diff the coverage of corpus snapshots, or to feed it to external analyses.
+
+use alloc::string::{String, ToString};
+use core::{fmt::Write, marker::PhantomData};
+use std::path::{Path, PathBuf};
+
+use hashbrown::HashSet;
+
+use crate::{
+    bolts::{fs::write_file_atomic, tuples::MatchName},
+    corpus::Corpus,
+    executors::{Executor, HasObservers},
+    inputs::Input,
+    observers::{MapObserver, ObserversTuple},
+    stages::Stage,
+    state::{HasCorpus, UsesState},
+    Error, ExecutesInput,
+};
+
+/// The coverage map of `observer` in the `afl-showmap` format: one `index:value` line
+/// for each entry not at its initial value
+#[must_use]
+pub fn showmap<O>(observer: &O) -> String
+where
+    O: MapObserver,
+{
+    let initial = observer.initial();
+    let mut content = String::new();
+    for idx in 0..observer.usable_count() {
+        let entry = observer.get(idx);
+        if *entry != initial {
+            writeln!(content, "{idx:06}:{entry:?}").unwrap();
+        }
+    }
+    content
+}
+
+/// A stage that runs each corpus entry it did not run before, and writes the coverage map of the [`MapObserver`]
+/// to a file of the same name in `out_dir`, in the `afl-showmap` format, see [`showmap`].
+/// To dump a whole corpus once, e.g., as a standalone utility, call [`ShowmapStage::dump_corpus`] after loading it.
+#[derive(Debug)]
+pub struct ShowmapStage<E, EM, O, Z> {
+    map_observer_name: String,
+    out_dir: PathBuf,
+    /// The names of the files written so far, one per corpus entry
+    dumped: HashSet<String>,
+    phantom: PhantomData<(E, EM, O, Z)>,
+}
+
+impl<E, EM, O, Z> UsesState for ShowmapStage<E, EM, O, Z>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, EM, O, Z> Stage<E, EM, Z> for ShowmapStage<E, EM, O, Z>
+where
+    O: MapObserver,
+    E: Executor<EM, Z> + HasObservers,
+    E::Observers: ObserversTuple<E::State>,
+    E::State: HasCorpus,
+    EM: UsesState<State = E::State>,
+    Z: ExecutesInput<E, EM, State = E::State>,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut E::State,
+        manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        self.dump_corpus(fuzzer, executor, state, manager)?;
+        Ok(())
+    }
+}
+
+impl<E, EM, O, Z> ShowmapStage<E, EM, O, Z>
+where
+    O: MapObserver,
+    E: Executor<EM, Z> + HasObservers,
+    E::Observers: ObserversTuple<E::State>,
+    E::State: HasCorpus,
+    EM: UsesState<State = E::State>,
+    Z: ExecutesInput<E, EM, State = E::State>,
+{
+    /// Creates a new [`ShowmapStage`], writing the coverage of `map_observer` to `out_dir`.
+    /// Will error, if [`std::fs::create_dir_all()`] failed for `out_dir`.
+    pub fn new<P>(map_observer: &O, out_dir: P) -> Result<Self, Error>
+    where
+        P: AsRef<Path>,
+    {
+        std::fs::create_dir_all(out_dir.as_ref())?;
+        Ok(Self {
+            map_observer_name: map_observer.name().to_string(),
+            out_dir: out_dir.as_ref().to_path_buf(),
+            dumped: HashSet::new(),
+            phantom: PhantomData,
+        })
+    }
+
+    /// Runs the corpus entries not written before, and writes their coverage maps.
+    /// Entries without a file name get named like an on-disk corpus would name them, see [`Input::generate_name`].
+    /// Returns the number of entries written.
+    pub fn dump_corpus(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut E::State,
+        manager: &mut EM,
+    ) -> Result<usize, Error> {
+        // Entries may have been removed since the last call, so track them by name, not by index.
+        // Only the entries not written before get loaded, and cloned.
+        let mut written = 0;
+        for idx in 0..state.corpus().count() {
+            let (input, name) = {
+                let mut testcase = state.corpus().get(idx)?.borrow_mut();
+                if let Some(filename) = testcase.filename() {
+                    let name = Path::new(filename)
+                        .file_name()
+                        .ok_or_else(|| {
+                            Error::illegal_argument(format!(
+                                "The file of the corpus entry {filename} has no name"
+                            ))
+                        })?
+                        .to_string_lossy()
+                        .into_owned();
+                    if self.dumped.contains(&name) {
+                        continue;
+                    }
+                    (testcase.load_input()?.clone(), name)
+                } else {
+                    // Without a file, the input is in memory, and naming it needs no loading
+                    let input = testcase.input().as_ref().ok_or_else(|| {
+                        Error::empty(format!(
+                            "The corpus entry {idx} has neither an input nor a file"
+                        ))
+                    })?;
+                    let name = input.generate_name(idx);
+                    if self.dumped.contains(&name) {
+                        continue;
+                    }
+                    (input.clone(), name)
+                }
+            };
+
+            fuzzer.execute_input(state, executor, manager, &input)?;
+            let observer = executor
+                .observers()
+                .match_name::<O>(&self.map_observer_name)
+                .ok_or_else(|| Error::key_not_found("MapObserver not found".to_string()))?;
+            write_file_atomic(self.out_dir.join(&name), showmap(observer).as_bytes())?;
+            self.dumped.insert(name);
+            written += 1;
+        }
+        Ok(written)
+    }
+}
+
+#[cfg(all(test, feature = "executors"))]
+mod tests {
+    use alloc::format;
+    use std::{env, fs, process};
+
+    use crate::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::{Corpus, InMemoryCorpus, Testcase},
+        events::NopEventManager,
+        executors::{ExitKind, InProcessExecutor},
+        feedbacks::ConstFeedback,
+        inputs::{BytesInput, Input},
+        observers::{MapObserver, StdMapObserver},
+        schedulers::QueueScheduler,
+        stages::ShowmapStage,
+        state::{HasCorpus, StdState},
+        StdFuzzer,
+    };
+
+    #[test]
+    fn test_showmap() {
+        let mut observer = StdMapObserver::new_owned("map", vec![0_u8; 1024]);
+        *observer.get_mut(3) = 1;
+        *observer.get_mut(1000) = 42;
+        assert_eq!(super::showmap(&observer), "000003:1\n001000:42\n");
+
+        observer.reset_map().unwrap();
+        assert_eq!(super::showmap(&observer), "");
+    }
+
+    #[test]
+    fn test_showmap_stage_dump_corpus() {
+        let dir = env::temp_dir().join(format!("libafl_showmap_{}", process::id()));
+        let observer = StdMapObserver::new_owned("map", vec![0_u8; 16]);
+        let mut showmap_stage = ShowmapStage::new(&observer, &dir).unwrap();
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::<BytesInput>::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        for i in 0..3_u8 {
+            state
+                .corpus_mut()
+                .add(Testcase::new(BytesInput::new(vec![i])))
+                .unwrap();
+        }
+        let mut mgr = NopEventManager::new();
+        let mut fuzzer = StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+        let mut harness = |_input: &BytesInput| ExitKind::Ok;
+        let mut executor = InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(observer),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )
+        .unwrap();
+
+        let written = showmap_stage
+            .dump_corpus(&mut fuzzer, &mut executor, &mut state, &mut mgr)
+            .unwrap();
+        assert_eq!(written, 3);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 3);
+
+        // After a removal, only the new entry gets written, even though the corpus size did not change
+        state.corpus_mut().remove(0).unwrap();
+        let idx = state
+            .corpus_mut()
+            .add(Testcase::new(BytesInput::new(vec![3])))
+            .unwrap();
+        let written = showmap_stage
+            .dump_corpus(&mut fuzzer, &mut executor, &mut state, &mut mgr)
+            .unwrap();
+        assert_eq!(written, 1);
+        let name = BytesInput::new(vec![3]).generate_name(idx);
+        assert_eq!(fs::read_to_string(dir.join(name)).unwrap(), "");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,104 @@
+//! The [`SizeStatsStage`] reports the distribution of the corpus input sizes to the monitor.
+
+use alloc::{format, string::String};
+use core::marker::PhantomData;
+
+use crate::{
+    events::{Event, EventFirer},
+    monitors::UserStats,
+    schedulers::size_stats::InputSizeMetadata,
+    stages::Stage,
+    state::{HasMetadata, UsesState},
+    Error,
+};
+
+/// The default name of the user stat reported by the [`SizeStatsStage`]
+pub const SIZE_STATS_NAME: &str = "sizes";
+
+/// A stage that reports the [`struct@InputSizeMetadata`], kept up to date by a
+/// [`crate::schedulers::SizeStatsScheduler`], as user stat.
+/// To not flood the event manager, it only reports when the corpus changed.
+#[derive(Debug)]
+pub struct SizeStatsStage<E, EM, Z> {
+    name: String,
+    last_reported: Option<(usize, u64)>,
+    phantom: PhantomData<(E, EM, Z)>,
+}
+
+impl<E, EM, Z> UsesState for SizeStatsStage<E, EM, Z>
+where
+    E: UsesState,
+{
+    type State = E::State;
+}
+
+impl<E, EM, Z> Stage<E, EM, Z> for SizeStatsStage<E, EM, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: EventFirer<State = Z::State>,
+    Z: UsesState,
+    Z::State: HasMetadata,
+{
+    #[inline]
+    fn perform(
+        &mut self,
+        _fuzzer: &mut Z,
+        _executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+        _corpus_idx: usize,
+    ) -> Result<(), Error> {
+        let current = match state.metadata().get::<InputSizeMetadata>() {
+            Some(meta) => (meta.count(), meta.total_bytes()),
+            None => return Ok(()),
+        };
+        if self.last_reported == Some(current) {
+            return Ok(());
+        }
+        self.last_reported = Some(current);
+
+        let meta = state.metadata().get::<InputSizeMetadata>().unwrap();
+        let value = format!(
+            "min {}, median {}, max {}, total {}b",
+            meta.min().unwrap_or(0),
+            meta.median().unwrap_or(0),
+            meta.max().unwrap_or(0),
+            meta.total_bytes()
+        );
+        manager.fire(
+            state,
+            Event::UpdateUserStats {
+                name: self.name.clone(),
+                value: UserStats::String(value),
+                phantom: PhantomData,
+            },
+        )
+    }
+}
+
+impl<E, EM, Z> SizeStatsStage<E, EM, Z> {
+    /// Creates a new [`SizeStatsStage`], reporting as [`SIZE_STATS_NAME`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::with_name(SIZE_STATS_NAME)
+    }
+
+    /// Creates a new [`SizeStatsStage`], reporting as `name`
+    #[must_use]
+    pub fn with_name<N>(name: N) -> Self
+    where
+        N: Into<String>,
+    {
+        Self {
+            name: name.into(),
+            last_reported: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+impl<E, EM, Z> Default for SizeStatsStage<E, EM, Z> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
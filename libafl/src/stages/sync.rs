@@ -1,7 +1,8 @@
-//| The [`MutationalStage`] is the default stage used during fuzzing.
-//! For the current input, it will perform a range of random mutations, and then run them in the executor.
+//! The [`SyncFromDiskStage`] periodically imports the new testcases of other fuzzers, such as AFL++,
+//! so that `LibAFL` nodes can take part in campaigns mixing different fuzzers.
 
-use core::marker::PhantomData;
+use alloc::vec::Vec;
+use core::{marker::PhantomData, time::Duration};
 use std::{
     fs,
     path::{Path, PathBuf},
@@ -11,8 +12,11 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    bolts::current_time,
+    corpus::afl::AFL_QUEUE_DIR,
     fuzzer::Evaluator,
     inputs::{Input, UsesInput},
+    schedulers::multi_corpus::{CorpusNameMetadata, IMPORTED_CORPUS_NAME},
     stages::Stage,
     state::{HasClientPerfMonitor, HasCorpus, HasMetadata, HasRand, UsesState},
     Error,
@@ -35,11 +39,41 @@ impl SyncFromDiskMetadata {
     }
 }
 
-/// A stage that loads testcases from disk to sync with other fuzzers such as AFL++
+/// The default interval between two scans of the sync directories
+pub const DEFAULT_SYNC_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Gets the directories holding the testcases in `sync_dir`.
+/// For an AFL++ instance directory, this is its `queue/`, skipping the crashes, hangs, and internal state.
+/// For an AFL++ output directory, these are the `queue/`s of all instances in it.
+/// Any other directory is synced as a whole.
+fn queue_dirs(sync_dir: &Path) -> Result<Vec<PathBuf>, Error> {
+    let queue = sync_dir.join(AFL_QUEUE_DIR);
+    if queue.is_dir() {
+        return Ok(vec![queue]);
+    }
+    let mut queues = vec![];
+    for entry in fs::read_dir(sync_dir)? {
+        let queue = entry?.path().join(AFL_QUEUE_DIR);
+        if queue.is_dir() {
+            queues.push(queue);
+        }
+    }
+    if queues.is_empty() {
+        queues.push(sync_dir.to_path_buf());
+    }
+    Ok(queues)
+}
+
+/// A stage that loads testcases from disk to sync with other fuzzers such as AFL++.
+/// Every `interval`, it scans the sync directories for files newer than the last sync,
+/// and evaluates them, adding the interesting ones to the corpus.
+/// They are tagged as the [`IMPORTED_CORPUS_NAME`] corpus, for the [`crate::schedulers::MultiCorpusScheduler`].
 #[derive(Debug)]
 pub struct SyncFromDiskStage<CB, E, EM, Z> {
-    sync_dir: PathBuf,
+    sync_dirs: Vec<PathBuf>,
     load_callback: CB,
+    interval: Duration,
+    last_sync: Option<Duration>,
     phantom: PhantomData<(E, EM, Z)>,
 }
 
@@ -67,25 +101,20 @@ where
         manager: &mut EM,
         _corpus_idx: usize,
     ) -> Result<(), Error> {
-        let last = state
-            .metadata()
-            .get::<SyncFromDiskMetadata>()
-            .map(|m| m.last_time);
-        let path = self.sync_dir.clone();
-        if let Some(max_time) =
-            self.load_from_directory(&path, &last, fuzzer, executor, state, manager)?
-        {
-            if last.is_none() {
-                state
-                    .metadata_mut()
-                    .insert(SyncFromDiskMetadata::new(max_time));
-            } else {
-                state
-                    .metadata_mut()
-                    .get_mut::<SyncFromDiskMetadata>()
-                    .unwrap()
-                    .last_time = max_time;
+        let now = current_time();
+        if !matches!(self.last_sync, Some(last_sync) if now < last_sync + self.interval) {
+            self.last_sync = Some(now);
+            // Add the imported testcases to their own named corpus, see `MultiCorpusScheduler`
+            let previous = state.metadata_mut().remove::<CorpusNameMetadata>();
+            state.add_metadata(CorpusNameMetadata::new(IMPORTED_CORPUS_NAME));
+            let res = self.sync(fuzzer, executor, state, manager);
+            match previous {
+                Some(previous) => state.metadata_mut().insert_boxed(previous),
+                None => {
+                    let _ = state.metadata_mut().remove::<CorpusNameMetadata>();
+                }
             }
+            res?;
         }
 
         #[cfg(feature = "introspection")]
@@ -106,13 +135,67 @@ where
     /// Creates a new [`SyncFromDiskStage`]
     #[must_use]
     pub fn new(sync_dir: PathBuf, load_callback: CB) -> Self {
+        Self::with_sync_dirs(vec![sync_dir], load_callback)
+    }
+
+    /// Creates a new [`SyncFromDiskStage`] syncing from several directories,
+    /// e.g., the output directories of several AFL++ campaigns
+    #[must_use]
+    pub fn with_sync_dirs(sync_dirs: Vec<PathBuf>, load_callback: CB) -> Self {
         Self {
-            sync_dir,
+            sync_dirs,
             load_callback,
+            interval: DEFAULT_SYNC_INTERVAL,
+            last_sync: None,
             phantom: PhantomData,
         }
     }
 
+    /// Sets the interval between two scans of the sync directories, [`DEFAULT_SYNC_INTERVAL`] by default
+    #[must_use]
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Loads the testcases newer than the last sync from all sync directories
+    fn sync(
+        &mut self,
+        fuzzer: &mut Z,
+        executor: &mut E,
+        state: &mut Z::State,
+        manager: &mut EM,
+    ) -> Result<(), Error> {
+        let last = state
+            .metadata()
+            .get::<SyncFromDiskMetadata>()
+            .map(|m| m.last_time);
+        let mut max_time = None;
+        for sync_dir in self.sync_dirs.clone() {
+            for queue in queue_dirs(&sync_dir)? {
+                let dir_max_time =
+                    self.load_from_directory(&queue, &last, fuzzer, executor, state, manager)?;
+                if let Some(time) = dir_max_time {
+                    max_time = Some(max_time.map_or(time, |t: SystemTime| t.max(time)));
+                }
+            }
+        }
+        if let Some(max_time) = max_time {
+            if last.is_none() {
+                state
+                    .metadata_mut()
+                    .insert(SyncFromDiskMetadata::new(max_time));
+            } else {
+                state
+                    .metadata_mut()
+                    .get_mut::<SyncFromDiskMetadata>()
+                    .unwrap()
+                    .last_time = max_time;
+            }
+        }
+        Ok(())
+    }
+
     fn load_from_directory(
         &mut self,
         in_dir: &Path,
@@ -126,6 +209,10 @@ where
         for entry in fs::read_dir(in_dir)? {
             let entry = entry?;
             let path = entry.path();
+            // Skip hidden files, such as the `.state` directory and `.synced` markers of AFL++
+            if entry.file_name().to_string_lossy().starts_with('.') {
+                continue;
+            }
             let attributes = fs::metadata(&path);
 
             if attributes.is_err() {
@@ -179,10 +266,43 @@ where
         ) -> Result<S::Input, Error> {
             Input::from_file(p)
         }
-        Self {
-            sync_dir,
-            load_callback: load_callback::<_, _>,
-            phantom: PhantomData,
+        Self::with_sync_dirs(vec![sync_dir], load_callback::<_, _>)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process};
+
+    use crate::stages::sync::queue_dirs;
+
+    #[test]
+    fn test_afl_queue_dirs() {
+        let out = env::temp_dir().join(format!("libafl_sync_{}", process::id()));
+        for dir in [
+            "main/queue",
+            "main/crashes",
+            "secondary/queue",
+            "secondary/.state",
+        ] {
+            fs::create_dir_all(out.join(dir)).unwrap();
         }
+
+        let mut queues = queue_dirs(&out).unwrap();
+        queues.sort();
+        assert_eq!(
+            queues,
+            [out.join("main/queue"), out.join("secondary/queue")]
+        );
+        assert_eq!(
+            queue_dirs(&out.join("main")).unwrap(),
+            [out.join("main/queue")]
+        );
+        assert_eq!(
+            queue_dirs(&out.join("main/crashes")).unwrap(),
+            [out.join("main/crashes")]
+        );
+
+        fs::remove_dir_all(&out).unwrap();
     }
 }
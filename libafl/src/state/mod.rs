@@ -1,16 +1,19 @@
 //! The fuzzer, and state are the core pieces of every good fuzzer
 
-use core::{fmt::Debug, marker::PhantomData, time::Duration};
+use core::{fmt::Debug, hash::Hasher, marker::PhantomData, time::Duration};
 #[cfg(feature = "std")]
 use std::{
     fs,
     path::{Path, PathBuf},
 };
 
+use ahash::AHasher;
+use hashbrown::HashSet;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 
 use crate::{
     bolts::{
+        current_time,
         rands::Rand,
         serdeany::{NamedSerdeAnyMap, SerdeAny, SerdeAnyMap},
     },
@@ -485,9 +488,34 @@ where
         G: Generator<<Self as UsesInput>::Input, Self>,
         Z: Evaluator<E, EM, State = Self>,
     {
+        let budget = generator.budget();
+        let max_tries = num.saturating_mul(budget.tries_per_input);
+        let start_time = current_time();
+        let mut seen = HashSet::new();
         let mut added = 0;
-        for _ in 0..num {
-            let input = generator.generate(self)?;
+        let mut tries = 0;
+        while added < num && tries < max_tries {
+            if let Some(timeout) = budget.timeout {
+                if current_time() - start_time > timeout {
+                    break;
+                }
+            }
+            tries += 1;
+
+            let input = match generator.generate(self) {
+                Ok(input) => input,
+                // The generator cannot produce any more inputs.
+                Err(Error::IteratorEnd(_, _)) => break,
+                Err(e) => return Err(e),
+            };
+
+            // Skip duplicates, they will not add anything to the corpus.
+            let mut hasher = AHasher::new_with_keys(0, 0);
+            hasher.write(&postcard::to_allocvec(&input)?);
+            if !seen.insert(hasher.finish()) {
+                continue;
+            }
+
             if forced {
                 let _ = fuzzer.add_input(self, executor, manager, input)?;
                 added += 1;
@@ -502,7 +530,7 @@ where
             self,
             Event::Log {
                 severity_level: LogSeverity::Debug,
-                message: format!("Loaded {added} over {num} initial testcases"),
+                message: format!("Loaded {added} over {num} initial testcases ({tries} generated)"),
                 phantom: PhantomData,
             },
         )?;
@@ -510,6 +538,7 @@ where
     }
 
     /// Generate `num` initial inputs, using the passed-in generator and force the addition to corpus.
+    /// Duplicates are skipped, generation stops early once the [`Generator::budget`] is exhausted.
     pub fn generate_initial_inputs_forced<G, E, EM, Z>(
         &mut self,
         fuzzer: &mut Z,
@@ -528,6 +557,8 @@ where
     }
 
     /// Generate `num` initial inputs, using the passed-in generator.
+    /// Inputs are generated until `num` of them were added to the corpus,
+    /// or until the generator ran out of inputs or its [`Generator::budget`] is exhausted.
     pub fn generate_initial_inputs<G, E, EM, Z>(
         &mut self,
         fuzzer: &mut Z,
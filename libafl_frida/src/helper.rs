@@ -1,4 +1,5 @@
 use core::fmt::{self, Debug, Formatter};
+use std::path::Path;
 
 #[cfg(target_arch = "aarch64")]
 use capstone::{
@@ -149,6 +150,20 @@ pub fn get_module_size(module_name: &str) -> usize {
     code_size
 }
 
+/// Checks if the module, given by name or path, is in the list of `excluded` modules.
+/// Modules match by their full path, or by their file name.
+fn is_excluded(module: &str, excluded: &[String]) -> bool {
+    let file_name = Path::new(module)
+        .file_name()
+        .map_or(module.into(), |name| name.to_string_lossy());
+    excluded.iter().any(|excluded| {
+        excluded == module
+            || Path::new(excluded)
+                .file_name()
+                .map_or(false, |name| name.to_string_lossy() == file_name)
+    })
+}
+
 #[cfg(target_arch = "aarch64")]
 fn pc(context: &CpuContext) -> usize {
     context.pc() as usize
@@ -200,8 +215,11 @@ where
             .to_string_lossy()
             .to_string()];
         modules_to_instrument.append(&mut options.libs_to_instrument.clone());
-        let modules_to_instrument: Vec<&str> =
-            modules_to_instrument.iter().map(AsRef::as_ref).collect();
+        let modules_to_instrument: Vec<&str> = modules_to_instrument
+            .iter()
+            .map(AsRef::as_ref)
+            .filter(|module| !is_excluded(module, &options.exclude_modules))
+            .collect();
 
         let mut helper = Self {
             transformer: None,
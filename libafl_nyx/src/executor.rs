@@ -10,7 +10,7 @@ use libafl::{
 };
 use libnyx::NyxReturnValue;
 
-use crate::helper::NyxHelper;
+use crate::helper::{NyxHelper, MAX_FILE};
 
 /// executor for nyx standalone mode
 pub struct NyxExecutor<'a, S, OT> {
@@ -24,7 +24,7 @@ pub struct NyxExecutor<'a, S, OT> {
 
 impl<'a, S, OT> Debug for NyxExecutor<'a, S, OT> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("NyxInprocessExecutor")
+        f.debug_struct("NyxExecutor")
             .field("helper", &self.helper)
             .finish()
     }
@@ -61,6 +61,8 @@ where
     ) -> Result<ExitKind, Error> {
         let input_owned = input.target_bytes();
         let input = input_owned.as_slice();
+        // The payload page has a fixed size, so truncate larger inputs
+        let input = &input[..input.len().min(MAX_FILE as usize)];
         self.helper.nyx_process.set_input(input, input.len() as u32);
 
         // exec will take care of trace_bits, so no need to reset
@@ -70,7 +72,7 @@ where
             NyxReturnValue::Crash | NyxReturnValue::Asan => Ok(ExitKind::Crash),
             NyxReturnValue::Timeout => Ok(ExitKind::Timeout),
             NyxReturnValue::InvalidWriteToPayload => Err(libafl::Error::illegal_state(
-                "FixMe: Nyx InvalidWriteToPayload handler is missing",
+                "Error: the target wrote to the read-only input payload of Nyx",
             )),
             NyxReturnValue::Error => Err(libafl::Error::illegal_state(
                 "Error: Nyx runtime error has occured...",
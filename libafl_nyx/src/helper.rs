@@ -19,7 +19,8 @@ pub struct NyxHelper {
     pub trace_bits: *mut u8,
 }
 
-const MAX_FILE: u32 = 1024 * 1024;
+/// The size of the input payload page shared with the VM, larger inputs get truncated
+pub(crate) const MAX_FILE: u32 = 1024 * 1024;
 #[derive(Clone, Copy, Debug)]
 pub enum NyxProcessType {
     /// stand alone mode
@@ -102,14 +103,7 @@ impl NyxHelper {
         nyx_process.option_apply();
 
         // default timeout for initial dry-run
-        let sec = initial_timeout
-            .as_secs()
-            .try_into()
-            .map_err(|_| -> Error { Error::illegal_argument("can't cast time's sec to u8") })?;
-
-        let micro_sec: u32 = initial_timeout.subsec_micros();
-        nyx_process.option_set_timeout(sec, micro_sec);
-        nyx_process.option_apply();
+        set_nyx_timeout(&mut nyx_process, initial_timeout)?;
 
         // dry run to check if qemu is spawned
         nyx_process.set_input(b"INIT", 4);
@@ -138,18 +132,25 @@ impl NyxHelper {
         })
     }
 
-    /// set timeout
-    pub fn set_timeout(mut self, time: Duration) {
-        let sec: u8 = time
-            .as_secs()
-            .try_into()
-            .expect("can't cast time's sec to u8");
-        let micro_sec: u32 = time.subsec_micros();
-        self.nyx_process.option_set_timeout(sec, micro_sec);
-        self.nyx_process.option_apply();
+    /// Set the timeout of each run, enforced by the VM, which reports it as [`NyxReturnValue::Timeout`].
+    /// Nyx only supports timeouts below 256 seconds.
+    pub fn set_timeout(&mut self, time: Duration) -> Result<(), Error> {
+        set_nyx_timeout(&mut self.nyx_process, time)
     }
 }
 
+/// Set the timeout of the `nyx_process`, the seconds have to fit into a `u8`
+fn set_nyx_timeout(nyx_process: &mut NyxProcess, time: Duration) -> Result<(), Error> {
+    let sec: u8 = time
+        .as_secs()
+        .try_into()
+        .map_err(|_| -> Error { Error::illegal_argument("can't cast time's sec to u8") })?;
+    let micro_sec: u32 = time.subsec_micros();
+    nyx_process.option_set_timeout(sec, micro_sec);
+    nyx_process.option_apply();
+    Ok(())
+}
+
 impl Debug for NyxHelper {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("NyxInprocessHelper").finish()
@@ -1,3 +1,11 @@
+//! `libafl_nyx` runs targets inside a [`Nyx`](https://nyx-fuzz.com) VM, a QEMU-KVM fork with fast, full-VM snapshots.
+//!
+//! The [`helper::NyxHelper`] spawns the VM from a Nyx share directory, built with the `packer`,
+//! and the [`executor::NyxExecutor`] passes each input over the shared payload page,
+//! restores the snapshot, and maps the status reported by the VM to an [`libafl::executors::ExitKind`].
+//! The coverage, collected through hypercalls, ends up in [`helper::NyxHelper::trace_bits`],
+//! use it as map for a [`libafl::observers::StdMapObserver`].
+
 #[cfg(target_os = "linux")]
 pub mod executor;
 #[cfg(target_os = "linux")]
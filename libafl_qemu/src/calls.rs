@@ -99,6 +99,13 @@ where
         ret_addr
     };
 
+    #[cfg(any(cpu_target = "mips", cpu_target = "riscv64"))]
+    let ret_addr = {
+        let emu = hooks.emulator();
+        let ret_addr: GuestAddr = emu.read_reg(Regs::Ra).unwrap();
+        ret_addr
+    };
+
     // eprintln!("RET @ 0x{:#x}", ret_addr);
 
     if let Some(h) = hooks
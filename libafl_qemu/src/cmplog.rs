@@ -1,3 +1,6 @@
+//! Cmplog for `QEMU`: hooks on compare instructions record their operands into the [`CMPLOG_MAP`],
+//! so that a [`CmpLogObserver`] and the input-to-state (`Redqueen`) mutators also work on binary-only targets.
+
 use hashbrown::HashMap;
 use libafl::{inputs::UsesInput, state::HasMetadata};
 pub use libafl_targets::{
@@ -12,6 +15,7 @@ use crate::{
     GuestAddr,
 };
 
+/// A state metadata mapping the pc of each compare instruction to a unique id in the [`CMPLOG_MAP`]
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct QemuCmpsMapMetadata {
     pub map: HashMap<u64, u64>,
@@ -30,6 +34,8 @@ impl QemuCmpsMapMetadata {
 
 libafl::impl_serdeany!(QemuCmpsMapMetadata);
 
+/// A helper logging the operands of 1, 2, 4, and 8 bytes compare instructions into the [`CMPLOG_MAP`].
+/// Each compare instruction gets a unique id, stored in the [`QemuCmpsMapMetadata`], so this only works in-process.
 #[derive(Debug)]
 pub struct QemuCmpLogHelper {
     filter: QemuInstrumentationFilter,
@@ -71,6 +77,8 @@ where
     }
 }
 
+/// Like [`QemuCmpLogHelper`], for fork executors.
+/// The ids of the compare instructions are hashed from their pc, as the child can't update the state.
 #[derive(Debug)]
 pub struct QemuCmpLogChildHelper {
     filter: QemuInstrumentationFilter,
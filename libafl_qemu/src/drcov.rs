@@ -0,0 +1,229 @@
+//! Records the basic blocks executed for each input and writes them to [`DrCov`](https://dynamorio.org/page_drcov.html) files,
+//! to be loaded by coverage visualization tools, such as [Lighthouse](https://github.com/gaasedelen/lighthouse).
+
+use std::path::PathBuf;
+
+use capstone::prelude::*;
+use hashbrown::HashMap;
+use libafl::{
+    inputs::{Input, UsesInput},
+    Error,
+};
+use libafl_targets::drcov::{DrCovBasicBlock, DrCovWriter};
+use rangemap::RangeMap;
+
+use crate::{
+    capstone,
+    emu::{Emulator, GuestAddr},
+    helper::{QemuHelper, QemuHelperTuple, QemuInstrumentationFilter},
+    hooks::QemuHooks,
+};
+
+/// The maximum number of code bytes disassembled to find the end of a basic block
+const MAX_BLOCK_LEN: usize = 512;
+
+/// A helper recording the basic blocks executed for each input.
+/// Call [`QemuDrCovHelper::write_drcov`] after running an input to write them to `<coverage_dir>/<input name>.drcov`,
+/// or enable [`QemuDrCovHelper::with_write_each_run`] to write a file after every execution,
+/// for example when replaying a corpus, as writing a file per run slows down fuzzing.
+/// Only blocks inside of the modules in the `module_mapping` are written.
+#[derive(Debug)]
+pub struct QemuDrCovHelper {
+    filter: QemuInstrumentationFilter,
+    cs: Capstone,
+    module_mapping: RangeMap<usize, (u16, String)>,
+    coverage_dir: PathBuf,
+    full_trace: bool,
+    write_each_run: bool,
+    block_lens: HashMap<GuestAddr, GuestAddr>,
+    trace: Vec<GuestAddr>,
+}
+
+impl QemuDrCovHelper {
+    /// Creates a new [`QemuDrCovHelper`], writing the `DrCov` files to `coverage_dir`.
+    /// If `full_trace` is set, each execution of a block is written, else each block only once per input.
+    #[must_use]
+    pub fn new(
+        filter: QemuInstrumentationFilter,
+        module_mapping: RangeMap<usize, (u16, String)>,
+        coverage_dir: PathBuf,
+        full_trace: bool,
+    ) -> Self {
+        Self {
+            filter,
+            cs: capstone().detail(true).build().unwrap(),
+            module_mapping,
+            coverage_dir,
+            full_trace,
+            write_each_run: false,
+            block_lens: HashMap::new(),
+            trace: vec![],
+        }
+    }
+
+    /// If set, a `DrCov` file is written after each execution. Failing writes are logged, but do not stop the fuzzer.
+    #[must_use]
+    pub fn with_write_each_run(mut self, write_each_run: bool) -> Self {
+        self.write_each_run = write_each_run;
+        self
+    }
+
+    /// Builds a module mapping for [`QemuDrCovHelper::new`] out of the executable, file backed mappings of the target
+    #[cfg(emulation_mode = "usermode")]
+    #[must_use]
+    pub fn module_mapping(emulator: &Emulator) -> RangeMap<usize, (u16, String)> {
+        let mut ids: HashMap<String, u16> = HashMap::new();
+        let mut module_mapping = RangeMap::new();
+        for map in emulator.mappings() {
+            if !map.flags().is_x() {
+                continue;
+            }
+            if let Some(path) = map.path() {
+                let next_id = ids.len() as u16;
+                let id = *ids.entry(path.to_string()).or_insert(next_id);
+                module_mapping.insert(
+                    map.start() as usize..map.end() as usize,
+                    (id, path.to_string()),
+                );
+            }
+        }
+        module_mapping
+    }
+
+    #[must_use]
+    pub fn must_instrument(&self, addr: u64) -> bool {
+        self.filter.allowed(addr)
+    }
+
+    /// The basic blocks executed for the current input, by start address
+    #[must_use]
+    pub fn trace(&self) -> &[GuestAddr] {
+        &self.trace
+    }
+
+    /// Finds the length of the basic block at `pc`, stopping at the first branching instruction
+    fn block_len(&self, emu: &Emulator, pc: GuestAddr) -> GuestAddr {
+        let code = {
+            // Don't read past the end of the mapping of the block
+            #[cfg(emulation_mode = "usermode")]
+            let max_len = emu
+                .mappings()
+                .find(|map| map.start() <= pc && pc < map.end())
+                .map_or(0, |map| MAX_BLOCK_LEN.min((map.end() - pc) as usize));
+            #[cfg(emulation_mode = "usermode")]
+            unsafe {
+                std::slice::from_raw_parts(emu.g2h(pc), max_len)
+            }
+            #[cfg(emulation_mode = "systemmode")]
+            &mut [0; MAX_BLOCK_LEN]
+        };
+        #[cfg(emulation_mode = "systemmode")]
+        unsafe {
+            emu.read_mem(pc, code)
+        }; // TODO handle faults
+
+        let mut len = 0;
+        if let Ok(insns) = self.cs.disasm_all(code, pc.into()) {
+            'disasm: for insn in insns.iter() {
+                len += insn.bytes().len() as GuestAddr;
+                let insn_detail: InsnDetail = self.cs.insn_detail(insn).unwrap();
+                for detail in insn_detail.groups() {
+                    match u32::from(detail.0) {
+                        capstone::InsnGroupType::CS_GRP_CALL
+                        | capstone::InsnGroupType::CS_GRP_RET
+                        | capstone::InsnGroupType::CS_GRP_INVALID
+                        | capstone::InsnGroupType::CS_GRP_JUMP
+                        | capstone::InsnGroupType::CS_GRP_IRET
+                        | capstone::InsnGroupType::CS_GRP_PRIVILEGE => {
+                            break 'disasm;
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+        // An undecodable block still gets a size, so it shows up in the trace
+        len.max(1)
+    }
+
+    /// Writes the basic blocks executed by the last run to `<coverage_dir>/<input_name>.drcov`
+    pub fn write_drcov(&mut self, input_name: &str) -> Result<(), Error> {
+        if !self.full_trace {
+            self.trace.sort_unstable();
+            self.trace.dedup();
+        }
+        let blocks: Vec<DrCovBasicBlock> = self
+            .trace
+            .iter()
+            .filter(|pc| self.module_mapping.contains_key(&(**pc as usize)))
+            .map(|pc| {
+                let len = self.block_lens.get(pc).map_or(1, |len| *len);
+                DrCovBasicBlock::new_with_size(*pc as usize, len as usize)
+            })
+            .collect();
+        std::fs::create_dir_all(&self.coverage_dir)?;
+        let path = self.coverage_dir.join(format!("{input_name}.drcov"));
+        DrCovWriter::new(&self.module_mapping).write(path, &blocks)
+    }
+}
+
+impl<S> QemuHelper<S> for QemuDrCovHelper
+where
+    S: UsesInput,
+{
+    fn first_exec<QT>(&self, hooks: &QemuHooks<'_, QT, S>)
+    where
+        QT: QemuHelperTuple<S>,
+    {
+        hooks.blocks(Some(gen_block_lengths::<QT, S>), Some(trace_block::<QT, S>));
+    }
+
+    fn pre_exec(&mut self, _emulator: &Emulator, _input: &S::Input) {
+        self.trace.clear();
+    }
+
+    fn post_exec(&mut self, _emulator: &Emulator, input: &S::Input) {
+        if self.write_each_run {
+            let input_name = input.generate_name(0);
+            if let Err(err) = self.write_drcov(&input_name) {
+                eprintln!("Failed to write the DrCov file of {input_name}: {err}");
+            }
+        }
+    }
+}
+
+pub fn gen_block_lengths<QT, S>(
+    hooks: &mut QemuHooks<'_, QT, S>,
+    _state: Option<&mut S>,
+    pc: GuestAddr,
+) -> Option<u64>
+where
+    S: UsesInput,
+    QT: QemuHelperTuple<S>,
+{
+    let emu = hooks.emulator().clone();
+    let len = match hooks.helpers().match_first_type::<QemuDrCovHelper>() {
+        Some(h) if h.must_instrument(pc.into()) => h.block_len(&emu, pc),
+        _ => return None,
+    };
+    if let Some(h) = hooks
+        .helpers_mut()
+        .match_first_type_mut::<QemuDrCovHelper>()
+    {
+        h.block_lens.insert(pc, len);
+    }
+    Some(pc.into())
+}
+
+pub fn trace_block<QT, S>(hooks: &mut QemuHooks<'_, QT, S>, _state: Option<&mut S>, id: u64)
+where
+    S: UsesInput,
+    QT: QemuHelperTuple<S>,
+{
+    if let Some(h) = hooks
+        .helpers_mut()
+        .match_first_type_mut::<QemuDrCovHelper>()
+    {
+        h.trace.push(id as GuestAddr);
+    }
+}
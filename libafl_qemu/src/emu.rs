@@ -15,16 +15,25 @@ use num_enum::{IntoPrimitive, TryFromPrimitive};
 use num_traits::Num;
 use strum_macros::EnumIter;
 
-#[cfg(not(any(cpu_target = "x86_64", cpu_target = "aarch64")))]
+#[cfg(not(any(cpu_target = "x86_64", cpu_target = "aarch64", cpu_target = "riscv64")))]
 /// `GuestAddr` is u32 for 32-bit targets
 pub type GuestAddr = u32;
 
-#[cfg(any(cpu_target = "x86_64", cpu_target = "aarch64"))]
+#[cfg(any(cpu_target = "x86_64", cpu_target = "aarch64", cpu_target = "riscv64"))]
 /// `GuestAddr` is u64 for 64-bit targets
 pub type GuestAddr = u64;
 
+/// `GuestUsize` is the `abi_ulong` of the target
 pub type GuestUsize = GuestAddr;
 
+#[cfg(not(any(cpu_target = "x86_64", cpu_target = "aarch64", cpu_target = "riscv64")))]
+/// `GuestIsize` is the `abi_long` of the target, i32 for 32-bit targets
+pub type GuestIsize = i32;
+
+#[cfg(any(cpu_target = "x86_64", cpu_target = "aarch64", cpu_target = "riscv64"))]
+/// `GuestIsize` is the `abi_long` of the target, i64 for 64-bit targets
+pub type GuestIsize = i64;
+
 #[cfg(feature = "python")]
 use pyo3::{prelude::*, PyIterProtocol};
 
@@ -91,8 +100,12 @@ impl IntoPy<PyObject> for MmapPerms {
 #[repr(C)]
 #[cfg_attr(feature = "python", pyclass)]
 #[cfg_attr(feature = "python", derive(FromPyObject))]
+/// The result of a pre syscall hook.
+/// If `skip_syscall` is set, the syscall is not emulated, and the guest gets `retval` as its result.
 pub struct SyscallHookResult {
+    /// The result of the syscall, returned to the guest if the syscall is skipped
     pub retval: u64,
+    /// Whether to skip the emulation of the syscall
     pub skip_syscall: bool,
 }
 
@@ -196,14 +209,20 @@ extern "C" {
     fn libafl_set_brk(brk: u64) -> u64;
 
     /// abi_long target_mmap(abi_ulong start, abi_ulong len, int target_prot, int flags, int fd, abi_ulong offset)
-    fn target_mmap(start: u64, len: u64, target_prot: i32, flags: i32, fd: i32, offset: u64)
-        -> u64;
+    fn target_mmap(
+        start: GuestAddr,
+        len: GuestUsize,
+        target_prot: i32,
+        flags: i32,
+        fd: i32,
+        offset: GuestUsize,
+    ) -> GuestIsize;
 
     /// int target_mprotect(abi_ulong start, abi_ulong len, int prot)
-    fn target_mprotect(start: u64, len: u64, target_prot: i32) -> i32;
+    fn target_mprotect(start: GuestAddr, len: GuestUsize, target_prot: i32) -> i32;
 
     /// int target_munmap(abi_ulong start, abi_ulong len)
-    fn target_munmap(start: u64, len: u64) -> i32;
+    fn target_munmap(start: GuestAddr, len: GuestUsize) -> i32;
 
     fn read_self_maps() -> *const c_void;
     fn free_self_maps(map_info: *const c_void);
@@ -773,12 +792,13 @@ impl Emulator {
         size: usize,
         perms: MmapPerms,
         flags: c_int,
-    ) -> Result<u64, ()> {
-        let res = unsafe { target_mmap(addr.into(), size as u64, perms.into(), flags, -1, 0) };
-        if res == 0 {
+    ) -> Result<GuestAddr, ()> {
+        let res = unsafe { target_mmap(addr, size as GuestUsize, perms.into(), flags, -1, 0) };
+        // `target_mmap` returns -1 and sets errno on failure
+        if res == -1 {
             Err(())
         } else {
-            Ok(res)
+            Ok(res as GuestAddr)
         }
     }
 
@@ -791,7 +811,6 @@ impl Emulator {
     ) -> Result<GuestAddr, String> {
         self.mmap(addr, size, perms, libc::MAP_PRIVATE | libc::MAP_ANONYMOUS)
             .map_err(|_| format!("Failed to map {addr}"))
-            .map(|addr| addr as GuestAddr)
     }
 
     #[cfg(emulation_mode = "usermode")]
@@ -808,12 +827,11 @@ impl Emulator {
             libc::MAP_FIXED | libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
         )
         .map_err(|_| format!("Failed to map {addr}"))
-        .map(|addr| addr as GuestAddr)
     }
 
     #[cfg(emulation_mode = "usermode")]
     pub fn mprotect(&self, addr: GuestAddr, size: usize, perms: MmapPerms) -> Result<(), String> {
-        let res = unsafe { target_mprotect(addr.into(), size as u64, perms.into()) };
+        let res = unsafe { target_mprotect(addr, size as GuestUsize, perms.into()) };
         if res == 0 {
             Ok(())
         } else {
@@ -823,7 +841,7 @@ impl Emulator {
 
     #[cfg(emulation_mode = "usermode")]
     pub fn unmap(&self, addr: GuestAddr, size: usize) -> Result<(), String> {
-        if unsafe { target_munmap(addr.into(), size as u64) } == 0 {
+        if unsafe { target_munmap(addr, size as GuestUsize) } == 0 {
             Ok(())
         } else {
             Err(format!("Failed to unmap {addr}"))
@@ -922,6 +940,9 @@ impl Emulator {
         unsafe { libafl_load_qemu_snapshot(s.as_ptr() as *const _) };
     }*/
 
+    /// Set the hook called before each syscall of the guest.
+    /// It gets the syscall number and its 8 arguments, and can return a synthetic result
+    /// with [`SyscallHookResult::new`], for example to stub out `read` to inject the input.
     #[cfg(emulation_mode = "usermode")]
     pub fn set_pre_syscall_hook(
         &self,
@@ -932,6 +953,8 @@ impl Emulator {
         }
     }
 
+    /// Set the hook called after each syscall of the guest.
+    /// It gets the result, the syscall number, and its 8 arguments, and returns the result for the guest.
     #[cfg(emulation_mode = "usermode")]
     pub fn set_post_syscall_hook(
         &self,
@@ -20,6 +20,14 @@ use libafl::{
 pub use crate::emu::SyscallHookResult;
 use crate::{emu::Emulator, helper::QemuHelperTuple, hooks::QemuHooks};
 
+/// An [`Executor`] running a target in `QEMU` usermode, in-process.
+///
+/// The harness writes the input into guest memory, sets up the registers (arguments, program counter,
+/// stack pointer), and calls [`Emulator::run`] until the return breakpoint is hit.
+/// Before and after each run, the [`QemuHelperTuple`] gets called, so that helpers such as
+/// [`crate::edges::QemuEdgeCoverageHelper`] and [`crate::snapshot::QemuSnapshotHelper`]
+/// can reset their maps and the guest state. The edges map is exported in [`crate::edges::EDGES_MAP`],
+/// to be used by a map observer.
 pub struct QemuExecutor<'a, H, OT, QT, S>
 where
     H: FnMut(&S::Input) -> ExitKind,
@@ -54,6 +62,7 @@ where
     OT: ObserversTuple<S>,
     QT: QemuHelperTuple<S>,
 {
+    /// Create a new [`QemuExecutor`] for the given harness, registering the hooks of the helpers.
     pub fn new<EM, OF, Z>(
         hooks: &'a mut QemuHooks<'a, QT, S>,
         harness_fn: &'a mut H,
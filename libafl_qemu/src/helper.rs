@@ -114,6 +114,39 @@ pub enum QemuInstrumentationFilter {
 }
 
 impl QemuInstrumentationFilter {
+    /// Only instrument code in the given address ranges
+    #[must_use]
+    pub fn include_ranges(ranges: Vec<Range<u64>>) -> Self {
+        QemuInstrumentationFilter::AllowList(ranges)
+    }
+
+    /// Instrument all code, except in the given address ranges
+    #[must_use]
+    pub fn exclude_ranges(ranges: Vec<Range<u64>>) -> Self {
+        QemuInstrumentationFilter::DenyList(ranges)
+    }
+
+    /// Only instrument the executable mappings of the target binary,
+    /// skipping the dynamic loader, libc, and all other libraries.
+    /// Call it after the target got loaded, for example at the breakpoint on the harness entry.
+    #[cfg(emulation_mode = "usermode")]
+    #[must_use]
+    pub fn target_binary(emulator: &Emulator) -> Self {
+        let binary = emulator.binary_path();
+        let binary = std::fs::canonicalize(binary).unwrap_or_else(|_| binary.into());
+        let ranges = emulator
+            .mappings()
+            .filter(|map| map.flags().is_x())
+            .filter(|map| {
+                map.path().map_or(false, |path| {
+                    std::fs::canonicalize(path).map_or(false, |path| path == binary)
+                })
+            })
+            .map(|map| u64::from(map.start())..u64::from(map.end()))
+            .collect();
+        QemuInstrumentationFilter::AllowList(ranges)
+    }
+
     #[must_use]
     pub fn allowed(&self, addr: u64) -> bool {
         match self {
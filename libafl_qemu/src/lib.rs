@@ -3,7 +3,7 @@
 // This lint triggers too often on the current GuestAddr type when emulating 64-bit targets because
 // u64::from(GuestAddr) is a no-op, but the .into() call is needed when GuestAddr is u32.
 #![cfg_attr(
-    any(cpu_target = "x86_64", cpu_target = "aarch64"),
+    any(cpu_target = "x86_64", cpu_target = "aarch64", cpu_target = "riscv64"),
     allow(clippy::useless_conversion)
 )]
 #![allow(clippy::needless_pass_by_value)]
@@ -34,6 +34,16 @@ pub mod i386;
 #[cfg(all(cpu_target = "i386", not(feature = "clippy")))]
 pub use i386::*;
 
+#[cfg(cpu_target = "mips")]
+pub mod mips;
+#[cfg(all(cpu_target = "mips", not(feature = "clippy")))]
+pub use mips::*;
+
+#[cfg(cpu_target = "riscv64")]
+pub mod riscv64;
+#[cfg(all(cpu_target = "riscv64", not(feature = "clippy")))]
+pub use riscv64::*;
+
 #[cfg(cpu_target = "x86_64")]
 pub mod x86_64;
 #[cfg(cpu_target = "x86_64")]
@@ -58,8 +68,14 @@ pub use snapshot::QemuSnapshotHelper;
 pub mod asan;
 #[cfg(emulation_mode = "usermode")]
 pub use asan::{init_with_asan, QemuAsanHelper};
+#[cfg(emulation_mode = "usermode")]
+pub mod threads;
+#[cfg(emulation_mode = "usermode")]
+pub use threads::QemuThreadsHelper;
 
 pub mod calls;
+pub mod drcov;
+pub use drcov::QemuDrCovHelper;
 
 pub mod executor;
 pub use executor::QemuExecutor;
@@ -0,0 +1,88 @@
+use capstone::arch::{BuildsCapstone, BuildsCapstoneEndian};
+use num_enum::{IntoPrimitive, TryFromPrimitive};
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+pub use strum_macros::EnumIter;
+pub use syscall_numbers::mips::*;
+
+/// Registers for the MIPS instruction set, in the order of the QEMU gdbstub.
+#[derive(IntoPrimitive, TryFromPrimitive, Debug, Clone, Copy, EnumIter)]
+#[repr(i32)]
+pub enum Regs {
+    R0 = 0,
+    R1 = 1,
+    R2 = 2,
+    R3 = 3,
+    R4 = 4,
+    R5 = 5,
+    R6 = 6,
+    R7 = 7,
+    R8 = 8,
+    R9 = 9,
+    R10 = 10,
+    R11 = 11,
+    R12 = 12,
+    R13 = 13,
+    R14 = 14,
+    R15 = 15,
+    R16 = 16,
+    R17 = 17,
+    R18 = 18,
+    R19 = 19,
+    R20 = 20,
+    R21 = 21,
+    R22 = 22,
+    R23 = 23,
+    R24 = 24,
+    R25 = 25,
+    R26 = 26,
+    R27 = 27,
+    R28 = 28,
+    R29 = 29,
+    R30 = 30,
+    R31 = 31,
+    Sr = 32,
+    Lo = 33,
+    Hi = 34,
+    Badvaddr = 35,
+    Cause = 36,
+    Pc = 37,
+}
+
+/// alias registers
+#[allow(non_upper_case_globals)]
+impl Regs {
+    pub const Zero: Regs = Regs::R0;
+    pub const At: Regs = Regs::R1;
+    pub const V0: Regs = Regs::R2;
+    pub const V1: Regs = Regs::R3;
+    pub const A0: Regs = Regs::R4;
+    pub const A1: Regs = Regs::R5;
+    pub const A2: Regs = Regs::R6;
+    pub const A3: Regs = Regs::R7;
+    pub const T9: Regs = Regs::R25;
+    pub const Gp: Regs = Regs::R28;
+    pub const Sp: Regs = Regs::R29;
+    pub const Fp: Regs = Regs::R30;
+    pub const Ra: Regs = Regs::R31;
+}
+
+#[cfg(feature = "python")]
+impl IntoPy<PyObject> for Regs {
+    fn into_py(self, py: Python) -> PyObject {
+        let n: i32 = self.into();
+        n.into_py(py)
+    }
+}
+
+/// Return a MIPS ArchCapstoneBuilder
+pub fn capstone() -> capstone::arch::mips::ArchCapstoneBuilder {
+    #[cfg(feature = "be")]
+    let endian = capstone::Endian::Big;
+    #[cfg(not(feature = "be"))]
+    let endian = capstone::Endian::Little;
+    capstone::Capstone::new()
+        .mips()
+        .mode(capstone::arch::mips::ArchMode::Mips32)
+        .endian(endian)
+}
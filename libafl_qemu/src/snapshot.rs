@@ -12,12 +12,12 @@ use crate::{
     emu::{Emulator, MmapPerms, SyscallHookResult},
     helper::{QemuHelper, QemuHelperTuple},
     hooks::QemuHooks,
-    GuestAddr, SYS_fstat, SYS_fstatfs, SYS_futex, SYS_getrandom, SYS_mprotect, SYS_mremap,
-    SYS_munmap, SYS_pread64, SYS_read, SYS_readlinkat, SYS_statfs,
+    GuestAddr, IntoEnumIterator, Regs, SYS_fstat, SYS_fstatfs, SYS_futex, SYS_getrandom,
+    SYS_mprotect, SYS_mremap, SYS_munmap, SYS_pread64, SYS_read, SYS_readlinkat, SYS_statfs,
 };
-#[cfg(cpu_target = "arm")]
+#[cfg(any(cpu_target = "arm", cpu_target = "mips"))]
 use crate::{SYS_fstatat64, SYS_mmap2};
-#[cfg(not(cpu_target = "arm"))]
+#[cfg(not(any(cpu_target = "arm", cpu_target = "mips")))]
 use crate::{SYS_mmap, SYS_newfstatat};
 
 pub const SNAPSHOT_PAGE_SIZE: usize = 4096;
@@ -71,6 +71,10 @@ pub struct QemuSnapshotHelper {
     pub stop_execution: Option<StopExecutionCallback>,
     pub empty: bool,
     pub accurate_unmap: bool,
+    /// Whether to also snapshot and restore the registers
+    pub snapshot_regs: bool,
+    /// The registers at snapshot time, in the order of [`Regs::iter`]
+    pub regs: Vec<GuestAddr>,
 }
 
 impl core::fmt::Debug for QemuSnapshotHelper {
@@ -83,6 +87,7 @@ impl core::fmt::Debug for QemuSnapshotHelper {
             .field("mmap_start", &self.mmap_start)
             .field("mmap_limit", &self.mmap_limit)
             .field("empty", &self.empty)
+            .field("regs", &self.regs)
             .finish()
     }
 }
@@ -101,6 +106,8 @@ impl QemuSnapshotHelper {
             stop_execution: None,
             empty: true,
             accurate_unmap: false,
+            snapshot_regs: false,
+            regs: Vec::new(),
         }
     }
 
@@ -117,6 +124,8 @@ impl QemuSnapshotHelper {
             stop_execution: Some(stop_execution),
             empty: true,
             accurate_unmap: false,
+            snapshot_regs: false,
+            regs: Vec::new(),
         }
     }
 
@@ -124,6 +133,12 @@ impl QemuSnapshotHelper {
         self.accurate_unmap = true;
     }
 
+    /// Also snapshot the registers, and restore them on each reset, next to the dirty pages.
+    /// This way, the harness can resume the target at the snapshot point, instead of setting up each register.
+    pub fn use_register_snapshot(&mut self) {
+        self.snapshot_regs = true;
+    }
+
     #[allow(clippy::uninit_assumed_init)]
     pub fn snapshot(&mut self, emulator: &Emulator) {
         self.brk = emulator.get_brk();
@@ -158,6 +173,15 @@ impl QemuSnapshotHelper {
             );
             self.maps.size += (map.end() - map.start()) as usize;
         }
+        if self.snapshot_regs {
+            self.regs = Regs::iter()
+                .map(|r| {
+                    emulator
+                        .read_reg(r)
+                        .expect("Failed to read a register to snapshot")
+                })
+                .collect();
+        }
         self.empty = false;
         *self.new_maps.lock().unwrap() = self.maps.clone();
     }
@@ -284,6 +308,14 @@ impl QemuSnapshotHelper {
 
         emulator.set_brk(self.brk);
         emulator.set_mmap_start(self.mmap_start);
+
+        if self.snapshot_regs {
+            for (r, val) in Regs::iter().zip(self.regs.iter()) {
+                emulator
+                    .write_reg(r, *val)
+                    .expect("Failed to restore a register from the snapshot");
+            }
+        }
     }
 
     pub fn is_unmap_allowed(&mut self, start: GuestAddr, mut size: usize) -> bool {
@@ -629,14 +661,14 @@ where
             let h = hooks.match_helper_mut::<QemuSnapshotHelper>().unwrap();
             h.access(a0 as GuestAddr, a3 as usize);
         }
-        #[cfg(not(cpu_target = "arm"))]
+        #[cfg(not(any(cpu_target = "arm", cpu_target = "mips")))]
         SYS_newfstatat => {
             if a2 != 0 {
                 let h = hooks.match_helper_mut::<QemuSnapshotHelper>().unwrap();
                 h.access(a2 as GuestAddr, 4096); // stat is not greater than a page
             }
         }
-        #[cfg(cpu_target = "arm")]
+        #[cfg(any(cpu_target = "arm", cpu_target = "mips"))]
         SYS_fstatat64 => {
             if a2 != 0 {
                 let h = hooks.match_helper_mut::<QemuSnapshotHelper>().unwrap();
@@ -661,7 +693,7 @@ where
 
             // TODO handle huge pages
 
-            #[cfg(cpu_target = "arm")]
+            #[cfg(any(cpu_target = "arm", cpu_target = "mips"))]
             if i64::from(sys_num) == SYS_mmap2 {
                 if let Ok(prot) = MmapPerms::try_from(a2 as i32) {
                     let h = hooks.match_helper_mut::<QemuSnapshotHelper>().unwrap();
@@ -684,7 +716,7 @@ where
                 }
             }
 
-            #[cfg(not(cpu_target = "arm"))]
+            #[cfg(not(any(cpu_target = "arm", cpu_target = "mips")))]
             if i64::from(sys_num) == SYS_mmap {
                 if let Ok(prot) = MmapPerms::try_from(a2 as i32) {
                     let h = hooks.match_helper_mut::<QemuSnapshotHelper>().unwrap();
@@ -0,0 +1,244 @@
+//! Tracking and scheduling of guest threads in usermode.
+//!
+//! The [`QemuThreadsHelper`] keeps track of the live guest threads.
+//! Optionally, it serializes the guest threads, so that only one of them runs guest code at a time,
+//! and the order in which they run is deterministic. This makes coverage of multi-threaded targets
+//! more stable, and crashes more reproducible.
+
+use std::{
+    collections::BTreeSet,
+    sync::{Condvar, Mutex},
+};
+
+use libafl::inputs::UsesInput;
+
+use crate::{
+    emu::{Emulator, SyscallHookResult},
+    helper::{QemuHelper, QemuHelperTuple},
+    hooks::QemuHooks,
+    SYS_exit,
+};
+
+/// The host thread id of the calling thread, which, in usermode, is also the guest thread id.
+fn current_tid() -> u32 {
+    unsafe { libc::syscall(libc::SYS_gettid) as u32 }
+}
+
+#[derive(Debug, Default)]
+struct ThreadsState {
+    /// All live threads, by tid
+    live: BTreeSet<u32>,
+    /// The threads waiting to run guest code
+    waiting: BTreeSet<u32>,
+    /// The thread currently running guest code, if serialized
+    running: Option<u32>,
+    /// The thread that ran last, to hand over round-robin
+    last: u32,
+    /// The number of threads created since the start
+    created: usize,
+}
+
+impl ThreadsState {
+    /// The next thread allowed to run: the first waiting thread after the last one, round-robin by tid.
+    fn next_waiting(&self) -> Option<u32> {
+        self.waiting
+            .range((self.last + 1)..)
+            .next()
+            .or_else(|| self.waiting.iter().next())
+            .copied()
+    }
+}
+
+/// A helper that tracks the lifecycle of guest threads and, optionally, serializes them.
+///
+/// When serialized, a thread hands over to the next waiting thread, ordered by tid,
+/// at each syscall, and waits for its turn again after the syscall returned.
+/// Blocking syscalls are executed without holding the turn, so threads waiting on each other
+/// cannot deadlock, but the order may then depend on when the syscall returns.
+#[derive(Debug)]
+pub struct QemuThreadsHelper {
+    serialize: bool,
+    state: Mutex<ThreadsState>,
+    turn: Condvar,
+}
+
+impl QemuThreadsHelper {
+    /// Creates a new [`QemuThreadsHelper`] that only tracks the guest threads.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            serialize: false,
+            state: Mutex::new(ThreadsState::default()),
+            turn: Condvar::new(),
+        }
+    }
+
+    /// Creates a new [`QemuThreadsHelper`] that tracks and deterministically serializes the guest threads.
+    #[must_use]
+    pub fn serialized() -> Self {
+        Self {
+            serialize: true,
+            ..Self::new()
+        }
+    }
+
+    /// If the guest threads are serialized
+    #[must_use]
+    pub fn is_serialized(&self) -> bool {
+        self.serialize
+    }
+
+    /// The tids of all live guest threads
+    #[must_use]
+    pub fn live_threads(&self) -> Vec<u32> {
+        self.state.lock().unwrap().live.iter().copied().collect()
+    }
+
+    /// The number of guest threads created since the start
+    #[must_use]
+    pub fn threads_created(&self) -> usize {
+        self.state.lock().unwrap().created
+    }
+
+    /// Registers a new guest thread
+    pub fn on_thread_create(&self, tid: u32) {
+        {
+            let mut state = self.state.lock().unwrap();
+            state.live.insert(tid);
+            state.created += 1;
+        }
+        self.acquire(tid);
+    }
+
+    /// Unregisters an exiting guest thread
+    pub fn on_thread_exit(&self, tid: u32) {
+        self.release(tid);
+        self.state.lock().unwrap().live.remove(&tid);
+    }
+
+    /// Waits until the thread `tid` may run guest code
+    pub fn acquire(&self, tid: u32) {
+        if !self.serialize {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.running == Some(tid) {
+            return;
+        }
+        state.waiting.insert(tid);
+        while state.running.is_some() || state.next_waiting() != Some(tid) {
+            state = self.turn.wait(state).unwrap();
+        }
+        state.waiting.remove(&tid);
+        state.running = Some(tid);
+        state.last = tid;
+    }
+
+    /// Hands the turn of the thread `tid` over to the next waiting thread
+    pub fn release(&self, tid: u32) {
+        if !self.serialize {
+            return;
+        }
+        let mut state = self.state.lock().unwrap();
+        if state.running == Some(tid) {
+            state.running = None;
+            self.turn.notify_all();
+        }
+    }
+}
+
+impl Default for QemuThreadsHelper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> QemuHelper<S> for QemuThreadsHelper
+where
+    S: UsesInput,
+{
+    fn init_hooks<QT>(&self, hooks: &QemuHooks<'_, QT, S>)
+    where
+        QT: QemuHelperTuple<S>,
+    {
+        hooks.thread_creation(on_thread_create_threads::<QT, S>);
+        hooks.syscalls(pre_syscall_threads::<QT, S>);
+        if self.serialize {
+            hooks.after_syscalls(post_syscall_threads::<QT, S>);
+        }
+    }
+
+    fn pre_exec(&mut self, _emulator: &Emulator, _input: &S::Input) {
+        let tid = current_tid();
+        self.state.lock().unwrap().live.insert(tid);
+        self.acquire(tid);
+    }
+
+    fn post_exec(&mut self, _emulator: &Emulator, _input: &S::Input) {
+        self.release(current_tid());
+    }
+}
+
+pub fn on_thread_create_threads<QT, S>(
+    hooks: &mut QemuHooks<'_, QT, S>,
+    _state: Option<&mut S>,
+    tid: u32,
+) where
+    S: UsesInput,
+    QT: QemuHelperTuple<S>,
+{
+    let h = hooks.match_helper::<QemuThreadsHelper>().unwrap();
+    h.on_thread_create(tid);
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn pre_syscall_threads<QT, S>(
+    hooks: &mut QemuHooks<'_, QT, S>,
+    _state: Option<&mut S>,
+    sys_num: i32,
+    _a0: u64,
+    _a1: u64,
+    _a2: u64,
+    _a3: u64,
+    _a4: u64,
+    _a5: u64,
+    _a6: u64,
+    _a7: u64,
+) -> SyscallHookResult
+where
+    S: UsesInput,
+    QT: QemuHelperTuple<S>,
+{
+    let h = hooks.match_helper::<QemuThreadsHelper>().unwrap();
+    let tid = current_tid();
+    if i64::from(sys_num) == SYS_exit {
+        h.on_thread_exit(tid);
+    } else {
+        h.release(tid);
+    }
+    SyscallHookResult::new(None)
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn post_syscall_threads<QT, S>(
+    hooks: &mut QemuHooks<'_, QT, S>,
+    _state: Option<&mut S>,
+    result: u64,
+    _sys_num: i32,
+    _a0: u64,
+    _a1: u64,
+    _a2: u64,
+    _a3: u64,
+    _a4: u64,
+    _a5: u64,
+    _a6: u64,
+    _a7: u64,
+) -> u64
+where
+    S: UsesInput,
+    QT: QemuHelperTuple<S>,
+{
+    let h = hooks.match_helper::<QemuThreadsHelper>().unwrap();
+    h.acquire(current_tid());
+    result
+}
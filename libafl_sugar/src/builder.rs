@@ -0,0 +1,127 @@
+//! A fuzzer with sensible defaults, for `libfuzzer`-style harnesses.
+//! The [`FuzzerBuilder`] wires up the same fuzzer as the [`crate::InMemoryBytesCoverageSugar`],
+//! for a single client with a restarting event manager, instead of a launcher.
+
+use core::fmt::{self, Debug, Formatter};
+use std::{path::PathBuf, time::Duration};
+
+use libafl::{
+    events::{setup_restarting_mgr_std, EventConfig},
+    monitors::MultiMonitor,
+    Error,
+};
+use typed_builder::TypedBuilder;
+
+use crate::{
+    inmemory::{create_output_dir, fuzz_in_memory, InMemoryOptions},
+    DEFAULT_TIMEOUT_SECS,
+};
+
+/// Builds and runs a coverage-guided fuzzer for a bytes harness, with the defaults of the [`crate::InMemoryBytesCoverageSugar`]:
+/// the corpus and the crashes are on disk, in the `queue` and `crashes` subdirectories of the output directory,
+/// and a restarting event manager respawns the fuzzer after each crash or timeout.
+///
+/// Only the harness and the output directory are required, e.g.,
+/// `FuzzerBuilder::builder().harness(harness).output_dir(out_dir).build().run()`.
+/// For more than one core, use the [`crate::InMemoryBytesCoverageSugar`].
+#[derive(TypedBuilder)]
+pub struct FuzzerBuilder<H>
+where
+    H: FnMut(&[u8]),
+{
+    /// Configuration name of this fuzzer, the nodes with the same name share the observers of their events (default is unique)
+    #[builder(default = None, setter(strip_option))]
+    configuration: Option<String>,
+    /// Timeout of the executor
+    #[builder(default = None)]
+    timeout: Option<u64>,
+    /// Input directories, the fuzzer starts from random inputs if empty
+    #[builder(default = vec![])]
+    input_dirs: Vec<PathBuf>,
+    /// Output directory
+    output_dir: PathBuf,
+    /// Dictionary
+    #[builder(default = None)]
+    tokens_file: Option<PathBuf>,
+    /// Flag if use `CmpLog`
+    #[builder(default = None)]
+    use_cmplog: Option<bool>,
+    /// The port of the broker of the restarting event manager
+    #[builder(default = 1337_u16)]
+    broker_port: u16,
+    /// Bytes harness
+    #[builder(setter(strip_option))]
+    harness: Option<H>,
+    /// Fuzz `iterations` number of times, instead of indefinitely; implies use of `fuzz_loop_for`.
+    /// Afterwards, the client stores its state and [`FuzzerBuilder::run`] returns, and the client is not respawned.
+    #[builder(default = None)]
+    iterations: Option<u64>,
+}
+
+impl<H> Debug for FuzzerBuilder<H>
+where
+    H: FnMut(&[u8]),
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FuzzerBuilder")
+            .field("configuration", &self.configuration)
+            .field("timeout", &self.timeout)
+            .field("input_dirs", &self.input_dirs)
+            .field("output_dir", &self.output_dir)
+            .field("tokens_file", &self.tokens_file)
+            .field("use_cmplog", &self.use_cmplog)
+            .field("broker_port", &self.broker_port)
+            .field(
+                "harness",
+                if self.harness.is_some() {
+                    &"<harness_fn>"
+                } else {
+                    &"None"
+                },
+            )
+            .field("iterations", &self.iterations)
+            .finish()
+    }
+}
+
+impl<H> FuzzerBuilder<H>
+where
+    H: FnMut(&[u8]),
+{
+    /// Runs the fuzzer, respawning itself after each crash or timeout.
+    /// Returns once the user stops the fuzzer, or, if `iterations` is set, once they are done,
+    /// both in the client and in the respawner.
+    /// The client has to exit normally afterwards, for the respawner to know it finished.
+    pub fn run(&mut self) -> Result<(), Error> {
+        let conf = match self.configuration.as_ref() {
+            Some(name) => EventConfig::from_name(name),
+            None => EventConfig::AlwaysUnique,
+        };
+
+        let timeout = Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
+
+        let mut harness_bytes = self.harness.take().unwrap();
+
+        create_output_dir(&self.output_dir);
+        let options = InMemoryOptions {
+            output_dir: &self.output_dir,
+            input_dirs: &self.input_dirs,
+            tokens_file: self.tokens_file.as_deref(),
+            timeout,
+            use_cmplog: self.use_cmplog.unwrap_or(false),
+            iterations: self.iterations,
+        };
+
+        let monitor = MultiMonitor::new(|s| println!("{s}"));
+
+        // The restarting manager will spawn the same process again as child, and restart it each time it crashes.
+        match setup_restarting_mgr_std(monitor, self.broker_port, conf) {
+            Ok((state, mgr)) => fuzz_in_memory(&mut harness_bytes, state, mgr, &options),
+            Err(Error::ShuttingDown) => {
+                println!("\nFuzzing stopped by user. Good Bye.");
+                Ok(())
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
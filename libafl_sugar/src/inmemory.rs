@@ -2,7 +2,12 @@
 //! Use this sugar for scaling `libfuzzer`-style fuzzers.
 
 use core::fmt::{self, Debug, Formatter};
-use std::{fs, net::SocketAddr, path::PathBuf, time::Duration};
+use std::{
+    fs,
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 
 use libafl::{
     bolts::{
@@ -38,6 +43,210 @@ use typed_builder::TypedBuilder;
 
 use crate::{CORPUS_CACHE_SIZE, DEFAULT_TIMEOUT_SECS};
 
+/// The state of the in-memory fuzzers, with the corpus in `queue` and the solutions in `crashes`
+pub(crate) type InMemoryState =
+    StdState<BytesInput, CachedOnDiskCorpus<BytesInput>, StdRand, OnDiskCorpus<BytesInput>>;
+
+/// The restarting event manager of the in-memory fuzzers
+pub(crate) type InMemoryEventManager = LlmpRestartingEventManager<InMemoryState, StdShMemProvider>;
+
+/// The options of an in-memory fuzzer client, shared by [`InMemoryBytesCoverageSugar`] and [`crate::FuzzerBuilder`]
+#[derive(Debug)]
+pub(crate) struct InMemoryOptions<'a> {
+    /// Output directory, the corpus goes to `queue` and the solutions to `crashes`
+    pub(crate) output_dir: &'a Path,
+    /// Input directories, random inputs are generated if empty
+    pub(crate) input_dirs: &'a [PathBuf],
+    /// Dictionary
+    pub(crate) tokens_file: Option<&'a Path>,
+    /// Timeout of the executor
+    pub(crate) timeout: Duration,
+    /// Flag if use `CmpLog`
+    pub(crate) use_cmplog: bool,
+    /// Fuzz `iterations` number of times, instead of indefinitely
+    pub(crate) iterations: Option<u64>,
+}
+
+/// Creates the output directory, if it does not exist yet
+pub(crate) fn create_output_dir(output_dir: &Path) {
+    if fs::create_dir(output_dir).is_err() {
+        println!("Out dir at {output_dir:?} already exists.");
+        assert!(
+            output_dir.is_dir(),
+            "Out dir at {output_dir:?} is not a valid directory!"
+        );
+    }
+}
+
+/// Fuzzes the bytes harness in this client, until stopped or for the given number of iterations.
+/// After the iterations, the state is stored and the broker is told to forget this client,
+/// then this function returns. Exit the client normally afterwards, so the respawner does not spawn the next one.
+#[allow(clippy::too_many_lines, clippy::similar_names)]
+pub(crate) fn fuzz_in_memory<H>(
+    harness_bytes: &mut H,
+    state: Option<InMemoryState>,
+    mut mgr: InMemoryEventManager,
+    options: &InMemoryOptions,
+) -> Result<(), Error>
+where
+    H: FnMut(&[u8]),
+{
+    // Create an observation channel using the coverage map
+    let edges = unsafe { &mut EDGES_MAP[0..MAX_EDGES_NUM] };
+    let edges_observer = HitcountsMapObserver::new(StdMapObserver::new("edges", edges));
+
+    // Create an observation channel to keep track of the execution time
+    let time_observer = TimeObserver::new("time");
+
+    let cmplog = unsafe { &mut CMPLOG_MAP };
+    let cmplog_observer = CmpLogObserver::new("cmplog", cmplog, true);
+
+    // Feedback to rate the interestingness of an input
+    // This one is composed by two Feedbacks in OR
+    let mut feedback = feedback_or!(
+        // New maximization map feedback linked to the edges observer and the feedback state
+        MaxMapFeedback::new_tracking(&edges_observer, true, false),
+        // Time feedback, this one does not need a feedback state
+        TimeFeedback::new_with_observer(&time_observer)
+    );
+
+    // A feedback to choose if an input is a solution or not
+    let mut objective = feedback_or_fast!(CrashFeedback::new(), TimeoutFeedback::new());
+
+    // If not restarting, create a State from scratch
+    let mut state = match state {
+        Some(state) => state,
+        None => StdState::new(
+            // RNG
+            StdRand::with_seed(current_nanos()),
+            // Corpus that will be evolved, we keep a part in memory for performance
+            CachedOnDiskCorpus::new(options.output_dir.join("queue"), CORPUS_CACHE_SIZE)?,
+            // Corpus in which we store solutions (crashes in this example),
+            // on disk so the user can get them after stopping the fuzzer
+            OnDiskCorpus::new(options.output_dir.join("crashes"))?,
+            &mut feedback,
+            &mut objective,
+        )?,
+    };
+
+    // Create a dictionary if not existing
+    if let Some(tokens_file) = options.tokens_file {
+        if state.metadata().get::<Tokens>().is_none() {
+            state.add_metadata(Tokens::from_file(tokens_file)?);
+        }
+    }
+
+    // A minimization+queue policy to get testcasess from the corpus
+    let scheduler = IndexesLenTimeMinimizerScheduler::new(QueueScheduler::new());
+
+    // A fuzzer with feedbacks and a corpus scheduler
+    let mut fuzzer = StdFuzzer::new(scheduler, feedback, objective);
+
+    // The wrapped harness function, calling out to the LLVM-style harness
+    let mut harness = |input: &BytesInput| {
+        let target = input.target_bytes();
+        let buf = target.as_slice();
+        (harness_bytes)(buf);
+        ExitKind::Ok
+    };
+
+    // Create the executor for an in-process function with one observer for edge coverage and one for the execution time
+    let mut executor = ShadowExecutor::new(
+        TimeoutExecutor::new(
+            InProcessExecutor::new(
+                &mut harness,
+                tuple_list!(edges_observer, time_observer),
+                &mut fuzzer,
+                &mut state,
+                &mut mgr,
+            )?,
+            options.timeout,
+        ),
+        tuple_list!(cmplog_observer),
+    );
+
+    // In case the corpus is empty (on first run), reset
+    if state.corpus().count() < 1 {
+        if options.input_dirs.is_empty() {
+            // Generator of printable bytearrays of max size 32
+            let mut generator = RandBytesGenerator::new(32);
+
+            // Generate 8 initial inputs
+            state
+                .generate_initial_inputs(&mut fuzzer, &mut executor, &mut generator, &mut mgr, 8)
+                .expect("Failed to generate the initial corpus");
+            println!(
+                "We imported {} inputs from the generator.",
+                state.corpus().count()
+            );
+        } else {
+            println!("Loading from {:?}", options.input_dirs);
+            // Load from disk
+            state
+                .load_initial_inputs(&mut fuzzer, &mut executor, &mut mgr, options.input_dirs)
+                .unwrap_or_else(|_| {
+                    panic!("Failed to load initial corpus at {:?}", options.input_dirs);
+                });
+            println!("We imported {} inputs from disk.", state.corpus().count());
+        }
+    }
+
+    // Setup a tracing stage in which we log comparisons
+    let tracing = ShadowTracingStage::new(&mut executor);
+
+    // Setup a randomic Input2State stage
+    let i2s = StdMutationalStage::new(StdScheduledMutator::new(tuple_list!(I2SRandReplace::new())));
+
+    if options.tokens_file.is_some() {
+        // Setup a basic mutator
+        let mutator = StdScheduledMutator::new(havoc_mutations().merge(tokens_mutations()));
+        let mutational = StdMutationalStage::new(mutator);
+
+        // The order of the stages matter!
+        if options.use_cmplog {
+            let mut stages = tuple_list!(tracing, i2s, mutational);
+            if let Some(iters) = options.iterations {
+                fuzzer.fuzz_loop_for(&mut stages, &mut executor, &mut state, &mut mgr, iters)?;
+            } else {
+                fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
+            }
+        } else {
+            let mut stages = tuple_list!(mutational);
+            if let Some(iters) = options.iterations {
+                fuzzer.fuzz_loop_for(&mut stages, &mut executor, &mut state, &mut mgr, iters)?;
+            } else {
+                fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
+            }
+        }
+    } else {
+        // Setup a basic mutator
+        let mutator = StdScheduledMutator::new(havoc_mutations());
+        let mutational = StdMutationalStage::new(mutator);
+
+        // The order of the stages matter!
+        if options.use_cmplog {
+            let mut stages = tuple_list!(tracing, i2s, mutational);
+            if let Some(iters) = options.iterations {
+                fuzzer.fuzz_loop_for(&mut stages, &mut executor, &mut state, &mut mgr, iters)?;
+            } else {
+                fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
+            }
+        } else {
+            let mut stages = tuple_list!(mutational);
+            if let Some(iters) = options.iterations {
+                fuzzer.fuzz_loop_for(&mut stages, &mut executor, &mut state, &mut mgr, iters)?;
+            } else {
+                fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
+            }
+        }
+    }
+
+    // Only reached after the iterations, `fuzz_loop` never returns `Ok`
+    mgr.on_restart(&mut state)?;
+    mgr.send_exiting()?;
+    Ok(())
+}
+
 /// In-Memory fuzzing made easy.
 /// Use this sugar for scaling `libfuzzer`-style fuzzers.
 #[derive(TypedBuilder)]
@@ -73,7 +282,8 @@ where
     /// Bytes harness
     #[builder(setter(strip_option))]
     harness: Option<H>,
-    /// Fuzz `iterations` number of times, instead of indefinitely; implies use of `fuzz_loop_for`
+    /// Fuzz `iterations` number of times, instead of indefinitely; implies use of `fuzz_loop_for`.
+    /// Afterwards, each client stores its state and exits, and is not respawned.
     #[builder(default = None)]
     iterations: Option<u64>,
 }
@@ -111,7 +321,6 @@ where
     H: FnMut(&[u8]),
 {
     /// Run the fuzzer
-    #[allow(clippy::too_many_lines, clippy::similar_names)]
     pub fn run(&mut self) {
         let conf = match self.configuration.as_ref() {
             Some(name) => EventConfig::from_name(name),
@@ -120,219 +329,26 @@ where
 
         let timeout = Duration::from_secs(self.timeout.unwrap_or(DEFAULT_TIMEOUT_SECS));
 
-        let mut out_dir = self.output_dir.clone();
-        if fs::create_dir(&out_dir).is_err() {
-            println!("Out dir at {:?} already exists.", &out_dir);
-            assert!(
-                out_dir.is_dir(),
-                "Out dir at {:?} is not a valid directory!",
-                &out_dir
-            );
-        }
-        let mut crashes = out_dir.clone();
-        crashes.push("crashes");
-        out_dir.push("queue");
-
         let mut harness_bytes = self.harness.take().unwrap();
 
+        create_output_dir(&self.output_dir);
+        let options = InMemoryOptions {
+            output_dir: &self.output_dir,
+            input_dirs: self.input_dirs,
+            tokens_file: self.tokens_file.as_deref(),
+            timeout,
+            use_cmplog: self.use_cmplog.unwrap_or(false),
+            iterations: self.iterations,
+        };
+
         let shmem_provider = StdShMemProvider::new().expect("Failed to init shared memory");
 
         let monitor = MultiMonitor::new(|s| println!("{s}"));
 
-        let mut run_client = |state: Option<_>,
-                              mut mgr: LlmpRestartingEventManager<_, _>,
-                              _core_id| {
-            // Create an observation channel using the coverage map
-            let edges = unsafe { &mut EDGES_MAP[0..MAX_EDGES_NUM] };
-            let edges_observer = HitcountsMapObserver::new(StdMapObserver::new("edges", edges));
-
-            // Create an observation channel to keep track of the execution time
-            let time_observer = TimeObserver::new("time");
-
-            let cmplog = unsafe { &mut CMPLOG_MAP };
-            let cmplog_observer = CmpLogObserver::new("cmplog", cmplog, true);
-
-            // Feedback to rate the interestingness of an input
-            // This one is composed by two Feedbacks in OR
-            let mut feedback = feedback_or!(
-                // New maximization map feedback linked to the edges observer and the feedback state
-                MaxMapFeedback::new_tracking(&edges_observer, true, false),
-                // Time feedback, this one does not need a feedback state
-                TimeFeedback::new_with_observer(&time_observer)
-            );
-
-            // A feedback to choose if an input is a solution or not
-            let mut objective = feedback_or_fast!(CrashFeedback::new(), TimeoutFeedback::new());
-
-            // If not restarting, create a State from scratch
-            let mut state = state.unwrap_or_else(|| {
-                StdState::new(
-                    // RNG
-                    StdRand::with_seed(current_nanos()),
-                    // Corpus that will be evolved, we keep a part in memory for performance
-                    CachedOnDiskCorpus::new(out_dir.clone(), CORPUS_CACHE_SIZE).unwrap(),
-                    // Corpus in which we store solutions (crashes in this example),
-                    // on disk so the user can get them after stopping the fuzzer
-                    OnDiskCorpus::new(crashes.clone()).unwrap(),
-                    &mut feedback,
-                    &mut objective,
-                )
-                .unwrap()
-            });
-
-            // Create a dictionary if not existing
-            if let Some(tokens_file) = &self.tokens_file {
-                if state.metadata().get::<Tokens>().is_none() {
-                    state.add_metadata(Tokens::from_file(tokens_file)?);
-                }
-            }
-
-            // A minimization+queue policy to get testcasess from the corpus
-            let scheduler = IndexesLenTimeMinimizerScheduler::new(QueueScheduler::new());
-
-            // A fuzzer with feedbacks and a corpus scheduler
-            let mut fuzzer = StdFuzzer::new(scheduler, feedback, objective);
-
-            // The wrapped harness function, calling out to the LLVM-style harness
-            let mut harness = |input: &BytesInput| {
-                let target = input.target_bytes();
-                let buf = target.as_slice();
-                (harness_bytes)(buf);
-                ExitKind::Ok
-            };
-
-            // Create the executor for an in-process function with one observer for edge coverage and one for the execution time
-            let mut executor = ShadowExecutor::new(
-                TimeoutExecutor::new(
-                    InProcessExecutor::new(
-                        &mut harness,
-                        tuple_list!(edges_observer, time_observer),
-                        &mut fuzzer,
-                        &mut state,
-                        &mut mgr,
-                    )?,
-                    timeout,
-                ),
-                tuple_list!(cmplog_observer),
-            );
-
-            // In case the corpus is empty (on first run), reset
-            if state.corpus().count() < 1 {
-                if self.input_dirs.is_empty() {
-                    // Generator of printable bytearrays of max size 32
-                    let mut generator = RandBytesGenerator::new(32);
-
-                    // Generate 8 initial inputs
-                    state
-                        .generate_initial_inputs(
-                            &mut fuzzer,
-                            &mut executor,
-                            &mut generator,
-                            &mut mgr,
-                            8,
-                        )
-                        .expect("Failed to generate the initial corpus");
-                    println!(
-                        "We imported {} inputs from the generator.",
-                        state.corpus().count()
-                    );
-                } else {
-                    println!("Loading from {:?}", &self.input_dirs);
-                    // Load from disk
-                    state
-                        .load_initial_inputs(&mut fuzzer, &mut executor, &mut mgr, self.input_dirs)
-                        .unwrap_or_else(|_| {
-                            panic!("Failed to load initial corpus at {:?}", &self.input_dirs);
-                        });
-                    println!("We imported {} inputs from disk.", state.corpus().count());
-                }
-            }
-
-            // Setup a tracing stage in which we log comparisons
-            let tracing = ShadowTracingStage::new(&mut executor);
-
-            // Setup a randomic Input2State stage
-            let i2s = StdMutationalStage::new(StdScheduledMutator::new(tuple_list!(
-                I2SRandReplace::new()
-            )));
-
-            if self.tokens_file.is_some() {
-                // Setup a basic mutator
-                let mutator = StdScheduledMutator::new(havoc_mutations().merge(tokens_mutations()));
-                let mutational = StdMutationalStage::new(mutator);
-
-                // The order of the stages matter!
-                if self.use_cmplog.unwrap_or(false) {
-                    let mut stages = tuple_list!(tracing, i2s, mutational);
-                    if let Some(iters) = self.iterations {
-                        fuzzer.fuzz_loop_for(
-                            &mut stages,
-                            &mut executor,
-                            &mut state,
-                            &mut mgr,
-                            iters,
-                        )?;
-                        mgr.on_restart(&mut state)?;
-                        std::process::exit(0);
-                    } else {
-                        fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
-                    }
-                } else {
-                    let mut stages = tuple_list!(mutational);
-                    if let Some(iters) = self.iterations {
-                        fuzzer.fuzz_loop_for(
-                            &mut stages,
-                            &mut executor,
-                            &mut state,
-                            &mut mgr,
-                            iters,
-                        )?;
-                        mgr.on_restart(&mut state)?;
-                        std::process::exit(0);
-                    } else {
-                        fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
-                    }
-                }
-            } else {
-                // Setup a basic mutator
-                let mutator = StdScheduledMutator::new(havoc_mutations());
-                let mutational = StdMutationalStage::new(mutator);
-
-                // The order of the stages matter!
-                if self.use_cmplog.unwrap_or(false) {
-                    let mut stages = tuple_list!(tracing, i2s, mutational);
-                    if let Some(iters) = self.iterations {
-                        fuzzer.fuzz_loop_for(
-                            &mut stages,
-                            &mut executor,
-                            &mut state,
-                            &mut mgr,
-                            iters,
-                        )?;
-                        mgr.on_restart(&mut state)?;
-                        std::process::exit(0);
-                    } else {
-                        fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
-                    }
-                } else {
-                    let mut stages = tuple_list!(mutational);
-                    if let Some(iters) = self.iterations {
-                        fuzzer.fuzz_loop_for(
-                            &mut stages,
-                            &mut executor,
-                            &mut state,
-                            &mut mgr,
-                            iters,
-                        )?;
-                        mgr.on_restart(&mut state)?;
-                        std::process::exit(0);
-                    } else {
-                        fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
-                    }
-                }
-            }
-
-            Ok(())
+        let mut run_client = |state: Option<_>, mgr: InMemoryEventManager, _core_id| {
+            fuzz_in_memory(&mut harness_bytes, state, mgr, &options)?;
+            // Only reached after the iterations, the respawner does not spawn the next client
+            std::process::exit(0)
         };
 
         let launcher = Launcher::builder()
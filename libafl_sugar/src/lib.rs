@@ -58,6 +58,9 @@
     )
 )]
 
+pub mod builder;
+pub use builder::FuzzerBuilder;
+
 pub mod inmemory;
 pub use inmemory::InMemoryBytesCoverageSugar;
 
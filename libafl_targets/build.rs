@@ -26,6 +26,9 @@ fn main() {
     let acc_map_size: usize = option_env!("LIBAFL_ACCOUNTING_MAP_SIZE")
         .map_or(Ok(65536), str::parse)
         .expect("Could not parse LIBAFL_ACCOUNTING_MAP_SIZE");
+    let cmp_progress_map_size: usize = option_env!("LIBAFL_CMP_PROGRESS_MAP_SIZE")
+        .map_or(Ok(65536), str::parse)
+        .expect("Could not parse LIBAFL_CMP_PROGRESS_MAP_SIZE");
 
     write!(
         constants_file,
@@ -41,8 +44,15 @@ fn main() {
         pub const CMPLOG_MAP_H: usize = {};
         /// The size of the accounting maps
         pub const ACCOUNTING_MAP_SIZE: usize = {};
+        /// The size of the compare progress map
+        pub const CMP_PROGRESS_MAP_SIZE: usize = {};
 ",
-        edges_map_size, cmp_map_size, cmplog_map_w, cmplog_map_h, acc_map_size
+        edges_map_size,
+        cmp_map_size,
+        cmplog_map_w,
+        cmplog_map_h,
+        acc_map_size,
+        cmp_progress_map_size
     )
     .expect("Could not write file");
 
@@ -51,6 +61,7 @@ fn main() {
     println!("cargo:rerun-if-env-changed=LIBAFL_CMPLOG_MAP_W");
     println!("cargo:rerun-if-env-changed=LIBAFL_CMPLOG_MAP_H");
     println!("cargo:rerun-if-env-changed=LIBAFL_ACCOUNTING_MAP_SIZE");
+    println!("cargo:rerun-if-env-changed=LIBAFL_CMP_PROGRESS_MAP_SIZE");
 
     //std::env::set_var("CC", "clang");
     //std::env::set_var("CXX", "clang++");
@@ -80,6 +91,28 @@ fn main() {
             .compile("sancov_cmp");
     }
 
+    #[cfg(feature = "cmp_progress")]
+    {
+        println!("cargo:rerun-if-changed=src/cmp_progress.c");
+
+        let mut cmp_progress = cc::Build::new();
+
+        #[cfg(feature = "cmp_progress_interpose")]
+        {
+            cmp_progress
+                .define("CMP_PROGRESS_INTERPOSE", "1")
+                .flag_if_supported("-fno-builtin");
+        }
+
+        cmp_progress
+            .define(
+                "CMP_PROGRESS_MAP_SIZE",
+                Some(&*format!("{cmp_progress_map_size}")),
+            )
+            .file(src_dir.join("cmp_progress.c"))
+            .compile("cmp_progress");
+    }
+
     #[cfg(feature = "libfuzzer")]
     {
         println!("cargo:rerun-if-changed=src/libfuzzer.c");
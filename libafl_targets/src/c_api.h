@@ -0,0 +1,70 @@
+/*
+   The stable C API to embed the LibAFL fuzzing loop, see c_api.rs.
+   Enable the `c_api` feature of libafl_targets to get these symbols.
+*/
+
+#ifndef LIBAFL_C_API_H
+#define LIBAFL_C_API_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define LIBAFL_C_API_VERSION 1
+
+/* A libFuzzer-style harness, returning non-zero on failure */
+typedef int (*libafl_harness_fn)(const uint8_t *data, size_t size);
+
+/* An opaque fuzzer instance */
+typedef struct LibaflFuzzer libafl_fuzzer_t;
+
+typedef struct {
+  uint64_t executions;
+  uint64_t corpus_size;
+  uint64_t crashes;
+  uint64_t run_time_ms;
+} libafl_stats_t;
+
+/* Returns the LIBAFL_C_API_VERSION the library implements */
+uint32_t libafl_c_api_version(void);
+
+/* Creates a new fuzzer, seed 0 uses the current time.
+   Only one fuzzer may be alive per process, as the crash handlers and the
+   coverage map are global: free it before creating the next one.
+   Returns NULL on error, or if harness is NULL. */
+libafl_fuzzer_t *libafl_fuzzer_new(libafl_harness_fn harness, uint64_t seed,
+                                   uint64_t timeout_ms, const char *crash_dir);
+
+/* Like libafl_fuzzer_new, observing the map_size bytes at map instead of the
+   sancov coverage map. The map has to outlive the fuzzer.
+   Returns NULL on error, or if harness or map is NULL. */
+libafl_fuzzer_t *libafl_fuzzer_new_with_map(libafl_harness_fn harness,
+                                            uint64_t seed, uint64_t timeout_ms,
+                                            const char *crash_dir, uint8_t *map,
+                                            size_t map_size);
+
+/* Runs the input and adds it to the corpus. Returns 0 on success, -1 on error. */
+int libafl_fuzzer_add_seed(libafl_fuzzer_t *fuzzer, const uint8_t *data,
+                           size_t len);
+
+/* Runs iters fuzzing iterations. Returns 0 on success, -1 on error. */
+int libafl_fuzzer_run(libafl_fuzzer_t *fuzzer, uint64_t iters);
+
+/* Writes the current statistics to stats */
+void libafl_fuzzer_stats(const libafl_fuzzer_t *fuzzer, libafl_stats_t *stats);
+
+/* Gets the crash at idx, valid until the next call. Returns -1 if there is none. */
+int libafl_fuzzer_crash(libafl_fuzzer_t *fuzzer, size_t idx,
+                        const uint8_t **data, size_t *len);
+
+/* Frees the fuzzer, NULL is ignored */
+void libafl_fuzzer_free(libafl_fuzzer_t *fuzzer);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
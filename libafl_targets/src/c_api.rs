@@ -0,0 +1,528 @@
+//! A small, stable C API to embed the `LibAFL` fuzzing loop in C/C++ test drivers and tools.
+//!
+//! The functions are declared in `c_api.h`, next to this file. Link the harness, built with
+//! `-fsanitize-coverage=trace-pc-guard`, against a `staticlib` depending on `libafl_targets`
+//! with the `c_api` and `sancov_pcguard_hitcounts` features. A typical driver looks like:
+//!
+//! ```c
+//! libafl_fuzzer_t *fuzzer = libafl_fuzzer_new(LLVMFuzzerTestOneInput, 1234, 1000, "./crashes");
+//! libafl_fuzzer_add_seed(fuzzer, (const uint8_t *)"seed", 4);
+//! libafl_fuzzer_run(fuzzer, 10000);
+//!
+//! libafl_stats_t stats;
+//! libafl_fuzzer_stats(fuzzer, &stats);
+//! const uint8_t *data; size_t len;
+//! for (size_t i = 0; libafl_fuzzer_crash(fuzzer, i, &data, &len) == 0; i++) { /* report */ }
+//! libafl_fuzzer_free(fuzzer);
+//! ```
+//!
+//! Drivers with their own coverage instrumentation pass their map to `libafl_fuzzer_new_with_map` instead.
+//!
+//! A harness returning non-zero counts as a crash, and the fuzzer keeps going.
+//! Real crashes and timeouts get stored to the crash directory, then they end the process,
+//! as there is no restarting manager around the embedded fuzzer.
+//!
+//! Only one embedded fuzzer may be alive in a process at a time: the crash and timeout handlers,
+//! and the `EDGES_MAP` of the sancov instrumentation, are global.
+//! Free the fuzzer with `libafl_fuzzer_free` before creating the next one.
+
+use alloc::boxed::Box;
+use core::{ptr, slice, time::Duration};
+use std::{ffi::CStr, os::raw::c_char, path::Path};
+
+use libafl::{
+    bolts::{current_nanos, current_time, rands::StdRand, tuples::tuple_list, AsSlice},
+    corpus::{Corpus, InMemoryCorpus, OnDiskCorpus},
+    events::{ProgressReporter, SimpleEventManager},
+    executors::{inprocess::OwnedInProcessExecutor, ExitKind, TimeoutExecutor},
+    feedback_or, feedback_or_fast,
+    feedbacks::{CrashFeedback, MaxMapFeedback, TimeFeedback, TimeoutFeedback},
+    fuzzer::{Evaluator, Fuzzer, StdFuzzer},
+    inputs::{BytesInput, HasBytesVec, HasTargetBytes, UsesInput},
+    monitors::NopMonitor,
+    mutators::scheduled::{havoc_mutations, StdScheduledMutator},
+    observers::{HitcountsMapObserver, StdMapObserver, TimeObserver},
+    schedulers::{
+        powersched::PowerSchedule, IndexesLenTimeMinimizerScheduler, StdWeightedScheduler,
+    },
+    stages::{calibrate::CalibrationStage, power::StdPowerMutationalStage, StagesTuple},
+    state::{
+        HasClientPerfMonitor, HasCorpus, HasExecutions, HasMetadata, HasSolutions, HasStartTime,
+        StdState, UsesState,
+    },
+    Error,
+};
+
+use crate::std_edges_map_observer;
+
+/// The version of the C API, bumped on each incompatible change
+pub const LIBAFL_C_API_VERSION: u32 = 1;
+
+/// A `libFuzzer`-style harness: `int harness(const uint8_t *data, size_t size)`, returning non-zero on failure
+pub type LibaflHarnessFn = extern "C" fn(*const u8, usize) -> i32;
+
+/// The statistics of an embedded fuzzer, `libafl_stats_t` in C
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LibaflStats {
+    /// The number of executions of the harness
+    pub executions: u64,
+    /// The number of inputs in the corpus
+    pub corpus_size: u64,
+    /// The number of crashes found
+    pub crashes: u64,
+    /// The time since the creation of the fuzzer, in milliseconds
+    pub run_time_ms: u64,
+}
+
+/// The operations of an embedded fuzzer, independent of its concrete components
+trait EmbeddedFuzzer {
+    fn add_seed(&mut self, bytes: &[u8]) -> Result<(), Error>;
+    fn run(&mut self, iters: u64) -> Result<(), Error>;
+    fn stats(&self) -> LibaflStats;
+    fn crash(&mut self, idx: usize) -> Result<Option<(*const u8, usize)>, Error>;
+}
+
+/// All components of an embedded fuzzer
+struct Embedded<E, EM, ST, Z>
+where
+    Z: UsesState,
+{
+    state: Z::State,
+    executor: E,
+    mgr: EM,
+    stages: ST,
+    fuzzer: Z,
+}
+
+impl<E, EM, ST, Z> EmbeddedFuzzer for Embedded<E, EM, ST, Z>
+where
+    E: UsesState<State = Z::State>,
+    EM: ProgressReporter<State = Z::State>,
+    ST: StagesTuple<E, EM, Z::State, Z>,
+    Z: Fuzzer<E, EM, ST> + Evaluator<E, EM>,
+    Z::State: UsesInput<Input = BytesInput>
+        + HasClientPerfMonitor
+        + HasMetadata
+        + HasExecutions
+        + HasCorpus
+        + HasSolutions
+        + HasStartTime,
+{
+    fn add_seed(&mut self, bytes: &[u8]) -> Result<(), Error> {
+        self.fuzzer.add_input(
+            &mut self.state,
+            &mut self.executor,
+            &mut self.mgr,
+            BytesInput::new(bytes.to_vec()),
+        )?;
+        Ok(())
+    }
+
+    fn run(&mut self, iters: u64) -> Result<(), Error> {
+        self.fuzzer.fuzz_loop_for(
+            &mut self.stages,
+            &mut self.executor,
+            &mut self.state,
+            &mut self.mgr,
+            iters,
+        )?;
+        Ok(())
+    }
+
+    fn stats(&self) -> LibaflStats {
+        LibaflStats {
+            executions: *self.state.executions() as u64,
+            corpus_size: self.state.corpus().count() as u64,
+            crashes: self.state.solutions().count() as u64,
+            run_time_ms: current_time()
+                .saturating_sub(*self.state.start_time())
+                .as_millis() as u64,
+        }
+    }
+
+    fn crash(&mut self, idx: usize) -> Result<Option<(*const u8, usize)>, Error> {
+        if idx >= self.state.solutions().count() {
+            return Ok(None);
+        }
+        let mut testcase = self.state.solutions().get(idx)?.borrow_mut();
+        let bytes = testcase.load_input()?.bytes();
+        Ok(Some((bytes.as_ptr(), bytes.len())))
+    }
+}
+
+/// An embedded fuzzer, the opaque `libafl_fuzzer_t` in C
+pub struct LibaflFuzzer {
+    inner: Box<dyn EmbeddedFuzzer>,
+}
+
+impl core::fmt::Debug for LibaflFuzzer {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("LibaflFuzzer").finish_non_exhaustive()
+    }
+}
+
+impl LibaflFuzzer {
+    /// Creates a new embedded fuzzer for `harness`, storing crashes and timeouts in `crash_dir`
+    pub fn new(
+        harness: LibaflHarnessFn,
+        seed: u64,
+        timeout: Duration,
+        crash_dir: &Path,
+    ) -> Result<Self, Error> {
+        let edges_observer = unsafe { std_edges_map_observer("edges") };
+        Self::with_map_observer(edges_observer, harness, seed, timeout, crash_dir)
+    }
+
+    /// Creates a new embedded fuzzer for `harness`, observing the coverage in the map of `map_observer`,
+    /// instead of the map of the sancov instrumentation, and storing crashes and timeouts in `crash_dir`
+    pub fn with_map_observer(
+        map_observer: StdMapObserver<'static, u8>,
+        harness: LibaflHarnessFn,
+        seed: u64,
+        timeout: Duration,
+        crash_dir: &Path,
+    ) -> Result<Self, Error> {
+        let edges_observer = HitcountsMapObserver::new(map_observer);
+        let time_observer = TimeObserver::new("time");
+
+        let map_feedback = MaxMapFeedback::new_tracking(&edges_observer, true, false);
+        let calibration = CalibrationStage::new(&map_feedback);
+
+        let mut feedback = feedback_or!(
+            map_feedback,
+            TimeFeedback::new_with_observer(&time_observer)
+        );
+        let mut objective = feedback_or_fast!(CrashFeedback::new(), TimeoutFeedback::new());
+
+        let mut state = StdState::new(
+            StdRand::with_seed(seed),
+            InMemoryCorpus::new(),
+            OnDiskCorpus::new(crash_dir)?,
+            &mut feedback,
+            &mut objective,
+        )?;
+        let mut mgr = SimpleEventManager::new(NopMonitor::new());
+
+        let mutator = StdScheduledMutator::new(havoc_mutations());
+        let power = StdPowerMutationalStage::new(mutator, &edges_observer);
+        let stages = tuple_list!(calibration, power);
+
+        let scheduler = IndexesLenTimeMinimizerScheduler::new(StdWeightedScheduler::with_schedule(
+            PowerSchedule::FAST,
+        ));
+        let mut fuzzer = StdFuzzer::new(scheduler, feedback, objective);
+
+        let harness = move |input: &BytesInput| {
+            let target = input.target_bytes();
+            let buf = target.as_slice();
+            if harness(buf.as_ptr(), buf.len()) == 0 {
+                ExitKind::Ok
+            } else {
+                ExitKind::Crash
+            }
+        };
+        let executor = TimeoutExecutor::new(
+            OwnedInProcessExecutor::new(
+                Box::new(harness),
+                tuple_list!(edges_observer, time_observer),
+                &mut fuzzer,
+                &mut state,
+                &mut mgr,
+            )?,
+            timeout,
+        );
+
+        Ok(Self {
+            inner: Box::new(Embedded {
+                state,
+                executor,
+                mgr,
+                stages,
+                fuzzer,
+            }),
+        })
+    }
+}
+
+/// Prints the error, and turns the result into the C return code, `0` on success, `-1` on error
+fn c_result(res: Result<(), Error>) -> i32 {
+    match res {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            -1
+        }
+    }
+}
+
+/// Returns the [`LIBAFL_C_API_VERSION`] this library implements
+#[no_mangle]
+pub extern "C" fn libafl_c_api_version() -> u32 {
+    LIBAFL_C_API_VERSION
+}
+
+/// Creates a new fuzzer for `harness`, seeded with `seed`, or the current time if `seed` is `0`.
+/// Each run may take up to `timeout_ms`, crashes and timeouts get stored in the directory `crash_dir`.
+/// Returns `NULL` on error, or if `harness` is `NULL`.
+///
+/// # Safety
+/// `crash_dir` has to be a valid, nul-terminated string.
+/// No other fuzzer of this API may be alive in this process, they share the global handlers and coverage map.
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_new(
+    harness: Option<LibaflHarnessFn>,
+    seed: u64,
+    timeout_ms: u64,
+    crash_dir: *const c_char,
+) -> *mut LibaflFuzzer {
+    new_fuzzer(harness, crash_dir, |harness, crash_dir| {
+        LibaflFuzzer::new(
+            harness,
+            if seed == 0 { current_nanos() } else { seed },
+            Duration::from_millis(timeout_ms),
+            crash_dir,
+        )
+    })
+}
+
+/// Creates a new fuzzer like [`libafl_fuzzer_new`], observing the coverage in the `map_size` bytes at `map`,
+/// e.g., filled by custom instrumentation, instead of the map of the sancov instrumentation.
+/// Returns `NULL` on error, or if `harness` or `map` is `NULL`.
+///
+/// # Safety
+/// `crash_dir` has to be a valid, nul-terminated string.
+/// `map` has to point to `map_size` bytes, valid for reads and writes as long as the fuzzer is alive.
+/// No other fuzzer of this API may be alive in this process, they share the global handlers.
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_new_with_map(
+    harness: Option<LibaflHarnessFn>,
+    seed: u64,
+    timeout_ms: u64,
+    crash_dir: *const c_char,
+    map: *mut u8,
+    map_size: usize,
+) -> *mut LibaflFuzzer {
+    new_fuzzer(harness, crash_dir, |harness, crash_dir| {
+        if map.is_null() || map_size == 0 {
+            return Err(Error::illegal_argument("The coverage map is empty"));
+        }
+        LibaflFuzzer::with_map_observer(
+            StdMapObserver::new_from_ptr("edges", map, map_size),
+            harness,
+            if seed == 0 { current_nanos() } else { seed },
+            Duration::from_millis(timeout_ms),
+            crash_dir,
+        )
+    })
+}
+
+/// Creates a fuzzer with `create` for `harness` and the nul-terminated `crash_dir`, returning `NULL` on error
+unsafe fn new_fuzzer<F>(
+    harness: Option<LibaflHarnessFn>,
+    crash_dir: *const c_char,
+    create: F,
+) -> *mut LibaflFuzzer
+where
+    F: FnOnce(LibaflHarnessFn, &Path) -> Result<LibaflFuzzer, Error>,
+{
+    let res = harness
+        .ok_or_else(|| Error::illegal_argument("The harness is NULL"))
+        .and_then(|harness| {
+            let crash_dir = CStr::from_ptr(crash_dir)
+                .to_str()
+                .map_err(|_| Error::illegal_argument("crash_dir is not valid UTF-8"))?;
+            create(harness, Path::new(crash_dir))
+        });
+    match res {
+        Ok(fuzzer) => Box::into_raw(Box::new(fuzzer)),
+        Err(err) => {
+            eprintln!("Error: {err}");
+            ptr::null_mut()
+        }
+    }
+}
+
+/// Runs the `len` bytes at `data` and adds them to the corpus. Returns `0` on success, `-1` on error.
+///
+/// # Safety
+/// `fuzzer` has to come from [`libafl_fuzzer_new`] or [`libafl_fuzzer_new_with_map`], `data` has to point to `len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_add_seed(
+    fuzzer: *mut LibaflFuzzer,
+    data: *const u8,
+    len: usize,
+) -> i32 {
+    let bytes = if len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    };
+    c_result((*fuzzer).inner.add_seed(bytes))
+}
+
+/// Runs `iters` fuzzing iterations. The corpus must not be empty.
+/// Returns `0` on success, `-1` on error.
+///
+/// # Safety
+/// `fuzzer` has to come from [`libafl_fuzzer_new`] or [`libafl_fuzzer_new_with_map`].
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_run(fuzzer: *mut LibaflFuzzer, iters: u64) -> i32 {
+    c_result((*fuzzer).inner.run(iters))
+}
+
+/// Writes the current statistics of `fuzzer` to `stats`.
+///
+/// # Safety
+/// `fuzzer` has to come from [`libafl_fuzzer_new`] or [`libafl_fuzzer_new_with_map`], `stats` has to be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_stats(fuzzer: *const LibaflFuzzer, stats: *mut LibaflStats) {
+    *stats = (*fuzzer).inner.stats();
+}
+
+/// Gets the crash at `idx`, counting from `0`, to iterate over all crashes found so far.
+/// The data stays valid until the next call to any other function of this API.
+/// Returns `0` on success, `-1` if there is no crash at `idx`.
+///
+/// # Safety
+/// `fuzzer` has to come from [`libafl_fuzzer_new`] or [`libafl_fuzzer_new_with_map`], `data` and `len` have to be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_crash(
+    fuzzer: *mut LibaflFuzzer,
+    idx: usize,
+    data: *mut *const u8,
+    len: *mut usize,
+) -> i32 {
+    match (*fuzzer).inner.crash(idx) {
+        Ok(Some((crash_data, crash_len))) => {
+            *data = crash_data;
+            *len = crash_len;
+            0
+        }
+        Ok(None) => -1,
+        Err(err) => {
+            eprintln!("Error: {err}");
+            -1
+        }
+    }
+}
+
+/// Frees `fuzzer`, which must not be used afterwards. Does nothing for `NULL`.
+///
+/// # Safety
+/// `fuzzer` has to come from [`libafl_fuzzer_new`] or [`libafl_fuzzer_new_with_map`], and must not have been freed before.
+#[no_mangle]
+pub unsafe extern "C" fn libafl_fuzzer_free(fuzzer: *mut LibaflFuzzer) {
+    if !fuzzer.is_null() {
+        drop(Box::from_raw(fuzzer));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::format;
+    use core::{
+        ptr::{self, addr_of_mut},
+        slice,
+    };
+    use std::{env, ffi::CString, fs, process};
+
+    use serial_test::serial;
+
+    use super::{
+        libafl_fuzzer_add_seed, libafl_fuzzer_crash, libafl_fuzzer_free, libafl_fuzzer_new,
+        libafl_fuzzer_new_with_map, libafl_fuzzer_run, libafl_fuzzer_stats, LibaflHarnessFn,
+        LibaflStats,
+    };
+
+    /// The coverage map of [`map_harness`]
+    static mut MAP: [u8; 16] = [0; 16];
+
+    /// Fails on all inputs starting with `!`
+    extern "C" fn harness(data: *const u8, size: usize) -> i32 {
+        let buf = unsafe { slice::from_raw_parts(data, size) };
+        i32::from(buf.first() == Some(&b'!'))
+    }
+
+    /// Covers one entry of [`MAP`] per input length
+    extern "C" fn map_harness(_data: *const u8, size: usize) -> i32 {
+        unsafe {
+            MAP[size % 16] = 1;
+        }
+        0
+    }
+
+    #[test]
+    #[serial]
+    fn test_c_api_fuzzer() {
+        let dir = env::temp_dir().join(format!("libafl_c_api_{}", process::id()));
+        let crash_dir = CString::new(dir.to_str().unwrap()).unwrap();
+        unsafe {
+            assert!(libafl_fuzzer_new(None, 1, 1000, crash_dir.as_ptr()).is_null());
+
+            let fuzzer = libafl_fuzzer_new(Some(harness), 1, 1000, crash_dir.as_ptr());
+            assert!(!fuzzer.is_null());
+            assert_eq!(libafl_fuzzer_add_seed(fuzzer, b"seed".as_ptr(), 4), 0);
+            assert_eq!(libafl_fuzzer_run(fuzzer, 100), 0);
+
+            let mut stats = LibaflStats::default();
+            libafl_fuzzer_stats(fuzzer, addr_of_mut!(stats));
+            assert!(stats.executions > 100);
+            assert!(stats.corpus_size >= 1);
+            assert!(stats.crashes >= 1);
+
+            let mut data = ptr::null();
+            let mut len = 0;
+            assert_eq!(
+                libafl_fuzzer_crash(fuzzer, 0, addr_of_mut!(data), addr_of_mut!(len)),
+                0
+            );
+            assert_eq!(slice::from_raw_parts(data, len).first(), Some(&b'!'));
+            let crashes = usize::try_from(stats.crashes).unwrap();
+            assert_eq!(
+                libafl_fuzzer_crash(fuzzer, crashes, addr_of_mut!(data), addr_of_mut!(len)),
+                -1
+            );
+
+            libafl_fuzzer_free(fuzzer);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[serial]
+    fn test_c_api_fuzzer_with_map() {
+        let dir = env::temp_dir().join(format!("libafl_c_api_map_{}", process::id()));
+        let crash_dir = CString::new(dir.to_str().unwrap()).unwrap();
+        unsafe {
+            let map = addr_of_mut!(MAP).cast::<u8>();
+            let harness = Some(map_harness as LibaflHarnessFn);
+            assert!(libafl_fuzzer_new_with_map(
+                harness,
+                1,
+                1000,
+                crash_dir.as_ptr(),
+                ptr::null_mut(),
+                16
+            )
+            .is_null());
+            assert!(
+                libafl_fuzzer_new_with_map(harness, 1, 1000, crash_dir.as_ptr(), map, 0).is_null()
+            );
+
+            let fuzzer = libafl_fuzzer_new_with_map(harness, 1, 1000, crash_dir.as_ptr(), map, 16);
+            assert!(!fuzzer.is_null());
+            assert_eq!(libafl_fuzzer_add_seed(fuzzer, b"seed".as_ptr(), 4), 0);
+            assert_eq!(libafl_fuzzer_run(fuzzer, 100), 0);
+
+            // Inputs of new lengths cover new entries of the map, and get added to the corpus
+            let mut stats = LibaflStats::default();
+            libafl_fuzzer_stats(fuzzer, addr_of_mut!(stats));
+            assert!(stats.corpus_size > 1);
+            assert_eq!(stats.crashes, 0);
+
+            libafl_fuzzer_free(fuzzer);
+        }
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,173 @@
+#ifdef CMP_PROGRESS_INTERPOSE
+  #ifndef _GNU_SOURCE
+    #define _GNU_SOURCE
+  #endif
+#endif
+
+#include "common.h"
+
+#include <stddef.h>
+
+#ifndef CMP_PROGRESS_MAP_SIZE
+  #define CMP_PROGRESS_MAP_SIZE 65536
+#endif
+
+extern uint8_t libafl_cmp_progress_map[CMP_PROGRESS_MAP_SIZE];
+
+// Record how many leading bytes matched at the given call site.
+// Each call site keeps the longest prefix seen so far in this run, saturating at 255.
+static inline void __libafl_cmp_progress(uintptr_t k, size_t matched) {
+  k = (k >> 4) ^ (k << 8);
+  k &= CMP_PROGRESS_MAP_SIZE - 1;
+
+  uint8_t progress = matched > 255 ? 255 : (uint8_t)matched;
+  libafl_cmp_progress_map[k] = MAX(libafl_cmp_progress_map[k], progress);
+}
+
+static inline size_t __libafl_mem_prefix(const uint8_t *s1, const uint8_t *s2,
+                                         size_t n) {
+  size_t i = 0;
+  while (i < n && s1[i] == s2[i]) {
+    i++;
+  }
+  return i;
+}
+
+static inline size_t __libafl_str_prefix(const uint8_t *s1, const uint8_t *s2,
+                                         size_t n) {
+  size_t i = 0;
+  while (i < n && s1[i] && s1[i] == s2[i]) {
+    i++;
+  }
+  return i;
+}
+
+// Called by the sanitizer runtimes (and libFuzzer) from their interceptors.
+
+void __sanitizer_weak_hook_memcmp(void *caller_pc, const void *s1,
+                                  const void *s2, size_t n, int result) {
+  if (result == 0) { return; }
+  __libafl_cmp_progress((uintptr_t)caller_pc,
+                        __libafl_mem_prefix(s1, s2, n));
+}
+
+void __sanitizer_weak_hook_strncmp(void *caller_pc, const char *s1,
+                                   const char *s2, size_t n, int result) {
+  if (result == 0) { return; }
+  __libafl_cmp_progress(
+      (uintptr_t)caller_pc,
+      __libafl_str_prefix((const uint8_t *)s1, (const uint8_t *)s2, n));
+}
+
+void __sanitizer_weak_hook_strcmp(void *caller_pc, const char *s1,
+                                  const char *s2, int result) {
+  if (result == 0) { return; }
+  __libafl_cmp_progress(
+      (uintptr_t)caller_pc,
+      __libafl_str_prefix((const uint8_t *)s1, (const uint8_t *)s2, SIZE_MAX));
+}
+
+#ifdef CMP_PROGRESS_INTERPOSE
+
+  #ifdef _WIN32
+    #error "Interposing the compare functions is only supported on unix"
+  #endif
+
+  #include <dlfcn.h>
+
+  #ifndef CMP_PROGRESS_MAX_MODULES
+    #define CMP_PROGRESS_MAX_MODULES 64
+  #endif
+
+// Without a sanitizer runtime, nobody calls the hooks above.
+// Interpose the libc functions instead. They are weak, so a sanitizer interceptor,
+// or any other definition in the target, takes precedence.
+// They are called from everywhere, e.g., from libc itself, or the fuzzer,
+// so only the calls from the instrumented modules get passed on to the hooks.
+// Must be compiled with -fno-builtin, else the loops get turned back into calls to these functions.
+
+// The load addresses of the instrumented modules
+static uintptr_t __libafl_cmp_modules[CMP_PROGRESS_MAX_MODULES];
+static size_t    __libafl_cmp_modules_len;
+
+// The caller looked up last, as dladdr is slow
+static uintptr_t __libafl_cmp_last_pc;
+static int       __libafl_cmp_last_instrumented;
+
+// dladdr may compare strings itself
+static MAYBE_THREAD_LOCAL int __libafl_cmp_in_lookup;
+
+static uintptr_t __libafl_module_base(uintptr_t addr) {
+  Dl_info info;
+  if (!dladdr((void *)addr, &info)) { return 0; }
+  return (uintptr_t)info.dli_fbase;
+}
+
+// Registers the module containing `addr` as instrumented.
+// Called by the coverage runtimes, when they initialize the counters of a module.
+void __libafl_cmp_progress_add_module(const void *addr) {
+  uintptr_t base = __libafl_module_base((uintptr_t)addr);
+  if (!base) { return; }
+  for (size_t i = 0; i < __libafl_cmp_modules_len; i++) {
+    if (__libafl_cmp_modules[i] == base) { return; }
+  }
+  if (__libafl_cmp_modules_len < CMP_PROGRESS_MAX_MODULES) {
+    __libafl_cmp_modules[__libafl_cmp_modules_len++] = base;
+  }
+  __libafl_cmp_last_pc = 0;
+}
+
+static int __libafl_cmp_instrumented(uintptr_t caller_pc) {
+  if (caller_pc == __libafl_cmp_last_pc) {
+    return __libafl_cmp_last_instrumented;
+  }
+  if (__libafl_cmp_in_lookup) { return 0; }
+
+  __libafl_cmp_in_lookup = 1;
+  uintptr_t base = __libafl_module_base(caller_pc);
+  __libafl_cmp_in_lookup = 0;
+
+  int instrumented = 0;
+  for (size_t i = 0; base && i < __libafl_cmp_modules_len; i++) {
+    if (__libafl_cmp_modules[i] == base) { instrumented = 1; }
+  }
+  __libafl_cmp_last_pc = caller_pc;
+  __libafl_cmp_last_instrumented = instrumented;
+  return instrumented;
+}
+
+__attribute__((weak)) int memcmp(const void *s1, const void *s2, size_t n) {
+  const uint8_t *a = s1;
+  const uint8_t *b = s2;
+  size_t         i = __libafl_mem_prefix(a, b, n);
+  int            result = i == n ? 0 : (int)a[i] - (int)b[i];
+  if (result && __libafl_cmp_instrumented(RETADDR)) {
+    __sanitizer_weak_hook_memcmp((void *)RETADDR, s1, s2, n, result);
+  }
+  return result;
+}
+
+__attribute__((weak)) int strncmp(const char *s1, const char *s2, size_t n) {
+  const uint8_t *a = (const uint8_t *)s1;
+  const uint8_t *b = (const uint8_t *)s2;
+  size_t         i = __libafl_str_prefix(a, b, n);
+  int            result =
+      (i == n || a[i] == b[i]) ? 0 : (int)a[i] - (int)b[i];
+  if (result && __libafl_cmp_instrumented(RETADDR)) {
+    __sanitizer_weak_hook_strncmp((void *)RETADDR, s1, s2, n, result);
+  }
+  return result;
+}
+
+__attribute__((weak)) int strcmp(const char *s1, const char *s2) {
+  const uint8_t *a = (const uint8_t *)s1;
+  const uint8_t *b = (const uint8_t *)s2;
+  size_t         i = __libafl_str_prefix(a, b, SIZE_MAX);
+  int            result = a[i] == b[i] ? 0 : (int)a[i] - (int)b[i];
+  if (result && __libafl_cmp_instrumented(RETADDR)) {
+    __sanitizer_weak_hook_strcmp((void *)RETADDR, s1, s2, result);
+  }
+  return result;
+}
+
+#endif
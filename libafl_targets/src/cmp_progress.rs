@@ -0,0 +1,83 @@
+//! Compare progress for `memcmp`, `strcmp`, and `strncmp`.
+//!
+//! For each call site, the map holds the longest prefix that matched in the current run.
+//! A [`CmpProgressFeedback`] on this map rewards inputs that get one byte closer to the expected string,
+//! so string-guarded branches become solvable without full cmplog instrumentation.
+//!
+//! The hooks are called by the sanitizer runtimes. For targets without a sanitizer,
+//! enable the `cmp_progress_interpose` feature to interpose the libc functions themselves, on `unix`.
+//! The interposed functions only pass the calls from instrumented modules on to the hooks,
+//! the coverage runtimes register each module when they initialize its counters.
+
+#[cfg(feature = "cmp_progress_interpose")]
+use core::ffi::c_void;
+
+use libafl::{feedbacks::MaxMapFeedback, observers::StdMapObserver};
+
+use crate::CMP_PROGRESS_MAP_SIZE;
+
+/// The name of the observer returned by [`cmp_progress_observer`]
+pub const CMP_PROGRESS_OBSERVER_NAME: &str = "cmp_progress";
+
+/// The compare progress map for the current `LibAFL` target
+#[no_mangle]
+pub static mut libafl_cmp_progress_map: [u8; CMP_PROGRESS_MAP_SIZE] = [0; CMP_PROGRESS_MAP_SIZE];
+
+pub use libafl_cmp_progress_map as CMP_PROGRESS_MAP;
+
+#[cfg(feature = "cmp_progress_interpose")]
+extern "C" {
+    /// Marks the module containing `addr` as instrumented, so the interposed compare functions report the calls from it.
+    /// The coverage runtimes call this for each module.
+    pub fn __libafl_cmp_progress_add_module(addr: *const c_void);
+}
+
+/// A feedback that reports inputs as interesting if they matched a longer prefix at any call site
+pub type CmpProgressFeedback<S> = MaxMapFeedback<StdMapObserver<'static, u8>, S, u8>;
+
+/// Creates a new [`StdMapObserver`] on the [`CMP_PROGRESS_MAP`], named [`CMP_PROGRESS_OBSERVER_NAME`].
+///
+/// # Safety
+/// The observer holds a mutable reference to the global map, there must be only one of it.
+#[must_use]
+pub unsafe fn cmp_progress_observer() -> StdMapObserver<'static, u8> {
+    StdMapObserver::new(CMP_PROGRESS_OBSERVER_NAME, &mut CMP_PROGRESS_MAP)
+}
+
+#[cfg(all(test, feature = "cmp_progress_interpose"))]
+mod tests {
+    use core::{ffi::c_void, hint::black_box};
+
+    use super::{__libafl_cmp_progress_add_module, CMP_PROGRESS_MAP};
+
+    extern "C" {
+        fn memcmp(s1: *const c_void, s2: *const c_void, n: usize) -> i32;
+    }
+
+    #[inline(never)]
+    fn compare(s1: &[u8], s2: &[u8]) -> i32 {
+        unsafe {
+            memcmp(
+                black_box(s1.as_ptr()).cast(),
+                black_box(s2.as_ptr()).cast(),
+                s1.len(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_interpose_instrumented_only() {
+        unsafe {
+            CMP_PROGRESS_MAP.fill(0);
+
+            // The test itself is not instrumented, so its compares are not reported
+            assert!(compare(b"abcd", b"abXd") < 0);
+            assert!(CMP_PROGRESS_MAP.iter().all(|progress| *progress == 0));
+
+            __libafl_cmp_progress_add_module(compare as *const c_void);
+            assert!(compare(b"abcd", b"abXd") < 0);
+            assert_eq!(compare(b"abcd", b"abcd"), 0);
+            assert_eq!(CMP_PROGRESS_MAP.iter().max(), Some(&2));
+        }
+    }
+}
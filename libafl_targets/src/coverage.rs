@@ -1,5 +1,8 @@
 //! Coverage maps as static mut array
 
+use alloc::string::String;
+
+use libafl::observers::StdMapObserver;
 #[cfg(any(target_os = "linux", target_vendor = "apple"))]
 use libafl::{mutators::Tokens, Error};
 
@@ -89,3 +92,30 @@ pub fn edges_max_num() -> usize {
         }
     }
 }
+
+/// Gets the part of the [`EDGES_MAP`] used by the instrumented target, to be wrapped in a map observer.
+/// Call this after the target got initialized, else the whole map is returned.
+///
+/// # Safety
+/// Returns a mutable reference to the global map, there should only be one alive at a time.
+#[must_use]
+pub unsafe fn edges_map_mut<'a>() -> &'a mut [u8] {
+    let len = if MAX_EDGES_NUM > 0 && MAX_EDGES_NUM <= EDGES_MAP.len() {
+        MAX_EDGES_NUM
+    } else {
+        EDGES_MAP.len()
+    };
+    &mut EDGES_MAP[..len]
+}
+
+/// Creates a new [`StdMapObserver`] on the part of the [`EDGES_MAP`] used by the instrumented target.
+///
+/// # Safety
+/// The observer holds a mutable reference to the global map, there should only be one alive at a time.
+#[must_use]
+pub unsafe fn std_edges_map_observer<'a, S>(name: S) -> StdMapObserver<'a, u8>
+where
+    S: Into<String>,
+{
+    StdMapObserver::new(name, edges_map_mut())
+}
@@ -79,11 +79,26 @@ pub mod sancov_cmp;
 #[cfg(any(feature = "sancov_cmplog", feature = "sancov_value_profile"))]
 pub use sancov_cmp::*;
 
+#[cfg(feature = "cmp_progress")]
+pub mod cmp_progress;
+#[cfg(feature = "cmp_progress")]
+pub use cmp_progress::*;
+
 #[cfg(feature = "libfuzzer")]
 pub mod libfuzzer;
 #[cfg(feature = "libfuzzer")]
 pub use libfuzzer::*;
 
+#[cfg(feature = "libfuzzer_main")]
+pub mod libfuzzer_main;
+#[cfg(feature = "libfuzzer_main")]
+pub use libfuzzer_main::*;
+
+#[cfg(feature = "c_api")]
+pub mod c_api;
+#[cfg(feature = "c_api")]
+pub use c_api::*;
+
 #[cfg(feature = "sancov_8bit")]
 pub mod sancov_8bit;
 #[cfg(feature = "sancov_8bit")]
@@ -4,6 +4,8 @@
 
 use alloc::{string::String, vec::Vec};
 
+use libafl::Error;
+
 extern "C" {
     /// int LLVMFuzzerTestOneInput(const uint8_t *Data, size_t Size)
     fn LLVMFuzzerTestOneInput(data: *const u8, size: usize) -> i32;
@@ -30,6 +32,16 @@ pub fn libfuzzer_initialize(args: &[String]) -> i32 {
     }
 }
 
+/// Calls the (native) libfuzzer initialize function, like [`libfuzzer_initialize`],
+/// but turns the `-1` returned by a failing `LLVMFuzzerInitialize` into an [`Error`].
+/// Pass it to a [`libafl::executors::HarnessInit`] to retry, or skip the client, on failure.
+pub fn libfuzzer_initialize_checked(args: &[String]) -> Result<(), Error> {
+    match libfuzzer_initialize(args) {
+        -1 => Err(Error::unknown("LLVMFuzzerInitialize failed with -1")),
+        _ => Ok(()),
+    }
+}
+
 /// Call a single input of a libfuzzer-style cpp-harness
 /// # Safety
 /// Calls the libfuzzer harness. We actually think the target is unsafe and crashes eventually, that's why we do all this fuzzing.
@@ -0,0 +1,331 @@
+//! A drop-in `main` for harnesses written for [`Libfuzzer`](https://www.llvm.org/docs/LibFuzzer.html).
+//!
+//! Link a harness that exports `LLVMFuzzerTestOneInput` (and, optionally, `LLVMFuzzerInitialize`)
+//! against `libafl_targets` with the `libfuzzer_main` feature. The weak `main` in `libfuzzer.c`
+//! then calls [`libafl_main`], which runs a default `LibAFL` fuzzer, without any per-target code.
+//! The archive has to be linked with `--whole-archive`, as `libafl_cc` does, else the linker may pick the
+//! weak, empty `libafl_main` instead. Don't enable this feature if your fuzzer defines its own `libafl_main`.
+//!
+//! The command line follows `libFuzzer`:
+//! - `./harness [-flag=value ...] [corpus_dir ...]` fuzzes, starting from the inputs in the `corpus_dir`s.
+//! - `./harness [-flag=value ...] file ...` runs each file once, to reproduce crashes.
+//!
+//! Supported flags are `-artifact_prefix`, `-dict`, `-init_retries`, `-seed`, and `-timeout`, others are ignored.
+//! The corpus directories are only read. New inputs are written to the directory given with the
+//! `LibAFL`-specific `-out_dir` flag, or kept in memory without it.
+
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Debug, time::Duration};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use libafl::{
+    bolts::{
+        current_nanos,
+        rands::StdRand,
+        shmem::{ShMemProvider, StdShMemProvider},
+        tuples::{tuple_list, Merge},
+        AsSlice,
+    },
+    corpus::{CachedOnDiskCorpus, Corpus, InMemoryCorpus, OnDiskCorpus},
+    events::SimpleRestartingEventManager,
+    executors::{inprocess::InProcessExecutor, ExitKind, HarnessInit, TimeoutExecutor},
+    feedback_or, feedback_or_fast,
+    feedbacks::{CrashFeedback, MaxMapFeedback, TimeFeedback, TimeoutFeedback},
+    fuzzer::{Fuzzer, StdFuzzer},
+    inputs::{BytesInput, HasTargetBytes},
+    monitors::SimpleMonitor,
+    mutators::{
+        scheduled::{havoc_mutations, tokens_mutations, StdScheduledMutator},
+        token_mutations::Tokens,
+    },
+    observers::{HitcountsMapObserver, TimeObserver},
+    schedulers::{
+        powersched::PowerSchedule, IndexesLenTimeMinimizerScheduler, StdWeightedScheduler,
+    },
+    stages::{calibrate::CalibrationStage, power::StdPowerMutationalStage},
+    state::{HasCorpus, HasMetadata, StdState},
+    Error, Evaluator,
+};
+use serde::{de::DeserializeOwned, Serialize};
+
+#[cfg(any(target_os = "linux", target_vendor = "apple"))]
+use crate::autotokens;
+use crate::{libfuzzer_initialize_checked, libfuzzer_test_one_input, std_edges_map_observer};
+
+/// The default timeout per run, as in `libFuzzer`
+pub const LIBFUZZER_DEFAULT_TIMEOUT: Duration = Duration::from_secs(1200);
+
+/// The options of the drop-in `libFuzzer` main, parsed from a `libFuzzer` command line
+#[derive(Debug, Clone)]
+pub struct LibfuzzerOptions {
+    /// The corpus directories to load the initial inputs from
+    pub corpus_dirs: Vec<PathBuf>,
+    /// The directory to write new inputs to, if any. It is never one of the `corpus_dirs`.
+    pub out_dir: Option<PathBuf>,
+    /// Single inputs to run once, instead of fuzzing
+    pub inputs: Vec<PathBuf>,
+    /// The directory to store crashes and timeouts in
+    pub artifact_dir: PathBuf,
+    /// The dictionaries to load tokens from
+    pub dicts: Vec<PathBuf>,
+    /// The seed for the random number generator
+    pub seed: Option<u64>,
+    /// The timeout of a single run
+    pub timeout: Duration,
+    /// How to run `LLVMFuzzerInitialize`, aborting on failure by default
+    pub init: HarnessInit,
+}
+
+impl LibfuzzerOptions {
+    /// Parses a `libFuzzer` command line. The first argument is the program name.
+    pub fn parse(args: &[String]) -> Result<Self, Error> {
+        let mut options = Self {
+            corpus_dirs: vec![],
+            out_dir: None,
+            inputs: vec![],
+            artifact_dir: PathBuf::from("."),
+            dicts: vec![],
+            seed: None,
+            timeout: LIBFUZZER_DEFAULT_TIMEOUT,
+            init: HarnessInit::default(),
+        };
+
+        for arg in args.iter().skip(1) {
+            if let Some(flag) = arg.strip_prefix('-') {
+                let (name, value) = flag.split_once('=').unwrap_or((flag, ""));
+                match name {
+                    "artifact_prefix" => options.artifact_dir = PathBuf::from(value),
+                    "dict" => options.dicts.push(PathBuf::from(value)),
+                    "init_retries" => options.init = HarnessInit::with_retries(value.parse()?),
+                    "out_dir" => options.out_dir = Some(PathBuf::from(value)),
+                    "seed" => options.seed = Some(value.parse()?),
+                    "timeout" => options.timeout = Duration::from_secs(value.parse()?),
+                    _ => println!("Ignoring unsupported libFuzzer flag -{name}"),
+                }
+            } else if Path::new(arg).is_dir() {
+                options.corpus_dirs.push(PathBuf::from(arg));
+            } else {
+                options.inputs.push(PathBuf::from(arg));
+            }
+        }
+
+        if !options.corpus_dirs.is_empty() && !options.inputs.is_empty() {
+            return Err(Error::illegal_argument(
+                "Pass either corpus directories to fuzz, or files to run, not both",
+            ));
+        }
+        if let Some(out_dir) = &options.out_dir {
+            if options.corpus_dirs.contains(out_dir) {
+                return Err(Error::illegal_argument(format!(
+                    "The output directory {} must not be one of the corpus directories",
+                    out_dir.display()
+                )));
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// The main fn, called by the weak `main` in `libfuzzer.c`, runs a `libFuzzer`-like fuzzer.
+#[no_mangle]
+pub extern "C" fn libafl_main() {
+    let args: Vec<String> = std::env::args().collect();
+    let res = LibfuzzerOptions::parse(&args).and_then(|options| libfuzzer_main(&args, &options));
+    if let Err(err) = res {
+        println!("Error: {err}");
+        process::exit(1);
+    }
+}
+
+/// Runs the given inputs once each, or fuzzes, depending on the `options`.
+pub fn libfuzzer_main(args: &[String], options: &LibfuzzerOptions) -> Result<(), Error> {
+    if !options.inputs.is_empty() {
+        libfuzzer_initialize_checked(args)?;
+        for input in &options.inputs {
+            println!("Running: {}", input.display());
+            libfuzzer_test_one_input(&fs::read(input)?);
+        }
+        return Ok(());
+    }
+
+    match &options.out_dir {
+        Some(dir) => fuzz(args, options, CachedOnDiskCorpus::new(dir.clone(), 4096)?),
+        None => fuzz(args, options, InMemoryCorpus::new()),
+    }
+}
+
+/// The fuzzer, with the given corpus for the evolving inputs
+fn fuzz<C>(args: &[String], options: &LibfuzzerOptions, corpus: C) -> Result<(), Error>
+where
+    C: Corpus<Input = BytesInput> + Debug + Serialize + DeserializeOwned,
+{
+    let monitor = SimpleMonitor::new(|s| println!("{s}"));
+
+    // The restarting manager respawns the fuzzer as child each time the target crashes.
+    let mut shmem_provider = StdShMemProvider::new()?;
+    let (state, mut mgr) = match SimpleRestartingEventManager::launch(monitor, &mut shmem_provider)
+    {
+        Ok(res) => res,
+        Err(Error::ShuttingDown) => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    let edges_observer = HitcountsMapObserver::new(unsafe { std_edges_map_observer("edges") });
+    let time_observer = TimeObserver::new("time");
+
+    let map_feedback = MaxMapFeedback::new_tracking(&edges_observer, true, false);
+    let calibration = CalibrationStage::new(&map_feedback);
+
+    let mut feedback = feedback_or!(
+        map_feedback,
+        TimeFeedback::new_with_observer(&time_observer)
+    );
+    let mut objective = feedback_or_fast!(CrashFeedback::new(), TimeoutFeedback::new());
+
+    let mut state = match state {
+        Some(state) => state,
+        None => StdState::new(
+            StdRand::with_seed(options.seed.unwrap_or_else(current_nanos)),
+            corpus,
+            OnDiskCorpus::new(&options.artifact_dir)?,
+            &mut feedback,
+            &mut objective,
+        )?,
+    };
+
+    // Initialize the target in each (re)started client, not in the parent process
+    options
+        .init
+        .run(&mut state, &mut mgr, || libfuzzer_initialize_checked(args))?;
+
+    if state.metadata().get::<Tokens>().is_none() {
+        let mut toks = Tokens::default();
+        for dict in &options.dicts {
+            toks.add_from_file(dict)?;
+        }
+        #[cfg(any(target_os = "linux", target_vendor = "apple"))]
+        {
+            toks += autotokens()?;
+        }
+        if !toks.is_empty() {
+            state.add_metadata(toks);
+        }
+    }
+
+    let mutator = StdScheduledMutator::new(havoc_mutations().merge(tokens_mutations()));
+    let power = StdPowerMutationalStage::new(mutator, &edges_observer);
+    let mut stages = tuple_list!(calibration, power);
+
+    let scheduler = IndexesLenTimeMinimizerScheduler::new(StdWeightedScheduler::with_schedule(
+        PowerSchedule::FAST,
+    ));
+    let mut fuzzer = StdFuzzer::new(scheduler, feedback, objective);
+
+    let mut harness = |input: &BytesInput| {
+        let target = input.target_bytes();
+        libfuzzer_test_one_input(target.as_slice());
+        ExitKind::Ok
+    };
+
+    let mut executor = TimeoutExecutor::new(
+        InProcessExecutor::new(
+            &mut harness,
+            tuple_list!(edges_observer, time_observer),
+            &mut fuzzer,
+            &mut state,
+            &mut mgr,
+        )?,
+        options.timeout,
+    );
+
+    if state.corpus().count() < 1 {
+        state.load_initial_inputs(&mut fuzzer, &mut executor, &mut mgr, &options.corpus_dirs)?;
+        println!("We imported {} inputs from disk.", state.corpus().count());
+        // Like libFuzzer, start from a single, minimal input if there are no (interesting) seeds.
+        if state.corpus().count() < 1 {
+            fuzzer.add_input(
+                &mut state,
+                &mut executor,
+                &mut mgr,
+                BytesInput::new(vec![b'\n']),
+            )?;
+        }
+    }
+
+    fuzzer.fuzz_loop(&mut stages, &mut executor, &mut state, &mut mgr)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use alloc::{
+        string::{String, ToString},
+        vec::Vec,
+    };
+    use core::time::Duration;
+    use std::{env, fs, path::PathBuf, process};
+
+    use super::{LibfuzzerOptions, LIBFUZZER_DEFAULT_TIMEOUT};
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_parse_libfuzzer_options() {
+        let options = LibfuzzerOptions::parse(&args(&["harness"])).unwrap();
+        assert!(options.corpus_dirs.is_empty());
+        assert!(options.inputs.is_empty());
+        assert_eq!(options.out_dir, None);
+        assert_eq!(options.artifact_dir, PathBuf::from("."));
+        assert_eq!(options.seed, None);
+        assert_eq!(options.timeout, LIBFUZZER_DEFAULT_TIMEOUT);
+
+        let options = LibfuzzerOptions::parse(&args(&[
+            "harness",
+            "-artifact_prefix=crashes",
+            "-dict=a.dict",
+            "-dict=b.dict",
+            "-seed=1337",
+            "-timeout=5",
+            "-runs=100",
+            "crash-1234",
+        ]))
+        .unwrap();
+        assert_eq!(options.artifact_dir, PathBuf::from("crashes"));
+        assert_eq!(
+            options.dicts,
+            [PathBuf::from("a.dict"), PathBuf::from("b.dict")]
+        );
+        assert_eq!(options.seed, Some(1337));
+        assert_eq!(options.timeout, Duration::from_secs(5));
+        // Files that are no directories are run once
+        assert_eq!(options.inputs, [PathBuf::from("crash-1234")]);
+        assert!(options.corpus_dirs.is_empty());
+
+        assert!(LibfuzzerOptions::parse(&args(&["harness", "-seed=abc"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_libfuzzer_corpus_dirs() {
+        let corpus_dir = env::temp_dir().join(format!("libafl_libfuzzer_corpus_{}", process::id()));
+        fs::create_dir_all(&corpus_dir).unwrap();
+        let corpus = corpus_dir.to_str().unwrap();
+
+        let options = LibfuzzerOptions::parse(&args(&["harness", "-out_dir=out", corpus])).unwrap();
+        assert_eq!(options.corpus_dirs, [corpus_dir.clone()]);
+        assert_eq!(options.out_dir, Some(PathBuf::from("out")));
+
+        // New inputs never end up in a corpus directory
+        let out_dir = format!("-out_dir={corpus}");
+        assert!(LibfuzzerOptions::parse(&args(&["harness", &out_dir, corpus])).is_err());
+        // Either fuzz, or run files
+        assert!(LibfuzzerOptions::parse(&args(&["harness", corpus, "crash-1234"])).is_err());
+
+        fs::remove_dir_all(&corpus_dir).unwrap();
+    }
+}
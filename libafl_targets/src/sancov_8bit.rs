@@ -14,5 +14,9 @@ pub static mut COUNTERS_MAPS: Vec<&'static mut [u8]> = Vec::new();
 #[allow(clippy::cast_sign_loss)]
 #[allow(clippy::not_unsafe_ptr_arg_deref)]
 pub fn __sanitizer_cov_8bit_counters_init(start: *mut u8, stop: *mut u8) {
-    unsafe { COUNTERS_MAPS.push(from_raw_parts_mut(start, stop.offset_from(start) as usize)) }
+    unsafe {
+        #[cfg(feature = "cmp_progress_interpose")]
+        crate::cmp_progress::__libafl_cmp_progress_add_module(start as *const core::ffi::c_void);
+        COUNTERS_MAPS.push(from_raw_parts_mut(start, stop.offset_from(start) as usize));
+    }
 }
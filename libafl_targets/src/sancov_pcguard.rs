@@ -61,6 +61,9 @@ pub unsafe extern "C" fn __sanitizer_cov_trace_pc_guard_init(mut start: *mut u32
         return;
     }
 
+    #[cfg(feature = "cmp_progress_interpose")]
+    crate::cmp_progress::__libafl_cmp_progress_add_module(start as *const core::ffi::c_void);
+
     while start < stop {
         *start = MAX_EDGES_NUM as u32;
         start = start.offset(1);
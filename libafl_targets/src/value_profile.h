@@ -17,14 +17,16 @@ extern uint8_t libafl_cmp_map[CMP_MAP_SIZE];
 
 static void __libafl_targets_value_profile1(uintptr_t k, uint8_t arg1,
                                             uint8_t arg2) {
+  // Truncate after the integer promotion, to only count the bits of the operands
   libafl_cmp_map[k] =
-      MAX(libafl_cmp_map[k], (__builtin_popcount(~(arg1 ^ arg2))));
+      MAX(libafl_cmp_map[k], (__builtin_popcount((uint8_t)~(arg1 ^ arg2))));
 }
 
 static void __libafl_targets_value_profile2(uintptr_t k, uint16_t arg1,
                                             uint16_t arg2) {
+  // Truncate after the integer promotion, to only count the bits of the operands
   libafl_cmp_map[k] =
-      MAX(libafl_cmp_map[k], (__builtin_popcount(~(arg1 ^ arg2))));
+      MAX(libafl_cmp_map[k], (__builtin_popcount((uint16_t)~(arg1 ^ arg2))));
 }
 
 static void __libafl_targets_value_profile4(uintptr_t k, uint32_t arg1,
@@ -1,26 +1,40 @@
 //! Value profile support for `LibAFL`
+//!
+//! With the `sancov_value_profile` feature, the `trace-cmp` hooks store, for each comparison site,
+//! the highest number of bits the two operands had in common in the current run, as `libFuzzer` does.
+//! A [`ValueProfileFeedback`] on this map rewards inputs that get closer to satisfying a comparison,
+//! so progress on comparisons counts as partial coverage.
+
+use libafl::{feedbacks::MaxMapFeedback, observers::StdMapObserver};
 
 use crate::CMP_MAP_SIZE;
 
-/// The constant cmplog map for the current `LibAFL` target
+/// The name of the observer returned by [`value_profile_observer`]
+pub const VALUE_PROFILE_OBSERVER_NAME: &str = "value_profile";
+
+/// The value profile map for the current `LibAFL` target
 #[no_mangle]
 pub static mut libafl_cmp_map: [u8; CMP_MAP_SIZE] = [0; CMP_MAP_SIZE];
 
 pub use libafl_cmp_map as CMP_MAP;
 
-/*
-extern {
-    #[link_name = "llvm.returnaddress"]
-    fn return_address() -> usize;
-}
+/// A feedback that reports inputs as interesting if more bits matched at any comparison site
+pub type ValueProfileFeedback<S> = MaxMapFeedback<StdMapObserver<'static, u8>, S, u8>;
 
-#[no_mangle]
-pub unsafe extern "C" fn __sanitizer_cov_trace_cmp1(arg1: u8, arg2: u8) {
-    let mut pos = return_address();
-    pos = (pos >> 4) ^ (pos << 8);
-    pos &= CMP_MAP_SIZE - 1;
-    *CMP_MAP.get_unchecked_mut(pos) = core::cmp::max(*CMP_MAP.get_unchecked(pos), (!(arg1 ^ arg2)).count_ones() as u8);
+/// Gets the [`CMP_MAP`], to be wrapped in a map observer.
+///
+/// # Safety
+/// Returns a mutable reference to the global map, there should only be one alive at a time.
+#[must_use]
+pub unsafe fn cmp_map_mut<'a>() -> &'a mut [u8] {
+    &mut CMP_MAP
 }
-*/
 
-// TODO complete when linking to LLVM intrinsic will land to stable Rust
+/// Creates a new [`StdMapObserver`] on the [`CMP_MAP`], named [`VALUE_PROFILE_OBSERVER_NAME`].
+///
+/// # Safety
+/// The observer holds a mutable reference to the global map, there must be only one of it.
+#[must_use]
+pub unsafe fn value_profile_observer() -> StdMapObserver<'static, u8> {
+    StdMapObserver::new(VALUE_PROFILE_OBSERVER_NAME, &mut CMP_MAP)
+}
@@ -0,0 +1,380 @@
+//! The [`TinyInstExecutor`] runs a binary under `TinyInst`, and collects the basic blocks each run reached first
+
+use std::{
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    time::Duration,
+};
+
+use libafl::{
+    bolts::{fs::InputFile, tuples::MatchName, AsSlice},
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::{HasTargetBytes, UsesInput},
+    observers::{ListObserver, ObserversTuple, UsesObservers},
+    state::{State, UsesState},
+    Error,
+};
+use tinyinst::tinyinst::{litecov::RunResult, TinyInst};
+
+/// The default timeout of a run
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The default name of the [`ListObserver`] the coverage gets reported to
+pub const DEFAULT_COVERAGE_OBSERVER_NAME: &str = "tinyinst_coverage";
+
+/// An executor running the target under `TinyInst`.
+/// After each run, it writes the offsets of the basic blocks reached for the first time
+/// to the [`ListObserver<u64>`] of its observers, so a [`libafl::feedbacks::ListFeedback`]
+/// deems the runs reaching new blocks interesting.
+pub struct TinyInstExecutor<S, OT> {
+    tinyinst: TinyInst,
+    input_file: InputFile,
+    coverage_observer_name: String,
+    observers: OT,
+    phantom: PhantomData<S>,
+}
+
+impl<S, OT> Debug for TinyInstExecutor<S, OT>
+where
+    OT: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TinyInstExecutor")
+            .field("input_file", &self.input_file)
+            .field("coverage_observer_name", &self.coverage_observer_name)
+            .field("observers", &self.observers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, OT> UsesState for TinyInstExecutor<S, OT>
+where
+    S: UsesInput,
+{
+    type State = S;
+}
+
+impl<S, OT> UsesObservers for TinyInstExecutor<S, OT>
+where
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    type Observers = OT;
+}
+
+impl<EM, OT, S, Z> Executor<EM, Z> for TinyInstExecutor<S, OT>
+where
+    EM: UsesState<State = S>,
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+    S::Input: HasTargetBytes,
+    Z: UsesState<State = S>,
+{
+    fn run_target(
+        &mut self,
+        _fuzzer: &mut Z,
+        _state: &mut Self::State,
+        _mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        self.input_file.write_buf(input.target_bytes().as_slice())?;
+
+        let status = unsafe { self.tinyinst.run() };
+
+        let coverage = self
+            .observers
+            .match_name_mut::<ListObserver<u64>>(&self.coverage_observer_name)
+            .ok_or_else(|| Error::key_not_found("TinyInst coverage observer not found"))?;
+        unsafe {
+            self.tinyinst.vec_coverage(coverage.list_mut(), false);
+        }
+
+        exit_kind(status)
+    }
+}
+
+/// The [`ExitKind`] of a run `TinyInst` reported as `status`
+fn exit_kind(status: RunResult) -> Result<ExitKind, Error> {
+    match status {
+        RunResult::OK => Ok(ExitKind::Ok),
+        RunResult::CRASH => Ok(ExitKind::Crash),
+        RunResult::HANG => Ok(ExitKind::Timeout),
+        RunResult::OTHER_ERROR => Err(Error::unknown("TinyInst failed to run the target")),
+        _ => Err(Error::unknown("TinyInst returned an unknown result")),
+    }
+}
+
+impl<S, OT> HasObservers for TinyInstExecutor<S, OT>
+where
+    S: State,
+    OT: ObserversTuple<S>,
+{
+    fn observers(&self) -> &OT {
+        &self.observers
+    }
+
+    fn observers_mut(&mut self) -> &mut OT {
+        &mut self.observers
+    }
+}
+
+/// The builder for [`TinyInstExecutor`]
+#[derive(Debug, Clone)]
+pub struct TinyInstExecutorBuilder {
+    tinyinst_args: Vec<String>,
+    program_args: Vec<String>,
+    timeout: Duration,
+    input_filename: String,
+    coverage_observer_name: String,
+}
+
+impl Default for TinyInstExecutorBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TinyInstExecutorBuilder {
+    /// Creates a new [`TinyInstExecutorBuilder`], without modules to instrument and without target
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            tinyinst_args: vec![],
+            program_args: vec![],
+            timeout: DEFAULT_TIMEOUT,
+            input_filename: ".cur_input".to_string(),
+            coverage_observer_name: DEFAULT_COVERAGE_OBSERVER_NAME.to_string(),
+        }
+    }
+
+    /// Instruments the module of the given name, such as `target.exe` or `libtarget.dylib`, for coverage.
+    /// Call it again to instrument more modules.
+    #[must_use]
+    pub fn instrument_module(mut self, module: &str) -> Self {
+        self.tinyinst_args.push("-instrument_module".to_string());
+        self.tinyinst_args.push(module.to_string());
+        self
+    }
+
+    /// Runs the function `target_method`, with `nargs` arguments, of the module `target_module` in a loop,
+    /// restarting the target only every `iterations` inputs, or after a crash or a hang.
+    /// The function needs to read the input file on each call.
+    #[must_use]
+    pub fn persistent(
+        mut self,
+        target_module: &str,
+        target_method: &str,
+        nargs: usize,
+        iterations: usize,
+    ) -> Self {
+        self.tinyinst_args.extend([
+            "-target_module".to_string(),
+            target_module.to_string(),
+            "-target_method".to_string(),
+            target_method.to_string(),
+            "-nargs".to_string(),
+            nargs.to_string(),
+            "-iterations".to_string(),
+            iterations.to_string(),
+            "-persist".to_string(),
+            "-loop".to_string(),
+        ]);
+        self
+    }
+
+    /// Passes a raw option to `TinyInst`, such as `-patch_return_addresses`
+    #[must_use]
+    pub fn tinyinst_arg(mut self, arg: &str) -> Self {
+        self.tinyinst_args.push(arg.to_string());
+        self
+    }
+
+    /// The command line of the target, starting with the program.
+    /// The argument `@@` is replaced with the file each input gets written to.
+    #[must_use]
+    pub fn program_args<IT, O>(mut self, args: IT) -> Self
+    where
+        IT: IntoIterator<Item = O>,
+        O: AsRef<str>,
+    {
+        self.program_args = args
+            .into_iter()
+            .map(|arg| arg.as_ref().to_string())
+            .collect();
+        self
+    }
+
+    /// The file each input gets written to, `.cur_input` by default
+    #[must_use]
+    pub fn input_filename(mut self, input_filename: &str) -> Self {
+        self.input_filename = input_filename.to_string();
+        self
+    }
+
+    /// The timeout of each run, [`DEFAULT_TIMEOUT`] by default
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// The name of the [`ListObserver<u64>`] the coverage gets reported to,
+    /// [`DEFAULT_COVERAGE_OBSERVER_NAME`] by default
+    #[must_use]
+    pub fn coverage_observer_name(mut self, name: &str) -> Self {
+        self.coverage_observer_name = name.to_string();
+        self
+    }
+
+    /// Builds the [`TinyInstExecutor`], starting `TinyInst`.
+    /// Will error if no target is set, or if `observers` lack the [`ListObserver<u64>`] for the coverage.
+    pub fn build<OT, S>(self, observers: OT) -> Result<TinyInstExecutor<S, OT>, Error>
+    where
+        OT: ObserversTuple<S>,
+        S: UsesInput,
+    {
+        if self.program_args.is_empty() {
+            return Err(Error::illegal_argument(
+                "TinyInstExecutorBuilder::build: no target program set",
+            ));
+        }
+        if observers
+            .match_name::<ListObserver<u64>>(&self.coverage_observer_name)
+            .is_none()
+        {
+            return Err(Error::illegal_argument(format!(
+                "TinyInstExecutorBuilder::build: no ListObserver<u64> named {} for the coverage",
+                self.coverage_observer_name
+            )));
+        }
+
+        let input_file = InputFile::create(&self.input_filename)?;
+        let program_args = self
+            .program_args
+            .into_iter()
+            .map(|arg| {
+                if arg == "@@" {
+                    self.input_filename.clone()
+                } else {
+                    arg
+                }
+            })
+            .collect();
+        #[allow(clippy::cast_possible_truncation)]
+        let timeout_ms = self.timeout.as_millis() as u32;
+        let tinyinst = unsafe { TinyInst::new(self.tinyinst_args, program_args, timeout_ms) };
+
+        Ok(TinyInstExecutor {
+            tinyinst,
+            input_file,
+            coverage_observer_name: self.coverage_observer_name,
+            observers,
+            phantom: PhantomData,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, time::Duration};
+
+    use libafl::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::{Executor, ExitKind, HasObservers},
+        feedbacks::ConstFeedback,
+        fuzzer::StdFuzzer,
+        inputs::BytesInput,
+        observers::ListObserver,
+        schedulers::QueueScheduler,
+        state::StdState,
+        Error,
+    };
+    use tinyinst::tinyinst::litecov::RunResult;
+
+    use super::{exit_kind, TinyInstExecutorBuilder, DEFAULT_COVERAGE_OBSERVER_NAME};
+
+    type TestState =
+        StdState<BytesInput, InMemoryCorpus<BytesInput>, StdRand, InMemoryCorpus<BytesInput>>;
+
+    #[test]
+    fn test_build_rejects_bad_settings() {
+        // No target program
+        let res = TinyInstExecutorBuilder::new()
+            .instrument_module("target.exe")
+            .build::<_, TestState>(tuple_list!());
+        assert!(matches!(res, Err(Error::IllegalArgument(..))));
+
+        // No coverage observer of the configured name
+        let mut coverage = vec![];
+        let res = TinyInstExecutorBuilder::new()
+            .instrument_module("target.exe")
+            .program_args(["target.exe", "@@"])
+            .build::<_, TestState>(tuple_list!(ListObserver::<u64>::new(
+                "not_the_coverage",
+                &mut coverage
+            )));
+        assert!(matches!(res, Err(Error::IllegalArgument(..))));
+    }
+
+    #[test]
+    fn test_exit_kind() {
+        assert!(matches!(exit_kind(RunResult::OK), Ok(ExitKind::Ok)));
+        assert!(matches!(exit_kind(RunResult::CRASH), Ok(ExitKind::Crash)));
+        assert!(matches!(exit_kind(RunResult::HANG), Ok(ExitKind::Timeout)));
+        assert!(exit_kind(RunResult::OTHER_ERROR).is_err());
+    }
+
+    /// The target of [`test_coverage`], run in the test binary under `TinyInst`
+    #[test]
+    #[ignore]
+    fn coverage_target() {}
+
+    #[test]
+    fn test_coverage() {
+        // Instrument this test binary, running only `coverage_target`
+        let exe = env::current_exe().unwrap();
+        let module = exe.file_name().unwrap().to_str().unwrap();
+
+        let mut coverage = vec![];
+        let mut executor = TinyInstExecutorBuilder::new()
+            .instrument_module(module)
+            .program_args([
+                exe.to_str().unwrap(),
+                "--ignored",
+                "--exact",
+                "executor::tests::coverage_target",
+            ])
+            .input_filename(".cur_input_tinyinst_test")
+            .timeout(Duration::from_secs(30))
+            .build::<_, TestState>(tuple_list!(ListObserver::<u64>::new(
+                DEFAULT_COVERAGE_OBSERVER_NAME,
+                &mut coverage
+            )))
+            .unwrap();
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = TestState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut fuzzer: StdFuzzer<_, _, _, ()> =
+            StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+        let mut mgr = NopEventManager::new();
+
+        let exit_kind = executor
+            .run_target(&mut fuzzer, &mut state, &mut mgr, &BytesInput::new(vec![0]))
+            .unwrap();
+        assert_eq!(exit_kind, ExitKind::Ok);
+
+        // The first run reaches the blocks of the test harness
+        let (observer, _) = executor.observers();
+        assert!(!observer.list().is_empty());
+    }
+}
@@ -0,0 +1,11 @@
+//! `libafl_tinyinst` runs closed-source binaries under [`TinyInst`](https://github.com/googleprojectzero/TinyInst),
+//! for binary-only coverage on Windows and macOS.
+//!
+//! The [`executor::TinyInstExecutor`], built with the [`executor::TinyInstExecutorBuilder`], instruments the selected modules of the target,
+//! optionally runs a target function in a loop, and reports the basic blocks newly reached by each run
+//! to a [`libafl::observers::ListObserver`]. Crashes and hangs map to the [`libafl::executors::ExitKind`].
+
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub mod executor;
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub use executor::{TinyInstExecutor, TinyInstExecutorBuilder};
@@ -0,0 +1,420 @@
+//! The [`UnicornExecutor`] runs each input in the [`Unicorn`] CPU emulator, and collects the edge coverage of the target
+
+use std::{
+    cell::Cell,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    rc::Rc,
+    time::Duration,
+};
+
+use libafl::{
+    bolts::{xxh3_rrmxmx_mixer, AsSlice},
+    executors::{Executor, ExitKind, HasObservers},
+    inputs::{HasTargetBytes, UsesInput},
+    observers::{ObserversTuple, UsesObservers},
+    state::{State, UsesState},
+    Error,
+};
+use libafl_targets::{EDGES_MAP, EDGES_MAP_SIZE};
+use unicorn_engine::{
+    unicorn_const::{uc_error, Permission, Query},
+    Context, Unicorn,
+};
+
+/// The granularity of the mappings of Unicorn
+const PAGE_SIZE: u64 = 0x1000;
+
+/// Converts an error of Unicorn into an [`Error`]
+fn unicorn_error(err: uc_error) -> Error {
+    Error::unknown(format!("Unicorn error: {err:?}"))
+}
+
+/// If Unicorn stopped at a fault of the target, rather than at an error of the setup
+fn is_crash(err: uc_error) -> bool {
+    matches!(
+        err,
+        uc_error::READ_UNMAPPED
+            | uc_error::WRITE_UNMAPPED
+            | uc_error::FETCH_UNMAPPED
+            | uc_error::READ_PROT
+            | uc_error::WRITE_PROT
+            | uc_error::FETCH_PROT
+            | uc_error::READ_UNALIGNED
+            | uc_error::WRITE_UNALIGNED
+            | uc_error::FETCH_UNALIGNED
+            | uc_error::INSN_INVALID
+            | uc_error::EXCEPTION
+    )
+}
+
+/// Maps `blob`, such as a code snippet or a firmware image, at `addr`, with the given permissions.
+/// The mapping is rounded up to whole pages, so `addr` needs to be page-aligned.
+pub fn map_blob<D>(
+    emu: &mut Unicorn<D>,
+    addr: u64,
+    blob: &[u8],
+    perms: Permission,
+) -> Result<(), Error> {
+    let size = (blob.len() as u64 + PAGE_SIZE - 1) & !(PAGE_SIZE - 1);
+    emu.mem_map(addr, size as usize, perms)
+        .map_err(unicorn_error)?;
+    emu.mem_write(addr, blob).map_err(unicorn_error)
+}
+
+/// Where the [`UnicornExecutor`] puts each input: a memory region, and optionally registers
+/// holding the address and the length of the input, such as the arguments of the function under test
+#[derive(Debug, Clone, Copy)]
+pub struct InputLocation {
+    /// The address of the memory region the input is written to
+    pub addr: u64,
+    /// The size of the memory region, longer inputs are truncated
+    pub max_len: usize,
+    /// The register set to the address of the input, if any
+    pub addr_reg: Option<i32>,
+    /// The register set to the length of the input, if any
+    pub len_reg: Option<i32>,
+}
+
+impl InputLocation {
+    /// Creates a new [`InputLocation`], writing inputs of up to `max_len` bytes to `addr`
+    #[must_use]
+    pub fn new(addr: u64, max_len: usize) -> Self {
+        Self {
+            addr,
+            max_len,
+            addr_reg: None,
+            len_reg: None,
+        }
+    }
+
+    /// Also sets the register `reg` to the address of the input
+    #[must_use]
+    pub fn with_addr_reg<R>(mut self, reg: R) -> Self
+    where
+        R: Into<i32>,
+    {
+        self.addr_reg = Some(reg.into());
+        self
+    }
+
+    /// Also sets the register `reg` to the length of the input
+    #[must_use]
+    pub fn with_len_reg<R>(mut self, reg: R) -> Self
+    where
+        R: Into<i32>,
+    {
+        self.len_reg = Some(reg.into());
+        self
+    }
+}
+
+/// An executor running the target in the [`Unicorn`] CPU emulator, from `entry` until it reaches `exit`.
+/// Before each run, it restores the registers to the ones saved at creation, or with [`UnicornExecutor::save_context`],
+/// and writes the input to its [`InputLocation`]. Other memory is not restored.
+/// A basic block hook records the transitions between blocks in [`EDGES_MAP`].
+/// Memory faults and invalid instructions end the run as [`ExitKind::Crash`].
+pub struct UnicornExecutor<'a, D, OT, S> {
+    emu: Unicorn<'a, D>,
+    entry: u64,
+    exit: u64,
+    input_location: InputLocation,
+    timeout: Option<Duration>,
+    context: Context,
+    /// The id of the previous block, shared with the block hook
+    prev_loc: Rc<Cell<u64>>,
+    observers: OT,
+    phantom: PhantomData<S>,
+}
+
+impl<'a, D, OT, S> Debug for UnicornExecutor<'a, D, OT, S>
+where
+    OT: Debug,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UnicornExecutor")
+            .field("entry", &self.entry)
+            .field("exit", &self.exit)
+            .field("input_location", &self.input_location)
+            .field("timeout", &self.timeout)
+            .field("observers", &self.observers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<'a, D, OT, S> UsesState for UnicornExecutor<'a, D, OT, S>
+where
+    S: UsesInput,
+{
+    type State = S;
+}
+
+impl<'a, D, OT, S> UsesObservers for UnicornExecutor<'a, D, OT, S>
+where
+    OT: ObserversTuple<S>,
+    S: UsesInput,
+{
+    type Observers = OT;
+}
+
+impl<'a, D, EM, OT, S, Z> Executor<EM, Z> for UnicornExecutor<'a, D, OT, S>
+where
+    EM: UsesState<State = S>,
+    OT: Debug,
+    S: UsesInput,
+    S::Input: HasTargetBytes,
+    Z: UsesState<State = S>,
+{
+    fn run_target(
+        &mut self,
+        _fuzzer: &mut Z,
+        _state: &mut Self::State,
+        _mgr: &mut EM,
+        input: &Self::Input,
+    ) -> Result<ExitKind, Error> {
+        let target = input.target_bytes();
+        let buf = target.as_slice();
+        let buf = &buf[..buf.len().min(self.input_location.max_len)];
+
+        self.emu
+            .context_restore(&self.context)
+            .map_err(unicorn_error)?;
+        self.emu
+            .mem_write(self.input_location.addr, buf)
+            .map_err(unicorn_error)?;
+        if let Some(reg) = self.input_location.addr_reg {
+            self.emu
+                .reg_write(reg, self.input_location.addr)
+                .map_err(unicorn_error)?;
+        }
+        if let Some(reg) = self.input_location.len_reg {
+            self.emu
+                .reg_write(reg, buf.len() as u64)
+                .map_err(unicorn_error)?;
+        }
+        self.prev_loc.set(0);
+
+        // Unicorn takes the timeout in microseconds, 0 means no timeout
+        #[allow(clippy::cast_possible_truncation)]
+        let timeout = self.timeout.map_or(0, |timeout| timeout.as_micros() as u64);
+        match self.emu.emu_start(self.entry, self.exit, timeout, 0) {
+            Ok(()) => {
+                if timeout != 0 && self.emu.query(Query::TIMEOUT).map_err(unicorn_error)? != 0 {
+                    Ok(ExitKind::Timeout)
+                } else {
+                    Ok(ExitKind::Ok)
+                }
+            }
+            Err(err) if is_crash(err) => Ok(ExitKind::Crash),
+            Err(err) => Err(unicorn_error(err)),
+        }
+    }
+}
+
+impl<'a, D, OT, S> HasObservers for UnicornExecutor<'a, D, OT, S>
+where
+    S: State,
+    OT: ObserversTuple<S>,
+{
+    fn observers(&self) -> &OT {
+        &self.observers
+    }
+
+    fn observers_mut(&mut self) -> &mut OT {
+        &mut self.observers
+    }
+}
+
+impl<'a, D, OT, S> UnicornExecutor<'a, D, OT, S>
+where
+    D: 'a,
+{
+    /// Creates a new [`UnicornExecutor`], running `emu` from `entry` to `exit` for each input.
+    /// Map the target and set up the registers, e.g., the stack pointer, before,
+    /// as the registers at creation are restored before each run.
+    pub fn new(
+        mut emu: Unicorn<'a, D>,
+        entry: u64,
+        exit: u64,
+        input_location: InputLocation,
+        observers: OT,
+    ) -> Result<Self, Error> {
+        let context = emu.context_init().map_err(unicorn_error)?;
+
+        let prev_loc = Rc::new(Cell::new(0));
+        let hook_prev_loc = prev_loc.clone();
+        emu.add_block_hook(move |_emu, addr, _size| {
+            let cur_loc = xxh3_rrmxmx_mixer(addr) & (EDGES_MAP_SIZE as u64 - 1);
+            let idx = (cur_loc ^ hook_prev_loc.get()) as usize;
+            unsafe {
+                EDGES_MAP[idx] = EDGES_MAP[idx].wrapping_add(1);
+            }
+            hook_prev_loc.set(cur_loc >> 1);
+        })
+        .map_err(unicorn_error)?;
+
+        Ok(Self {
+            emu,
+            entry,
+            exit,
+            input_location,
+            timeout: None,
+            context,
+            prev_loc,
+            observers,
+            phantom: PhantomData,
+        })
+    }
+
+    /// Sets the timeout of each run, `None` to run until the exit address or a fault
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Saves the current registers, to restore them before each run
+    pub fn save_context(&mut self) -> Result<(), Error> {
+        self.emu
+            .context_save(&mut self.context)
+            .map_err(unicorn_error)
+    }
+
+    /// The emulator
+    #[must_use]
+    pub fn emu(&self) -> &Unicorn<'a, D> {
+        &self.emu
+    }
+
+    /// The emulator (mutable)
+    pub fn emu_mut(&mut self) -> &mut Unicorn<'a, D> {
+        &mut self.emu
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use libafl::{
+        bolts::{rands::StdRand, tuples::tuple_list},
+        corpus::InMemoryCorpus,
+        events::NopEventManager,
+        executors::{Executor, ExitKind},
+        feedbacks::ConstFeedback,
+        fuzzer::StdFuzzer,
+        inputs::BytesInput,
+        schedulers::QueueScheduler,
+        state::StdState,
+    };
+    use unicorn_engine::{
+        unicorn_const::{Arch, Mode, Permission},
+        RegisterX86, Unicorn,
+    };
+
+    use super::{map_blob, InputLocation, UnicornExecutor, EDGES_MAP};
+
+    const CODE_ADDR: u64 = 0x1000;
+    const INPUT_ADDR: u64 = 0x2000;
+
+    /// Reads unmapped memory for `A`, loops forever for `B`, and returns for other inputs
+    const SNIPPET: [u8; 21] = [
+        0x8a, 0x07, // mov al, byte [rdi]
+        0x3c, 0x41, // cmp al, 'A'
+        0x75, 0x08, // jne +8
+        0x48, 0x8b, 0x04, 0x25, 0x00, 0x00, 0x00, 0x00, // mov rax, qword [0]
+        0x3c, 0x42, // cmp al, 'B'
+        0x75, 0x02, // jne +2
+        0xeb, 0xfe, // jmp -2
+        0x90, // nop, the exit
+    ];
+
+    /// The edges of the last run
+    fn take_edges() -> Vec<usize> {
+        unsafe {
+            let edges = EDGES_MAP
+                .iter()
+                .enumerate()
+                .filter(|(_, hits)| **hits != 0)
+                .map(|(idx, _)| idx)
+                .collect();
+            EDGES_MAP.fill(0);
+            edges
+        }
+    }
+
+    #[test]
+    fn test_x86_snippet() {
+        let mut emu = Unicorn::new(Arch::X86, Mode::MODE_64).unwrap();
+        map_blob(
+            &mut emu,
+            CODE_ADDR,
+            &SNIPPET,
+            Permission::READ | Permission::EXEC,
+        )
+        .unwrap();
+        map_blob(
+            &mut emu,
+            INPUT_ADDR,
+            &[0; 16],
+            Permission::READ | Permission::WRITE,
+        )
+        .unwrap();
+
+        let mut executor = UnicornExecutor::new(
+            emu,
+            CODE_ADDR,
+            CODE_ADDR + SNIPPET.len() as u64 - 1,
+            InputLocation::new(INPUT_ADDR, 16)
+                .with_addr_reg(RegisterX86::RDI)
+                .with_len_reg(RegisterX86::RSI),
+            tuple_list!(),
+        )
+        .unwrap();
+        executor.set_timeout(Some(Duration::from_millis(100)));
+
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state: StdState<BytesInput, _, _, _> = StdState::new(
+            StdRand::with_seed(0),
+            InMemoryCorpus::new(),
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut fuzzer: StdFuzzer<_, _, _, ()> =
+            StdFuzzer::new(QueueScheduler::new(), feedback, objective);
+        let mut mgr = NopEventManager::new();
+
+        let mut run = |input: &[u8]| {
+            executor
+                .run_target(
+                    &mut fuzzer,
+                    &mut state,
+                    &mut mgr,
+                    &BytesInput::new(input.to_vec()),
+                )
+                .unwrap()
+        };
+
+        take_edges();
+        assert_eq!(run(b"C"), ExitKind::Ok);
+        let edges_ok = take_edges();
+        assert!(!edges_ok.is_empty());
+
+        // The same path hits the same edges, each run starts from the saved registers
+        assert_eq!(run(b"D"), ExitKind::Ok);
+        assert_eq!(take_edges(), edges_ok);
+
+        assert_eq!(run(b"A"), ExitKind::Crash);
+        let edges_crash = take_edges();
+        assert!(!edges_crash.is_empty());
+        assert_ne!(edges_crash, edges_ok);
+
+        assert_eq!(run(b"B"), ExitKind::Timeout);
+        take_edges();
+
+        // The executor is still usable after a crash or a timeout
+        assert_eq!(run(b"C"), ExitKind::Ok);
+        assert_eq!(take_edges(), edges_ok);
+    }
+}
@@ -0,0 +1,12 @@
+//! `libafl_unicorn` runs code snippets or firmware blobs in the [`Unicorn`](https://www.unicorn-engine.org) CPU emulator.
+//!
+//! Map the target with [`executor::map_blob`], and pass the emulator to the [`executor::UnicornExecutor`].
+//! It writes each input to an [`executor::InputLocation`], runs the target from the entry to the exit address,
+//! and reports memory faults and invalid instructions as [`libafl::executors::ExitKind::Crash`].
+//! The transitions between basic blocks end up in [`EDGES_MAP`],
+//! use it as map for a [`libafl::observers::StdMapObserver`].
+
+pub mod executor;
+pub use executor::{map_blob, InputLocation, UnicornExecutor};
+pub use libafl_targets::{EDGES_MAP, EDGES_MAP_SIZE};
+pub use unicorn_engine;
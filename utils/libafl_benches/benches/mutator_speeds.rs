@@ -0,0 +1,67 @@
+//! Compare the cost of preparing havoc inputs: loading the parent from the corpus each iteration vs. copying a preloaded parent
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use libafl::{
+    bolts::rands::StdRand,
+    corpus::{Corpus, InMemoryCorpus, Testcase},
+    feedbacks::ConstFeedback,
+    inputs::BytesInput,
+    mutators::{havoc_mutations, Mutator, StdScheduledMutator},
+    state::{HasCorpus, StdState},
+};
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("havoc_prepare");
+    for size in [1 << 10, 1 << 20, 1 << 24] {
+        let mut corpus = InMemoryCorpus::new();
+        corpus
+            .add(Testcase::new(BytesInput::new(vec![0x41; size])))
+            .unwrap();
+        let mut feedback = ConstFeedback::new(false);
+        let mut objective = ConstFeedback::new(false);
+        let mut state = StdState::new(
+            StdRand::with_seed(0),
+            corpus,
+            InMemoryCorpus::new(),
+            &mut feedback,
+            &mut objective,
+        )
+        .unwrap();
+        let mut mutator = StdScheduledMutator::new(havoc_mutations());
+
+        group.bench_with_input(BenchmarkId::new("load_each", size), &size, |b, _| {
+            b.iter(|| {
+                let mut input = state
+                    .corpus()
+                    .get(0)
+                    .unwrap()
+                    .borrow_mut()
+                    .load_input()
+                    .unwrap()
+                    .clone();
+                mutator.mutate(&mut state, &mut input, 0).unwrap();
+                black_box(input);
+            });
+        });
+
+        let parent = state
+            .corpus()
+            .get(0)
+            .unwrap()
+            .borrow_mut()
+            .load_input()
+            .unwrap()
+            .clone();
+        group.bench_with_input(BenchmarkId::new("preloaded", size), &size, |b, _| {
+            b.iter(|| {
+                let mut input = parent.clone();
+                mutator.mutate(&mut state, &mut input, 0).unwrap();
+                black_box(input);
+            });
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
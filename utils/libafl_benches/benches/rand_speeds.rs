@@ -2,21 +2,27 @@
 
 use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use libafl::bolts::rands::{
-    Lehmer64Rand, Rand, RomuDuoJrRand, RomuTrioRand, XorShift64Rand, Xoshiro256StarRand,
+    Lehmer64Rand, Rand, RomuDuoJrRand, RomuTrioRand, StdRand, XorShift64Rand,
+    Xoshiro256PlusPlusRand,
 };
 
 fn criterion_benchmark(c: &mut Criterion) {
     let mut xorshift = XorShift64Rand::with_seed(1);
-    let mut xoshiro = Xoshiro256StarRand::with_seed(1);
+    let mut xoshiro = Xoshiro256PlusPlusRand::with_seed(1);
     let mut romu = RomuDuoJrRand::with_seed(1);
     let mut lehmer = Lehmer64Rand::with_seed(1);
     let mut romu_trio = RomuTrioRand::with_seed(1);
+    let mut std_rand = StdRand::with_seed(1);
 
     c.bench_function("xorshift", |b| b.iter(|| black_box(xorshift.next())));
     c.bench_function("xoshiro", |b| b.iter(|| black_box(xoshiro.next())));
     c.bench_function("romu", |b| b.iter(|| black_box(romu.next())));
     c.bench_function("romu_trio", |b| b.iter(|| black_box(romu_trio.next())));
     c.bench_function("lehmer", |b| b.iter(|| black_box(lehmer.next())));
+    c.bench_function("std_rand", |b| b.iter(|| black_box(std_rand.next())));
+    c.bench_function("below", |b| {
+        b.iter(|| black_box(std_rand.below(black_box(1000))))
+    });
 }
 
 criterion_group!(benches, criterion_benchmark);